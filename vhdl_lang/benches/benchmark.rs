@@ -8,7 +8,7 @@ use brunch::{Bench, Benches};
 use std::{path::Path, time::Duration};
 use vhdl_lang::{
     ast::search::{SearchState, Searcher},
-    Config, MessagePrinter, NullMessages, Project,
+    Config, MessagePrinter, NullMessages, Position, Project, Range, Source,
 };
 
 fn load_config(include_example_project: bool) -> Config {
@@ -78,6 +78,30 @@ fn main() {
         );
     }
 
+    {
+        // A synthetic file with 100k lines to measure position lookups and edits of large sources
+        let code: String = (0..100_000)
+            .map(|i| format!("  constant c{i} : natural := {i};\n"))
+            .collect();
+        let source = Source::inline(Path::new("large.vhd"), &code);
+        let last_line = Position::new(99_999, 10);
+        let last_offset = source.position_to_offset(last_line);
+
+        benches.push(Bench::new("position to offset in 100k lines").run(|| {
+            assert_eq!(source.position_to_offset(last_line), last_offset);
+        }));
+
+        benches.push(Bench::new("offset to position in 100k lines").run(|| {
+            assert_eq!(source.offset_to_position(last_offset), last_line);
+        }));
+
+        benches.push(Bench::new("change in 100k lines").run(|| {
+            let pos = Position::new(50_000, 2);
+            source.change(Some(&Range::new(pos, pos)), "x\n");
+            source.change(Some(&Range::new(pos, Position::new(50_001, 0))), "");
+        }));
+    }
+
     benches.finish();
 }
 
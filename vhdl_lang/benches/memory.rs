@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Measures the heap memory retained by a project after analyzing the installed libraries
+//! and a generated design where every unit uses them
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vhdl_lang::{Config, MessagePrinter, NullMessages, Project};
+
+struct CountingAllocator {
+    allocated: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let allocated = self.allocated.fetch_add(layout.size(), Ordering::Relaxed);
+            self.peak
+                .fetch_max(allocated + layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.allocated.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    allocated: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// A design unit count large enough for the visibility of the units to dominate
+const NUM_DESIGN_FILES: usize = 500;
+
+fn write_design(root: &Path) {
+    for idx in 0..NUM_DESIGN_FILES {
+        std::fs::write(
+            root.join(format!("ent{idx}.vhd")),
+            format!(
+                "
+library ieee;
+context ieee.ieee_std_context;
+use ieee.math_real.all;
+
+entity ent{idx} is
+  port (
+    clk : in std_logic;
+    d : in unsigned(7 downto 0);
+    q : out unsigned(7 downto 0)
+  );
+end entity;
+
+architecture rtl of ent{idx} is
+  signal r : unsigned(7 downto 0);
+begin
+  process (clk)
+  begin
+    if rising_edge(clk) then
+      r <= d + 1;
+    end if;
+  end process;
+  q <= r;
+end architecture;
+"
+            ),
+        )
+        .unwrap();
+    }
+}
+
+fn megabytes(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn main() {
+    let repo_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut config = Config::default();
+    config.append(
+        &Config::read_file_path(&repo_root.join("vhdl_libraries").join("vhdl_ls.toml"))
+            .expect("Failed to read installed config file"),
+        &mut MessagePrinter::default(),
+    );
+
+    let design_root = tempfile::tempdir().unwrap();
+    write_design(design_root.path());
+    config.append(
+        &Config::from_str("[libraries]\nlib.files = ['*.vhd']", design_root.path()).unwrap(),
+        &mut MessagePrinter::default(),
+    );
+
+    let before = ALLOCATOR.allocated.load(Ordering::Relaxed);
+    ALLOCATOR.peak.store(before, Ordering::Relaxed);
+
+    let mut project = Project::from_config(config, &mut NullMessages);
+    let parsed = ALLOCATOR.allocated.load(Ordering::Relaxed);
+    project.analyse();
+    let analyzed = ALLOCATOR.allocated.load(Ordering::Relaxed);
+    let peak = ALLOCATOR.peak.load(Ordering::Relaxed);
+
+    println!("parsed:   {:8.1} MB", megabytes(parsed - before));
+    println!("analyzed: {:8.1} MB", megabytes(analyzed - before));
+    println!("peak:     {:8.1} MB", megabytes(peak - before));
+    drop(project);
+}
@@ -0,0 +1,47 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this file,
+ * You can obtain one at http://mozilla.org/MPL/2.0/.
+ *
+ * Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+ *
+ * C interface of vhdl_lang built with the capi feature, see src/capi.rs.
+ */
+
+#ifndef VHDL_LANG_H
+#define VHDL_LANG_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define VHDL_OK 0
+#define VHDL_ERROR_NULL_POINTER 1
+#define VHDL_ERROR_INVALID_UTF8 2
+#define VHDL_ERROR_IO 3
+#define VHDL_ERROR_OUT_OF_RANGE 4
+#define VHDL_ERROR_CONFIG 5
+#define VHDL_ERROR_PANIC 6
+
+#define VHDL_SEVERITY_HINT 0
+#define VHDL_SEVERITY_INFO 1
+#define VHDL_SEVERITY_WARNING 2
+#define VHDL_SEVERITY_ERROR 3
+
+typedef struct VhdlHandle VhdlHandle;
+
+/* The strings are valid until the handle is freed, the line and column are one-based */
+typedef struct VhdlDiagnostic {
+    const char *file;
+    uint32_t line;
+    uint32_t column;
+    int severity;
+    const char *message;
+} VhdlDiagnostic;
+
+int vhdl_parse_file(const char *path, VhdlHandle **out_handle);
+int vhdl_analyze_project(const char *config_path, VhdlHandle **out_handle);
+int vhdl_get_diagnostic_count(const VhdlHandle *handle, size_t *out_count);
+int vhdl_get_diagnostic(const VhdlHandle *handle, size_t index, VhdlDiagnostic *out_diagnostic);
+void vhdl_free(VhdlHandle *handle);
+
+#endif
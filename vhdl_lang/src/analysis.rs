@@ -6,18 +6,31 @@
 
 #[macro_use]
 mod analyze;
+mod array_length;
+mod assertion;
 mod assignment;
 mod association;
+mod choice_coverage;
 mod concurrent;
+mod configuration;
+mod constant_condition;
 mod declarative;
+mod dependency_graph;
 mod design_unit;
+mod drivers;
+mod evaluate;
 mod expression;
+mod latch;
 mod literals;
 mod lock;
 mod names;
 mod overloaded;
 mod package_instance;
+mod progress;
+mod psl;
+mod purity;
 mod range;
+mod return_paths;
 mod root;
 mod scope;
 mod semantic;
@@ -25,11 +38,18 @@ mod sequential;
 mod standard;
 mod static_expression;
 mod subprogram;
+mod synthesis;
 mod target;
 mod types;
+mod uninitialized;
 
 #[cfg(test)]
 pub(crate) mod tests;
 pub(crate) use root::{Library, LockedUnit};
 
-pub use self::root::{DesignRoot, EntHierarchy};
+pub use self::dependency_graph::{CycleError, DependencyGraph, DependencyNode};
+pub use self::progress::{AnalysisPhase, AnalysisProgress};
+pub use self::root::{
+    DesignRoot, EntHierarchy, FileStatus, HoverInfo, SecondaryUnitDescription, TypeDescription,
+    UnitDescription,
+};
@@ -6,12 +6,15 @@
 
 use super::root::*;
 pub(crate) use super::scope::Scope;
+use crate::ast::search::Search;
 use crate::ast::*;
+use crate::call_hierarchy::find_callees;
 use crate::data::*;
 use crate::named_entity::*;
 use crate::syntax::TokenAccess;
-use fnv::FnvHashSet;
-use std::cell::RefCell;
+use crate::VHDLStandard;
+use fnv::{FnvHashMap, FnvHashSet};
+use std::cell::{Cell, RefCell};
 use std::ops::Deref;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -22,7 +25,7 @@ pub enum AnalysisError {
 
 impl AnalysisError {
     pub fn not_fatal_error(pos: impl AsRef<SrcPos>, msg: impl Into<String>) -> AnalysisError {
-        AnalysisError::NotFatal(Diagnostic::error(pos, msg))
+        AnalysisError::NotFatal(Diagnostic::error(pos, msg).with_code(ErrorCode::MismatchedKinds))
     }
 }
 
@@ -30,18 +33,57 @@ impl AnalysisError {
 #[must_use]
 pub struct CircularDependencyError {
     reference: Option<SrcPos>,
+    // Boxed to keep the error small since it is part of the result of all analysis
+    cycle: Option<Box<DependencyCycle>>,
+}
+
+/// The units that form a cycle of dependencies starting and ending with the same unit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyCycle {
+    pub units: Vec<UnitId>,
+    /// The positions where the units of the cycle use the next unit, except the first use
+    pub uses: Vec<SrcPos>,
 }
 
 impl CircularDependencyError {
     pub fn new(reference: Option<&SrcPos>) -> CircularDependencyError {
         CircularDependencyError {
             reference: reference.cloned(),
+            cycle: None,
+        }
+    }
+
+    pub fn with_cycle(self, cycle: Option<DependencyCycle>) -> CircularDependencyError {
+        CircularDependencyError {
+            cycle: cycle.map(Box::new),
+            ..self
         }
     }
 
     pub fn push_into(self, diagnostics: &mut dyn DiagnosticHandler) {
         if let Some(pos) = self.reference {
-            diagnostics.push(Diagnostic::error(pos, "Found circular dependency"));
+            let Some(cycle) = self.cycle else {
+                diagnostics.push(
+                    Diagnostic::error(pos, "Found circular dependency")
+                        .with_code(ErrorCode::CircularDependency),
+                );
+                return;
+            };
+            let message = format!(
+                "Found circular dependency: {}",
+                cycle
+                    .units
+                    .iter()
+                    .map(|unit_id| unit_id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            );
+            let mut diagnostic =
+                Diagnostic::error(pos, message).with_code(ErrorCode::CircularDependency);
+            for use_pos in cycle.uses.iter() {
+                diagnostic.add_related(use_pos, "Used here");
+            }
+            diagnostics.push(diagnostic);
         }
     }
 }
@@ -156,7 +198,21 @@ pub(super) struct AnalyzeContext<'a> {
     uses: RefCell<FnvHashSet<UnitId>>,
     missing_unit: RefCell<FnvHashSet<(Symbol, Symbol, Option<Symbol>)>>,
     uses_library_all: RefCell<FnvHashSet<Symbol>>,
+    // Nesting of the static expression currently being evaluated
+    pub(super) evaluation_depth: Cell<usize>,
+    // The static values of the generics of the instance whose port lengths are being evaluated
+    pub(super) generic_values: RefCell<FnvHashMap<EntityId, Value>>,
+    // The resolved types of the expressions and names of the unit, see DesignRoot::type_at
+    pub(super) expression_types: RefCell<Vec<(SrcPos, EntityId)>>,
+    // The position, caller and callee of the calls of the unit, see DesignRoot::incoming_calls
+    pub(super) calls: RefCell<Vec<(SrcPos, EntityId, EntityId)>>,
+    // The components whose binding to an entity has been checked at their first instance
+    pub(super) checked_components: RefCell<FnvHashSet<EntityId>>,
     pub ctx: &'a dyn TokenAccess,
+    // The VHDL revision of the unit being analyzed
+    pub(super) standard: VHDLStandard,
+    // Warn about constructs that are not synthesizable
+    pub(super) synthesis_lint: bool,
 }
 
 impl<'a> AnalyzeContext<'a> {
@@ -165,6 +221,7 @@ impl<'a> AnalyzeContext<'a> {
         current_unit: &UnitId,
         arena: &'a Arena,
         ctx: &'a dyn TokenAccess,
+        standard: VHDLStandard,
     ) -> AnalyzeContext<'a> {
         AnalyzeContext {
             work_sym: root.symbol_utf8("work"),
@@ -181,7 +238,16 @@ impl<'a> AnalyzeContext<'a> {
             uses: RefCell::new(FnvHashSet::default()),
             missing_unit: RefCell::new(FnvHashSet::default()),
             uses_library_all: RefCell::new(FnvHashSet::default()),
+            evaluation_depth: Cell::new(0),
+            generic_values: RefCell::new(FnvHashMap::default()),
+            expression_types: RefCell::new(Vec::new()),
+            calls: RefCell::new(Vec::new()),
+            checked_components: RefCell::new(FnvHashSet::default()),
             ctx,
+            standard,
+            synthesis_lint: root
+                .synthesis_lint_libraries
+                .contains(current_unit.library_name()),
         }
     }
 
@@ -197,6 +263,29 @@ impl<'a> AnalyzeContext<'a> {
         &self.current_unit
     }
 
+    /// Remember the type of the expression or name at the position once it is resolved
+    pub(super) fn record_expression_type(&self, pos: &SrcPos, typ: TypeEnt<'a>) {
+        self.expression_types
+            .borrow_mut()
+            .push((pos.clone(), typ.id()));
+    }
+
+    /// Record the calls within the declarations and statements of `caller` once they have
+    /// been analyzed
+    pub(super) fn record_calls(
+        &self,
+        caller: EntRef<'a>,
+        declarations: &[Declaration],
+        statements: &impl Search,
+    ) {
+        let callees = find_callees(self.arena, self.ctx, declarations, statements);
+        self.calls.borrow_mut().extend(
+            callees
+                .into_iter()
+                .map(|(pos, callee)| (pos, caller.id(), callee.id())),
+        );
+    }
+
     fn make_use_of(&self, use_pos: Option<&SrcPos>, unit_id: &UnitId) -> FatalResult {
         // Check local cache before taking lock
         if self.uses.borrow_mut().insert(unit_id.clone()) {
@@ -283,10 +372,10 @@ impl<'a> AnalyzeContext<'a> {
         if let Some(std_library) = self.get_library(&self.std_sym) {
             scope.make_potentially_visible(None, std_library);
 
-            let standard_region = self
-                .standard_package_region()
-                .expect("Expected standard package");
-            scope.make_all_potentially_visible(None, standard_region);
+            // The library std may be configured with files that do not contain the standard package
+            if let Some(standard_region) = self.standard_package_region() {
+                scope.make_all_potentially_visible(None, standard_region);
+            }
         }
 
         Ok(())
@@ -325,13 +414,19 @@ impl<'a> AnalyzeContext<'a> {
         // Change circular dependency reference when used by another unit during analysis
         // The error is changed from within the used unit into the position of the use of the unit
         if data.result().has_circular_dependency {
-            Err(CircularDependencyError::new(use_pos))
+            Err(self
+                .root
+                .circular_dependency(use_pos, &self.current_unit, unit.unit_id()))
         } else {
             Ok(data)
         }
     }
 
-    fn get_primary_unit(&self, library_name: &Symbol, name: &Symbol) -> Option<&'a LockedUnit> {
+    pub(super) fn get_primary_unit(
+        &self,
+        library_name: &Symbol,
+        name: &Symbol,
+    ) -> Option<&'a LockedUnit> {
         let units = self.root.get_library_units(library_name)?;
         if let Some(unit) = units.get(&UnitKey::Primary(name.clone())) {
             return Some(unit);
@@ -361,33 +456,62 @@ impl<'a> AnalyzeContext<'a> {
         entity_name: &Symbol,
         architecture_name: &Symbol,
     ) -> AnalysisResult<DesignEnt<'a>> {
+        let data =
+            self.get_architecture_analysis(library_name, pos, entity_name, architecture_name)?;
+        if let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = data.deref() {
+            if let Some(id) = arch.ident.decl.get() {
+                let ent = self.arena.get(id);
+                let design = DesignEnt::from_any(ent).ok_or_else(|| {
+                    // Almost impossible but better not fail silently
+                    Diagnostic::error(
+                        pos,
+                        format!(
+                            "Found non-design {} unit within library {}",
+                            ent.describe(),
+                            library_name
+                        ),
+                    )
+                    .with_code(ErrorCode::MismatchedKinds)
+                })?;
+                return Ok(design);
+            }
+        }
+
+        Err(AnalysisError::NotFatal(
+            Diagnostic::error(
+                pos,
+                format!(
+                "No architecture '{architecture_name}' for entity '{library_name}.{entity_name}'"
+            ),
+            )
+            .with_code(ErrorCode::MissingDecl),
+        ))
+    }
+
+    /// The analyzed architecture unit, which is always an architecture body
+    pub(super) fn get_architecture_analysis(
+        &self,
+        library_name: &Symbol,
+        pos: &SrcPos,
+        entity_name: &Symbol,
+        architecture_name: &Symbol,
+    ) -> AnalysisResult<UnitReadGuard<'a>> {
         if let Some(unit) = self.get_secondary_unit(library_name, entity_name, architecture_name) {
             let data = self.get_analysis(Some(pos), unit)?;
-            if let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = data.deref() {
-                if let Some(id) = arch.ident.decl.get() {
-                    let ent = self.arena.get(id);
-                    let design = DesignEnt::from_any(ent).ok_or_else(|| {
-                        // Almost impossible but better not fail silently
-                        Diagnostic::error(
-                            pos,
-                            format!(
-                                "Found non-design {} unit within library {}",
-                                ent.describe(),
-                                library_name
-                            ),
-                        )
-                    })?;
-                    return Ok(design);
-                }
+            if let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(_)) = data.deref() {
+                return Ok(data);
             }
         }
 
-        Err(AnalysisError::NotFatal(Diagnostic::error(
-            pos,
-            format!(
+        Err(AnalysisError::NotFatal(
+            Diagnostic::error(
+                pos,
+                format!(
                 "No architecture '{architecture_name}' for entity '{library_name}.{entity_name}'"
             ),
-        )))
+            )
+            .with_code(ErrorCode::MissingDecl),
+        ))
     }
 
     pub fn lookup_in_library(
@@ -412,6 +536,7 @@ impl<'a> AnalyzeContext<'a> {
                                     library_name
                                 ),
                             )
+                            .with_code(ErrorCode::MismatchedKinds)
                         })?;
                         return Ok(design);
                     }
@@ -419,10 +544,13 @@ impl<'a> AnalyzeContext<'a> {
             }
         }
 
-        Err(AnalysisError::NotFatal(Diagnostic::error(
-            pos,
-            format!("No primary unit '{primary_name}' within library '{library_name}'"),
-        )))
+        Err(AnalysisError::NotFatal(
+            Diagnostic::error(
+                pos,
+                format!("No primary unit '{primary_name}' within library '{library_name}'"),
+            )
+            .with_code(ErrorCode::MissingDecl),
+        ))
     }
 
     // Returns None when analyzing the standard package itsel
@@ -0,0 +1,191 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Lengths of one-dimensional arrays that are known during analysis
+//!
+//! An array has a static length when its index constraint has locally static bounds
+//! such as `(7 downto 0)` or `(0 to width - 1)` where `width` is a locally static
+//! constant. Anything that is not static has no length and is not checked.
+//!
+//! The length of a port such as `d : bit_vector(width - 1 downto 0)` is known for an
+//! instance that associates `width` with a locally static actual or leaves it
+//! unassociated when it has a locally static default.
+
+use super::analyze::*;
+use super::static_expression::bit_string_to_string;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use fnv::FnvHashMap;
+
+impl<'a> AnalyzeContext<'a> {
+    /// The length of a name denoting a statically constrained array object or subtype
+    /// or a slice with a static range
+    pub(crate) fn static_name_length(&self, name: &Name) -> Option<u64> {
+        let reference = match name {
+            Name::Designator(designator) => &designator.reference,
+            Name::Selected(_, suffix) => &suffix.item.reference,
+            Name::Slice(_, drange) => {
+                // Overflows within the range have already been reported when it was analyzed
+                return self
+                    .static_discrete_range(drange, &mut NullDiagnostics)
+                    .map(|range| range.length());
+            }
+            _ => return None,
+        };
+
+        let ent = self.arena.get(reference.get()?);
+        match ent.actual_kind() {
+            AnyEntKind::Object(obj) => obj.subtype.static_length(),
+            AnyEntKind::Type(Type::Subtype(subtype)) => subtype.static_length(),
+            _ => None,
+        }
+    }
+
+    /// The length of an expression with a number of elements known during analysis.
+    /// Aggregates have no static length since their elements may be arrays themselves
+    /// or be given by an others choice.
+    fn static_expression_length(&self, expr: &Expression) -> Option<u64> {
+        match expr {
+            Expression::Literal(Literal::String(string)) => Some(string.len() as u64),
+            Expression::Literal(Literal::BitString(bit_string)) => bit_string_to_string(bit_string)
+                .ok()
+                .map(|string| string.len() as u64),
+            Expression::Name(name) => self.static_name_length(name),
+            Expression::Qualified(qexpr) => self.static_expression_length(&qexpr.expr.item),
+            _ => None,
+        }
+    }
+
+    /// The static length of the target of an assignment
+    pub(crate) fn static_target_length(&self, target: &Target) -> Option<u64> {
+        match target {
+            Target::Name(name) => self.static_name_length(name),
+            Target::Aggregate(..) => None,
+        }
+    }
+
+    /// Report an error when an expression with a static length is assigned to
+    /// a target with a different static length
+    pub(crate) fn check_static_length(
+        &self,
+        target_length: Option<u64>,
+        expr: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let Some(target_length) = target_length else {
+            return;
+        };
+
+        if let Some(length) = self.static_expression_length(&expr.item) {
+            if length != target_length {
+                diagnostics.push(
+                    Diagnostic::error(
+                        &expr.pos,
+                        format!("Expected an array of length {target_length}, got length {length}"),
+                    )
+                    .with_code(ErrorCode::LengthMismatch),
+                );
+            }
+        }
+    }
+
+    /// Report an error when the static length of the actual of a port differs from
+    /// the length of the port evaluated with the static generic actuals of the instance
+    /// and the static defaults of the generics it leaves unassociated
+    pub(crate) fn check_instance_port_lengths(
+        &self,
+        generic_region: &FormalRegion<'a>,
+        generic_map: &[AssociationElement],
+        port_region: &FormalRegion<'a>,
+        port_map: &[AssociationElement],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let mut generic_values: FnvHashMap<_, _> = associated_actuals(generic_region, generic_map)
+            .filter_map(|(generic, pos, expr)| {
+                // Errors within the actual have already been reported when it was analyzed
+                let value = self.evaluate_static_pos(pos, expr, &mut NullDiagnostics)?;
+                Some((generic.id(), value))
+            })
+            .collect();
+        for generic in generic_region.iter() {
+            if let AnyEntKind::Object(Object {
+                default_value: Some(ref value),
+                ..
+            }) = generic.kind()
+            {
+                generic_values
+                    .entry(generic.id())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+        if generic_values.is_empty() {
+            return;
+        }
+
+        *self.generic_values.borrow_mut() = generic_values;
+        for (port, pos, expr) in associated_actuals(port_region, port_map) {
+            let AnyEntKind::Object(Object {
+                index_range: Some(ref drange),
+                ..
+            }) = port.kind()
+            else {
+                continue;
+            };
+            let Some(port_length) = self
+                .static_discrete_range(drange, &mut NullDiagnostics)
+                .map(|range| range.length())
+            else {
+                continue;
+            };
+            let Some(length) = self.static_expression_length(expr) else {
+                continue;
+            };
+
+            if length != port_length {
+                diagnostics.push(
+                    Diagnostic::error(
+                        pos,
+                        format!("Expected an array of length {port_length}, got length {length}"),
+                    )
+                    .opt_related(
+                        port.decl_pos(),
+                        format!(
+                            "Port '{}' has length {port_length} with the generics of the instance",
+                            port.designator()
+                        ),
+                    )
+                    .with_code(ErrorCode::LengthMismatch),
+                );
+            }
+        }
+        self.generic_values.borrow_mut().clear();
+    }
+}
+
+/// The interface elements of a formal region with the expression they are associated with,
+/// associations with a partial or converted formal are ignored
+fn associated_actuals<'a, 'e>(
+    region: &'e FormalRegion<'a>,
+    elements: &'e [AssociationElement],
+) -> impl Iterator<Item = (InterfaceEnt<'a>, &'e SrcPos, &'e Expression)> + 'e {
+    elements.iter().enumerate().filter_map(|(idx, element)| {
+        let ActualPart::Expression(ref expr) = element.actual.item else {
+            return None;
+        };
+        let ent = match element.formal {
+            None => region.nth(idx)?,
+            Some(WithPos {
+                item: Name::Designator(ref designator),
+                ..
+            }) => region
+                .iter()
+                .find(|ent| ent.designator() == &designator.item)?,
+            Some(_) => return None,
+        };
+        Some((ent, &element.actual.pos, expr))
+    })
+}
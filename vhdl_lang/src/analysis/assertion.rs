@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Lint of assertions whose condition is locally static
+//!
+//! The check is only done when enabled in the configuration since `assert false report ...`
+//! is a common idiom in testbenches.
+
+use super::analyze::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+impl AnalyzeContext<'_> {
+    /// An assertion that never fails has no effect, one that always fails with a severity
+    /// below error is probably meant to be a report statement
+    pub(crate) fn check_static_assertion(
+        &self,
+        condition: &WithPos<Expression>,
+        severity: Option<&WithPos<Expression>>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.root.lint_static_assertions {
+            return;
+        }
+        let Some(Value::Enumeration(Designator::Identifier(condition_value))) =
+            self.evaluate_static(condition, &mut NullDiagnostics)
+        else {
+            return;
+        };
+
+        if condition_value == self.root.symbol_utf8("true") {
+            diagnostics.push(
+                Diagnostic::warning(
+                    &condition.pos,
+                    "Assertion condition is always true, the assertion has no effect",
+                )
+                .with_code(ErrorCode::StaticAssertion),
+            );
+        } else if condition_value == self.root.symbol_utf8("false") {
+            // The default severity of an assertion is error
+            let Some(severity) = severity else {
+                return;
+            };
+            let Some(Value::Enumeration(Designator::Identifier(severity_value))) =
+                self.evaluate_static(severity, &mut NullDiagnostics)
+            else {
+                return;
+            };
+            let below_error = ["note", "warning"]
+                .iter()
+                .any(|name| severity_value == self.root.symbol_utf8(name));
+            if below_error {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        &condition.pos,
+                        format!(
+                            "Assertion condition is always false with severity {}, \
+                             use a report statement if this is intended",
+                            severity_value.name_utf8().to_ascii_lowercase()
+                        ),
+                    )
+                    .with_code(ErrorCode::StaticAssertion),
+                );
+            }
+        }
+    }
+}
@@ -23,9 +23,10 @@ impl<'a> AnalyzeContext<'a> {
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
         let ttyp = as_fatal(self.resolve_target(scope, target, assignment_type, diagnostics))?;
+        let target_length = self.static_target_length(&target.item);
         match rhs {
             AssignmentRightHand::Simple(expr) => {
-                self.analyze_expression_for_target(scope, ttyp, expr, diagnostics)?;
+                self.analyze_expression_for_target(scope, ttyp, target_length, expr, diagnostics)?;
             }
             AssignmentRightHand::Conditional(conditionals) => {
                 let Conditionals {
@@ -34,11 +35,23 @@ impl<'a> AnalyzeContext<'a> {
                 } = conditionals;
                 for conditional in conditionals {
                     let Conditional { condition, item } = conditional;
-                    self.analyze_expression_for_target(scope, ttyp, item, diagnostics)?;
+                    self.analyze_expression_for_target(
+                        scope,
+                        ttyp,
+                        target_length,
+                        item,
+                        diagnostics,
+                    )?;
                     self.boolean_expr(scope, condition, diagnostics)?;
                 }
                 if let Some(expr) = else_item {
-                    self.analyze_expression_for_target(scope, ttyp, expr, diagnostics)?;
+                    self.analyze_expression_for_target(
+                        scope,
+                        ttyp,
+                        target_length,
+                        expr,
+                        diagnostics,
+                    )?;
                 }
             }
             AssignmentRightHand::Selected(selection) => {
@@ -48,9 +61,19 @@ impl<'a> AnalyzeContext<'a> {
                 } = selection;
                 let ctyp = as_fatal(self.expr_unambiguous_type(scope, expression, diagnostics))?;
                 for Alternative { choices, item } in alternatives.iter_mut() {
-                    self.analyze_expression_for_target(scope, ttyp, item, diagnostics)?;
+                    self.analyze_expression_for_target(
+                        scope,
+                        ttyp,
+                        target_length,
+                        item,
+                        diagnostics,
+                    )?;
                     self.choice_with_ttyp(scope, ctyp, choices, diagnostics)?;
                 }
+                if let Some(ctyp) = ctyp {
+                    let choices = alternatives.iter().flat_map(|alt| alt.choices.iter());
+                    self.check_choice_coverage(expression, ctyp, choices, diagnostics);
+                }
             }
         }
         Ok(())
@@ -65,9 +88,10 @@ impl<'a> AnalyzeContext<'a> {
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
         let ttyp = as_fatal(self.resolve_target(scope, target, assignment_type, diagnostics))?;
+        let target_length = self.static_target_length(&target.item);
         match rhs {
             AssignmentRightHand::Simple(wavf) => {
-                self.analyze_waveform(scope, ttyp, wavf, diagnostics)?;
+                self.analyze_waveform(scope, ttyp, target_length, wavf, diagnostics)?;
             }
             AssignmentRightHand::Conditional(conditionals) => {
                 let Conditionals {
@@ -76,11 +100,11 @@ impl<'a> AnalyzeContext<'a> {
                 } = conditionals;
                 for conditional in conditionals {
                     let Conditional { condition, item } = conditional;
-                    self.analyze_waveform(scope, ttyp, item, diagnostics)?;
+                    self.analyze_waveform(scope, ttyp, target_length, item, diagnostics)?;
                     self.boolean_expr(scope, condition, diagnostics)?;
                 }
                 if let Some(wavf) = else_item {
-                    self.analyze_waveform(scope, ttyp, wavf, diagnostics)?;
+                    self.analyze_waveform(scope, ttyp, target_length, wavf, diagnostics)?;
                 }
             }
             AssignmentRightHand::Selected(selection) => {
@@ -90,9 +114,13 @@ impl<'a> AnalyzeContext<'a> {
                 } = selection;
                 let ctyp = as_fatal(self.expr_unambiguous_type(scope, expression, diagnostics))?;
                 for Alternative { choices, item } in alternatives.iter_mut() {
-                    self.analyze_waveform(scope, ttyp, item, diagnostics)?;
+                    self.analyze_waveform(scope, ttyp, target_length, item, diagnostics)?;
                     self.choice_with_ttyp(scope, ctyp, choices, diagnostics)?;
                 }
+                if let Some(ctyp) = ctyp {
+                    let choices = alternatives.iter().flat_map(|alt| alt.choices.iter());
+                    self.check_choice_coverage(expression, ctyp, choices, diagnostics);
+                }
             }
         }
         Ok(())
@@ -102,6 +130,7 @@ impl<'a> AnalyzeContext<'a> {
         &self,
         scope: &Scope<'a>,
         ttyp: Option<TypeEnt<'a>>,
+        target_length: Option<u64>,
         wavf: &mut Waveform,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
@@ -109,9 +138,16 @@ impl<'a> AnalyzeContext<'a> {
             Waveform::Elements(ref mut elems) => {
                 for elem in elems.iter_mut() {
                     let WaveformElement { value, after } = elem;
-                    self.analyze_expression_for_target(scope, ttyp, value, diagnostics)?;
+                    self.analyze_expression_for_target(
+                        scope,
+                        ttyp,
+                        target_length,
+                        value,
+                        diagnostics,
+                    )?;
                     if let Some(expr) = after {
                         self.expr_with_ttyp(scope, self.time(), expr, diagnostics)?;
+                        self.check_synthesis_delay(expr, diagnostics);
                     }
                 }
             }
@@ -124,11 +160,13 @@ impl<'a> AnalyzeContext<'a> {
         &self,
         scope: &Scope<'a>,
         ttyp: Option<TypeEnt<'a>>,
+        target_length: Option<u64>,
         expr: &mut WithPos<Expression>,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
         if let Some(ttyp) = ttyp {
             self.expr_with_ttyp(scope, ttyp, expr, diagnostics)?;
+            self.check_static_length(target_length, expr, diagnostics);
         } else {
             self.expr_unknown_ttyp(scope, expr, diagnostics)?;
         }
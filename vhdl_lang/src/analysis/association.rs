@@ -9,7 +9,7 @@ use fnv::FnvHashMap;
 use itertools::Itertools;
 
 use super::analyze::*;
-use super::names::ResolvedName;
+use super::names::{not_closely_related_reason, ResolvedName};
 use super::scope::*;
 use crate::ast::*;
 use crate::data::*;
@@ -109,14 +109,20 @@ impl<'a> AnalyzeContext<'a> {
                     {
                         Ok(resolved_formal)
                     } else {
-                        Err(Diagnostic::error(name_pos, "Invalid formal").into())
+                        Err(Diagnostic::error(name_pos, "Invalid formal")
+                            .with_code(ErrorCode::InvalidFormal)
+                            .into())
                     }
                 } else {
-                    Err(Diagnostic::error(name_pos, "Invalid formal").into())
+                    Err(Diagnostic::error(name_pos, "Invalid formal")
+                        .with_code(ErrorCode::InvalidFormal)
+                        .into())
                 }
             }
 
-            Name::SelectedAll(_) => Err(Diagnostic::error(name_pos, "Invalid formal").into()),
+            Name::SelectedAll(_) => Err(Diagnostic::error(name_pos, "Invalid formal")
+                .with_code(ErrorCode::InvalidFormal)
+                .into()),
             Name::Designator(designator) => {
                 let (idx, ent) = formal_region.lookup(name_pos, designator.designator())?;
                 designator.set_unique_reference(ent.inner());
@@ -133,18 +139,24 @@ impl<'a> AnalyzeContext<'a> {
 
                 if resolved_prefix.is_converted {
                     // Converted formals may not be further selected
-                    return Err(Diagnostic::error(name_pos, "Invalid formal").into());
+                    return Err(Diagnostic::error(name_pos, "Invalid formal")
+                        .with_code(ErrorCode::InvalidFormal)
+                        .into());
                 }
 
                 self.drange_unknown_type(scope, drange.as_mut(), diagnostics)?;
                 Ok(resolved_prefix.partial())
             }
-            Name::Attribute(..) => Err(Diagnostic::error(name_pos, "Invalid formal").into()),
+            Name::Attribute(..) => Err(Diagnostic::error(name_pos, "Invalid formal")
+                .with_code(ErrorCode::InvalidFormal)
+                .into()),
             Name::CallOrIndexed(ref mut fcall) => {
                 let prefix = if let Some(prefix) = fcall.name.item.prefix() {
                     prefix
                 } else {
-                    return Err(Diagnostic::error(name_pos, "Invalid formal").into());
+                    return Err(Diagnostic::error(name_pos, "Invalid formal")
+                        .with_code(ErrorCode::InvalidFormal)
+                        .into());
                 };
 
                 if formal_region.lookup(name_pos, prefix.designator()).is_err() {
@@ -165,7 +177,9 @@ impl<'a> AnalyzeContext<'a> {
                             )?,
                         )
                     } else {
-                        return Err(Diagnostic::error(name_pos, "Invalid formal conversion").into());
+                        return Err(Diagnostic::error(name_pos, "Invalid formal conversion")
+                            .with_code(ErrorCode::InvalidFormal)
+                            .into());
                     };
 
                     let converted_typ = match as_fatal(self.name_resolve(
@@ -180,11 +194,13 @@ impl<'a> AnalyzeContext<'a> {
                                 return Err(Diagnostic::error(
                                     pos,
                                     format!(
-                                        "{} cannot be converted to {}",
+                                        "{} cannot be converted to {}{}",
                                         ctyp.describe(),
-                                        typ.describe()
+                                        typ.describe(),
+                                        not_closely_related_reason(typ.base(), ctyp)
                                     ),
                                 )
+                                .with_code(ErrorCode::InvalidConversion)
                                 .into());
                             }
                             typ
@@ -207,7 +223,8 @@ impl<'a> AnalyzeContext<'a> {
                                 let mut diagnostic = Diagnostic::error(
                                     &fcall.name.pos,
                                     format!("Ambiguous call to function '{des}'"),
-                                );
+                                )
+                                .with_code(ErrorCode::Ambiguous);
 
                                 diagnostic.add_subprogram_candidates("might be", candidates);
 
@@ -225,13 +242,14 @@ impl<'a> AnalyzeContext<'a> {
                                         resolved_formal.type_mark.describe()
                                     ),
                                 )
+                                .with_code(ErrorCode::InvalidCall)
                                 .into());
                             }
                         }
                         _ => {
-                            return Err(
-                                Diagnostic::error(name_pos, "Invalid formal conversion").into()
-                            );
+                            return Err(Diagnostic::error(name_pos, "Invalid formal conversion")
+                                .with_code(ErrorCode::InvalidFormal)
+                                .into());
                         }
                     };
 
@@ -257,13 +275,19 @@ impl<'a> AnalyzeContext<'a> {
                     if let Some(resolved_formal) = resolved_prefix.partial_with_typ(new_typ) {
                         Ok(resolved_formal)
                     } else {
-                        Err(Diagnostic::error(name_pos, "Invalid formal").into())
+                        Err(Diagnostic::error(name_pos, "Invalid formal")
+                            .with_code(ErrorCode::InvalidFormal)
+                            .into())
                     }
                 } else {
-                    Err(Diagnostic::error(name_pos, "Invalid formal").into())
+                    Err(Diagnostic::error(name_pos, "Invalid formal")
+                        .with_code(ErrorCode::InvalidFormal)
+                        .into())
                 }
             }
-            Name::External(..) => Err(Diagnostic::error(name_pos, "Invalid formal").into()),
+            Name::External(..) => Err(Diagnostic::error(name_pos, "Invalid formal")
+                .with_code(ErrorCode::InvalidFormal)
+                .into()),
         }
     }
 
@@ -279,10 +303,13 @@ impl<'a> AnalyzeContext<'a> {
                 if is_positional {
                     fail = true;
 
-                    diagnostics.push(Diagnostic::error(
-                        formal,
-                        "Named arguments are not allowed before positional arguments",
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            formal,
+                            "Named arguments are not allowed before positional arguments",
+                        )
+                        .with_code(ErrorCode::InvalidCall),
+                    );
                 }
             } else {
                 is_positional = true;
@@ -330,7 +357,8 @@ impl<'a> AnalyzeContext<'a> {
             } else {
                 result.push((
                     &actual.pos,
-                    Err(Diagnostic::error(&actual.pos, "Unexpected extra argument")),
+                    Err(Diagnostic::error(&actual.pos, "Unexpected extra argument")
+                        .with_code(ErrorCode::InvalidCall)),
                 ));
             };
         }
@@ -353,13 +381,20 @@ impl<'a> AnalyzeContext<'a> {
                 Ok(resolved_formal) => {
                     if let Some((prev_pos, prev_formal)) = associated.get(&resolved_formal.idx) {
                         if !(resolved_formal.is_partial && prev_formal.is_partial) {
-                            let mut diag = Diagnostic::error(
-                                actual_pos,
+                            let message = if formal_region.typ == InterfaceType::Parameter {
                                 format!(
                                     "{} has already been associated",
                                     resolved_formal.iface.describe()
-                                ),
-                            );
+                                )
+                            } else {
+                                format!(
+                                    "{} '{}' is associated more than once",
+                                    formal_region.describe_kind(),
+                                    resolved_formal.iface.designator()
+                                )
+                            };
+                            let mut diag = Diagnostic::error(actual_pos, message)
+                                .with_code(ErrorCode::InvalidCall);
 
                             diag.add_related(prev_pos, "Previously associated here");
                             is_error = true;
@@ -377,23 +412,9 @@ impl<'a> AnalyzeContext<'a> {
         }
 
         for (idx, formal) in formal_region.iter().enumerate() {
-            if !(associated.contains_key(&idx)
-                // Default may be unconnected
-                || formal.has_default()
-                // Output ports are allowed to be unconnected
-                || (formal_region.typ == InterfaceType::Port && formal.is_out_or_inout_signal()))
-            {
-                let mut diagnostic = Diagnostic::error(
-                    error_pos,
-                    format!("No association of {}", formal.describe()),
-                );
-
-                if let Some(decl_pos) = formal.decl_pos() {
-                    diagnostic.add_related(decl_pos, "Defined here");
-                }
-
+            if !(associated.contains_key(&idx) || may_be_unassociated(formal_region, formal)) {
                 is_error = true;
-                diagnostics.push(diagnostic);
+                diagnostics.push(missing_association(error_pos, formal_region, formal));
             }
         }
 
@@ -457,18 +478,45 @@ impl<'a> AnalyzeContext<'a> {
                                     diagnostics,
                                 )?;
                             }
-                            self.expr_pos_with_ttyp(
-                                scope,
-                                resolved_formal.type_mark,
-                                &actual.pos,
-                                expr,
-                                diagnostics,
-                            )?;
+                            if let (Some(Mode::Out | Mode::Linkage), Expression::Name(name)) =
+                                (resolved_formal.iface.mode(), &mut *expr)
+                            {
+                                // The actual of an output is written, not read
+                                self.name_with_ttyp(
+                                    scope,
+                                    &actual.pos,
+                                    name,
+                                    resolved_formal.type_mark,
+                                    diagnostics,
+                                )?;
+                            } else {
+                                self.expr_pos_with_ttyp(
+                                    scope,
+                                    resolved_formal.type_mark,
+                                    &actual.pos,
+                                    expr,
+                                    diagnostics,
+                                )?;
+                            }
                         } else {
                             self.expr_pos_unknown_ttyp(scope, &actual.pos, expr, diagnostics)?;
                         }
                     }
-                    ActualPart::Open => {}
+                    ActualPart::Open => {
+                        // An open actual leaves the formal unassociated
+                        if let Ok(resolved_formal) = resolved_formal {
+                            if formal_region.typ != InterfaceType::Parameter
+                                && !resolved_formal.is_partial
+                                && !may_be_unassociated(formal_region, resolved_formal.iface)
+                            {
+                                diagnostics.push(missing_association(
+                                    &actual.pos,
+                                    formal_region,
+                                    resolved_formal.iface,
+                                ));
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -490,23 +538,35 @@ impl<'a> AnalyzeContext<'a> {
                 let Some(name) =
                     as_fatal(self.expression_as_name(expr, scope, actual_pos, diagnostics))?
                 else {
-                    diagnostics.error(actual_pos, "Expression must be a name denoting a signal");
+                    diagnostics.push(
+                        Diagnostic::error(
+                            actual_pos,
+                            "Expression must be a name denoting a signal",
+                        )
+                        .with_code(ErrorCode::MismatchedKinds),
+                    );
                     return Ok(());
                 };
                 if !matches!(name, ResolvedName::ObjectName(
                                                         ObjectName { base, .. },
                                                     ) if base.class() == ObjectClass::Signal)
                 {
-                    diagnostics.error(actual_pos, "Name must denote a signal name");
+                    diagnostics.push(
+                        Diagnostic::error(actual_pos, "Name must denote a signal name")
+                            .with_code(ErrorCode::MismatchedKinds),
+                    );
                 }
             }
             InterfaceClass::Variable => {
                 let Some(name) =
                     as_fatal(self.expression_as_name(expr, scope, actual_pos, diagnostics))?
                 else {
-                    diagnostics.error(
-                        actual_pos,
-                        "Expression must be a name denoting a variable or shared variable",
+                    diagnostics.push(
+                        Diagnostic::error(
+                            actual_pos,
+                            "Expression must be a name denoting a variable or shared variable",
+                        )
+                        .with_code(ErrorCode::MismatchedKinds),
                     );
                     return Ok(());
                 };
@@ -514,21 +574,30 @@ impl<'a> AnalyzeContext<'a> {
                                                         ObjectName { base, .. },
                                                     ) if base.class() == ObjectClass::Variable || base.class() == ObjectClass::SharedVariable)
                 {
-                    diagnostics.error(actual_pos, "Name must denote a variable name");
+                    diagnostics.push(
+                        Diagnostic::error(actual_pos, "Name must denote a variable name")
+                            .with_code(ErrorCode::MismatchedKinds),
+                    );
                 }
             }
             InterfaceClass::File => {
                 let Some(name) =
                     as_fatal(self.expression_as_name(expr, scope, actual_pos, diagnostics))?
                 else {
-                    diagnostics.error(actual_pos, "Expression must be a name denoting a file");
+                    diagnostics.push(
+                        Diagnostic::error(actual_pos, "Expression must be a name denoting a file")
+                            .with_code(ErrorCode::MismatchedKinds),
+                    );
                     return Ok(());
                 };
                 if !matches!(name, ResolvedName::Final(ent) if matches!(
                     ent.kind(),
                     AnyEntKind::File(_)
                 )) {
-                    diagnostics.error(actual_pos, "Name must denote a file name");
+                    diagnostics.push(
+                        Diagnostic::error(actual_pos, "Name must denote a file name")
+                            .with_code(ErrorCode::MismatchedKinds),
+                    );
                 }
             }
             _ => {}
@@ -553,6 +622,37 @@ impl<'a> AnalyzeContext<'a> {
     }
 }
 
+/// Formals with a default and output ports may be left without an association
+pub(super) fn may_be_unassociated(formal_region: &FormalRegion, formal: InterfaceEnt) -> bool {
+    formal.has_default()
+        || (formal_region.typ == InterfaceType::Port && formal.is_out_or_inout_signal())
+}
+
+fn missing_association(
+    pos: &SrcPos,
+    formal_region: &FormalRegion,
+    formal: InterfaceEnt,
+) -> Diagnostic {
+    let message = match formal_region.typ {
+        InterfaceType::Port => format!(
+            "Missing association for port '{}' of mode {} with no default",
+            formal.designator(),
+            formal.mode().unwrap_or(Mode::In)
+        ),
+        InterfaceType::Generic => format!(
+            "Missing association for generic '{}' with no default",
+            formal.designator()
+        ),
+        InterfaceType::Parameter => format!("No association of {}", formal.describe()),
+    };
+
+    let mut diagnostic = Diagnostic::error(pos, message).with_code(ErrorCode::InvalidCall);
+    if let Some(decl_pos) = formal.decl_pos() {
+        diagnostic.add_related(decl_pos, "Defined here");
+    }
+    diagnostic
+}
+
 fn to_formal_conversion_argument(
     parameters: &mut [AssociationElement],
 ) -> Option<(&SrcPos, &mut Box<Name>)> {
@@ -0,0 +1,274 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Coverage of the choices of case statements and selected assignments
+//!
+//! Every value of the selector must be covered exactly once unless there is an `others`
+//! choice, which must be the last choice. The values of an enumeration type are the positions
+//! of its literals and the values of an integer selector are those of its locally static
+//! subtype. Selectors of a subtype of std_ulogic such as std_logic only get a warning for
+//! missing choices since few designs cover all nine values. Other subtypes of enumeration
+//! types and integer selectors without a static range are only checked for duplicates.
+
+use super::analyze::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+/// The values a selector is checked against
+enum Domain<'a> {
+    /// The literals of an enumeration type in declaration order
+    Enum {
+        literals: Vec<EntRef<'a>>,
+        is_std_ulogic: bool,
+    },
+    Integer(Option<StaticRange>),
+}
+
+impl<'a> Domain<'a> {
+    /// The full range of values to be covered, if it is known
+    fn bounds(&self, ctyp: TypeEnt<'a>) -> Option<(i64, i64)> {
+        match self {
+            Domain::Enum { literals, .. } => {
+                let is_constrained = !matches!(ctyp.kind(), Type::Enum(..));
+                if literals.is_empty() || (is_constrained && !self.is_std_ulogic()) {
+                    None
+                } else {
+                    Some((0, literals.len() as i64 - 1))
+                }
+            }
+            Domain::Integer(range) => range
+                .filter(|range| range.length() > 0)
+                .map(|range| (range.low(), range.high())),
+        }
+    }
+
+    fn is_std_ulogic(&self) -> bool {
+        matches!(
+            self,
+            Domain::Enum {
+                is_std_ulogic: true,
+                ..
+            }
+        )
+    }
+
+    fn describe(&self, low: i64, high: i64) -> Vec<String> {
+        match self {
+            Domain::Enum { literals, .. } => (low..=high)
+                .map(|idx| literals[idx as usize].designator().to_string())
+                .collect(),
+            Domain::Integer(_) if low == high => vec![low.to_string()],
+            Domain::Integer(_) => vec![format!("{low} to {high}")],
+        }
+    }
+}
+
+/// The values covered by a single choice
+enum Covered {
+    Values(i64, i64),
+    Others,
+    /// The choice is not locally static or could not be analyzed
+    Unknown,
+}
+
+impl<'a> AnalyzeContext<'a> {
+    pub(crate) fn check_choice_coverage<'c>(
+        &self,
+        selector: &WithPos<Expression>,
+        ctyp: TypeEnt<'a>,
+        choices: impl Iterator<Item = &'c WithPos<Choice>>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let Some(domain) = self.choice_domain(selector, ctyp) else {
+            return;
+        };
+
+        // Covered values as inclusive ranges
+        let mut covered: Vec<(i64, i64, &SrcPos)> = Vec::new();
+        let mut others_pos: Option<&SrcPos> = None;
+        let mut is_complete = true;
+
+        for choice in choices {
+            if let Some(others_pos) = others_pos {
+                diagnostics.push(
+                    Diagnostic::error(&choice.pos, "Choice after 'others' is unreachable")
+                        .with_code(ErrorCode::RedundantChoice)
+                        .related(others_pos, "The 'others' choice is here"),
+                );
+                continue;
+            }
+
+            let (low, high) = match self.covered_values(&domain, choice) {
+                Covered::Values(low, high) => (low, high),
+                Covered::Others => {
+                    others_pos = Some(&choice.pos);
+                    continue;
+                }
+                Covered::Unknown => {
+                    is_complete = false;
+                    continue;
+                }
+            };
+
+            if low > high {
+                continue;
+            }
+
+            for (prev_low, prev_high, prev_pos) in covered.iter() {
+                let overlap = (low.max(*prev_low), high.min(*prev_high));
+                if overlap.0 <= overlap.1 {
+                    for value in domain.describe(overlap.0, overlap.1) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                &choice.pos,
+                                format!("Choice {value} is covered more than once"),
+                            )
+                            .with_code(ErrorCode::RedundantChoice)
+                            .related(*prev_pos, "Previously covered here"),
+                        );
+                    }
+                }
+            }
+            covered.push((low, high, &choice.pos));
+        }
+
+        if others_pos.is_some()
+            || !is_complete
+            || (domain.is_std_ulogic() && self.root.ignore_incomplete_std_logic_case)
+        {
+            return;
+        }
+
+        let Some((low, high)) = domain.bounds(ctyp) else {
+            return;
+        };
+
+        let mut missing = Vec::new();
+        let mut next = Some(low);
+        covered.sort_by_key(|(low, ..)| *low);
+        for (covered_low, covered_high, _) in covered {
+            let Some(value) = next.filter(|value| *value <= high) else {
+                break;
+            };
+            if covered_low > value {
+                missing.extend(domain.describe(value, (covered_low - 1).min(high)));
+            }
+            if covered_high >= value {
+                next = covered_high.checked_add(1);
+            }
+        }
+        if let Some(value) = next.filter(|value| *value <= high) {
+            missing.extend(domain.describe(value, high));
+        }
+
+        if !missing.is_empty() {
+            let message = format!("Missing choice(s): {}", missing.join(", "));
+            if domain.is_std_ulogic() {
+                diagnostics.push(
+                    Diagnostic::warning(&selector.pos, message).with_code(ErrorCode::MissingChoice),
+                );
+            } else {
+                diagnostics.push(
+                    Diagnostic::error(&selector.pos, message).with_code(ErrorCode::MissingChoice),
+                );
+            }
+        }
+    }
+
+    fn choice_domain(
+        &self,
+        selector: &WithPos<Expression>,
+        ctyp: TypeEnt<'a>,
+    ) -> Option<Domain<'a>> {
+        let base_type = ctyp.base_type();
+        match base_type.kind() {
+            Type::Enum(..) => Some(Domain::Enum {
+                literals: base_type
+                    .implicits
+                    .iter()
+                    .copied()
+                    .filter(|ent| {
+                        matches!(
+                            ent.kind(),
+                            AnyEntKind::Overloaded(Overloaded::EnumLiteral(..))
+                        )
+                    })
+                    .collect(),
+                is_std_ulogic: self.root.std_ulogic == Some(base_type.id()),
+            }),
+            Type::Integer => {
+                let range = match &selector.item {
+                    Expression::Name(name) => self.static_name_range(name, &mut NullDiagnostics),
+                    _ => None,
+                };
+                Some(Domain::Integer(range))
+            }
+            _ => None,
+        }
+    }
+
+    fn covered_values(&self, domain: &Domain<'a>, choice: &WithPos<Choice>) -> Covered {
+        let value = |pos: &SrcPos, expr: &Expression| match (
+            domain,
+            self.evaluate_static_pos(pos, expr, &mut NullDiagnostics)?,
+        ) {
+            (Domain::Enum { literals, .. }, Value::Enumeration(designator)) => literals
+                .iter()
+                .position(|literal| literal.designator() == &designator)
+                .map(|idx| idx as i64),
+            (Domain::Integer(_), Value::Integer(value)) => Some(value),
+            _ => None,
+        };
+
+        let values = match &choice.item {
+            Choice::Others => return Covered::Others,
+            Choice::Expression(expr) => value(&choice.pos, expr).map(|value| (value, value)),
+            Choice::DiscreteRange(DiscreteRange::Range(crate::ast::Range::Range(constraint))) => {
+                value(&constraint.left_expr.pos, &constraint.left_expr.item)
+                    .zip(value(
+                        &constraint.right_expr.pos,
+                        &constraint.right_expr.item,
+                    ))
+                    .map(|(left, right)| match constraint.direction {
+                        Direction::Ascending => (left, right),
+                        Direction::Descending => (right, left),
+                    })
+            }
+            Choice::DiscreteRange(DiscreteRange::Discrete(type_mark, None))
+                if type_mark.item.attr.is_none() =>
+            {
+                let name = &type_mark.item.name.item;
+                match domain {
+                    Domain::Enum { literals, .. } => {
+                        // The type itself covers all of its literals
+                        let is_type = match name {
+                            Name::Designator(designator) => designator.reference.get(),
+                            Name::Selected(_, suffix) => suffix.item.reference.get(),
+                            _ => None,
+                        }
+                        .map(|id| {
+                            matches!(self.arena.get(id).kind(), AnyEntKind::Type(Type::Enum(..)))
+                        })
+                        .unwrap_or(false);
+
+                        if is_type {
+                            Some((0, literals.len() as i64 - 1))
+                        } else {
+                            None
+                        }
+                    }
+                    Domain::Integer(_) => self
+                        .static_name_range(name, &mut NullDiagnostics)
+                        .map(|range| (range.low(), range.high())),
+                }
+            }
+            Choice::DiscreteRange(..) => None,
+        };
+
+        values.map_or(Covered::Unknown, |(low, high)| Covered::Values(low, high))
+    }
+}
@@ -12,8 +12,12 @@ use crate::ast::*;
 use crate::data::*;
 use crate::named_entity::*;
 use analyze::*;
+use association::may_be_unassociated;
 use target::AssignmentType;
 
+/// The custom attribute that excludes a component from the comparison with its entity
+const PARTIAL_COMPONENT_ATTRIBUTE: &str = "partial_component";
+
 impl<'a> AnalyzeContext<'a> {
     pub fn analyze_concurrent_part(
         &self,
@@ -54,12 +58,13 @@ impl<'a> AnalyzeContext<'a> {
                 scope.add(ent, diagnostics);
             } else if statement.statement.item.can_have_label() {
                 // Generate an anonymous label if it is not explicitly defined
+                // The position of the statement is used so that it can be shown in the document outline
                 let ent = self.arena.alloc(
                     Designator::Anonymous(scope.next_anonymous()),
                     Some(parent),
                     Related::None,
                     AnyEntKind::Concurrent(statement.statement.item.label_typ()),
-                    None,
+                    Some(statement.statement.pos.clone()),
                 );
                 statement.label.decl.set(ent.id());
             }
@@ -77,10 +82,28 @@ impl<'a> AnalyzeContext<'a> {
     ) -> FatalResult {
         match statement.statement.item {
             ConcurrentStatement::Block(ref mut block) => {
+                let nested = scope.nested();
                 if let Some(ref mut guard_condition) = block.guard_condition {
                     self.boolean_expr(scope, guard_condition, diagnostics)?;
+                    // LRM 11.2 A guarded block implicitly declares the boolean signal GUARD
+                    let guard = self.arena.alloc(
+                        Designator::Identifier(self.root.symbol_utf8("guard")),
+                        Some(parent),
+                        Related::None,
+                        AnyEntKind::Object(Object {
+                            class: ObjectClass::Signal,
+                            iface: None,
+                            subtype: Subtype::new(self.boolean()),
+                            has_default: true,
+                            static_value: None,
+                            signal_kind: None,
+                            index_range: None,
+                            default_value: None,
+                        }),
+                        Some(guard_condition.pos.clone()),
+                    );
+                    nested.add(guard, diagnostics);
                 }
-                let nested = scope.nested();
                 if let Some(ref mut list) = block.header.generic_clause {
                     self.analyze_interface_list(&nested, parent, list, diagnostics)?;
                 }
@@ -124,6 +147,10 @@ impl<'a> AnalyzeContext<'a> {
                 self.define_labels_for_sequential_part(&nested, parent, statements, diagnostics)?;
                 self.analyze_declarative_part(&nested, parent, decl, diagnostics)?;
                 self.analyze_sequential_part(&nested, parent, statements, diagnostics)?;
+                self.record_calls(parent, decl, statements);
+                self.check_uninitialized_variables(decl, statements, diagnostics);
+                let label = statement.label.tree.as_ref().map(|label| &label.pos);
+                self.check_latches(label, &statement.statement.pos, process, diagnostics);
             }
             ConcurrentStatement::ForGenerate(ref mut gen) => {
                 let ForGenerateStatement {
@@ -134,11 +161,27 @@ impl<'a> AnalyzeContext<'a> {
                     ..
                 } = gen;
                 let typ = as_fatal(self.drange_type(scope, discrete_range, diagnostics))?;
+                // LRM 11.8 The generate parameter is a constant of the base type of the range
+                // where a range of universal integers is converted to INTEGER
+                let kind = match typ {
+                    Some(typ) => AnyEntKind::Object(Object {
+                        class: ObjectClass::Constant,
+                        iface: None,
+                        subtype: Subtype::new(if typ == self.universal_integer() {
+                            self.integer()
+                        } else {
+                            typ.into()
+                        }),
+                        has_default: true,
+                        static_value: None,
+                        signal_kind: None,
+                        index_range: None,
+                        default_value: None,
+                    }),
+                    None => AnyEntKind::LoopParameter(None),
+                };
                 let nested = scope.nested();
-                nested.add(
-                    index_name.define(self.arena, parent, AnyEntKind::LoopParameter(typ)),
-                    diagnostics,
-                );
+                nested.add(index_name.define(self.arena, parent, kind), diagnostics);
                 self.analyze_generate_body(&nested, parent, body, diagnostics)?;
             }
             ConcurrentStatement::IfGenerate(ref mut gen) => {
@@ -196,6 +239,7 @@ impl<'a> AnalyzeContext<'a> {
             ConcurrentStatement::ProcedureCall(ref mut pcall) => {
                 let ConcurrentProcedureCall { call, .. } = pcall;
                 self.analyze_procedure_call(scope, call, diagnostics)?;
+                self.record_calls(parent, &[], &call.item);
             }
             ConcurrentStatement::Assert(ref mut assert) => {
                 let ConcurrentAssertStatement {
@@ -207,14 +251,24 @@ impl<'a> AnalyzeContext<'a> {
                             severity,
                         },
                 } = assert;
-                self.boolean_expr(scope, condition, diagnostics)?;
+                // `assert p;` is a PSL directive when p is a property
+                self.psl_boolean(scope, condition, diagnostics)?;
                 if let Some(expr) = report {
                     self.expr_with_ttyp(scope, self.string(), expr, diagnostics)?;
                 }
                 if let Some(expr) = severity {
                     self.expr_with_ttyp(scope, self.severity_level(), expr, diagnostics)?;
                 }
+                self.check_static_assertion(condition, severity.as_ref(), diagnostics);
+            }
+            ConcurrentStatement::PslDirective(ref mut directive) => {
+                self.analyze_psl_directive(scope, directive, diagnostics)?;
             }
+            ConcurrentStatement::PslDeclaration(ref mut decl) => {
+                self.analyze_psl_declaration(scope, parent, decl, diagnostics)?;
+            }
+            // The syntax error has already been reported
+            ConcurrentStatement::Error => {}
         };
         Ok(())
     }
@@ -245,10 +299,10 @@ impl<'a> AnalyzeContext<'a> {
         }
 
         // Pre-declare labels
-        self.define_labels_for_concurrent_part(scope, parent, statements, diagnostics)?;
+        self.define_labels_for_concurrent_part(scope, inner_parent, statements, diagnostics)?;
 
         if let Some(ref mut decl) = decl {
-            self.analyze_declarative_part(scope, parent, decl, diagnostics)?;
+            self.analyze_declarative_part(scope, inner_parent, decl, diagnostics)?;
         }
         self.analyze_concurrent_part(scope, inner_parent, statements, diagnostics)?;
 
@@ -263,75 +317,25 @@ impl<'a> AnalyzeContext<'a> {
     ) -> FatalResult {
         match instance.unit {
             InstantiatedUnit::Entity(ref mut entity_name, ref mut architecture_name) => {
-                let Some(resolved) = as_fatal(self.name_resolve(
+                let Some((ent, ent_region)) = as_fatal(self.resolve_entity_aspect(
                     scope,
-                    &entity_name.pos,
-                    &mut entity_name.item,
+                    entity_name,
+                    architecture_name.as_mut(),
                     diagnostics,
                 ))?
                 else {
                     return Ok(());
                 };
-                match resolved {
-                    ResolvedName::Design(ent) => match ent.kind() {
-                        Design::Entity(_, ent_region) => {
-                            if let Designator::Identifier(entity_ident) = ent.designator() {
-                                if let Some(library_name) = ent.library_name() {
-                                    if let Some(ref mut architecture_name) = architecture_name {
-                                        match self.get_architecture(
-                                            library_name,
-                                            &architecture_name.item.pos,
-                                            entity_ident,
-                                            &architecture_name.item.item,
-                                        ) {
-                                            Ok(arch) => {
-                                                architecture_name.set_unique_reference(&arch);
-                                            }
-                                            Err(err) => {
-                                                diagnostics.push(err.into_non_fatal()?);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-
-                            let (generic_region, port_region) = ent_region.to_entity_formal();
-
-                            self.check_association(
-                                &entity_name.pos,
-                                &generic_region,
-                                scope,
-                                instance
-                                    .generic_map
-                                    .as_mut()
-                                    .map(|it| it.list.items.as_mut_slice())
-                                    .unwrap_or(&mut []),
-                                diagnostics,
-                            )?;
-                            self.check_association(
-                                &entity_name.pos,
-                                &port_region,
-                                scope,
-                                instance
-                                    .port_map
-                                    .as_mut()
-                                    .map(|it| it.list.items.as_mut_slice())
-                                    .unwrap_or(&mut []),
-                                diagnostics,
-                            )?;
-                            Ok(())
-                        }
-                        _ => {
-                            diagnostics
-                                .push(resolved.kind_error(entity_name.suffix_pos(), "entity"));
-                            Ok(())
-                        }
-                    },
-                    other => {
-                        diagnostics.push(other.kind_error(entity_name.suffix_pos(), "entity"));
-                        Ok(())
-                    }
-                }
+
+                self.check_instance_association(
+                    scope,
+                    &entity_name.pos,
+                    ent,
+                    ent_region,
+                    &mut instance.generic_map,
+                    &mut instance.port_map,
+                    diagnostics,
+                )
             }
             InstantiatedUnit::Component(ref mut component_name) => {
                 let Some(resolved) = as_fatal(self.name_resolve(
@@ -354,30 +358,16 @@ impl<'a> AnalyzeContext<'a> {
                 };
 
                 if let AnyEntKind::Component(ent_region) = ent.kind() {
-                    let (generic_region, port_region) = ent_region.to_entity_formal();
-                    self.check_association(
-                        &component_name.pos,
-                        &generic_region,
+                    self.check_instance_association(
                         scope,
-                        instance
-                            .generic_map
-                            .as_mut()
-                            .map(|it| it.list.items.as_mut_slice())
-                            .unwrap_or(&mut []),
-                        diagnostics,
-                    )?;
-                    self.check_association(
                         &component_name.pos,
-                        &port_region,
-                        scope,
-                        instance
-                            .port_map
-                            .as_mut()
-                            .map(|it| it.list.items.as_mut_slice())
-                            .unwrap_or(&mut []),
+                        ent,
+                        ent_region,
+                        &mut instance.generic_map,
+                        &mut instance.port_map,
                         diagnostics,
                     )?;
-                    Ok(())
+                    self.check_component_binding(&component_name.pos, ent, ent_region, diagnostics)
                 } else {
                     diagnostics.push(resolved.kind_error(component_name.suffix_pos(), "component"));
                     Ok(())
@@ -408,6 +398,281 @@ impl<'a> AnalyzeContext<'a> {
         }
     }
 
+    /// Check the generic and port map of an instance of an entity or component.
+    /// When there are generic types, subprograms or packages the generic map is associated
+    /// like that of a package instance and the ports are instantiated with the actual types.
+    #[allow(clippy::too_many_arguments)]
+    fn check_instance_association(
+        &self,
+        scope: &Scope<'a>,
+        pos: &SrcPos,
+        ent: EntRef<'a>,
+        region: &'a Region<'a>,
+        generic_map: &mut Option<MapAspect>,
+        port_map: &mut Option<MapAspect>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let (generic_region, port_region) = region.to_entity_formal();
+        let (generic_region, mut port_region) =
+            (generic_region.with_owner(ent), port_region.with_owner(ent));
+        let generic_map = generic_map
+            .as_mut()
+            .map(|it| it.list.items.as_mut_slice())
+            .unwrap_or(&mut []);
+
+        let (generics, _) = region.to_package_generic();
+        let generic_types: Vec<_> = generics
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, generic)| match generic {
+                GpkgInterfaceEnt::Type(typ) => Some((idx, typ)),
+                _ => None,
+            })
+            .collect();
+
+        if generics
+            .iter()
+            .all(|generic| matches!(generic, GpkgInterfaceEnt::Constant(_)))
+        {
+            self.check_association(pos, &generic_region, scope, generic_map, diagnostics)?;
+        } else {
+            let mapping = as_fatal(self.generic_map(scope, generics, generic_map, diagnostics))?;
+
+            // A generic type has no default and must always be associated
+            for (idx, typ) in generic_types.iter() {
+                let is_associated =
+                    generic_map
+                        .iter()
+                        .enumerate()
+                        .any(|(assoc_idx, assoc)| match &assoc.formal {
+                            Some(formal) => matches!(
+                                &formal.item,
+                                Name::Designator(des) if des.item == *typ.designator()
+                            ),
+                            None => assoc_idx == *idx,
+                        });
+                if !is_associated {
+                    let mut diagnostic = Diagnostic::error(
+                        pos,
+                        format!(
+                            "Missing association for generic type '{}'",
+                            typ.designator()
+                        ),
+                    )
+                    .with_code(ErrorCode::InvalidCall);
+                    if let Some(decl_pos) = typ.decl_pos() {
+                        diagnostic.add_related(decl_pos, "Defined here");
+                    }
+                    diagnostics.push(diagnostic);
+                }
+            }
+
+            let Some(mapping) = mapping.filter(|mapping| {
+                generic_types
+                    .iter()
+                    .all(|(_, typ)| mapping.contains_key(&typ.id()))
+            }) else {
+                // The types of the ports are unknown without the actual types
+                return self.analyze_map_aspect(scope, port_map, diagnostics);
+            };
+
+            let mut ports = Vec::with_capacity(port_region.len());
+            for port in port_region.iter() {
+                match self.instantiate(None, &mapping, port.inner()) {
+                    Ok(inst) => ports.extend(InterfaceEnt::from_any(inst)),
+                    Err(err) => {
+                        diagnostics.push(
+                            Diagnostic::error(pos, err)
+                                .opt_related(port.decl_pos(), "When instantiating this port")
+                                .with_code(ErrorCode::InvalidInstantiation),
+                        );
+                        return Ok(());
+                    }
+                }
+            }
+            port_region = FormalRegion::new_with(InterfaceType::Port, ports).with_owner(ent);
+        }
+
+        let port_map = port_map
+            .as_mut()
+            .map(|it| it.list.items.as_mut_slice())
+            .unwrap_or(&mut []);
+        self.check_association(pos, &port_region, scope, port_map, diagnostics)?;
+        self.check_instance_port_lengths(
+            &generic_region,
+            generic_map,
+            &port_region,
+            port_map,
+            diagnostics,
+        );
+        Ok(())
+    }
+
+    /// Compare the generics and ports of an instantiated component with those of the entity
+    /// with the same name in the work library that the component is bound to by default.
+    /// Components that intentionally declare fewer generics or ports than the entity can be
+    /// excluded by an attribute specification such as
+    /// `attribute partial_component of comp : component is true;`
+    /// Each component is only compared at its first instance within the unit.
+    fn check_component_binding(
+        &self,
+        pos: &SrcPos,
+        component: EntRef<'a>,
+        component_region: &'a Region<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        if component
+            .get_attribute(&self.root.symbol_utf8(PARTIAL_COMPONENT_ATTRIBUTE))
+            .is_some()
+            || !self.checked_components.borrow_mut().insert(component.id())
+        {
+            return Ok(());
+        }
+        let entity =
+            match self.lookup_in_library(self.work_library_name(), pos, component.designator()) {
+                Ok(entity) => entity,
+                // Without an entity the component is a black box or is bound by a configuration
+                Err(err) => {
+                    let _ = err.into_non_fatal()?;
+                    return Ok(());
+                }
+            };
+        let Design::Entity(_, entity_region) = entity.kind() else {
+            return Ok(());
+        };
+
+        let (component_generics, component_ports) = component_region.to_entity_formal();
+        let (entity_generics, entity_ports) = entity_region.to_entity_formal();
+        let entity_name = format!("{}.{}", self.work_library_name(), entity.designator());
+        let mismatch =
+            |message: String, component_pos: Option<&SrcPos>, entity_pos: Option<&SrcPos>| {
+                let mut diagnostic =
+                    Diagnostic::warning(pos, message).with_code(ErrorCode::ComponentMismatch);
+                if let Some(component_pos) = component_pos.or(component.decl_pos()) {
+                    diagnostic.add_related(component_pos, "Declared in the component here");
+                }
+                if let Some(entity_pos) = entity_pos.or(entity.decl_pos()) {
+                    diagnostic.add_related(entity_pos, "Declared in the entity here");
+                }
+                diagnostic
+            };
+
+        for (component_formals, entity_formals) in [
+            (&component_generics, &entity_generics),
+            (&component_ports, &entity_ports),
+        ] {
+            let kind = match entity_formals.typ {
+                InterfaceType::Generic => "Generic",
+                _ => "Port",
+            };
+            for formal in entity_formals.iter() {
+                let designator = formal.designator();
+                let Some(local) = component_formals
+                    .iter()
+                    .find(|local| local.designator() == designator)
+                else {
+                    if !may_be_unassociated(entity_formals, formal) {
+                        diagnostics.push(mismatch(
+                            format!(
+                                "{kind} '{designator}' of entity '{entity_name}' is missing from component '{}'",
+                                component.designator()
+                            ),
+                            None,
+                            formal.decl_pos(),
+                        ));
+                    }
+                    continue;
+                };
+
+                if local.mode() != formal.mode() {
+                    diagnostics.push(mismatch(
+                        format!(
+                            "{kind} '{designator}' has mode {} in component '{}' but mode {} in entity '{entity_name}'",
+                            local.mode().unwrap_or(Mode::In),
+                            component.designator(),
+                            formal.mode().unwrap_or(Mode::In),
+                        ),
+                        local.decl_pos(),
+                        formal.decl_pos(),
+                    ));
+                } else if local.base_type() != formal.base_type()
+                    && !matches!(local.base_type().kind(), Type::Interface)
+                    && !matches!(formal.base_type().kind(), Type::Interface)
+                {
+                    diagnostics.push(mismatch(
+                        format!(
+                            "{kind} '{designator}' has {} in component '{}' but {} in entity '{entity_name}'",
+                            local.type_mark().describe(),
+                            component.designator(),
+                            formal.type_mark().describe(),
+                        ),
+                        local.decl_pos(),
+                        formal.decl_pos(),
+                    ));
+                }
+            }
+
+            for local in component_formals.iter() {
+                let designator = local.designator();
+                if !entity_formals
+                    .iter()
+                    .any(|formal| formal.designator() == designator)
+                {
+                    diagnostics.push(mismatch(
+                        format!(
+                            "{kind} '{designator}' of component '{}' does not exist in entity '{entity_name}'",
+                            component.designator()
+                        ),
+                        local.decl_pos(),
+                        None,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the entity name and optional architecture name of an
+    /// entity instantiation or a binding indication, returning the entity region
+    pub fn resolve_entity_aspect(
+        &self,
+        scope: &Scope<'a>,
+        entity_name: &mut WithPos<Name>,
+        architecture_name: Option<&mut WithRef<Ident>>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> EvalResult<(EntRef<'a>, &'a Region<'a>)> {
+        let resolved =
+            self.name_resolve(scope, &entity_name.pos, &mut entity_name.item, diagnostics)?;
+        let ResolvedName::Design(ent) = resolved else {
+            diagnostics.push(resolved.kind_error(entity_name.suffix_pos(), "entity"));
+            return Err(EvalError::Unknown);
+        };
+        let AnyEntKind::Design(Design::Entity(_, ent_region)) = ent.0.kind() else {
+            diagnostics.push(resolved.kind_error(entity_name.suffix_pos(), "entity"));
+            return Err(EvalError::Unknown);
+        };
+
+        if let (Designator::Identifier(entity_ident), Some(library_name), Some(architecture_name)) =
+            (ent.designator(), ent.library_name(), architecture_name)
+        {
+            match self.get_architecture(
+                library_name,
+                &architecture_name.item.pos,
+                entity_ident,
+                &architecture_name.item.item,
+            ) {
+                Ok(arch) => {
+                    architecture_name.set_unique_reference(&arch);
+                }
+                Err(err) => {
+                    diagnostics.push(err.into_non_fatal()?);
+                }
+            }
+        }
+
+        Ok((ent.0, ent_region))
+    }
+
     pub fn analyze_map_aspect(
         &self,
         scope: &Scope<'a>,
@@ -435,21 +700,27 @@ impl<'a> AnalyzeContext<'a> {
                 diagnostics,
             ))? {
                 if object_name.base.class() != ObjectClass::Signal {
-                    diagnostics.error(
-                        &name.pos,
-                        format!(
-                            "{} is not a signal and cannot be in a sensitivity list",
-                            object_name.base.describe_class()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!(
+                                "{} is not a signal and cannot be in a sensitivity list",
+                                object_name.base.describe_class()
+                            ),
+                        )
+                        .with_code(ErrorCode::SensitivityList),
                     )
                 } else if object_name.base.mode() == Some(Mode::Out) && !object_name.base.is_port()
                 {
-                    diagnostics.error(
-                        &name.pos,
-                        format!(
-                            "{} cannot be in a sensitivity list",
-                            object_name.base.describe_class()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!(
+                                "{} cannot be in a sensitivity list",
+                                object_name.base.describe_class()
+                            ),
+                        )
+                        .with_code(ErrorCode::SensitivityList),
                     )
                 }
             }
@@ -0,0 +1,542 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! LRM 3.4 Configuration declarations
+//!
+//! The block configurations of a configuration declaration are checked against the analyzed
+//! architecture they configure. Nested block configurations must name a block or generate
+//! statement and component configurations must name component instantiations within the
+//! configured block. The entity bound to a component must have all ports and generics of the
+//! component unless they are associated by an explicit port or generic map.
+
+use super::analyze::*;
+use super::names::ResolvedName;
+use super::scope::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use std::ops::Deref;
+
+/// The concurrent statements of an architecture, block or generate statement
+struct ConfiguredBlock<'s> {
+    /// Such as "architecture 'rtl'"
+    description: String,
+    pos: &'s SrcPos,
+    statements: Vec<&'s LabeledConcurrentStatement>,
+}
+
+impl<'s> ConfiguredBlock<'s> {
+    fn find(
+        &self,
+        label: &Symbol,
+        matches: impl Fn(&ConcurrentStatement) -> bool,
+    ) -> Option<&'s LabeledConcurrentStatement> {
+        self.statements.iter().copied().find(|statement| {
+            statement
+                .label
+                .tree
+                .as_ref()
+                .is_some_and(|ident| &ident.item == label)
+                && matches(&statement.statement.item)
+        })
+    }
+
+    fn not_found(&self, pos: &SrcPos, message: String) -> Diagnostic {
+        Diagnostic::error(pos, format!("{message} within {}", self.description))
+            .with_code(ErrorCode::InvalidConfiguration)
+            .related(
+                self.pos,
+                format!("{} declared here", capitalize(&self.description)),
+            )
+    }
+}
+
+/// The statements within a block or generate statement, for all alternatives of a generate statement
+fn nested_statements(statement: &ConcurrentStatement) -> Option<Vec<&LabeledConcurrentStatement>> {
+    let statements = match statement {
+        ConcurrentStatement::Block(block) => block.statements.iter().collect(),
+        ConcurrentStatement::ForGenerate(gen) => gen.body.statements.iter().collect(),
+        ConcurrentStatement::IfGenerate(gen) => gen
+            .conds
+            .conditionals
+            .iter()
+            .map(|cond| &cond.item)
+            .chain(gen.conds.else_item.iter())
+            .flat_map(|body| body.statements.iter())
+            .collect(),
+        ConcurrentStatement::CaseGenerate(gen) => gen
+            .sels
+            .alternatives
+            .iter()
+            .flat_map(|alternative| alternative.item.statements.iter())
+            .collect(),
+        _ => return None,
+    };
+    Some(statements)
+}
+
+/// The alternatives of an if or case generate statement
+fn generate_alternatives(statement: &ConcurrentStatement) -> Vec<&GenerateBody> {
+    match statement {
+        ConcurrentStatement::IfGenerate(gen) => gen
+            .conds
+            .conditionals
+            .iter()
+            .map(|cond| &cond.item)
+            .chain(gen.conds.else_item.iter())
+            .collect(),
+        ConcurrentStatement::CaseGenerate(gen) => gen
+            .sels
+            .alternatives
+            .iter()
+            .map(|alternative| &alternative.item)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The alternative label of a block specification `label(alternative_label)`
+fn alternative_label(name: &mut Name) -> Option<(&SrcPos, &mut WithRef<Designator>)> {
+    let Name::CallOrIndexed(call) = name else {
+        return None;
+    };
+    let [AssociationElement {
+        formal: None,
+        actual,
+    }] = &mut call.parameters[..]
+    else {
+        return None;
+    };
+    match actual.item {
+        ActualPart::Expression(Expression::Name(ref mut name)) => match name.as_mut() {
+            Name::Designator(designator) => Some((&actual.pos, designator)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The label of a block specification, which is either `label`, `label(index)` or `label(range)`
+fn block_label(name: &mut Name) -> Option<&mut WithRef<Designator>> {
+    match name {
+        Name::Designator(designator) => Some(designator),
+        Name::CallOrIndexed(call) => block_label(&mut call.name.item),
+        Name::Slice(prefix, _) => block_label(&mut prefix.item),
+        _ => None,
+    }
+}
+
+fn suffix_designator(name: &Name) -> Option<&Designator> {
+    match name {
+        Name::Designator(designator) => Some(&designator.item),
+        Name::Selected(_, suffix) => Some(&suffix.item.item),
+        _ => None,
+    }
+}
+
+impl<'a> AnalyzeContext<'a> {
+    /// The component of a component instantiation statement. An instantiation without
+    /// generic and port map is parsed as a procedure call that denotes the component.
+    fn instantiated_component<'s>(
+        &self,
+        statement: &'s ConcurrentStatement,
+    ) -> Option<&'s WithPos<Name>> {
+        match statement {
+            ConcurrentStatement::Instance(inst) => match inst.unit {
+                InstantiatedUnit::Component(ref name) => Some(name),
+                _ => None,
+            },
+            ConcurrentStatement::ProcedureCall(pcall) => {
+                let name = &pcall.call.item.name;
+                let id = name.item.get_suffix_reference()?;
+                matches!(self.arena.get(id).kind(), AnyEntKind::Component(_)).then_some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Analyze the block configuration of an architecture of `entity`
+    pub(super) fn analyze_architecture_configuration(
+        &self,
+        scope: &Scope<'a>,
+        entity: DesignEnt<'a>,
+        block_config: &mut BlockConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let (Designator::Identifier(entity_name), Some(library_name)) =
+            (entity.designator(), entity.library_name())
+        else {
+            return Ok(());
+        };
+
+        let block_spec = &mut block_config.block_spec;
+        let designator = match block_spec.item {
+            Name::Designator(ref mut designator) => designator,
+            _ => {
+                diagnostics.push(
+                    Diagnostic::error(
+                        &block_spec.pos,
+                        format!(
+                            "Expected the name of an architecture of {}",
+                            entity.describe()
+                        ),
+                    )
+                    .with_code(ErrorCode::InvalidConfiguration),
+                );
+                return Ok(());
+            }
+        };
+        let Designator::Identifier(ref architecture_name) = designator.item else {
+            return Ok(());
+        };
+
+        let data = match self.get_architecture_analysis(
+            library_name,
+            &block_spec.pos,
+            entity_name,
+            architecture_name,
+        ) {
+            Ok(data) => data,
+            Err(err) => {
+                let mut diagnostic = err.into_non_fatal()?;
+                if let Some(decl_pos) = entity.decl_pos() {
+                    diagnostic.add_related(
+                        decl_pos,
+                        format!("{} declared here", capitalize(&entity.describe())),
+                    );
+                }
+                diagnostics.push(diagnostic);
+                return Ok(());
+            }
+        };
+
+        let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(ref arch)) = *data.deref()
+        else {
+            return Ok(());
+        };
+        if let Some(id) = arch.ident.decl.get() {
+            designator.reference.set(id);
+        }
+
+        let block = ConfiguredBlock {
+            description: format!("architecture '{}'", arch.ident.tree.item),
+            pos: &arch.ident.tree.pos,
+            statements: arch.statements.iter().collect(),
+        };
+        self.analyze_configuration_items(scope, &block, &mut block_config.items, diagnostics)
+    }
+
+    fn analyze_configuration_items(
+        &self,
+        scope: &Scope<'a>,
+        block: &ConfiguredBlock,
+        items: &mut [ConfigurationItem],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        for item in items.iter_mut() {
+            match item {
+                ConfigurationItem::Block(block_config) => {
+                    self.analyze_nested_block_configuration(
+                        scope,
+                        block,
+                        block_config,
+                        diagnostics,
+                    )?;
+                }
+                ConfigurationItem::Component(component_config) => {
+                    self.analyze_component_configuration(
+                        scope,
+                        block,
+                        component_config,
+                        diagnostics,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A block configuration of a block or generate statement within `block`
+    fn analyze_nested_block_configuration(
+        &self,
+        scope: &Scope<'a>,
+        block: &ConfiguredBlock,
+        block_config: &mut BlockConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let pos = &block_config.block_spec.pos;
+        let Some(designator) = block_label(&mut block_config.block_spec.item) else {
+            diagnostics.push(
+                Diagnostic::error(pos, "Expected the label of a block or generate statement")
+                    .with_code(ErrorCode::InvalidConfiguration),
+            );
+            return Ok(());
+        };
+        let Designator::Identifier(ref label) = designator.item else {
+            diagnostics.push(
+                Diagnostic::error(pos, "Expected the label of a block or generate statement")
+                    .with_code(ErrorCode::InvalidConfiguration),
+            );
+            return Ok(());
+        };
+        let label = label.clone();
+
+        let Some(statement) =
+            block.find(&label, |statement| nested_statements(statement).is_some())
+        else {
+            diagnostics
+                .push(block.not_found(pos, format!("No block or generate statement '{label}'")));
+            return Ok(());
+        };
+
+        if let Some(id) = statement.label.decl.get() {
+            designator.reference.set(id);
+        }
+
+        let kind = if let ConcurrentStatement::Block(_) = statement.statement.item {
+            "block"
+        } else {
+            "generate statement"
+        };
+        let mut nested = ConfiguredBlock {
+            description: format!("{kind} '{label}'"),
+            pos: statement
+                .label
+                .tree
+                .as_ref()
+                .map(|ident| &ident.pos)
+                .unwrap_or(&statement.statement.pos),
+            statements: nested_statements(&statement.statement.item).unwrap_or_default(),
+        };
+
+        // LRM 3.4.2 The generate specification of an if or case generate statement
+        // is an alternative label which selects the statements of that alternative
+        let alternatives = generate_alternatives(&statement.statement.item);
+        if let (false, Some((alternative_pos, alternative))) = (
+            alternatives.is_empty(),
+            alternative_label(&mut block_config.block_spec.item),
+        ) {
+            let Designator::Identifier(ref alternative_name) = alternative.item else {
+                return Ok(());
+            };
+            let Some((ident, body)) = alternatives.iter().find_map(|body| {
+                body.alternative_label
+                    .as_ref()
+                    .filter(|ident| &ident.tree.item == alternative_name)
+                    .map(|ident| (ident, body))
+            }) else {
+                diagnostics.push(nested.not_found(
+                    alternative_pos,
+                    format!("No alternative '{alternative_name}'"),
+                ));
+                return Ok(());
+            };
+            if let Some(id) = ident.decl.get() {
+                alternative.reference.set(id);
+            }
+            nested = ConfiguredBlock {
+                description: format!("alternative '{alternative_name}' of {}", nested.description),
+                pos: &ident.tree.pos,
+                statements: body.statements.iter().collect(),
+            };
+        }
+        self.analyze_configuration_items(scope, &nested, &mut block_config.items, diagnostics)
+    }
+
+    fn analyze_component_configuration(
+        &self,
+        scope: &Scope<'a>,
+        block: &ConfiguredBlock,
+        config: &mut ComponentConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let component_name = &mut config.spec.component_name;
+        let Some(component_designator) = suffix_designator(&component_name.item).cloned() else {
+            diagnostics.push(
+                Diagnostic::error(&component_name.pos, "Expected the name of a component")
+                    .with_code(ErrorCode::MismatchedKinds),
+            );
+            return Ok(());
+        };
+        let is_component = |id: Option<EntityId>| {
+            id.map(|id| self.arena.get(id))
+                .filter(|ent| ent.designator() == &component_designator)
+        };
+
+        let mut component = None;
+        match config.spec.instantiation_list {
+            InstantiationList::Labels(ref mut labels) => {
+                for label in labels.iter_mut() {
+                    let Some(statement) = block.find(&label.item.item, |statement| {
+                        self.instantiated_component(statement).is_some()
+                    }) else {
+                        diagnostics.push(block.not_found(
+                            &label.item.pos,
+                            format!("No component instantiation '{}'", label.item.item),
+                        ));
+                        continue;
+                    };
+
+                    if let Some(id) = statement.label.decl.get() {
+                        label.reference.set(id);
+                    }
+
+                    let instance_component = self
+                        .instantiated_component(&statement.statement.item)
+                        .expect("Statement is a component instantiation");
+                    if let Some(ent) = is_component(instance_component.item.get_suffix_reference())
+                    {
+                        component = Some(ent);
+                    } else {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                &component_name.pos,
+                                format!(
+                                    "'{}' is not an instance of component {}",
+                                    label.item.item,
+                                    component_designator.describe()
+                                ),
+                            )
+                            .with_code(ErrorCode::InvalidInstantiation)
+                            .related(
+                                &instance_component.pos,
+                                format!("'{}' instantiates this component", label.item.item),
+                            ),
+                        );
+                    }
+                }
+            }
+            InstantiationList::Others | InstantiationList::All => {
+                component = block.statements.iter().find_map(|statement| {
+                    is_component(
+                        self.instantiated_component(&statement.statement.item)?
+                            .item
+                            .get_suffix_reference(),
+                    )
+                });
+            }
+        }
+
+        if let Some(component) = component {
+            component_name.item.set_unique_reference(component);
+        }
+
+        let Some(ref mut bind_ind) = config.bind_ind else {
+            return Ok(());
+        };
+        let Some((entity, entity_region)) =
+            self.analyze_binding_indication(scope, bind_ind, diagnostics)?
+        else {
+            return Ok(());
+        };
+
+        if let (Some(component), Some(EntityAspect::Entity(ref entity_name, _))) =
+            (component, &bind_ind.entity_aspect)
+        {
+            self.check_default_binding(
+                component,
+                entity,
+                entity_region,
+                &entity_name.pos,
+                bind_ind,
+                diagnostics,
+            );
+        }
+
+        if let (Some(block_config), Some(entity)) =
+            (config.block_config.as_mut(), DesignEnt::from_any(entity))
+        {
+            self.analyze_architecture_configuration(scope, entity, block_config, diagnostics)?;
+        }
+
+        Ok(())
+    }
+
+    /// The ports and generics of the component that are not associated by an explicit map
+    /// are associated with the ports and generics of the entity with the same name
+    fn check_default_binding(
+        &self,
+        component: EntRef<'a>,
+        entity: EntRef<'a>,
+        entity_region: &Region<'a>,
+        pos: &SrcPos,
+        bind_ind: &BindingIndication,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let AnyEntKind::Component(ref component_region) = component.kind() else {
+            return;
+        };
+        let (component_ports, component_generics) = component_region.ports_and_generics();
+        let (entity_ports, entity_generics) = entity_region.ports_and_generics();
+
+        let mut check = |kind: &str, locals: Vec<InterfaceEnt>, formals: Vec<InterfaceEnt>| {
+            for local in locals {
+                if !formals
+                    .iter()
+                    .any(|formal| formal.designator() == local.designator())
+                {
+                    let mut diagnostic = Diagnostic::error(
+                        pos,
+                        format!(
+                            "{} has no {kind} '{}' of {}",
+                            capitalize(&entity.describe()),
+                            local.designator(),
+                            component.describe()
+                        ),
+                    )
+                    .with_code(ErrorCode::MismatchedKinds);
+                    if let Some(decl_pos) = local.decl_pos() {
+                        diagnostic.add_related(decl_pos, format!("The {kind} is declared here"));
+                    }
+                    diagnostics.push(diagnostic);
+                }
+            }
+        };
+
+        if bind_ind.generic_map.is_none() {
+            check("generic", component_generics, entity_generics);
+        }
+        if bind_ind.port_map.is_none() {
+            check("port", component_ports, entity_ports);
+        }
+    }
+
+    /// Resolve the entity or configuration of a binding indication,
+    /// returning the entity region when an entity is bound
+    pub(super) fn analyze_binding_indication(
+        &self,
+        scope: &Scope<'a>,
+        bind_ind: &mut BindingIndication,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<(EntRef<'a>, &'a Region<'a>)>> {
+        match bind_ind.entity_aspect {
+            Some(EntityAspect::Entity(ref mut entity_name, ref mut architecture_name)) => {
+                as_fatal(self.resolve_entity_aspect(
+                    scope,
+                    entity_name,
+                    architecture_name.as_mut(),
+                    diagnostics,
+                ))
+            }
+            Some(EntityAspect::Configuration(ref mut config_name)) => {
+                if let Some(resolved) = as_fatal(self.name_resolve(
+                    scope,
+                    &config_name.pos,
+                    &mut config_name.item,
+                    diagnostics,
+                ))? {
+                    if !matches!(resolved, ResolvedName::Design(ent) if matches!(ent.kind(), Design::Configuration))
+                    {
+                        diagnostics
+                            .push(resolved.kind_error(config_name.suffix_pos(), "configuration"));
+                    }
+                }
+                Ok(None)
+            }
+            Some(EntityAspect::Open) | None => Ok(None),
+        }
+    }
+}
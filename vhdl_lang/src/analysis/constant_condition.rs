@@ -0,0 +1,216 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Lint of if and while conditions that are always true or false and of case statements
+//! whose selector is constant
+//!
+//! A condition is constant when it is built from literals alone or when it compares an object
+//! with itself such as `sig = sig`. Conditions on constants such as `if C_DEBUG then` are
+//! intended to be constant so they are not reported. Objects are compared by the named entities that the names
+//! denote, so an alias of a whole object denotes the same object as the object itself.
+
+use super::analyze::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+impl AnalyzeContext<'_> {
+    /// Warn about a condition that is always true or false. A while loop with a condition that
+    /// is always true is the common idiom of an infinite loop so it is only checked for false.
+    pub(crate) fn check_constant_condition(
+        &self,
+        condition: &WithPos<Expression>,
+        check_true: bool,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.root.lint_constant_conditions {
+            return;
+        }
+        let Some(value) = self.constant_condition(condition) else {
+            return;
+        };
+        if value && !check_true {
+            return;
+        }
+        diagnostics.push(
+            Diagnostic::warning(&condition.pos, format!("Condition is always {value}"))
+                .with_code(ErrorCode::ConstantCondition),
+        );
+    }
+
+    pub(crate) fn check_constant_selector(
+        &self,
+        selector: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.root.lint_constant_conditions {
+            return;
+        }
+        if self.is_literal(&selector.item)
+            && self
+                .evaluate_static(selector, &mut NullDiagnostics)
+                .is_some()
+        {
+            diagnostics.push(
+                Diagnostic::warning(
+                    &selector.pos,
+                    "Case selector is constant, only one alternative can execute",
+                )
+                .with_code(ErrorCode::ConstantCondition),
+            );
+        }
+    }
+
+    fn constant_condition(&self, condition: &WithPos<Expression>) -> Option<bool> {
+        if !self.is_literal(&condition.item) {
+            return self.self_comparison(&condition.item);
+        }
+        if let Some(value) = self.evaluate_static(condition, &mut NullDiagnostics) {
+            let Value::Enumeration(Designator::Identifier(value)) = value else {
+                return None;
+            };
+            return if value == self.root.symbol_utf8("true") {
+                Some(true)
+            } else if value == self.root.symbol_utf8("false") {
+                Some(false)
+            } else {
+                None
+            };
+        }
+        None
+    }
+
+    /// True if the expression is built from literals and predefined operators alone
+    fn is_literal(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Literal(_) => true,
+            Expression::Name(name) => match name.as_ref() {
+                Name::Designator(designator) => designator.reference.get().is_some_and(|id| {
+                    matches!(
+                        self.arena.get(id).kind(),
+                        AnyEntKind::Overloaded(Overloaded::EnumLiteral(..))
+                    )
+                }),
+                _ => false,
+            },
+            Expression::Unary(op, expr) => {
+                self.is_predefined_operator(&op.item) && self.is_literal(&expr.item)
+            }
+            Expression::Binary(op, left, right) => {
+                self.is_predefined_operator(&op.item)
+                    && self.is_literal(&left.item)
+                    && self.is_literal(&right.item)
+            }
+            Expression::Qualified(qexpr) => self.is_literal(&qexpr.expr.item),
+            Expression::Aggregate(..) | Expression::New(..) => false,
+        }
+    }
+
+    /// The value of a comparison of an object with itself
+    fn self_comparison(&self, condition: &Expression) -> Option<bool> {
+        let Expression::Binary(op, left, right) = condition else {
+            return None;
+        };
+        if !self.is_predefined_operator(&op.item) || !self.is_same_object(&left.item, &right.item) {
+            return None;
+        }
+        match op.item.item {
+            Operator::EQ | Operator::LTE | Operator::GTE => Some(true),
+            Operator::NE | Operator::LT | Operator::GT => Some(false),
+            _ => None,
+        }
+    }
+
+    /// True if both expressions are names of the same object or of the same element
+    /// or slice of it
+    fn is_same_object(&self, left: &Expression, right: &Expression) -> bool {
+        let (Expression::Name(left), Expression::Name(right)) = (left, right) else {
+            return false;
+        };
+        self.is_same_name(left, right)
+    }
+
+    fn is_same_name(&self, left: &Name, right: &Name) -> bool {
+        match (left, right) {
+            (
+                Name::Selected(left_prefix, left_suffix),
+                Name::Selected(right_prefix, right_suffix),
+            ) if self.is_element(&left_suffix.item.reference) => {
+                left_suffix.item.reference.get() == right_suffix.item.reference.get()
+                    && self.is_same_name(&left_prefix.item, &right_prefix.item)
+            }
+            (Name::Slice(left_prefix, left_range), Name::Slice(right_prefix, right_range)) => {
+                self.is_same_name(&left_prefix.item, &right_prefix.item)
+                    && self
+                        .static_discrete_range(left_range, &mut NullDiagnostics)
+                        .is_some_and(|range| {
+                            Some(range)
+                                == self.static_discrete_range(right_range, &mut NullDiagnostics)
+                        })
+            }
+            (Name::CallOrIndexed(left), Name::CallOrIndexed(right)) => {
+                // The prefixes are objects so these are indexed names rather than calls
+                self.is_same_name(&left.name.item, &right.name.item)
+                    && left.parameters.len() == right.parameters.len()
+                    && left
+                        .parameters
+                        .iter()
+                        .zip(right.parameters.iter())
+                        .all(|(left, right)| self.is_same_index(left, right))
+            }
+            _ => self
+                .denoted_object(left)
+                .is_some_and(|id| Some(id) == self.denoted_object(right)),
+        }
+    }
+
+    /// True if both indexes are the same static value or the same object
+    fn is_same_index(&self, left: &AssociationElement, right: &AssociationElement) -> bool {
+        let (None, None, ActualPart::Expression(left_expr), ActualPart::Expression(right_expr)) = (
+            &left.formal,
+            &right.formal,
+            &left.actual.item,
+            &right.actual.item,
+        ) else {
+            return false;
+        };
+        if let Some(value) =
+            self.evaluate_static_pos(&left.actual.pos, left_expr, &mut NullDiagnostics)
+        {
+            return Some(value)
+                == self.evaluate_static_pos(&right.actual.pos, right_expr, &mut NullDiagnostics);
+        }
+        self.is_same_object(left_expr, right_expr)
+    }
+
+    fn is_element(&self, reference: &Reference) -> bool {
+        reference.get().is_some_and(|id| {
+            matches!(
+                self.arena.get(id).kind(),
+                AnyEntKind::ElementDeclaration(..)
+            )
+        })
+    }
+
+    /// The object denoted by a simple or selected name. An alias of a whole object denotes the
+    /// object itself while an alias of an element or slice is only the same as itself.
+    fn denoted_object(&self, name: &Name) -> Option<EntityId> {
+        let ent = self.arena.get(name.get_suffix_reference()?);
+        match ent.kind() {
+            AnyEntKind::Object(..) => Some(ent.id()),
+            AnyEntKind::ObjectAlias {
+                base_object,
+                whole_object,
+                ..
+            } => Some(if *whole_object {
+                base_object.id()
+            } else {
+                ent.id()
+            }),
+            _ => None,
+        }
+    }
+}
@@ -5,11 +5,12 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 use super::names::*;
+use super::return_paths::check_function_return_paths;
 use super::*;
 use crate::ast::*;
 use crate::data::*;
 use crate::named_entity::{Signature, *};
-use crate::{ast, named_entity, HasTokenSpan};
+use crate::{ast, named_entity, HasTokenSpan, VHDLStandard};
 use analyze::*;
 use fnv::FnvHashMap;
 use std::collections::hash_map::Entry;
@@ -36,6 +37,8 @@ impl Declaration {
                     | Use(_)
                     | Package(_)
                     | Configuration(_)
+                    | Disconnection(_)
+                    | Psl(_)
             ),
             AnyEntKind::Design(Design::Configuration) => {
                 matches!(self, Use(_) | Attribute(ast::Attribute::Specification(_)))
@@ -52,6 +55,7 @@ impl Declaration {
                     | SubprogramBody(_)
                     | Use(_)
                     | Package(_)
+                    | Disconnection(_)
             ),
             AnyEntKind::Design(Design::PackageBody | Design::UninstPackage(..))
             | AnyEntKind::Overloaded(
@@ -88,6 +92,7 @@ impl Declaration {
                     | SubprogramInstantiation(_)
                     | Use(_)
                     | Package(_)
+                    | Disconnection(_)
             ),
             _ => {
                 // AnyEntKind::Library is used in tests for a generic declarative region
@@ -116,9 +121,12 @@ impl<'a> AnalyzeContext<'a> {
             let (decl, remaining) = declarations[i..].split_first_mut().unwrap();
 
             if !decl.is_allowed_in_context(parent.kind()) {
-                diagnostics.error(
-                    decl.get_pos(self.ctx),
-                    format!("{} declaration not allowed here", decl.describe(),),
+                diagnostics.push(
+                    Diagnostic::error(
+                        decl.get_pos(self.ctx),
+                        format!("{} declaration not allowed here", decl.describe(),),
+                    )
+                    .with_code(ErrorCode::IllegalDeclaration),
                 )
             }
 
@@ -139,7 +147,8 @@ impl<'a> AnalyzeContext<'a> {
                                             "Missing full type declaration of incomplete type '{}'",
                                             type_decl.ident.name()
                                         ),
-                                        );
+                                        )
+                                        .with_code(ErrorCode::IllegalDeclaration);
                                         error.add_related(type_decl.ident.pos(), "The full type declaration shall occur immediately within the same declarative part");
                                         diagnostics.push(error);
                                         type_decl.ident.pos()
@@ -231,14 +240,25 @@ impl<'a> AnalyzeContext<'a> {
                     if let Some(ref signature) = signature {
                         diagnostics.push(Diagnostic::should_not_have_signature("Alias", signature));
                     }
+                    // The name of an element or slice references the element or nothing
+                    let whole_object =
+                        name.item.get_suffix_reference().is_some_and(|id| {
+                            match self.arena.get(id).kind() {
+                                AnyEntKind::Object(..) => true,
+                                AnyEntKind::ObjectAlias { whole_object, .. } => *whole_object,
+                                _ => false,
+                            }
+                        });
                     match oname.base {
                         ObjectBase::Object(base_object) => AnyEntKind::ObjectAlias {
                             base_object,
                             type_mark: oname.type_mark(),
+                            whole_object,
                         },
                         ObjectBase::ObjectAlias(base_object, _) => AnyEntKind::ObjectAlias {
                             base_object,
                             type_mark: oname.type_mark(),
+                            whole_object,
                         },
                         ObjectBase::ExternalName(class) => AnyEntKind::ExternalAlias {
                             class,
@@ -256,9 +276,12 @@ impl<'a> AnalyzeContext<'a> {
                     if let Some(ref signature) = signature {
                         diagnostics.push(Diagnostic::should_not_have_signature("Alias", signature));
                     }
-                    diagnostics.error(
-                        &name.pos,
-                        format!("{} cannot be aliased", resolved_name.describe_type()),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!("{} cannot be aliased", resolved_name.describe_type()),
+                        )
+                        .with_code(ErrorCode::MismatchedKinds),
                     );
                     return Err(EvalError::Unknown);
                 }
@@ -273,7 +296,21 @@ impl<'a> AnalyzeContext<'a> {
                         // TODO: Uninstantiated subprogram in aliases
                         let signature_key =
                             self.resolve_signature(scope, signature, diagnostics)?;
-                        if let Some(ent) = overloaded.get(&SubprogramKey::Normal(signature_key)) {
+                        let key = SubprogramKey::Normal(signature_key);
+                        let homographs = overloaded.get_homographs(&key);
+                        if homographs.len() > 1 {
+                            let mut diagnostic = Diagnostic::error(
+                                &des.pos,
+                                format!(
+                                    "Ambiguous alias of {}, more than one declaration matches the signature",
+                                    des.item.describe()
+                                ),
+                            ).with_code(ErrorCode::Ambiguous);
+                            diagnostic.add_subprogram_candidates("Might be", homographs);
+                            diagnostics.push(diagnostic);
+                            return Err(EvalError::Unknown);
+                        }
+                        if let Some(ent) = overloaded.get(&key) {
                             if let Some(reference) = name.item.suffix_reference_mut() {
                                 reference.set_unique_reference(&ent);
                             }
@@ -343,21 +380,62 @@ impl<'a> AnalyzeContext<'a> {
                     diagnostics,
                 );
 
+                let mut static_value = None;
                 if let Some(ref mut expr) = object_decl.expression {
-                    if let Ok(ref subtype) = subtype {
-                        self.expr_pos_with_ttyp(
-                            scope,
-                            subtype.type_mark(),
-                            &expr.pos,
-                            &mut expr.item,
+                    let is_self_referential = object_decl.class == ObjectClass::Constant
+                        && self.check_self_referential_constant(
+                            &object_decl.ident.tree,
+                            expr,
                             diagnostics,
-                        )?;
-                    } else {
-                        self.expr_unknown_ttyp(scope, expr, diagnostics)?;
+                        );
+
+                    // The constant is not visible within its own declaration
+                    if !is_self_referential {
+                        if let Ok(ref subtype) = subtype {
+                            self.expr_pos_with_ttyp(
+                                scope,
+                                subtype.type_mark(),
+                                &expr.pos,
+                                &mut expr.item,
+                                diagnostics,
+                            )?;
+                            self.check_static_length(subtype.static_length(), expr, diagnostics);
+                            if object_decl.class == ObjectClass::Constant {
+                                self.check_deferred_constants_used(scope, expr, diagnostics);
+                                static_value = self.evaluate_static(expr, diagnostics);
+                            }
+                        } else {
+                            self.expr_unknown_ttyp(scope, expr, diagnostics)?;
+                        }
                     }
                 }
 
+                self.check_synthesis_signal_initial_value(object_decl, diagnostics);
+
                 if let Some(subtype) = as_fatal(subtype)? {
+                    if object_decl.class == ObjectClass::SharedVariable
+                        && !matches!(
+                            subtype.base_type().kind(),
+                            Type::Protected(..) | Type::Interface
+                        )
+                    {
+                        if self.standard < VHDLStandard::V2002 {
+                            self.check_synthesis_shared_variable(object_decl, diagnostics);
+                        } else {
+                            // Since VHDL-2002 shared variables must be of a protected type
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &object_decl.subtype_indication.type_mark.pos,
+                                    format!(
+                                        "Shared variable '{}' must be of a protected type",
+                                        object_decl.ident.tree.item
+                                    ),
+                                )
+                                .with_code(ErrorCode::IllegalDeclaration),
+                            );
+                        }
+                    }
+
                     let kind = if object_decl.class == ObjectClass::Constant
                         && object_decl.expression.is_none()
                     {
@@ -368,6 +446,10 @@ impl<'a> AnalyzeContext<'a> {
                             iface: None,
                             has_default: object_decl.expression.is_some(),
                             subtype,
+                            static_value,
+                            signal_kind: object_decl.signal_kind,
+                            index_range: None,
+                            default_value: None,
                         })
                     };
 
@@ -502,6 +584,16 @@ impl<'a> AnalyzeContext<'a> {
                     &mut body.statements,
                     diagnostics,
                 )?;
+                self.record_calls(subpgm_ent.into(), &body.declarations, &body.statements);
+                if self.root.lint_missing_return {
+                    check_function_return_paths(body, diagnostics);
+                }
+                self.check_function_purity(subpgm_ent.into(), body, diagnostics);
+                self.check_uninitialized_variables(
+                    &body.declarations,
+                    &body.statements,
+                    diagnostics,
+                );
             }
             Declaration::SubprogramDeclaration(ref mut subdecl) => {
                 match as_fatal(self.subprogram_specification(
@@ -571,13 +663,94 @@ impl<'a> AnalyzeContext<'a> {
                     scope.add(ent, diagnostics);
                 }
             }
-            Declaration::Configuration(..) => {}
+            Declaration::Configuration(ref mut config) => {
+                self.analyze_configuration_specification(scope, config, diagnostics)?;
+            }
+            Declaration::Disconnection(ref mut disconnection) => {
+                self.analyze_disconnection_specification(scope, disconnection, diagnostics)?;
+            }
+            Declaration::Psl(ref mut decl) => {
+                self.analyze_psl_declaration(scope, parent, decl, diagnostics)?;
+            }
+            // The syntax error has already been reported
+            Declaration::Error(_) => {}
             Declaration::Type(..) => unreachable!("Handled elsewhere"),
         };
 
         Ok(())
     }
 
+    fn analyze_configuration_specification(
+        &self,
+        scope: &Scope<'a>,
+        config: &mut ConfigurationSpecification,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let component_name = &mut config.spec.component_name;
+        if let Some(resolved) = as_fatal(self.name_resolve(
+            scope,
+            &component_name.pos,
+            &mut component_name.item,
+            diagnostics,
+        ))? {
+            if !matches!(resolved, ResolvedName::Final(ent) if matches!(ent.kind(), AnyEntKind::Component(_)))
+            {
+                diagnostics.push(resolved.kind_error(component_name.suffix_pos(), "component"));
+            }
+        }
+
+        self.analyze_binding_indication(scope, &mut config.bind_ind, diagnostics)?;
+
+        Ok(())
+    }
+
+    /// LRM 7.4 Each signal in the list must be a guarded signal of the type denoted by the type mark
+    fn analyze_disconnection_specification(
+        &self,
+        scope: &Scope<'a>,
+        disconnection: &mut DisconnectionSpecification,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let typ =
+            as_fatal(self.resolve_type_mark(scope, &mut disconnection.type_mark, diagnostics))?;
+
+        if let GuardedSignalList::Names(ref mut names) = disconnection.signal_list {
+            for name in names.iter_mut() {
+                let Some(object_name) = as_fatal(self.resolve_object_name(
+                    scope,
+                    &name.pos,
+                    &mut name.item,
+                    "is not a guarded signal",
+                    diagnostics,
+                ))?
+                else {
+                    continue;
+                };
+
+                if object_name.base.class() != ObjectClass::Signal || !object_name.base.is_guarded()
+                {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!("{} is not a guarded signal", object_name.base.describe()),
+                        )
+                        .with_code(ErrorCode::MismatchedKinds),
+                    );
+                } else if let Some(typ) = typ {
+                    if object_name.type_mark().base() != typ.base() {
+                        diagnostics.push(Diagnostic::type_mismatch(
+                            &name.pos,
+                            &object_name.base.describe(),
+                            typ,
+                        ));
+                    }
+                }
+            }
+        }
+
+        self.expr_with_ttyp(scope, self.time(), &mut disconnection.after, diagnostics)
+    }
+
     fn find_deferred_constant_declaration(
         &self,
         scope: &Scope<'a>,
@@ -591,6 +764,69 @@ impl<'a> AnalyzeContext<'a> {
         None
     }
 
+    /// Report the names in the initial value of a constant that denote the constant itself.
+    /// Returns true if there were any.
+    fn check_self_referential_constant(
+        &self,
+        ident: &Ident,
+        expr: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> bool {
+        let mut names = Vec::new();
+        expression_simple_names(&expr.pos, &expr.item, &mut names);
+
+        let mut found = false;
+        for (pos, designator) in names {
+            if matches!(&designator.item, Designator::Identifier(sym) if sym == &ident.item) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        pos,
+                        format!(
+                            "constant '{}' references itself in its initial value",
+                            ident.item
+                        ),
+                    )
+                    .with_code(ErrorCode::IllegalDeclaration),
+                );
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// A deferred constant of the current package may not be used in the initial value of another
+    /// constant before its full declaration, this also catches mutually referential constants
+    fn check_deferred_constants_used(
+        &self,
+        scope: &Scope<'a>,
+        expr: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let mut names = Vec::new();
+        expression_simple_names(&expr.pos, &expr.item, &mut names);
+
+        for (pos, designator) in names {
+            let Designator::Identifier(ref sym) = designator.item else {
+                continue;
+            };
+            let Some(id) = designator.reference.get() else {
+                continue;
+            };
+            if self
+                .find_deferred_constant_declaration(scope, sym)
+                .is_some_and(|deferred| deferred.id() == id)
+            {
+                diagnostics.push(
+                    Diagnostic::error(
+                        pos,
+                        format!("Deferred constant '{sym}' is used before its full declaration"),
+                    )
+                    .with_code(ErrorCode::IllegalDeclaration),
+                );
+            }
+        }
+    }
+
     fn attribute_specification(
         &self,
         scope: &Scope<'a>,
@@ -622,17 +858,23 @@ impl<'a> AnalyzeContext<'a> {
                     )?;
                     attr_ent
                 } else {
-                    diagnostics.error(
-                        &ident.item.pos,
-                        format!("{} is not an attribute", ent.describe()),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &ident.item.pos,
+                            format!("{} is not an attribute", ent.describe()),
+                        )
+                        .with_code(ErrorCode::IllegalAttribute),
                     );
                     return Ok(());
                 }
             }
             Ok(NamedEntities::Overloaded(_)) => {
-                diagnostics.error(
-                    &ident.item.pos,
-                    format!("Overloaded name '{}' is not an attribute", ident.item),
+                diagnostics.push(
+                    Diagnostic::error(
+                        &ident.item.pos,
+                        format!("Overloaded name '{}' is not an attribute", ident.item),
+                    )
+                    .with_code(ErrorCode::IllegalAttribute),
                 );
                 return Ok(());
             }
@@ -699,10 +941,13 @@ impl<'a> AnalyzeContext<'a> {
             let ent = ent.as_actual();
 
             if Some(*entity_class) != get_entity_class(ent) {
-                diagnostics.push(Diagnostic::error(
-                    designator,
-                    format!("{} is not of class {}", ent.describe(), entity_class),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        designator,
+                        format!("{} is not of class {}", ent.describe(), entity_class),
+                    )
+                    .with_code(ErrorCode::IllegalAttribute),
+                );
                 return Ok(());
             }
 
@@ -712,10 +957,13 @@ impl<'a> AnalyzeContext<'a> {
                 | EntityClass::Package
                 | EntityClass::Configuration => {
                     if ent != parent {
-                        diagnostics.push(Diagnostic::error(
-                            designator,
-                            "Attribute specification must be in the immediate declarative part",
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                designator,
+                                "Attribute specification must be in the immediate declarative part",
+                            )
+                            .with_code(ErrorCode::IllegalDeclaration),
+                        );
                         return Ok(());
                     }
                 }
@@ -732,10 +980,13 @@ impl<'a> AnalyzeContext<'a> {
                 | EntityClass::File
                 | EntityClass::Label => {
                     if ent.parent != Some(parent) {
-                        diagnostics.push(Diagnostic::error(
-                            designator,
-                            "Attribute specification must be in the immediate declarative part",
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                designator,
+                                "Attribute specification must be in the immediate declarative part",
+                            )
+                            .with_code(ErrorCode::IllegalDeclaration),
+                        );
                         return Ok(());
                     }
                 }
@@ -793,6 +1044,25 @@ impl<'a> AnalyzeContext<'a> {
                 }
 
                 let subtype = subtype?;
+                let index_range = match object_decl.subtype_indication.constraint {
+                    Some(WithPos {
+                        item: SubtypeConstraint::Array(ref ranges, None),
+                        ..
+                    }) if object_decl.list_type == InterfaceType::Port
+                        && ranges.len() == 1
+                        && subtype.static_range().is_none() =>
+                    {
+                        Some(Box::new(ranges[0].clone()))
+                    }
+                    _ => None,
+                };
+                let default_value = match object_decl.expression {
+                    // Errors within the default have already been reported when it was analyzed
+                    Some(ref expr) if object_decl.list_type == InterfaceType::Generic => {
+                        self.evaluate_static(expr, &mut NullDiagnostics)
+                    }
+                    _ => None,
+                };
                 self.arena.define(
                     &mut object_decl.ident,
                     parent,
@@ -804,6 +1074,10 @@ impl<'a> AnalyzeContext<'a> {
                         )),
                         subtype,
                         has_default: object_decl.expression.is_some(),
+                        static_value: None,
+                        signal_kind: None,
+                        index_range,
+                        default_value,
                     }),
                 )
             }
@@ -921,7 +1195,8 @@ impl Diagnostic {
                 "Could not find declaration of {} with given signature",
                 des.describe()
             ),
-        );
+        )
+        .with_code(ErrorCode::MissingDecl);
         diagnostic.add_subprogram_candidates("Found", overloaded.entities());
         diagnostic
     }
@@ -931,6 +1206,7 @@ impl Diagnostic {
             pos,
             format!("{prefix} should only have a signature for subprograms and enum literals"),
         )
+        .with_code(ErrorCode::IllegalSignature)
     }
 
     fn signature_required(pos: impl AsRef<SrcPos>) -> Diagnostic {
@@ -938,6 +1214,7 @@ impl Diagnostic {
             pos,
             "Signature required for alias of subprogram and enum literals",
         )
+        .with_code(ErrorCode::IllegalSignature)
     }
 }
 
@@ -1017,3 +1294,63 @@ fn find_full_type_definition<'a>(
     }
     None
 }
+
+/// The simple names within an expression that are evaluated as part of it.
+/// Formals of associations and choices of aggregates are not included.
+fn expression_simple_names<'e>(
+    pos: &'e SrcPos,
+    expr: &'e Expression,
+    names: &mut Vec<(&'e SrcPos, &'e WithRef<Designator>)>,
+) {
+    match expr {
+        Expression::Binary(_, left, right) => {
+            expression_simple_names(&left.pos, &left.item, names);
+            expression_simple_names(&right.pos, &right.item, names);
+        }
+        Expression::Unary(_, operand) => {
+            expression_simple_names(&operand.pos, &operand.item, names)
+        }
+        Expression::Aggregate(assocs) => {
+            for assoc in assocs.iter() {
+                let expr = match assoc {
+                    ElementAssociation::Positional(expr) => expr,
+                    ElementAssociation::Named(_, expr) => expr,
+                };
+                expression_simple_names(&expr.pos, &expr.item, names);
+            }
+        }
+        Expression::Qualified(qexpr) => {
+            expression_simple_names(&qexpr.expr.pos, &qexpr.expr.item, names)
+        }
+        Expression::Name(name) => name_simple_names(pos, name, names),
+        Expression::Literal(_) | Expression::New(_) => {}
+    }
+}
+
+fn name_simple_names<'e>(
+    pos: &'e SrcPos,
+    name: &'e Name,
+    names: &mut Vec<(&'e SrcPos, &'e WithRef<Designator>)>,
+) {
+    match name {
+        Name::Designator(designator) => names.push((pos, designator)),
+        Name::Selected(prefix, _) | Name::SelectedAll(prefix) | Name::Slice(prefix, _) => {
+            name_simple_names(&prefix.pos, &prefix.item, names)
+        }
+        Name::Attribute(attr) => {
+            name_simple_names(&attr.name.pos, &attr.name.item, names);
+            if let Some(ref expr) = attr.expr {
+                expression_simple_names(&expr.pos, &expr.item, names);
+            }
+        }
+        Name::CallOrIndexed(fcall) => {
+            name_simple_names(&fcall.name.pos, &fcall.name.item, names);
+            for param in fcall.parameters.iter() {
+                if let ActualPart::Expression(ref expr) = param.actual.item {
+                    expression_simple_names(&param.actual.pos, expr, names);
+                }
+            }
+        }
+        Name::External(_) => {}
+    }
+}
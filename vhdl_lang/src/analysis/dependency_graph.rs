@@ -0,0 +1,241 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! The dependencies between the design units of a design
+//!
+//! A design unit depends on another design unit when it cannot be analyzed before it, for
+//! example due to a use clause, a context reference, an entity instantiation or the binding
+//! in a configuration. Secondary units always depend on their primary unit.
+
+use crate::ast::*;
+use crate::data::*;
+use fnv::FnvHashMap;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// A design unit of the dependency graph
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyNode {
+    pub unit_id: UnitId,
+    pub source: Source,
+}
+
+impl DependencyNode {
+    /// The name of the unit itself, which is the architecture name for architectures
+    pub fn name(&self) -> &Symbol {
+        self.unit_id
+            .secondary_name()
+            .unwrap_or_else(|| self.unit_id.primary_name())
+    }
+
+    pub fn describe_kind(&self) -> &'static str {
+        match self.unit_id.kind() {
+            AnyKind::Primary(kind) => kind.describe(),
+            AnyKind::Secondary(kind) => kind.describe(),
+        }
+    }
+
+    /// Nodes are sorted by library and primary unit with the primary unit before its secondary units
+    fn sort_key(&self) -> (String, String, bool, String) {
+        (
+            self.unit_id.library_name().name_utf8(),
+            self.unit_id.primary_name().name_utf8(),
+            matches!(self.unit_id.kind(), AnyKind::Secondary(_)),
+            self.name().name_utf8(),
+        )
+    }
+}
+
+/// The dependency graph of all design units of a design
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct DependencyGraph {
+    /// Sorted by library and unit name
+    pub nodes: Vec<DependencyNode>,
+    /// Pairs of indices into `nodes` where the first unit depends on the second unit
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// The design units that depend on each other so that no compile order exists
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    /// The units of the cycle starting and ending with the same unit
+    pub cycle: Vec<UnitId>,
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Found circular dependency: {}",
+            self.cycle
+                .iter()
+                .map(|unit_id| unit_id.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        )
+    }
+}
+
+impl DependencyGraph {
+    pub(crate) fn new(
+        mut nodes: Vec<DependencyNode>,
+        dependencies: impl Iterator<Item = (UnitId, UnitId)>,
+    ) -> DependencyGraph {
+        nodes.sort_by_cached_key(|node| node.sort_key());
+
+        let index_of: FnvHashMap<&UnitId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (&node.unit_id, idx))
+            .collect();
+        let edges: BTreeSet<_> = dependencies
+            .filter_map(|(user, used)| Some((*index_of.get(&user)?, *index_of.get(&used)?)))
+            .filter(|(user, used)| user != used)
+            .collect();
+
+        DependencyGraph {
+            edges: edges.into_iter().collect(),
+            nodes,
+        }
+    }
+
+    /// The units that `unit_id` depends on directly
+    pub fn dependencies_of(&self, unit_id: &UnitId) -> Vec<&DependencyNode> {
+        self.edges
+            .iter()
+            .filter(|(user, _)| &self.nodes[*user].unit_id == unit_id)
+            .map(|(_, used)| &self.nodes[*used])
+            .collect()
+    }
+
+    /// The units in an order where every unit comes after its dependencies.
+    /// Units that could come in either order are sorted by library and name.
+    pub fn compile_order(&self) -> Result<Vec<&DependencyNode>, CycleError> {
+        let mut num_dependencies = vec![0; self.nodes.len()];
+        for (user, _) in self.edges.iter() {
+            num_dependencies[*user] += 1;
+        }
+
+        let mut ready: BTreeSet<usize> = (0..self.nodes.len())
+            .filter(|idx| num_dependencies[*idx] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(idx) = ready.pop_first() {
+            order.push(&self.nodes[idx]);
+            for (user, _) in self.edges.iter().filter(|(_, used)| *used == idx) {
+                num_dependencies[*user] -= 1;
+                if num_dependencies[*user] == 0 {
+                    ready.insert(*user);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&num_dependencies))
+        }
+    }
+
+    /// Find a cycle among the units whose dependencies could not all be ordered
+    fn find_cycle(&self, num_dependencies: &[usize]) -> CycleError {
+        let remaining = |idx: usize| num_dependencies[idx] > 0;
+        let mut path: Vec<usize> = Vec::new();
+        let mut idx = (0..self.nodes.len()).find(|idx| remaining(*idx)).unwrap();
+
+        // Every remaining unit depends on at least one other remaining unit
+        while !path.contains(&idx) {
+            path.push(idx);
+            idx = self
+                .edges
+                .iter()
+                .find(|(user, used)| *user == idx && remaining(*used))
+                .map(|(_, used)| *used)
+                .unwrap();
+        }
+
+        let start = path.iter().position(|path_idx| *path_idx == idx).unwrap();
+        let mut cycle: Vec<_> = path[start..]
+            .iter()
+            .map(|idx| self.nodes[*idx].unit_id.clone())
+            .collect();
+        cycle.push(self.nodes[idx].unit_id.clone());
+        CycleError { cycle }
+    }
+
+    /// The graph in the DOT language of Graphviz with an edge from each unit to its dependencies
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+        for node in self.nodes.iter() {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\\n{}\", tooltip=\"{}\"];",
+                escape(&node.unit_id.to_string()),
+                escape(&node.unit_id.to_string()),
+                node.describe_kind(),
+                escape(&node.source.file_name().to_string_lossy())
+            );
+        }
+        for (user, used) in self.edges.iter() {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\";",
+                escape(&self.nodes[*user].unit_id.to_string()),
+                escape(&self.nodes[*used].unit_id.to_string())
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The graph as a JSON object with a list of nodes and a list of edges,
+    /// where each edge refers to the indices of the user and the used node
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "{{\"library\":\"{}\",\"primary_unit\":\"{}\",\"name\":\"{}\",\"kind\":\"{}\",\"file\":\"{}\"}}",
+                    escape(&node.unit_id.library_name().name_utf8()),
+                    escape(&node.unit_id.primary_name().name_utf8()),
+                    escape(&node.name().name_utf8()),
+                    node.describe_kind(),
+                    escape(&node.source.file_name().to_string_lossy())
+                )
+            })
+            .collect();
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .map(|(user, used)| format!("{{\"from\":{user},\"to\":{used}}}"))
+            .collect();
+
+        format!(
+            "{{\"nodes\":[{}],\"edges\":[{}]}}",
+            nodes.join(","),
+            edges.join(",")
+        )
+    }
+}
+
+/// Escape a string for use within double quotes in DOT or JSON
+fn escape(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    for chr in string.chars() {
+        match chr {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            chr if chr.is_control() => {
+                let _ = write!(result, "\\u{:04x}", chr as u32);
+            }
+            chr => result.push(chr),
+        }
+    }
+    result
+}
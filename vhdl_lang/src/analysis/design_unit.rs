@@ -101,16 +101,26 @@ impl<'a> AnalyzeContext<'a> {
                 if primary_pos.source == secondary_pos.source
                     && primary_pos.start() > secondary_pos.start()
                 {
-                    diagnostics.push(Diagnostic::error(
-                        secondary_pos,
-                        capitalize(&format!(
-                            "{} declared before {}",
-                            self.current_unit_id().describe(),
-                            named_entity.describe()
-                        )),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            secondary_pos,
+                            capitalize(&format!(
+                                "{} declared before {}",
+                                self.current_unit_id().describe(),
+                                named_entity.describe()
+                            )),
+                        )
+                        .with_code(ErrorCode::IllegalDeclaration),
+                    );
                 }
             }
+
+            self.analyze_architecture_configuration(
+                &root_region,
+                named_entity,
+                &mut unit.block_config,
+                diagnostics,
+            )?;
         };
 
         self.arena.define(
@@ -207,7 +217,7 @@ impl<'a> AnalyzeContext<'a> {
         let root_scope = Scope::default();
         self.add_implicit_context_clause(&root_scope)?;
         let scope = root_scope.nested();
-        self.analyze_context_clause(&scope, &mut unit.items, diagnostics)?;
+        self.analyze_context_items(&scope, &mut unit.items, true, diagnostics)?;
 
         self.arena.define(
             &mut unit.ident,
@@ -223,10 +233,10 @@ impl<'a> AnalyzeContext<'a> {
         unit: &mut ArchitectureBody,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
-        let primary = match self.lookup_in_library(
-            self.work_library_name(),
+        let primary = match self.lookup_primary_of_secondary(
+            "entity",
             &unit.entity_name.item.pos,
-            &Designator::Identifier(unit.entity_name.item.item.clone()),
+            &unit.entity_name.item.item,
         ) {
             Ok(primary) => primary,
             Err(err) => {
@@ -241,16 +251,19 @@ impl<'a> AnalyzeContext<'a> {
             if let Design::Entity(ref visibility, ref region) = primary.kind() {
                 (visibility, region)
             } else {
-                let mut diagnostic = Diagnostic::error(unit.pos(), "Expected an entity");
+                let mut diagnostic =
+                    Diagnostic::error(&unit.entity_name.item.pos, "Expected an entity")
+                        .with_code(ErrorCode::MismatchedKinds);
 
                 if let Some(pos) = primary.decl_pos() {
                     diagnostic.add_related(pos, format!("Found {}", primary.describe()))
                 }
+                diagnostics.push(diagnostic);
 
                 return Ok(());
             };
 
-        let root_scope = Scope::new(Region::with_visibility(visibility.clone()));
+        let root_scope = Scope::new(Region::with_visibility(visibility.extended()));
         self.analyze_context_clause(&root_scope, &mut unit.context_clause, diagnostics)?;
 
         let arch = self.arena.define(
@@ -269,6 +282,7 @@ impl<'a> AnalyzeContext<'a> {
         self.define_labels_for_concurrent_part(&scope, arch, &mut unit.statements, diagnostics)?;
         self.analyze_declarative_part(&scope, arch, &mut unit.decl, diagnostics)?;
         self.analyze_concurrent_part(&scope, arch, &mut unit.statements, diagnostics)?;
+        self.check_multiple_drivers(&unit.statements, diagnostics);
         scope.close(diagnostics);
         Ok(())
     }
@@ -278,10 +292,10 @@ impl<'a> AnalyzeContext<'a> {
         unit: &mut PackageBody,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
-        let primary = match self.lookup_in_library(
-            self.work_library_name(),
+        let primary = match self.lookup_primary_of_secondary(
+            "package",
             &unit.ident.tree.pos,
-            &Designator::Identifier(unit.ident.tree.item.clone()),
+            &unit.ident.tree.item,
         ) {
             Ok(primary) => primary,
             Err(err) => {
@@ -294,11 +308,13 @@ impl<'a> AnalyzeContext<'a> {
             Design::Package(ref visibility, ref region)
             | Design::UninstPackage(ref visibility, ref region) => (visibility, region),
             _ => {
-                let mut diagnostic = Diagnostic::error(unit.pos(), "Expected a package");
+                let mut diagnostic = Diagnostic::error(&unit.ident.tree.pos, "Expected a package")
+                    .with_code(ErrorCode::MismatchedKinds);
 
                 if let Some(pos) = primary.decl_pos() {
                     diagnostic.add_related(pos, format!("Found {}", primary.describe()))
                 }
+                diagnostics.push(diagnostic);
 
                 return Ok(());
             }
@@ -316,7 +332,7 @@ impl<'a> AnalyzeContext<'a> {
         self.check_secondary_before_primary(&primary, unit.pos(), diagnostics);
 
         // @TODO make pattern of primary/secondary extension
-        let root_scope = Scope::new(Region::with_visibility(visibility.clone()));
+        let root_scope = Scope::new(Region::with_visibility(visibility.extended()));
 
         self.analyze_context_clause(&root_scope, &mut unit.context_clause, diagnostics)?;
 
@@ -330,6 +346,38 @@ impl<'a> AnalyzeContext<'a> {
         Ok(())
     }
 
+    /// The entity of an architecture or the package of a package body within the work library,
+    /// a missing primary unit is reported as a missing entity or package
+    fn lookup_primary_of_secondary(
+        &self,
+        primary_kind: &str,
+        pos: &SrcPos,
+        primary_name: &Symbol,
+    ) -> AnalysisResult<DesignEnt<'a>> {
+        let library_name = self.work_library_name();
+        self.lookup_in_library(
+            library_name,
+            pos,
+            &Designator::Identifier(primary_name.clone()),
+        )
+        .map_err(|err| match err {
+            AnalysisError::NotFatal(_)
+                if self.get_primary_unit(library_name, primary_name).is_none() =>
+            {
+                AnalysisError::NotFatal(
+                    Diagnostic::error(
+                        pos,
+                        format!(
+                            "No {primary_kind} '{primary_name}' within library '{library_name}'"
+                        ),
+                    )
+                    .with_code(ErrorCode::MissingDecl),
+                )
+            }
+            err => err,
+        })
+    }
+
     fn check_secondary_before_primary(
         &self,
         primary: &DesignEnt,
@@ -340,14 +388,17 @@ impl<'a> AnalyzeContext<'a> {
             if primary_pos.source == secondary_pos.source
                 && primary_pos.start() > secondary_pos.start()
             {
-                diagnostics.push(Diagnostic::error(
-                    secondary_pos,
-                    format!(
-                        "{} declared before {}",
-                        capitalize(&self.current_unit_id().describe()),
-                        primary.describe(),
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        secondary_pos,
+                        format!(
+                            "{} declared before {}",
+                            capitalize(&self.current_unit_id().describe()),
+                            primary.describe(),
+                        ),
+                    )
+                    .with_code(ErrorCode::IllegalDeclaration),
+                );
             }
         }
     }
@@ -378,9 +429,9 @@ impl<'a> AnalyzeContext<'a> {
                 match name {
                     ResolvedName::Library(ref library_name) => {
                         if library_name != self.work_library_name() {
-                            diagnostics.error(
+                            diagnostics.push(Diagnostic::error(
                                 &prefix.pos,
-                                format!("Configuration must be within the same library '{}' as the corresponding entity", self.work_library_name()));
+                                format!("Configuration must be within the same library '{}' as the corresponding entity", self.work_library_name())).with_code(ErrorCode::InvalidConfiguration));
                             Err(EvalError::Unknown)
                         } else {
                             let primary_ent = catch_analysis_err(
@@ -398,12 +449,15 @@ impl<'a> AnalyzeContext<'a> {
                             match primary_ent.kind() {
                                 Design::Entity(..) => Ok(primary_ent),
                                 _ => {
-                                    diagnostics.error(
-                                        designator,
-                                        format!(
-                                            "{} does not denote an entity",
-                                            primary_ent.describe()
-                                        ),
+                                    diagnostics.push(
+                                        Diagnostic::error(
+                                            designator,
+                                            format!(
+                                                "{} does not denote an entity",
+                                                primary_ent.describe()
+                                            ),
+                                        )
+                                        .with_code(ErrorCode::MismatchedKinds),
                                     );
                                     Err(EvalError::Unknown)
                                 }
@@ -417,7 +471,10 @@ impl<'a> AnalyzeContext<'a> {
                 }
             }
             _ => {
-                diagnostics.error(&ent_name, "Expected selected name");
+                diagnostics.push(
+                    Diagnostic::error(&ent_name, "Expected selected name")
+                        .with_code(ErrorCode::MismatchedKinds),
+                );
                 Err(EvalError::Unknown)
             }
         }
@@ -478,6 +535,17 @@ impl<'a> AnalyzeContext<'a> {
         scope: &Scope<'a>,
         context_clause: &mut [ContextItem],
         diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        self.analyze_context_items(scope, context_clause, false, diagnostics)
+    }
+
+    /// The items of a context clause or of a context declaration
+    fn analyze_context_items(
+        &self,
+        scope: &Scope<'a>,
+        context_clause: &mut [ContextItem],
+        is_context_declaration: bool,
+        diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
         for context_item in context_clause.iter_mut() {
             match context_item {
@@ -487,18 +555,32 @@ impl<'a> AnalyzeContext<'a> {
                     for library_name in name_list.items.iter_mut() {
                         if self.work_sym == library_name.item.item {
                             library_name.set_unique_reference(self.work_library());
-                            diagnostics.push(Diagnostic::hint(
-                                &library_name.item,
-                                "Library clause not necessary for current working library",
-                            ))
+                            if is_context_declaration {
+                                // LRM 13.3: The library work may not be named in a context declaration
+                                diagnostics.push(Diagnostic::error(
+                                    &library_name.item,
+                                    "Library clause of a context declaration may not name the library work",
+                                ).with_code(ErrorCode::IllegalDeclaration))
+                            } else {
+                                diagnostics.push(
+                                    Diagnostic::hint(
+                                        &library_name.item,
+                                        "Library clause not necessary for current working library",
+                                    )
+                                    .with_code(ErrorCode::UnnecessaryWorkLibrary),
+                                )
+                            }
                         } else if let Some(library) = self.get_library(&library_name.item.item) {
                             library_name.set_unique_reference(library);
                             scope.make_potentially_visible(Some(&library_name.item.pos), library);
                         } else {
-                            diagnostics.push(Diagnostic::error(
-                                &library_name.item,
-                                format!("No such library '{}'", library_name.item),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &library_name.item,
+                                    format!("No such library '{}'", library_name.item),
+                                )
+                                .with_code(ErrorCode::MissingDecl),
+                            );
                         }
                     }
                 }
@@ -512,10 +594,13 @@ impl<'a> AnalyzeContext<'a> {
                         match name.item {
                             Name::Selected(..) => {}
                             _ => {
-                                diagnostics.push(Diagnostic::error(
-                                    &name.pos,
-                                    "Context reference must be a selected name",
-                                ));
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &name.pos,
+                                        "Context reference must be a selected name",
+                                    )
+                                    .with_code(ErrorCode::MismatchedKinds),
+                                );
                                 continue;
                             }
                         }
@@ -533,13 +618,16 @@ impl<'a> AnalyzeContext<'a> {
                                     }
                                     _ => {
                                         if let Name::Selected(_, ref suffix) = name.item {
-                                            diagnostics.push(Diagnostic::error(
-                                                suffix,
-                                                format!(
-                                                    "{} does not denote a context declaration",
-                                                    ent.describe()
-                                                ),
-                                            ));
+                                            diagnostics.push(
+                                                Diagnostic::error(
+                                                    suffix,
+                                                    format!(
+                                                        "{} does not denote a context declaration",
+                                                        ent.describe()
+                                                    ),
+                                                )
+                                                .with_code(ErrorCode::MismatchedKinds),
+                                            );
                                         }
                                     }
                                 }
@@ -570,16 +658,19 @@ impl<'a> AnalyzeContext<'a> {
                 Name::Selected(..) => {}
                 Name::SelectedAll(..) => {}
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        &name.pos,
-                        "Use clause must be a selected name",
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(&name.pos, "Use clause must be a selected name")
+                            .with_code(ErrorCode::MismatchedKinds),
+                    );
                     continue;
                 }
             }
 
             match self.resolve_context_item_name(scope, name) {
                 Ok(UsedNames::Single(visible)) => {
+                    if let Some(prev_pos) = scope.made_visible_by(&visible) {
+                        diagnostics.push(Diagnostic::redundant_use_clause(name, &prev_pos));
+                    }
                     visible.make_potentially_visible_in(Some(&name.pos), scope);
                 }
                 Ok(UsedNames::AllWithin(visibility_pos, named_entity)) => {
@@ -597,6 +688,10 @@ impl<'a> AnalyzeContext<'a> {
                             }
                             Design::Package(_, ref primary_region)
                             | Design::PackageInstance(ref primary_region) => {
+                                if let Some(prev_pos) = scope.all_made_visible_by(primary_region) {
+                                    diagnostics
+                                        .push(Diagnostic::redundant_use_clause(name, &prev_pos));
+                                }
                                 scope.make_all_potentially_visible(Some(&name.pos), primary_region);
                             }
                             _ => {
@@ -606,7 +701,13 @@ impl<'a> AnalyzeContext<'a> {
                         },
 
                         _ => {
-                            diagnostics.error(visibility_pos, "Invalid prefix for selected name");
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    visibility_pos,
+                                    "Invalid prefix for selected name",
+                                )
+                                .with_code(ErrorCode::MismatchedKinds),
+                            );
                         }
                     }
                 }
@@ -637,9 +738,12 @@ impl<'a> AnalyzeContext<'a> {
                 return Ok(package_region.clone());
             }
         }
-        diagnostics.error(
-            &package_name.pos,
-            format!("'{package_name}' is not an uninstantiated generic package"),
+        diagnostics.push(
+            Diagnostic::error(
+                &package_name.pos,
+                format!("'{package_name}' is not an uninstantiated generic package"),
+            )
+            .with_code(ErrorCode::InvalidInstantiation),
         );
         Err(EvalError::Unknown)
     }
@@ -652,3 +756,14 @@ pub enum UsedNames<'a> {
     /// @TODO add pos for where declaration was made visible into VisibleDeclaration
     AllWithin(SrcPos, EntRef<'a>),
 }
+
+impl Diagnostic {
+    fn redundant_use_clause(name: &WithPos<Name>, prev_pos: &SrcPos) -> Diagnostic {
+        Diagnostic::warning(
+            &name.pos,
+            format!("Use clause is redundant, '{name}' is already made visible in this region"),
+        )
+        .related(prev_pos, "Previously made visible here")
+        .with_code(ErrorCode::RedundantUseClause)
+    }
+}
@@ -0,0 +1,377 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::analyze::*;
+use crate::ast::search::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::syntax::TokenAccess;
+use fnv::FnvHashMap;
+
+/// A statically known part of the longest static prefix of a target name
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Selection {
+    /// A record element
+    Element(EntityId),
+    /// An index or slice with literal bounds
+    Range(u64, u64),
+    /// An index denoted by a constant or enumeration literal
+    Named(EntityId),
+    /// A static index or slice whose value is not known during analysis
+    Unknown,
+}
+
+impl Selection {
+    /// Returns true if the two selections may select the same sub-element
+    fn overlaps(&self, other: &Selection) -> bool {
+        match (self, other) {
+            (Selection::Element(lhs), Selection::Element(rhs)) => lhs == rhs,
+            (Selection::Range(llo, lhi), Selection::Range(rlo, rhi)) => llo <= rhi && rlo <= lhi,
+            (Selection::Named(lhs), Selection::Named(rhs)) => lhs == rhs,
+            // Indexes that cannot be compared are assumed to be disjoint
+            // to avoid false positives
+            _ => false,
+        }
+    }
+}
+
+struct Driver<'a> {
+    signal: EntRef<'a>,
+    /// The concurrent statement that contains the driver
+    statement: usize,
+    selections: Vec<Selection>,
+    pos: SrcPos,
+}
+
+impl<'a> Driver<'a> {
+    fn overlaps(&self, other: &Driver<'a>) -> bool {
+        self.signal.id() == other.signal.id()
+            && self
+                .selections
+                .iter()
+                .zip(other.selections.iter())
+                .all(|(lhs, rhs)| lhs.overlaps(rhs))
+    }
+}
+
+/// Collects the signals driven by a single concurrent statement
+struct DriverSearcher<'a, 'b> {
+    arena: &'a Arena,
+    statement: usize,
+    drivers: &'b mut Vec<Driver<'a>>,
+}
+
+impl<'a, 'b> DriverSearcher<'a, 'b> {
+    fn add_target(&mut self, target: &WithPos<Target>) {
+        match &target.item {
+            Target::Name(name) => self.add_name(name, &target.pos),
+            Target::Aggregate(assocs) => {
+                for assoc in assocs.iter() {
+                    let expr = match assoc {
+                        ElementAssociation::Positional(expr) => expr,
+                        ElementAssociation::Named(_, expr) => expr,
+                    };
+                    if let Expression::Name(ref name) = expr.item {
+                        self.add_name(name, &expr.pos);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_name(&mut self, name: &Name, pos: &SrcPos) {
+        if let Some((signal, selections)) = self.driven_signal(name) {
+            self.drivers.push(Driver {
+                signal,
+                statement: self.statement,
+                // Only the longest static prefix is driven
+                selections: selections.into_iter().map_while(|sel| sel).collect(),
+                pos: pos.clone(),
+            });
+        }
+    }
+
+    /// Actuals associated with ports of mode out, inout or buffer are driven by the instance
+    fn add_port_map(&mut self, inst: &InstantiationStatement) {
+        let Some(ref port_map) = inst.port_map else {
+            return;
+        };
+        let Some(id) = inst.entity_reference() else {
+            return;
+        };
+        let region = match self.arena.get(id).kind() {
+            AnyEntKind::Component(region) => region,
+            AnyEntKind::Design(Design::Entity(_, region)) => region,
+            _ => return,
+        };
+        let (ports, _) = region.ports_and_generics();
+
+        for (idx, elem) in port_map.list.items.iter().enumerate() {
+            let port = if let Some(ref formal) = elem.formal {
+                formal
+                    .item
+                    .get_formal_reference()
+                    .map(|id| self.arena.get(id))
+            } else {
+                ports.get(idx).map(|port| port.inner())
+            };
+
+            let is_driving = matches!(
+                port.map(|port| port.kind()),
+                Some(AnyEntKind::Object(Object {
+                    iface: Some(ObjectInterface::Port(
+                        Mode::Out | Mode::InOut | Mode::Buffer
+                    )),
+                    ..
+                }))
+            );
+
+            if is_driving {
+                if let ActualPart::Expression(Expression::Name(ref name)) = elem.actual.item {
+                    self.add_name(name, &elem.actual.pos);
+                }
+            }
+        }
+    }
+
+    /// Returns the signal denoted by the name together with the selections of the name.
+    /// A selection of None means the selection is not static.
+    fn driven_signal(&self, name: &Name) -> Option<(EntRef<'a>, Vec<Option<Selection>>)> {
+        match name {
+            Name::Designator(designator) => {
+                let ent = self.arena.get(designator.reference.get()?);
+                is_signal(ent).then(|| (ent, Vec::new()))
+            }
+            Name::Selected(prefix, suffix) => {
+                let id = suffix.item.reference.get()?;
+                let ent = self.arena.get(id);
+                if is_signal(ent) {
+                    // An expanded name such as pkg.sig
+                    return Some((ent, Vec::new()));
+                }
+                let (signal, mut selections) = self.driven_signal(&prefix.item)?;
+                selections.push(Some(Selection::Element(id)));
+                Some((signal, selections))
+            }
+            Name::CallOrIndexed(call) => {
+                let (signal, mut selections) = self.driven_signal(&call.name.item)?;
+                for param in call.parameters.iter() {
+                    selections.push(match param.actual.item {
+                        ActualPart::Expression(ref expr) => self.index_selection(expr),
+                        ActualPart::Open => None,
+                    });
+                }
+                Some((signal, selections))
+            }
+            Name::Slice(prefix, drange) => {
+                let (signal, mut selections) = self.driven_signal(&prefix.item)?;
+                selections.push(self.range_selection(drange));
+                Some((signal, selections))
+            }
+            Name::SelectedAll(..) | Name::Attribute(..) | Name::External(..) => None,
+        }
+    }
+
+    fn index_selection(&self, expr: &Expression) -> Option<Selection> {
+        match expr {
+            Expression::Literal(Literal::AbstractLiteral(AbstractLiteral::Integer(value))) => {
+                Some(Selection::Range(*value, *value))
+            }
+            Expression::Name(name) => match name.as_ref() {
+                Name::Designator(designator) => {
+                    let id = designator.reference.get()?;
+                    self.is_static_name(id).then_some(Selection::Named(id))
+                }
+                _ => None,
+            },
+            _ => self.is_static(expr).then_some(Selection::Unknown),
+        }
+    }
+
+    fn range_selection(&self, drange: &DiscreteRange) -> Option<Selection> {
+        match drange {
+            DiscreteRange::Range(crate::ast::Range::Range(constraint)) => {
+                let left = integer_literal(&constraint.left_expr.item);
+                let right = integer_literal(&constraint.right_expr.item);
+                if let (Some(left), Some(right)) = (left, right) {
+                    Some(Selection::Range(left.min(right), left.max(right)))
+                } else {
+                    (self.is_static(&constraint.left_expr.item)
+                        && self.is_static(&constraint.right_expr.item))
+                    .then_some(Selection::Unknown)
+                }
+            }
+            // Ranges given by a type or attribute are static unless they depend on a variable
+            // which cannot be known without evaluating the attribute prefix
+            DiscreteRange::Range(crate::ast::Range::Attribute(..))
+            | DiscreteRange::Discrete(..) => Some(Selection::Unknown),
+        }
+    }
+
+    fn is_static_name(&self, id: EntityId) -> bool {
+        matches!(
+            self.arena.get(id).kind(),
+            AnyEntKind::Object(Object {
+                class: ObjectClass::Constant,
+                ..
+            }) | AnyEntKind::DeferredConstant(..)
+                | AnyEntKind::Overloaded(Overloaded::EnumLiteral(..))
+        )
+    }
+
+    fn is_static(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Literal(..) => true,
+            Expression::Name(name) => match name.as_ref() {
+                Name::Designator(designator) => designator
+                    .reference
+                    .get()
+                    .map(|id| self.is_static_name(id))
+                    .unwrap_or(false),
+                _ => false,
+            },
+            Expression::Unary(_, expr) => self.is_static(&expr.item),
+            Expression::Binary(_, left, right) => {
+                self.is_static(&left.item) && self.is_static(&right.item)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'b> Searcher for DriverSearcher<'a, 'b> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        match decl {
+            FoundDeclaration::SequentialStatement(stmt) => {
+                if let SequentialStatement::SignalAssignment(ref assign) = stmt.statement.item {
+                    self.add_target(&assign.target);
+                }
+            }
+            FoundDeclaration::ConcurrentStatement(stmt) => match stmt.statement.item {
+                ConcurrentStatement::Assignment(ref assign) => self.add_target(&assign.target),
+                ConcurrentStatement::Instance(ref inst) => self.add_port_map(inst),
+                _ => {}
+            },
+            _ => {}
+        }
+        SearchState::NotFinished
+    }
+}
+
+pub(super) fn is_signal(ent: EntRef) -> bool {
+    matches!(
+        ent.kind(),
+        AnyEntKind::Object(Object {
+            class: ObjectClass::Signal,
+            iface: None | Some(ObjectInterface::Port(..)),
+            ..
+        })
+    )
+}
+
+fn integer_literal(expr: &Expression) -> Option<u64> {
+    if let Expression::Literal(Literal::AbstractLiteral(AbstractLiteral::Integer(value))) = expr {
+        Some(*value)
+    } else {
+        None
+    }
+}
+
+impl<'a> AnalyzeContext<'a> {
+    /// Report signals that are driven by more than one concurrent statement.
+    /// Each process, concurrent assignment and instance contributes a driver
+    /// for the longest static prefix of its targets.
+    pub fn check_multiple_drivers(
+        &self,
+        statements: &[LabeledConcurrentStatement],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let mut drivers = Vec::new();
+        self.collect_drivers(statements, &mut 0, &mut drivers);
+
+        let mut by_signal: FnvHashMap<EntityId, Vec<&Driver>> = FnvHashMap::default();
+        let mut signals = Vec::new();
+        for driver in drivers.iter() {
+            let id = driver.signal.id();
+            by_signal
+                .entry(id)
+                .or_insert_with(|| {
+                    signals.push(id);
+                    Vec::new()
+                })
+                .push(driver);
+        }
+
+        for id in signals {
+            let drivers = &by_signal[&id];
+            let conflicting: Vec<&Driver> = drivers
+                .iter()
+                .filter(|driver| {
+                    drivers
+                        .iter()
+                        .any(|other| other.statement != driver.statement && driver.overlaps(other))
+                })
+                .copied()
+                .collect();
+
+            let Some((first, others)) = conflicting.split_first() else {
+                continue;
+            };
+
+            let signal = first.signal;
+            let is_resolved = matches!(
+                signal.kind(),
+                AnyEntKind::Object(obj) if obj.subtype.is_resolved()
+            );
+
+            let mut diagnostic = if is_resolved {
+                Diagnostic::warning(
+                    &first.pos,
+                    format!(
+                        "Resolved signal '{}' has multiple drivers",
+                        signal.designator()
+                    ),
+                )
+                .with_code(ErrorCode::MultipleDrivers)
+            } else {
+                Diagnostic::error(
+                    &first.pos,
+                    format!("Signal '{}' has multiple drivers", signal.designator()),
+                )
+                .with_code(ErrorCode::MultipleDrivers)
+            };
+
+            for other in others {
+                diagnostic.add_related(&other.pos, "Also driven here");
+            }
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    fn collect_drivers(
+        &self,
+        statements: &[LabeledConcurrentStatement],
+        next_statement: &mut usize,
+        drivers: &mut Vec<Driver<'a>>,
+    ) {
+        for statement in statements.iter() {
+            if let ConcurrentStatement::Block(ref block) = statement.statement.item {
+                // Each statement of a block is a separate driver
+                self.collect_drivers(&block.statements, next_statement, drivers);
+                continue;
+            }
+
+            let mut searcher = DriverSearcher {
+                arena: self.arena,
+                statement: *next_statement,
+                drivers,
+            };
+            *next_statement += 1;
+            let _ = statement.search(self.ctx, &mut searcher);
+        }
+    }
+}
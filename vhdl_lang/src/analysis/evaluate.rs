@@ -0,0 +1,319 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Evaluation of locally static expressions
+//!
+//! The names within the expression must already have been resolved by the analysis.
+//! Expressions that are not locally static, or that use operators or attributes which are
+//! not supported, evaluate to `None`.
+
+use super::analyze::*;
+use super::static_expression::bit_string_to_string;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+/// The maximum nesting of a static expression that is evaluated
+const MAX_EVALUATION_DEPTH: usize = 256;
+
+impl<'a> AnalyzeContext<'a> {
+    /// Evaluate a locally static expression, an overflow is reported as an error
+    pub fn evaluate_static(
+        &self,
+        expr: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<Value> {
+        self.evaluate_static_pos(&expr.pos, &expr.item, diagnostics)
+    }
+
+    /// Evaluate a locally static expression without a position of its own such as a choice
+    pub(crate) fn evaluate_static_pos(
+        &self,
+        pos: &SrcPos,
+        expr: &Expression,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<Value> {
+        // Guard against overflowing the stack on pathologically nested expressions
+        let depth = self.evaluation_depth.get();
+        if depth >= MAX_EVALUATION_DEPTH {
+            diagnostics.push(
+                Diagnostic::error(
+                    pos,
+                    "Static expression is nested too deeply to be evaluated",
+                )
+                .with_code(ErrorCode::StaticEvaluation),
+            );
+            return None;
+        }
+
+        self.evaluation_depth.set(depth + 1);
+        let value = self.evaluate_expression(pos, expr, diagnostics);
+        self.evaluation_depth.set(depth);
+        value
+    }
+
+    fn evaluate_expression(
+        &self,
+        pos: &SrcPos,
+        expr: &Expression,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<Value> {
+        match expr {
+            Expression::Literal(literal) => self.evaluate_literal(pos, literal, diagnostics),
+            Expression::Name(name) => self.evaluate_name(name, diagnostics),
+            Expression::Qualified(qexpr) => self.evaluate_static(&qexpr.expr, diagnostics),
+            Expression::Unary(op, operand) => {
+                if !self.is_predefined_operator(&op.item) {
+                    return None;
+                }
+                let value = self.evaluate_static(operand, diagnostics)?;
+                match (op.item.item, value) {
+                    (Operator::Plus, value @ (Value::Integer(_) | Value::Real(_))) => Some(value),
+                    (Operator::Minus, Value::Integer(value)) => {
+                        overflow(pos, value.checked_neg(), diagnostics)
+                    }
+                    (Operator::Abs, Value::Integer(value)) => {
+                        overflow(pos, value.checked_abs(), diagnostics)
+                    }
+                    (Operator::Minus, Value::Real(value)) => Some(Value::Real(-value)),
+                    (Operator::Abs, Value::Real(value)) => Some(Value::Real(value.abs())),
+                    _ => None,
+                }
+            }
+            Expression::Binary(op, left, right) => {
+                if !self.is_predefined_operator(&op.item) {
+                    return None;
+                }
+                let left = self.evaluate_static(left, diagnostics)?;
+                let right = self.evaluate_static(right, diagnostics)?;
+                match (left, right) {
+                    (Value::Integer(left), Value::Integer(right)) => {
+                        self.evaluate_integer_operator(pos, op.item.item, left, right, diagnostics)
+                    }
+                    (Value::Real(left), Value::Real(right)) => match op.item.item {
+                        Operator::Plus => Some(Value::Real(left + right)),
+                        Operator::Minus => Some(Value::Real(left - right)),
+                        Operator::Times => Some(Value::Real(left * right)),
+                        Operator::Div if right != 0.0 => Some(Value::Real(left / right)),
+                        _ => None,
+                    },
+                    (Value::Enumeration(left), Value::Enumeration(right)) => match op.item.item {
+                        Operator::EQ => Some(self.boolean_value(left == right)),
+                        Operator::NE => Some(self.boolean_value(left != right)),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            Expression::Aggregate(..) | Expression::New(..) => None,
+        }
+    }
+
+    fn evaluate_literal(
+        &self,
+        pos: &SrcPos,
+        literal: &Literal,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<Value> {
+        match literal {
+            Literal::AbstractLiteral(AbstractLiteral::Integer(value)) => {
+                overflow(pos, i64::try_from(*value).ok(), diagnostics)
+            }
+            Literal::AbstractLiteral(AbstractLiteral::Real(value)) => Some(Value::Real(*value)),
+            Literal::Physical(PhysicalLiteral { value, unit }) => {
+                Some(Value::Physical(*value, unit.item.item.clone()))
+            }
+            Literal::Character(chr) => Some(Value::Enumeration(Designator::Character(*chr))),
+            Literal::String(string) => Some(Value::String(string.clone())),
+            Literal::BitString(bit_string) => {
+                bit_string_to_string(bit_string).ok().map(Value::String)
+            }
+            Literal::Null => None,
+        }
+    }
+
+    fn evaluate_name(&self, name: &Name, diagnostics: &mut dyn DiagnosticHandler) -> Option<Value> {
+        match name {
+            Name::Designator(designator) => self.evaluate_reference(&designator.reference),
+            Name::Selected(_, suffix) => self.evaluate_reference(&suffix.item.reference),
+            Name::Attribute(attr) if attr.expr.is_none() && attr.signature.is_none() => {
+                let range = self.static_name_range(&attr.name.item, diagnostics)?;
+                let value = match attr.attr.item {
+                    AttributeDesignator::Left => range.left,
+                    AttributeDesignator::Right => range.right,
+                    AttributeDesignator::Low => range.low(),
+                    AttributeDesignator::High => range.high(),
+                    AttributeDesignator::Length => i64::try_from(range.length()).ok()?,
+                    _ => return None,
+                };
+                Some(Value::Integer(value))
+            }
+            _ => None,
+        }
+    }
+
+    fn evaluate_reference(&self, reference: &Reference) -> Option<Value> {
+        let ent = self.arena.get(reference.get()?);
+        if let Some(value) = self.generic_values.borrow().get(&ent.id()) {
+            return Some(value.clone());
+        }
+        match ent.actual_kind() {
+            AnyEntKind::Object(Object {
+                class: ObjectClass::Constant,
+                static_value,
+                ..
+            }) => static_value.clone(),
+            AnyEntKind::Overloaded(Overloaded::EnumLiteral(..)) => {
+                Some(Value::Enumeration(ent.designator().clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn evaluate_integer_operator(
+        &self,
+        pos: &SrcPos,
+        op: Operator,
+        left: i64,
+        right: i64,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<Value> {
+        let value = match op {
+            Operator::Plus => left.checked_add(right),
+            Operator::Minus => left.checked_sub(right),
+            Operator::Times => left.checked_mul(right),
+            Operator::Div if right != 0 => left.checked_div(right),
+            Operator::Rem if right != 0 => left.checked_rem(right),
+            // The result of mod has the sign of the right operand
+            Operator::Mod if right != 0 => left.checked_rem(right).map(|rem| {
+                if rem != 0 && (rem < 0) != (right < 0) {
+                    rem + right
+                } else {
+                    rem
+                }
+            }),
+            Operator::Pow => u32::try_from(right)
+                .ok()
+                .and_then(|exp| left.checked_pow(exp)),
+            Operator::EQ => return Some(self.boolean_value(left == right)),
+            Operator::NE => return Some(self.boolean_value(left != right)),
+            Operator::LT => return Some(self.boolean_value(left < right)),
+            Operator::LTE => return Some(self.boolean_value(left <= right)),
+            Operator::GT => return Some(self.boolean_value(left > right)),
+            Operator::GTE => return Some(self.boolean_value(left >= right)),
+            _ => return None,
+        };
+        overflow(pos, value, diagnostics)
+    }
+
+    fn boolean_value(&self, value: bool) -> Value {
+        let name = if value { "true" } else { "false" };
+        Value::Enumeration(Designator::Identifier(self.root.symbol_utf8(name)))
+    }
+
+    /// Operators of user defined functions are never locally static
+    pub(super) fn is_predefined_operator(&self, op: &WithRef<Operator>) -> bool {
+        op.reference
+            .get()
+            .map(|id| self.arena.get(id).is_implicit())
+            .unwrap_or(true)
+    }
+
+    /// The range of a scalar range constraint or a one-dimensional index constraint
+    /// with locally static bounds
+    pub(crate) fn static_constraint_range(
+        &self,
+        constraint: &SubtypeConstraint,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<StaticRange> {
+        match constraint {
+            SubtypeConstraint::Range(range) => self.static_range(range, diagnostics),
+            SubtypeConstraint::Array(ranges, _) => {
+                let [drange] = ranges.as_slice() else {
+                    return None;
+                };
+                self.static_discrete_range(drange, diagnostics)
+            }
+            SubtypeConstraint::Record(..) => None,
+        }
+    }
+
+    pub(crate) fn static_discrete_range(
+        &self,
+        drange: &DiscreteRange,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<StaticRange> {
+        match drange {
+            DiscreteRange::Range(range) => self.static_range(range, diagnostics),
+            DiscreteRange::Discrete(..) => None,
+        }
+    }
+
+    fn static_range(
+        &self,
+        range: &crate::ast::Range,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<StaticRange> {
+        match range {
+            crate::ast::Range::Range(constraint) => {
+                let left = self.evaluate_static(&constraint.left_expr, diagnostics)?;
+                let right = self.evaluate_static(&constraint.right_expr, diagnostics)?;
+                Some(StaticRange::new(
+                    left.as_integer()?,
+                    constraint.direction,
+                    right.as_integer()?,
+                ))
+            }
+            crate::ast::Range::Attribute(attr) if attr.expr.is_none() => {
+                let range = self.static_name_range(&attr.name.item, diagnostics)?;
+                match attr.attr.item {
+                    AttributeDesignator::Range(RangeAttribute::Range) => Some(range),
+                    AttributeDesignator::Range(RangeAttribute::ReverseRange) => {
+                        Some(range.reversed())
+                    }
+                    _ => None,
+                }
+            }
+            crate::ast::Range::Attribute(..) => None,
+        }
+    }
+
+    /// The static range of a name denoting a statically constrained object or subtype
+    /// or of a slice name with a static range
+    pub(crate) fn static_name_range(
+        &self,
+        name: &Name,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<StaticRange> {
+        let reference = match name {
+            Name::Designator(designator) => &designator.reference,
+            Name::Selected(_, suffix) => &suffix.item.reference,
+            Name::Slice(_, drange) => return self.static_discrete_range(drange, diagnostics),
+            _ => return None,
+        };
+
+        let ent = self.arena.get(reference.get()?);
+        match ent.actual_kind() {
+            AnyEntKind::Object(obj) => obj.subtype.static_range(),
+            AnyEntKind::Type(Type::Subtype(subtype)) => subtype.static_range(),
+            _ => None,
+        }
+    }
+}
+
+fn overflow(
+    pos: &SrcPos,
+    value: Option<i64>,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> Option<Value> {
+    if value.is_none() {
+        diagnostics.push(
+            Diagnostic::error(pos, "Integer overflow in locally static expression")
+                .with_code(ErrorCode::StaticEvaluation),
+        );
+    }
+    value.map(Value::Integer)
+}
@@ -15,14 +15,17 @@ use super::scope::*;
 use crate::ast::*;
 use crate::data::*;
 use crate::named_entity::*;
+use crate::VHDLStandard;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ExpressionType<'a> {
     Unambiguous(TypeEnt<'a>),
     Ambiguous(FnvHashSet<BaseType<'a>>),
     String,
     Null,
     Aggregate,
+    /// An allocator of an object of the type, which is a value of any access type designating it
+    Allocator(TypeEnt<'a>),
 }
 
 impl<'a> ExpressionType<'a> {
@@ -33,6 +36,9 @@ impl<'a> ExpressionType<'a> {
             ExpressionType::String => other.is_compatible_with_string_literal(),
             ExpressionType::Null => other.is_access(),
             ExpressionType::Aggregate => other.is_composite(),
+            ExpressionType::Allocator(typ) => other
+                .accessed_type()
+                .is_some_and(|accessed| accessed.base() == typ.base()),
         }
     }
 
@@ -43,6 +49,7 @@ impl<'a> ExpressionType<'a> {
             ExpressionType::String => "string literal".to_owned(),
             ExpressionType::Null => "null literal".to_owned(),
             ExpressionType::Aggregate => "aggregate expression".to_owned(),
+            ExpressionType::Allocator(typ) => format!("allocator of {}", typ.describe()),
         }
     }
 }
@@ -229,11 +236,12 @@ impl<'a> AnalyzeContext<'a> {
             ExpressionType::Ambiguous(_)
             | ExpressionType::String
             | ExpressionType::Null
-            | ExpressionType::Aggregate => {
-                diagnostics.error(
+            | ExpressionType::Aggregate
+            | ExpressionType::Allocator(_) => {
+                diagnostics.push(Diagnostic::error(
                 &expr.pos,
                 "Ambiguous expression. You can use a qualified expression type'(expr) to disambiguate.",
-            );
+            ).with_code(ErrorCode::Ambiguous));
                 Err(EvalError::Unknown)
             }
         }
@@ -257,6 +265,7 @@ impl<'a> AnalyzeContext<'a> {
                         ent.describe(),
                     ),
                 )
+                .with_code(ErrorCode::MismatchedKinds)
                 .into())
             }
             NamedEntities::Overloaded(overloaded) => {
@@ -271,6 +280,7 @@ impl<'a> AnalyzeContext<'a> {
                         op_pos,
                         format!("Found no match for {}", designator.describe()),
                     )
+                    .with_code(ErrorCode::InvalidCall)
                     .into())
                 } else {
                     Ok(op_candidates)
@@ -395,10 +405,12 @@ impl<'a> AnalyzeContext<'a> {
         }
 
         if candidates.is_empty() {
-            diagnostics.error(
+            diagnostics.push(self.no_match_for_operator(
                 &op.pos,
-                format!("Found no match for {}", designator.describe()),
-            );
+                &designator,
+                &overloaded,
+                &operand_types,
+            ));
 
             Err(EvalError::Unknown)
         } else if candidates.len() == 1 {
@@ -462,9 +474,16 @@ impl<'a> AnalyzeContext<'a> {
         expr: &mut Expression,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<ExpressionType<'a>> {
-        match expr {
+        let typ = match expr {
             Expression::Binary(ref mut op, ref mut left, ref mut right) => {
-                self.operator_type(scope, op, &mut [left.as_mut(), right.as_mut()], diagnostics)
+                let typ = self.operator_type(
+                    scope,
+                    op,
+                    &mut [left.as_mut(), right.as_mut()],
+                    diagnostics,
+                );
+                self.check_synthesis_division(op, right, diagnostics);
+                typ
             }
             Expression::Unary(ref mut op, ref mut inner) => {
                 self.operator_type(scope, op, &mut [inner.as_mut()], diagnostics)
@@ -480,11 +499,11 @@ impl<'a> AnalyzeContext<'a> {
             Expression::New(ref mut alloc) => match &mut alloc.item {
                 Allocator::Qualified(ref mut qexpr) => {
                     let typ = self.analyze_qualified_expression(scope, qexpr, diagnostics)?;
-                    Ok(ExpressionType::Unambiguous(typ))
+                    Ok(ExpressionType::Allocator(typ))
                 }
                 Allocator::Subtype(ref mut subtype) => self
                     .resolve_subtype_indication(scope, subtype, diagnostics)
-                    .map(|typ| ExpressionType::Unambiguous(typ.type_mark())),
+                    .map(|typ| ExpressionType::Allocator(typ.type_mark())),
             },
             Expression::Literal(ref mut literal) => match literal {
                 Literal::Physical(PhysicalLiteral { ref mut unit, .. }) => {
@@ -502,12 +521,15 @@ impl<'a> AnalyzeContext<'a> {
                     match scope.lookup(expr_pos, &Designator::Character(*chr)) {
                         Ok(NamedEntities::Single(ent)) => {
                             // Should never happen but better know if it does
-                            diagnostics.error(
-                                expr_pos,
-                                format!(
-                                    "Character literal cannot denote non-overloaded symbol {}",
-                                    ent.describe(),
-                                ),
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    expr_pos,
+                                    format!(
+                                        "Character literal cannot denote non-overloaded symbol {}",
+                                        ent.describe(),
+                                    ),
+                                )
+                                .with_code(ErrorCode::MismatchedKinds),
                             );
                             Err(EvalError::Unknown)
                         }
@@ -517,12 +539,15 @@ impl<'a> AnalyzeContext<'a> {
                                 if let Some(return_type) = ent.return_type() {
                                     Ok(ExpressionType::Unambiguous(return_type))
                                 } else {
-                                    diagnostics.error(
-                                        expr_pos,
-                                        format!(
+                                    diagnostics.push(
+                                        Diagnostic::error(
+                                            expr_pos,
+                                            format!(
                                             "Character literal cannot denote procedure symbol {}",
                                             ent.describe(),
                                         ),
+                                        )
+                                        .with_code(ErrorCode::MismatchedKinds),
                                     );
                                     Err(EvalError::Unknown)
                                 }
@@ -550,7 +575,14 @@ impl<'a> AnalyzeContext<'a> {
                 }
                 Literal::Null => Ok(ExpressionType::Null),
             },
+        };
+        // The type of a name is recorded when it is resolved
+        if let Ok(ExpressionType::Unambiguous(typ)) = typ {
+            if !matches!(expr, Expression::Name(..)) {
+                self.record_expression_type(expr_pos, typ);
+            }
         }
+        typ
     }
 
     // Fallback for analyzing an expression without a known target type
@@ -591,21 +623,22 @@ impl<'a> AnalyzeContext<'a> {
         }
     }
 
+    /// Analyze an allocator and return the type of the allocated object
     pub fn analyze_allocation(
         &self,
         scope: &Scope<'a>,
         alloc: &mut WithPos<Allocator>,
         diagnostics: &mut dyn DiagnosticHandler,
-    ) -> FatalResult {
+    ) -> EvalResult<TypeEnt<'a>> {
         match &mut alloc.item {
             Allocator::Qualified(ref mut qexpr) => {
-                as_fatal(self.analyze_qualified_expression(scope, qexpr, diagnostics))?;
+                self.analyze_qualified_expression(scope, qexpr, diagnostics)
             }
             Allocator::Subtype(ref mut subtype) => {
-                self.analyze_subtype_indication(scope, subtype, diagnostics)?;
+                let typ = self.resolve_subtype_indication(scope, subtype, diagnostics)?;
+                Ok(typ.type_mark())
             }
         }
-        Ok(())
     }
 
     pub fn expr_with_ttyp(
@@ -618,8 +651,11 @@ impl<'a> AnalyzeContext<'a> {
         self.expr_pos_with_ttyp(scope, target_type, &expr.pos, &mut expr.item, diagnostics)
     }
 
+    /// The types with a condition operator ?? which is applied implicitly since VHDL-2008
     fn implicit_bool_types(&self, scope: &Scope<'a>, pos: &SrcPos) -> FnvHashSet<BaseType<'a>> {
-        if let Ok(NamedEntities::Overloaded(overloaded)) =
+        if self.standard < VHDLStandard::V2008 {
+            FnvHashSet::default()
+        } else if let Ok(NamedEntities::Overloaded(overloaded)) =
             scope.lookup(pos, &Designator::OperatorSymbol(Operator::QueQue))
         {
             overloaded
@@ -643,15 +679,22 @@ impl<'a> AnalyzeContext<'a> {
                 ExpressionType::Unambiguous(typ) => {
                     if typ.base() != self.boolean().base() {
                         let implicit_bools = self.implicit_bool_types(scope, &expr.pos);
-                        if !implicit_bools.contains(&typ.base()) {
-                            diagnostics.error(
+                        if self.standard < VHDLStandard::V2008 {
+                            // There is no implicit condition conversion before VHDL-2008
+                            diagnostics.push(Diagnostic::type_mismatch(
+                                &expr.pos,
+                                &typ.describe(),
+                                self.boolean(),
+                            ));
+                        } else if !implicit_bools.contains(&typ.base()) {
+                            diagnostics.push(Diagnostic::error(
                                 &expr.pos,
                                 format!(
                                     "{} cannot be implictly converted to {}. Operator ?? is not defined for this type.",
                                     typ.describe(),
                                     self.boolean().describe()
                                 ),
-                            );
+                            ).with_code(ErrorCode::TypeMismatch));
                         }
                     }
                 }
@@ -674,7 +717,8 @@ impl<'a> AnalyzeContext<'a> {
                                 let mut diag = Diagnostic::error(
                                     &expr.pos,
                                     "Ambiguous use of implicit boolean conversion ??",
-                                );
+                                )
+                                .with_code(ErrorCode::Ambiguous);
                                 diag.add_type_candididates("Could be", implicit_bool_types);
                                 diagnostics.push(diag);
                             }
@@ -686,7 +730,8 @@ impl<'a> AnalyzeContext<'a> {
                                         "Cannot disambiguate expression to {}",
                                         self.boolean().describe()
                                     ),
-                                );
+                                )
+                                .with_code(ErrorCode::TypeMismatch);
                                 diag.add_type_candididates(
                                     "Implicit boolean conversion operator ?? is not defined for",
                                     types,
@@ -696,7 +741,10 @@ impl<'a> AnalyzeContext<'a> {
                         }
                     }
                 }
-                ExpressionType::String | ExpressionType::Null | ExpressionType::Aggregate => {
+                ExpressionType::String
+                | ExpressionType::Null
+                | ExpressionType::Aggregate
+                | ExpressionType::Allocator(_) => {
                     self.expr_with_ttyp(scope, self.boolean(), expr, diagnostics)?;
                 }
             }
@@ -717,13 +765,16 @@ impl<'a> AnalyzeContext<'a> {
     ) -> FatalResult {
         let target_base = target_type.base_type();
         match expr {
-            Expression::Literal(ref mut lit) => self.analyze_literal_with_target_type(
-                scope,
-                target_type,
-                expr_pos,
-                lit,
-                diagnostics,
-            )?,
+            Expression::Literal(ref mut lit) => {
+                self.analyze_literal_with_target_type(
+                    scope,
+                    target_type,
+                    expr_pos,
+                    lit,
+                    diagnostics,
+                )?;
+                self.record_expression_type(expr_pos, target_type);
+            }
             Expression::Name(ref mut name) => self.expression_name_with_ttyp(
                 scope,
                 expr_pos,
@@ -735,6 +786,7 @@ impl<'a> AnalyzeContext<'a> {
                 if let Some(type_mark) =
                     as_fatal(self.analyze_qualified_expression(scope, qexpr, diagnostics))?
                 {
+                    self.record_expression_type(expr_pos, type_mark);
                     if !self.can_be_target_type(type_mark, target_base.base()) {
                         diagnostics.push(Diagnostic::type_mismatch(
                             expr_pos,
@@ -763,6 +815,7 @@ impl<'a> AnalyzeContext<'a> {
                 ))? {
                     Some(Disambiguated::Unambiguous(overloaded)) => {
                         let op_type = overloaded.return_type().unwrap();
+                        self.record_expression_type(expr_pos, op_type);
 
                         if !self.can_be_target_type(op_type, target_type.base()) {
                             diagnostics.push(Diagnostic::type_mismatch(
@@ -781,6 +834,7 @@ impl<'a> AnalyzeContext<'a> {
                     }
                     None => {}
                 }
+                self.check_synthesis_division(op, right, diagnostics);
             }
             Expression::Unary(ref mut op, ref mut expr) => {
                 let op_candidates = match self.lookup_operator(scope, &op.pos, op.item.item, 1) {
@@ -801,6 +855,7 @@ impl<'a> AnalyzeContext<'a> {
                 ))? {
                     Some(Disambiguated::Unambiguous(overloaded)) => {
                         let op_type = overloaded.return_type().unwrap();
+                        self.record_expression_type(expr_pos, op_type);
 
                         if !self.can_be_target_type(op_type, target_type.base()) {
                             diagnostics.push(Diagnostic::type_mismatch(
@@ -820,42 +875,61 @@ impl<'a> AnalyzeContext<'a> {
                     None => {}
                 }
             }
-            Expression::Aggregate(assocs) => match target_base.kind() {
-                Type::Array {
-                    elem_type, indexes, ..
-                } => {
-                    for assoc in assocs.iter_mut() {
-                        as_fatal(self.array_assoc_elem(
+            Expression::Aggregate(assocs) => {
+                self.record_expression_type(expr_pos, target_type);
+                match target_base.kind() {
+                    Type::Array {
+                        elem_type, indexes, ..
+                    } => {
+                        for assoc in assocs.iter_mut() {
+                            as_fatal(self.array_assoc_elem(
+                                scope,
+                                target_base,
+                                indexes,
+                                *elem_type,
+                                assoc,
+                                diagnostics,
+                            ))?;
+                        }
+                    }
+                    Type::Record(record_scope) => {
+                        self.analyze_record_aggregate(
                             scope,
                             target_base,
-                            indexes,
-                            *elem_type,
-                            assoc,
+                            record_scope,
+                            expr_pos,
+                            assocs,
                             diagnostics,
-                        ))?;
+                        )?;
                     }
-                }
-                Type::Record(record_scope) => {
-                    self.analyze_record_aggregate(
-                        scope,
-                        target_base,
-                        record_scope,
-                        expr_pos,
-                        assocs,
-                        diagnostics,
-                    )?;
-                }
-                _ => {
-                    self.analyze_aggregate(scope, assocs, diagnostics)?;
+                    _ => {
+                        self.analyze_aggregate(scope, assocs, diagnostics)?;
 
-                    diagnostics.error(
-                        expr_pos,
-                        format!("composite does not match {}", target_type.describe()),
-                    );
+                        diagnostics.push(
+                            Diagnostic::error(
+                                expr_pos,
+                                format!("composite does not match {}", target_type.describe()),
+                            )
+                            .with_code(ErrorCode::TypeMismatch),
+                        );
+                    }
                 }
-            },
+            }
             Expression::New(ref mut alloc) => {
-                self.analyze_allocation(scope, alloc, diagnostics)?;
+                if let Some(allocated) =
+                    as_fatal(self.analyze_allocation(scope, alloc, diagnostics))?
+                {
+                    let matches = target_base
+                        .accessed_type()
+                        .is_some_and(|accessed| accessed.base() == allocated.base());
+                    if !matches {
+                        diagnostics.push(Diagnostic::type_mismatch(
+                            expr_pos,
+                            &ExpressionType::Allocator(allocated).describe(),
+                            target_type,
+                        ));
+                    }
+                }
             }
         }
 
@@ -907,6 +981,8 @@ impl<'a> AnalyzeContext<'a> {
         for (idx, assoc) in assocs.iter_mut().enumerate() {
             match assoc {
                 ElementAssociation::Named(ref mut choices, ref mut actual_expr) => {
+                    // The types of the elements of others when they are not all the same
+                    let mut other_types = Vec::new();
                     let typ = if choices.len() == 1 {
                         let choice = choices.first_mut().unwrap();
                         match &mut choice.item {
@@ -929,18 +1005,24 @@ impl<'a> AnalyzeContext<'a> {
                                     }
                                 } else {
                                     is_ok_so_far = false;
-                                    diagnostics.error(
-                                        &choice.pos,
-                                        "Record aggregate choice must be a simple name",
+                                    diagnostics.push(
+                                        Diagnostic::error(
+                                            &choice.pos,
+                                            "Record aggregate choice must be a simple name",
+                                        )
+                                        .with_code(ErrorCode::InvalidAggregate),
                                     );
                                     None
                                 }
                             }
                             Choice::DiscreteRange(_) => {
                                 is_ok_so_far = false;
-                                diagnostics.error(
-                                    &choice.pos,
-                                    "Record aggregate choice must be a simple name",
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &choice.pos,
+                                        "Record aggregate choice must be a simple name",
+                                    )
+                                    .with_code(ErrorCode::InvalidAggregate),
                                 );
                                 None
                             }
@@ -957,8 +1039,20 @@ impl<'a> AnalyzeContext<'a> {
                                     })
                                     .collect();
 
-                                if remaining_types.len() > 1 {
-                                    let mut diag = Diagnostic::error(&choice.pos, format!("Other elements of record '{}' are not of the same type", record_type.designator()));
+                                if remaining_types.len() > 1 && self.standard >= VHDLStandard::V2019
+                                {
+                                    // The expression of others is analyzed for the type of each
+                                    // element since VHDL-2019
+                                    for elem in elems.iter() {
+                                        let typ = elem.type_mark().base();
+                                        if !associated.is_associated(&elem)
+                                            && !other_types.contains(&typ)
+                                        {
+                                            other_types.push(typ);
+                                        }
+                                    }
+                                } else if remaining_types.len() > 1 {
+                                    let mut diag = Diagnostic::error(&choice.pos, format!("Other elements of record '{}' are not of the same type, this requires {}", record_type.designator(), VHDLStandard::V2019)).with_code(ErrorCode::InvalidAggregate);
                                     for elem in elems.iter() {
                                         if !associated.is_associated(&elem) {
                                             if let Some(decl_pos) = elem.decl_pos() {
@@ -983,6 +1077,7 @@ impl<'a> AnalyzeContext<'a> {
                                             record_type.designator()
                                         ),
                                         )
+                                        .with_code(ErrorCode::InvalidAggregate)
                                         .opt_related(
                                             record_type.decl_pos(),
                                             format!(
@@ -1006,13 +1101,80 @@ impl<'a> AnalyzeContext<'a> {
                                 }
                             }
                         }
-                    } else {
-                        if let (Some(first), Some(last)) = (choices.first(), choices.last()) {
+                    } else if let (Some(first), Some(last)) = (choices.first(), choices.last()) {
+                        let full_choice_pos = first.pos.combine(&last.pos);
+                        let names: Vec<_> = choices
+                            .iter_mut()
+                            .map(|choice| {
+                                let pos = &choice.pos;
+                                match &mut choice.item {
+                                    Choice::Expression(choice_expr) => as_name_mut(choice_expr)
+                                        .and_then(as_simple_name_mut)
+                                        .map(|name| (pos, name)),
+                                    _ => None,
+                                }
+                            })
+                            .collect();
+
+                        if names.iter().all(|name| name.is_some()) {
+                            // (a | b => x) associates all the elements with the same expression
+                            let mut types = Vec::new();
+                            for (pos, simple_name) in names.into_iter().flatten() {
+                                if let Some(elem) = elems.lookup(&simple_name.item) {
+                                    simple_name.set_unique_reference(&elem);
+                                    associated.associate(&elem, pos, diagnostics);
+                                    types.push(elem);
+                                } else {
+                                    is_ok_so_far = false;
+                                    diagnostics.push(Diagnostic::no_declaration_within(
+                                        &record_type,
+                                        pos,
+                                        &simple_name.item,
+                                    ));
+                                }
+                            }
+
+                            let typ = types.first().map(|elem| elem.type_mark().base());
+                            if types
+                                .iter()
+                                .any(|elem| Some(elem.type_mark().base()) != typ)
+                            {
+                                let mut diag = Diagnostic::error(
+                                    &full_choice_pos,
+                                    format!(
+                                        "Elements of record '{}' associated with the same expression are not of the same type",
+                                        record_type.designator()
+                                    ),
+                                ).with_code(ErrorCode::InvalidAggregate);
+                                for elem in types.iter() {
+                                    if let Some(decl_pos) = elem.decl_pos() {
+                                        diag.add_related(
+                                            decl_pos,
+                                            format!(
+                                                "Element '{}' has {}",
+                                                elem.designator(),
+                                                elem.type_mark().describe()
+                                            ),
+                                        );
+                                    }
+                                }
+                                diagnostics.push(diag);
+                                None
+                            } else {
+                                typ
+                            }
+                        } else {
                             is_ok_so_far = false;
-                            let pos = first.pos.combine(&last.pos);
-                            diagnostics
-                                .error(&pos, "Record aggregate choice must be a simple name");
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &full_choice_pos,
+                                    "Record aggregate choice must be a simple name",
+                                )
+                                .with_code(ErrorCode::InvalidAggregate),
+                            );
+                            None
                         }
+                    } else {
                         None
                     };
 
@@ -1024,6 +1186,16 @@ impl<'a> AnalyzeContext<'a> {
                             &mut actual_expr.item,
                             diagnostics,
                         )?;
+                    } else if !other_types.is_empty() {
+                        for typ in other_types {
+                            self.expr_pos_with_ttyp(
+                                scope,
+                                typ.into(),
+                                &actual_expr.pos,
+                                &mut actual_expr.item,
+                                diagnostics,
+                            )?;
+                        }
                     } else {
                         self.expr_unknown_ttyp(scope, actual_expr, diagnostics)?;
                     }
@@ -1043,6 +1215,7 @@ impl<'a> AnalyzeContext<'a> {
                                     record_type.designator()
                                 ),
                             )
+                            .with_code(ErrorCode::InvalidAggregate)
                             .opt_related(
                                 record_type.decl_pos(),
                                 format!("Record '{}' defined here", record_type.designator()),
@@ -1065,6 +1238,7 @@ impl<'a> AnalyzeContext<'a> {
                                 elem.designator()
                             ),
                         )
+                        .with_code(ErrorCode::InvalidAggregate)
                         .opt_related(
                             elem.decl_pos(),
                             format!("Record element '{}' defined here", elem.designator()),
@@ -1091,6 +1265,7 @@ impl<'a> AnalyzeContext<'a> {
 
         let expr = match assoc {
             ElementAssociation::Named(ref mut choices, ref mut expr) => {
+                let num_choices = choices.len();
                 for choice in choices.iter_mut() {
                     match &mut choice.item {
                         Choice::Expression(index_expr) => {
@@ -1144,7 +1319,12 @@ impl<'a> AnalyzeContext<'a> {
                             }
                         }
                         Choice::Others => {
-                            // @TODO choice must be alone so cannot appear here
+                            if num_choices > 1 {
+                                diagnostics.push(Diagnostic::error(
+                                    &choice.pos,
+                                    "'others' must be the only choice of an element association",
+                                ).with_code(ErrorCode::InvalidAggregate));
+                            }
                             can_be_array = false;
                         }
                     }
@@ -1167,12 +1347,15 @@ impl<'a> AnalyzeContext<'a> {
                     ))?;
                 }
             } else {
-                diagnostics.error(
-                    &expr.pos,
-                    format!(
-                        "Expected sub-aggregate for target {}",
-                        array_type.describe()
-                    ),
+                diagnostics.push(
+                    Diagnostic::error(
+                        &expr.pos,
+                        format!(
+                            "Expected sub-aggregate for target {}",
+                            array_type.describe()
+                        ),
+                    )
+                    .with_code(ErrorCode::InvalidAggregate),
                 );
             }
         } else if can_be_array {
@@ -1207,7 +1390,8 @@ impl Diagnostic {
                 "ambiguous use of {}",
                 Designator::OperatorSymbol(op).describe()
             ),
-        );
+        )
+        .with_code(ErrorCode::Ambiguous);
         diag.add_subprogram_candidates("might be", candidates);
         diag
     }
@@ -1232,6 +1416,7 @@ impl<'a> RecordAssociations<'a> {
                         elem.designator()
                     ),
                 )
+                .with_code(ErrorCode::InvalidAggregate)
                 .related(prev_pos, "Previously associated here"),
             );
         }
@@ -1364,7 +1549,7 @@ mod test {
                 &test.snippet("new integer_vector'(0, 1)"),
                 &mut NoDiagnostics
             ),
-            Some(ExpressionType::Unambiguous(
+            Some(ExpressionType::Allocator(
                 test.lookup_type("integer_vector")
             ))
         );
@@ -1375,7 +1560,7 @@ mod test {
                 &test.snippet("new integer_vector(0 to 1)"),
                 &mut NoDiagnostics
             ),
-            Some(ExpressionType::Unambiguous(
+            Some(ExpressionType::Allocator(
                 test.lookup_type("integer_vector")
             ))
         );
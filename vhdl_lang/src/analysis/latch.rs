@@ -0,0 +1,370 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Signals of a combinational process that are not assigned in all branches
+//!
+//! A process with a sensitivity list is combinational unless it tests a clock edge with
+//! `rising_edge`, `falling_edge` or the `'event` attribute anywhere in its statements.
+//! Synthesis infers a latch for a signal that such a process assigns on some but not all
+//! paths through its if and case statements, so that the signal keeps its value on the
+//! other paths. An assignment before the branches such as a default value assigns the signal
+//! on all paths. Assigning an element or slice of a signal assigns the whole signal, the
+//! body of a loop is assumed to be executed and a signal associated with a signal parameter of
+//! mode out or inout of a procedure is assumed to be assigned by the call.
+//!
+//! The check is part of the synthesis lint so it is only done for the libraries that enable it.
+
+use super::analyze::*;
+use super::drivers::is_signal;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+struct LatchChecker<'a> {
+    arena: &'a Arena,
+}
+
+impl<'a> LatchChecker<'a> {
+    /// The signals assigned by the statements in order of their first assignment
+    fn assigned_signals(
+        &self,
+        statements: &[LabeledSequentialStatement],
+        signals: &mut Vec<EntRef<'a>>,
+    ) {
+        for statement in statements.iter() {
+            match statement.statement.item {
+                SequentialStatement::SignalAssignment(ref assign) => {
+                    self.add_target(&assign.target, signals)
+                }
+                SequentialStatement::ProcedureCall(ref pcall) => {
+                    for signal in self.procedure_assigns(&pcall.item) {
+                        add_signal(signal, signals);
+                    }
+                }
+                SequentialStatement::If(ref ifstmt) => {
+                    for conditional in ifstmt.conds.conditionals.iter() {
+                        self.assigned_signals(&conditional.item, signals);
+                    }
+                    if let Some(ref else_item) = ifstmt.conds.else_item {
+                        self.assigned_signals(else_item, signals);
+                    }
+                }
+                SequentialStatement::Case(ref case) => {
+                    for alternative in case.alternatives.iter() {
+                        self.assigned_signals(&alternative.item, signals);
+                    }
+                }
+                SequentialStatement::Loop(ref loop_stmt) => {
+                    self.assigned_signals(&loop_stmt.statements, signals)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn add_target(&self, target: &WithPos<Target>, signals: &mut Vec<EntRef<'a>>) {
+        match target.item {
+            Target::Name(ref name) => {
+                if let Some(signal) = self.signal_of(name) {
+                    add_signal(signal, signals);
+                }
+            }
+            Target::Aggregate(ref assocs) => {
+                for assoc in assocs.iter() {
+                    let expr = match assoc {
+                        ElementAssociation::Positional(expr) => expr,
+                        ElementAssociation::Named(_, expr) => expr,
+                    };
+                    if let Expression::Name(ref name) = expr.item {
+                        if let Some(signal) = self.signal_of(name) {
+                            add_signal(signal, signals);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The signal denoted by the longest prefix of a name
+    fn signal_of(&self, name: &Name) -> Option<EntRef<'a>> {
+        match name {
+            Name::Designator(designator) => designator
+                .reference
+                .get()
+                .map(|id| self.arena.get(id))
+                .filter(|ent| is_signal(ent)),
+            Name::Selected(prefix, suffix) => suffix
+                .item
+                .reference
+                .get()
+                .map(|id| self.arena.get(id))
+                .filter(|ent| is_signal(ent))
+                .or_else(|| self.signal_of(&prefix.item)),
+            Name::Slice(prefix, _) => self.signal_of(&prefix.item),
+            Name::CallOrIndexed(call) => self.signal_of(&call.name.item),
+            Name::SelectedAll(..) | Name::Attribute(..) | Name::External(..) => None,
+        }
+    }
+
+    /// The signals associated with the formal signal parameters of mode out or inout of the
+    /// called procedure. Nothing is assigned by a call that could not be resolved.
+    fn procedure_assigns(&self, call: &CallOrIndexed) -> Vec<EntRef<'a>> {
+        let Some(procedure) = call
+            .name
+            .item
+            .get_suffix_reference()
+            .and_then(|id| OverloadedEnt::from_any(self.arena.get(id)))
+        else {
+            return Vec::new();
+        };
+        let mut signals = Vec::new();
+        for (idx, param) in call.parameters.iter().enumerate() {
+            let formal = match param.formal {
+                Some(ref formal) => formal
+                    .item
+                    .get_formal_reference()
+                    .and_then(|id| InterfaceEnt::from_any(self.arena.get(id))),
+                None => procedure.formals().nth(idx),
+            };
+            if !formal.is_some_and(|formal| formal.is_out_or_inout_signal()) {
+                continue;
+            }
+            if let ActualPart::Expression(Expression::Name(ref name)) = param.actual.item {
+                if let Some(signal) = self.signal_of(name) {
+                    signals.push(signal);
+                }
+            }
+        }
+        signals
+    }
+
+    fn target_assigns(&self, target: &WithPos<Target>, signal: EntRef) -> bool {
+        let mut signals = Vec::new();
+        self.add_target(target, &mut signals);
+        signals.iter().any(|ent| ent.id() == signal.id())
+    }
+
+    /// Returns true if the statements assign the signal on every path. Otherwise the
+    /// positions of the innermost branches that do not assign it are added to `missing`.
+    fn assigns(
+        &self,
+        statements: &[LabeledSequentialStatement],
+        signal: EntRef,
+        missing: &mut Vec<(SrcPos, &'static str)>,
+    ) -> bool {
+        // The branches before an assignment of the signal are not reported
+        let mut branches = Vec::new();
+        for statement in statements.iter() {
+            if self.statement_assigns(statement, signal, &mut branches) {
+                return true;
+            }
+        }
+        missing.extend(branches);
+        false
+    }
+
+    fn statement_assigns(
+        &self,
+        statement: &LabeledSequentialStatement,
+        signal: EntRef,
+        missing: &mut Vec<(SrcPos, &'static str)>,
+    ) -> bool {
+        match statement.statement.item {
+            SequentialStatement::SignalAssignment(ref assign) => {
+                // A conditional assignment without an else branch does not always assign
+                !matches!(
+                    assign.rhs,
+                    AssignmentRightHand::Conditional(Conditionals {
+                        else_item: None,
+                        ..
+                    })
+                ) && self.target_assigns(&assign.target, signal)
+            }
+            SequentialStatement::ProcedureCall(ref pcall) => self
+                .procedure_assigns(&pcall.item)
+                .iter()
+                .any(|ent| ent.id() == signal.id()),
+            SequentialStatement::If(ref ifstmt) => {
+                let mut all = true;
+                for conditional in ifstmt.conds.conditionals.iter() {
+                    all &= self.branch_assigns(
+                        &conditional.item,
+                        &conditional.condition.pos,
+                        signal,
+                        missing,
+                    );
+                }
+                match ifstmt.conds.else_item {
+                    Some(ref else_item) => {
+                        let pos = else_item
+                            .first()
+                            .map(|statement| &statement.statement.pos)
+                            .unwrap_or(&statement.statement.pos);
+                        all &= self.branch_assigns(else_item, pos, signal, missing);
+                    }
+                    None => {
+                        missing.push((
+                            statement.statement.pos.clone(),
+                            "Not assigned when no condition is true",
+                        ));
+                        all = false;
+                    }
+                }
+                all
+            }
+            SequentialStatement::Case(ref case) => {
+                let mut all = true;
+                for alternative in case.alternatives.iter() {
+                    let pos = alternative
+                        .choices
+                        .first()
+                        .map(|choice| &choice.pos)
+                        .unwrap_or(&statement.statement.pos);
+                    all &= self.branch_assigns(&alternative.item, pos, signal, missing);
+                }
+                all
+            }
+            SequentialStatement::Loop(ref loop_stmt) => {
+                self.assigns(&loop_stmt.statements, signal, missing)
+            }
+            _ => false,
+        }
+    }
+
+    /// A branch that does not assign the signal anywhere is reported at `pos`
+    fn branch_assigns(
+        &self,
+        statements: &[LabeledSequentialStatement],
+        pos: &SrcPos,
+        signal: EntRef,
+        missing: &mut Vec<(SrcPos, &'static str)>,
+    ) -> bool {
+        let before = missing.len();
+        if self.assigns(statements, signal, missing) {
+            return true;
+        }
+        if missing.len() == before {
+            missing.push((pos.clone(), "Not assigned in this branch"));
+        }
+        false
+    }
+}
+
+fn add_signal<'a>(signal: EntRef<'a>, signals: &mut Vec<EntRef<'a>>) {
+    if !signals.iter().any(|ent| ent.id() == signal.id()) {
+        signals.push(signal);
+    }
+}
+
+/// True if the statements test a clock edge or contain a wait statement
+fn is_clocked(statements: &[LabeledSequentialStatement]) -> bool {
+    statements
+        .iter()
+        .any(|statement| match statement.statement.item {
+            SequentialStatement::Wait(..) => true,
+            SequentialStatement::If(ref ifstmt) => {
+                ifstmt.conds.conditionals.iter().any(|conditional| {
+                    has_clock_edge(&conditional.condition.item) || is_clocked(&conditional.item)
+                }) || ifstmt.conds.else_item.as_deref().is_some_and(is_clocked)
+            }
+            SequentialStatement::Case(ref case) => case
+                .alternatives
+                .iter()
+                .any(|alternative| is_clocked(&alternative.item)),
+            SequentialStatement::Loop(ref loop_stmt) => is_clocked(&loop_stmt.statements),
+            SequentialStatement::SignalAssignment(ref assign) => match assign.rhs {
+                AssignmentRightHand::Conditional(ref conds) => conds
+                    .conditionals
+                    .iter()
+                    .any(|conditional| has_clock_edge(&conditional.condition.item)),
+                _ => false,
+            },
+            _ => false,
+        })
+}
+
+/// True for a call of `rising_edge` or `falling_edge` or an `'event` attribute
+fn has_clock_edge(expr: &Expression) -> bool {
+    match expr {
+        Expression::Binary(_, left, right) => {
+            has_clock_edge(&left.item) || has_clock_edge(&right.item)
+        }
+        Expression::Unary(_, expr) => has_clock_edge(&expr.item),
+        Expression::Name(name) => match name.as_ref() {
+            Name::CallOrIndexed(call) => matches!(
+                call.name.item,
+                Name::Designator(ref designator) if matches!(
+                    designator.item,
+                    Designator::Identifier(ref sym)
+                        if ["rising_edge", "falling_edge"].iter().any(|edge| {
+                            sym.name_utf8().eq_ignore_ascii_case(edge)
+                        })
+                )
+            ),
+            Name::Attribute(attr) => {
+                matches!(
+                    attr.attr.item,
+                    AttributeDesignator::Signal(SignalAttribute::Event)
+                )
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+impl<'a> AnalyzeContext<'a> {
+    /// Warn about the signals of a combinational process that are not assigned in all
+    /// branches when the synthesis lint is enabled. The warning is at the label of the
+    /// process or at its first keyword.
+    pub fn check_latches(
+        &self,
+        label: Option<&SrcPos>,
+        statement_pos: &SrcPos,
+        process: &ProcessStatement,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.synthesis_lint
+            || process.sensitivity_list.is_none()
+            || is_clocked(&process.statements)
+        {
+            return;
+        }
+        let pos = label.cloned().unwrap_or_else(|| {
+            let keyword = if process.postponed {
+                "postponed"
+            } else {
+                "process"
+            };
+            let start = statement_pos.start();
+            let end = Position::new(start.line, start.character + keyword.len() as u32);
+            SrcPos::new(statement_pos.source.clone(), start.range_to(end))
+        });
+
+        let checker = LatchChecker { arena: self.arena };
+        let mut signals = Vec::new();
+        checker.assigned_signals(&process.statements, &mut signals);
+
+        for signal in signals {
+            let mut missing = Vec::new();
+            if checker.assigns(&process.statements, signal, &mut missing) {
+                continue;
+            }
+            let mut diagnostic = Diagnostic::warning(
+                &pos,
+                format!(
+                    "Signal '{}' may infer a latch since it is not assigned in all branches",
+                    signal.designator()
+                ),
+            )
+            .with_code(ErrorCode::InferredLatch);
+            for (branch_pos, message) in missing {
+                diagnostic.add_related(branch_pos, message);
+            }
+            diagnostics.push(diagnostic);
+        }
+    }
+}
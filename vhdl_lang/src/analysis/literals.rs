@@ -13,10 +13,12 @@ use crate::data::*;
 use crate::named_entity::*;
 
 impl<'a> AnalyzeContext<'a> {
-    /// Analyze a string literal or expanded bit-string literal for type-matching
+    /// Analyze a string literal or expanded bit-string literal for type-matching,
+    /// `literal_kind` is either "string literal" or "bit string literal"
     fn analyze_string_literal(
         &self,
         pos: &SrcPos,
+        literal_kind: &str,
         string_lit: Latin1String,
         target_base: TypeEnt,
         target_type: TypeEnt,
@@ -26,18 +28,24 @@ impl<'a> AnalyzeContext<'a> {
             for chr in string_lit.chars() {
                 let chr = Designator::Character(*chr);
                 if !literals.contains(&chr) {
-                    diagnostics.push(Diagnostic::error(
-                        pos,
-                        format!("{} does not define character {}", elem_type.describe(), chr),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pos,
+                            format!("{} does not define character {}", elem_type.describe(), chr),
+                        )
+                        .with_code(ErrorCode::TypeMismatch),
+                    );
                     break;
                 }
             }
         } else {
-            diagnostics.push(Diagnostic::error(
-                pos,
-                format!("string literal does not match {}", target_type.describe()),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    pos,
+                    format!("{literal_kind} does not match {}", target_type.describe()),
+                )
+                .with_code(ErrorCode::TypeMismatch),
+            );
         }
     }
 
@@ -58,46 +66,62 @@ impl<'a> AnalyzeContext<'a> {
                 AbstractLiteral::Integer(_) => {
                     if !self.can_be_target_type(self.universal_integer().into(), target_type.base())
                     {
-                        diagnostics.push(Diagnostic::error(
-                            pos,
-                            format!("integer literal does not match {}", target_type.describe()),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pos,
+                                format!(
+                                    "integer literal does not match {}",
+                                    target_type.describe()
+                                ),
+                            )
+                            .with_code(ErrorCode::TypeMismatch),
+                        );
                     }
                 }
                 AbstractLiteral::Real(_) => {
                     if !self.can_be_target_type(self.universal_real().into(), target_type.base()) {
-                        diagnostics.push(Diagnostic::error(
-                            pos,
-                            format!("real literal does not match {}", target_type.describe()),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pos,
+                                format!("real literal does not match {}", target_type.describe()),
+                            )
+                            .with_code(ErrorCode::TypeMismatch),
+                        );
                     }
                 }
             },
             Literal::Character(char) => match target_base.kind() {
                 Type::Enum(literals) => {
                     if !literals.contains(&Designator::Character(*char)) {
-                        diagnostics.push(Diagnostic::error(
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pos,
+                                format!(
+                                    "character literal does not match {}",
+                                    target_type.describe()
+                                ),
+                            )
+                            .with_code(ErrorCode::TypeMismatch),
+                        );
+                    }
+                }
+                _ => {
+                    diagnostics.push(
+                        Diagnostic::error(
                             pos,
                             format!(
                                 "character literal does not match {}",
                                 target_type.describe()
                             ),
-                        ));
-                    }
-                }
-                _ => {
-                    diagnostics.push(Diagnostic::error(
-                        pos,
-                        format!(
-                            "character literal does not match {}",
-                            target_type.describe()
-                        ),
-                    ));
+                        )
+                        .with_code(ErrorCode::TypeMismatch),
+                    );
                 }
             },
             Literal::String(string_lit) => {
                 self.analyze_string_literal(
                     pos,
+                    "string literal",
                     string_lit.to_owned(),
                     target_base,
                     target_type,
@@ -108,6 +132,7 @@ impl<'a> AnalyzeContext<'a> {
                 match bit_string_to_string(bit_string) {
                     Ok(string_lit) => self.analyze_string_literal(
                         pos,
+                        "bit string literal",
                         string_lit,
                         target_base,
                         target_type,
@@ -115,26 +140,38 @@ impl<'a> AnalyzeContext<'a> {
                     ),
                     Err(err) => {
                         match err {
-                            BitStringConversionError::IllegalDecimalCharacter(rel_pos) => {
-                                diagnostics.error(
+                            BitStringConversionError::IllegalDecimalCharacter(rel_pos)
+                            | BitStringConversionError::IllegalDigit(rel_pos) => diagnostics.push(
+                                Diagnostic::error(
                                     pos,
                                     format!(
-                                        "Illegal digit '{}' for base 10",
+                                        "Illegal digit '{}' for base {}",
                                         bit_string.value.bytes[rel_pos] as char,
+                                        bit_string.base.radix(),
                                     ),
                                 )
-                            }
+                                .with_code(ErrorCode::StaticEvaluation),
+                            ),
                             BitStringConversionError::IllegalTruncate(_, _) => {
-                                diagnostics.error(
-                                    pos,
-                                    format!(
-                                        "Truncating vector to length {} would lose information",
-                                        bit_string.length.unwrap() // Safe as this error can only happen when there is a length
-                                    ),
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        pos,
+                                        format!(
+                                            "Truncating vector to length {} would lose information",
+                                            bit_string.length.unwrap() // Safe as this error can only happen when there is a length
+                                        ),
+                                    )
+                                    .with_code(ErrorCode::LengthMismatch),
                                 );
                             }
                             BitStringConversionError::EmptySignedExpansion => {
-                                diagnostics.error(pos, "Cannot expand an empty signed bit string");
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        pos,
+                                        "Cannot expand an empty signed bit string",
+                                    )
+                                    .with_code(ErrorCode::StaticEvaluation),
+                                );
                             }
                         }
                     }
@@ -158,10 +195,13 @@ impl<'a> AnalyzeContext<'a> {
             }
             Literal::Null => {
                 if !matches!(target_base.kind(), Type::Access(_)) {
-                    diagnostics.push(Diagnostic::error(
-                        pos,
-                        format!("null literal does not match {}", target_base.describe()),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pos,
+                            format!("null literal does not match {}", target_base.describe()),
+                        )
+                        .with_code(ErrorCode::TypeMismatch),
+                    );
                 }
             }
         };
@@ -185,13 +225,15 @@ impl<'a> AnalyzeContext<'a> {
                     Err(Diagnostic::error(
                         &unit.item.pos,
                         format!("{} is not a physical unit", unit_ent.describe()),
-                    ))
+                    )
+                    .with_code(ErrorCode::MismatchedKinds))
                 }
             }
             NamedEntities::Overloaded(_) => Err(Diagnostic::error(
                 &unit.item.pos,
                 "Overloaded name may not be physical unit",
-            )),
+            )
+            .with_code(ErrorCode::MismatchedKinds)),
         }
     }
 }
@@ -15,6 +15,7 @@ use super::scope::*;
 use crate::ast::*;
 use crate::data::*;
 use crate::named_entity::*;
+use crate::VHDLStandard;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ObjectBase<'a> {
@@ -69,6 +70,15 @@ impl<'a> ObjectBase<'a> {
         }
     }
 
+    pub fn is_guarded(&self) -> bool {
+        match self {
+            ObjectBase::Object(obj) => obj.kind().is_guarded(),
+            ObjectBase::ObjectAlias(obj, _) => obj.kind().is_guarded(),
+            ObjectBase::DeferredConstant(_) => false,
+            ObjectBase::ExternalName(_) => false,
+        }
+    }
+
     pub fn is_port(&self) -> bool {
         match self {
             ObjectBase::Object(obj) => obj.kind().is_port(),
@@ -141,6 +151,7 @@ impl<'a> ResolvedName<'a> {
             AnyEntKind::ObjectAlias {
                 base_object,
                 type_mark,
+                ..
             } => ResolvedName::ObjectName(ObjectName {
                 base: ObjectBase::ObjectAlias(*base_object, ent),
                 type_mark: Some(type_mark.to_owned()),
@@ -194,6 +205,7 @@ impl<'a> ResolvedName<'a> {
             AnyEntKind::ObjectAlias {
                 base_object,
                 type_mark,
+                ..
             } => ResolvedName::ObjectName(ObjectName {
                 base: ObjectBase::ObjectAlias(*base_object, ent),
                 type_mark: Some(type_mark.to_owned()),
@@ -325,13 +337,16 @@ impl<'a> ResolvedName<'a> {
             }
         }
 
-        diagnostics.error(
-            prefix_pos,
-            format!(
-                "Expected signal prefix for '{} attribute, got {}",
-                attr.attr,
-                self.describe()
-            ),
+        diagnostics.push(
+            Diagnostic::error(
+                prefix_pos,
+                format!(
+                    "Expected signal prefix for '{} attribute, got {}",
+                    attr.attr,
+                    self.describe()
+                ),
+            )
+            .with_code(ErrorCode::IllegalAttribute),
         );
         Err(EvalError::Unknown)
     }
@@ -439,6 +454,29 @@ fn could_be_indexed_name(assocs: &[AssociationElement]) -> bool {
         .all(|assoc| assoc.formal.is_none() && !matches!(assoc.actual.item, ActualPart::Open))
 }
 
+/// LRM 9.3.6 Why the operand type of a conversion is not closely related to the target type
+pub(crate) fn not_closely_related_reason(typ: BaseType, ctyp: BaseType) -> &'static str {
+    match (typ.kind(), ctyp.kind()) {
+        (
+            Type::Array {
+                indexes: target_indexes,
+                ..
+            },
+            Type::Array {
+                indexes: operand_indexes,
+                ..
+            },
+        ) => {
+            if target_indexes.len() != operand_indexes.len() {
+                ", the arrays have a different number of dimensions"
+            } else {
+                ", the element types are not closely related"
+            }
+        }
+        _ => ", only abstract numeric types and arrays with closely related elements can be converted",
+    }
+}
+
 pub fn as_type_conversion(assocs: &mut [AssociationElement]) -> Option<(&SrcPos, &mut Expression)> {
     if assocs.len() == 1 && could_be_indexed_name(assocs) {
         if let ActualPart::Expression(ref mut expr) = assocs[0].actual.item {
@@ -461,7 +499,8 @@ impl<'a> AnalyzeContext<'a> {
                 Err(Diagnostic::error(
                     pos,
                     format!("{} cannot be used in an expression", name.describe_type()),
-                ))
+                )
+                .with_code(ErrorCode::MismatchedKinds))
             }
             ResolvedName::Final(ent) => match ent.actual_kind() {
                 AnyEntKind::LoopParameter(typ) => {
@@ -475,7 +514,8 @@ impl<'a> AnalyzeContext<'a> {
                 _ => Err(Diagnostic::error(
                     pos,
                     format!("{} cannot be used in an expression", name.describe_type()),
-                )),
+                )
+                .with_code(ErrorCode::MismatchedKinds)),
             },
             ResolvedName::Overloaded(des, overloaded) => {
                 if let Some(disamb) = self.disambiguate_no_actuals(&des, None, &overloaded)? {
@@ -509,7 +549,8 @@ impl<'a> AnalyzeContext<'a> {
                 Err(Diagnostic::error(
                     pos,
                     format!("{} cannot be used in an expression", name.describe_type()),
-                ))
+                )
+                .with_code(ErrorCode::MismatchedKinds))
             }
             ResolvedName::Final(ent) => match ent.actual_kind() {
                 AnyEntKind::LoopParameter(typ) => Ok(typ.map(|typ| typ.into())),
@@ -519,7 +560,8 @@ impl<'a> AnalyzeContext<'a> {
                 _ => Err(Diagnostic::error(
                     pos,
                     format!("{} cannot be used in an expression", name.describe_type()),
-                )),
+                )
+                .with_code(ErrorCode::MismatchedKinds)),
             },
             ResolvedName::Overloaded(des, overloaded) => {
                 if let Some(disamb) = self.disambiguate_no_actuals(des, Some(ttyp), overloaded)? {
@@ -610,6 +652,7 @@ impl<'a> AnalyzeContext<'a> {
                         expr_pos,
                         format!("{} cannot be used as a discrete range", typ.describe()),
                     )
+                    .with_code(ErrorCode::InvalidRange)
                     .into())
                 };
             }
@@ -652,13 +695,16 @@ impl<'a> AnalyzeContext<'a> {
                             self.drange_unknown_type(scope, drange, diagnostics)?;
                         }
                     } else {
-                        diagnostics.error(
-                            name_pos,
-                            format!(
-                                "Cannot slice {}-dimensional {}",
-                                indexes.len(),
-                                typ.describe()
-                            ),
+                        diagnostics.push(
+                            Diagnostic::error(
+                                name_pos,
+                                format!(
+                                    "Cannot slice {}-dimensional {}",
+                                    indexes.len(),
+                                    typ.describe()
+                                ),
+                            )
+                            .with_code(ErrorCode::DimensionMismatch),
                         )
                     }
                 }
@@ -741,7 +787,10 @@ impl<'a> AnalyzeContext<'a> {
             {
                 idx as usize
             } else {
-                diagnostics.error(&expr.pos, "Expected an integer literal");
+                diagnostics.push(
+                    Diagnostic::error(&expr.pos, "Expected an integer literal")
+                        .with_code(ErrorCode::IllegalAttribute),
+                );
                 return Err(EvalError::Unknown);
             }
         } else {
@@ -759,7 +808,7 @@ impl<'a> AnalyzeContext<'a> {
             if let Some(expr) = expr {
                 let ndims = indexes.len();
                 let dimensions = plural("dimension", "dimensions", ndims);
-                diagnostics.error(&expr.pos, format!("Index {idx} out of range for array with {ndims} {dimensions}, expected 1 to {ndims}"));
+                diagnostics.push(Diagnostic::error(&expr.pos, format!("Index {idx} out of range for array with {ndims} {dimensions}, expected 1 to {ndims}")).with_code(ErrorCode::DimensionMismatch));
             }
             Err(EvalError::Unknown)
         }
@@ -974,25 +1023,34 @@ impl<'a> AnalyzeContext<'a> {
                         sym.set_unique_reference(attr.into());
                         Ok(AttrResolveResult::Value(attr.typ().base()))
                     } else {
-                        diagnostics.error(
-                            &attr.attr.pos,
-                            format!("Unknown attribute '{}", attr.attr.item),
+                        diagnostics.push(
+                            Diagnostic::error(
+                                &attr.attr.pos,
+                                format!("Unknown attribute '{}", attr.attr.item),
+                            )
+                            .with_code(ErrorCode::IllegalAttribute),
                         );
                         Err(EvalError::Unknown)
                     }
                 } else {
-                    diagnostics.error(
-                        name_pos,
-                        format!(
-                            "{} may not be the prefix of a user defined attribute",
-                            prefix.describe()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            name_pos,
+                            format!(
+                                "{} may not be the prefix of a user defined attribute",
+                                prefix.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::IllegalAttribute),
                     );
                     Err(EvalError::Unknown)
                 }
             }
             AttributeDesignator::Range(_) => {
-                diagnostics.error(name_pos, "Range cannot be used as an expression");
+                diagnostics.push(
+                    Diagnostic::error(name_pos, "Range cannot be used as an expression")
+                        .with_code(ErrorCode::InvalidRange),
+                );
                 Err(EvalError::Unknown)
             }
             AttributeDesignator::Type(attr) => self
@@ -1018,13 +1076,16 @@ impl<'a> AnalyzeContext<'a> {
     ) -> EvalResult<TypeEnt<'a>> {
         // all type attribute suffixes require that the prefix be an object type
         let Some(obj) = prefix.as_object_name() else {
-            diagnostics.error(
-                pos,
-                format!(
-                    "The {} attribute can only be used on objects, not {}",
-                    suffix,
-                    prefix.describe()
-                ),
+            diagnostics.push(
+                Diagnostic::error(
+                    pos,
+                    format!(
+                        "The {} attribute can only be used on objects, not {}",
+                        suffix,
+                        prefix.describe()
+                    ),
+                )
+                .with_code(ErrorCode::IllegalAttribute),
             );
             return Err(EvalError::Unknown);
         };
@@ -1034,9 +1095,12 @@ impl<'a> AnalyzeContext<'a> {
                 if let Some((elem_type, _)) = obj.type_mark().array_type() {
                     Ok(elem_type)
                 } else {
-                    diagnostics.error(
-                        pos,
-                        "The element attribute can only be used for array types",
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pos,
+                            "The element attribute can only be used for array types",
+                        )
+                        .with_code(ErrorCode::IllegalAttribute),
                     );
                     Err(EvalError::Unknown)
                 }
@@ -1074,8 +1138,9 @@ impl<'a> AnalyzeContext<'a> {
                         designator.set_unique_reference(ent);
 
                         catch_diagnostic(
-                            ResolvedName::from_scope_not_overloaded(ent)
-                                .map_err(|e| Diagnostic::error(name_pos, e)),
+                            ResolvedName::from_scope_not_overloaded(ent).map_err(|e| {
+                                Diagnostic::error(name_pos, e).with_code(ErrorCode::MismatchedKinds)
+                            }),
                             diagnostics,
                         )?
                     }
@@ -1133,9 +1198,12 @@ impl<'a> AnalyzeContext<'a> {
                                 resolved =
                                     ResolvedName::Expression(DisambiguatedType::Ambiguous(types));
                             } else {
-                                diagnostics.error(
-                                    &prefix.pos,
-                                    "Procedure calls are not valid in names and expressions",
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &prefix.pos,
+                                        "Procedure calls are not valid in names and expressions",
+                                    )
+                                    .with_code(ErrorCode::InvalidCall),
                                 );
                                 return Err(EvalError::Unknown);
                             }
@@ -1147,9 +1215,12 @@ impl<'a> AnalyzeContext<'a> {
                                 resolved =
                                     ResolvedName::Expression(DisambiguatedType::Unambiguous(typ));
                             } else {
-                                diagnostics.error(
-                                    &prefix.pos,
-                                    "Procedure calls are not valid in names and expressions",
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &prefix.pos,
+                                        "Procedure calls are not valid in names and expressions",
+                                    )
+                                    .with_code(ErrorCode::InvalidCall),
                                 );
                                 return Err(EvalError::Unknown);
                             }
@@ -1207,9 +1278,12 @@ impl<'a> AnalyzeContext<'a> {
                                 resolved =
                                     ResolvedName::Expression(DisambiguatedType::Ambiguous(types));
                             } else {
-                                diagnostics.error(
-                                    &prefix.pos,
-                                    "Procedure calls are not valid in names and expressions",
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &prefix.pos,
+                                        "Procedure calls are not valid in names and expressions",
+                                    )
+                                    .with_code(ErrorCode::InvalidCall),
                                 );
                                 return Err(EvalError::Unknown);
                             }
@@ -1221,9 +1295,12 @@ impl<'a> AnalyzeContext<'a> {
                                     DisambiguatedType::Unambiguous(return_type),
                                 );
                             } else {
-                                diagnostics.error(
-                                    &prefix.pos,
-                                    "Procedure calls are not valid in names and expressions",
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &prefix.pos,
+                                        "Procedure calls are not valid in names and expressions",
+                                    )
+                                    .with_code(ErrorCode::InvalidCall),
                                 );
                                 return Err(EvalError::Unknown);
                             }
@@ -1337,8 +1414,12 @@ impl<'a> AnalyzeContext<'a> {
                             designator.set_reference(&name);
 
                             catch_diagnostic(
-                                ResolvedName::from_design_not_overloaded(named_entity)
-                                    .map_err(|e| Diagnostic::error(&designator.pos, e)),
+                                ResolvedName::from_design_not_overloaded(named_entity).map_err(
+                                    |e| {
+                                        Diagnostic::error(&designator.pos, e)
+                                            .with_code(ErrorCode::MismatchedKinds)
+                                    },
+                                ),
                                 diagnostics,
                             )?
                         }
@@ -1397,7 +1478,10 @@ impl<'a> AnalyzeContext<'a> {
             | ResolvedName::Overloaded { .. }
             | ResolvedName::Expression(_)
             | ResolvedName::Final(_) => {
-                diagnostics.error(name_pos, format!("{} {}", resolved.describe(), err_msg));
+                diagnostics.push(
+                    Diagnostic::error(name_pos, format!("{} {}", resolved.describe(), err_msg))
+                        .with_code(ErrorCode::MismatchedKinds),
+                );
                 Err(EvalError::Unknown)
             }
         }
@@ -1419,9 +1503,12 @@ impl<'a> AnalyzeContext<'a> {
             | ResolvedName::Overloaded { .. }
             | ResolvedName::Expression(_)
             | ResolvedName::Final(_) => {
-                diagnostics.error(
-                    name_pos,
-                    format!("Expected type name, got {}", resolved.describe()),
+                diagnostics.push(
+                    Diagnostic::error(
+                        name_pos,
+                        format!("Expected type name, got {}", resolved.describe()),
+                    )
+                    .with_code(ErrorCode::MismatchedKinds),
                 );
                 Err(EvalError::Unknown)
             }
@@ -1440,25 +1527,33 @@ impl<'a> AnalyzeContext<'a> {
             match types {
                 ExpressionType::Unambiguous(ctyp) => {
                     if !typ.base().is_closely_related(ctyp.base()) {
-                        diagnostics.error(
-                            pos,
-                            format!(
-                                "{} cannot be converted to {}",
-                                ctyp.describe(),
-                                typ.describe()
-                            ),
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pos,
+                                format!(
+                                    "{} cannot be converted to {}{}",
+                                    ctyp.describe(),
+                                    typ.describe(),
+                                    not_closely_related_reason(typ.base(), ctyp.base())
+                                ),
+                            )
+                            .with_code(ErrorCode::InvalidConversion),
                         )
                     }
                 }
                 ExpressionType::String
                 | ExpressionType::Ambiguous(_)
                 | ExpressionType::Null
-                | ExpressionType::Aggregate => diagnostics.error(
-                    pos,
-                    format!(
-                        "{} cannot be the argument of type conversion",
-                        types.describe()
-                    ),
+                | ExpressionType::Aggregate
+                | ExpressionType::Allocator(_) => diagnostics.push(
+                    Diagnostic::error(
+                        pos,
+                        format!(
+                            "{} cannot be the argument of type conversion",
+                            types.describe()
+                        ),
+                    )
+                    .with_code(ErrorCode::MismatchedKinds),
                 ),
             }
         }
@@ -1475,8 +1570,14 @@ impl<'a> AnalyzeContext<'a> {
     ) -> EvalResult<DisambiguatedType<'a>> {
         let resolved =
             self.name_resolve_with_suffixes(scope, expr_pos, name, None, false, diagnostics)?;
+        self.check_object_read(expr_pos, &resolved, diagnostics);
         match self.name_to_type(expr_pos, name.suffix_reference_mut(), resolved) {
-            Ok(Some(typ)) => Ok(typ),
+            Ok(Some(typ)) => {
+                if let DisambiguatedType::Unambiguous(typ) = typ {
+                    self.record_expression_type(expr_pos, typ);
+                }
+                Ok(typ)
+            }
             Ok(None) => Err(EvalError::Unknown),
             Err(diag) => {
                 diagnostics.push(diag);
@@ -1494,22 +1595,40 @@ impl<'a> AnalyzeContext<'a> {
         ttyp: TypeEnt<'a>,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
-        if let Some(resolved) = as_fatal(self.name_resolve_with_suffixes(
+        if let Some(resolved) = self.name_with_ttyp(scope, expr_pos, name, ttyp, diagnostics)? {
+            self.check_object_read(expr_pos, &resolved, diagnostics);
+        }
+        Ok(())
+    }
+
+    /// Analyze a name that must be unambiguous without reading the object it denotes,
+    /// such as the actual of an output
+    pub fn name_with_ttyp(
+        &self,
+        scope: &Scope<'a>,
+        expr_pos: &SrcPos,
+        name: &mut Name,
+        ttyp: TypeEnt<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<ResolvedName<'a>>> {
+        let resolved = as_fatal(self.name_resolve_with_suffixes(
             scope,
             expr_pos,
             name,
             Some(ttyp),
             false,
             diagnostics,
-        ))? {
+        ))?;
+        if let Some(ref resolved) = resolved {
             // @TODO target_type already used above, functions could probably be simplified
             match self.name_to_unambiguous_type(
                 expr_pos,
-                &resolved,
+                resolved,
                 ttyp,
                 name.suffix_reference_mut(),
             ) {
                 Ok(Some(type_mark)) => {
+                    self.record_expression_type(expr_pos, type_mark);
                     if !self.can_be_target_type(type_mark, ttyp.base()) {
                         diagnostics.push(Diagnostic::type_mismatch(
                             expr_pos,
@@ -1524,7 +1643,40 @@ impl<'a> AnalyzeContext<'a> {
                 }
             }
         }
-        Ok(())
+        Ok(resolved)
+    }
+
+    /// Check that an object whose value is read in an expression may be read.
+    /// Ports of mode linkage may never be read and ports of mode out only since VHDL-2008.
+    fn check_object_read(
+        &self,
+        pos: &SrcPos,
+        resolved: &ResolvedName<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let ResolvedName::ObjectName(oname) = resolved else {
+            return;
+        };
+        if !oname.base.is_port() {
+            return;
+        }
+        match oname.base.mode() {
+            Some(Mode::Linkage) => diagnostics.push(
+                Diagnostic::error(
+                    pos,
+                    format!("{} may not be read", oname.base.describe_class()),
+                )
+                .with_code(ErrorCode::ModeViolation),
+            ),
+            Some(Mode::Out) if self.standard < VHDLStandard::V2008 => diagnostics.push(
+                Diagnostic::error(
+                    pos,
+                    format!("Reading {} requires VHDL-2008", oname.base.describe_class()),
+                )
+                .with_code(ErrorCode::ModeViolation),
+            ),
+            _ => {}
+        }
     }
 
     /// Analyze an indexed name where the prefix entity is already known
@@ -1571,6 +1723,7 @@ impl<'a> AnalyzeContext<'a> {
                 suffix_pos,
                 format!("{} cannot be indexed", type_mark.describe()),
             )
+            .with_code(ErrorCode::MismatchedKinds)
             .into())
         }
     }
@@ -1615,6 +1768,7 @@ impl<'a> AnalyzeContext<'a> {
                             prefix.describe()
                         ),
                     )
+                    .with_code(ErrorCode::Internal)
                 })?;
 
                 let named = design.selected(prefix_pos, suffix)?;
@@ -1660,6 +1814,11 @@ impl Declaration {
             Declaration::Use(_) => "use",
             Declaration::Package(_) => "package instantiation",
             Declaration::Configuration(_) => "configuration",
+            Declaration::Disconnection(_) => "disconnection specification",
+            Declaration::Psl(PslDeclaration::DefaultClock(_)) => "default clock",
+            Declaration::Psl(PslDeclaration::Property(_)) => "property",
+            Declaration::Psl(PslDeclaration::Sequence(_)) => "sequence",
+            Declaration::Error(_) => "invalid declaration",
         }
     }
 }
@@ -1689,6 +1848,7 @@ impl Diagnostic {
         };
 
         Diagnostic::error(prefix_pos, format!("{name_desc} cannot be {suffix_desc}"))
+            .with_code(ErrorCode::MismatchedKinds)
     }
 
     fn cannot_be_prefix_of_attribute(
@@ -1699,11 +1859,12 @@ impl Diagnostic {
         Diagnostic::error(
             prefix_pos,
             format!(
-                "{} cannot be the the prefix of '{} attribute",
+                "{} cannot be the prefix of '{} attribute",
                 resolved.describe_type(),
                 attr.attr
             ),
         )
+        .with_code(ErrorCode::IllegalAttribute)
     }
 
     fn dimension_mismatch(
@@ -1712,7 +1873,8 @@ impl Diagnostic {
         got: usize,
         expected: usize,
     ) -> Diagnostic {
-        let mut diag = Diagnostic::error(pos, "Number of indexes does not match array dimension");
+        let mut diag = Diagnostic::error(pos, "Number of indexes does not match array dimension")
+            .with_code(ErrorCode::DimensionMismatch);
 
         if let Some(decl_pos) = base_type.decl_pos() {
             diag.add_related(
@@ -1734,6 +1896,7 @@ impl Diagnostic {
     /// An internal logic error that we want to show to the user to get bug reports
     fn unreachable(pos: &SrcPos, expected: &str) -> Diagnostic {
         Diagnostic::warning(pos, format!("Internal error, unreachable code {expected}"))
+            .with_code(ErrorCode::Internal)
     }
 
     pub fn ambiguous_call<'a>(
@@ -1743,7 +1906,8 @@ impl Diagnostic {
         let mut diag = Diagnostic::error(
             &call_name.pos,
             format!("Ambiguous call to {}", call_name.item.describe()),
-        );
+        )
+        .with_code(ErrorCode::Ambiguous);
         diag.add_subprogram_candidates("Might be", candidates);
         diag
     }
@@ -1751,9 +1915,12 @@ impl Diagnostic {
 
 fn check_no_attr_argument(suffix: &AttributeSuffix, diagnostics: &mut dyn DiagnosticHandler) {
     if let Some(ref expr) = suffix.expr {
-        diagnostics.error(
-            &expr.pos,
-            format!("'{} attribute does not take an argument", suffix.attr),
+        diagnostics.push(
+            Diagnostic::error(
+                &expr.pos,
+                format!("'{} attribute does not take an argument", suffix.attr),
+            )
+            .with_code(ErrorCode::IllegalAttribute),
         )
     }
 }
@@ -1764,9 +1931,12 @@ fn check_no_sattr_argument(
     diagnostics: &mut dyn DiagnosticHandler,
 ) {
     if let Some(ref expr) = expr {
-        diagnostics.error(
-            &expr.pos,
-            format!("'{attr} attribute does not take an argument"),
+        diagnostics.push(
+            Diagnostic::error(
+                &expr.pos,
+                format!("'{attr} attribute does not take an argument"),
+            )
+            .with_code(ErrorCode::IllegalAttribute),
         )
     }
 }
@@ -1779,9 +1949,12 @@ fn check_single_argument<'a>(
     if let Some(ref mut expr) = suffix.expr {
         Some(expr)
     } else {
-        diagnostics.error(
-            pos,
-            format!("'{} attribute requires a single argument", suffix.attr),
+        diagnostics.push(
+            Diagnostic::error(
+                pos,
+                format!("'{} attribute requires a single argument", suffix.attr),
+            )
+            .with_code(ErrorCode::IllegalAttribute),
         );
         None
     }
@@ -2665,6 +2838,77 @@ variable thevar : integer;
         )
     }
 
+    #[test]
+    fn event_attribute() {
+        let test = TestSetup::new();
+        test.declarative_part(
+            "
+signal thesig : bit;
+variable thevar : bit;
+        ",
+        );
+
+        let code = test.snippet("thesig'event");
+        assert_eq!(
+            test.name_resolve(&code, None, &mut NoDiagnostics),
+            Ok(ResolvedName::Expression(DisambiguatedType::Unambiguous(
+                test.ctx().boolean()
+            )))
+        );
+
+        let code = test.snippet("thevar'event");
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            test.name_resolve(&code, None, &mut diagnostics),
+            Err(EvalError::Unknown)
+        );
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("thevar"),
+                "Expected signal prefix for 'event attribute, got variable 'thevar'",
+            )],
+        )
+    }
+
+    #[test]
+    fn attribute_with_wrong_prefix_class() {
+        let test = TestSetup::new();
+        test.declarative_part(
+            "
+variable thevar : integer;
+        ",
+        );
+
+        let code = test.snippet("thevar'length");
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            test.name_resolve(&code, None, &mut diagnostics),
+            Err(EvalError::Unknown)
+        );
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("thevar'length"),
+                "variable 'thevar' of integer type 'INTEGER' cannot be the prefix of 'length attribute",
+            )],
+        );
+
+        let code = test.snippet("bit_vector'image(\"01\")");
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            test.name_resolve(&code, None, &mut diagnostics),
+            Err(EvalError::Unknown)
+        );
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("bit_vector'image(\"01\")"),
+                "array type 'BIT_VECTOR' cannot be the prefix of 'image attribute",
+            )],
+        );
+    }
+
     #[test]
     fn signal_attributes() {
         let test = TestSetup::new();
@@ -2884,7 +3128,8 @@ signal thesig : integer;
             diagnostics,
             vec![Diagnostic::error(
                 code.s1("'a'"),
-                "type 'CHARACTER' cannot be converted to integer type 'INTEGER'",
+                "type 'CHARACTER' cannot be converted to integer type 'INTEGER', \
+                 only abstract numeric types and arrays with closely related elements can be converted",
             )],
         );
 
@@ -2901,7 +3146,8 @@ signal thesig : integer;
             diagnostics,
             vec![Diagnostic::error(
                 code.s1("false"),
-                "type 'BOOLEAN' cannot be converted to real type 'REAL'",
+                "type 'BOOLEAN' cannot be converted to real type 'REAL', \
+                 only abstract numeric types and arrays with closely related elements can be converted",
             )],
         );
     }
@@ -2945,7 +3191,8 @@ type character_vector_2d is array (natural range 0 to 1, natural range 0 to 2) o
             diagnostics,
             vec![Diagnostic::error(
                 code.s1("string'(\"01\")"),
-                "array type 'STRING' cannot be converted to array type 'character_vector_2d'",
+                "array type 'STRING' cannot be converted to array type 'character_vector_2d', \
+                 the arrays have a different number of dimensions",
             )],
         );
 
@@ -2962,7 +3209,8 @@ type character_vector_2d is array (natural range 0 to 1, natural range 0 to 2) o
             diagnostics,
             vec![Diagnostic::error(
                 code.s1("string'(\"01\")"),
-                "array type 'STRING' cannot be converted to array type 'INTEGER_VECTOR'",
+                "array type 'STRING' cannot be converted to array type 'INTEGER_VECTOR', \
+                 the element types are not closely related",
             )],
         );
     }
@@ -5,6 +5,7 @@
 // Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
 
 use fnv::FnvHashSet;
+use itertools::Itertools;
 
 use super::analyze::*;
 use super::expression::ExpressionType;
@@ -32,7 +33,7 @@ pub enum SubprogramKind<'a> {
 }
 
 // The reason a subprogram was rejected as a candidate of a call
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 enum Rejection<'a> {
     // The return type of the subprogram is not correct
     ReturnType,
@@ -40,8 +41,56 @@ enum Rejection<'a> {
     // The subprogram is a procedure but we expected a function
     Procedure,
 
+    // The subprogram is a function but we expected a procedure
+    Function,
+
     // The amount of actuals or named actuals do not match formals
     MissingFormals(Vec<InterfaceEnt<'a>>),
+
+    // The actuals could not be associated with the formals
+    Association(String),
+
+    // The one-based index of an actual whose type does not match the formal
+    ArgumentType {
+        idx: usize,
+        formal: TypeEnt<'a>,
+        actual: Option<TypeEnt<'a>>,
+    },
+}
+
+impl<'a> Rejection<'a> {
+    fn reason(&self) -> String {
+        match self {
+            Rejection::ReturnType => "return type does not match".to_owned(),
+            Rejection::Procedure => "procedure cannot be called as a function".to_owned(),
+            Rejection::Function => "function cannot be called as a procedure".to_owned(),
+            Rejection::MissingFormals(missing) => format!(
+                "missing association of {}",
+                missing.iter().map(|formal| formal.describe()).join(", ")
+            ),
+            Rejection::Association(message) => {
+                let mut chars = message.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_lowercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            Rejection::ArgumentType {
+                idx,
+                formal,
+                actual,
+            } => {
+                let mut reason = format!(
+                    "type mismatch of argument {idx}, expected {}",
+                    formal.describe()
+                );
+                if let Some(actual) = actual {
+                    reason.push_str(&format!(" but got {}", actual.describe()));
+                }
+                reason
+            }
+        }
+    }
 }
 
 struct Candidate<'a> {
@@ -99,7 +148,8 @@ impl<'a> Candidates<'a> {
                         return Err(Diagnostic::error(
                             name,
                             format!("'{}' does not match {}", name, ttyp.describe()),
-                        ));
+                        )
+                        .with_code(ErrorCode::TypeMismatch));
                     }
                 }
             }
@@ -111,7 +161,8 @@ impl<'a> Candidates<'a> {
                 "Could not resolve"
             };
 
-            let mut diag = Diagnostic::error(name, format!("{err_prefix} '{name}'"));
+            let mut diag = Diagnostic::error(name, format!("{err_prefix} '{name}'"))
+                .with_code(ErrorCode::InvalidCall);
 
             rejected.sort_by(|x, y| x.ent.decl_pos().cmp(&y.ent.decl_pos()));
 
@@ -139,6 +190,14 @@ impl<'a> Candidates<'a> {
                                 )
                             }
                         }
+                        rejection => diag.add_related(
+                            decl_pos,
+                            format!(
+                                "Does not match {}: {}",
+                                cand.ent.describe(),
+                                rejection.reason()
+                            ),
+                        ),
                     };
                 }
             }
@@ -208,20 +267,32 @@ impl<'a> AnalyzeContext<'a> {
         call_pos: &SrcPos,
         candidates: &[OverloadedEnt<'a>],
         assocs: &mut [AssociationElement],
+        rejected: &mut Vec<Candidate<'a>>,
     ) -> EvalResult<Vec<ResolvedCall<'a>>> {
         let mut result = Vec::with_capacity(candidates.len());
         for ent in candidates.iter() {
+            let mut formal_diagnostics = Vec::new();
             if let Some(resolved) = as_fatal(self.resolve_association_formals(
                 call_pos,
                 ent.formals(),
                 scope,
                 assocs,
-                &mut NullDiagnostics,
+                &mut formal_diagnostics,
             ))? {
                 result.push(ResolvedCall {
                     subpgm: *ent,
                     formals: resolved,
                 });
+            } else {
+                let message = formal_diagnostics
+                    .into_iter()
+                    .next()
+                    .map(|diagnostic| diagnostic.message)
+                    .unwrap_or_else(|| "Arguments do not match the formals".to_owned());
+                rejected.push(Candidate {
+                    ent: *ent,
+                    rejection: Some(Rejection::Association(message)),
+                });
             }
 
             for elem in assocs.iter_mut() {
@@ -232,6 +303,79 @@ impl<'a> AnalyzeContext<'a> {
         Ok(result)
     }
 
+    /// The first actual of the call whose type does not match the formal,
+    /// there is no known reason when the types of all actuals are possible
+    fn argument_type_rejection(
+        &self,
+        actual_types: &[Option<ExpressionType<'a>>],
+        resolved: &ResolvedCall<'a>,
+    ) -> Option<Rejection<'a>> {
+        let matcher = self.implicit_matcher();
+        actual_types
+            .iter()
+            .zip(resolved.formals.iter())
+            .enumerate()
+            .find_map(|(idx, (actual_type, formal))| {
+                let actual_type = actual_type.as_ref()?;
+                if matcher.is_possible(actual_type, formal.base()) {
+                    return None;
+                }
+                Some(Rejection::ArgumentType {
+                    idx: idx + 1,
+                    formal: *formal,
+                    actual: match actual_type {
+                        ExpressionType::Unambiguous(typ) => Some(*typ),
+                        _ => None,
+                    },
+                })
+            })
+    }
+
+    /// The operator candidates are listed when at least one of the operands matches
+    /// since otherwise every predefined operator of the same arity would be listed
+    pub(super) fn no_match_for_operator(
+        &self,
+        op_pos: &SrcPos,
+        designator: &Designator,
+        candidates: &[OverloadedEnt<'a>],
+        operand_types: &[ExpressionType<'a>],
+    ) -> Diagnostic {
+        let matcher = self.implicit_matcher();
+        let mut rejected = Vec::new();
+        for ent in candidates.iter() {
+            let formals: Vec<_> = ent
+                .formals()
+                .iter()
+                .map(|formal| formal.type_mark())
+                .collect();
+            let matches: Vec<bool> = operand_types
+                .iter()
+                .zip(formals.iter())
+                .map(|(operand_type, formal)| matcher.is_possible(operand_type, formal.base()))
+                .collect();
+            if !matches.contains(&true) {
+                continue;
+            }
+            let resolved = ResolvedCall {
+                subpgm: *ent,
+                formals,
+            };
+            let actual_types: Vec<_> = operand_types.iter().cloned().map(Some).collect();
+            rejected.push(Candidate {
+                ent: *ent,
+                rejection: self.argument_type_rejection(&actual_types, &resolved),
+            });
+        }
+
+        let mut diag = Diagnostic::error(
+            op_pos,
+            format!("Found no match for {}", designator.describe()),
+        )
+        .with_code(ErrorCode::InvalidCall);
+        diag.add_rejected_candidates(rejected);
+        diag
+    }
+
     fn actual_types(
         &self,
         scope: &Scope<'a>,
@@ -282,7 +426,17 @@ impl<'a> AnalyzeContext<'a> {
             self.check_call(scope, call_pos, ent, assocs, diagnostics)?;
             return Ok(Disambiguated::Unambiguous(ent));
         } else if ok_kind.is_empty() {
-            diagnostics.push(Diagnostic::could_not_resolve(call_name, all_overloaded));
+            let rejection = match kind {
+                SubprogramKind::Function(_) => Rejection::Procedure,
+                SubprogramKind::Procedure => Rejection::Function,
+            };
+            diagnostics.push(Diagnostic::could_not_resolve(
+                call_name,
+                all_overloaded.into_iter().map(|ent| Candidate {
+                    ent,
+                    rejection: Some(rejection.clone()),
+                }),
+            ));
             return Err(EvalError::Unknown);
         }
 
@@ -294,14 +448,24 @@ impl<'a> AnalyzeContext<'a> {
             self.check_call(scope, call_pos, ent, assocs, diagnostics)?;
             return Ok(Disambiguated::Unambiguous(ent));
         } else if ok_kind.is_empty() {
-            diagnostics.push(Diagnostic::error(
-                call_name,
-                format!("uninstantiated subprogram {} cannot be called", call_name),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    call_name,
+                    format!("uninstantiated subprogram {} cannot be called", call_name),
+                )
+                .with_code(ErrorCode::InvalidCall),
+            );
             return Err(EvalError::Unknown);
         }
 
-        let ok_formals = self.disambiguate_by_assoc_formals(scope, call_pos, &ok_kind, assocs)?;
+        let mut formals_rejected = Vec::new();
+        let ok_formals = self.disambiguate_by_assoc_formals(
+            scope,
+            call_pos,
+            &ok_kind,
+            assocs,
+            &mut formals_rejected,
+        )?;
 
         // Only one candidate matched actual/formal profile
         if ok_formals.len() == 1 {
@@ -310,7 +474,7 @@ impl<'a> AnalyzeContext<'a> {
             return Ok(Disambiguated::Unambiguous(ent));
         } else if ok_formals.is_empty() {
             // No candidate matched actual/formal profile
-            diagnostics.push(Diagnostic::could_not_resolve(call_name, ok_kind));
+            diagnostics.push(Diagnostic::could_not_resolve(call_name, formals_rejected));
             return Err(EvalError::Unknown);
         }
 
@@ -327,7 +491,10 @@ impl<'a> AnalyzeContext<'a> {
         } else if ok_assoc_types.is_empty() {
             diagnostics.push(Diagnostic::could_not_resolve(
                 call_name,
-                ok_formals.into_iter().map(|resolved| resolved.subpgm),
+                ok_formals.iter().map(|resolved| Candidate {
+                    ent: resolved.subpgm,
+                    rejection: self.argument_type_rejection(&actual_types, resolved),
+                }),
             ));
             return Err(EvalError::Unknown);
         }
@@ -345,7 +512,10 @@ impl<'a> AnalyzeContext<'a> {
             } else if ok_return_type.is_empty() {
                 diagnostics.push(Diagnostic::could_not_resolve(
                     call_name,
-                    ok_assoc_types.into_iter().map(|resolved| resolved.subpgm),
+                    ok_assoc_types.into_iter().map(|resolved| Candidate {
+                        ent: resolved.subpgm,
+                        rejection: Some(Rejection::ReturnType),
+                    }),
                 ));
                 return Err(EvalError::Unknown);
             }
@@ -408,15 +578,36 @@ impl<'a> AnalyzeContext<'a> {
 impl Diagnostic {
     fn could_not_resolve<'a>(
         name: &WithPos<Designator>,
-        rejected: impl IntoIterator<Item = OverloadedEnt<'a>>,
+        rejected: impl IntoIterator<Item = Candidate<'a>>,
     ) -> Self {
         let mut diag = Diagnostic::error(
             &name.pos,
             format!("Could not resolve call to '{}'", name.designator()),
-        );
-        diag.add_subprogram_candidates("Does not match", rejected);
+        )
+        .with_code(ErrorCode::InvalidCall);
+        diag.add_rejected_candidates(rejected);
         diag
     }
+
+    fn add_rejected_candidates<'a>(&mut self, rejected: impl IntoIterator<Item = Candidate<'a>>) {
+        let mut rejected: Vec<_> = rejected.into_iter().collect();
+        rejected.sort_by(|x, y| x.ent.decl_pos().cmp(&y.ent.decl_pos()));
+
+        for cand in rejected {
+            if let Some(decl_pos) = cand.ent.decl_pos() {
+                // A candidate without a known reason is listed without one
+                let message = match cand.rejection {
+                    Some(rejection) => format!(
+                        "Does not match {}: {}",
+                        cand.ent.describe(),
+                        rejection.reason()
+                    ),
+                    None => format!("Does not match {}", cand.ent.describe()),
+                };
+                self.add_related(decl_pos, message);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -572,11 +763,70 @@ function myfun(arg2 : integer) return character;
                 Diagnostic::error(fcall.s1("myfun"), "Could not resolve call to 'myfun'")
                     .related(
                         decl.s1("myfun"),
-                        "Does not match function myfun[INTEGER return INTEGER]",
+                        "Does not match function myfun[INTEGER return INTEGER]: no association of parameter 'arg1'",
+                    )
+                    .related(
+                        decl.s("myfun", 2),
+                        "Does not match function myfun[INTEGER return CHARACTER]: no association of parameter 'arg2'",
+                    ),
+            ],
+        );
+    }
+
+    #[test]
+    fn one_argument_too_many() {
+        let test = TestSetup::new();
+        let decl = test.declarative_part(
+            "
+function myfun(arg1 : integer) return integer;
+function myfun(arg1 : character) return integer;
+        ",
+        );
+
+        let fcall = test.snippet("myfun(0, 1)");
+        let mut diagnostics = Vec::new();
+        assert_eq!(test.disambiguate(&fcall, None, &mut diagnostics), None);
+        check_diagnostics(
+            diagnostics,
+            vec![
+                Diagnostic::error(fcall.s1("myfun"), "Could not resolve call to 'myfun'")
+                    .related(
+                        decl.s1("myfun"),
+                        "Does not match function myfun[INTEGER return INTEGER]: unexpected extra argument",
+                    )
+                    .related(
+                        decl.s("myfun", 2),
+                        "Does not match function myfun[CHARACTER return INTEGER]: unexpected extra argument",
+                    ),
+            ],
+        );
+    }
+
+    #[test]
+    fn argument_type_mismatch_against_two_candidates() {
+        let test = TestSetup::new();
+        let decl = test.declarative_part(
+            "
+function myfun(arg1 : integer; arg2 : integer) return integer;
+function myfun(arg1 : integer; arg2 : character) return integer;
+constant c0 : boolean := false;
+        ",
+        );
+
+        let fcall = test.snippet("myfun(0, c0)");
+        let mut diagnostics = Vec::new();
+        assert_eq!(test.disambiguate(&fcall, None, &mut diagnostics), None);
+        check_diagnostics(
+            diagnostics,
+            vec![
+                Diagnostic::error(fcall.s1("myfun"), "Could not resolve call to 'myfun'")
+                    .related(
+                        decl.s1("myfun"),
+                        "Does not match function myfun[INTEGER, INTEGER return INTEGER]: type mismatch of argument 2, expected integer type 'INTEGER' but got type 'BOOLEAN'",
                     )
                     .related(
                         decl.s("myfun", 2),
-                        "Does not match function myfun[INTEGER return CHARACTER]",
+                        "Does not match function myfun[INTEGER, CHARACTER return INTEGER]: type mismatch of argument 2, expected type 'CHARACTER' but got type 'BOOLEAN'",
                     ),
             ],
         );
@@ -645,11 +895,11 @@ function myfun(arg1 : character) return character;
                 Diagnostic::error(fcall.s1("myfun"), "Could not resolve call to 'myfun'")
                     .related(
                         decl.s1("myfun"),
-                        "Does not match function myfun[CHARACTER return INTEGER]",
+                        "Does not match function myfun[CHARACTER return INTEGER]: type mismatch of argument 1, expected type 'CHARACTER' but got type universal_integer",
                     )
                     .related(
                         decl.s("myfun", 2),
-                        "Does not match function myfun[CHARACTER return CHARACTER]",
+                        "Does not match function myfun[CHARACTER return CHARACTER]: type mismatch of argument 1, expected type 'CHARACTER' but got type universal_integer",
                     ),
             ],
         );
@@ -700,11 +950,11 @@ function myfun(arg1 : integer) return character;
                 Diagnostic::error(fcall.s1("myfun"), "Could not resolve call to 'myfun'")
                     .related(
                         decl.s1("myfun"),
-                        "Does not match function myfun[INTEGER return INTEGER]",
+                        "Does not match function myfun[INTEGER return INTEGER]: return type does not match",
                     )
                     .related(
                         decl.s("myfun", 2),
-                        "Does not match function myfun[INTEGER return CHARACTER]",
+                        "Does not match function myfun[INTEGER return CHARACTER]: return type does not match",
                     ),
             ],
         )
@@ -18,6 +18,7 @@ use crate::ast::Operator;
 use crate::ast::PackageInstantiation;
 use crate::ast::{ActualPart, MapAspect};
 use crate::data::DiagnosticHandler;
+use crate::data::ErrorCode;
 use crate::named_entity::*;
 use crate::Diagnostic;
 use crate::NullDiagnostics;
@@ -73,16 +74,22 @@ impl<'a> AnalyzeContext<'a> {
                         }
                     }
                 } else {
-                    diagnostics.error(
-                        &formal.pos,
-                        "Expected simple name for package generic formal",
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &formal.pos,
+                            "Expected simple name for package generic formal",
+                        )
+                        .with_code(ErrorCode::InvalidFormal),
                     );
                     continue;
                 }
             } else if let Some(ent) = generics.nth(idx) {
                 ent
             } else {
-                diagnostics.error(&assoc.actual.pos, "Extra actual for generic map");
+                diagnostics.push(
+                    Diagnostic::error(&assoc.actual.pos, "Extra actual for generic map")
+                        .with_code(ErrorCode::InvalidFormal),
+                );
                 continue;
             };
 
@@ -110,12 +117,15 @@ impl<'a> AnalyzeContext<'a> {
                                             )?;
                                         }
                                     } else {
-                                        diagnostics.error(
-                                            &assoc.actual.pos,
-                                            format!(
-                                                "Array constraint cannot be used for {}",
-                                                typ.describe()
-                                            ),
+                                        diagnostics.push(
+                                            Diagnostic::error(
+                                                &assoc.actual.pos,
+                                                format!(
+                                                    "Array constraint cannot be used for {}",
+                                                    typ.describe()
+                                                ),
+                                            )
+                                            .with_code(ErrorCode::IllegalConstraint),
                                         );
                                     }
                                     typ
@@ -168,7 +178,8 @@ impl<'a> AnalyzeContext<'a> {
                                             target.designator(),
                                             signature.key().describe()
                                         ),
-                                    );
+                                    )
+                                    .with_code(ErrorCode::InvalidFormal);
 
                                     diag.add_subprogram_candidates(
                                         "Does not match",
@@ -178,32 +189,44 @@ impl<'a> AnalyzeContext<'a> {
                                     diagnostics.push(diag)
                                 }
                             } else {
-                                diagnostics.error(
-                                    &assoc.actual.pos,
-                                    format!(
-                                        "Cannot map {} to subprogram generic",
-                                        resolved.describe()
-                                    ),
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &assoc.actual.pos,
+                                        format!(
+                                            "Cannot map {} to subprogram generic",
+                                            resolved.describe()
+                                        ),
+                                    )
+                                    .with_code(ErrorCode::InvalidFormal),
                                 )
                             }
                         }
                         Expression::Literal(Literal::String(string)) => {
                             if Operator::from_latin1(string.clone()).is_none() {
-                                diagnostics.error(&assoc.actual.pos, "Invalid operator symbol");
+                                diagnostics.push(
+                                    Diagnostic::error(&assoc.actual.pos, "Invalid operator symbol")
+                                        .with_code(ErrorCode::MismatchedKinds),
+                                );
                             }
                         }
-                        _ => diagnostics.error(
-                            &assoc.actual.pos,
-                            "Cannot map expression to subprogram generic",
+                        _ => diagnostics.push(
+                            Diagnostic::error(
+                                &assoc.actual.pos,
+                                "Cannot map expression to subprogram generic",
+                            )
+                            .with_code(ErrorCode::InvalidFormal),
                         ),
                     },
                     GpkgInterfaceEnt::Package(_) => match expr {
                         Expression::Name(name) => {
                             self.name_resolve(scope, &assoc.actual.pos, name, diagnostics)?;
                         }
-                        _ => diagnostics.error(
-                            &assoc.actual.pos,
-                            "Cannot map expression to package generic",
+                        _ => diagnostics.push(
+                            Diagnostic::error(
+                                &assoc.actual.pos,
+                                "Cannot map expression to package generic",
+                            )
+                            .with_code(ErrorCode::InvalidFormal),
                         ),
                     },
                 },
@@ -227,7 +250,7 @@ impl<'a> AnalyzeContext<'a> {
         let nested = scope.nested().in_package_declaration();
         let (generics, other) = uninst_region.to_package_generic();
 
-        let mapping = if let Some(generic_map) = generic_map {
+        let mut mapping = if let Some(generic_map) = generic_map {
             self.generic_map(
                 &nested,
                 generics,
@@ -241,12 +264,18 @@ impl<'a> AnalyzeContext<'a> {
         for uninst in other {
             match self.instantiate(Some(ent), &mapping, uninst) {
                 Ok(inst) => {
+                    // Later declarations that reference a type declared within the package
+                    // must reference the instantiated type, the declarations are in declaration order
+                    if let Some(typ) = TypeEnt::from_any(inst) {
+                        mapping.insert(uninst.id(), typ);
+                    }
                     // We ignore diagnostics here, for example when adding implicit operators EQ and NE for interface types
                     // They can collide if there are more than one interface type that map to the same actual type
                     nested.add(inst, &mut NullDiagnostics);
                 }
                 Err(err) => {
-                    let mut diag = Diagnostic::error(decl_pos, err);
+                    let mut diag =
+                        Diagnostic::error(decl_pos, err).with_code(ErrorCode::InvalidInstantiation);
                     if let Some(pos) = uninst.decl_pos() {
                         diag.add_related(pos, "When instantiating this declaration");
                     }
@@ -280,6 +309,17 @@ impl<'a> AnalyzeContext<'a> {
             inst.set_kind(kind);
         }
 
+        // Implicit declarations such as the operators of a type reference the instantiated type
+        let implicit_mapping;
+        let mapping = if let Some(typ) = TypeEnt::from_any(inst) {
+            let mut with_typ = mapping.clone();
+            with_typ.insert(uninst.id(), typ);
+            implicit_mapping = with_typ;
+            &implicit_mapping
+        } else {
+            mapping
+        };
+
         for implicit_uninst in uninst.implicits.iter() {
             unsafe {
                 self.arena.add_implicit(
@@ -306,6 +346,7 @@ impl<'a> AnalyzeContext<'a> {
             AnyEntKind::ObjectAlias {
                 base_object,
                 type_mark,
+                whole_object,
             } => AnyEntKind::ObjectAlias {
                 base_object: if let Some(obj) =
                     ObjectEnt::from_any(self.instantiate(None, mapping, base_object)?)
@@ -317,6 +358,7 @@ impl<'a> AnalyzeContext<'a> {
                     );
                 },
                 type_mark: self.map_type_ent(mapping, *type_mark),
+                whole_object: *whole_object,
             },
             AnyEntKind::File(subtype) => AnyEntKind::File(self.map_subtype(mapping, *subtype)?),
             AnyEntKind::InterfaceFile(typ) => {
@@ -416,11 +458,13 @@ impl<'a> AnalyzeContext<'a> {
         let Signature {
             formals,
             return_type,
+            pure,
         } = signature;
 
         let FormalRegion {
             typ,
             entities: uninst_entities,
+            owner,
         } = formals;
 
         let mut inst_entities = Vec::with_capacity(uninst_entities.len());
@@ -440,8 +484,10 @@ impl<'a> AnalyzeContext<'a> {
             formals: FormalRegion {
                 typ: *typ,
                 entities: inst_entities,
+                owner: *owner,
             },
             return_type: return_type.map(|typ| self.map_type_ent(mapping, typ)),
+            pure: *pure,
         })
     }
 
@@ -451,16 +497,13 @@ impl<'a> AnalyzeContext<'a> {
         mapping: &FnvHashMap<EntityId, TypeEnt<'a>>,
         region: &'a Region<'a>,
     ) -> Result<Region<'a>, String> {
-        let Region {
+        let RegionData {
             entities: uninst_entities,
             kind,
             ..
-        } = region;
+        } = &**region;
 
-        let mut inst_region = Region {
-            kind: *kind,
-            ..Region::default()
-        };
+        let mut inst_region = Region::new(Visibility::default(), FnvHashMap::default(), *kind);
 
         for (_, uninst) in uninst_entities.iter() {
             match uninst {
@@ -541,6 +584,10 @@ impl<'a> AnalyzeContext<'a> {
             iface,
             subtype,
             has_default,
+            static_value,
+            signal_kind,
+            index_range,
+            default_value,
         } = obj;
 
         Ok(Object {
@@ -548,6 +595,10 @@ impl<'a> AnalyzeContext<'a> {
             iface: *iface,
             subtype: self.map_subtype(mapping, *subtype)?,
             has_default: *has_default,
+            static_value: static_value.clone(),
+            signal_kind: *signal_kind,
+            index_range: index_range.clone(),
+            default_value: default_value.clone(),
         })
     }
 
@@ -564,10 +615,16 @@ impl<'a> AnalyzeContext<'a> {
         mapping: &FnvHashMap<EntityId, TypeEnt<'a>>,
         subtype: Subtype<'a>,
     ) -> Result<Subtype<'a>, String> {
-        let Subtype { type_mark } = subtype;
+        let Subtype {
+            type_mark,
+            resolved,
+            static_range,
+        } = subtype;
 
         Ok(Subtype {
             type_mark: self.map_type_ent(mapping, type_mark),
+            resolved,
+            static_range,
         })
     }
 }
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Progress reporting of the analysis of a design
+
+use crate::ast::*;
+
+/// The phases of analyzing a design in the order they occur
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalysisPhase {
+    /// Parsing the files of a project, this is only reported when a project is created or
+    /// its configuration is replaced
+    Parsing,
+    /// Invalidating changed units, checking for duplicates and analyzing the standard packages
+    Preparing,
+    /// Analyzing the design units
+    Analyzing,
+    /// All design units have been analyzed
+    Finished,
+}
+
+/// Receives the progress of the analysis
+///
+/// Units are analyzed by several threads at once so the callbacks may be invoked
+/// concurrently and units may finish in a different order than they started.
+pub trait AnalysisProgress: Sync {
+    fn phase(&self, phase: AnalysisPhase);
+
+    /// A file of the project has been parsed, the files are parsed in no particular order
+    fn file_parsed(&self, file_name: &str);
+
+    /// The unit is given without library name, such as `pkg`, `pkg body` or `ent(rtl)`
+    fn unit_started(&self, library_name: &str, unit_name: &str);
+
+    fn unit_finished(&self, library_name: &str, unit_name: &str);
+}
+
+/// The name of a unit as it is reported to the progress callback
+pub(super) fn progress_unit_name(unit_id: &UnitId) -> String {
+    let primary_name = unit_id.primary_name().name_utf8();
+    match (unit_id.kind(), unit_id.secondary_name()) {
+        (AnyKind::Secondary(SecondaryKind::PackageBody), _) => format!("{primary_name} body"),
+        (_, Some(name)) => format!("{primary_name}({})", name.name_utf8()),
+        (_, None) => primary_name,
+    }
+}
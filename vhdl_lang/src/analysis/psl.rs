@@ -0,0 +1,155 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! LRM 11.1 PSL directives and declarations
+//!
+//! The booleans of PSL properties and sequences are VHDL expressions that are analyzed as
+//! conditions. A boolean may also be the name of a previously declared property or sequence.
+
+use super::analyze::*;
+use super::scope::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+impl<'a> AnalyzeContext<'a> {
+    pub fn analyze_psl_directive(
+        &self,
+        scope: &Scope<'a>,
+        directive: &mut PslDirective,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let PslDirective {
+            kind: _,
+            property,
+            report,
+            severity,
+        } = directive;
+        self.analyze_psl_property(scope, &mut property.item, diagnostics)?;
+        if let Some(expr) = report {
+            self.expr_with_ttyp(scope, self.string(), expr, diagnostics)?;
+        }
+        if let Some(expr) = severity {
+            self.expr_with_ttyp(scope, self.severity_level(), expr, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    pub fn analyze_psl_declaration(
+        &self,
+        scope: &Scope<'a>,
+        parent: EntRef<'a>,
+        decl: &mut PslDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        match decl {
+            PslDeclaration::DefaultClock(default_clock) => {
+                self.boolean_expr(scope, &mut default_clock.clock, diagnostics)?;
+            }
+            PslDeclaration::Property(decl) => {
+                self.analyze_psl_property(scope, &mut decl.property.item, diagnostics)?;
+                let ent = self.arena.define(
+                    &mut decl.ident,
+                    parent,
+                    AnyEntKind::Concurrent(Some(Concurrent::Property)),
+                );
+                scope.add(ent, diagnostics);
+            }
+            PslDeclaration::Sequence(decl) => {
+                self.analyze_psl_sequence(scope, &mut decl.sequence.item, diagnostics)?;
+                let ent = self.arena.define(
+                    &mut decl.ident,
+                    parent,
+                    AnyEntKind::Concurrent(Some(Concurrent::Sequence)),
+                );
+                scope.add(ent, diagnostics);
+            }
+        }
+        Ok(())
+    }
+
+    fn analyze_psl_property(
+        &self,
+        scope: &Scope<'a>,
+        property: &mut PslProperty,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        match property {
+            PslProperty::Boolean(expr) => self.psl_boolean(scope, expr, diagnostics),
+            PslProperty::Sequence { sequence, .. } => {
+                self.analyze_psl_sequence(scope, &mut sequence.item, diagnostics)
+            }
+            PslProperty::Unary(_, operand) => {
+                self.analyze_psl_property(scope, &mut operand.item, diagnostics)
+            }
+            PslProperty::Binary(_, lhs, rhs) => {
+                self.analyze_psl_property(scope, &mut lhs.item, diagnostics)?;
+                self.analyze_psl_property(scope, &mut rhs.item, diagnostics)
+            }
+            PslProperty::SuffixImplication {
+                sequence, property, ..
+            } => {
+                self.analyze_psl_sequence(scope, &mut sequence.item, diagnostics)?;
+                self.analyze_psl_property(scope, &mut property.item, diagnostics)
+            }
+            PslProperty::Abort(property, condition) | PslProperty::Clocked(property, condition) => {
+                self.analyze_psl_property(scope, &mut property.item, diagnostics)?;
+                self.boolean_expr(scope, condition, diagnostics)
+            }
+        }
+    }
+
+    fn analyze_psl_sequence(
+        &self,
+        scope: &Scope<'a>,
+        sequence: &mut PslSequence,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        match sequence {
+            PslSequence::Boolean(expr) => self.psl_boolean(scope, expr, diagnostics),
+            PslSequence::Binary(_, lhs, rhs) => {
+                self.analyze_psl_sequence(scope, &mut lhs.item, diagnostics)?;
+                self.analyze_psl_sequence(scope, &mut rhs.item, diagnostics)
+            }
+            PslSequence::Repeated(sequence, repetition) => {
+                if let Some(sequence) = sequence {
+                    self.analyze_psl_sequence(scope, &mut sequence.item, diagnostics)?;
+                }
+                if let Some(PslCount { low, high }) = &mut repetition.count {
+                    self.expr_unknown_ttyp(scope, low, diagnostics)?;
+                    if let Some(PslBound::Expression(high)) = high {
+                        self.expr_unknown_ttyp(scope, high, diagnostics)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// A condition or the name of a declared property or sequence
+    /// A boolean that may also be the name of a property or sequence
+    pub fn psl_boolean(
+        &self,
+        scope: &Scope<'a>,
+        expr: &mut WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        if let Expression::Name(ref mut name) = expr.item {
+            if let Name::Designator(ref mut designator) = name.as_mut() {
+                if let Ok(NamedEntities::Single(ent)) = scope.lookup(&expr.pos, &designator.item) {
+                    if matches!(
+                        ent.kind(),
+                        AnyEntKind::Concurrent(Some(Concurrent::Property | Concurrent::Sequence))
+                    ) {
+                        designator.set_unique_reference(ent);
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        self.boolean_expr(scope, expr, diagnostics)
+    }
+}
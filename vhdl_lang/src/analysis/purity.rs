@@ -0,0 +1,180 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! References within a pure function that make it impure
+//!
+//! A function that is not declared `impure` may not reference a signal, variable or file
+//! declared outside of it, including shared variables, and may not call an impure function
+//! such as `now` or one of the implicit file operations of a file type. Objects declared
+//! within the function and its parameters may be referenced. Nested subprogram bodies are
+//! checked on their own when they are analyzed, calling a nested impure function is still
+//! an error.
+
+use super::analyze::*;
+use crate::ast::search::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::syntax::TokenAccess;
+
+struct PuritySearcher<'a, 'b> {
+    arena: &'a Arena,
+    function: EntRef<'a>,
+    diagnostics: &'b mut dyn DiagnosticHandler,
+}
+
+impl<'a, 'b> PuritySearcher<'a, 'b> {
+    /// True if the entity is declared within the function or is one of its parameters
+    fn is_declared_within(&self, ent: EntRef<'a>) -> bool {
+        let mut parent = ent.parent;
+        while let Some(ent) = parent {
+            if ent.id() == self.function.id() {
+                return true;
+            }
+            parent = ent.parent;
+        }
+        false
+    }
+
+    /// True if the entity is a parameter of a subprogram that does not enclose the function,
+    /// it is then only referenced as the formal of an association
+    fn is_formal_of_other_subprogram(&self, ent: EntRef<'a>) -> bool {
+        let Some(subprogram) = ent.parent.filter(|parent| parent.is_overloaded()) else {
+            return false;
+        };
+        let mut enclosing = Some(self.function);
+        while let Some(ent) = enclosing {
+            if ent.id() == subprogram.id() {
+                return false;
+            }
+            enclosing = ent.parent;
+        }
+        true
+    }
+
+    /// The reason why referencing the entity makes the function impure
+    fn violation(&self, ent: EntRef<'a>) -> Option<String> {
+        match ent.kind() {
+            AnyEntKind::Object(object) => match object.class {
+                ObjectClass::Constant => None,
+                ObjectClass::SharedVariable => Some(format!(
+                    "Pure function cannot reference shared variable '{}'",
+                    ent.designator()
+                )),
+                ObjectClass::Signal | ObjectClass::Variable => {
+                    if self.is_declared_within(ent) || self.is_formal_of_other_subprogram(ent) {
+                        None
+                    } else {
+                        Some(format!(
+                            "Pure function cannot reference {} declared outside of it",
+                            ent.describe()
+                        ))
+                    }
+                }
+            },
+            AnyEntKind::File(..) | AnyEntKind::InterfaceFile(..) => {
+                if self.is_declared_within(ent) || self.is_formal_of_other_subprogram(ent) {
+                    None
+                } else {
+                    Some(format!(
+                        "Pure function cannot reference {} declared outside of it",
+                        ent.describe()
+                    ))
+                }
+            }
+            AnyEntKind::Overloaded(overloaded) => {
+                if is_file_operation(ent) {
+                    Some(format!(
+                        "Pure function cannot call file operation '{}'",
+                        ent.designator()
+                    ))
+                } else if overloaded.signature().return_type().is_some()
+                    && !overloaded.signature().is_pure()
+                {
+                    Some(format!(
+                        "Pure function cannot call impure function '{}'",
+                        ent.designator()
+                    ))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// True for the implicitly declared subprograms of a file type such as `read` and `endfile`
+fn is_file_operation(ent: EntRef) -> bool {
+    matches!(
+        ent.related,
+        Related::ImplicitOf(typ) if matches!(typ.kind(), AnyEntKind::Type(Type::File))
+    )
+}
+
+impl<'a, 'b> Searcher for PuritySearcher<'a, 'b> {
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        let Some(id) = reference.get() else {
+            return NotFinished;
+        };
+        // An alias denotes the object or subprogram it is an alias of
+        let ent = self.arena.get(id).as_actual();
+
+        if let Some(message) = self.violation(ent) {
+            let mut diagnostic =
+                Diagnostic::error(pos, message).with_code(ErrorCode::PurityViolation);
+            if let Some(decl_pos) = self.function.decl_pos() {
+                diagnostic.add_related(
+                    decl_pos,
+                    format!("Function '{}' is pure", self.function.designator()),
+                );
+            }
+            self.diagnostics.push(diagnostic);
+        }
+        NotFinished
+    }
+}
+
+impl<'a> AnalyzeContext<'a> {
+    /// Report the references within the body of a pure function that make it impure
+    pub(super) fn check_function_purity(
+        &self,
+        function: EntRef<'a>,
+        body: &SubprogramBody,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let SubprogramSpecification::Function(ref spec) = body.specification else {
+            return;
+        };
+        // Third-party libraries such as VITAL call `now` from pure functions,
+        // which simulators accept
+        if !spec.pure
+            || self
+                .root
+                .third_party_libraries
+                .contains(self.work_library_name())
+        {
+            return;
+        }
+
+        let mut searcher = PuritySearcher {
+            arena: self.arena,
+            function,
+            diagnostics,
+        };
+        for decl in body.declarations.iter() {
+            if !matches!(decl, Declaration::SubprogramBody(..)) {
+                let _ = decl.search(self.ctx, &mut searcher);
+            }
+        }
+        let _ = body.statements.search(self.ctx, &mut searcher);
+    }
+}
@@ -48,9 +48,12 @@ impl<'a> AnalyzeContext<'a> {
                 if typ.base().is_scalar() {
                     Ok(DisambiguatedType::Unambiguous(typ))
                 } else {
-                    diagnostics.error(
-                        &expr.pos,
-                        format!("Non-scalar {} cannot be used in a range", typ.describe()),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &expr.pos,
+                            format!("Non-scalar {} cannot be used in a range", typ.describe()),
+                        )
+                        .with_code(ErrorCode::InvalidRange),
                     );
                     Err(EvalError::Unknown)
                 }
@@ -58,8 +61,14 @@ impl<'a> AnalyzeContext<'a> {
             ExpressionType::Ambiguous(types) => Ok(DisambiguatedType::Ambiguous(
                 types.into_iter().filter(|typ| typ.is_scalar()).collect(),
             )),
-            ExpressionType::String | ExpressionType::Null | ExpressionType::Aggregate => {
-                diagnostics.error(&expr.pos, "Non-scalar expression cannot be used in a range");
+            ExpressionType::String
+            | ExpressionType::Null
+            | ExpressionType::Aggregate
+            | ExpressionType::Allocator(_) => {
+                diagnostics.push(
+                    Diagnostic::error(&expr.pos, "Non-scalar expression cannot be used in a range")
+                        .with_code(ErrorCode::InvalidRange),
+                );
                 Err(EvalError::Unknown)
             }
         }
@@ -87,13 +96,13 @@ impl<'a> AnalyzeContext<'a> {
                         attr.name.set_unique_reference(ent);
                         ent.return_type().unwrap()
                     } else {
-                        diagnostics.error(
+                        diagnostics.push(Diagnostic::error(
                         &attr.name.pos,
                         format!(
                             "{} cannot be prefix of range attribute, array type or object is required",
                             resolved.describe()
                         ),
-                    );
+                    ).with_code(ErrorCode::IllegalAttribute));
                         return Err(EvalError::Unknown);
                     }
                 } else {
@@ -105,12 +114,15 @@ impl<'a> AnalyzeContext<'a> {
             | ResolvedName::Final(_)
             | ResolvedName::Library(_)
             | ResolvedName::Design(_) => {
-                diagnostics.error(
-                    &attr.name.pos,
-                    format!(
+                diagnostics.push(
+                    Diagnostic::error(
+                        &attr.name.pos,
+                        format!(
                         "{} cannot be prefix of range attribute, array type or object is required",
                         resolved.describe()
                     ),
+                    )
+                    .with_code(ErrorCode::IllegalAttribute),
                 );
                 return Err(EvalError::Unknown);
             }
@@ -135,12 +147,15 @@ impl<'a> AnalyzeContext<'a> {
                 Err(EvalError::Unknown)
             }
         } else {
-            diagnostics.error(
-                &attr.name.pos,
-                format!(
-                    "{} cannot be prefix of range attribute, array type or object is required",
-                    resolved.describe()
-                ),
+            diagnostics.push(
+                Diagnostic::error(
+                    &attr.name.pos,
+                    format!(
+                        "{} cannot be prefix of range attribute, array type or object is required",
+                        resolved.describe()
+                    ),
+                )
+                .with_code(ErrorCode::IllegalAttribute),
             );
             Err(EvalError::Unknown)
         }
@@ -167,13 +182,16 @@ impl<'a> AnalyzeContext<'a> {
                         if let Some(typ) = self.common_type(l.base(), r.base()) {
                             return Ok(typ);
                         } else {
-                            diagnostics.error(
-                                constraint.pos(),
-                                format!(
-                                    "Range type mismatch, left is {}, right is {}",
-                                    l.base().describe(),
-                                    r.base().describe()
-                                ),
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    constraint.pos(),
+                                    format!(
+                                        "Range type mismatch, left is {}, right is {}",
+                                        l.base().describe(),
+                                        r.base().describe()
+                                    ),
+                                )
+                                .with_code(ErrorCode::InvalidRange),
                             );
                             return Err(EvalError::Unknown);
                         }
@@ -186,7 +204,10 @@ impl<'a> AnalyzeContext<'a> {
                         self.common_types(l, r.base())
                     }
                     (DisambiguatedType::Ambiguous(_), DisambiguatedType::Ambiguous(_)) => {
-                        diagnostics.error(constraint.pos(), "Range is ambiguous");
+                        diagnostics.push(
+                            Diagnostic::error(constraint.pos(), "Range is ambiguous")
+                                .with_code(ErrorCode::Ambiguous),
+                        );
                         return Err(EvalError::Unknown);
                     }
                 };
@@ -214,13 +235,19 @@ impl<'a> AnalyzeContext<'a> {
 
                     Ok(typ)
                 } else if types.is_empty() {
-                    diagnostics.error(
-                        constraint.pos(),
-                        "Range type of left and right side does not match",
+                    diagnostics.push(
+                        Diagnostic::error(
+                            constraint.pos(),
+                            "Range type of left and right side does not match",
+                        )
+                        .with_code(ErrorCode::InvalidRange),
                     );
                     Err(EvalError::Unknown)
                 } else {
-                    diagnostics.error(constraint.pos(), "Range is ambiguous");
+                    diagnostics.push(
+                        Diagnostic::error(constraint.pos(), "Range is ambiguous")
+                            .with_code(ErrorCode::Ambiguous),
+                    );
                     Err(EvalError::Unknown)
                 }
             }
@@ -250,12 +277,15 @@ impl<'a> AnalyzeContext<'a> {
         if typ.is_discrete() {
             Ok(typ)
         } else {
-            diagnostics.error(
-                &drange.pos(),
-                format!(
-                    "Non-discrete {} cannot be used in discrete range",
-                    typ.describe()
-                ),
+            diagnostics.push(
+                Diagnostic::error(
+                    &drange.pos(),
+                    format!(
+                        "Non-discrete {} cannot be used in discrete range",
+                        typ.describe()
+                    ),
+                )
+                .with_code(ErrorCode::InvalidRange),
             );
             Err(EvalError::Unknown)
         }
@@ -309,9 +339,12 @@ impl<'a> AnalyzeContext<'a> {
                 )?;
 
                 if let Some(ref mut signature) = signature {
-                    diagnostics.error(
-                        &signature.pos,
-                        format!("Did not expect signature for '{attr} attribute"),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &signature.pos,
+                            format!("Did not expect signature for '{attr} attribute"),
+                        )
+                        .with_code(ErrorCode::IllegalAttribute),
                     );
                 }
 
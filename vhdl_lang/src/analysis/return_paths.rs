@@ -0,0 +1,112 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Function bodies where a control path can reach the end without a return statement
+//!
+//! Conditions are not evaluated, so every branch of an if statement is assumed to be taken
+//! and every loop with an iteration scheme or an exit statement may complete. A case statement
+//! always covers all values of its selector, so only its alternatives need to return.
+
+use crate::ast::*;
+use crate::data::*;
+
+pub(crate) fn check_function_return_paths(
+    body: &SubprogramBody,
+    diagnostics: &mut dyn DiagnosticHandler,
+) {
+    let SubprogramSpecification::Function(ref function) = body.specification else {
+        return;
+    };
+
+    if can_complete(&body.statements) {
+        diagnostics.push(
+            Diagnostic::warning(
+                &function.designator.tree.pos,
+                format!(
+                    "Function '{}' can reach its end without a return statement",
+                    function.designator.tree.item
+                ),
+            )
+            .with_code(ErrorCode::MissingReturn),
+        );
+    }
+}
+
+/// True when execution can continue after the statements
+fn can_complete(statements: &[LabeledSequentialStatement]) -> bool {
+    statements.iter().all(statement_can_complete)
+}
+
+fn statement_can_complete(statement: &LabeledSequentialStatement) -> bool {
+    match &statement.statement.item {
+        SequentialStatement::Return(_) => false,
+        // A syntax error has already been reported, the statement may have been a return
+        SequentialStatement::Error => false,
+        SequentialStatement::If(ifstmt) => {
+            let Conditionals {
+                conditionals,
+                else_item,
+            } = &ifstmt.conds;
+            match else_item {
+                Some(else_item) => {
+                    can_complete(else_item)
+                        || conditionals
+                            .iter()
+                            .any(|conditional| can_complete(&conditional.item))
+                }
+                None => true,
+            }
+        }
+        SequentialStatement::Case(case) => case
+            .alternatives
+            .iter()
+            .any(|alternative| can_complete(&alternative.item)),
+        SequentialStatement::Loop(loop_stmt) => {
+            loop_stmt.iteration_scheme.is_some()
+                || contains_exit(&loop_stmt.statements, statement.label.tree.as_ref(), false)
+        }
+        _ => true,
+    }
+}
+
+/// True when any of the statements is or contains an exit statement that leaves the loop
+/// with the label. Within nested loops only exits with the label leave the loop.
+fn contains_exit(
+    statements: &[LabeledSequentialStatement],
+    label: Option<&Ident>,
+    nested: bool,
+) -> bool {
+    statements
+        .iter()
+        .any(|statement| match &statement.statement.item {
+            SequentialStatement::Exit(exit) => match exit.loop_label {
+                Some(ref loop_label) => {
+                    label.is_some_and(|label| label.item == loop_label.item.item)
+                }
+                None => !nested,
+            },
+            SequentialStatement::If(ifstmt) => {
+                ifstmt
+                    .conds
+                    .conditionals
+                    .iter()
+                    .any(|conditional| contains_exit(&conditional.item, label, nested))
+                    || ifstmt
+                        .conds
+                        .else_item
+                        .as_deref()
+                        .is_some_and(|else_item| contains_exit(else_item, label, nested))
+            }
+            SequentialStatement::Case(case) => case
+                .alternatives
+                .iter()
+                .any(|alternative| contains_exit(&alternative.item, label, nested)),
+            SequentialStatement::Loop(loop_stmt) => {
+                contains_exit(&loop_stmt.statements, label, true)
+            }
+            _ => false,
+        })
+}
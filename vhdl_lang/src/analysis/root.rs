@@ -5,7 +5,9 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use super::analyze::*;
+use super::dependency_graph::*;
 use super::lock::*;
+use super::progress::*;
 use super::standard::StandardTypes;
 use super::standard::UniversalTypes;
 use crate::named_entity::*;
@@ -13,12 +15,16 @@ use crate::named_entity::*;
 use crate::ast::search::*;
 use crate::ast::*;
 use crate::data::*;
-use crate::syntax::{Symbols, Token, TokenAccess};
+use crate::syntax::{doc_comment, Kind, Symbols, Token, TokenAccess, Tokenizer, VHDLParser, Value};
+use crate::VHDLStandard;
 use fnv::{FnvHashMap, FnvHashSet};
 use parking_lot::RwLock;
 use std::collections::hash_map::Entry;
+use std::collections::VecDeque;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 /// A design unit with design unit data
@@ -26,6 +32,10 @@ pub(crate) struct AnalysisData {
     pub diagnostics: Vec<Diagnostic>,
     pub has_circular_dependency: bool,
     pub arena: FinalArena,
+    /// The resolved types of the expressions and names within the unit
+    pub expression_types: Vec<(SrcPos, EntityId)>,
+    /// The position, caller and callee of the calls within the unit
+    pub calls: Vec<(SrcPos, EntityId, EntityId)>,
 }
 
 pub(super) type UnitReadGuard<'a> = ReadGuard<'a, AnyDesignUnit, AnalysisData>;
@@ -39,6 +49,8 @@ pub(crate) struct LockedUnit {
     unit_id: UnitId,
     pub unit: AnalysisLock<AnyDesignUnit, AnalysisData>,
     pub tokens: Vec<Token>,
+    /// The VHDL revision of the file that contains the unit
+    pub standard: VHDLStandard,
 }
 
 impl HasUnitId for LockedUnit {
@@ -48,7 +60,12 @@ impl HasUnitId for LockedUnit {
 }
 
 impl LockedUnit {
-    fn new(library_name: &Symbol, unit: AnyDesignUnit, tokens: Vec<Token>) -> LockedUnit {
+    fn new(
+        library_name: &Symbol,
+        unit: AnyDesignUnit,
+        tokens: Vec<Token>,
+        standard: VHDLStandard,
+    ) -> LockedUnit {
         let unit_id = match unit {
             AnyDesignUnit::Primary(ref unit) => {
                 UnitId::primary(library_name, PrimaryKind::kind_of(unit), unit.name())
@@ -67,6 +84,7 @@ impl LockedUnit {
             unit_id,
             unit: AnalysisLock::new(unit),
             tokens,
+            standard,
         }
     }
 }
@@ -77,6 +95,43 @@ impl HasIdent for LockedUnit {
     }
 }
 
+/// Bookkeeping of a design file of a library that is kept between analyses
+#[derive(Default)]
+struct FileRecord {
+    /// The diagnostics from parsing the file
+    parser_diagnostics: Vec<Diagnostic>,
+    /// The units of the file were added or removed since the last analysis
+    changed: bool,
+    /// The revision of the design root when the file was last analyzed
+    revision: u64,
+    /// The revision of the source contents that the units of the file were parsed from
+    source_revision: u64,
+    /// The VHDL revision that the file was parsed with, the standard of its library
+    standard: VHDLStandard,
+}
+
+/// The parse and analysis status of a design file within a library
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileStatus {
+    pub source: Source,
+    pub library_name: Symbol,
+    /// The number of design units of the file, units with syntax errors are not included
+    pub num_units: usize,
+    /// The file was parsed without syntax errors
+    pub parsed: bool,
+    /// The number of parse and analysis diagnostics of each severity
+    pub num_errors: usize,
+    pub num_warnings: usize,
+    pub num_infos: usize,
+    pub num_hints: usize,
+    /// The revision of the design root when the file was last analyzed,
+    /// the revision is incremented by every analysis
+    pub revision: u64,
+    /// The revision of the source contents that the diagnostics of the file belong to,
+    /// see [`Source::revision`]
+    pub source_revision: u64,
+}
+
 /// Represents a VHDL library containing zero or more design units.
 ///
 /// This struct also keeps track of which source file contained which design units.
@@ -99,6 +154,9 @@ pub struct Library {
     /// Design units which were not added since they were duplicates.
     /// They need to be kept for later refresh which might make them not duplicates.
     duplicates: Vec<(SrcPos, LockedUnit)>,
+
+    /// The design files of the library, including files without any design units
+    files: FnvHashMap<Source, FileRecord>,
 }
 
 impl Library {
@@ -122,6 +180,7 @@ impl Library {
             added: FnvHashSet::default(),
             removed: FnvHashSet::default(),
             duplicates: Vec::new(),
+            files: FnvHashMap::default(),
         }
     }
 
@@ -130,6 +189,7 @@ impl Library {
     }
 
     fn add_design_unit(&mut self, unit: LockedUnit) {
+        self.files.entry(unit.source().clone()).or_default().changed = true;
         let unit_id = unit.unit_id().clone();
         match self.units.entry(unit.key().clone()) {
             Entry::Occupied(entry) => {
@@ -155,8 +215,104 @@ impl Library {
 
     fn add_design_file(&mut self, design_file: DesignFile) {
         for (tokens, design_unit) in design_file.design_units {
-            self.add_design_unit(LockedUnit::new(self.name(), design_unit, tokens));
+            self.add_design_unit(LockedUnit::new(
+                self.name(),
+                design_unit,
+                tokens,
+                design_file.standard,
+            ));
+        }
+    }
+
+    fn add_source_file(
+        &mut self,
+        source: &Source,
+        design_file: DesignFile,
+        parser_diagnostics: &[Diagnostic],
+    ) {
+        let record = match self.files.entry(source.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(FileRecord {
+                changed: true,
+                ..FileRecord::default()
+            }),
+        };
+        record.parser_diagnostics = parser_diagnostics.to_vec();
+        record.source_revision = design_file.source_revision;
+        record.standard = design_file.standard;
+        self.add_design_file(design_file);
+    }
+
+    /// Set the revision of the files with units that were analyzed or changed
+    fn set_analyzed_revision(&mut self, analyzed: &[UnitId], revision: u64) {
+        for unit_id in analyzed {
+            if let Some(unit) = self.units.get(unit_id.key()) {
+                if unit.unit_id() == unit_id {
+                    if let Some(record) = self.files.get_mut(unit.source()) {
+                        record.revision = revision;
+                    }
+                }
+            }
+        }
+        for record in self.files.values_mut() {
+            if record.changed {
+                record.changed = false;
+                record.revision = revision;
+            }
+        }
+    }
+
+    fn file_status(&self, source: &Source, record: &FileRecord) -> FileStatus {
+        let mut status = FileStatus {
+            source: source.clone(),
+            library_name: self.name.clone(),
+            num_units: 0,
+            parsed: !record
+                .parser_diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity == Severity::Error),
+            num_errors: 0,
+            num_warnings: 0,
+            num_infos: 0,
+            num_hints: 0,
+            revision: record.revision,
+            source_revision: record.source_revision,
+        };
+
+        let mut count = |severity: Severity| match severity {
+            Severity::Error => status.num_errors += 1,
+            Severity::Warning => status.num_warnings += 1,
+            Severity::Info => status.num_infos += 1,
+            Severity::Hint => status.num_hints += 1,
+        };
+
+        for diagnostic in record.parser_diagnostics.iter() {
+            count(diagnostic.severity);
+        }
+
+        let unit_ids = self.units_by_source.get(source);
+        for unit_id in unit_ids.into_iter().flatten() {
+            if let Some(result) = self
+                .units
+                .get(unit_id.key())
+                .and_then(|unit| unit.unit.get())
+            {
+                for diagnostic in result.result().diagnostics.iter() {
+                    count(diagnostic.severity);
+                }
+            }
+        }
+        let num_duplicates = self
+            .duplicates
+            .iter()
+            .filter(|(_, unit)| unit.source() == source)
+            .count();
+        for _ in 0..num_duplicates {
+            count(Severity::Error);
         }
+
+        status.num_units = unit_ids.map(|ids| ids.len()).unwrap_or(0) + num_duplicates;
+        status
     }
 
     /// Refresh library after removing or adding new design units.
@@ -173,16 +329,19 @@ impl Library {
                         "A primary unit has already been declared with name '{}' in library '{}'",
                         primary_name, &self.name
                     ),
-                ),
+                )
+                .with_code(ErrorCode::DuplicateDecl),
                 UnitKey::Secondary(ref primary_name, ref name) => match unit.kind() {
                     AnyKind::Secondary(SecondaryKind::Architecture) => Diagnostic::error(
                         unit.ident(),
                         format!("Duplicate architecture '{name}' of entity '{primary_name}'",),
-                    ),
+                    )
+                    .with_code(ErrorCode::DuplicateDecl),
                     AnyKind::Secondary(SecondaryKind::PackageBody) => Diagnostic::error(
                         unit.pos(),
                         format!("Duplicate package body of package '{primary_name}'"),
-                    ),
+                    )
+                    .with_code(ErrorCode::DuplicateDecl),
                     AnyKind::Primary(_) => {
                         unreachable!();
                     }
@@ -194,6 +353,17 @@ impl Library {
         }
     }
 
+    /// True if a file other than `excluded` declares the primary unit, even as a duplicate
+    fn declares_primary_unit_outside(&self, name: &Symbol, excluded: Option<&Source>) -> bool {
+        let key = UnitKey::Primary(name.clone());
+        let is_outside = |unit: &LockedUnit| Some(unit.source()) != excluded;
+        self.units.get(&key).is_some_and(is_outside)
+            || self
+                .duplicates
+                .iter()
+                .any(|(_, unit)| unit.key() == &key && is_outside(unit))
+    }
+
     /// Remove all design units defined in source.
     /// This is used for incremental analysis where only a single source file is updated.
     fn remove_source(&mut self, source: &Source) {
@@ -207,6 +377,7 @@ impl Library {
             }
         });
         self.units_by_source.remove(source);
+        self.files.remove(source);
         self.duplicates
             .retain(|(_, value)| value.source() != source);
 
@@ -279,6 +450,60 @@ impl Library {
     }
 }
 
+/// Describes a primary design unit of a library and the secondary units that belong to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnitDescription {
+    pub library_name: Symbol,
+    pub kind: PrimaryKind,
+    pub name: Symbol,
+    pub source: Source,
+    /// The position of the identifier of the unit
+    pub pos: SrcPos,
+    /// The architectures of an entity or the body of a package, sorted by name
+    pub secondary_units: Vec<SecondaryUnitDescription>,
+}
+
+/// Describes an architecture or package body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecondaryUnitDescription {
+    pub kind: SecondaryKind,
+    pub name: Symbol,
+    pub source: Source,
+    /// The position of the identifier of the unit
+    pub pos: SrcPos,
+}
+
+impl Library {
+    fn describe_unit(&self, unit: &LockedUnit) -> Option<UnitDescription> {
+        let AnyKind::Primary(kind) = unit.unit_id().kind() else {
+            return None;
+        };
+
+        let mut secondary_units: Vec<_> = self
+            .secondary_units(unit.name())
+            .filter_map(|secondary| match secondary.unit_id().kind() {
+                AnyKind::Secondary(kind) => Some(SecondaryUnitDescription {
+                    kind,
+                    name: secondary.name().clone(),
+                    source: secondary.ident().pos.source.clone(),
+                    pos: secondary.ident().pos.clone(),
+                }),
+                AnyKind::Primary(_) => None,
+            })
+            .collect();
+        secondary_units.sort_by_key(|unit| unit.name.name_utf8());
+
+        Some(UnitDescription {
+            library_name: self.name.clone(),
+            kind,
+            name: unit.name().clone(),
+            source: unit.ident().pos.source.clone(),
+            pos: unit.ident().pos.clone(),
+            secondary_units,
+        })
+    }
+}
+
 /// Contains the entire design state.
 ///
 /// Besides all loaded libraries and design units, `DesignRoot` also keeps track of
@@ -290,7 +515,31 @@ pub struct DesignRoot {
     pub(super) universal: Option<UniversalTypes>,
     pub(super) standard_types: Option<StandardTypes>,
     pub(super) std_ulogic: Option<EntityId>,
+    // Do not warn about case statements on std_logic that do not cover all values
+    pub(super) ignore_incomplete_std_logic_case: bool,
+    // Warn about assertions whose condition is always true or always false
+    pub(super) lint_static_assertions: bool,
+    // Warn about if and while conditions and case selectors that are constant
+    pub(super) lint_constant_conditions: bool,
+    // Warn about functions that can reach their end without a return statement
+    pub(super) lint_missing_return: bool,
+    // Warn about variables that may be read before they are assigned
+    pub(super) lint_uninitialized_variables: bool,
+    // The libraries whose units are checked for constructs that are not synthesizable
+    pub(super) synthesis_lint_libraries: FnvHashSet<Symbol>,
+    // The libraries that are not maintained by the user, such as vendor libraries
+    pub(super) third_party_libraries: FnvHashSet<Symbol>,
+    // The source of the standard package that was added as a fallback, missing declarations
+    // of the names of std and ieee are not reported since they are most likely declared in
+    // the missing files of these libraries
+    fallback_standard: Option<Source>,
+    // The packages of std and ieee that declare each lower case name, only while the
+    // fallback standard package is used
+    standard_library_names: FnvHashMap<String, FnvHashSet<String>>,
     libraries: FnvHashMap<Symbol, Library>,
+    // The lower case names of the public symbols for workspace symbol queries,
+    // rebuilt when units are analyzed
+    symbol_index: Vec<(String, EntityId)>,
 
     // Arena storage of all declaration in the design
     pub(super) arenas: FinalArena,
@@ -303,9 +552,19 @@ pub struct DesignRoot {
     #[allow(clippy::type_complexity)]
     missing_unit: RwLock<FnvHashMap<(Symbol, Symbol, Option<Symbol>), FnvHashSet<UnitId>>>,
 
+    // The position where a unit uses another unit, used to report circular dependencies
+    // (user, used)  =>  position
+    use_positions: RwLock<FnvHashMap<(UnitId, UnitId), SrcPos>>,
+
     // Tracks which units have a "use library.all;" clause.
     // library name  =>  set(affected)
     users_of_library_all: RwLock<FnvHashMap<Symbol, FnvHashSet<UnitId>>>,
+
+    // Incremented by every analysis
+    revision: u64,
+
+    // The number of design units analyzed since the design root was created
+    num_analyzed_units: AtomicUsize,
 }
 
 impl DesignRoot {
@@ -316,15 +575,71 @@ impl DesignRoot {
             standard_arena: None,
             standard_types: None,
             std_ulogic: None,
+            ignore_incomplete_std_logic_case: false,
+            lint_static_assertions: false,
+            lint_constant_conditions: false,
+            lint_missing_return: false,
+            lint_uninitialized_variables: false,
+            synthesis_lint_libraries: FnvHashSet::default(),
+            third_party_libraries: FnvHashSet::default(),
+            fallback_standard: None,
+            standard_library_names: FnvHashMap::default(),
             symbols,
             arenas: FinalArena::default(),
             libraries: FnvHashMap::default(),
+            symbol_index: Vec::new(),
             users_of: RwLock::new(FnvHashMap::default()),
             missing_unit: RwLock::new(FnvHashMap::default()),
+            use_positions: RwLock::new(FnvHashMap::default()),
             users_of_library_all: RwLock::new(FnvHashMap::default()),
+            revision: 0,
+            num_analyzed_units: AtomicUsize::new(0),
         }
     }
 
+    /// Do not warn about case statements on std_logic selectors that do not cover all nine values.
+    /// Units that are already analyzed keep their diagnostics.
+    pub fn set_ignore_incomplete_std_logic_case(&mut self, ignore: bool) {
+        self.ignore_incomplete_std_logic_case = ignore;
+    }
+
+    /// Warn about assertions whose condition is a locally static `true`, or `false`
+    /// with a severity below error. Units that are already analyzed keep their diagnostics.
+    pub fn set_lint_static_assertions(&mut self, lint: bool) {
+        self.lint_static_assertions = lint;
+    }
+
+    /// Warn about if and while conditions that are built from literals alone or that compare
+    /// an object with itself, and about case selectors that are literals.
+    /// Units that are already analyzed keep their diagnostics.
+    pub fn set_lint_constant_conditions(&mut self, lint: bool) {
+        self.lint_constant_conditions = lint;
+    }
+
+    /// Warn about function bodies where a control path can reach the end without a return
+    /// statement. Units that are already analyzed keep their diagnostics.
+    pub fn set_lint_missing_return(&mut self, lint: bool) {
+        self.lint_missing_return = lint;
+    }
+
+    /// Warn about variables without an initial value that may be read before they are assigned.
+    /// Units that are already analyzed keep their diagnostics.
+    pub fn set_lint_uninitialized_variables(&mut self, lint: bool) {
+        self.lint_uninitialized_variables = lint;
+    }
+
+    /// Warn about constructs that are not synthesizable in the units of these libraries.
+    /// Units that are already analyzed keep their diagnostics.
+    pub fn set_synthesis_lint_libraries(&mut self, libraries: FnvHashSet<Symbol>) {
+        self.synthesis_lint_libraries = libraries;
+    }
+
+    /// Skip the checks that simulators do not enforce in the units of these libraries.
+    /// Units that are already analyzed keep their diagnostics.
+    pub fn set_third_party_libraries(&mut self, libraries: FnvHashSet<Symbol>) {
+        self.third_party_libraries = libraries;
+    }
+
     /// Create library if it does not exist or return existing
     fn get_or_create_library(&mut self, name: Symbol) -> &mut Library {
         match self.libraries.entry(name) {
@@ -363,6 +678,138 @@ impl DesignRoot {
         self.libraries.get(sym)
     }
 
+    /// The primary units of a library sorted by name, empty if there is no such library
+    pub fn units_in_library(&self, library_name: &Symbol) -> Vec<UnitDescription> {
+        let Some(library) = self.libraries.get(library_name) else {
+            return Vec::new();
+        };
+
+        let mut units: Vec<_> = library
+            .primary_units()
+            .filter_map(|unit| library.describe_unit(unit))
+            .collect();
+        units.sort_by_key(|unit| unit.name.name_utf8());
+        units
+    }
+
+    /// Find the primary unit `name` of a library such as `work.foo`
+    pub fn find_unit(&self, library_name: &Symbol, name: &Symbol) -> Option<UnitDescription> {
+        let library = self.libraries.get(library_name)?;
+        library.describe_unit(library.primary_unit(name)?)
+    }
+
+    /// The dependencies between all design units
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut nodes = Vec::new();
+        let mut dependencies = Vec::new();
+
+        for library in self.libraries.values() {
+            for unit in library.units() {
+                nodes.push(DependencyNode {
+                    unit_id: unit.unit_id().clone(),
+                    source: unit.ident().pos.source.clone(),
+                });
+            }
+        }
+
+        for (used, users) in self.users_of.read().iter() {
+            for user in users.iter() {
+                dependencies.push((user.clone(), used.clone()));
+            }
+        }
+
+        DependencyGraph::new(nodes, dependencies.into_iter())
+    }
+
+    /// All design units in an order where each unit comes after the units it depends on
+    pub fn compile_order(&self) -> Result<Vec<DependencyNode>, CycleError> {
+        let graph = self.dependency_graph();
+        let order = graph.compile_order()?;
+        Ok(order.into_iter().cloned().collect())
+    }
+
+    /// The primary units that are not used by any of the top-level `roots` given as library and
+    /// unit name. A unit is used when it is a dependency of a used unit, which covers entity and
+    /// configuration instantiations as well as use clauses. A component instantiation uses the
+    /// entity with the same name in the library of the instantiating unit, which is the default
+    /// binding. All secondary units of a used primary unit are used.
+    pub fn unused_units(&self, roots: &[(Symbol, Symbol)]) -> Vec<UnitDescription> {
+        // user  =>  set(used)
+        let mut dependencies: FnvHashMap<UnitId, Vec<UnitId>> = FnvHashMap::default();
+        for (used, users) in self.users_of.read().iter() {
+            for user in users.iter() {
+                dependencies
+                    .entry(user.clone())
+                    .or_default()
+                    .push(used.clone());
+            }
+        }
+
+        let mut used: FnvHashSet<UnitId> = FnvHashSet::default();
+        let mut pending: Vec<UnitId> = roots
+            .iter()
+            .filter_map(|(library_name, name)| self.libraries.get(library_name)?.primary_unit(name))
+            .map(|unit| unit.unit_id().clone())
+            .collect();
+
+        while let Some(unit_id) = pending.pop() {
+            if !used.insert(unit_id.clone()) {
+                continue;
+            }
+
+            pending.extend(dependencies.get(&unit_id).into_iter().flatten().cloned());
+
+            let Some(library) = self.libraries.get(unit_id.library_name()) else {
+                continue;
+            };
+
+            if let AnyKind::Primary(_) = unit_id.kind() {
+                pending.extend(
+                    library
+                        .secondary_units(unit_id.primary_name())
+                        .map(|unit| unit.unit_id().clone()),
+                );
+            }
+
+            if let Some(unit) = library.units.get(unit_id.key()) {
+                let mut searcher = FindComponentInstances::default();
+                let _ = unit
+                    .unit
+                    .expect_analyzed()
+                    .search(&unit.tokens, &mut searcher);
+
+                for component_id in searcher.result {
+                    let component = self.get_ent(component_id);
+                    let (AnyEntKind::Component(_), Designator::Identifier(name)) =
+                        (component.kind(), component.designator())
+                    else {
+                        continue;
+                    };
+                    if let Some(entity) = library
+                        .primary_unit(name)
+                        .filter(|unit| unit.kind() == AnyKind::Primary(PrimaryKind::Entity))
+                    {
+                        pending.push(entity.unit_id().clone());
+                    }
+                }
+            }
+        }
+
+        let mut library_names: Vec<_> = self.libraries.keys().collect();
+        library_names.sort_by_key(|library_name| library_name.name_utf8());
+
+        library_names
+            .into_iter()
+            .flat_map(|library_name| {
+                self.units_in_library(library_name)
+                    .into_iter()
+                    .filter(|unit| {
+                        !used.contains(&UnitId::primary(library_name, unit.kind, &unit.name))
+                    })
+            })
+            .collect()
+    }
+
     pub(crate) fn get_design_entity<'a>(
         &'a self,
         library_name: &Symbol,
@@ -398,6 +845,92 @@ impl DesignRoot {
             .add_design_file(design_file);
     }
 
+    /// Add the fallback standard package given by `parse` to library std when no other file
+    /// of the library declares package standard, the fallback is removed again once a file
+    /// declares it. Missing declarations of the names of std and ieee are not reported while
+    /// the fallback is used.
+    pub fn update_fallback_standard_package(
+        &mut self,
+        parse: impl FnOnce() -> (Source, DesignFile),
+    ) {
+        let std_sym = self.symbol_utf8("std");
+        let standard_sym = self.symbol_utf8("standard");
+        let has_standard = self.libraries.get(&std_sym).is_some_and(|library| {
+            library.declares_primary_unit_outside(&standard_sym, self.fallback_standard.as_ref())
+        });
+        match self.fallback_standard.take() {
+            Some(fallback) if has_standard => {
+                self.remove_source(std_sym, &fallback);
+                self.standard_library_names.clear();
+            }
+            Some(fallback) => self.fallback_standard = Some(fallback),
+            None if has_standard => {}
+            None => {
+                let (source, design_file) = parse();
+                self.add_source_file(std_sym, &source, design_file, &[]);
+                self.fallback_standard = Some(source);
+                for line in STANDARD_LIBRARY_NAMES.lines() {
+                    if let Some((package, name)) = line.split_once(' ') {
+                        self.standard_library_names
+                            .entry(name.to_owned())
+                            .or_default()
+                            .insert(package.to_owned());
+                    }
+                }
+            }
+        }
+    }
+
+    /// True if the fallback standard package is used
+    pub fn uses_fallback_standard_package(&self) -> bool {
+        self.fallback_standard.is_some()
+    }
+
+    /// Add the design units parsed from `source` along with the diagnostics of parsing it.
+    /// The file is listed by [`DesignRoot::files`] even if no design unit could be parsed.
+    /// A source that is already added to another library is not added again so that its
+    /// design units are only analyzed once, in the first library.
+    pub fn add_source_file(
+        &mut self,
+        library_name: Symbol,
+        source: &Source,
+        design_file: DesignFile,
+        parser_diagnostics: &[Diagnostic],
+    ) {
+        if self
+            .library_of_source(source)
+            .is_some_and(|library| library.name() != &library_name)
+        {
+            return;
+        }
+        self.get_or_create_library(library_name).add_source_file(
+            source,
+            design_file,
+            parser_diagnostics,
+        );
+    }
+
+    /// The parse and analysis status of every design file sorted by library and file name.
+    /// The fallback standard package is not a design file of the project.
+    pub fn files(&self) -> Vec<FileStatus> {
+        let mut files: Vec<_> = self
+            .libraries
+            .values()
+            .flat_map(|library| {
+                library
+                    .files
+                    .iter()
+                    .filter(|(source, _)| Some(*source) != self.fallback_standard.as_ref())
+                    .map(|(source, record)| library.file_status(source, record))
+            })
+            .collect();
+        files.sort_by(|lhs, rhs| {
+            (lhs.library_name.name_utf8(), lhs.source.file_name())
+                .cmp(&(rhs.library_name.name_utf8(), rhs.source.file_name()))
+        });
+        files
+    }
+
     pub fn remove_source(&mut self, library_name: Symbol, source: &Source) {
         self.get_or_create_library(library_name)
             .remove_source(source);
@@ -451,13 +984,44 @@ impl DesignRoot {
         }
     }
 
+    /// The definition of the item at the cursor.
+    /// At a reference to an alias it is the alias declaration,
+    /// at the alias declaration itself it is the definition of the aliased entity.
+    pub fn find_definition<'a>(&'a self, source: &Source, cursor: Position) -> Option<EntRef<'a>> {
+        let (pos, ent) = self.item_at_cursor(source, cursor)?;
+        if ent.decl_pos() == Some(&pos) {
+            if let Some(aliased) = ent.aliased() {
+                return self.find_definition_of(aliased);
+            }
+        }
+        self.find_definition_of(ent)
+    }
+
+    /// Entities that implement the declaration:
+    /// architectures and components of an entity, the entity of a component,
+    /// the body of a package or protected type, the body of a subprogram declaration
+    /// and the full declaration of a deferred constant
     pub fn find_implementation<'a>(&'a self, ent: EntRef<'a>) -> Vec<EntRef<'a>> {
+        if matches!(ent.kind(), AnyEntKind::Design(Design::Package(..)))
+            || ent.is_protected_type()
+            || ent.is_subprogram_decl()
+            || ent.kind().is_deferred_constant()
+        {
+            // The body is always analyzed within the same library as the declaration
+            let Some(library_name) = ent.library_name() else {
+                return Vec::default();
+            };
+            let mut searcher = FindAllEnt::new(self, |other| other.is_declared_by(ent));
+            let _ = self.search_library(library_name, &mut searcher);
+            return searcher.result;
+        }
+
         if let Designator::Identifier(ident) = ent.designator() {
             if let Some(library_name) = ent.library_name() {
                 match ent.kind() {
-                    // Find entity with same name as component in the library
+                    // The entity of the default binding of the component
                     AnyEntKind::Component(_) => {
-                        if let Some(design) = self.get_design_entity(library_name, ident) {
+                        if let Some(design) = self.default_binding(ent, library_name, ident) {
                             return vec![design.into()];
                         }
                     }
@@ -489,11 +1053,76 @@ impl DesignRoot {
         Vec::default()
     }
 
+    /// The entity that a component is bound to when there is no configuration, the entity with
+    /// the same name in the library of the component or else the one that is visible where
+    /// the component is declared, either directly or within a visible library
+    fn default_binding<'a>(
+        &'a self,
+        component: EntRef<'a>,
+        library_name: &Symbol,
+        ident: &Symbol,
+    ) -> Option<DesignEnt<'a>> {
+        if let Some(design) = self.get_design_entity(library_name, ident) {
+            return Some(design);
+        }
+
+        let mut parent = component.parent;
+        let visibility = loop {
+            let ent = parent?;
+            match ent.kind() {
+                AnyEntKind::Design(Design::Entity(visibility, _))
+                | AnyEntKind::Design(Design::Package(visibility, _))
+                | AnyEntKind::Design(Design::UninstPackage(visibility, _)) => break visibility,
+                // The architecture has the visibility of its entity
+                AnyEntKind::Design(Design::Architecture(entity)) => parent = Some((*entity).into()),
+                _ => parent = ent.parent,
+            }
+        };
+        visibility
+            .visible()
+            .find_map(|visible| match visible.kind() {
+                AnyEntKind::Design(Design::Entity(..)) => (visible.designator()
+                    == &Designator::Identifier(ident.clone()))
+                    .then(|| DesignEnt::from_any(visible))
+                    .flatten(),
+                AnyEntKind::Library => self.get_design_entity(visible.library_name()?, ident),
+                _ => None,
+            })
+    }
+
+    /// The positions of the implementations of the declaration at the cursor
+    pub fn find_implementations(&self, source: &Source, cursor: Position) -> Vec<SrcPos> {
+        let Some(ent) = self.search_reference(source, cursor) else {
+            return Vec::default();
+        };
+        self.find_implementation(ent.declaration())
+            .into_iter()
+            .filter_map(|ent| ent.decl_pos().cloned())
+            .collect()
+    }
+
     #[cfg(test)]
     pub fn search_reference_pos(&self, source: &Source, cursor: Position) -> Option<SrcPos> {
         self.search_reference(source, cursor)
             .and_then(|ent| ent.decl_pos().cloned())
     }
+
+    /// The declaration of the entity as written in the source
+    fn declaration_text(&self, ent: EntRef) -> Option<String> {
+        let decl_pos = ent.decl_pos()?;
+        let mut searcher = DeclarationText::new(ent.id());
+        for unit in self.units_by_source(decl_pos.source()) {
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+            if searcher.result.is_some() {
+                break;
+            }
+        }
+        searcher.result
+    }
+
     /// Search for the declaration at decl_pos and format it
     pub fn format_declaration(&self, ent: &AnyEnt) -> Option<String> {
         if let AnyEntKind::Library = ent.kind() {
@@ -511,13 +1140,192 @@ impl DesignRoot {
         }
     }
 
+    /// Information about the item at the cursor suitable for a hover.
+    /// The signature is the declaration of the item as written in the source such as
+    /// `signal clk : std_logic;`, other declarations such as ports and design units, or
+    /// implicit declarations are formatted instead.
+    pub fn hover<'a>(&'a self, source: &Source, cursor: Position) -> Option<HoverInfo<'a>> {
+        let ent = self.search_reference(source, cursor)?.declaration();
+        let signature = self
+            .declaration_text(ent)
+            .or_else(|| self.format_declaration(ent))?;
+
+        Some(HoverInfo {
+            ent,
+            kind: ent.kind().describe(),
+            signature,
+            decl_pos: ent.decl_pos(),
+            doc_comment: self.doc_comment(ent),
+        })
+    }
+
+    /// The resolved type of the innermost expression or name that covers the cursor
+    pub fn type_at<'a>(&'a self, source: &Source, cursor: Position) -> Option<TypeDescription<'a>> {
+        let mut innermost: Option<(crate::data::Range, EntityId)> = None;
+        for unit in self.units_by_source(source) {
+            let data = unit.unit.expect_analyzed();
+            for (pos, id) in data.result().expression_types.iter() {
+                if pos.source() != source || !pos.range().contains(cursor) {
+                    continue;
+                }
+                // A later type of the same expression is the one after disambiguation
+                let is_inner = innermost
+                    .is_none_or(|(range, _)| range.start <= pos.start() && pos.end() <= range.end);
+                if is_inner {
+                    innermost = Some((pos.range(), *id));
+                }
+            }
+        }
+        let type_mark = TypeEnt::from_any(self.get_ent(innermost?.1))?;
+        Some(self.describe_type(type_mark))
+    }
+
+    fn describe_type<'a>(&'a self, type_mark: TypeEnt<'a>) -> TypeDescription<'a> {
+        let base_type = type_mark.base_type();
+        let (element_type, index_constraint) =
+            if let Type::Array { elem_type, .. } = base_type.kind() {
+                (Some((*elem_type).into()), self.index_constraint(type_mark))
+            } else {
+                (None, None)
+            };
+        TypeDescription {
+            type_mark: type_mark.into(),
+            is_subtype: matches!(type_mark.kind(), Type::Subtype(..)),
+            is_alias: matches!(type_mark.kind(), Type::Alias(..)),
+            base_type: base_type.into(),
+            element_type,
+            index_constraint,
+        }
+    }
+
+    /// The index constraint of the first declaration of the array type, its subtypes and aliases
+    /// that has one
+    fn index_constraint(&self, mut typ: TypeEnt) -> Option<String> {
+        loop {
+            match typ.kind() {
+                Type::Alias(aliased) => typ = *aliased,
+                Type::Subtype(..) | Type::Array { .. } => {
+                    let decl_pos = typ.decl_pos()?;
+                    let mut searcher = FindIndexConstraint::new(typ.id());
+                    let _ = self.search_source(decl_pos.source(), &mut searcher);
+                    if let Some(constraint) = searcher.result? {
+                        return Some(constraint);
+                    }
+                    let Type::Subtype(subtype) = typ.kind() else {
+                        return None;
+                    };
+                    typ = subtype.type_mark();
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// True if the type is an array type, or a subtype or alias of one, without an index
+    /// constraint so that the bounds of an object of the type come from its value or actual
+    pub(crate) fn is_unconstrained_array(&self, mut typ: TypeEnt) -> bool {
+        loop {
+            let searcher = typ.decl_pos().map(|decl_pos| {
+                let mut searcher = FindIndexConstraint::new(typ.id());
+                let _ = self.search_source(decl_pos.source(), &mut searcher);
+                searcher
+            });
+            match typ.kind() {
+                Type::Alias(aliased) => typ = *aliased,
+                Type::Subtype(subtype) => {
+                    if searcher.is_some_and(|searcher| matches!(searcher.result, Some(Some(_)))) {
+                        return false;
+                    }
+                    typ = subtype.type_mark();
+                }
+                Type::Array { .. } => {
+                    return searcher.is_some_and(|searcher| searcher.is_unbounded)
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    /// The comments above the declaration of the entity and on the same line as its designator
+    pub fn doc_comment(&self, ent: EntRef) -> Option<String> {
+        let decl_pos = ent.decl_pos()?;
+        self.units_by_source(decl_pos.source())
+            .find_map(|unit| doc_comment(&unit.tokens, decl_pos))
+    }
+
     /// Search for all references to the declaration at decl_pos
     pub fn find_all_references(&self, ent: EntRef) -> Vec<SrcPos> {
+        self.find_all_references_with_kind(ent)
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Like find_all_references but also classifies each position as the declaration,
+    /// a use or an end label
+    pub fn find_all_references_with_kind(&self, ent: EntRef) -> Vec<(SrcPos, ReferenceKind)> {
         let mut searcher = FindAllReferences::new(self, ent);
         let _ = self.search(&mut searcher);
         searcher.references
     }
 
+    /// The positions where the signal declared at `decl` is driven, by assignments
+    /// or by being associated with an `out`, `inout` or `buffer` port of an instance.
+    /// Assignments to an element or a slice of the signal drive the whole signal.
+    pub fn signal_drivers(&self, decl: &SrcPos) -> Vec<SrcPos> {
+        self.signal_references(decl, |kind| {
+            matches!(kind, ReferenceKind::Write | ReferenceKind::ReadWrite)
+        })
+    }
+
+    /// The positions where the signal declared at `decl` is read,
+    /// including associations with `inout` ports of instances
+    pub fn signal_readers(&self, decl: &SrcPos) -> Vec<SrcPos> {
+        self.signal_references(decl, |kind| {
+            matches!(kind, ReferenceKind::Use | ReferenceKind::ReadWrite)
+        })
+    }
+
+    fn signal_references(
+        &self,
+        decl: &SrcPos,
+        is_kind: impl Fn(ReferenceKind) -> bool,
+    ) -> Vec<SrcPos> {
+        let Some(ent) = self.search_reference(decl.source(), decl.start()) else {
+            return Vec::new();
+        };
+        if !matches!(
+            ent.kind(),
+            AnyEntKind::Object(Object {
+                class: ObjectClass::Signal,
+                ..
+            })
+        ) {
+            return Vec::new();
+        }
+        self.find_all_references_with_kind(ent)
+            .into_iter()
+            .filter_map(|(pos, kind)| is_kind(kind).then_some(pos))
+            .collect()
+    }
+
+    /// The references to the item at the cursor within the same source only,
+    /// such as for highlighting the occurrences of the item at the cursor
+    pub fn document_references(
+        &self,
+        source: &Source,
+        cursor: Position,
+    ) -> Vec<(SrcPos, ReferenceKind)> {
+        let Some(ent) = self.search_reference(source, cursor) else {
+            return Vec::new();
+        };
+        let mut searcher = FindAllReferences::new(self, ent.declaration());
+        let _ = self.search_source(source, &mut searcher);
+        let mut references = searcher.references;
+        references.retain(|(pos, _)| pos.source() == source);
+        references
+    }
+
     pub fn public_symbols<'a>(&'a self) -> Box<dyn Iterator<Item = EntRef<'a>> + 'a> {
         Box::new(self.libraries.values().flat_map(|library| {
             std::iter::once(self.arenas.get(library.id)).chain(library.units.values().flat_map(
@@ -559,12 +1367,62 @@ impl DesignRoot {
         }))
     }
 
+    /// Find public symbols whose name matches the query, at most limit symbols are returned.
+    /// Symbols where the query is a prefix of the name are ranked first, then symbols where the query
+    /// is a substring and finally symbols where the characters of the query appear in order.
+    pub fn workspace_symbols<'a>(&'a self, query: &str, limit: usize) -> Vec<EntRef<'a>> {
+        let query = query.to_ascii_lowercase();
+        let mut symbols: Vec<_> = self
+            .symbol_index
+            .iter()
+            .filter_map(|(name, id)| {
+                let rank = SymbolMatch::of(name, &query)?;
+                Some((rank, name, self.get_ent(*id)))
+            })
+            .collect();
+
+        symbols.sort_by(|(rank1, name1, ent1), (rank2, name2, ent2)| {
+            (rank1, name1)
+                .cmp(&(rank2, name2))
+                .then_with(|| ent1.decl_pos().cmp(&ent2.decl_pos()))
+        });
+        symbols
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, ent)| ent)
+            .collect()
+    }
+
+    /// The lower case names of the public symbols that are declared in the source
+    fn build_symbol_index(&self) -> Vec<(String, EntityId)> {
+        self.public_symbols()
+            .filter(|ent| ent.decl_pos().is_some())
+            .filter_map(|ent| {
+                let name = match ent.designator() {
+                    Designator::Identifier(_) | Designator::Character(_) => {
+                        ent.designator().to_string()
+                    }
+                    Designator::OperatorSymbol(op) => op.to_string(),
+                    Designator::Anonymous(_) => return None,
+                };
+                Some((name.to_ascii_lowercase(), ent.id()))
+            })
+            .collect()
+    }
+
     pub fn document_symbols<'a>(
         &'a self,
         library_name: &Symbol,
         source: &Source,
     ) -> Vec<EntHierarchy<'a>> {
-        let mut searcher = FindAllEnt::new(self, |ent| ent.is_explicit() && !ent.is_anonymous());
+        let mut searcher = FindAllEnt::new(self, |ent| {
+            ent.is_explicit()
+                && (!ent.is_anonymous()
+                    || matches!(
+                        ent.kind(),
+                        AnyEntKind::Concurrent(Some(Concurrent::Block | Concurrent::Process))
+                    ))
+        });
 
         if let Some(library) = self.libraries.get(library_name) {
             if let Some(unit_ids) = library.units_by_source.get(source) {
@@ -597,6 +1455,131 @@ impl DesignRoot {
         }
     }
 
+    /// Compute the text edits needed to rename the declaration at decl_pos and all its references.
+    /// Renaming an entity also renames the components that are bound to it by name.
+    ///
+    /// Fails if new_name is not a legal identifier or if it would collide with another
+    /// declaration in the same declarative region or one that is visible at a reference.
+    pub fn rename(
+        &self,
+        decl_pos: &SrcPos,
+        new_name: &str,
+    ) -> Result<Vec<(SrcPos, String)>, Diagnostic> {
+        let ent = self
+            .search_reference(decl_pos.source(), decl_pos.start())
+            .ok_or_else(|| Diagnostic::error(decl_pos, "No declaration found"))?
+            .declaration();
+
+        if !matches!(ent.designator(), Designator::Identifier(_)) {
+            // Operator symbols and character literals have different representations
+            // at the declaration and the references
+            return Err(Diagnostic::error(
+                decl_pos,
+                format!("Cannot rename {}", ent.describe()),
+            ));
+        }
+
+        let new_designator = self
+            .parse_identifier(new_name)
+            .map(Designator::Identifier)
+            .ok_or_else(|| {
+                Diagnostic::error(decl_pos, format!("'{new_name}' is not a legal identifier"))
+            })?;
+
+        let mut renamed = vec![ent];
+        if matches!(ent.kind(), AnyEntKind::Design(Design::Entity(..))) {
+            renamed.extend(
+                self.find_implementation(ent)
+                    .into_iter()
+                    .filter(|ent| matches!(ent.kind(), AnyEntKind::Component(_))),
+            );
+        }
+
+        let mut edits = Vec::new();
+        for ent in renamed {
+            self.check_rename_collision(ent, &new_designator, decl_pos)?;
+            edits.extend(
+                self.find_all_references(ent)
+                    .into_iter()
+                    .map(|pos| (pos, new_name.to_owned())),
+            );
+        }
+        edits.sort_by(|(pos1, _), (pos2, _)| pos1.cmp(pos2));
+        edits.dedup();
+        Ok(edits)
+    }
+
+    fn check_rename_collision(
+        &self,
+        ent: EntRef,
+        new_designator: &Designator,
+        decl_pos: &SrcPos,
+    ) -> Result<(), Diagnostic> {
+        let parent_id = ent.parent.map(|parent| parent.id());
+        let mut searcher = FindEnt::new(self, |other| {
+            other.id() != ent.id()
+                && other.parent.map(|parent| parent.id()) == parent_id
+                && other.designator() == new_designator
+        });
+        let _ = self.search(&mut searcher);
+
+        // A declaration of the new name in an enclosing or nested scope would change
+        // what the references bind to
+        let other = searcher.result.or_else(|| {
+            self.find_all_references(ent).into_iter().find_map(|pos| {
+                self.visible_declarations(pos.source(), pos.start())
+                    .into_iter()
+                    .find(|other| other.id() != ent.id() && other.designator() == new_designator)
+            })
+        });
+
+        if let Some(other) = other {
+            let mut diagnostic = Diagnostic::error(
+                decl_pos,
+                format!(
+                    "Cannot rename {} since it would collide with {}",
+                    ent.describe(),
+                    other.describe()
+                ),
+            );
+            if let Some(pos) = other.decl_pos() {
+                diagnostic.add_related(pos, "Previously defined here");
+            }
+            Err(diagnostic)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the symbol if name is a single basic or extended identifier
+    pub(crate) fn parse_identifier(&self, name: &str) -> Option<Symbol> {
+        let source = Source::inline(Path::new("identifier"), name);
+        let contents = source.contents();
+        let mut tokenizer = Tokenizer::new(&self.symbols, &source, ContentReader::new(&contents));
+
+        let token = tokenizer.pop().ok()??;
+        if tokenizer.pop().ok()?.is_some()
+            || token.comments.is_some()
+            || token.pos.start() != contents.start()
+            || token.pos.end() != contents.end()
+        {
+            return None;
+        }
+
+        match token.value {
+            Value::Identifier(symbol) if token.kind == Kind::Identifier => {
+                // The tokenizer accepts consecutive and trailing underscores
+                // in basic identifiers which are not legal VHDL
+                if !name.starts_with('\\') && (name.contains("__") || name.ends_with('_')) {
+                    None
+                } else {
+                    Some(symbol)
+                }
+            }
+            _ => None,
+        }
+    }
+
     #[cfg(test)]
     fn find_std_package(&self, symbol: &str) -> &AnyEnt {
         let std_lib = self.libraries.get(&self.symbol_utf8("std")).unwrap();
@@ -674,7 +1657,13 @@ impl DesignRoot {
         NotFound
     }
 
-    fn units_by_source<'a>(
+    /// Returns the library that the design units of the source have been added to.
+    pub(crate) fn library_of_source(&self, source: &Source) -> Option<&Library> {
+        self.libraries()
+            .find(|lib| lib.units_by_source.contains_key(source))
+    }
+
+    pub(crate) fn units_by_source<'a>(
         &'a self,
         source: &'a Source,
     ) -> impl Iterator<Item = &'a LockedUnit> + 'a {
@@ -723,16 +1712,18 @@ impl DesignRoot {
         unit_id: &UnitId,
         unit: &mut UnitWriteGuard,
         ctx: &dyn TokenAccess,
+        standard: VHDLStandard,
     ) {
         // All units reference the standard arena
         // @TODO keep the same ArenaId when re-using unit
         let arena = Arena::new(arena_id);
-        let context = AnalyzeContext::new(self, unit_id, &arena, ctx);
+        let context = AnalyzeContext::new(self, unit_id, &arena, ctx, standard);
+        self.num_analyzed_units.fetch_add(1, Ordering::Relaxed);
 
         let mut diagnostics = Vec::new();
         let mut has_circular_dependency = false;
 
-        let result = match unit.deref_mut() {
+        let mut result = match unit.deref_mut() {
             AnyDesignUnit::Primary(unit) => {
                 if let Err(err) = context.analyze_primary_unit(unit, &mut diagnostics) {
                     has_circular_dependency = true;
@@ -740,6 +1731,8 @@ impl DesignRoot {
                 };
 
                 AnalysisData {
+                    expression_types: context.expression_types.take(),
+                    calls: context.calls.take(),
                     arena: arena.finalize(),
                     diagnostics,
                     has_circular_dependency,
@@ -755,6 +1748,8 @@ impl DesignRoot {
                 };
 
                 AnalysisData {
+                    expression_types: context.expression_types.take(),
+                    calls: context.calls.take(),
                     arena: arena.finalize(),
                     diagnostics,
                     has_circular_dependency,
@@ -762,9 +1757,77 @@ impl DesignRoot {
             }
         };
 
+        if self.fallback_standard.is_some() {
+            let used_packages = self.used_packages(unit_id, unit);
+            result.diagnostics.retain(|diagnostic| {
+                !self.is_missing_standard_library_name(diagnostic, &used_packages)
+            });
+        }
+
         unit.finish(result);
     }
 
+    /// The lower case `library.unit` names of the units that the use clauses and context
+    /// references of the unit and its primary unit refer to
+    fn used_packages(&self, unit_id: &UnitId, unit: &AnyDesignUnit) -> FnvHashSet<String> {
+        let primary_id = match unit_id.kind() {
+            AnyKind::Secondary(SecondaryKind::Architecture) => Some(UnitId::primary(
+                unit_id.library_name(),
+                PrimaryKind::Entity,
+                unit_id.primary_name(),
+            )),
+            AnyKind::Secondary(SecondaryKind::PackageBody) => Some(UnitId::package(
+                unit_id.library_name(),
+                unit_id.primary_name(),
+            )),
+            AnyKind::Primary(_) => None,
+        };
+        let primary = primary_id
+            .and_then(|primary_id| self.get_unit(&primary_id))
+            .and_then(|primary| primary.unit.get());
+
+        let mut used = FnvHashSet::default();
+        let context_clauses = std::iter::once(unit.context_clause())
+            .chain(primary.as_ref().map(|primary| primary.context_clause()));
+        for item in context_clauses.flatten().flatten() {
+            let name_list = match item {
+                ContextItem::Use(use_clause) => &use_clause.name_list,
+                ContextItem::Context(context) => &context.name_list,
+                ContextItem::Library(_) => continue,
+            };
+            for name in name_list.items.iter() {
+                if let Some((library_name, unit_name)) = library_unit_prefix(&name.item) {
+                    used.insert(format!("{library_name}.{unit_name}").to_lowercase());
+                }
+            }
+        }
+        used
+    }
+
+    /// True for a missing declaration of std or ieee, of a unit of these libraries or of a name
+    /// that is declared by one of the packages of these libraries that the unit uses
+    fn is_missing_standard_library_name(
+        &self,
+        diagnostic: &Diagnostic,
+        used_packages: &FnvHashSet<String>,
+    ) -> bool {
+        if diagnostic.code != Some(ErrorCode::MissingDecl) {
+            return false;
+        }
+        let name = diagnostic.pos.text().to_lowercase();
+        if name == "std" || name == "ieee" {
+            return true;
+        }
+        let is_used = |package: &String| used_packages.contains(package);
+        ["std", "ieee"]
+            .iter()
+            .any(|library_name| is_used(&format!("{library_name}.{name}")))
+            || self
+                .standard_library_names
+                .get(&name)
+                .is_some_and(|packages| packages.iter().any(is_used))
+    }
+
     pub(super) fn get_analysis<'a>(&self, locked_unit: &'a LockedUnit) -> UnitReadGuard<'a> {
         match locked_unit.unit.entry() {
             AnalysisEntry::Vacant(mut unit) => {
@@ -773,6 +1836,7 @@ impl DesignRoot {
                     locked_unit.unit_id(),
                     &mut unit,
                     &locked_unit.tokens,
+                    locked_unit.standard,
                 );
                 unit.downgrade()
             }
@@ -817,17 +1881,54 @@ impl DesignRoot {
             }
         }
 
+        if let Some(use_pos) = use_pos {
+            self.use_positions
+                .write()
+                .insert((user.clone(), unit_id.clone()), use_pos.clone());
+        }
+
         let mut affected = FnvHashSet::default();
         affected.insert(user.clone());
         let all_affected = get_all_affected(&users_of, affected);
 
         if all_affected.contains(unit_id) {
-            Err(CircularDependencyError::new(use_pos))
+            Err(self.circular_dependency_of(&users_of, use_pos, user, unit_id))
         } else {
             Ok(())
         }
     }
 
+    /// The error of user using unit_id at use_pos when unit_id depends on user
+    pub(super) fn circular_dependency(
+        &self,
+        use_pos: Option<&SrcPos>,
+        user: &UnitId,
+        unit_id: &UnitId,
+    ) -> CircularDependencyError {
+        self.circular_dependency_of(&self.users_of.read(), use_pos, user, unit_id)
+    }
+
+    fn circular_dependency_of(
+        &self,
+        users_of: &FnvHashMap<UnitId, FnvHashSet<UnitId>>,
+        use_pos: Option<&SrcPos>,
+        user: &UnitId,
+        unit_id: &UnitId,
+    ) -> CircularDependencyError {
+        let cycle = dependency_cycle(users_of, user, unit_id).map(|units| {
+            let use_positions = self.use_positions.read();
+            // The first use is the position of the error itself
+            let uses = units
+                .windows(2)
+                .skip(1)
+                .filter_map(|pair| use_positions.get(&(pair[0].clone(), pair[1].clone())))
+                .cloned()
+                .collect();
+            DependencyCycle { units, uses }
+        });
+        CircularDependencyError::new(use_pos).with_cycle(cycle)
+    }
+
     /// Register a dependency of library unit for everything within library since .all was used
     pub(super) fn make_use_of_library_all(&self, user: &UnitId, library_name: &Symbol) {
         match self
@@ -939,6 +2040,9 @@ impl DesignRoot {
         let mut missing_unit = self.missing_unit.write();
 
         // Clean-up after removed units
+        self.use_positions
+            .write()
+            .retain(|(user, used), _| !removed.contains(user) && !removed.contains(used));
         for removed_unit in removed.iter() {
             users_of.remove(removed_unit);
             if let Some(library_all_affected) =
@@ -1010,7 +2114,13 @@ impl DesignRoot {
             &mut std_package.decl,
         ));
 
-        let context = AnalyzeContext::new(self, locked_unit.unit_id(), &arena, &locked_unit.tokens);
+        let context = AnalyzeContext::new(
+            self,
+            locked_unit.unit_id(),
+            &arena,
+            &locked_unit.tokens,
+            locked_unit.standard,
+        );
 
         let mut diagnostics = Vec::new();
         let root_scope = Scope::default();
@@ -1065,6 +2175,8 @@ impl DesignRoot {
             standard_pkg.set_kind(kind);
         }
 
+        let expression_types = context.expression_types.take();
+        let calls = context.calls.take();
         self.standard_pkg_id = Some(standard_pkg.id());
         let arena = arena.finalize();
         self.standard_arena = Some(arena.clone());
@@ -1073,6 +2185,8 @@ impl DesignRoot {
             arena,
             diagnostics,
             has_circular_dependency: false,
+            expression_types,
+            calls,
         };
 
         unit.finish(result);
@@ -1108,8 +2222,103 @@ impl DesignRoot {
         }
     }
 
-    // Returns the units that where re-analyzed
+    /// Analyze the design units that have been added since the last analysis.
+    /// Adding or removing a source only invalidates the units of that source and
+    /// the units that transitively depend on them, all other units keep their analysis result.
+    /// The diagnostics of all units are emitted, not only of the re-analyzed ones.
+    ///
+    /// Units are analyzed in parallel where each unit first waits for the analysis
+    /// of the units that it depends on. The diagnostics are sorted by file name and position
+    /// so that their order does not depend on the scheduling of the threads.
+    ///
+    /// Returns the units that where re-analyzed
     pub fn analyze(&mut self, diagnostics: &mut dyn DiagnosticHandler) -> Vec<UnitId> {
+        self.analyze_with_progress(diagnostics, None)
+    }
+
+    /// Same as [`DesignRoot::analyze`] but reports the phases of the analysis and
+    /// every re-analyzed unit to `progress`
+    pub fn analyze_with_progress(
+        &mut self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        progress: Option<&dyn AnalysisProgress>,
+    ) -> Vec<UnitId> {
+        let mut unsorted = Vec::new();
+        let units = self.analyze_units(&mut unsorted, progress);
+        // Stable sort to keep the order of diagnostics at the same position
+        unsorted.sort_by(|lhs, rhs| lhs.pos.cmp(&rhs.pos));
+        diagnostics.append(unsorted);
+        units
+    }
+
+    /// Parse the `changed` sources again and analyze the design units of them and the units
+    /// that transitively depend on them, all other units keep their analysis result.
+    /// Sources that are not added to a library are ignored. The parser must share the symbols
+    /// of the design root, each source is parsed with the VHDL revision of its library.
+    ///
+    /// The diagnostics are the full up-to-date set of the design, the parse diagnostics of
+    /// all added source files followed by the analysis diagnostics of all units.
+    ///
+    /// Returns the units that where re-analyzed
+    pub fn analyze_incremental(
+        &mut self,
+        changed: &[Source],
+        parser: &VHDLParser,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Vec<UnitId> {
+        for source in changed {
+            let Some(library) = self.libraries().find(|library| {
+                library.units_by_source.contains_key(source) || library.files.contains_key(source)
+            }) else {
+                continue;
+            };
+            let library_name = library.name().clone();
+            // The source is parsed again with the standard of its library it was added with
+            let standard = library
+                .files
+                .get(source)
+                .map(|record| record.standard)
+                .or_else(|| {
+                    self.units_by_source(source)
+                        .next()
+                        .map(|unit| unit.standard)
+                })
+                .unwrap_or(parser.standard);
+            let mut parser_diagnostics = Vec::new();
+            let design_file = parser
+                .with_standard(standard)
+                .parse_design_source(source, &mut parser_diagnostics);
+            self.remove_source(library_name.clone(), source);
+            self.add_source_file(library_name, source, design_file, &parser_diagnostics);
+        }
+
+        let mut unsorted: Vec<_> = self
+            .libraries
+            .values()
+            .flat_map(|library| library.files.values())
+            .flat_map(|record| record.parser_diagnostics.iter().cloned())
+            .collect();
+        let units = self.analyze_units(&mut unsorted, None);
+        unsorted.sort_by(|lhs, rhs| lhs.pos.cmp(&rhs.pos));
+        diagnostics.append(unsorted);
+        units
+    }
+
+    /// The number of design units that were analyzed since the design root was created,
+    /// a unit that is re-analyzed is counted again
+    pub fn num_analyzed_units(&self) -> usize {
+        self.num_analyzed_units.load(Ordering::Relaxed)
+    }
+
+    fn analyze_units(
+        &mut self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        progress: Option<&dyn AnalysisProgress>,
+    ) -> Vec<UnitId> {
+        if let Some(progress) = progress {
+            progress.phase(AnalysisPhase::Preparing);
+        }
+
         self.reset();
 
         let mut units = Vec::default();
@@ -1141,10 +2350,29 @@ impl DesignRoot {
 
         use rayon::prelude::*;
 
+        if let Some(progress) = progress {
+            progress.phase(AnalysisPhase::Analyzing);
+        }
+
         units.par_iter().for_each(|id| {
-            self.get_analysis(self.get_unit(id).unwrap());
+            if let Some(progress) = progress {
+                // A unit may already have been analyzed as a dependency of another unit,
+                // it is still reported once here to give one started and finished pair per unit
+                let library_name = id.library_name().name_utf8();
+                let unit_name = progress_unit_name(id);
+                progress.unit_started(&library_name, &unit_name);
+                self.get_analysis(self.get_unit(id).unwrap());
+                progress.unit_finished(&library_name, &unit_name);
+            } else {
+                self.get_analysis(self.get_unit(id).unwrap());
+            }
         });
 
+        self.revision += 1;
+        for library in self.libraries.values_mut() {
+            library.set_analyzed_revision(&units, self.revision);
+        }
+
         for library in self.libraries.values() {
             self.arenas.link(&library.arena);
             for unit in library.units.values() {
@@ -1153,8 +2381,8 @@ impl DesignRoot {
                 }
             }
         }
+        self.symbol_index = self.build_symbol_index();
 
-        // Emit diagnostics sorted within a file
         for library in self.libraries.values() {
             for unit_id in library.sorted_unit_ids() {
                 let unit = library.units.get(unit_id.key()).unwrap();
@@ -1162,6 +2390,10 @@ impl DesignRoot {
             }
         }
 
+        if let Some(progress) = progress {
+            progress.phase(AnalysisPhase::Finished);
+        }
+
         units
     }
 
@@ -1176,6 +2408,42 @@ impl DesignRoot {
     }
 }
 
+/// Returns the chain of units user -> unit_id -> ... -> user when unit_id depends on user
+fn dependency_cycle(
+    users_of: &FnvHashMap<UnitId, FnvHashSet<UnitId>>,
+    user: &UnitId,
+    unit_id: &UnitId,
+) -> Option<Vec<UnitId>> {
+    // Breadth first search through the units that depend on user
+    // remembering which unit each dependent unit was reached from
+    let mut used_by: FnvHashMap<&UnitId, &UnitId> = Default::default();
+    let mut queue = VecDeque::new();
+    queue.push_back(user);
+
+    while let Some(used) = queue.pop_front() {
+        for dependent in users_of.get(used).into_iter().flatten() {
+            if used_by.contains_key(dependent) {
+                continue;
+            }
+            used_by.insert(dependent, used);
+
+            if dependent == unit_id {
+                let mut cycle = vec![user.clone()];
+                let mut current = unit_id;
+                loop {
+                    cycle.push(current.clone());
+                    if current == user {
+                        return Some(cycle);
+                    }
+                    current = used_by[current];
+                }
+            }
+            queue.push_back(dependent);
+        }
+    }
+    None
+}
+
 fn get_all_affected(
     users_of: &FnvHashMap<UnitId, FnvHashSet<UnitId>>,
     mut affected: FnvHashSet<UnitId>,
@@ -1201,6 +2469,55 @@ fn get_all_affected(
     all_affected
 }
 
+/// How well a symbol name matches a workspace symbol query, better matches are ordered first
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum SymbolMatch {
+    Prefix,
+    Substring,
+    Subsequence,
+}
+
+impl SymbolMatch {
+    fn of(name: &str, query: &str) -> Option<SymbolMatch> {
+        if name.starts_with(query) {
+            Some(SymbolMatch::Prefix)
+        } else if name.contains(query) {
+            Some(SymbolMatch::Substring)
+        } else {
+            let mut chars = name.chars();
+            if query.chars().all(|q| chars.any(|c| c == q)) {
+                Some(SymbolMatch::Subsequence)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+pub struct HoverInfo<'a> {
+    pub ent: EntRef<'a>,
+    /// The kind of the item such as 'signal' or 'function'
+    pub kind: &'a str,
+    pub signature: String,
+    pub decl_pos: Option<&'a SrcPos>,
+    pub doc_comment: Option<String>,
+}
+
+/// The resolved type of an expression
+pub struct TypeDescription<'a> {
+    /// The type or subtype of the expression
+    pub type_mark: EntRef<'a>,
+    pub is_subtype: bool,
+    /// True if the type mark is an alias of a type
+    pub is_alias: bool,
+    pub base_type: EntRef<'a>,
+    /// The element type of an array type
+    pub element_type: Option<EntRef<'a>>,
+    /// The index constraint of an array type as written such as `(7 downto 0)`
+    /// or `(natural range <>)`
+    pub index_constraint: Option<String>,
+}
+
 pub struct EntHierarchy<'a> {
     pub ent: EntRef<'a>,
     pub children: Vec<EntHierarchy<'a>>,
@@ -1272,6 +2589,22 @@ fn public_symbols<'a>(ent: EntRef<'a>) -> Box<dyn Iterator<Item = EntRef<'a>> +
     }
 }
 
+/// The names declared by the packages of the libraries std and ieee other than std.standard,
+/// one package and name per line
+const STANDARD_LIBRARY_NAMES: &str = include_str!("standard_library_names.txt");
+
+/// The library and primary unit prefix of a selected name such as `ieee.numeric_std.all`
+fn library_unit_prefix(name: &Name) -> Option<(&Designator, &Designator)> {
+    match name {
+        Name::SelectedAll(prefix) => library_unit_prefix(&prefix.item),
+        Name::Selected(prefix, suffix) => match prefix.item {
+            Name::Designator(ref library_name) => Some((&library_name.item, &suffix.item.item)),
+            _ => library_unit_prefix(&prefix.item),
+        },
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
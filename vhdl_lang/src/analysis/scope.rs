@@ -30,7 +30,7 @@ impl<'a> ScopeInner<'a> {
     }
 
     pub fn into_visibility(self) -> Visibility<'a> {
-        self.region.visibility
+        self.region.into_visibility()
     }
 
     pub fn close(&self, diagnostics: &mut dyn DiagnosticHandler) {
@@ -172,7 +172,8 @@ impl<'a> ScopeInner<'a> {
                     }
                     Designator::Anonymous(_) => "No declaration of <anonymous>".to_owned(),
                 },
-            )),
+            )
+            .with_code(ErrorCode::MissingDecl)),
         }
     }
 
@@ -228,11 +229,8 @@ impl<'a> Scope<'a> {
             _ => RegionKind::Other,
         };
 
-        let extended_region = Region {
-            visibility: region.visibility.clone(),
-            entities: region.entities.clone(),
-            kind,
-        };
+        let extended_region =
+            Region::new(region.visibility.extended(), region.entities.clone(), kind);
 
         if let Some(parent) = parent {
             Scope::new(extended_region).with_parent(parent)
@@ -287,6 +285,34 @@ impl<'a> Scope<'a> {
             .make_all_potentially_visible(visible_pos, region);
     }
 
+    /// The position of an earlier use clause of this scope that made all names
+    /// within the region potentially visible
+    pub fn all_made_visible_by(&self, region: &'a Region<'a>) -> Option<SrcPos> {
+        self.0
+            .borrow()
+            .region
+            .visibility
+            .all_made_visible_by(region)
+            .cloned()
+    }
+
+    /// The position of an earlier use clause of this scope that made all of the
+    /// named entities potentially visible
+    pub fn made_visible_by(&self, named_entities: &NamedEntities<'a>) -> Option<SrcPos> {
+        let inner = self.0.borrow();
+        let visibility = &inner.region.visibility;
+        match named_entities {
+            NamedEntities::Single(ent) => visibility.made_visible_by(ent).cloned(),
+            NamedEntities::Overloaded(overloaded) => {
+                let mut pos = None;
+                for ent in overloaded.entities() {
+                    pos = Some(visibility.made_visible_by(ent.into())?);
+                }
+                pos.cloned()
+            }
+        }
+    }
+
     pub fn close(&self, diagnostics: &mut dyn DiagnosticHandler) {
         self.0.as_ref().borrow().close(diagnostics)
     }
@@ -363,7 +389,8 @@ impl Diagnostic {
         pos: &SrcPos,
         prev_pos: Option<&SrcPos>,
     ) -> Diagnostic {
-        let mut diagnostic = Diagnostic::error(pos, format!("Duplicate declaration of '{name}'"));
+        let mut diagnostic = Diagnostic::error(pos, format!("Duplicate declaration of '{name}'"))
+            .with_code(ErrorCode::DuplicateDecl);
 
         if let Some(prev_pos) = prev_pos {
             diagnostic.add_related(prev_pos, "Previously defined here");
@@ -36,7 +36,8 @@ impl<'a> AnalyzeContext<'a> {
                     let mut diag = Diagnostic::error(
                         type_mark,
                         format!("Expected type, got {}", other.describe()),
-                    );
+                    )
+                    .with_code(ErrorCode::MismatchedKinds);
                     if let Some(pos) = other.decl_pos() {
                         diag.add_related(pos, "Defined here");
                     }
@@ -64,7 +65,8 @@ impl<'a> AnalyzeContext<'a> {
                     let mut diag = Diagnostic::error(
                         type_mark,
                         format!("Expected type, got {}", other.describe()),
-                    );
+                    )
+                    .with_code(ErrorCode::MismatchedKinds);
                     if let Some(pos) = other.decl_pos() {
                         diag.add_related(pos, "Defined here");
                     }
@@ -158,7 +160,8 @@ impl<'a> AnalyzeContext<'a> {
 
                         if !ent.is_procedure() {
                             let mut diagnostic =
-                                Diagnostic::error(&name.pos, "Invalid procedure call");
+                                Diagnostic::error(&name.pos, "Invalid procedure call")
+                                    .with_code(ErrorCode::InvalidCall);
                             for ent in names.sorted_entities() {
                                 if let Some(decl_pos) = ent.decl_pos() {
                                     diagnostic.add_related(
@@ -169,9 +172,12 @@ impl<'a> AnalyzeContext<'a> {
                             }
                             diagnostics.push(diagnostic);
                         } else if ent.is_uninst_subprogram_body() {
-                            diagnostics.error(
-                                &name.pos,
-                                format!("uninstantiated {} cannot be called", ent.describe()),
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &name.pos,
+                                    format!("uninstantiated {} cannot be called", ent.describe()),
+                                )
+                                .with_code(ErrorCode::InvalidCall),
                             )
                         }
                     }
@@ -197,18 +203,24 @@ impl<'a> AnalyzeContext<'a> {
                         diagnostics,
                     )?;
                 } else {
-                    diagnostics.push(Diagnostic::error(
-                        &name.pos,
-                        format!("{} is not a procedure", resolved.describe_type()),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!("{} is not a procedure", resolved.describe_type()),
+                        )
+                        .with_code(ErrorCode::MismatchedKinds),
+                    );
                     self.analyze_assoc_elems(scope, parameters, diagnostics)?;
                 }
             }
             resolved => {
-                diagnostics.push(Diagnostic::error(
-                    &name.pos,
-                    format!("{} is not a procedure", resolved.describe_type()),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        &name.pos,
+                        format!("{} is not a procedure", resolved.describe_type()),
+                    )
+                    .with_code(ErrorCode::MismatchedKinds),
+                );
                 self.analyze_assoc_elems(scope, parameters, diagnostics)?;
             }
         };
@@ -254,7 +266,8 @@ impl<'a> ResolvedName<'a> {
         let mut error = Diagnostic::error(
             pos,
             format!("Expected {}, got {}", expected, self.describe()),
-        );
+        )
+        .with_code(ErrorCode::MismatchedKinds);
         if let Some(decl_pos) = self.decl_pos() {
             error.add_related(decl_pos, "Defined here");
         }
@@ -268,6 +281,7 @@ impl Diagnostic {
             pos,
             format!("{} does not match {}", desc, expected_type.describe()),
         )
+        .with_code(ErrorCode::TypeMismatch)
     }
 
     pub(crate) fn invalid_selected_name_prefix(
@@ -281,6 +295,7 @@ impl Diagnostic {
                 named_entity.describe(),
             )),
         )
+        .with_code(ErrorCode::MismatchedKinds)
     }
 
     pub(crate) fn no_declaration_within(
@@ -288,7 +303,7 @@ impl Diagnostic {
         pos: &SrcPos,
         suffix: &Designator,
     ) -> Diagnostic {
-        Diagnostic::error(
+        let mut diagnostic = Diagnostic::error(
             pos,
             format!(
                 "No declaration of '{}' within {}",
@@ -296,5 +311,16 @@ impl Diagnostic {
                 named_entity.describe(),
             ),
         )
+        .with_code(ErrorCode::MissingDecl);
+        // Point at the record type to show the elements that may be selected
+        if let AnyEntKind::Type(Type::Record(..)) = named_entity.kind() {
+            if let Some(decl_pos) = named_entity.decl_pos() {
+                diagnostic.add_related(
+                    decl_pos,
+                    format!("Record type '{}' declared here", named_entity.designator()),
+                );
+            }
+        }
+        diagnostic
     }
 }
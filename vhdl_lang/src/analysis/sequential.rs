@@ -113,22 +113,34 @@ impl<'a> AnalyzeContext<'a> {
                         if let Some(ref mut expression) = expression {
                             self.expr_with_ttyp(scope, ttyp, expression, diagnostics)?;
                         } else {
-                            diagnostics.error(
-                                &statement.statement.pos,
-                                "Functions cannot return without a value",
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &statement.statement.pos,
+                                    "Functions cannot return without a value",
+                                )
+                                .with_code(ErrorCode::IllegalReturn),
                             );
                         }
                     }
                     SequentialRoot::Procedure => {
                         if expression.is_some() {
-                            diagnostics.error(
-                                &statement.statement.pos,
-                                "Procedures cannot return a value",
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &statement.statement.pos,
+                                    "Procedures cannot return a value",
+                                )
+                                .with_code(ErrorCode::IllegalReturn),
                             );
                         }
                     }
                     SequentialRoot::Process => {
-                        diagnostics.error(&statement.statement.pos, "Cannot return from a process");
+                        diagnostics.push(
+                            Diagnostic::error(
+                                &statement.statement.pos,
+                                "Cannot return from a process",
+                            )
+                            .with_code(ErrorCode::IllegalReturn),
+                        );
                     }
                 }
             }
@@ -144,6 +156,7 @@ impl<'a> AnalyzeContext<'a> {
                 }
                 if let Some(expr) = timeout_clause {
                     self.expr_with_ttyp(scope, self.time(), expr, diagnostics)?;
+                    self.check_synthesis_wait_timeout(expr, diagnostics);
                 }
             }
             SequentialStatement::Assert(ref mut assert_stmt) => {
@@ -159,6 +172,7 @@ impl<'a> AnalyzeContext<'a> {
                 if let Some(expr) = severity {
                     self.expr_with_ttyp(scope, self.severity_level(), expr, diagnostics)?;
                 }
+                self.check_static_assertion(condition, severity.as_ref(), diagnostics);
             }
             SequentialStatement::Report(ref mut report_stmt) => {
                 let ReportStatement { report, severity } = report_stmt;
@@ -176,9 +190,12 @@ impl<'a> AnalyzeContext<'a> {
                 if let Some(loop_label) = loop_label {
                     self.check_loop_label(scope, parent, loop_label, diagnostics);
                 } else if !find_outer_loop(parent, None) {
-                    diagnostics.error(
-                        &statement.statement.pos,
-                        "Exit can only be used inside a loop",
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &statement.statement.pos,
+                            "Exit can only be used inside a loop",
+                        )
+                        .with_code(ErrorCode::IllegalLoopControl),
                     )
                 }
 
@@ -195,9 +212,12 @@ impl<'a> AnalyzeContext<'a> {
                 if let Some(loop_label) = loop_label {
                     self.check_loop_label(scope, parent, loop_label, diagnostics);
                 } else if !find_outer_loop(parent, None) {
-                    diagnostics.error(
-                        &statement.statement.pos,
-                        "Next can only be used inside a loop",
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &statement.statement.pos,
+                            "Next can only be used inside a loop",
+                        )
+                        .with_code(ErrorCode::IllegalLoopControl),
                     )
                 }
 
@@ -215,6 +235,7 @@ impl<'a> AnalyzeContext<'a> {
                 for conditional in conditionals {
                     let Conditional { condition, item } = conditional;
                     self.boolean_expr(scope, condition, diagnostics)?;
+                    self.check_constant_condition(condition, true, diagnostics);
                     self.analyze_sequential_part(scope, parent, item, diagnostics)?;
                 }
                 if let Some(else_item) = else_item {
@@ -223,17 +244,22 @@ impl<'a> AnalyzeContext<'a> {
             }
             SequentialStatement::Case(ref mut case_stmt) => {
                 let CaseStatement {
-                    is_matching: _,
+                    is_matching,
                     expression,
                     alternatives,
                     end_label_pos: _,
                 } = case_stmt;
                 let ctyp = as_fatal(self.expr_unambiguous_type(scope, expression, diagnostics))?;
+                self.check_constant_selector(expression, diagnostics);
                 for alternative in alternatives.iter_mut() {
                     let Alternative { choices, item } = alternative;
                     self.choice_with_ttyp(scope, ctyp, choices, diagnostics)?;
                     self.analyze_sequential_part(scope, parent, item, diagnostics)?;
                 }
+                if let (Some(ctyp), false) = (ctyp, *is_matching) {
+                    let choices = alternatives.iter().flat_map(|alt| alt.choices.iter());
+                    self.check_choice_coverage(expression, ctyp, choices, diagnostics);
+                }
             }
             SequentialStatement::Loop(ref mut loop_stmt) => {
                 let LoopStatement {
@@ -254,6 +280,7 @@ impl<'a> AnalyzeContext<'a> {
                     }
                     Some(IterationScheme::While(ref mut expr)) => {
                         self.boolean_expr(scope, expr, diagnostics)?;
+                        self.check_constant_condition(expr, false, diagnostics);
                         self.analyze_sequential_part(scope, parent, statements, diagnostics)?;
                     }
                     None => {
@@ -306,7 +333,8 @@ impl<'a> AnalyzeContext<'a> {
                 } = assign;
                 as_fatal(self.resolve_target(scope, target, AssignmentType::Signal, diagnostics))?;
             }
-            SequentialStatement::Null => {}
+            // The syntax error has already been reported
+            SequentialStatement::Null | SequentialStatement::Error => {}
         }
         Ok(())
     }
@@ -326,24 +354,33 @@ impl<'a> AnalyzeContext<'a> {
                 label.set_unique_reference(ent);
                 if matches!(ent.kind(), AnyEntKind::Sequential(Some(Sequential::Loop))) {
                     if !find_outer_loop(parent, Some(label.item.name())) {
-                        diagnostics.error(
-                            &label.item.pos,
-                            format!("Cannot be used outside of loop '{}'", ent.designator()),
+                        diagnostics.push(
+                            Diagnostic::error(
+                                &label.item.pos,
+                                format!("Cannot be used outside of loop '{}'", ent.designator()),
+                            )
+                            .with_code(ErrorCode::IllegalLoopControl),
                         );
                     }
                 } else {
-                    diagnostics.error(
-                        &label.item.pos,
-                        format!("Expected loop label, got {}", ent.describe()),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &label.item.pos,
+                            format!("Expected loop label, got {}", ent.describe()),
+                        )
+                        .with_code(ErrorCode::IllegalLoopControl),
                     );
                 }
             }
-            Ok(NamedEntities::Overloaded(_)) => diagnostics.error(
-                &label.item.pos,
-                format!(
-                    "Expected loop label, got overloaded name {}",
-                    &label.item.item
-                ),
+            Ok(NamedEntities::Overloaded(_)) => diagnostics.push(
+                Diagnostic::error(
+                    &label.item.pos,
+                    format!(
+                        "Expected loop label, got overloaded name {}",
+                        &label.item.item
+                    ),
+                )
+                .with_code(ErrorCode::IllegalLoopControl),
             ),
             Err(diag) => {
                 diagnostics.push(diag);
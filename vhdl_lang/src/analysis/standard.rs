@@ -449,6 +449,10 @@ impl<'a> AnalyzeContext<'a> {
                             iface: Some(ObjectInterface::Parameter(Mode::Out)),
                             subtype: Subtype::new(type_mark),
                             has_default: false,
+                            static_value: None,
+                            signal_kind: None,
+                            index_range: None,
+                            default_value: None,
                         }),
                     ),
                 ],
@@ -533,6 +537,10 @@ impl<'a> AnalyzeContext<'a> {
                     iface: Some(ObjectInterface::Parameter(Mode::InOut)),
                     subtype: Subtype::new(type_ent.to_owned()),
                     has_default: false,
+                    static_value: None,
+                    signal_kind: None,
+                    index_range: None,
+                    default_value: None,
                 }),
             )],
             None,
@@ -114,6 +114,16 @@ impl BaseSpecifier {
         }
     }
 
+    /// The base of the digits of the bit value such as 16 for `X`, `UX` and `SX`
+    pub fn radix(&self) -> u32 {
+        match self {
+            BaseSpecifier::B | BaseSpecifier::UB | BaseSpecifier::SB => 2,
+            BaseSpecifier::O | BaseSpecifier::UO | BaseSpecifier::SO => 8,
+            BaseSpecifier::X | BaseSpecifier::UX | BaseSpecifier::SX => 16,
+            BaseSpecifier::D => 10,
+        }
+    }
+
     /// Get the digits that are obtained by replacing `byte` with the
     /// appropriate sequence of characters as defined in the standard (section 15.8).
     ///
@@ -181,6 +191,11 @@ pub(crate) enum BitStringConversionError {
     /// The `usize` argument represent the position for the first illegal character in the
     /// bit_string's `value` string, (i.e. 2 for the example above)
     IllegalDecimalCharacter(usize),
+    /// A digit that is not valid for a binary or octal base (i.e. O"778")
+    /// Other characters such as 'Z' are not digits and are replicated by the expansion.
+    /// The `usize` argument is the position of the first illegal digit in the
+    /// bit_string's `value` string, (i.e. 2 for the example above)
+    IllegalDigit(usize),
     /// Signals that when converting a value and truncating, information would be lost.
     /// # Example
     /// 5B"111111" => The first '0' would be lost
@@ -199,6 +214,14 @@ pub(crate) enum BitStringConversionError {
 pub(crate) fn bit_string_to_string(
     bit_string: &BitString,
 ) -> Result<Latin1String, BitStringConversionError> {
+    if let Some(idx) =
+        bit_string.value.bytes.iter().position(|byte| {
+            byte.is_ascii_digit() && u32::from(byte - b'0') >= bit_string.base.radix()
+        })
+    {
+        return Err(BitStringConversionError::IllegalDigit(idx));
+    }
+
     // Simplifies the bit string by removing all occurrences of the underscore
     // character
     let simplified_value: Vec<u8> = bit_string
@@ -351,6 +374,29 @@ mod test_mod {
         );
     }
 
+    #[test]
+    fn test_illegal_digit() {
+        assert_eq!(
+            bit_string_to_string(&BitString::new(None, BaseSpecifier::O, "778")),
+            Err(BitStringConversionError::IllegalDigit(2))
+        );
+
+        assert_eq!(
+            bit_string_to_string(&BitString::new(Some(4), BaseSpecifier::SB, "1_2")),
+            Err(BitStringConversionError::IllegalDigit(2))
+        );
+
+        assert_eq!(
+            bit_string_to_string(&BitString::new(None, BaseSpecifier::UO, "7Z")),
+            Ok(Latin1String::from_utf8_unchecked("111ZZZ"))
+        );
+
+        assert_eq!(
+            bit_string_to_string(&BitString::new(None, BaseSpecifier::X, "9F")),
+            Ok(Latin1String::from_utf8_unchecked("10011111"))
+        );
+    }
+
     #[test]
     fn test_decimal_conversion() {
         let test_cases = [
@@ -68,7 +68,22 @@ impl<'a> AnalyzeContext<'a> {
                 );
                 let return_type =
                     self.resolve_type_mark(&subpgm_region, &mut fun.return_type, diagnostics);
-                (Signature::new(params?, Some(return_type?)), generic_map)
+                // The return identifier denotes the subtype of the returned value
+                if let (Some(ident), Ok(typ)) = (&mut fun.return_identifier, &return_type) {
+                    let subtype = TypeEnt::define_with_opt_id(
+                        self.arena,
+                        None,
+                        ident,
+                        ent,
+                        None,
+                        Type::Subtype(Subtype::new(*typ)),
+                    );
+                    subpgm_region.add(subtype.into(), diagnostics);
+                }
+                (
+                    Signature::new(params?, Some(return_type?)).with_pure(fun.pure),
+                    generic_map,
+                )
             }
             SubprogramSpecification::Procedure(procedure) => {
                 let generic_map = if let Some(header) = &mut procedure.header {
@@ -227,7 +242,8 @@ impl<'a> AnalyzeContext<'a> {
                 ) {
                     Ok(signature) => Ok(signature),
                     Err(err) => {
-                        let mut diag = Diagnostic::error(&instance.ident.tree.pos, err);
+                        let mut diag = Diagnostic::error(&instance.ident.tree.pos, err)
+                            .with_code(ErrorCode::InvalidInstantiation);
                         if let Some(pos) = uninstantiated_subprogram.decl_pos() {
                             diag.add_related(pos, "When instantiating this declaration");
                         }
@@ -263,12 +279,15 @@ impl<'a> AnalyzeContext<'a> {
                     .filter(|ent| ent.is_uninst_subprogram())
                     .collect_vec();
                 if choices.is_empty() {
-                    diagnostics.error(
-                        &instantiation.ident.tree.pos,
-                        format!(
-                            "{} does not denote an uninstantiated subprogram",
-                            name.describe()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &instantiation.ident.tree.pos,
+                            format!(
+                                "{} does not denote an uninstantiated subprogram",
+                                name.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::InvalidInstantiation),
                     );
                     return Err(EvalError::Unknown);
                 } else if choices.len() == 1 {
@@ -279,12 +298,15 @@ impl<'a> AnalyzeContext<'a> {
                     if let Some((key, pos)) = signature_key {
                         match overloaded.get(&SubprogramKey::Uninstantiated(key)) {
                             None => {
-                                diagnostics.error(
-                                    pos.clone(),
-                                    format!(
-                                        "Signature does not match the the signature of {}",
-                                        ent.describe()
-                                    ),
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        pos.clone(),
+                                        format!(
+                                            "Signature does not match the the signature of {}",
+                                            ent.describe()
+                                        ),
+                                    )
+                                    .with_code(ErrorCode::IllegalSignature),
                                 );
                                 return Err(EvalError::Unknown);
                             }
@@ -301,12 +323,15 @@ impl<'a> AnalyzeContext<'a> {
                     {
                         resolved_ent
                     } else {
-                        diagnostics.error(
-                            &instantiation.subprogram_name.pos,
-                            format!(
-                                "No uninstantiated subprogram exists with signature {}",
-                                key.describe()
-                            ),
+                        diagnostics.push(
+                            Diagnostic::error(
+                                &instantiation.subprogram_name.pos,
+                                format!(
+                                    "No uninstantiated subprogram exists with signature {}",
+                                    key.describe()
+                                ),
+                            )
+                            .with_code(ErrorCode::MissingDecl),
                         );
                         return Err(EvalError::Unknown);
                     }
@@ -316,7 +341,8 @@ impl<'a> AnalyzeContext<'a> {
                     let mut err = Diagnostic::error(
                         &instantiation.subprogram_name.pos,
                         format!("Ambiguous instantiation of '{}'", overloaded.designator()),
-                    );
+                    )
+                    .with_code(ErrorCode::Ambiguous);
                     for ent in choices {
                         if let Some(pos) = &ent.decl_pos {
                             err.add_related(pos.clone(), format!("Might be {}", ent.describe()))
@@ -327,12 +353,15 @@ impl<'a> AnalyzeContext<'a> {
                 }
             }
             _ => {
-                diagnostics.error(
-                    &instantiation.subprogram_name.pos,
-                    format!(
-                        "{} does not denote an uninstantiated subprogram",
-                        name.describe()
-                    ),
+                diagnostics.push(
+                    Diagnostic::error(
+                        &instantiation.subprogram_name.pos,
+                        format!(
+                            "{} does not denote an uninstantiated subprogram",
+                            name.describe()
+                        ),
+                    )
+                    .with_code(ErrorCode::InvalidInstantiation),
                 );
                 return Err(EvalError::Unknown);
             }
@@ -340,9 +369,12 @@ impl<'a> AnalyzeContext<'a> {
         if overloaded_ent.is_uninst_subprogram() {
             Ok(overloaded_ent)
         } else {
-            diagnostics.error(
-                &instantiation.subprogram_name.pos,
-                format!("{} cannot be instantiated", overloaded_ent.describe()),
+            diagnostics.push(
+                Diagnostic::error(
+                    &instantiation.subprogram_name.pos,
+                    format!("{} cannot be instantiated", overloaded_ent.describe()),
+                )
+                .with_code(ErrorCode::InvalidInstantiation),
             );
             Err(EvalError::Unknown)
         }
@@ -374,7 +406,8 @@ impl<'a> AnalyzeContext<'a> {
             None
         };
         if let Some(msg) = err_msg {
-            let mut err = Diagnostic::error(self.ctx.get_pos(instance.get_start_token()), msg);
+            let mut err = Diagnostic::error(self.ctx.get_pos(instance.get_start_token()), msg)
+                .with_code(ErrorCode::InvalidInstantiation);
             if let Some(pos) = ent.decl_pos() {
                 err.add_related(pos, format!("{} declared here", ent.describe()));
             }
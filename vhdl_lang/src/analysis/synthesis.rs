@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Lint of constructs that synthesis tools reject or silently ignore
+//!
+//! The checks are only done for the libraries that enable the synthesis lint in the
+//! configuration, so that testbench libraries can use these constructs freely.
+
+use super::analyze::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+
+impl<'a> AnalyzeContext<'a> {
+    /// A wait statement with a timeout clause such as `wait for 10 ns`
+    pub(crate) fn check_synthesis_wait_timeout(
+        &self,
+        timeout: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if self.synthesis_lint {
+            diagnostics.push(
+                Diagnostic::warning(
+                    &timeout.pos,
+                    "Wait statement with a timeout is not synthesizable",
+                )
+                .with_code(ErrorCode::SynthesisWaitFor),
+            );
+        }
+    }
+
+    /// The initial value of a signal declaration which is not a reset of the hardware
+    pub(crate) fn check_synthesis_signal_initial_value(
+        &self,
+        object_decl: &ObjectDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.synthesis_lint || object_decl.class != ObjectClass::Signal {
+            return;
+        }
+        if let Some(ref expr) = object_decl.expression {
+            diagnostics.push(
+                Diagnostic::warning(
+                    &expr.pos,
+                    format!(
+                        "Initial value of signal '{}' is not a reset value in synthesis",
+                        object_decl.ident.tree.item
+                    ),
+                )
+                .with_code(ErrorCode::SynthesisInitialValue),
+            );
+        }
+    }
+
+    /// The `after` delay of a waveform element which is ignored by synthesis
+    pub(crate) fn check_synthesis_delay(
+        &self,
+        delay: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if self.synthesis_lint {
+            diagnostics.push(
+                Diagnostic::warning(&delay.pos, "Delay is ignored by synthesis")
+                    .with_code(ErrorCode::SynthesisDelay),
+            );
+        }
+    }
+
+    /// A shared variable that is not of a protected type, only legal before VHDL-2002
+    pub(crate) fn check_synthesis_shared_variable(
+        &self,
+        object_decl: &ObjectDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if self.synthesis_lint {
+            diagnostics.push(
+                Diagnostic::warning(
+                    &object_decl.subtype_indication.type_mark.pos,
+                    format!(
+                        "Shared variable '{}' of a non-protected type is not synthesizable",
+                        object_decl.ident.tree.item
+                    ),
+                )
+                .with_code(ErrorCode::SynthesisSharedVariable),
+            );
+        }
+    }
+
+    /// Division, `mod` and `rem` are only synthesized for constant divisors that are a power of 2
+    pub(crate) fn check_synthesis_division(
+        &self,
+        op: &WithPos<WithRef<Operator>>,
+        divisor: &WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.synthesis_lint
+            || !matches!(op.item.item, Operator::Div | Operator::Mod | Operator::Rem)
+        {
+            return;
+        }
+
+        // Overflows are reported when the expression is analyzed
+        let is_synthesizable = match self.evaluate_static(divisor, &mut NullDiagnostics) {
+            Some(Value::Integer(value)) => value > 0 && (value as u64).is_power_of_two(),
+            Some(_) => true,
+            // The value of a generic or of a constant that cannot be evaluated is not known
+            None => self.is_constant_expression(&divisor.item),
+        };
+        if !is_synthesizable {
+            diagnostics.push(
+                Diagnostic::warning(
+                    &divisor.pos,
+                    format!(
+                        "Operator '{}' is only synthesizable with a constant divisor that is a power of 2",
+                        op.item.item
+                    ),
+                )
+                .with_code(ErrorCode::SynthesisDivision),
+            );
+        }
+    }
+
+    fn is_constant_expression(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Literal(..) => true,
+            Expression::Name(name) => match name.as_ref() {
+                Name::Designator(designator) => designator.reference.get().is_some_and(|id| {
+                    matches!(
+                        self.arena.get(id).kind(),
+                        AnyEntKind::Object(Object {
+                            class: ObjectClass::Constant,
+                            ..
+                        }) | AnyEntKind::DeferredConstant(..)
+                    )
+                }),
+                // Attributes such as 'length of a constrained object are constant
+                Name::Attribute(..) => true,
+                _ => false,
+            },
+            Expression::Unary(_, expr) => self.is_constant_expression(&expr.item),
+            Expression::Binary(_, left, right) => {
+                self.is_constant_expression(&left.item) && self.is_constant_expression(&right.item)
+            }
+            Expression::Qualified(qexpr) => self.is_constant_expression(&qexpr.expr.item),
+            _ => false,
+        }
+    }
+}
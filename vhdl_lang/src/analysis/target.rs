@@ -46,22 +46,28 @@ impl<'a> AnalyzeContext<'a> {
             diagnostics,
         )?;
         if !is_valid_assignment_target(&object_name.base) {
-            diagnostics.push(Diagnostic::error(
-                target_pos,
-                format!(
-                    "{} may not be the target of an assignment",
-                    object_name.base.describe_class()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    target_pos,
+                    format!(
+                        "{} may not be the target of an assignment",
+                        object_name.base.describe_class()
+                    ),
+                )
+                .with_code(ErrorCode::ModeViolation),
+            );
         } else if !is_valid_assignment_type(&object_name.base, assignment_type) {
-            diagnostics.push(Diagnostic::error(
-                target_pos,
-                format!(
-                    "{} may not be the target of a {} assignment",
-                    object_name.base.describe_class(),
-                    assignment_type.to_str()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    target_pos,
+                    format!(
+                        "{} may not be the target of a {} assignment",
+                        object_name.base.describe_class(),
+                        assignment_type.to_str()
+                    ),
+                )
+                .with_code(ErrorCode::ModeViolation),
+            );
         }
         Ok(object_name.type_mark())
     }
@@ -84,9 +90,9 @@ impl AssignmentType {
     }
 }
 
-/// Check that the assignment target is a writable object and not constant or input only
+/// Check that the assignment target is a writable object and not constant, input only or linkage
 fn is_valid_assignment_target(base: &ObjectBase) -> bool {
-    base.class() != ObjectClass::Constant && !matches!(base.mode(), Some(Mode::In))
+    base.class() != ObjectClass::Constant && !matches!(base.mode(), Some(Mode::In | Mode::Linkage))
 }
 
 // Check that a signal is not the target of a variable assignment and vice-versa
@@ -0,0 +1,224 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn names_are_resolved_through_implicit_and_explicit_dereference() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type node_t;
+type node_ptr_t is access node_t;
+type node_t is record
+  value : integer;
+  link : node_ptr_t;
+end record;
+type vec_ptr_t is access integer_vector;
+
+procedure push(node : node_ptr_t) is
+begin
+end procedure;
+
+procedure proc is
+  variable head, tmp : node_ptr_t;
+  variable vec : vec_ptr_t := new integer_vector'(1, 2, 3);
+  variable v : integer;
+begin
+  v := head.all.value;
+  v := head.link.link.value;
+  v := head.all.link.all.value;
+  tmp := head.link;
+  head.all := tmp.all;
+  head.all.link := tmp;
+  v := vec(0);
+  v := vec.all(1);
+  vec.all(1) := 2;
+  v := vec.all'length;
+end procedure;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for occurrence in 2..=4 {
+        assert_eq!(
+            root.search_reference_pos(code.source(), code.s("value", occurrence).start()),
+            Some(code.s1("value").pos())
+        );
+    }
+}
+
+#[test]
+fn dereference_errors() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type node_t;
+type node_ptr_t is access node_t;
+type node_t is record
+  value : integer;
+  link : node_ptr_t;
+end record;
+type vec_ptr_t is access integer_vector;
+
+procedure push(node : node_ptr_t) is
+begin
+end procedure;
+
+procedure proc is
+  variable head : node_ptr_t;
+  variable v : integer;
+begin
+  v := head.all;
+  v := head.all.missing;
+  v := head.link.missing;
+  v := v.all;
+  head := head.all;
+end procedure;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("v := head.all").s1("head.all"),
+                "record type 'node_t' does not match integer type 'INTEGER'",
+            ),
+            Diagnostic::error(
+                code.s("missing", 1),
+                "No declaration of 'missing' within record type 'node_t'",
+            )
+            .related(
+                code.s1("node_t is record").s1("node_t"),
+                "Record type 'node_t' declared here",
+            ),
+            Diagnostic::error(
+                code.s("missing", 2),
+                "No declaration of 'missing' within record type 'node_t'",
+            )
+            .related(
+                code.s1("node_t is record").s1("node_t"),
+                "Record type 'node_t' declared here",
+            ),
+            Diagnostic::error(
+                code.s1("v := v.all").s("v", 2),
+                "variable 'v' of integer type 'INTEGER' cannot be accessed with .all",
+            ),
+            Diagnostic::error(
+                code.s1("head := head.all").s1("head.all"),
+                "record type 'node_t' does not match access type 'node_ptr_t'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn allocator_is_checked_against_designated_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type node_t;
+type node_ptr_t is access node_t;
+type node_t is record
+  value : integer;
+  link : node_ptr_t;
+end record;
+type vec_ptr_t is access integer_vector;
+
+procedure push(node : node_ptr_t) is
+begin
+end procedure;
+
+procedure proc is
+  variable head : node_ptr_t := new node_t'(value => 0, link => null);
+begin
+  head.link := new node_t;
+  push(new node_t'(1, head));
+  push(new node_t);
+
+  head := new integer'(0);
+  head := new integer;
+  head := new node_t'(value => 'a', link => null);
+  push(new integer'(0));
+end procedure;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("new integer'(0)"),
+                "allocator of integer type 'INTEGER' does not match access type 'node_ptr_t'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+            Diagnostic::error(
+                code.s1("new integer;").s1("new integer"),
+                "allocator of integer type 'INTEGER' does not match access type 'node_ptr_t'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+            Diagnostic::error(
+                code.s1("'a'"),
+                "character literal does not match integer type 'INTEGER'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+            Diagnostic::error(
+                code.s("new integer'(0)", 2),
+                "allocator of integer type 'INTEGER' does not match access type 'node_ptr_t'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+        ],
+    );
+}
+
+#[test]
+fn deallocate_requires_access_object() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type node_t;
+type node_ptr_t is access node_t;
+type node_t is record
+  value : integer;
+  link : node_ptr_t;
+end record;
+type vec_ptr_t is access integer_vector;
+
+procedure push(node : node_ptr_t) is
+begin
+end procedure;
+
+procedure proc is
+  variable head : node_ptr_t;
+  variable v : integer;
+begin
+  deallocate(head);
+  deallocate(head.link);
+  deallocate(v);
+end procedure;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("deallocate(v)").s1("deallocate"),
+            "Could not resolve call to 'deallocate'",
+        )
+        .related(
+            code.s1("node_ptr_t"),
+            "Does not match procedure DEALLOCATE[node_ptr_t]: type mismatch of argument 1, \
+             expected access type 'node_ptr_t' but got integer type 'INTEGER'",
+        )
+        .related(
+            code.s1("vec_ptr_t"),
+            "Does not match procedure DEALLOCATE[vec_ptr_t]: type mismatch of argument 1, \
+             expected access type 'vec_ptr_t' but got integer type 'INTEGER'",
+        )],
+    );
+}
@@ -0,0 +1,181 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::VHDLStandard;
+
+#[test]
+fn report_and_severity_expressions_are_typechecked() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+procedure proc is
+  variable n : natural := 0;
+begin
+  assert n > 0 report \"positive\" severity warning;
+  report \"done\" severity note;
+  assert n > 1 report n severity warning;
+  report \"done\" severity 3;
+  assert n report \"n\";
+end procedure;
+",
+    );
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("report n").s1("n"),
+                "variable 'n' of subtype 'NATURAL' does not match array type 'STRING'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+            Diagnostic::error(
+                code.s1("severity 3").s1("3"),
+                "integer literal does not match type 'SEVERITY_LEVEL'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+            Diagnostic::error(
+                code.s1("assert n report").s1("n"),
+                "subtype 'NATURAL' cannot be implictly converted to type 'BOOLEAN'. \
+                 Operator ?? is not defined for this type.",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+        ],
+    );
+}
+
+#[test]
+fn std_ulogic_condition_is_converted_since_vhdl_2008() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    builder.code_with_standard(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal valid : std_ulogic;
+begin
+  assert valid report \"not valid\";
+
+  process
+  begin
+    assert valid report \"not valid\";
+    wait;
+  end process;
+end architecture;
+",
+        VHDLStandard::V2008,
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn std_ulogic_condition_is_not_converted_before_vhdl_2008() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal valid : std_ulogic;
+begin
+  assert valid report \"not valid\";
+
+  process
+  begin
+    assert valid report \"not valid\";
+    wait;
+  end process;
+end architecture;
+",
+        VHDLStandard::V1993,
+    );
+    let not_boolean = |occurrence| {
+        Diagnostic::error(
+            code.s("assert valid", occurrence).s1("valid"),
+            "type 'STD_ULOGIC' does not match type 'BOOLEAN'",
+        )
+    };
+    check_diagnostics(builder.analyze(), vec![not_boolean(1), not_boolean(2)]);
+}
+
+#[test]
+fn static_assertions_are_not_reported_by_default() {
+    let mut builder = LibraryBuilder::new();
+    builder.in_declarative_region(
+        "
+constant enabled : boolean := true;
+
+procedure proc is
+  variable n : natural := 0;
+begin
+  assert true report \"useless\";
+  assert enabled or n > 0;
+  assert false report \"finished\" severity note;
+  assert 1 > 2 report \"unexpected\" severity warning;
+  assert false report \"failed\";
+  assert false report \"failed\" severity failure;
+  assert n > 0 report \"n\" severity note;
+end procedure;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn static_assertions_are_reported_when_enabled() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_static_assertion_lint();
+    let code = builder.in_declarative_region(
+        "
+constant enabled : boolean := true;
+
+procedure proc is
+  variable n : natural := 0;
+begin
+  assert true report \"useless\";
+  assert enabled or n > 0;
+  assert false report \"finished\" severity note;
+  assert 1 > 2 report \"unexpected\" severity warning;
+  assert false report \"failed\";
+  assert false report \"failed\" severity failure;
+  assert n > 0 report \"n\" severity note;
+end procedure;
+",
+    );
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::warning(
+                code.s1("assert true").s1("true"),
+                "Assertion condition is always true, the assertion has no effect",
+            )
+            .with_code(ErrorCode::StaticAssertion),
+            Diagnostic::warning(
+                code.s1("false report \"finished\"").s1("false"),
+                "Assertion condition is always false with severity note, \
+                 use a report statement if this is intended",
+            )
+            .with_code(ErrorCode::StaticAssertion),
+            Diagnostic::warning(
+                code.s1("1 > 2"),
+                "Assertion condition is always false with severity warning, \
+                 use a report statement if this is intended",
+            )
+            .with_code(ErrorCode::StaticAssertion),
+        ],
+    );
+}
@@ -5,6 +5,7 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 use super::*;
+use crate::VHDLStandard;
 
 #[test]
 fn overloaded_name_may_not_be_assignment_target() {
@@ -176,7 +177,7 @@ end architecture;
 #[test]
 fn objects_may_be_assignment_target() {
     let mut builder = LibraryBuilder::new();
-    builder.code(
+    builder.code_with_standard(
         "libname",
         "
 entity ent is
@@ -197,6 +198,7 @@ begin
   end process;
 end architecture;
 ",
+        VHDLStandard::V1993,
     );
 
     let diagnostics = builder.analyze();
@@ -584,7 +586,7 @@ end package body Test;",
 #[test]
 pub fn assignment_mode_checking() {
     let mut builder = LibraryBuilder::new();
-    let code = builder.code(
+    let code = builder.code_with_standard(
         "libname",
         "\
 entity bar is
@@ -616,6 +618,7 @@ begin
 
 end architecture foo;
 ",
+        VHDLStandard::V1993,
     );
     let diagnostics = builder.analyze();
     check_diagnostics(
@@ -640,3 +643,108 @@ end architecture foo;
         ],
     )
 }
+
+#[test]
+fn static_array_length_of_assignments() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal byte : std_logic_vector(7 downto 0);
+  signal word : std_logic_vector(0 to 2 * 8 - 1);
+  signal copy : std_logic_vector(byte'length - 1 downto 0);
+  signal init : std_logic_vector(3 downto 0) := \"00000\";
+begin
+  process
+    variable idx : natural;
+    variable var : std_logic_vector(byte'range);
+  begin
+    byte <= \"01010101\";
+    byte <= \"0101010\";
+    byte <= x\"ab\";
+    byte <= (others => '0');
+    byte <= ('0', others => '1');
+    word <= x\"abc\";
+    copy <= byte;
+    copy <= word;
+    var := \"0101\";
+    byte(idx downto 0) <= \"0101\";
+    byte(3 downto 0) <= \"01\";
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1(":= \"00000\"").s1("\"00000\""),
+                "Expected an array of length 4, got length 5",
+            ),
+            Diagnostic::error(
+                code.s1("\"0101010\""),
+                "Expected an array of length 8, got length 7",
+            ),
+            Diagnostic::error(
+                code.s1("x\"abc\""),
+                "Expected an array of length 16, got length 12",
+            ),
+            Diagnostic::error(
+                code.s1("copy <= word").s1("word"),
+                "Expected an array of length 8, got length 16",
+            ),
+            Diagnostic::error(
+                code.s1("var := \"0101\"").s1("\"0101\""),
+                "Expected an array of length 8, got length 4",
+            ),
+            Diagnostic::error(
+                code.s1("\"01\""),
+                "Expected an array of length 4, got length 2",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn typecheck_external_names() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal good1, good2 : bit;
+  signal bad1, bad2 : integer;
+begin
+  good1 <= << signal .tb.dut.sig : bit >>;
+  good2 <= << constant ^.^.dut.c : bit >> and '1';
+  bad1 <= << signal ^.^.dut.sig : bit >>;
+  bad2 <= << variable dut.var : missing_t >>;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("<< signal ^.^.dut.sig : bit >>"),
+                "type 'BIT' does not match integer type 'INTEGER'",
+            ),
+            Diagnostic::error(code.s1("missing_t"), "No declaration of 'missing_t'"),
+        ],
+    );
+}
@@ -37,7 +37,11 @@ end architecture;
         diagnostics,
         vec![Diagnostic::error(
             code.s1("missing"),
-            "No declaration of 'missing'",
+            "No port 'missing' on entity 'libname.ent_inst'",
+        )
+        .related(
+            code.s1("ent_inst"),
+            "Declaration of entity 'libname.ent_inst' without any ports",
         )],
     );
 }
@@ -72,7 +76,11 @@ end architecture;
         diagnostics,
         vec![Diagnostic::error(
             code.s1("missing"),
-            "No declaration of 'missing'",
+            "No generic 'missing' on entity 'libname.ent_inst'",
+        )
+        .related(
+            code.s1("ent_inst"),
+            "Declaration of entity 'libname.ent_inst' without any generics",
         )],
     );
 }
@@ -186,16 +194,30 @@ end architecture;
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("theport", 2), "No declaration of 'theport'"),
+            Diagnostic::error(
+                code.s("theport", 2),
+                "No generic 'theport' on entity 'libname.ent_inst'",
+            )
+            .related(
+                code.s1("thegeneric"),
+                "The generics of entity 'libname.ent_inst' are declared here",
+            ),
             Diagnostic::error(
                 code.s1("work.ent_inst"),
-                "No association of port 'theport' : in",
+                "Missing association for port 'theport' of mode in with no default",
             )
             .related(code.s1("theport"), "Defined here"),
-            Diagnostic::error(code.s("thegeneric", 2), "No declaration of 'thegeneric'"),
+            Diagnostic::error(
+                code.s("thegeneric", 2),
+                "No port 'thegeneric' on entity 'libname.ent_inst'",
+            )
+            .related(
+                code.s1("theport"),
+                "The ports of entity 'libname.ent_inst' are declared here",
+            ),
             Diagnostic::error(
                 code.s1("work.ent_inst"),
-                "No association of generic 'thegeneric'",
+                "Missing association for generic 'thegeneric' with no default",
             )
             .related(code.s1("thegeneric"), "Defined here"),
         ],
@@ -541,10 +563,17 @@ end architecture;
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s1("missing"), "No declaration of 'missing'"),
+            Diagnostic::error(
+                code.s1("missing"),
+                "No port 'missing' on entity 'libname.ent_inst'",
+            )
+            .related(
+                code.s1("prt0"),
+                "The ports of entity 'libname.ent_inst' are declared here",
+            ),
             Diagnostic::error(
                 code.s1("work.ent_inst"),
-                "No association of port 'prt0' : in",
+                "Missing association for port 'prt0' of mode in with no default",
             )
             .related(code.s1("prt0"), "Defined here"),
         ],
@@ -567,3 +596,487 @@ end architecture;
         code.s1("prt1").pos()
     );
 }
+
+#[test]
+fn port_associated_more_than_once() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent_inst is
+  port (
+    clk : in boolean;
+    rst : in boolean
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+   signal sig0, sig1 : boolean;
+begin
+   ent: entity work.ent_inst
+      port map (sig0, clk => sig1, rst => sig1);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("clk => sig1").s1("clk"),
+            "port 'clk' is associated more than once",
+        )
+        .related(code.s1("(sig0").s1("sig0"), "Previously associated here")],
+    );
+}
+
+#[test]
+fn generic_associated_more_than_once() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent_inst is
+  generic (
+    width : natural
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+   ent: entity work.ent_inst
+      generic map (width => 1, width => 2);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s("width", 3),
+            "generic 'width' is associated more than once",
+        )
+        .related(code.s("width", 2), "Previously associated here")],
+    );
+}
+
+#[test]
+fn missing_port_of_component() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+   component comp is
+     port (
+       clk : in boolean;
+       rst : in boolean;
+       q : out boolean
+     );
+   end component;
+   signal sig : boolean;
+begin
+   inst: comp
+      port map (clk => sig, missing => sig);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s1("missing"), "No port 'missing' on component 'comp'").related(
+                code.s1("clk"),
+                "The ports of component 'comp' are declared here",
+            ),
+            Diagnostic::error(
+                code.s1("inst: comp").s1("comp"),
+                "Missing association for port 'rst' of mode in with no default",
+            )
+            .related(code.s1("rst"), "Defined here"),
+        ],
+    );
+}
+
+#[test]
+fn open_association_without_default() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent_inst is
+  generic (
+    width : natural;
+    depth : natural := 0
+  );
+  port (
+    clk : in boolean;
+    rst : in boolean := false;
+    q : out boolean
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+   ent: entity work.ent_inst
+      generic map (open, depth => open)
+      port map (clk => open, rst => open, q => open);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("(open").s1("open"),
+                "Missing association for generic 'width' with no default",
+            )
+            .related(code.s1("width"), "Defined here"),
+            Diagnostic::error(
+                code.s1("clk => open").s1("open"),
+                "Missing association for port 'clk' of mode in with no default",
+            )
+            .related(code.s1("clk"), "Defined here"),
+        ],
+    );
+}
+
+#[test]
+fn correct_instantiation_with_mixed_associations() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent_inst is
+  generic (
+    width : natural;
+    depth : natural := 0
+  );
+  port (
+    clk : in boolean;
+    rst : in boolean := false;
+    cnt : out natural;
+    q : out boolean
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+   signal clk : boolean;
+   signal cnt : real;
+begin
+   ent: entity work.ent_inst
+      generic map (8, depth => 4)
+      port map (clk, rst => open, real(cnt) => cnt);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn typecheck_port_map_actuals() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+package pkg is
+  type signed is array (natural range <>) of std_logic;
+end package;
+
+use work.pkg.all;
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent_inst is
+  generic (
+    width : natural;
+    scale : real
+  );
+  port (
+    data : in std_logic_vector(width - 1 downto 0);
+    value : in signed(width - 1 downto 0);
+    cnt : out natural
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+use work.pkg.all;
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal bit_sig : std_logic;
+  signal vec_sig : std_logic_vector(7 downto 0);
+  signal int_sig : integer;
+  signal real_sig : real;
+begin
+  good: entity work.ent_inst
+    generic map (width => 8, scale => 0.5)
+    port map (data => vec_sig, value => signed(vec_sig), real(cnt) => real_sig);
+
+  bad: entity work.ent_inst
+    generic map (width => 8, scale => 1.0)
+    port map (data => bit_sig, value => int_sig, cnt => real_sig);
+
+  bad_conversion: entity work.ent_inst
+    generic map (width => 8, scale => 1)
+    port map (data => vec_sig, value => signed(vec_sig), real(cnt) => int_sig);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("data => bit_sig").s1("bit_sig"),
+                "signal 'bit_sig' of subtype 'STD_LOGIC' does not match subtype 'STD_LOGIC_VECTOR'",
+            ),
+            Diagnostic::error(
+                code.s1("value => int_sig").s1("int_sig"),
+                "signal 'int_sig' of integer type 'INTEGER' does not match array type 'signed'",
+            ),
+            Diagnostic::error(
+                code.s1("cnt => real_sig").s1("real_sig"),
+                "signal 'real_sig' of real type 'REAL' does not match subtype 'NATURAL'",
+            ),
+            Diagnostic::error(
+                code.s1("scale => 1)").s1("1"),
+                "integer literal does not match real type 'REAL'",
+            ),
+            Diagnostic::error(
+                code.s1("real(cnt) => int_sig").s1("int_sig"),
+                "signal 'int_sig' of integer type 'INTEGER' does not match real type 'REAL'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn entity_with_generic_type_used_in_ports() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let gen_code = builder.code(
+        "libname",
+        "
+entity gen_ent is
+  generic (
+    type data_t;
+    function compare(a, b : data_t) return boolean
+  );
+  port (
+    din : in data_t;
+    dout : out data_t;
+    eq : out boolean
+  );
+end entity;
+
+architecture rtl of gen_ent is
+  signal last : data_t;
+begin
+  dout <= din;
+  eq <= compare(din, last);
+end architecture;
+        ",
+    );
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+end entity;
+
+architecture a of top is
+  signal vec_in, vec_out : std_logic_vector(7 downto 0);
+  signal int_in, int_out : integer;
+  signal eq0, eq1 : boolean;
+begin
+  inst0 : entity work.gen_ent
+    generic map (
+      data_t => std_logic_vector(7 downto 0),
+      compare => \"=\")
+    port map (din => vec_in, dout => vec_out, eq => eq0);
+
+  inst1 : entity work.gen_ent
+    generic map (integer, \"=\")
+    port map (int_in, int_out, eq1);
+end architecture;
+        ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("data_t =>").start()),
+        Some(gen_code.s1("data_t").pos())
+    );
+}
+
+#[test]
+fn ports_of_generic_type_are_checked_against_the_actual_type() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    builder.code(
+        "libname",
+        "
+entity gen_ent is
+  generic (
+    type data_t;
+    function compare(a, b : data_t) return boolean
+  );
+  port (
+    din : in data_t;
+    dout : out data_t;
+    eq : out boolean
+  );
+end entity;
+
+architecture rtl of gen_ent is
+  signal last : data_t;
+begin
+  dout <= din;
+  eq <= compare(din, last);
+end architecture;
+        ",
+    );
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+end entity;
+
+architecture a of top is
+  signal vec_in, vec_out : std_logic_vector(7 downto 0);
+  signal eq : boolean;
+begin
+  inst : entity work.gen_ent
+    generic map (data_t => integer, compare => \"=\")
+    port map (din => vec_in, dout => vec_out, eq => eq);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("din => vec_in").s1("vec_in"),
+                "signal 'vec_in' of subtype 'STD_LOGIC_VECTOR' does not match integer type 'INTEGER'",
+            ),
+            Diagnostic::error(
+                code.s1("dout => vec_out").s1("vec_out"),
+                "signal 'vec_out' of subtype 'STD_LOGIC_VECTOR' does not match integer type 'INTEGER'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn generic_type_must_be_associated() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let gen_code = builder.code(
+        "libname",
+        "
+entity gen_ent is
+  generic (
+    type data_t;
+    function compare(a, b : data_t) return boolean
+  );
+  port (
+    din : in data_t;
+    dout : out data_t;
+    eq : out boolean
+  );
+end entity;
+
+architecture rtl of gen_ent is
+  signal last : data_t;
+begin
+  dout <= din;
+  eq <= compare(din, last);
+end architecture;
+        ",
+    );
+    let code = builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture a of top is
+  signal int_in, int_out : integer;
+  signal eq : boolean;
+begin
+  inst : entity work.gen_ent
+    generic map (compare => \"=\")
+    port map (din => int_in, dout => int_out, eq => eq);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("work.gen_ent"),
+            "Missing association for generic type 'data_t'",
+        )
+        .related(gen_code.s1("data_t"), "Defined here")],
+    );
+}
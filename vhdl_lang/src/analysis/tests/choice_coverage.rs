@@ -0,0 +1,302 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn case_covers_all_literals() {
+    let mut builder = LibraryBuilder::new();
+    builder.in_declarative_region(
+        "
+type state_t is (idle, start, busy, done);
+
+procedure proc(state : state_t; flag : boolean) is
+begin
+  case state is
+    when idle => null;
+    when start | busy => null;
+    when done => null;
+  end case;
+
+  case state is
+    when idle to busy => null;
+    when done => null;
+  end case;
+
+  case flag is
+    when true => null;
+    when false => null;
+  end case;
+end procedure;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn case_with_others_covers_remaining_literals() {
+    let mut builder = LibraryBuilder::new();
+    builder.in_declarative_region(
+        "
+type state_t is (idle, start, busy, done);
+
+procedure proc(state : state_t) is
+begin
+  case state is
+    when idle => null;
+    when others => null;
+  end case;
+end procedure;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn case_with_missing_literals() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type state_t is (idle, start, busy, done);
+
+procedure proc(state : state_t) is
+begin
+  case state is
+    when start => null;
+    when busy => null;
+  end case;
+
+  case state is
+    when busy downto idle => null;
+  end case;
+end procedure;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s("case state", 1).s1("state"),
+                "Missing choice(s): idle, done",
+            ),
+            Diagnostic::error(
+                code.s("case state", 2).s1("state"),
+                "Missing choice(s): done",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn case_with_duplicate_literals() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type state_t is (idle, start, busy, done);
+
+procedure proc(state : state_t) is
+begin
+  case state is
+    when idle to busy => null;
+    when start => null;
+    when done | idle => null;
+  end case;
+end procedure;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("when start").s1("start"),
+                "Choice start is covered more than once",
+            )
+            .related(code.s1("idle to busy"), "Previously covered here"),
+            Diagnostic::error(
+                code.s1("done | idle").s1("idle"),
+                "Choice idle is covered more than once",
+            )
+            .related(code.s1("idle to busy"), "Previously covered here"),
+        ],
+    );
+}
+
+#[test]
+fn case_on_std_logic_with_missing_values_is_a_warning() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sel : std_logic;
+begin
+  process (sel)
+  begin
+    case sel is
+      when '0' => null;
+      when '1' => null;
+      when 'Z' | 'X' => null;
+    end case;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::warning(
+            code.s1("case sel").s1("sel"),
+            "Missing choice(s): 'U', 'W', 'L', 'H', '-'",
+        )],
+    );
+}
+
+#[test]
+fn selected_assignment_with_duplicate_and_missing_choices() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+type state_t is (idle, start, busy, done);
+signal state : state_t;
+signal y : bit;
+signal z : natural;
+begin
+with state select y <=
+  '0' when idle | start,
+  '1' when start;
+
+with state select z <=
+  0 when idle to done;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s("with state", 1).s1("state"),
+                "Missing choice(s): busy, done",
+            ),
+            Diagnostic::error(
+                code.s1("when start;").s1("start"),
+                "Choice start is covered more than once",
+            )
+            .related(
+                code.s1("idle | start").s1("start"),
+                "Previously covered here",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn selected_assignment_with_choices_after_others() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+type state_t is (idle, start, busy, done);
+signal state : state_t;
+signal y : bit;
+begin
+with state select y <=
+  '0' when idle,
+  '1' when others,
+  '0' when done;
+
+process
+  variable v : bit;
+begin
+  with state select v :=
+    '0' when others,
+    '1' when start | busy;
+  wait;
+end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("when done").s1("done"),
+                "Choice after 'others' is unreachable",
+            )
+            .related(code.s("others", 1), "The 'others' choice is here"),
+            Diagnostic::error(
+                code.s1("start | busy").s1("start"),
+                "Choice after 'others' is unreachable",
+            )
+            .related(code.s("others", 2), "The 'others' choice is here"),
+            Diagnostic::error(
+                code.s1("start | busy").s1("busy"),
+                "Choice after 'others' is unreachable",
+            )
+            .related(code.s("others", 2), "The 'others' choice is here"),
+        ],
+    );
+}
+
+#[test]
+fn choices_of_static_integer_selector() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+constant last : natural := 7;
+signal sel : integer range 0 to last;
+signal y1, y2, y3 : bit;
+begin
+with sel select y1 <=
+  '0' when 0 | 1,
+  '1' when 2 to 4;
+
+with sel select y2 <=
+  '0' when 0 to 3,
+  '1' when 3 to last;
+
+with sel select y3 <=
+  '0' when 0 to 3,
+  '1' when 4 to 7;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(code.s("with sel", 1).s1("sel"), "Missing choice(s): 5 to 7"),
+            Diagnostic::error(code.s1("3 to last"), "Choice 3 is covered more than once")
+                .related(code.s("0 to 3", 1), "Previously covered here"),
+        ],
+    );
+}
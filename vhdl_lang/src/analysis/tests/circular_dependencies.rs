@@ -28,8 +28,16 @@ end context;
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("ctx1", 2), "Found circular dependency"),
-            Diagnostic::error(code.s("ctx2", 1), "Found circular dependency"),
+            Diagnostic::error(
+                code.s("ctx1", 2),
+                "Found circular dependency: libname.ctx2 -> libname.ctx1 -> libname.ctx2",
+            )
+            .related(code.s("ctx2", 1), "Used here"),
+            Diagnostic::error(
+                code.s("ctx2", 1),
+                "Found circular dependency: libname.ctx1 -> libname.ctx2 -> libname.ctx1",
+            )
+            .related(code.s("ctx1", 2), "Used here"),
         ],
     );
 }
@@ -56,8 +64,16 @@ end package;",
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("pkg1", 2), "Found circular dependency"),
-            Diagnostic::error(code.s("pkg2", 1), "Found circular dependency"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg2 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s("pkg2", 1), "Used here"),
+            Diagnostic::error(
+                code.s("pkg2", 1),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg1",
+            )
+            .related(code.s("pkg1", 2), "Used here"),
         ],
     );
 }
@@ -84,8 +100,16 @@ package pkg2 is new work.gpkg generic map(c => true);
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("pkg1", 2), "Found circular dependency"),
-            Diagnostic::error(code.s1("pkg2"), "Found circular dependency"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg2 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s1("pkg2"), "Used here"),
+            Diagnostic::error(
+                code.s1("pkg2"),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg1",
+            )
+            .related(code.s("pkg1", 2), "Used here"),
         ],
     );
 }
@@ -113,8 +137,16 @@ end package;
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s1("pkg2"), "Found circular dependency"),
-            Diagnostic::error(code.s("gpkg", 2), "Found circular dependency"),
+            Diagnostic::error(
+                code.s1("pkg2"),
+                "Found circular dependency: libname.gpkg -> libname.pkg2 -> libname.gpkg",
+            )
+            .related(code.s("gpkg", 2), "Used here"),
+            Diagnostic::error(
+                code.s("gpkg", 2),
+                "Found circular dependency: libname.pkg2 -> libname.gpkg -> libname.pkg2",
+            )
+            .related(code.s1("pkg2"), "Used here"),
         ],
     );
 }
@@ -145,9 +177,24 @@ package pkg3 is new work.pkg2;
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s1("pkg3"), "Found circular dependency"),
-            Diagnostic::error(code.s("gpkg", 2), "Found circular dependency"),
-            Diagnostic::error(code.s("pkg2", 2), "Found circular dependency"),
+            Diagnostic::error(
+                code.s1("pkg3"),
+                "Found circular dependency: libname.gpkg -> libname.pkg3 -> libname.pkg2 -> libname.gpkg",
+            )
+            .related(code.s("pkg2", 2), "Used here")
+            .related(code.s("gpkg", 2), "Used here"),
+            Diagnostic::error(
+                code.s("gpkg", 2),
+                "Found circular dependency: libname.pkg2 -> libname.gpkg -> libname.pkg3 -> libname.pkg2",
+            )
+            .related(code.s1("pkg3"), "Used here")
+            .related(code.s("pkg2", 2), "Used here"),
+            Diagnostic::error(
+                code.s("pkg2", 2),
+                "Found circular dependency: libname.pkg3 -> libname.pkg2 -> libname.gpkg -> libname.pkg3",
+            )
+            .related(code.s("gpkg", 2), "Used here")
+            .related(code.s1("pkg3"), "Used here"),
         ],
     );
 }
@@ -174,8 +221,16 @@ end package;",
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("pkg1", 2), "Found circular dependency"),
-            Diagnostic::error(code.s1("pkg2"), "Found circular dependency"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg2 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s1("pkg2"), "Used here"),
+            Diagnostic::error(
+                code.s1("pkg2"),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg1",
+            )
+            .related(code.s("pkg1", 2), "Used here"),
         ],
     );
 }
@@ -205,8 +260,16 @@ package pkg2 is new work.gpkg generic map (g => true);
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("pkg1", 2), "Found circular dependency"),
-            Diagnostic::error(code.s1("pkg2"), "Found circular dependency"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg2 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s1("pkg2"), "Used here"),
+            Diagnostic::error(
+                code.s1("pkg2"),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg1",
+            )
+            .related(code.s("pkg1", 2), "Used here"),
         ],
     );
 }
@@ -233,8 +296,197 @@ end package;",
     check_diagnostics(
         diagnostics,
         vec![
-            Diagnostic::error(code.s("pkg1", 2), "Found circular dependency"),
-            Diagnostic::error(code.s("work.all", 1), "Found circular dependency"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg2 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s("work.all", 1), "Used here"),
+            Diagnostic::error(
+                code.s("work.all", 1),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg1",
+            )
+            .related(code.s("pkg1", 2), "Used here"),
         ],
     );
 }
+
+#[test]
+fn use_package_cycle_of_three() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+use work.pkg2.const;
+package pkg1 is
+  constant const : natural := 0;
+end package;
+
+use work.pkg3.const;
+package pkg2 is
+  constant const : natural := 0;
+end package;
+
+use work.pkg1.const;
+package pkg3 is
+  constant const : natural := 0;
+end package;",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("pkg2"),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg3 -> libname.pkg1",
+            )
+            .related(code.s1("pkg3"), "Used here")
+            .related(code.s("pkg1", 2), "Used here"),
+            Diagnostic::error(
+                code.s1("pkg3"),
+                "Found circular dependency: libname.pkg2 -> libname.pkg3 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s("pkg1", 2), "Used here")
+            .related(code.s1("pkg2"), "Used here"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg3 -> libname.pkg1 -> libname.pkg2 -> libname.pkg3",
+            )
+            .related(code.s1("pkg2"), "Used here")
+            .related(code.s1("pkg3"), "Used here"),
+        ],
+    );
+}
+
+#[test]
+fn use_package_within_itself() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+use work.pkg.const;
+package pkg is
+  constant const : natural := 0;
+end package;",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("pkg"),
+            "Found circular dependency: libname.pkg -> libname.pkg",
+        )],
+    );
+}
+
+#[test]
+fn units_outside_of_cycle_are_analyzed() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+use work.pkg2.const;
+package pkg1 is
+  constant const : natural := 0;
+end package;
+
+use work.pkg1.const;
+package pkg2 is
+  constant const : natural := 0;
+end package;
+
+use work.pkg1.const;
+package user is
+end package;
+
+package unrelated is
+  constant const : natural := missing;
+end package;",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("pkg2"),
+                "Found circular dependency: libname.pkg1 -> libname.pkg2 -> libname.pkg1",
+            )
+            .related(code.s("pkg1", 2), "Used here"),
+            Diagnostic::error(
+                code.s("pkg1", 2),
+                "Found circular dependency: libname.pkg2 -> libname.pkg1 -> libname.pkg2",
+            )
+            .related(code.s1("pkg2"), "Used here"),
+            // The user is not part of the cycle itself
+            Diagnostic::error(code.s("pkg1", 3), "Found circular dependency"),
+            Diagnostic::error(code.s1("missing"), "No declaration of 'missing'"),
+        ],
+    );
+}
+
+#[test]
+fn type_declaration_within_itself() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  subtype sub_t is sub_t range 0 to 1;
+  type arr_t is array (natural range <>) of arr_t;
+  type rec_t is record
+    field : rec_t;
+  end record;
+  type ptr_t is access ptr_t;
+end package;",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s("sub_t", 2),
+                "Found circular type declaration, 'sub_t' is used within its own declaration",
+            ),
+            Diagnostic::error(
+                code.s("arr_t", 2),
+                "Found circular type declaration, 'arr_t' is used within its own declaration",
+            ),
+            Diagnostic::error(
+                code.s("rec_t", 2),
+                "Found circular type declaration, 'rec_t' is used within its own declaration",
+            ),
+            Diagnostic::error(
+                code.s("ptr_t", 2),
+                "Found circular type declaration, 'ptr_t' is used within its own declaration",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn type_declaration_may_denote_type_of_enclosing_region() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  subtype word_t is natural range 0 to 255;
+  type rec_t;
+  type ptr_t is access rec_t;
+  type rec_t is record
+    next_rec : ptr_t;
+  end record;
+end package;
+
+use work.pkg.word_t;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  subtype word_t is word_t range 0 to 15;
+begin
+end architecture;",
+    );
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
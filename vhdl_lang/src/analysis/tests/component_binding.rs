@@ -0,0 +1,297 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn matching_component_and_entity() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity child is
+  generic (
+    width : natural := 8);
+  port (
+    clk : in bit;
+    data : in bit_vector(width - 1 downto 0);
+    valid : out boolean);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component child is
+    generic (
+      width : natural := 8);
+    port (
+      clk : in bit;
+      data : in bit_vector(width - 1 downto 0);
+      valid : out boolean);
+  end component;
+
+  signal clk : bit;
+  signal data : bit_vector(7 downto 0);
+begin
+  inst : child
+    port map (clk => clk, data => data);
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn optional_formals_may_be_omitted_from_component() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity child is
+  generic (
+    width : natural := 8);
+  port (
+    clk : in bit;
+    data : in bit_vector(width - 1 downto 0);
+    valid : out boolean);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component child is
+    port (
+      clk : in bit;
+      data : in bit_vector(7 downto 0));
+  end component;
+
+  signal clk : bit;
+  signal data : bit_vector(7 downto 0);
+begin
+  inst : child
+    port map (clk => clk, data => data);
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn port_missing_from_component() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity child is
+  generic (
+    width : natural := 8);
+  port (
+    clk : in bit;
+    data : in bit_vector(width - 1 downto 0);
+    valid : out boolean);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component child is
+    port (
+      data : in bit_vector(7 downto 0));
+  end component;
+
+  signal clk : bit;
+  signal data : bit_vector(7 downto 0);
+begin
+  inst : child
+    port map (data => data);
+
+  inst2 : child
+    port map (data => data);
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    // The mismatch is only reported at the first instance
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::warning(
+            code.s1("inst : child").s1("child"),
+            "Port 'clk' of entity 'libname.child' is missing from component 'child'",
+        )
+        .related(
+            code.s1("component child").s1("child"),
+            "Declared in the component here",
+        )
+        .related(code.s1("clk"), "Declared in the entity here")],
+    );
+}
+
+#[test]
+fn extra_port_and_type_mismatch() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity child is
+  generic (
+    width : natural := 8);
+  port (
+    clk : in bit;
+    data : in bit_vector(width - 1 downto 0);
+    valid : out boolean);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component child is
+    port (
+      clk : in bit;
+      data : in bit_vector(7 downto 0);
+      valid : out bit;
+      ready : in bit := '0');
+  end component;
+
+  signal clk : bit;
+  signal data : bit_vector(7 downto 0);
+begin
+  inst : child
+    port map (clk => clk, data => data);
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::warning(
+                code.s1("inst : child").s1("child"),
+                "Port 'valid' has type 'BIT' in component 'child' but type 'BOOLEAN' in entity 'libname.child'",
+            )
+            .related(code.s("valid", 2), "Declared in the component here")
+            .related(code.s1("valid"), "Declared in the entity here"),
+            Diagnostic::warning(
+                code.s1("inst : child").s1("child"),
+                "Port 'ready' of component 'child' does not exist in entity 'libname.child'",
+            )
+            .related(code.s1("ready"), "Declared in the component here")
+            .related(code.s1("entity child").s1("child"), "Declared in the entity here"),
+        ],
+    );
+}
+
+#[test]
+fn mode_mismatch() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity child is
+  generic (
+    width : natural := 8);
+  port (
+    clk : in bit;
+    data : in bit_vector(width - 1 downto 0);
+    valid : out boolean);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component child is
+    port (
+      clk : in bit;
+      data : inout bit_vector(7 downto 0));
+  end component;
+
+  signal clk : bit;
+  signal data : bit_vector(7 downto 0);
+begin
+  inst : child
+    port map (clk => clk, data => data);
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::warning(
+            code.s1("inst : child").s1("child"),
+            "Port 'data' has mode inout in component 'child' but mode in in entity 'libname.child'",
+        )
+        .related(code.s("data", 2), "Declared in the component here")
+        .related(code.s1("data"), "Declared in the entity here")],
+    );
+}
+
+#[test]
+fn partial_component_attribute_suppresses_comparison() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity child is
+  generic (
+    width : natural := 8);
+  port (
+    clk : in bit;
+    data : in bit_vector(width - 1 downto 0);
+    valid : out boolean);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component child is
+    port (
+      data : inout bit_vector(7 downto 0));
+  end component;
+  attribute partial_component : boolean;
+  attribute partial_component of child : component is true;
+
+  signal clk : bit;
+  signal data : bit_vector(7 downto 0);
+begin
+  inst : child
+    port map (data => data);
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
@@ -0,0 +1,601 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn good_configuration() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity sub is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of sub is
+begin
+end architecture;
+
+entity narrow is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of narrow is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+    generic (width : natural := 8);
+    port (clk : in bit);
+  end component;
+
+  component other is
+  end component;
+
+  signal clk : bit;
+begin
+  inst0 : comp port map (clk => clk);
+  inst_other : other;
+
+  blk : block
+  begin
+    inst1 : comp port map (clk => clk);
+  end block;
+
+  gen : for i in 0 to 1 generate
+    inst2 : comp port map (clk => clk);
+  end generate;
+end architecture;
+",
+    );
+    builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for rtl
+    for inst0 : comp
+      use entity work.sub(rtl);
+    end for;
+    for inst_other : other
+    end for;
+    for blk
+      for all : comp
+        use entity work.sub;
+      end for;
+    end for;
+    for gen(0)
+      for inst2 : comp
+        use entity work.sub(rtl);
+        for rtl
+        end for;
+      end for;
+    end for;
+    for gen(1)
+      for others : comp
+        use entity work.narrow generic map (open);
+      end for;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn search_instance_label_from_configuration() {
+    check_search_reference(
+        "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+  component comp is
+  end component;
+begin
+  decl : comp;
+end architecture;
+
+configuration cfg of ent is
+  for rtl
+    for decl : comp
+    end for;
+  end for;
+end configuration;
+",
+    );
+}
+
+#[test]
+fn error_on_missing_architecture() {
+    let mut builder = LibraryBuilder::new();
+    let design = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of sub is
+begin
+end architecture;
+
+entity narrow is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of narrow is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+    generic (width : natural := 8);
+    port (clk : in bit);
+  end component;
+
+  component other is
+  end component;
+
+  signal clk : bit;
+begin
+  inst0 : comp port map (clk => clk);
+  inst_other : other;
+
+  blk : block
+  begin
+    inst1 : comp port map (clk => clk);
+  end block;
+
+  gen : for i in 0 to 1 generate
+    inst2 : comp port map (clk => clk);
+  end generate;
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for bad
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("bad"),
+            "No architecture 'bad' for entity 'libname.top'",
+        )
+        .related(
+            design.s1("entity top").s1("top"),
+            "Entity 'top' declared here",
+        )],
+    );
+}
+
+#[test]
+fn error_on_missing_instance_label() {
+    let mut builder = LibraryBuilder::new();
+    let design = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of sub is
+begin
+end architecture;
+
+entity narrow is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of narrow is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+    generic (width : natural := 8);
+    port (clk : in bit);
+  end component;
+
+  component other is
+  end component;
+
+  signal clk : bit;
+begin
+  inst0 : comp port map (clk => clk);
+  inst_other : other;
+
+  blk : block
+  begin
+    inst1 : comp port map (clk => clk);
+  end block;
+
+  gen : for i in 0 to 1 generate
+    inst2 : comp port map (clk => clk);
+  end generate;
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for rtl
+    for missing : comp
+    end for;
+    for gen
+      for inst0 : comp
+      end for;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("missing"),
+                "No component instantiation 'missing' within architecture 'rtl'",
+            )
+            .related(
+                design.s1("architecture rtl of top").s1("rtl"),
+                "Architecture 'rtl' declared here",
+            ),
+            Diagnostic::error(
+                code.s1("inst0"),
+                "No component instantiation 'inst0' within generate statement 'gen'",
+            )
+            .related(
+                design.s1("gen : for").s1("gen"),
+                "Generate statement 'gen' declared here",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn error_on_missing_block_or_generate_label() {
+    let mut builder = LibraryBuilder::new();
+    let design = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of sub is
+begin
+end architecture;
+
+entity narrow is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of narrow is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+    generic (width : natural := 8);
+    port (clk : in bit);
+  end component;
+
+  component other is
+  end component;
+
+  signal clk : bit;
+begin
+  inst0 : comp port map (clk => clk);
+  inst_other : other;
+
+  blk : block
+  begin
+    inst1 : comp port map (clk => clk);
+  end block;
+
+  gen : for i in 0 to 1 generate
+    inst2 : comp port map (clk => clk);
+  end generate;
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for rtl
+    for nogen(0)
+    end for;
+    for inst0
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("nogen(0)"),
+                "No block or generate statement 'nogen' within architecture 'rtl'",
+            )
+            .related(
+                design.s1("architecture rtl of top").s1("rtl"),
+                "Architecture 'rtl' declared here",
+            ),
+            Diagnostic::error(
+                code.s1("inst0"),
+                "No block or generate statement 'inst0' within architecture 'rtl'",
+            )
+            .related(
+                design.s1("architecture rtl of top").s1("rtl"),
+                "Architecture 'rtl' declared here",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn configuration_of_generate_alternatives() {
+    let mut builder = LibraryBuilder::new();
+    let design = builder.code(
+        "libname",
+        "
+entity top is
+  generic (sel : natural := 0);
+end entity;
+
+architecture rtl of top is
+  component comp is
+  end component;
+begin
+  gen : if alt1: sel = 0 generate
+    inst1 : comp;
+  else alt2: generate
+    inst2 : comp;
+  end generate;
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for rtl
+    for gen(alt1)
+      for inst1 : comp
+      end for;
+    end for;
+    for gen(alt2)
+      for inst1 : comp
+      end for;
+    end for;
+    for gen(alt3)
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s("inst1", 2),
+                "No component instantiation 'inst1' within alternative 'alt2' of generate statement 'gen'",
+            )
+            .related(
+                design.s1("alt2"),
+                "Alternative 'alt2' of generate statement 'gen' declared here",
+            )
+            .with_code(ErrorCode::InvalidConfiguration),
+            Diagnostic::error(
+                code.s1("alt3"),
+                "No alternative 'alt3' within generate statement 'gen'",
+            )
+            .related(
+                design.s1("gen :").s1("gen"),
+                "Generate statement 'gen' declared here",
+            )
+            .with_code(ErrorCode::InvalidConfiguration),
+        ],
+    );
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("alt1").start()),
+        Some(design.s1("alt1").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("inst1").start()),
+        Some(design.s1("inst1").pos())
+    );
+}
+
+#[test]
+fn error_on_instance_of_other_component() {
+    let mut builder = LibraryBuilder::new();
+    let design = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of sub is
+begin
+end architecture;
+
+entity narrow is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of narrow is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+    generic (width : natural := 8);
+    port (clk : in bit);
+  end component;
+
+  component other is
+  end component;
+
+  signal clk : bit;
+begin
+  inst0 : comp port map (clk => clk);
+  inst_other : other;
+
+  blk : block
+  begin
+    inst1 : comp port map (clk => clk);
+  end block;
+
+  gen : for i in 0 to 1 generate
+    inst2 : comp port map (clk => clk);
+  end generate;
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for rtl
+    for inst_other : comp
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("comp"),
+            "'inst_other' is not an instance of component 'comp'",
+        )
+        .related(
+            design.s1("inst_other : other").s1(": other").s1("other"),
+            "'inst_other' instantiates this component",
+        )],
+    );
+}
+
+#[test]
+fn error_on_incompatible_entity() {
+    let mut builder = LibraryBuilder::new();
+    let design = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of sub is
+begin
+end architecture;
+
+entity narrow is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of narrow is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+    generic (width : natural := 8);
+    port (clk : in bit);
+  end component;
+
+  component other is
+  end component;
+
+  signal clk : bit;
+begin
+  inst0 : comp port map (clk => clk);
+  inst_other : other;
+
+  blk : block
+  begin
+    inst1 : comp port map (clk => clk);
+  end block;
+
+  gen : for i in 0 to 1 generate
+    inst2 : comp port map (clk => clk);
+  end generate;
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+configuration cfg of top is
+  for rtl
+    for inst0 : comp
+      use entity work.narrow;
+    end for;
+    for blk
+      for inst1 : comp
+        use entity work.narrow generic map (open);
+      end for;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("work.narrow"),
+            "Entity 'narrow' has no generic 'width' of component 'comp'",
+        )
+        .related(design.s("width", 2), "The generic is declared here")],
+    );
+}
@@ -0,0 +1,245 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn literal_conditions_and_selector() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_constant_condition_lint();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  process
+  begin
+    if bit'('1') = '0' then
+    elsif true then
+    end if;
+
+    while false loop
+    end loop;
+
+    -- An infinite loop is a common idiom
+    while true loop
+      wait;
+    end loop;
+
+    case 3 is
+      when others =>
+    end case;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::warning(code.s1("bit'('1') = '0'"), "Condition is always false")
+                .with_code(ErrorCode::ConstantCondition),
+            Diagnostic::warning(code.s1("elsif true").s1("true"), "Condition is always true")
+                .with_code(ErrorCode::ConstantCondition),
+            Diagnostic::warning(
+                code.s1("while false").s1("false"),
+                "Condition is always false",
+            )
+            .with_code(ErrorCode::ConstantCondition),
+            Diagnostic::warning(
+                code.s1("case 3").s1("3"),
+                "Case selector is constant, only one alternative can execute",
+            )
+            .with_code(ErrorCode::ConstantCondition),
+        ],
+    );
+}
+
+#[test]
+fn self_comparison_through_alias() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_constant_condition_lint();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    b : natural;
+  end record;
+
+  signal sig : natural;
+  signal rec : rec_t;
+  signal vec : bit_vector(7 downto 0);
+  alias sig_alias is sig;
+  alias rec_alias is rec;
+begin
+  process
+  begin
+    if sig_alias = sig then
+    end if;
+
+    if rec_alias.b /= rec.b then
+    end if;
+
+    if vec(3 downto 0) >= vec(3 downto 0) then
+    end if;
+
+    if vec(sig) < vec(sig) then
+    end if;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::warning(code.s1("sig_alias = sig"), "Condition is always true")
+                .with_code(ErrorCode::ConstantCondition),
+            Diagnostic::warning(code.s1("rec_alias.b /= rec.b"), "Condition is always false")
+                .with_code(ErrorCode::ConstantCondition),
+            Diagnostic::warning(
+                code.s1("vec(3 downto 0) >= vec(3 downto 0)"),
+                "Condition is always true",
+            )
+            .with_code(ErrorCode::ConstantCondition),
+            Diagnostic::warning(code.s1("vec(sig) < vec(sig)"), "Condition is always false")
+                .with_code(ErrorCode::ConstantCondition),
+        ],
+    );
+}
+
+#[test]
+fn conditions_on_constants_and_generics_are_not_flagged() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_constant_condition_lint();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  constant C_DEBUG : boolean := false;
+  constant C_WIDTH : natural := 8;
+end package;
+
+use work.pkg.all;
+
+entity ent is
+  generic (G_ENABLE : boolean := true);
+end entity;
+
+architecture a of ent is
+begin
+  process
+  begin
+    if C_DEBUG then
+    elsif not G_ENABLE then
+    end if;
+
+    if C_WIDTH > 4 then
+    end if;
+
+    case C_WIDTH is
+      when others =>
+    end case;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn constant_condition_lint_is_disabled_by_default() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  process
+  begin
+    if false then
+    end if;
+
+    case 0 is
+      when others =>
+    end case;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn non_constant_conditions_are_not_flagged() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_constant_condition_lint();
+    builder.code(
+        "libname",
+        "
+entity ent is
+  generic (g : natural);
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    b, c : natural;
+  end record;
+
+  signal sig, other : natural;
+  signal rec : rec_t;
+  signal vec : bit_vector(7 downto 0);
+  alias high is vec(7 downto 4);
+  alias low is vec(3 downto 0);
+begin
+  process
+  begin
+    if sig = other then
+    end if;
+
+    if rec.b = rec.c then
+    end if;
+
+    if high = low then
+    end if;
+
+    if vec(3 downto 0) = vec(7 downto 4) then
+    end if;
+
+    while sig < g loop
+    end loop;
+
+    case sig is
+      when others =>
+    end case;
+
+    -- Generics are not locally static
+    case g is
+      when others =>
+    end case;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
@@ -935,7 +935,7 @@ end package body;
 -- Configuration context clause reference
 use work.pkg.all;
 configuration cfg of ename1 is
-for rtl
+for a
 end for;
 end configuration;
 
@@ -1038,3 +1038,59 @@ end package;
 ",
     );
 }
+
+#[test]
+fn library_clause_of_context_declaration_may_not_name_work() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+context ctx is
+  library work;
+end context;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("work"),
+            "Library clause of a context declaration may not name the library work",
+        )],
+    );
+}
+
+#[test]
+fn resolves_library_prefix_of_context_reference() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+context ctx is
+end context;
+
+library libname;
+context libname.ctx;
+
+package pkg is
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let prefix = code.s1("libname.ctx").s1("libname");
+    let ent = root
+        .search_reference(code.source(), prefix.start())
+        .expect("Library prefix has no reference");
+    assert_eq!(
+        ent.designator(),
+        &Designator::Identifier(root.symbol_utf8("libname"))
+    );
+    assert_eq!(
+        root.find_all_references_pos(&prefix.pos()),
+        vec![code.s1("library libname").s1("libname").pos(), prefix.pos()]
+    );
+}
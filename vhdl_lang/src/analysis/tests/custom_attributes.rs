@@ -319,3 +319,35 @@ end architecture;
         )],
     );
 }
+
+#[test]
+fn goto_definition_of_custom_attribute() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    attribute myattr : boolean;
+    signal mysig : natural;
+    attribute myattr of mysig : signal is false;
+    constant c1 : boolean := mysig'myattr;
+begin
+end architecture;
+        ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let usage = code.s1("mysig'myattr").s1("myattr");
+    let ent = root
+        .search_reference(code.source(), usage.start())
+        .expect("Attribute has no reference");
+    assert_eq!(
+        ent.decl_pos(),
+        Some(&code.s1("attribute myattr : boolean").s1("myattr").pos())
+    );
+}
@@ -3,7 +3,7 @@
 // You can obtain one at http://mozilla.org/MPL/2.0/.
 //
 // Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
-use crate::analysis::tests::{check_diagnostics, LibraryBuilder};
+use crate::analysis::tests::{check_diagnostics, check_no_diagnostics, LibraryBuilder};
 use crate::Diagnostic;
 
 #[test]
@@ -95,3 +95,154 @@ end entity test;
         )],
     )
 }
+
+#[test]
+fn constant_referencing_itself() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant c : integer := c + 1;
+constant d : integer_vector(0 to 1) := (0, d(0));
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1(":= c").s1("c"),
+                "constant 'c' references itself in its initial value",
+            ),
+            Diagnostic::error(
+                code.s1("d(0)").s1("d"),
+                "constant 'd' references itself in its initial value",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn full_constant_declaration_referencing_itself() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  constant c : natural;
+end package;
+
+package body pkg is
+  constant c : natural := c + 1;
+end package body;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1(":= c").s1("c"),
+            "constant 'c' references itself in its initial value",
+        )],
+    );
+}
+
+#[test]
+fn mutually_referential_constants() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  constant a : natural;
+  constant b : natural;
+end package;
+
+package body pkg is
+  constant a : natural := b;
+  constant b : natural := a;
+end package body;
+
+package pkg2 is
+  constant a : natural := b;
+  constant b : natural := a;
+end package;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1(":= b").s1("b"),
+                "Deferred constant 'b' is used before its full declaration",
+            ),
+            Diagnostic::error(code.s(":= b", 2).s1("b"), "No declaration of 'b'"),
+        ],
+    );
+}
+
+#[test]
+fn constants_referencing_other_declarations_are_not_self_referential() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  constant early : natural;
+  constant late : natural;
+  constant other : natural := 0;
+end package;
+
+package body pkg is
+  function get_late return natural is
+  begin
+    return late;
+  end function;
+
+  constant early : natural := 1;
+  constant late : natural := early + other;
+  constant again : natural := late;
+end package body;
+
+package pkg2 is
+  constant late : natural := work.pkg.late;
+  type rec_t is record
+    elem : natural;
+  end record;
+  constant elem : rec_t := (elem => 0);
+end package;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn deeply_nested_static_expression() {
+    // The default stack of a test thread is too small for analyzing the expression
+    let diagnostics = std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(|| {
+            let mut builder = LibraryBuilder::new();
+            let depth = 300;
+            builder.in_declarative_region(&format!(
+                "constant c : integer := {}0{};",
+                "(1 + ".repeat(depth),
+                ")".repeat(depth)
+            ));
+            builder.analyze()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.message
+            == "Static expression is nested too deeply to be evaluated"));
+}
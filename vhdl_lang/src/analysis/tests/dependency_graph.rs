@@ -0,0 +1,251 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::ast::UnitId;
+use pretty_assertions::assert_eq;
+
+fn unit_names(root: &DesignRoot, unit_ids: impl Iterator<Item = UnitId>) -> Vec<String> {
+    let libname = root.symbol_utf8("libname");
+    unit_ids
+        .filter(|unit_id| unit_id.library_name() == &libname)
+        .map(|unit_id| unit_id.to_string())
+        .collect()
+}
+
+#[test]
+fn dependency_graph_edges() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  constant width : natural := 8;
+end package;
+
+package body pkg is
+end package body;
+
+context ctx is
+  library libname;
+  use libname.pkg.all;
+end context;
+
+entity sub is
+end entity;
+
+architecture a of sub is
+begin
+end architecture;
+
+entity bound is
+end entity;
+
+architecture a of bound is
+begin
+end architecture;
+
+context work.ctx;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component comp is
+  end component;
+  signal s : bit_vector(width - 1 downto 0);
+begin
+  inst0 : entity work.sub;
+  inst1 : component comp;
+end architecture;
+
+configuration cfg of top is
+  for a
+    for inst1 : comp
+      use entity work.bound;
+    end for;
+  end for;
+end configuration;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let graph = root.dependency_graph();
+    let dependencies = |unit: &str| {
+        let unit_id = graph
+            .nodes
+            .iter()
+            .find(|node| node.unit_id.to_string() == unit)
+            .unwrap()
+            .unit_id
+            .clone();
+        unit_names(
+            &root,
+            graph
+                .dependencies_of(&unit_id)
+                .into_iter()
+                .map(|node| node.unit_id.clone()),
+        )
+    };
+
+    assert_eq!(dependencies("libname.pkg"), Vec::<String>::new());
+    assert_eq!(dependencies("libname.pkg body"), vec!["libname.pkg"]);
+    assert_eq!(dependencies("libname.ctx"), vec!["libname.pkg"]);
+    assert_eq!(dependencies("libname.top"), vec!["libname.ctx"]);
+    assert_eq!(
+        dependencies("libname.top(a)"),
+        vec!["libname.sub", "libname.top"]
+    );
+    assert_eq!(
+        dependencies("libname.cfg"),
+        vec!["libname.bound", "libname.top", "libname.top(a)"]
+    );
+}
+
+#[test]
+fn compile_order_puts_dependencies_first() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  constant width : natural := 8;
+end package;
+
+package body pkg is
+end package body;
+
+context ctx is
+  library libname;
+  use libname.pkg.all;
+end context;
+
+entity sub is
+end entity;
+
+architecture a of sub is
+begin
+end architecture;
+
+entity bound is
+end entity;
+
+architecture a of bound is
+begin
+end architecture;
+
+context work.ctx;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component comp is
+  end component;
+  signal s : bit_vector(width - 1 downto 0);
+begin
+  inst0 : entity work.sub;
+  inst1 : component comp;
+end architecture;
+
+configuration cfg of top is
+  for a
+    for inst1 : comp
+      use entity work.bound;
+    end for;
+  end for;
+end configuration;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let order = root.compile_order().unwrap();
+    assert_eq!(
+        unit_names(&root, order.into_iter().map(|node| node.unit_id)),
+        vec![
+            "libname.bound",
+            "libname.bound(a)",
+            "libname.pkg",
+            "libname.ctx",
+            "libname.pkg body",
+            "libname.sub",
+            "libname.sub(a)",
+            "libname.top",
+            "libname.top(a)",
+            "libname.cfg",
+        ]
+    );
+}
+
+#[test]
+fn compile_order_reports_cycle() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+use work.pkg2.all;
+
+package pkg1 is
+end package;
+
+use work.pkg1.all;
+
+package pkg2 is
+end package;
+",
+    );
+    let (root, _) = builder.get_analyzed_root();
+
+    let error = root.compile_order().unwrap_err();
+    assert_eq!(
+        unit_names(&root, error.cycle.into_iter()),
+        vec!["libname.pkg1", "libname.pkg2", "libname.pkg1"]
+    );
+}
+
+#[test]
+fn dependency_graph_serialization() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+end package;
+
+package body pkg is
+end package body;
+",
+    );
+    let (root, _) = builder.get_analyzed_root();
+
+    let graph = root.dependency_graph();
+    let pkg = graph
+        .nodes
+        .iter()
+        .position(|node| node.unit_id.to_string() == "libname.pkg")
+        .unwrap();
+    let body = graph
+        .nodes
+        .iter()
+        .position(|node| node.unit_id.to_string() == "libname.pkg body")
+        .unwrap();
+    let file_name = code.source().file_name().to_string_lossy().to_string();
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph dependencies {\n"));
+    assert!(dot.contains(&format!(
+        "  \"libname.pkg body\" [label=\"libname.pkg body\\npackage body\", tooltip=\"{file_name}\"];\n"
+    )));
+    assert!(dot.contains("  \"libname.pkg body\" -> \"libname.pkg\";\n"));
+
+    let json = graph.to_json();
+    assert!(json.contains(&format!(
+        "{{\"library\":\"libname\",\"primary_unit\":\"pkg\",\"name\":\"pkg\",\"kind\":\"package body\",\"file\":\"{file_name}\"}}"
+    )));
+    assert!(json.contains(&format!("{{\"from\":{body},\"to\":{pkg}}}")));
+}
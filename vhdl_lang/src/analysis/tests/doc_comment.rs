@@ -0,0 +1,201 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+fn hover_doc(root: &DesignRoot, name: Code) -> Option<String> {
+    root.hover(name.source(), name.start()).unwrap().doc_comment
+}
+
+#[test]
+fn doc_comment_of_entity() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+library std;
+
+------------------------------
+-- The top level
+--   with two lines
+------------------------------
+entity ent is
+  port (
+    -- The clock
+    clk : in bit;
+    rst : in bit -- Active high reset
+  );
+end entity;
+
+architecture a of ent is
+  -- A documented signal
+  signal documented, other : bit;
+
+  -- Not attached to the signal below
+
+  signal undocumented : bit;
+
+  type state_t is (
+    -- The idle state
+    idle,
+    busy);
+begin
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover_doc(&root, code.s1("ent is")),
+        Some("The top level\n  with two lines".to_owned())
+    );
+}
+
+#[test]
+fn doc_comment_of_ports() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+library std;
+
+------------------------------
+-- The top level
+--   with two lines
+------------------------------
+entity ent is
+  port (
+    -- The clock
+    clk : in bit;
+    rst : in bit -- Active high reset
+  );
+end entity;
+
+architecture a of ent is
+  -- A documented signal
+  signal documented, other : bit;
+
+  -- Not attached to the signal below
+
+  signal undocumented : bit;
+
+  type state_t is (
+    -- The idle state
+    idle,
+    busy);
+begin
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover_doc(&root, code.s1("clk")),
+        Some("The clock".to_owned())
+    );
+    assert_eq!(
+        hover_doc(&root, code.s1("rst")),
+        Some("Active high reset".to_owned())
+    );
+}
+
+#[test]
+fn doc_comment_of_signals() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+library std;
+
+------------------------------
+-- The top level
+--   with two lines
+------------------------------
+entity ent is
+  port (
+    -- The clock
+    clk : in bit;
+    rst : in bit -- Active high reset
+  );
+end entity;
+
+architecture a of ent is
+  -- A documented signal
+  signal documented, other : bit;
+
+  -- Not attached to the signal below
+
+  signal undocumented : bit;
+
+  type state_t is (
+    -- The idle state
+    idle,
+    busy);
+begin
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover_doc(&root, code.s1("documented,")),
+        Some("A documented signal".to_owned())
+    );
+    assert_eq!(
+        hover_doc(&root, code.s1("other")),
+        Some("A documented signal".to_owned())
+    );
+    assert_eq!(
+        hover_doc(&root, code.s1("idle,")),
+        Some("The idle state".to_owned())
+    );
+    assert_eq!(hover_doc(&root, code.s1("busy")), None);
+}
+
+#[test]
+fn blank_line_breaks_doc_comment() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+library std;
+
+------------------------------
+-- The top level
+--   with two lines
+------------------------------
+entity ent is
+  port (
+    -- The clock
+    clk : in bit;
+    rst : in bit -- Active high reset
+  );
+end entity;
+
+architecture a of ent is
+  -- A documented signal
+  signal documented, other : bit;
+
+  -- Not attached to the signal below
+
+  signal undocumented : bit;
+
+  type state_t is (
+    -- The idle state
+    idle,
+    busy);
+begin
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(hover_doc(&root, code.s1("undocumented")), None);
+}
@@ -0,0 +1,73 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::syntax::VHDLParser;
+
+#[test]
+fn duplicate_and_missing_declarations_have_codes() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant cst : natural := 0;
+constant cst : natural := missing;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            duplicate(&code, "cst", 1, 2).with_code(ErrorCode::DuplicateDecl),
+            missing(&code, "missing", 1).with_code(ErrorCode::MissingDecl),
+        ],
+    );
+}
+
+#[test]
+fn mode_violation_has_code() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    input : in bit);
+end entity;
+
+architecture a of ent is
+begin
+  input <= '1';
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| (&diagnostic.pos, diagnostic.code))
+            .collect::<Vec<_>>(),
+        vec![(
+            &code.s1("input <= ").s1("input").pos(),
+            Some(ErrorCode::ModeViolation)
+        )]
+    );
+}
+
+#[test]
+fn syntax_errors_have_code() {
+    let code = Code::new(
+        "
+entity ent is
+end entity
+",
+    );
+    let mut diagnostics = Vec::new();
+    VHDLParser::default().parse_design_source(code.source(), &mut diagnostics);
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.code == Some(ErrorCode::SyntaxError)));
+}
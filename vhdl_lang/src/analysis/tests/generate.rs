@@ -0,0 +1,231 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn same_declaration_in_different_alternatives() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  generic (sel : natural);
+end entity;
+
+architecture a of ent is
+begin
+  gen_if: if alt1: sel = 0 generate
+    signal s : bit;
+  begin
+    s <= '0';
+  end alt1;
+  elsif alt2: sel = 1 generate
+    signal s : bit;
+  begin
+    s <= '1';
+  end alt2;
+  else alt3: generate
+    signal s : bit;
+  begin
+    s <= '0';
+  end alt3;
+  end generate;
+
+  gen_case: case sel generate
+    when alt1: 0 =>
+      signal s : bit;
+    begin
+      s <= '0';
+    end alt1;
+    when alt2: others =>
+      signal s : bit;
+    begin
+      s <= '1';
+  end generate;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for (alternative, nth) in [
+        ("alt1", 1),
+        ("alt2", 2),
+        ("alt3", 3),
+        ("alt1", 4),
+        ("alt2", 5),
+    ] {
+        let decl = root
+            .search_reference(code.source(), code.s("s <=", nth).start())
+            .unwrap();
+        assert_eq!(
+            decl.decl_pos(),
+            Some(&code.s("signal s", nth).sa("signal ", "s").pos())
+        );
+        assert_eq!(
+            decl.parent.map(|parent| parent.designator().to_string()),
+            Some(alternative.to_owned())
+        );
+    }
+}
+
+#[test]
+fn duplicate_declaration_within_alternative() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  gen: if alt1: true generate
+    signal s : bit;
+    signal s : bit;
+  begin
+  end generate;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(code.s("s : bit", 2).s1("s"), "Duplicate declaration of 's'")
+                .related(code.s1("s : bit").s1("s"), "Previously defined here")
+                .with_code(ErrorCode::DuplicateDecl),
+        ],
+    );
+}
+
+#[test]
+fn generate_parameter_is_constant_of_range_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type idx_t is range 0 to 7;
+begin
+  gen: for i in idx_t generate
+    constant c : idx_t := i;
+    signal s : bit_vector(0 to idx_t'pos(i));
+  begin
+    s <= (others => '0');
+  end generate;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1(":= i").s1("i").start()),
+        Some(code.s1("for i").s1("i").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("pos(i)").s1("i").start()),
+        Some(code.s1("for i").s1("i").pos())
+    );
+}
+
+#[test]
+fn generate_parameter_type_must_match() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  gen: for i in 0 to 3 generate
+    constant c : bit := i;
+  begin
+  end generate;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1(":= i").s1("i"),
+            "constant 'i' of integer type 'INTEGER' does not match type 'BIT'",
+        )
+        .with_code(ErrorCode::TypeMismatch)],
+    );
+}
+
+#[test]
+fn generate_parameter_is_not_a_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  gen: for i in 0 to 3 generate
+  begin
+    i <= 0;
+  end generate;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("i <=").s1("i"),
+            "constant 'i' may not be the target of an assignment",
+        )
+        .with_code(ErrorCode::ModeViolation)],
+    );
+}
+
+#[test]
+fn search_alternative_label() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  gen: case 0 generate
+    when alt: others =>
+    begin
+    end alt;
+  end generate;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let alt = root
+        .search_reference(code.source(), code.sa("end ", "alt").start())
+        .unwrap();
+    assert_eq!(alt.decl_pos(), Some(&code.s1("alt").pos()));
+    assert_eq!(
+        root.find_all_references(alt),
+        vec![code.s1("alt").pos(), code.sa("end ", "alt").pos()]
+    );
+}
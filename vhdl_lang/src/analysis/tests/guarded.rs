@@ -0,0 +1,183 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn guarded_block_with_disconnection_specification() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    clk : in bit;
+    d : in bit
+  );
+end entity;
+
+architecture a of ent is
+  function resolve(values : bit_vector) return bit is
+  begin
+    return values(values'left);
+  end function;
+
+  signal q : resolve bit register;
+  signal b : resolve bit bus;
+  disconnect q : bit after 1 ns;
+  disconnect others : bit after 2 ns;
+begin
+  blk: block (clk = '1' and not clk'stable) is
+  begin
+    q <= guarded d;
+    b <= guarded transport d after 1 ns when guard else '0';
+  end block;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let guard = root
+        .search_reference(code.source(), code.s1("when guard").s1("guard").start())
+        .unwrap();
+    assert_eq!(
+        guard.decl_pos(),
+        Some(&code.s1("clk = '1' and not clk'stable").pos())
+    );
+}
+
+#[test]
+fn guard_is_not_visible_outside_of_block() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : boolean;
+begin
+  blk: block (true) is
+  begin
+  end block;
+
+  s <= guard;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("<= guard").s1("guard"),
+            "No declaration of 'guard'",
+        )],
+    );
+}
+
+#[test]
+fn guard_condition_must_be_boolean() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  blk: block (0) is
+  begin
+  end block;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("(0)").s1("0"),
+            "type universal_integer cannot be implictly converted to type 'BOOLEAN'. \
+             Operator ?? is not defined for this type.",
+        )
+        .with_code(ErrorCode::TypeMismatch)],
+    );
+}
+
+#[test]
+fn disconnect_must_name_guarded_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c : bit := '0';
+  signal s : bit;
+  disconnect s : bit after 1 ns;
+  disconnect c : bit after 1 ns;
+begin
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.sa("disconnect ", "s"),
+                "signal 's' is not a guarded signal",
+            )
+            .with_code(ErrorCode::MismatchedKinds),
+            Diagnostic::error(
+                code.sa("disconnect ", "c"),
+                "constant 'c' is not a guarded signal",
+            )
+            .with_code(ErrorCode::MismatchedKinds),
+        ],
+    );
+}
+
+#[test]
+fn disconnect_signal_must_have_type_of_type_mark() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  function resolve(values : bit_vector) return bit is
+  begin
+    return values(values'left);
+  end function;
+
+  signal s : resolve bit bus;
+  disconnect s : boolean after 1 ns;
+begin
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.sa("disconnect ", "s"),
+            "signal 's' does not match type 'BOOLEAN'",
+        )
+        .with_code(ErrorCode::TypeMismatch)],
+    );
+}
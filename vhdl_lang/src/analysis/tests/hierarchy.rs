@@ -56,9 +56,13 @@ end architecture;
                     "a",
                     vec![
                         single("s0"),
-                        // @TODO add tree for anonymous block and process
-                        single("v0"),
-                        single("loop0"),
+                        nested(
+                            "block (line 14)",
+                            vec![nested(
+                                "process (line 16)",
+                                vec![single("v0"), single("loop0")]
+                            )]
+                        ),
                     ]
                 ),
             ]
@@ -66,6 +70,47 @@ end architecture;
     );
 }
 
+#[test]
+fn labeled_and_unlabeled_processes() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  named: process
+    variable v0 : natural;
+  begin
+  end process;
+
+  process
+    variable v1 : natural;
+  begin
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+    assert_eq!(
+        get_hierarchy(&root, "libname", code.source()),
+        vec![nested(
+            "ent",
+            vec![nested(
+                "a",
+                vec![
+                    nested("named", vec![single("v0")]),
+                    nested("process (line 12)", vec![single("v1")]),
+                ]
+            )]
+        )]
+    );
+}
+
 #[test]
 fn package() {
     let mut builder = LibraryBuilder::new();
@@ -240,6 +285,87 @@ end architecture;
     );
 }
 
+#[test]
+fn workspace_symbols_are_ranked() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package my_old_fifo_v2 is
+end package;
+
+package fifo_ctrl is
+  constant FIFO_DEPTH : natural := 16;
+end package;
+
+package first_in_first_out is
+end package;
+
+package unrelated is
+end package;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let names = |query: &str, limit: usize| -> Vec<String> {
+        root.workspace_symbols(query, limit)
+            .into_iter()
+            .map(|ent| ent.designator().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        names("fifo", 10),
+        vec![
+            "fifo_ctrl",
+            "FIFO_DEPTH",
+            "my_old_fifo_v2",
+            "first_in_first_out"
+        ]
+    );
+    assert_eq!(names("FIFO", 2), vec!["fifo_ctrl", "FIFO_DEPTH"]);
+    assert_eq!(names("xyz", 10), Vec::<String>::new());
+}
+
+#[test]
+fn workspace_symbols_are_updated_when_units_are_analyzed() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package old_fifo is
+end package;
+      ",
+    );
+    let changed = builder.snippet(
+        "
+package new_fifo is
+end package;
+      ",
+    );
+
+    let (mut root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let names = |root: &DesignRoot| -> Vec<String> {
+        root.workspace_symbols("fifo", 10)
+            .into_iter()
+            .map(|ent| ent.designator().to_string())
+            .collect()
+    };
+    assert_eq!(names(&root), vec!["old_fifo"]);
+
+    let libname = root.symbol_utf8("libname");
+    root.remove_source(libname.clone(), code.source());
+    root.add_design_file(libname, changed.design_file());
+    let mut diagnostics = Vec::new();
+    root.analyze(&mut diagnostics);
+    check_no_diagnostics(&diagnostics);
+    assert_eq!(names(&root), vec!["new_fifo"]);
+}
+
 #[derive(PartialEq, Debug)]
 struct NameHierarchy {
     name: String,
@@ -264,7 +390,11 @@ fn single(name: &str) -> NameHierarchy {
 impl<'a> From<EntHierarchy<'a>> for NameHierarchy {
     fn from(ent: EntHierarchy) -> Self {
         NameHierarchy {
-            name: ent.ent.designator().to_string(),
+            name: if ent.ent.is_anonymous() {
+                ent.ent.describe()
+            } else {
+                ent.ent.designator().to_string()
+            },
             children: ent.children.into_iter().map(NameHierarchy::from).collect(),
         }
     }
@@ -320,6 +450,156 @@ end architecture;
     assert_eq!(root.find_implementation(comp), vec![ent]);
 }
 
+#[test]
+fn find_implementation_of_component_bound_to_entity_in_visible_library() {
+    let mut builder = LibraryBuilder::new();
+    let lib2 = builder.code(
+        "lib2",
+        "
+entity ent0 is
+end entity;
+      ",
+    );
+    let code = builder.code(
+        "libname",
+        "
+library lib2;
+
+entity ent1 is
+end entity;
+
+architecture a of ent1 is
+  component ent0 is
+  end component;
+begin
+  inst: ent0;
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let ent = root
+        .search_reference(lib2.source(), lib2.s1("ent0").start())
+        .unwrap();
+    let comp = root
+        .search_reference(code.source(), code.sa("component ", "ent0").start())
+        .unwrap();
+
+    assert_eq!(root.find_implementation(comp), vec![ent]);
+}
+
+#[test]
+fn find_implementations_of_entity_with_two_architectures() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+
+architecture sim of ent is
+begin
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.find_implementations(code.source(), code.sb("ent", " is").start()),
+        vec![code.s1("rtl").pos(), code.s1("sim").pos()]
+    );
+    // From a reference to the entity
+    assert_eq!(
+        root.find_implementations(code.source(), code.sa("rtl of ", "ent").start()),
+        vec![code.s1("rtl").pos(), code.s1("sim").pos()]
+    );
+}
+
+#[test]
+fn find_implementations_of_overloaded_subprograms() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  function fun(arg : natural) return natural;
+  function fun(arg : boolean) return natural;
+  constant deferred : natural;
+end package;
+
+package body pkg is
+  function fun(arg : boolean) return natural is
+  begin
+    return 1;
+  end function;
+
+  function fun(arg : natural) return natural is
+  begin
+    return 0;
+  end function;
+
+  constant deferred : natural := 0;
+end package body;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.find_implementations(code.source(), code.s1("pkg").start()),
+        vec![code.s("pkg", 2).pos()]
+    );
+    assert_eq!(
+        root.find_implementations(code.source(), code.s("fun(", 1).s1("fun").start()),
+        vec![code.s("fun(", 4).s1("fun").pos()]
+    );
+    assert_eq!(
+        root.find_implementations(code.source(), code.s("fun(", 2).s1("fun").start()),
+        vec![code.s("fun(", 3).s1("fun").pos()]
+    );
+    assert_eq!(
+        root.find_implementations(code.source(), code.s1("deferred").start()),
+        vec![code.s("deferred", 2).pos()]
+    );
+}
+
+#[test]
+fn find_implementations_of_subprogram_in_entity_header() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  procedure proc;
+end entity;
+
+architecture a of ent is
+  procedure proc is
+  begin
+  end procedure;
+begin
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.find_implementations(code.source(), code.sb("proc", ";").start()),
+        vec![code.sb("proc", " is").pos()]
+    );
+}
+
 #[test]
 fn exit_and_next_outside_of_loop() {
     let mut builder = LibraryBuilder::new();
@@ -408,3 +688,53 @@ end architecture;
         ],
     );
 }
+
+#[test]
+fn loop_and_generate_labels_are_referenced() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  gen: for i in 0 to 1 generate
+  begin
+    process
+    begin
+      outer: loop
+        inner: loop
+          next outer;
+          exit inner;
+        end loop inner;
+      end loop outer;
+    end process;
+  end generate gen;
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for name in ["outer", "inner"] {
+        assert_eq!(
+            root.search_reference_pos(code.source(), code.sa(" ", name).start()),
+            Some(code.s1(name).pos())
+        );
+        assert_eq!(
+            root.find_all_references_pos(&code.s1(name).pos()),
+            vec![
+                code.s(name, 1).pos(),
+                code.s(name, 2).pos(),
+                code.s(name, 3).pos()
+            ]
+        );
+    }
+    assert_eq!(
+        root.find_all_references_pos(&code.s1("gen").pos()),
+        vec![code.s1("gen").pos(), code.sa("end generate ", "gen").pos()]
+    );
+}
@@ -9,7 +9,7 @@ use crate::analysis::DesignRoot;
 use crate::ast::search::*;
 use crate::data::SrcPos;
 use crate::named_entity::{EntityId, Reference};
-use crate::syntax::TokenAccess;
+use crate::syntax::{TokenAccess, VHDLParser};
 use fnv::FnvHashSet;
 use pretty_assertions::assert_eq;
 
@@ -158,7 +158,7 @@ end architecture;
         "libname",
         "
 configuration cfg of ent is
-for rtl
+for a
 end for;
 end configuration;
 ",
@@ -258,6 +258,107 @@ end package body;
     check_incremental_analysis(builder, vec![]);
 }
 
+#[test]
+fn analyze_incremental_only_reanalyzes_dependents_of_changed_source() {
+    let mut builder = LibraryBuilder::new();
+    let code_a = builder.code(
+        "libname",
+        "
+package pkg_a is
+  constant const : natural := 0;
+end package;
+",
+    );
+
+    let code_b = builder.code(
+        "libname",
+        "
+use work.pkg_a.const;
+
+package pkg_b is
+end package;
+",
+    );
+
+    builder.code(
+        "libname",
+        "
+package pkg_c is
+end package;
+",
+    );
+
+    let parser = VHDLParser {
+        symbols: builder.symbols(),
+        ..VHDLParser::default()
+    };
+    let (mut root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+    let num_analyzed_units = root.num_analyzed_units();
+
+    code_a
+        .source()
+        .change(None, "package pkg_a is\nend package;\n");
+
+    let mut diagnostics = Vec::new();
+    let analyzed: FnvHashSet<_> = root
+        .analyze_incremental(&[code_a.source().clone()], &parser, &mut diagnostics)
+        .into_iter()
+        .map(|unit_id| unit_id.primary_name().name_utf8())
+        .collect();
+
+    // The unrelated package pkg_c keeps its previous analysis result
+    assert_eq!(
+        analyzed,
+        ["pkg_a".to_owned(), "pkg_b".to_owned()]
+            .into_iter()
+            .collect()
+    );
+    assert_eq!(root.num_analyzed_units(), num_analyzed_units + 2);
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code_b.s1("const"),
+            "No declaration of 'const' within package 'pkg_a'",
+        )],
+    );
+
+    // The stale diagnostic of pkg_b is evicted when the change is reverted
+    code_a.source().change(
+        None,
+        "package pkg_a is\n  constant const : natural := 0;\nend package;\n",
+    );
+    let mut diagnostics = Vec::new();
+    root.analyze_incremental(&[code_a.source().clone()], &parser, &mut diagnostics);
+    check_no_diagnostics(&diagnostics);
+    assert_eq!(root.num_analyzed_units(), num_analyzed_units + 4);
+}
+
+#[test]
+fn analyze_incremental_parses_with_the_standard_of_the_library() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.snippet("");
+    let parser = VHDLParser {
+        symbols: builder.symbols(),
+        ..VHDLParser::default()
+    };
+    let (mut root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    // The file has no design units to take the standard from
+    let design_file = code
+        .clone()
+        .with_standard(VHDLStandard::V2019)
+        .design_file();
+    root.add_source_file(root.symbol_utf8("libname"), code.source(), design_file, &[]);
+
+    code.source()
+        .change(None, "entity ent is\n  port (a : bit;);\nend entity;\n");
+    let mut diagnostics = Vec::new();
+    root.analyze_incremental(&[code.source().clone()], &parser, &mut diagnostics);
+    check_no_diagnostics(&diagnostics);
+}
+
 fn check_incremental_analysis(builder: LibraryBuilder, expected_diagnostics: Vec<Diagnostic>) {
     let symbols = builder.symbols();
     let codes = builder.take_code();
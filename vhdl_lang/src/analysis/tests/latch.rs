@@ -0,0 +1,293 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn missing_else_branch_infers_latch() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    en : in bit;
+    d : in bit;
+    q : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  comb: process (en, d)
+  begin
+    if en = '1' then
+      q <= d;
+    end if;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::warning(
+            code.s1("comb"),
+            "Signal 'q' may infer a latch since it is not assigned in all branches",
+        )
+        .related(
+            code.s1("if en = '1' then\n      q <= d;\n    end if;"),
+            "Not assigned when no condition is true",
+        )
+        .with_code(ErrorCode::InferredLatch)],
+    );
+}
+
+#[test]
+fn branches_without_assignment_are_related() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    sel : in bit_vector(1 downto 0);
+    a, b : in bit;
+    y, z : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  process (all)
+  begin
+    z <= '0';
+    case sel is
+      when \"00\" =>
+        y <= a;
+      when \"01\" =>
+        if a = '1' then
+          y <= b;
+        else
+          z <= b;
+        end if;
+      when others =>
+        null;
+    end case;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::warning(
+            code.s1("process (all)\n  begin\n").s1("process"),
+            "Signal 'y' may infer a latch since it is not assigned in all branches",
+        )
+        .related(code.s1("z <= b;"), "Not assigned in this branch")
+        .related(code.s1("others"), "Not assigned in this branch")
+        .with_code(ErrorCode::InferredLatch)],
+    );
+}
+
+#[test]
+fn default_assignment_before_branches_does_not_infer_latch() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    en : in bit;
+    d : in bit_vector(3 downto 0);
+    q : out bit_vector(3 downto 0);
+    valid : out bit);
+end entity;
+
+architecture a of ent is
+  signal full_valid : bit;
+begin
+  comb: process (en, d)
+  begin
+    q <= (others => '0');
+    for i in 0 to 3 loop
+      valid <= '0';
+    end loop;
+    if en = '1' then
+      q(0) <= d(0);
+      valid <= '1';
+    elsif d = \"0000\" then
+      q <= d;
+    end if;
+  end process;
+
+  full: process (en)
+  begin
+    if en = '1' then
+      full_valid <= '1';
+    else
+      full_valid <= '0';
+    end if;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn clocked_process_does_not_infer_latch() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    clk, rst : in bit;
+    d : in bit;
+    q1, q2, q3 : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  reg1: process (clk)
+  begin
+    if rising_edge(clk) then
+      q1 <= d;
+    end if;
+  end process;
+
+  reg2: process (clk, rst)
+  begin
+    if rst = '1' then
+      q2 <= '0';
+    elsif clk'event and clk = '1' then
+      q2 <= d;
+    end if;
+  end process;
+
+  reg3: process
+  begin
+    wait until clk = '1';
+    if d = '1' then
+      q3 <= d;
+    end if;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn signal_read_by_procedure_is_not_assigned() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    en : in bit;
+    din : in bit);
+end entity;
+
+architecture a of ent is
+  procedure show(constant v : in bit) is
+  begin
+  end procedure;
+begin
+  process (en, din)
+  begin
+    if en = '1' then
+      show(din);
+    end if;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn signal_associated_with_out_parameter_is_assigned() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    en : in bit;
+    d : in bit;
+    q, r : out bit);
+end entity;
+
+architecture a of ent is
+  procedure drive(constant v : in bit; signal o : out bit) is
+  begin
+    o <= v;
+  end procedure;
+begin
+  comb: process (en, d)
+  begin
+    drive(d, r);
+    if en = '1' then
+      drive(o => q, v => d);
+    end if;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::warning(
+            code.s1("comb"),
+            "Signal 'q' may infer a latch since it is not assigned in all branches",
+        )
+        .related(
+            code.s1("if en = '1' then\n      drive(o => q, v => d);\n    end if;"),
+            "Not assigned when no condition is true",
+        )
+        .with_code(ErrorCode::InferredLatch)],
+    );
+}
+
+#[test]
+fn libraries_without_synthesis_lint_are_not_checked_for_latches() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("rtl_lib");
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    en : in bit;
+    d : in bit;
+    q : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  process (en, d)
+  begin
+    if en = '1' then
+      q <= d;
+    end if;
+  end process;
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
@@ -0,0 +1,288 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::ast::{PrimaryKind, SecondaryKind};
+use crate::{SecondaryUnitDescription, UnitDescription};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn lists_units_of_libraries() {
+    let mut builder = LibraryBuilder::new();
+    let pkg = builder.code(
+        "lib1",
+        "
+package pkg is
+end package;
+",
+    );
+    let body = builder.code(
+        "lib1",
+        "
+package body pkg is
+end package body;
+",
+    );
+    let ent = builder.code(
+        "lib2",
+        "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+
+architecture a of ent is
+begin
+end architecture;
+
+context ctx is
+end context;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let lib1 = root.symbol_utf8("lib1");
+    let lib2 = root.symbol_utf8("lib2");
+
+    let mut libraries: Vec<_> = root
+        .available_libraries()
+        .map(|sym| sym.name_utf8())
+        .collect();
+    libraries.sort();
+    assert_eq!(libraries, vec!["lib1", "lib2", "std"]);
+
+    assert_eq!(
+        root.units_in_library(&lib1),
+        vec![UnitDescription {
+            library_name: lib1.clone(),
+            kind: PrimaryKind::Package,
+            name: root.symbol_utf8("pkg"),
+            source: pkg.source().clone(),
+            pos: pkg.s1("pkg").pos(),
+            secondary_units: vec![SecondaryUnitDescription {
+                kind: SecondaryKind::PackageBody,
+                name: root.symbol_utf8("pkg"),
+                source: body.source().clone(),
+                pos: body.s1("pkg").pos(),
+            }],
+        }]
+    );
+
+    assert_eq!(
+        root.units_in_library(&lib2),
+        vec![
+            UnitDescription {
+                library_name: lib2.clone(),
+                kind: PrimaryKind::Context,
+                name: root.symbol_utf8("ctx"),
+                source: ent.source().clone(),
+                pos: ent.s1("ctx").pos(),
+                secondary_units: vec![],
+            },
+            UnitDescription {
+                library_name: lib2.clone(),
+                kind: PrimaryKind::Entity,
+                name: root.symbol_utf8("ent"),
+                source: ent.source().clone(),
+                pos: ent.s1("entity ent").s("ent", 2).pos(),
+                secondary_units: vec![
+                    SecondaryUnitDescription {
+                        kind: SecondaryKind::Architecture,
+                        name: root.symbol_utf8("a"),
+                        source: ent.source().clone(),
+                        pos: ent.s1("a of ent").s1("a").pos(),
+                    },
+                    SecondaryUnitDescription {
+                        kind: SecondaryKind::Architecture,
+                        name: root.symbol_utf8("rtl"),
+                        source: ent.source().clone(),
+                        pos: ent.s1("rtl").pos(),
+                    },
+                ],
+            },
+        ]
+    );
+
+    assert!(root
+        .units_in_library(&root.symbol_utf8("missing"))
+        .is_empty());
+}
+
+#[test]
+fn finds_unit_by_library_and_name() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "lib1",
+        "
+package pkg is
+end package;
+",
+    );
+    let ent = builder.code(
+        "lib2",
+        "
+entity pkg is
+end entity;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let unit = root
+        .find_unit(&root.symbol_utf8("lib2"), &root.symbol_utf8("pkg"))
+        .unwrap();
+    assert_eq!(unit.kind, PrimaryKind::Entity);
+    assert_eq!(unit.source, ent.source().clone());
+    assert_eq!(unit.pos, ent.s1("pkg").pos());
+
+    assert_eq!(
+        root.find_unit(&root.symbol_utf8("lib1"), &root.symbol_utf8("pkg"))
+            .map(|unit| unit.kind),
+        Some(PrimaryKind::Package)
+    );
+    assert_eq!(
+        root.find_unit(&root.symbol_utf8("lib1"), &root.symbol_utf8("missing")),
+        None
+    );
+    assert_eq!(
+        root.find_unit(&root.symbol_utf8("missing"), &root.symbol_utf8("pkg")),
+        None
+    );
+}
+
+fn unused_unit_names(root: &DesignRoot, top: &str) -> Vec<String> {
+    root.unused_units(&[(root.symbol_utf8("libname"), root.symbol_utf8(top))])
+        .into_iter()
+        .filter(|unit| unit.library_name == root.symbol_utf8("libname"))
+        .map(|unit| unit.name.name_utf8())
+        .collect()
+}
+
+#[test]
+fn unused_units_are_not_reachable_from_top() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package used_pkg is
+  constant width : natural := 8;
+end package;
+
+package body used_pkg is
+end package body;
+
+package unused_pkg is
+  constant depth : natural := 8;
+end package;
+
+entity leaf is
+end entity;
+
+architecture a of leaf is
+begin
+end architecture;
+
+entity comp_leaf is
+end entity;
+
+architecture a of comp_leaf is
+begin
+end architecture;
+
+use work.used_pkg.all;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component comp_leaf is
+  end component;
+  signal s : bit_vector(width - 1 downto 0);
+begin
+  inst0 : entity work.leaf;
+
+  gen : if true generate
+    inst1 : comp_leaf;
+  end generate;
+end architecture;
+
+use work.unused_pkg.all;
+
+entity unused is
+end entity;
+
+architecture a of unused is
+  signal s : bit_vector(depth - 1 downto 0);
+begin
+  inst : entity work.leaf;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        unused_unit_names(&root, "top"),
+        vec!["unused", "unused_pkg"]
+    );
+    assert_eq!(
+        unused_unit_names(&root, "unused"),
+        vec!["comp_leaf", "top", "used_pkg"]
+    );
+}
+
+#[test]
+fn unused_units_follow_configurations() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity sub is
+end entity;
+
+architecture a of sub is
+begin
+end architecture;
+
+entity other is
+end entity;
+
+architecture a of other is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component comp is
+  end component;
+begin
+  inst : comp;
+end architecture;
+
+configuration cfg of top is
+  for a
+    for inst : comp
+      use entity work.sub;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(unused_unit_names(&root, "cfg"), vec!["other"]);
+    assert_eq!(unused_unit_names(&root, "top"), vec!["cfg", "other", "sub"]);
+}
@@ -4,28 +4,55 @@
 //
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
+mod access_type;
+mod assertion;
 mod assignment_typecheck;
 mod association_formal;
+mod choice_coverage;
 mod circular_dependencies;
+mod component_binding;
+mod configuration;
+mod constant_condition;
 mod context_clause;
 mod custom_attributes;
 mod declarations;
 mod deferred_constant;
+mod dependency_graph;
+mod doc_comment;
+mod error_codes;
+mod generate;
+mod guarded;
 mod hierarchy;
 mod homographs;
 mod implicit;
 mod incomplete_type;
 mod incremental_analysis;
+mod latch;
+mod library_units;
+mod multiple_drivers;
 mod package_instance;
+mod parallel_analysis;
+mod port_length;
+mod port_mode;
 mod protected_type;
+mod psl;
+mod purity;
+mod record_fields;
+mod rename;
+mod resolution_function;
 mod resolves_design_units;
 mod resolves_names;
 mod resolves_type_mark;
+mod return_paths;
 mod sensitivity_list;
+mod static_value;
 mod subprogram_arguments;
 mod subprogram_instance;
+mod synthesis_lint;
 mod tool_directive;
+mod type_at;
 mod typecheck_expression;
+mod uninitialized;
 mod util;
 mod visibility;
 
@@ -35,9 +62,11 @@ pub use self::util::*;
 use crate::ast::Designator;
 use crate::ast::UnitId;
 pub use crate::data::Diagnostic;
+pub use crate::data::ErrorCode;
 use crate::data::NoDiagnostics;
 pub use crate::syntax::test::*;
 use crate::syntax::Token;
+use crate::VHDLStandard;
 
 use super::analyze::AnalyzeContext;
 use super::scope::*;
@@ -77,6 +106,7 @@ impl<'a> TestSetup<'a> {
             ),
             &self.arena,
             &self.tokens,
+            VHDLStandard::default(),
         );
         ctx.add_implicit_context_clause(&self.scope).unwrap();
         ctx
@@ -0,0 +1,298 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn unresolved_signal_driven_by_two_processes() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+end entity;
+
+architecture a of top is
+  signal sig : std_ulogic;
+begin
+  p1: process
+  begin
+    sig <= '0';
+    wait;
+  end process;
+
+  p2: process
+  begin
+    sig <= '1';
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("sig <= '0'").s1("sig"),
+            "Signal 'sig' has multiple drivers",
+        )
+        .related(code.s1("sig <= '1'").s1("sig"), "Also driven here")],
+    );
+}
+
+#[test]
+fn same_process_is_a_single_driver() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture a of top is
+  signal sig : bit;
+begin
+  process
+  begin
+    sig <= '0';
+    wait for 1 ns;
+    sig <= '1';
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn distinct_static_elements_are_separate_drivers() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture a of top is
+  type rec_t is record
+    field1 : bit;
+    field2 : bit;
+  end record;
+  constant idx : natural := 3;
+  signal vec : bit_vector(0 to 7);
+  signal rec : rec_t;
+begin
+  vec(0) <= '0';
+  vec(1) <= '1';
+  vec(2 to 3) <= \"01\";
+  vec(4 to 7) <= \"0101\";
+  vec(idx) <= '0';
+  rec.field1 <= '0';
+  rec.field2 <= '1';
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn overlapping_static_elements_are_multiple_drivers() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture a of top is
+  signal vec : bit_vector(0 to 7);
+begin
+  vec(0 to 3) <= \"0000\";
+  vec(3) <= '1';
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s1("vec(0 to 3)"), "Signal 'vec' has multiple drivers")
+                .related(code.s1("vec(3)"), "Also driven here"),
+        ],
+    );
+}
+
+#[test]
+fn non_static_index_drives_whole_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture a of top is
+  signal vec : bit_vector(0 to 7);
+begin
+  process
+  begin
+    for i in vec'range loop
+      vec(i) <= '0';
+    end loop;
+    wait;
+  end process;
+
+  vec(7) <= '1';
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s1("vec(i)"), "Signal 'vec' has multiple drivers")
+                .related(code.s1("vec(7)"), "Also driven here"),
+        ],
+    );
+}
+
+#[test]
+fn resolved_signal_with_multiple_drivers_is_a_warning() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+end entity;
+
+architecture a of top is
+  signal bus_sig : std_logic;
+  signal vec : std_logic_vector(0 to 1);
+begin
+  bus_sig <= '0';
+  bus_sig <= 'Z';
+  vec <= \"00\";
+  vec <= \"ZZ\";
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::warning(
+                code.s1("bus_sig <= '0'").s1("bus_sig"),
+                "Resolved signal 'bus_sig' has multiple drivers",
+            )
+            .related(code.s1("bus_sig <= 'Z'").s1("bus_sig"), "Also driven here"),
+            Diagnostic::warning(
+                code.s1("vec <= \"00\"").s1("vec"),
+                "Resolved signal 'vec' has multiple drivers",
+            )
+            .related(code.s1("vec <= \"ZZ\"").s1("vec"), "Also driven here"),
+        ],
+    );
+}
+
+#[test]
+fn output_port_driven_internally_and_by_port_map() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity sub is
+  port (
+    din : in bit;
+    dout : out bit
+  );
+end entity;
+
+architecture a of sub is
+begin
+  dout <= din;
+end architecture;
+
+entity top is
+  port (
+    result : out bit
+  );
+end entity;
+
+architecture a of top is
+  signal input : bit;
+begin
+  inst: entity work.sub
+    port map (
+      din => input,
+      dout => result
+    );
+
+  result <= '0';
+  input <= '1';
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("dout => result").s1("result"),
+            "Signal 'result' has multiple drivers",
+        )
+        .related(code.s1("result <= '0'").s1("result"), "Also driven here")],
+    );
+}
+
+#[test]
+fn block_statements_are_separate_drivers() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture a of top is
+  signal sig : bit;
+begin
+  blk: block
+  begin
+    sig <= '0';
+  end block;
+
+  sig <= '1';
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("sig <= '0'").s1("sig"),
+            "Signal 'sig' has multiple drivers",
+        )
+        .related(code.s1("sig <= '1'").s1("sig"), "Also driven here")],
+    );
+}
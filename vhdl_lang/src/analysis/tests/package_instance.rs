@@ -608,3 +608,67 @@ end package;
         vec![code.s("sub_t", 1).pos(), code.s("sub_t", 3).pos(),]
     );
 }
+
+#[test]
+fn use_constant_and_function_of_library_package_instance() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package generic_pkg is
+  generic (
+    type elem_t;
+    width : natural;
+    init_value : elem_t
+  );
+
+  type arr_t is array (0 to width - 1) of elem_t;
+  constant init : elem_t := init_value;
+  constant zeros : arr_t := (others => init_value);
+
+  function first(arr : arr_t) return elem_t;
+end package;
+
+package body generic_pkg is
+  function first(arr : arr_t) return elem_t is
+  begin
+    return arr(0);
+  end function;
+end package body;
+
+package my_inst is new work.generic_pkg generic map (
+  elem_t => character,
+  width => 8,
+  init_value => 'a');
+
+use work.my_inst.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c0 : character := init;
+  constant c1 : character := first(zeros);
+  constant c2 : arr_t := (others => 'b');
+  signal s0 : character := first(c2);
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    // References through the instance resolve to the declarations of the generic package
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("first(zeros)").start()),
+        Some(code.s1("function first").s1("first").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(
+            code.source(),
+            code.s1("character := init;").s1("init").start()
+        ),
+        Some(code.s1("constant init").s1("init").pos())
+    );
+}
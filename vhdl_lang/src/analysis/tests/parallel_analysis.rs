@@ -0,0 +1,177 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::analysis::{AnalysisPhase, AnalysisProgress};
+use crate::ast::SecondaryKind;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Packages that depend on each other in a chain, each with a user that has errors.
+/// The chain forces later units to wait for the analysis of earlier ones.
+fn add_units(builder: &mut LibraryBuilder, count: usize) {
+    for idx in 0..count {
+        let library = if idx % 2 == 0 { "lib1" } else { "lib2" };
+        let use_clause = if idx == 0 {
+            String::new()
+        } else {
+            let prev_library = if idx % 2 == 0 { "lib2" } else { "lib1" };
+            format!(
+                "library {prev_library};\nuse {prev_library}.pkg{}.all;\n",
+                idx - 1
+            )
+        };
+        builder.code(
+            library,
+            &format!(
+                "
+{use_clause}
+package pkg{idx} is
+  constant c{idx} : natural := 0;
+  constant bad{idx} : natural := missing{idx};
+end package;
+
+use work.pkg{idx}.all;
+
+entity ent{idx} is
+end entity;
+
+architecture a of ent{idx} is
+  signal sig : natural := c{idx};
+  signal sig : boolean := c{idx};
+begin
+end architecture;
+"
+            ),
+        );
+    }
+}
+
+fn analyze_with_threads(builder: &LibraryBuilder, num_threads: usize) -> Vec<Diagnostic> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| builder.analyze())
+}
+
+#[test]
+fn parallel_analysis_gives_same_diagnostics_as_sequential() {
+    let mut builder = LibraryBuilder::new();
+    add_units(&mut builder, 16);
+
+    let sequential = analyze_with_threads(&builder, 1);
+    // Each unit has a missing name, a duplicate signal and a type error
+    assert_eq!(sequential.len(), 16 * 3);
+
+    for _ in 0..4 {
+        assert_eq!(analyze_with_threads(&builder, 8), sequential);
+    }
+}
+
+#[test]
+fn diagnostics_are_sorted_by_file_and_position() {
+    let mut builder = LibraryBuilder::new();
+    add_units(&mut builder, 8);
+
+    let diagnostics = builder.analyze();
+    let mut sorted = diagnostics.clone();
+    sorted.sort_by(|lhs, rhs| lhs.pos.cmp(&rhs.pos));
+    assert_eq!(diagnostics, sorted);
+}
+
+/// Counts the started and finished callbacks of each unit
+#[derive(Default)]
+struct CountingProgress {
+    phases: Mutex<Vec<AnalysisPhase>>,
+    units: Mutex<HashMap<String, (usize, usize)>>,
+}
+
+impl AnalysisProgress for CountingProgress {
+    fn phase(&self, phase: AnalysisPhase) {
+        self.phases.lock().unwrap().push(phase);
+    }
+
+    fn file_parsed(&self, _file_name: &str) {}
+
+    fn unit_started(&self, library_name: &str, unit_name: &str) {
+        let mut units = self.units.lock().unwrap();
+        let counts = units
+            .entry(format!("{library_name}.{unit_name}"))
+            .or_default();
+        assert_eq!(counts, &(0, 0), "{library_name}.{unit_name} started twice");
+        counts.0 += 1;
+    }
+
+    fn unit_finished(&self, library_name: &str, unit_name: &str) {
+        let mut units = self.units.lock().unwrap();
+        let counts = units
+            .entry(format!("{library_name}.{unit_name}"))
+            .or_default();
+        assert_eq!(
+            counts,
+            &(1, 0),
+            "{library_name}.{unit_name} not started once"
+        );
+        counts.1 += 1;
+    }
+}
+
+#[test]
+fn progress_reports_every_unit_once() {
+    let mut builder = LibraryBuilder::new();
+    add_units(&mut builder, 8);
+    builder.code(
+        "lib1",
+        "
+package pkg is
+end package;
+
+package body pkg is
+end package body;",
+    );
+
+    let progress = CountingProgress::default();
+    let (root, _) = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap()
+        .install(|| builder.get_analyzed_root_with_progress(Some(&progress)));
+
+    assert_eq!(
+        progress.phases.into_inner().unwrap(),
+        vec![
+            AnalysisPhase::Preparing,
+            AnalysisPhase::Analyzing,
+            AnalysisPhase::Finished
+        ]
+    );
+
+    let units = progress.units.into_inner().unwrap();
+    let mut num_units = 0;
+    for library in ["std", "lib1", "lib2"] {
+        for unit in root.units_in_library(&root.symbol_utf8(library)) {
+            num_units += 1;
+            assert_eq!(
+                units.get(&format!("{library}.{}", unit.name)),
+                Some(&(1, 1))
+            );
+            for secondary in unit.secondary_units.iter() {
+                num_units += 1;
+                let name = match secondary.kind {
+                    SecondaryKind::PackageBody => format!("{library}.{} body", unit.name),
+                    SecondaryKind::Architecture => {
+                        format!("{library}.{}({})", unit.name, secondary.name)
+                    }
+                };
+                assert_eq!(units.get(&name), Some(&(1, 1)));
+            }
+        }
+    }
+    assert_eq!(units.len(), num_units);
+    assert!(units.contains_key("lib1.pkg body"));
+    assert!(units.contains_key("lib1.ent0(a)"));
+}
@@ -0,0 +1,245 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn port_length_of_instance_with_static_generics() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity sub is
+  generic (
+    width : natural;
+    depth : natural := 4);
+  port (
+    d : in bit_vector(width - 1 downto 0);
+    q : out bit_vector(0 to width + depth - 1));
+end entity;
+",
+    );
+    builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant bits : natural := 8;
+  signal d : bit_vector(7 downto 0);
+  signal q : bit_vector(11 downto 0);
+begin
+  inst0: entity work.sub
+    generic map (bits)
+    port map (d, open);
+
+  inst1: entity work.sub
+    generic map (width => 8, depth => 4)
+    port map (d => d, q => q);
+
+  inst2: entity work.sub
+    generic map (width => 4)
+    port map (d => d(3 downto 0), q => open);
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn port_length_mismatch_of_instance() {
+    let mut builder = LibraryBuilder::new();
+    let sub = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (
+    width : natural;
+    depth : natural := 4);
+  port (
+    d : in bit_vector(width - 1 downto 0);
+    q : out bit_vector(0 to width + depth - 1));
+end entity;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal d : bit_vector(7 downto 0);
+  signal q : bit_vector(11 downto 0);
+begin
+  inst0: entity work.sub
+    generic map (width => 9, depth => 3)
+    port map (d => d, q => q);
+
+  inst1: entity work.sub
+    generic map (7)
+    port map (d, open);
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("d => d").sa("=> ", "d"),
+                "Expected an array of length 9, got length 8",
+            )
+            .related(
+                sub.s1("d :").s1("d"),
+                "Port 'd' has length 9 with the generics of the instance",
+            )
+            .with_code(ErrorCode::LengthMismatch),
+            Diagnostic::error(
+                code.s1("port map (d,").sa("(", "d"),
+                "Expected an array of length 7, got length 8",
+            )
+            .related(
+                sub.s1("d :").s1("d"),
+                "Port 'd' has length 7 with the generics of the instance",
+            )
+            .with_code(ErrorCode::LengthMismatch),
+        ],
+    );
+}
+
+#[test]
+fn port_length_is_not_checked_for_non_static_generics() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity sub is
+  generic (
+    width : natural;
+    depth : natural := 4);
+  port (
+    d : in bit_vector(width - 1 downto 0);
+    q : out bit_vector(0 to width + depth - 1));
+end entity;
+",
+    );
+    builder.code(
+        "libname",
+        "
+entity ent is
+  generic (outer_width : natural);
+end entity;
+
+architecture a of ent is
+  signal d : bit_vector(7 downto 0);
+  signal q : bit_vector(11 downto 0);
+  signal n : bit_vector(outer_width - 1 downto 0);
+begin
+  inst0: entity work.sub
+    generic map (width => outer_width)
+    port map (d => d, q => q);
+
+  inst1: entity work.sub
+    generic map (width => outer_width + 1)
+    port map (d => d, q => open);
+
+  inst2: entity work.sub
+    generic map (width => 4)
+    port map (d => n, q => open);
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn port_length_mismatch_of_component_instance() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  component comp is
+    generic (width : natural);
+    port (d : in bit_vector(1 to width));
+  end component;
+
+  signal d : bit_vector(7 downto 0);
+begin
+  inst: comp
+    generic map (width => 16)
+    port map (d => d);
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("d => d").sa("=> ", "d"),
+            "Expected an array of length 16, got length 8",
+        )
+        .related(
+            code.s1("d : in").s1("d"),
+            "Port 'd' has length 16 with the generics of the instance",
+        )
+        .with_code(ErrorCode::LengthMismatch)],
+    );
+}
+
+#[test]
+fn port_length_mismatch_with_default_generic() {
+    let mut builder = LibraryBuilder::new();
+    let sub = builder.code(
+        "libname",
+        "
+entity sub is
+  generic (
+    width : natural;
+    depth : natural := 4);
+  port (
+    d : in bit_vector(width - 1 downto 0);
+    q : out bit_vector(0 to width + depth - 1));
+end entity;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal d : bit_vector(7 downto 0);
+  signal q : bit_vector(10 downto 0);
+begin
+  inst: entity work.sub
+    generic map (width => 8)
+    port map (d => d, q => q);
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("q => q").sa("=> ", "q"),
+            "Expected an array of length 12, got length 11",
+        )
+        .related(
+            sub.s1("q :").s1("q"),
+            "Port 'q' has length 12 with the generics of the instance",
+        )
+        .with_code(ErrorCode::LengthMismatch)],
+    );
+}
@@ -0,0 +1,269 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::VHDLStandard;
+
+#[test]
+fn assigning_in_port_is_an_error() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    i : in bit_vector(0 to 1);
+    o : out bit_vector(0 to 1);
+    b : buffer bit;
+    io : inout bit;
+    l : linkage bit);
+end entity;
+
+architecture a of ent is
+begin
+  process
+  begin
+    i <= \"00\";
+    i(0) <= '0';
+    i(0 to 1) <= \"00\";
+    wait;
+  end process;
+end architecture;",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("i <= \"00\"").s1("i"),
+                "interface signal 'i' of mode in may not be the target of an assignment",
+            ),
+            Diagnostic::error(
+                code.s1("i(0)"),
+                "interface signal 'i' of mode in may not be the target of an assignment",
+            ),
+            Diagnostic::error(
+                code.s1("i(0 to 1)"),
+                "interface signal 'i' of mode in may not be the target of an assignment",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn reading_out_port_before_vhdl_2008_is_an_error() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+entity ent is
+  port (
+    i : in bit_vector(0 to 1);
+    o : out bit_vector(0 to 1);
+    b : buffer bit;
+    io : inout bit;
+    l : linkage bit);
+end entity;
+
+architecture a of ent is
+  signal s : bit_vector(0 to 1);
+  signal t : bit;
+begin
+  o <= i;
+  s <= o;
+  t <= o(1);
+end architecture;",
+        VHDLStandard::V1993,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("s <= o").s1("o"),
+                "Reading interface signal 'o' of mode out requires VHDL-2008",
+            ),
+            Diagnostic::error(
+                code.s1("o(1)"),
+                "Reading interface signal 'o' of mode out requires VHDL-2008",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn reading_out_port_is_allowed_in_vhdl_2008() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    i : in bit_vector(0 to 1);
+    o : out bit_vector(0 to 1);
+    b : buffer bit;
+    io : inout bit;
+    l : linkage bit);
+end entity;
+
+architecture a of ent is
+  signal s : bit_vector(0 to 1);
+  signal t : bit;
+begin
+  o <= i;
+  s <= o;
+  t <= o(1);
+end architecture;",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn buffer_and_inout_ports_may_be_read_and_assigned() {
+    let mut builder = LibraryBuilder::new();
+    builder.code_with_standard(
+        "libname",
+        "
+entity ent is
+  port (
+    i : in bit_vector(0 to 1);
+    o : out bit_vector(0 to 1);
+    b : buffer bit;
+    io : inout bit;
+    l : linkage bit);
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+begin
+  b <= '0';
+  io <= b;
+  s <= io;
+end architecture;",
+        VHDLStandard::V1993,
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn linkage_port_may_neither_be_read_nor_assigned() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    i : in bit_vector(0 to 1);
+    o : out bit_vector(0 to 1);
+    b : buffer bit;
+    io : inout bit;
+    l : linkage bit);
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+begin
+  l <= '0';
+  s <= l;
+end architecture;",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("l <= '0'").s1("l"),
+                "interface signal 'l' of mode linkage may not be the target of an assignment",
+            ),
+            Diagnostic::error(
+                code.s1("s <= l").s1("l"),
+                "interface signal 'l' of mode linkage may not be read",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn parameter_modes() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  procedure proc(signal x : in bit; variable y : out bit);
+end package;
+
+package body pkg is
+  procedure proc(signal x : in bit; variable y : out bit) is
+    variable v : bit;
+  begin
+    x <= '0';
+    y := x;
+    v := y;
+  end procedure;
+end package body;
+",
+        VHDLStandard::V1993,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("x <= '0'").s1("x"),
+            "interface signal 'x' of mode in may not be the target of an assignment",
+        )],
+    );
+}
+
+#[test]
+fn out_port_may_be_associated_with_output_before_vhdl_2008() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+entity child is
+  port (
+    x : in bit;
+    y : out bit);
+end entity;
+
+architecture a of child is
+begin
+  y <= x;
+end architecture;
+
+entity ent is
+  port (
+    p : out bit;
+    q : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  inst1 : entity work.child port map (x => '0', y => p);
+  inst2 : entity work.child port map (x => p, y => q);
+end architecture;
+",
+        VHDLStandard::V1993,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("x => p").s1("p"),
+            "Reading interface signal 'p' of mode out requires VHDL-2008",
+        )],
+    );
+}
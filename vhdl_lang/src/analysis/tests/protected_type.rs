@@ -5,6 +5,7 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 use super::*;
+use crate::VHDLStandard;
 
 #[test]
 fn error_on_missing_protected_body() {
@@ -336,3 +337,122 @@ end package body;
         2
     );
 }
+
+#[test]
+fn protected_methods_are_visible_from_shared_variable() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type counter_t is protected
+    procedure increment;
+    impure function get return natural;
+  end protected;
+end package;
+
+package body pkg is
+  type counter_t is protected body
+    variable count : natural := 0;
+
+    procedure increment is
+    begin
+      count := count + 1;
+    end procedure;
+
+    impure function get return natural is
+    begin
+      return count;
+    end function;
+  end protected body;
+end package body;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  shared variable counter : counter_t;
+  signal value : natural;
+begin
+  incrementer: process
+  begin
+    counter.increment;
+    wait;
+  end process;
+
+  reader: process
+  begin
+    value <= counter.get;
+    wait;
+  end process;
+end architecture;",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(
+            code.source(),
+            code.s1("counter.increment").s1("increment").start()
+        ),
+        Some(code.s1("procedure increment;").s1("increment").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("counter.get").s1("get").start()),
+        Some(code.s1("function get return natural;").s1("get").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("counter.get").s1("counter").start()),
+        Some(code.s1("shared variable counter").s1("counter").pos())
+    );
+}
+
+#[test]
+fn error_on_shared_variable_of_non_protected_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type prot_t is protected
+  end protected;
+
+  subtype prot_sub_t is prot_t;
+  shared variable ok : prot_t;
+  shared variable also_ok : prot_sub_t;
+  shared variable bad : natural;
+end package;
+
+package body pkg is
+  type prot_t is protected body
+  end protected body;
+end package body;",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("bad : natural").s1("natural"),
+            "Shared variable 'bad' must be of a protected type",
+        )],
+    );
+}
+
+#[test]
+fn shared_variable_of_non_protected_type_is_allowed_before_vhdl_2002() {
+    let mut builder = LibraryBuilder::new();
+    builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  shared variable count : natural;
+end package;",
+        VHDLStandard::V1993,
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn psl_in_comments() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal clk, req, ack : bit;
+  -- psl default clock is rising_edge(clk);
+begin
+  -- psl assert always req -> next ack;
+  -- psl cover {req; missing};
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("missing"),
+            "No declaration of 'missing'",
+        )],
+    );
+}
+
+#[test]
+fn inline_psl() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal clk, req, ack : bit;
+  default clock is rising_edge(clk);
+  sequence handshake is {req; ack[*1 to inf]};
+  property responds is always req -> next ack;
+begin
+  assert responds report \"no response\" severity error;
+  assume always req |=> ack;
+  cover handshake;
+  lbl : cover {req[*2]; ack} @ falling_edge(clk);
+end architecture;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn psl_condition_must_be_boolean() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal clk : bit;
+  signal value : natural;
+  default clock is clk;
+begin
+  assert always value -> next clk;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    assert!(
+        diagnostics
+            .iter()
+            .any(|diag| diag.pos == code.s1("value ->").s1("value").pos()),
+        "{diagnostics:?}"
+    );
+}
+
+#[test]
+fn search_psl_property() {
+    check_search_reference(
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal req, ack : bit;
+  property decl is always req -> next ack;
+begin
+  assert decl;
+end architecture;
+",
+    );
+}
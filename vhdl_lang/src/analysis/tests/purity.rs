@@ -0,0 +1,157 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn pure_function_reads_outer_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+signal sig : natural;
+
+function get return natural is
+  variable local : natural := 0;
+begin
+  return sig + local;
+end function;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("sig +").s1("sig"),
+            "Pure function cannot reference signal 'sig' declared outside of it",
+        )
+        .related(code.s1("get"), "Function 'get' is pure")
+        .with_code(ErrorCode::PurityViolation)],
+    );
+}
+
+#[test]
+fn impure_function_may_read_outer_signal() {
+    let mut builder = LibraryBuilder::new();
+    builder.in_declarative_region(
+        "
+signal sig : natural;
+
+impure function get(arg : natural) return natural is
+begin
+  return sig + arg;
+end function;
+
+function pure_get(arg : natural) return natural is
+  variable local : natural := arg;
+begin
+  return local;
+end function;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn pure_function_calls_impure_function() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+impure function random return natural is
+begin
+  return 4;
+end function;
+
+function get return natural is
+begin
+  return random;
+end function;
+
+function get_time return time is
+begin
+  return now;
+end function;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.sa("return ", "random"),
+                "Pure function cannot call impure function 'random'",
+            )
+            .related(code.sa("function ", "get"), "Function 'get' is pure")
+            .with_code(ErrorCode::PurityViolation),
+            Diagnostic::error(
+                code.s1("now"),
+                "Pure function cannot call impure function 'NOW'",
+            )
+            .related(code.s1("get_time"), "Function 'get_time' is pure")
+            .with_code(ErrorCode::PurityViolation),
+        ],
+    );
+}
+
+#[test]
+fn pure_function_references_shared_variable_and_file() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type counter_t is protected
+  impure function value return natural;
+end protected;
+
+type counter_t is protected body
+  variable count : natural := 0;
+
+  impure function value return natural is
+  begin
+    return count;
+  end function;
+end protected body;
+
+type text_t is file of character;
+file text_file : text_t;
+shared variable counter : counter_t;
+
+function get return boolean is
+begin
+  return counter.value = 0 and endfile(text_file);
+end function;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("counter.value").s1("counter"),
+                "Pure function cannot reference shared variable 'counter'",
+            )
+            .related(code.sa("function ", "get"), "Function 'get' is pure")
+            .with_code(ErrorCode::PurityViolation),
+            Diagnostic::error(
+                code.s1("counter.value").s1("value"),
+                "Pure function cannot call impure function 'value'",
+            )
+            .related(code.sa("function ", "get"), "Function 'get' is pure")
+            .with_code(ErrorCode::PurityViolation),
+            Diagnostic::error(
+                code.s1("endfile"),
+                "Pure function cannot call file operation 'ENDFILE'",
+            )
+            .related(code.sa("function ", "get"), "Function 'get' is pure")
+            .with_code(ErrorCode::PurityViolation),
+            Diagnostic::error(
+                code.s1("(text_file)").s1("text_file"),
+                "Pure function cannot reference file 'text_file' declared outside of it",
+            )
+            .related(code.sa("function ", "get"), "Function 'get' is pure")
+            .with_code(ErrorCode::PurityViolation),
+        ],
+    );
+}
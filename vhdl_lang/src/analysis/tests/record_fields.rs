@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn nested_selection_interleaved_with_indexing() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type master_t is record
+    addr : bit_vector(7 downto 0);
+    valid : boolean;
+  end record;
+
+  type master_arr_t is array (natural range <>) of master_t;
+
+  type bus_t is record
+    master : master_t;
+    slaves : master_arr_t(0 to 3);
+  end record;
+
+  type system_t is record
+    bus_a : bus_t;
+  end record;
+end package;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sys : system_t;
+  signal buses : bus_t;
+  signal b : bit;
+begin
+  sys.bus_a.master.addr(3) <= '1';
+  b <= sys.bus_a.slaves(2).addr(3);
+
+  process
+    variable v : boolean;
+  begin
+    v := sys.bus_a.slaves(1).valid and buses.slaves(0).valid;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("addr(3)", 1).start()),
+        Some(code.s1("addr").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("addr(3)", 2).start()),
+        Some(code.s1("addr").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("valid and", 1).start()),
+        Some(code.s1("valid").pos())
+    );
+}
+
+#[test]
+fn misspelled_field_at_second_level() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type master_t is record
+    addr : bit_vector(7 downto 0);
+  end record;
+
+  type master_arr_t is array (natural range <>) of master_t;
+
+  type bus_t is record
+    master : master_t;
+    slaves : master_arr_t(0 to 3);
+  end record;
+
+  signal bus_s : bus_t;
+begin
+  bus_s.master.adr(3) <= '1';
+  bus_s.slaves(0).adr <= (others => '0');
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s("adr", 1),
+                "No declaration of 'adr' within record type 'master_t'",
+            )
+            .related(code.s1("master_t"), "Record type 'master_t' declared here")
+            .with_code(ErrorCode::MissingDecl),
+            Diagnostic::error(
+                code.s("adr", 2),
+                "No declaration of 'adr' within record type 'master_t'",
+            )
+            .related(code.s1("master_t"), "Record type 'master_t' declared here")
+            .with_code(ErrorCode::MissingDecl),
+        ],
+    );
+}
+
+#[test]
+fn references_of_field_through_different_signals() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type inner_t is record
+    field : natural;
+  end record;
+
+  type inner_arr_t is array (0 to 1) of inner_t;
+
+  type outer_t is record
+    inner : inner_t;
+    inners : inner_arr_t;
+  end record;
+
+  signal sig1 : outer_t;
+  signal sig2 : outer_t;
+begin
+  sig1.inner.field <= 1;
+  sig2.inners(1).field <= sig1.inners(0).field;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq_unordered(
+        &root.find_all_references_pos(&code.s1("field").pos()),
+        &[
+            code.s("field", 1).pos(),
+            code.s("field", 2).pos(),
+            code.s("field", 3).pos(),
+            code.s("field", 4).pos(),
+        ],
+    );
+}
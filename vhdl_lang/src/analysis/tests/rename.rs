@@ -0,0 +1,236 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::data::SrcPos;
+use pretty_assertions::assert_eq;
+
+fn edits(positions: Vec<SrcPos>, new_name: &str) -> Vec<(SrcPos, String)> {
+    let mut edits: Vec<_> = positions
+        .into_iter()
+        .map(|pos| (pos, new_name.to_owned()))
+        .collect();
+    edits.sort_by(|(pos1, _), (pos2, _)| pos1.cmp(pos2));
+    edits
+}
+
+#[test]
+fn rename_signal_used_in_two_architectures() {
+    let mut builder = LibraryBuilder::new();
+    let pkg = builder.code(
+        "libname",
+        "
+package pkg is
+  signal sig : natural;
+end package;
+",
+    );
+
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+use work.pkg.all;
+
+architecture a of ent is
+begin
+  sig <= 0;
+end architecture;
+
+use work.pkg.all;
+
+architecture b of ent is
+begin
+  SIG <= 1;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.rename(&code.s1("SIG").pos(), "new_sig"),
+        Ok(edits(
+            vec![
+                pkg.s1("sig :").s1("sig").pos(),
+                code.s1("sig").pos(),
+                code.s1("SIG").pos()
+            ],
+            "new_sig"
+        ))
+    );
+}
+
+#[test]
+fn rename_to_extended_identifier() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant foo : natural := 0;
+constant bar : natural := foo;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.rename(&code.s1("foo").pos(), "\\new name\\"),
+        Ok(edits(
+            vec![code.s("foo", 1).pos(), code.s("foo", 2).pos()],
+            "\\new name\\"
+        ))
+    );
+}
+
+#[test]
+fn rename_rejects_illegal_identifiers() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant foo : natural := 0;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for new_name in [
+        "", "1foo", "foo__bar", "foo_", "signal", "foo bar", " foo", "foo -- c",
+    ] {
+        assert_eq!(
+            root.rename(&code.s1("foo").pos(), new_name),
+            Err(Diagnostic::error(
+                code.s1("foo"),
+                format!("'{new_name}' is not a legal identifier")
+            ))
+        );
+    }
+}
+
+#[test]
+fn rename_rejects_collision() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant foo : natural := 0;
+constant bar : natural := foo;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.rename(&code.s1("foo").pos(), "BAR"),
+        Err(Diagnostic::error(
+            code.s1("foo"),
+            "Cannot rename constant 'foo' since it would collide with constant 'bar'"
+        )
+        .related(code.s1("bar"), "Previously defined here"))
+    );
+
+    // Changing the case of the name is not a collision
+    assert_eq!(
+        root.rename(&code.s1("foo").pos(), "FOO"),
+        Ok(edits(
+            vec![code.s("foo", 1).pos(), code.s("foo", 2).pos()],
+            "FOO"
+        ))
+    );
+}
+
+#[test]
+fn rename_entity_referenced_by_component_instantiation() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component ent is
+  end component;
+begin
+  inst: ent;
+  inst2: entity work.ent;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let mut positions: Vec<_> = (1..=3)
+        .map(|occ| code.s("ent is", occ).s1("ent").pos())
+        .collect();
+    positions.push(code.s1("inst: ent").s1("ent").pos());
+    positions.push(code.s1("work.ent").s1("ent").pos());
+
+    assert_eq!(
+        root.rename(&code.s1("ent is").s1("ent").pos(), "renamed"),
+        Ok(edits(positions, "renamed"))
+    );
+}
+
+#[test]
+fn rename_rejects_collision_with_declaration_in_nested_scope() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : natural;
+  signal other : natural;
+begin
+  main: process
+    variable var : natural;
+  begin
+    var := sig;
+    other <= var;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    // The variable would hide the signal where it is read
+    assert_eq!(
+        root.rename(&code.s1("sig :").s1("sig").pos(), "var"),
+        Err(Diagnostic::error(
+            code.s1("sig :").s1("sig"),
+            "Cannot rename signal 'sig' since it would collide with variable 'var'"
+        )
+        .related(code.s1("var :").s1("var"), "Previously defined here"))
+    );
+
+    // The variable would hide the signal that is assigned in the process
+    assert_eq!(
+        root.rename(&code.s1("var :").s1("var").pos(), "other"),
+        Err(Diagnostic::error(
+            code.s1("var :").s1("var"),
+            "Cannot rename variable 'var' since it would collide with signal 'other'"
+        )
+        .related(code.s1("other :").s1("other"), "Previously defined here"))
+    );
+}
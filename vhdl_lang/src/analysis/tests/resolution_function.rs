@@ -0,0 +1,174 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn resolved_subtypes() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec_t is record
+  a : bit;
+  b : integer;
+end record;
+
+function resolve_bit(values : bit_vector) return bit;
+function resolve_int(values : integer_vector) return integer;
+
+subtype rbit_t is resolve_bit bit;
+subtype rbit_vector_t is (resolve_bit) bit_vector;
+subtype rrec_t is (a resolve_bit, b resolve_int) rec_t;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(
+            code.source(),
+            code.s1("is resolve_bit").s1("resolve_bit").start()
+        ),
+        Some(code.s1("function resolve_bit").s1("resolve_bit").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(
+            code.source(),
+            code.s1("(resolve_bit)").s1("resolve_bit").start()
+        ),
+        Some(code.s1("function resolve_bit").s1("resolve_bit").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(
+            code.source(),
+            code.s1("b resolve_int").s1("resolve_int").start()
+        ),
+        Some(code.s1("function resolve_int").s1("resolve_int").pos())
+    );
+}
+
+#[test]
+fn resolution_function_with_wrong_profile() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+function resolve(value : bit) return bit;
+
+subtype rbit_t is resolve bit;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("is resolve").s1("resolve"),
+            "No resolution function 'resolve' for type 'BIT'",
+        )
+        .related(
+            code.s1("function resolve").s1("resolve"),
+            "Does not match function resolve[BIT return BIT]",
+        )
+        .with_code(ErrorCode::InvalidResolution)],
+    );
+}
+
+#[test]
+fn resolution_function_must_be_pure() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+impure function resolve(values : bit_vector) return bit;
+
+subtype rbit_t is resolve bit;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("is resolve").s1("resolve"),
+            "Resolution function 'resolve' must be pure",
+        )
+        .related(
+            code.s1("function resolve").s1("resolve"),
+            "Declared as impure here",
+        )
+        .with_code(ErrorCode::InvalidResolution)],
+    );
+}
+
+#[test]
+fn resolution_function_must_be_a_function() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant resolve : bit := '0';
+
+subtype rbit_t is resolve bit;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("is resolve").s1("resolve"),
+            "Expected resolution function, got constant 'resolve'",
+        )
+        .related(code.s1("constant resolve").s1("resolve"), "Defined here")
+        .with_code(ErrorCode::MismatchedKinds)],
+    );
+}
+
+#[test]
+fn record_element_resolution_of_missing_field() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec_t is record
+  a : bit;
+end record;
+
+function resolve(values : bit_vector) return bit;
+
+subtype rrec_t is (missing resolve) rec_t;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("missing"),
+            "No declaration of 'missing' within record type 'rec_t'",
+        )
+        .related(
+            code.sa("type ", "rec_t"),
+            "Record type 'rec_t' declared here",
+        )
+        .with_code(ErrorCode::MissingDecl)],
+    );
+}
+
+#[test]
+fn element_resolution_requires_array_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+function resolve(values : bit_vector) return bit;
+
+subtype rbit_t is (resolve) bit;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::error(
+            code.s1("(resolve)").s1("resolve"),
+            "Element resolution requires an array type, got type 'BIT'",
+        )
+        .with_code(ErrorCode::InvalidResolution)],
+    );
+}
@@ -6,6 +6,7 @@
 
 use super::resolves_type_mark::kind_error;
 use super::*;
+use crate::ast::search::ReferenceKind;
 
 #[test]
 fn error_on_configuration_before_entity_in_same_file() {
@@ -21,6 +22,10 @@ end configuration;
 
 entity ent is
 end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
 ",
     );
 
@@ -64,6 +69,10 @@ fn good_configurations() {
 entity ent is
 end entity;
 
+architecture rtl of ent is
+begin
+end architecture;
+
 configuration cfg_good1 of ent is
 for rtl
 end for;
@@ -122,6 +131,10 @@ fn search_reference_from_configuration_to_entity() {
 entity decl is
 end entity;
 
+architecture rtl of decl is
+begin
+end architecture;
+
 configuration cfg_good1 of decl is
 for rtl
 end for;
@@ -151,7 +164,7 @@ end architecture;
         builder.analyze(),
         vec![Diagnostic::error(
             code.s("missing", 1),
-            "No primary unit 'missing' within library 'libname'",
+            "No entity 'missing' within library 'libname'",
         )],
     );
 }
@@ -196,7 +209,7 @@ end package body;
         builder.analyze(),
         vec![Diagnostic::error(
             code.s("missing", 1),
-            "No primary unit 'missing' within library 'libname'",
+            "No package 'missing' within library 'libname'",
         )],
     );
 }
@@ -356,12 +369,19 @@ fn search_configuration_instance() {
 entity ent is
 end entity;
 
+architecture a of ent is
+begin
+end architecture;
+
 configuration decl of ent is
   for a
   end for;
 end configuration;
 
-architecture a of ent is
+entity top is
+end entity;
+
+architecture a of top is
 begin
   inst : configuration work.decl;
 end architecture;
@@ -539,3 +559,359 @@ end architecture;
         Some(&code.s1("empty").pos())
     );
 }
+
+#[test]
+fn resolves_entity_and_architecture_of_entity_instance() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+architecture rtl of ename1 is
+begin
+end architecture;
+
+entity ename2 is
+end entity;
+
+library libname;
+
+architecture a of ename2 is
+begin
+  inst : entity work.ename1(rtl);
+  inst2 : entity libname.ename1(rtl);
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for occurrence in [3, 4] {
+        assert_eq!(
+            root.search_reference_pos(code.source(), code.s("ename1", occurrence).start()),
+            Some(code.s("ename1", 1).pos())
+        );
+    }
+    for occurrence in [2, 3] {
+        assert_eq!(
+            root.search_reference_pos(code.source(), code.s("rtl", occurrence).start()),
+            Some(code.s("rtl", 1).pos())
+        );
+    }
+}
+
+#[test]
+fn resolves_entity_of_instance_with_missing_architecture() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+entity ename2 is
+end entity;
+
+architecture a of ename2 is
+begin
+  inst : entity work.ename1(missing);
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("missing"),
+            "No architecture 'missing' for entity 'libname.ename1'",
+        )],
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("ename1", 2).start()),
+        Some(code.s("ename1", 1).pos())
+    );
+}
+
+#[test]
+fn resolves_configuration_specification() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+architecture rtl of ename1 is
+begin
+end architecture;
+
+entity ename2 is
+end entity;
+
+architecture a of ename2 is
+  component cname is
+  end component;
+  for inst : cname use entity work.ename1(rtl);
+begin
+  inst : cname;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.sa("for inst : ", "cname").start()),
+        Some(code.s1("cname").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("ename1", 3).start()),
+        Some(code.s("ename1", 1).pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("rtl", 2).start()),
+        Some(code.s("rtl", 1).pos())
+    );
+    assert_eq_unordered(
+        &root.find_all_references_pos(&code.s1("ename1").pos()),
+        &[
+            code.s("ename1", 1).pos(),
+            code.s("ename1", 2).pos(),
+            code.s("ename1", 3).pos(),
+        ],
+    );
+}
+
+#[test]
+fn configuration_specification_is_correct() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity;
+
+entity ename2 is
+end entity;
+
+architecture a of ename2 is
+  signal sig : bit;
+  for inst : sig use entity work.missing;
+  for inst2 : ename1 use entity work.ename1(missing);
+begin
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            kind_error(&code, "sig", 3, 2, "component", "signal 'sig'"),
+            Diagnostic::error(
+                code.s("missing", 1),
+                "No primary unit 'missing' within library 'libname'",
+            ),
+            Diagnostic::error(code.s("ename1", 2), "No declaration of 'ename1'"),
+            Diagnostic::error(
+                code.s("missing", 2),
+                "No architecture 'missing' for entity 'libname.ename1'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn find_all_references_with_kind_of_entity() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ename1 is
+end entity ename1;
+
+architecture a of ename1 is
+begin
+end architecture;
+
+entity ename2 is
+end entity;
+
+architecture a of ename2 is
+begin
+  inst : entity work.ename1;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let ent = root
+        .search_reference(code.source(), code.s1("ename1").start())
+        .unwrap();
+    let mut references = root.find_all_references_with_kind(ent);
+    references.sort_by(|(pos1, _), (pos2, _)| pos1.cmp(pos2));
+    assert_eq!(
+        references,
+        vec![
+            (code.s("ename1", 1).pos(), ReferenceKind::Declaration),
+            (code.s("ename1", 2).pos(), ReferenceKind::EndLabel),
+            (code.s("ename1", 3).pos(), ReferenceKind::Use),
+            (code.s("ename1", 4).pos(), ReferenceKind::Use),
+        ]
+    );
+}
+
+#[test]
+fn error_on_duplicate_architecture_in_two_files() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+",
+    );
+    let arch1 = builder.code(
+        "libname",
+        "
+architecture rtl of ent is
+begin
+end architecture;
+",
+    );
+    let arch2 = builder.code(
+        "libname",
+        "
+architecture rtl of ent is
+begin
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![duplicate_unit_in_two_files(
+            &arch1.s1("rtl"),
+            &arch2.s1("rtl"),
+            "Duplicate architecture 'rtl' of entity 'ent'",
+        )],
+    );
+}
+
+#[test]
+fn error_on_duplicate_package_body_in_two_files() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+end package;
+",
+    );
+    let body1 = builder.code(
+        "libname",
+        "
+package body pkg is
+end package body;
+",
+    );
+    let body2 = builder.code(
+        "libname",
+        "
+package body pkg is
+end package body;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![duplicate_unit_in_two_files(
+            &body1.s1("pkg"),
+            &body2.s1("pkg"),
+            "Duplicate package body of package 'pkg'",
+        )],
+    );
+}
+
+#[test]
+fn error_on_configuration_with_name_of_entity_in_other_file() {
+    let mut builder = LibraryBuilder::new();
+    let ent = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+",
+    );
+    let cfg = builder.code(
+        "libname",
+        "
+configuration ent of ent is
+  for rtl
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![duplicate_unit_in_two_files(
+            &ent.sa("entity ", "ent"),
+            &cfg.sa("configuration ", "ent"),
+            "A primary unit has already been declared with name 'ent' in library 'libname'",
+        )],
+    );
+}
+
+#[test]
+fn error_on_secondary_unit_of_primary_unit_of_other_kind() {
+    let mut builder = LibraryBuilder::new();
+    let pkg = builder.code(
+        "libname",
+        "
+package pkg is
+end package;
+",
+    );
+    let ent = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+architecture rtl of pkg is
+begin
+end architecture;
+
+package body ent is
+end package body;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(code.s1("pkg"), "Expected an entity")
+                .related(pkg.s1("pkg"), "Found package 'pkg'")
+                .with_code(ErrorCode::MismatchedKinds),
+            Diagnostic::error(code.sa("body ", "ent"), "Expected a package")
+                .related(ent.sa("entity ", "ent"), "Found entity 'ent'")
+                .with_code(ErrorCode::MismatchedKinds),
+        ],
+    );
+}
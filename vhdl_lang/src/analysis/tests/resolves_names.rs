@@ -5,6 +5,7 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 use super::*;
+use crate::ast::search::ReferenceKind;
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -360,7 +361,7 @@ package body pkg is
 
      -- Slice
      constant vec : integer_vector(0 to 1) := (0, 1);
-     constant c4 : integer_vector(0 to 1) := vec(decl to decl);
+     constant c4 : integer_vector(0 to 0) := vec(decl to decl);
 
      constant c5 : string := decl'simple_name;
      constant c6 : boolean := boolean'val(decl);
@@ -868,11 +869,12 @@ architecture a of ent is
   end;
 
   signal decl : natural := 0;
+  signal other1, other2 : natural := 0;
 begin
   decl <= decl;
-  decl <= decl when decl = 0 else decl;
+  other1 <= decl when decl = 0 else decl;
   with decl select
-     decl <= decl when decl,
+     other2 <= decl when decl,
              decl when others;
   proc(decl);
   assert decl = 0 report decl'instance_name severity severity_level'val(decl);
@@ -938,6 +940,42 @@ end architecture;
     );
 }
 
+#[test]
+fn for_generate_over_range_attribute_has_index_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type state_t is (idle, busy);
+  type arr_t is array (state_t) of bit;
+  signal arr : arr_t;
+begin
+  gen: for i in arr'range generate
+    arr(i) <= '0';
+  end generate;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let ent = root
+        .search_reference(code.source(), code.s1("arr(i)").s1("i").start())
+        .expect("Generate parameter has no reference");
+    let AnyEntKind::Object(obj) = ent.kind() else {
+        panic!("Expected the generate parameter to be a constant");
+    };
+    assert_eq!(
+        obj.subtype.type_mark().designator(),
+        &Designator::Identifier(root.symbol_utf8("state_t"))
+    );
+}
+
 #[test]
 fn search_if_generate_conditions() {
     check_search_reference(
@@ -1256,18 +1294,34 @@ end package;
             Diagnostic::error(
                 code.s("missing", 1),
                 "No declaration of 'missing' within record type 'rec1_t'",
+            )
+            .related(
+                code.sa("type ", "rec1_t"),
+                "Record type 'rec1_t' declared here",
             ),
             Diagnostic::error(
                 code.s("missing", 2),
                 "No declaration of 'missing' within record type 'rec2_t'",
+            )
+            .related(
+                code.sa("type ", "rec2_t"),
+                "Record type 'rec2_t' declared here",
             ),
             Diagnostic::error(
                 code.s("missing", 3),
                 "No declaration of 'missing' within record type 'rec1_t'",
+            )
+            .related(
+                code.sa("type ", "rec1_t"),
+                "Record type 'rec1_t' declared here",
             ),
             Diagnostic::error(
                 code.s("missing", 4),
                 "No declaration of 'missing' within record type 'rec2_t'",
+            )
+            .related(
+                code.sa("type ", "rec2_t"),
+                "Record type 'rec2_t' declared here",
             ),
         ],
     );
@@ -1338,6 +1392,10 @@ end package body;
         vec![Diagnostic::error(
             code.s("missing", 1),
             "No declaration of 'missing' within record type 'rec_t'",
+        )
+        .related(
+            code.sa("type ", "rec_t"),
+            "Record type 'rec_t' declared here",
         )],
     );
 }
@@ -1379,6 +1437,10 @@ end architecture;
         vec![Diagnostic::error(
             code.s("missing", 1),
             "No declaration of 'missing' within record type 'rec_t'",
+        )
+        .related(
+            code.sa("type ", "rec_t"),
+            "Record type 'rec_t' declared here",
         )],
     );
 }
@@ -1474,10 +1536,18 @@ end architecture;
             Diagnostic::error(
                 code.s("missing", 1),
                 "No declaration of 'missing' within record type 'rec_t'",
+            )
+            .related(
+                code.s1("rec_t is record").s1("rec_t"),
+                "Record type 'rec_t' declared here",
             ),
             Diagnostic::error(
                 code.s("missing", 2),
                 "No declaration of 'missing' within record type 'rec_t'",
+            )
+            .related(
+                code.s1("rec_t is record").s1("rec_t"),
+                "Record type 'rec_t' declared here",
             ),
         ],
     );
@@ -1557,6 +1627,10 @@ constant bad : rec_t := (missing => 0);
         vec![Diagnostic::error(
             code.s1("missing"),
             "No declaration of 'missing' within record type 'rec_t'",
+        )
+        .related(
+            code.sa("type ", "rec_t"),
+            "Record type 'rec_t' declared here",
         )],
     );
     let field = root
@@ -1574,6 +1648,220 @@ constant bad : rec_t := (missing => 0);
     );
 }
 
+#[test]
+fn hover_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+signal clk :   bit := '0'; -- The clock
+signal other : bit := clk;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    // The signature is the declaration as written in the source
+    for i in 1..=2 {
+        let hover = root.hover(code.source(), code.s("clk", i).start()).unwrap();
+        assert_eq!(hover.kind, "signal");
+        assert_eq!(hover.signature, "signal clk :   bit := '0';");
+        assert_eq!(hover.decl_pos, Some(&code.s1("clk").pos()));
+    }
+
+    assert!(root
+        .hover(code.source(), code.s1("signal").start())
+        .is_none());
+}
+
+#[test]
+fn document_references_classify_writes() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig, other, inverted : bit;
+begin
+  sig <= '1';
+  other <= sig;
+  inverted <= not sig;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.document_references(code.source(), code.s1("not sig").s1("sig").start()),
+        vec![
+            (code.s1("sig,").s1("sig").pos(), ReferenceKind::Declaration),
+            (code.s1("sig <=").s1("sig").pos(), ReferenceKind::Write),
+            (code.s1("<= sig").s1("sig").pos(), ReferenceKind::Use),
+            (code.s1("not sig").s1("sig").pos(), ReferenceKind::Use),
+        ]
+    );
+}
+
+#[test]
+fn document_references_of_indexed_and_selected_targets() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    field : bit_vector(0 to 1);
+  end record;
+begin
+  process
+    variable idx : natural := 0;
+    variable rec : rec_t := (field => \"00\");
+    variable arr : bit_vector(0 to 1) := \"00\";
+  begin
+    rec.field(idx) := '1';
+    arr(idx) := rec.field(0);
+    (arr(0), arr(1)) := arr;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let references = |name: &str| {
+        root.document_references(code.source(), code.s1(name).start())
+            .into_iter()
+            .map(|(_, kind)| kind)
+            .collect::<Vec<_>>()
+    };
+    // The index is read when assigning an indexed name
+    assert_eq!(
+        references("idx"),
+        vec![
+            ReferenceKind::Declaration,
+            ReferenceKind::Use,
+            ReferenceKind::Use
+        ]
+    );
+    assert_eq!(
+        references("rec :"),
+        vec![
+            ReferenceKind::Declaration,
+            ReferenceKind::Write,
+            ReferenceKind::Use
+        ]
+    );
+    assert_eq!(
+        references("field"),
+        vec![
+            ReferenceKind::Declaration,
+            ReferenceKind::Use,
+            ReferenceKind::Write,
+            ReferenceKind::Use
+        ]
+    );
+    assert_eq!(
+        references("arr :"),
+        vec![
+            ReferenceKind::Declaration,
+            ReferenceKind::Write,
+            ReferenceKind::Write,
+            ReferenceKind::Write,
+            ReferenceKind::Use
+        ]
+    );
+}
+
+#[test]
+fn signal_drivers_and_readers_include_port_map_actuals() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity child is
+  port (
+    i : in bit;
+    o : out bit;
+    io : inout bit);
+end entity;
+
+architecture a of child is
+begin
+  o <= i;
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  signal sig : bit_vector(0 to 1);
+  signal ack, result : bit;
+begin
+  driver: process
+  begin
+    sig(0) <= '1';
+    wait;
+  end process;
+
+  inst: entity work.child port map (i => result, o => sig(1), io => ack);
+
+  reader: process
+  begin
+    result <= sig(0) and ack;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let sig = code.s1("sig :").s1("sig").pos();
+    assert_eq!(
+        root.signal_drivers(&sig),
+        vec![
+            code.s1("sig(0) <=").s1("sig").pos(),
+            code.s1("=> sig(1)").s1("sig").pos()
+        ]
+    );
+    assert_eq!(
+        root.signal_readers(&sig),
+        vec![code.s1("<= sig(0)").s1("sig").pos()]
+    );
+
+    // An inout port both reads and drives its actual
+    let ack = code.s1("ack").pos();
+    assert_eq!(
+        root.signal_drivers(&ack),
+        vec![code.s1("=> ack").s1("ack").pos()]
+    );
+    assert_eq!(
+        root.signal_readers(&ack),
+        vec![
+            code.s1("=> ack").s1("ack").pos(),
+            code.s1("and ack").s1("ack").pos()
+        ]
+    );
+    assert_eq!(
+        root.signal_readers(&code.s1("result").pos()),
+        vec![code.s1("i => result").s1("result").pos()]
+    );
+
+    // Only signals have drivers
+    assert_eq!(root.signal_drivers(&code.s1("child").pos()), vec![]);
+}
+
 #[test]
 fn unary_operator() {
     let mut builder = LibraryBuilder::new();
@@ -1802,6 +2090,178 @@ end;
     );
 }
 
+#[test]
+fn references_and_definition_of_object_alias() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : bit_vector(0 to 1);
+  alias sig_alias is sig;
+  signal other1, other2 : bit;
+begin
+  other1 <= sig_alias(0);
+  other2 <= sig(1);
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let alias = root
+        .search_reference(code.source(), code.s("sig_alias", 2).start())
+        .unwrap();
+    assert_eq!(alias.decl_pos(), Some(&code.s1("sig_alias").pos()));
+    assert_eq!(
+        root.find_all_references(alias),
+        vec![code.s("sig_alias", 1).pos(), code.s("sig_alias", 2).pos()]
+    );
+
+    // The alias declaration is a reference to the aliased signal
+    let sig = root
+        .search_reference(code.source(), code.s1("sig(1)").start())
+        .unwrap();
+    assert_eq!(
+        root.find_all_references(sig),
+        vec![
+            code.s1("sig :").s1("sig").pos(),
+            code.s1("is sig").s1("sig").pos(),
+            code.s1("sig(1)").s1("sig").pos()
+        ]
+    );
+
+    // The first jump goes to the alias declaration and the second to the aliased signal
+    assert_eq!(
+        root.find_definition(code.source(), code.s("sig_alias", 2).start())
+            .unwrap()
+            .decl_pos(),
+        Some(&code.s1("sig_alias").pos())
+    );
+    assert_eq!(
+        root.find_definition(code.source(), code.s1("sig_alias").start())
+            .unwrap()
+            .decl_pos(),
+        Some(&code.s1("sig :").s1("sig").pos())
+    );
+}
+
+#[test]
+fn references_and_definition_of_subprogram_alias() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  function fun(arg : integer) return integer;
+  function fun(arg : boolean) return integer;
+  alias fun_alias is fun[integer return integer];
+  constant c0 : integer := fun_alias(0);
+  constant c1 : integer := fun_alias(false);
+  constant c2 : integer := fun(1);
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    // Calls through the alias only consider the aliased function
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("false"),
+            "'false' does not match integer type 'INTEGER'",
+        )],
+    );
+
+    let alias = root
+        .search_reference(code.source(), code.s("fun_alias", 2).start())
+        .unwrap();
+    assert_eq!(
+        root.find_all_references(alias),
+        vec![
+            code.s("fun_alias", 1).pos(),
+            code.s("fun_alias", 2).pos(),
+            code.s("fun_alias", 3).pos()
+        ]
+    );
+
+    let fun = root
+        .search_reference(code.source(), code.s1("fun(1)").start())
+        .unwrap();
+    assert_eq!(
+        fun.decl_pos(),
+        Some(&code.s1("fun(arg : integer)").s1("fun").pos())
+    );
+    assert_eq!(
+        root.find_all_references(fun),
+        vec![
+            code.s1("fun(arg : integer)").s1("fun").pos(),
+            code.s1("is fun").s1("fun").pos(),
+            code.s1("fun(1)").s1("fun").pos()
+        ]
+    );
+
+    assert_eq!(
+        root.find_definition(code.source(), code.s("fun_alias", 3).start())
+            .unwrap()
+            .decl_pos(),
+        Some(&code.s1("fun_alias").pos())
+    );
+    assert_eq!(
+        root.find_definition(code.source(), code.s1("fun_alias").start())
+            .unwrap()
+            .decl_pos(),
+        Some(&code.s1("fun(arg : integer)").s1("fun").pos())
+    );
+}
+
+#[test]
+fn alias_with_ambiguous_signature() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg1 is
+  function fun(arg : integer) return integer;
+end package;
+
+package pkg2 is
+  function fun(arg : integer) return integer;
+  function fun(arg : boolean) return integer;
+end package;
+
+use work.pkg1.all;
+use work.pkg2.all;
+
+package pkg is
+  alias good is fun[boolean return integer];
+  alias bad is fun[integer return integer];
+end package;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("is fun[integer").s1("fun"),
+            "Ambiguous alias of 'fun', more than one declaration matches the signature",
+        )
+        .related(
+            code.s("fun(arg : integer)", 1).s1("fun"),
+            "Might be function fun[INTEGER return INTEGER]",
+        )
+        .related(
+            code.s("fun(arg : integer)", 2).s1("fun"),
+            "Might be function fun[INTEGER return INTEGER]",
+        )],
+    );
+}
+
 #[test]
 fn find_all_references_of_deferred_constant() {
     let mut builder = LibraryBuilder::new();
@@ -1953,7 +2413,7 @@ package body pkg is
 end package body pkg;
 
 configuration cfg1 of ent1 is
-  for rtl(0)
+  for a1
   end for;
 end configuration cfg1;
 
@@ -5,6 +5,7 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 use super::*;
+use crate::VHDLStandard;
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -496,3 +497,31 @@ pub fn kind_error(
     Diagnostic::error(code.s(name, occ), format!("Expected {expected}, got {got}"))
         .related(code.s(name, occ_decl), "Defined here")
 }
+
+#[test]
+fn resolves_return_identifier_within_function_body() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  function f(x : bit_vector) return r of bit_vector;
+end package;
+
+package body pkg is
+  function f(x : bit_vector) return r of bit_vector is
+    variable v : r(x'range) := x;
+  begin
+    return v;
+  end function;
+end package body;",
+        VHDLStandard::V2019,
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("r(x'range)").start()),
+        Some(code.s("r of", 2).s1("r").pos())
+    );
+}
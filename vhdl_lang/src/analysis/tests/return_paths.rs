@@ -0,0 +1,231 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+fn missing_return(code: &Code, name: &str) -> Diagnostic {
+    Diagnostic::warning(
+        code.s1(&format!("function {name}")).s1(name),
+        format!("Function '{name}' can reach its end without a return statement"),
+    )
+}
+
+#[test]
+fn return_only_in_if_branch() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    let code = builder.in_declarative_region(
+        "
+function fun1(arg : boolean) return natural is
+begin
+  if arg then
+    return 0;
+  end if;
+end function;
+
+function fun2(arg : natural) return natural is
+begin
+  if arg = 0 then
+    return 0;
+  elsif arg = 1 then
+    null;
+  else
+    return 2;
+  end if;
+end function;
+
+function fun3(arg : natural) return natural is
+  variable var : natural;
+begin
+  var := arg;
+end function;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            missing_return(&code, "fun1"),
+            missing_return(&code, "fun2"),
+            missing_return(&code, "fun3"),
+        ],
+    );
+}
+
+#[test]
+fn all_if_branches_return() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    builder.in_declarative_region(
+        "
+function fun1(arg : natural) return natural is
+begin
+  if arg = 0 then
+    return 0;
+  elsif arg = 1 then
+    return 1;
+  else
+    if arg = 2 then
+      return 2;
+    end if;
+    return 3;
+  end if;
+end function;
+
+function fun2(arg : boolean) return natural is
+begin
+  if arg then
+    return 0;
+  end if;
+  return 1;
+end function;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn all_case_alternatives_return() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    let code = builder.in_declarative_region(
+        "
+type state_t is (idle, busy, done);
+
+function fun1(state : state_t) return natural is
+begin
+  case state is
+    when idle => return 0;
+    when others => return 1;
+  end case;
+end function;
+
+function fun2(state : state_t) return natural is
+begin
+  case state is
+    when idle => return 0;
+    when busy => null;
+    when others => return 1;
+  end case;
+end function;
+",
+    );
+
+    check_diagnostics(builder.analyze(), vec![missing_return(&code, "fun2")]);
+}
+
+#[test]
+fn infinite_loop_does_not_complete() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    let code = builder.in_declarative_region(
+        "
+function fun1(arg : natural) return natural is
+begin
+  loop
+    if arg = 0 then
+      return 0;
+    end if;
+  end loop;
+end function;
+
+function fun2(arg : natural) return natural is
+begin
+  loop
+    if arg = 0 then
+      exit;
+    end if;
+  end loop;
+end function;
+
+function fun3(arg : natural) return natural is
+begin
+  while arg > 0 loop
+    return 0;
+  end loop;
+end function;
+
+function fun4(arg : natural) return natural is
+begin
+  for i in 0 to arg loop
+    return i;
+  end loop;
+end function;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            missing_return(&code, "fun2"),
+            missing_return(&code, "fun3"),
+            missing_return(&code, "fun4"),
+        ],
+    );
+}
+
+#[test]
+fn procedures_need_no_return() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    builder.in_declarative_region(
+        "
+procedure proc(arg : boolean) is
+begin
+  if arg then
+    return;
+  end if;
+end procedure;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn function_without_statements() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    let code = builder.in_declarative_region(
+        "
+function stub return natural is
+begin
+end function;
+",
+    );
+
+    check_diagnostics(builder.analyze(), vec![missing_return(&code, "stub")]);
+}
+
+#[test]
+fn exit_of_nested_loop_does_not_leave_outer_loop() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_missing_return_lint();
+    let code = builder.in_declarative_region(
+        "
+function fun1(arg : natural) return natural is
+begin
+  loop
+    for i in 0 to arg loop
+      exit when i = 2;
+    end loop;
+  end loop;
+end function;
+
+function fun2(arg : natural) return natural is
+begin
+  outer: loop
+    for i in 0 to arg loop
+      exit outer when i = 2;
+    end loop;
+  end loop;
+end function;
+",
+    );
+
+    check_diagnostics(builder.analyze(), vec![missing_return(&code, "fun2")]);
+}
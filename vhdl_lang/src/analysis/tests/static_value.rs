@@ -0,0 +1,194 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::ast::Direction;
+use crate::data::Latin1String;
+
+fn static_value(root: &DesignRoot, code: &Code, name: &str) -> Option<Value> {
+    let pos = code.s1(name).pos();
+    let ent = root
+        .search_reference(&pos.source, pos.start())
+        .expect("Constant has no reference");
+    match ent.kind() {
+        AnyEntKind::Object(Object { static_value, .. }) => static_value.clone(),
+        _ => panic!("Expecting object"),
+    }
+}
+
+fn static_range(root: &DesignRoot, code: &Code, name: &str) -> Option<StaticRange> {
+    let pos = code.s1(name).pos();
+    let ent = root
+        .search_reference(&pos.source, pos.start())
+        .expect("Object has no reference");
+    match ent.kind() {
+        AnyEntKind::Object(obj) => obj.subtype.static_range(),
+        _ => panic!("Expecting object"),
+    }
+}
+
+#[test]
+fn evaluates_nested_constants_across_packages() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  constant width : natural := 8;
+  constant depth : natural := 2 ** width;
+end package;
+
+use work.pkg.all;
+
+package pkg2 is
+  constant words : integer := depth / 4 - work.pkg.width;
+  constant neg : integer := -(words mod 5);
+  constant remainder : integer := -7 rem 2;
+  constant modulo : integer := (-7) mod 2;
+  constant abs_value : integer := abs neg;
+  constant ratio : real := 1.5 * 2.0;
+  constant flag : boolean := words > depth;
+  constant bit_value : bit := '1';
+  constant text : string := \"hello\";
+  constant delay : time := 10 ns;
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let integer = |name| static_value(&root, &code, name);
+    assert_eq!(integer("width :"), Some(Value::Integer(8)));
+    assert_eq!(integer("depth :"), Some(Value::Integer(256)));
+    assert_eq!(integer("words :"), Some(Value::Integer(56)));
+    assert_eq!(integer("neg :"), Some(Value::Integer(-1)));
+    assert_eq!(integer("remainder :"), Some(Value::Integer(-1)));
+    assert_eq!(integer("modulo :"), Some(Value::Integer(1)));
+    assert_eq!(integer("abs_value :"), Some(Value::Integer(1)));
+    assert_eq!(integer("ratio :"), Some(Value::Real(3.0)));
+    assert_eq!(
+        integer("flag :"),
+        Some(Value::Enumeration(Designator::Identifier(
+            root.symbol_utf8("false")
+        )))
+    );
+    assert_eq!(
+        integer("bit_value :"),
+        Some(Value::Enumeration(Designator::Character(b'1')))
+    );
+    assert_eq!(
+        integer("text :"),
+        Some(Value::String(Latin1String::from_utf8_unchecked("hello")))
+    );
+    assert!(matches!(integer("delay :"), Some(Value::Physical(..))));
+}
+
+#[test]
+fn evaluates_attributes_of_static_ranges() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+subtype word_t is bit_vector(15 downto 0);
+subtype small_t is integer range -3 to 4;
+constant word : word_t := (others => '0');
+constant len : natural := word'length;
+constant left : integer := word_t'left;
+constant low : integer := small_t'low;
+constant high : integer := small_t'high;
+signal sel : bit_vector(word'range);
+signal rev : bit_vector(small_t'reverse_range);
+signal empty : bit_vector(0 downto 1);
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        static_value(&root, &code, "len :"),
+        Some(Value::Integer(16))
+    );
+    assert_eq!(
+        static_value(&root, &code, "left :"),
+        Some(Value::Integer(15))
+    );
+    assert_eq!(
+        static_value(&root, &code, "low :"),
+        Some(Value::Integer(-3))
+    );
+    assert_eq!(
+        static_value(&root, &code, "high :"),
+        Some(Value::Integer(4))
+    );
+    assert_eq!(
+        static_range(&root, &code, "sel :"),
+        Some(StaticRange::new(15, Direction::Descending, 0))
+    );
+    assert_eq!(
+        static_range(&root, &code, "rev :"),
+        Some(StaticRange::new(4, Direction::Descending, -3))
+    );
+    assert_eq!(
+        static_range(&root, &code, "empty :").map(|range| range.length()),
+        Some(0)
+    );
+}
+
+#[test]
+fn non_static_expressions_have_no_value() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  function fun return natural;
+  function \"+\"(a, b : bit) return bit;
+  constant from_call : natural := fun;
+  constant overloaded : bit := '1' + '0';
+  constant zero : natural := 0;
+  constant by_zero : natural := 1 / zero;
+end package;
+
+entity ent is
+  generic (g : natural := 1);
+end entity;
+
+architecture a of ent is
+  constant from_generic : natural := g + 1;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(static_value(&root, &code, "from_call :"), None);
+    assert_eq!(static_value(&root, &code, "overloaded :"), None);
+    assert_eq!(static_value(&root, &code, "by_zero :"), None);
+    assert_eq!(static_value(&root, &code, "from_generic :"), None);
+}
+
+#[test]
+fn overflow_in_static_expression() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant big : integer := 2 ** 62;
+constant bigger : integer := big * 4;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("big * 4"),
+            "Integer overflow in locally static expression",
+        )],
+    );
+}
@@ -5,6 +5,7 @@
 // Copyright (c) 2022, Olof Kraigher olof.kraigher@gmail.com
 
 use super::*;
+use crate::VHDLStandard;
 
 #[test]
 fn wrong_number_of_arguments() {
@@ -413,3 +414,172 @@ end procedure;
         .related(code.s1("arg(0)"), "Previously associated here")],
     );
 }
+
+#[test]
+fn ambiguous_procedure_call_lists_remaining_candidates() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type enum1_t is (alpha, beta);
+  type enum2_t is (alpha, gamma);
+  procedure proc(arg : enum1_t);
+  procedure proc(arg : enum2_t);
+  procedure proc(arg : integer);
+end package;
+
+package body pkg is
+  procedure proc(arg : enum1_t) is
+  begin
+  end procedure;
+
+  procedure proc(arg : enum2_t) is
+  begin
+  end procedure;
+
+  procedure proc(arg : integer) is
+  begin
+  end procedure;
+
+  procedure test is
+  begin
+    proc(alpha);
+  end procedure;
+end package body;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("proc(alpha)").s1("proc"),
+            "Ambiguous call to 'proc'",
+        )
+        .related(
+            code.s("proc(arg : enum1_t)", 2).s1("proc"),
+            "Might be procedure proc[enum1_t]",
+        )
+        .related(
+            code.s("proc(arg : enum2_t)", 2).s1("proc"),
+            "Might be procedure proc[enum2_t]",
+        )],
+    );
+}
+
+#[test]
+fn operator_without_match_lists_rejected_candidates() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type rec_t is record
+    field : integer;
+  end record;
+  function \"+\"(left : rec_t; right : integer) return rec_t;
+  function \"+\"(left : rec_t; right : rec_t) return rec_t;
+end package;
+
+use work.pkg.all;
+
+package pkg2 is
+  constant c0 : rec_t := (field => 0);
+  constant c1 : rec_t := c0 + 'c';
+end package;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("c0 + 'c'").s1("+"),
+            "Found no match for operator \"+\"",
+        )
+        .related(
+            code.s1("\"+\"(left : rec_t; right : integer)").s1("\"+\""),
+            "Does not match operator \"+\"[rec_t, INTEGER return rec_t]: type mismatch of argument 2, expected integer type 'INTEGER' but got type 'CHARACTER'",
+        )
+        .related(
+            code.s1("\"+\"(left : rec_t; right : rec_t)").s1("\"+\""),
+            "Does not match operator \"+\"[rec_t, rec_t return rec_t]: type mismatch of argument 2, expected record type 'rec_t' but got type 'CHARACTER'",
+        )],
+    );
+}
+
+#[test]
+fn operator_candidate_without_argument_mismatch_is_listed_without_reason() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type rec_t is record
+    field : integer;
+  end record;
+  function \"+\"(left : rec_t; right : bit) return rec_t;
+  function \"+\"(left : rec_t; right : boolean) return rec_t;
+  function value return bit;
+  function value return boolean;
+end package;
+
+use work.pkg.all;
+
+package pkg2 is
+  constant c0 : rec_t := (field => 0);
+  constant c1 : integer := c0 + value;
+end package;
+",
+    );
+
+    // Both candidates accept the operands, the reason they are rejected is not known
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("c0 + value").s1("+"),
+            "Found no match for operator \"+\"",
+        )
+        .related(
+            code.s1("\"+\"(left : rec_t; right : bit)").s1("\"+\""),
+            "Does not match operator \"+\"[rec_t, BIT return rec_t]",
+        )
+        .related(
+            code.s1("\"+\"(left : rec_t; right : boolean)").s1("\"+\""),
+            "Does not match operator \"+\"[rec_t, BOOLEAN return rec_t]",
+        )],
+    );
+}
+
+#[test]
+fn signal_and_variable_parameters_with_defaults_may_be_omitted() {
+    let mut builder = LibraryBuilder::new();
+    builder.code_with_standard(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+
+  procedure theproc(signal sig : in bit := '0'; variable var : out natural := 0) is
+  begin
+  end procedure;
+begin
+  process
+    variable v : natural;
+  begin
+    theproc;
+    theproc(s, v);
+    wait;
+  end process;
+end architecture;
+",
+        VHDLStandard::V2019,
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
@@ -5,7 +5,7 @@
 // Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
 use crate::analysis::tests::{check_no_diagnostics, LibraryBuilder};
 use crate::syntax::test::check_diagnostics;
-use crate::Diagnostic;
+use crate::{Diagnostic, ErrorCode};
 
 #[test]
 pub fn cannot_instantiate_procedure_that_does_not_exist() {
@@ -24,10 +24,10 @@ procedure proc is new foo;
     let diagnostics = builder.analyze();
     assert_eq!(
         diagnostics,
-        vec![Diagnostic::error(
-            code.s1("foo").pos(),
-            "No declaration of 'foo'"
-        )]
+        vec![
+            Diagnostic::error(code.s1("foo").pos(), "No declaration of 'foo'")
+                .with_code(ErrorCode::MissingDecl)
+        ]
     );
 }
 
@@ -48,7 +48,8 @@ function proc is new x;
         vec![Diagnostic::error(
             code.s1("new x").s1("x"),
             "signal 'x' does not denote an uninstantiated subprogram"
-        )]
+        )
+        .with_code(ErrorCode::InvalidInstantiation)]
     )
 }
 
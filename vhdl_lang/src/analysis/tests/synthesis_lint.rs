@@ -0,0 +1,186 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::VHDLStandard;
+
+#[test]
+fn reports_constructs_that_are_not_synthesizable() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (
+    clk : in bit;
+    num : in natural;
+    den : in natural;
+    quotient : out natural;
+    remainder : out natural;
+    q : out bit);
+end entity;
+
+architecture a of ent is
+  constant words : natural := 3;
+  signal state : bit := '0';
+begin
+  process (clk)
+  begin
+    if clk'event and clk = '1' then
+      state <= not state after 1 ns;
+    end if;
+  end process;
+
+  quotient <= num / 4 + num / den;
+  remainder <= num mod words;
+  q <= state;
+end architecture;
+
+entity tb is
+end entity;
+
+architecture a of tb is
+  signal clk : bit;
+begin
+  process
+  begin
+    clk <= not clk;
+    wait for 5 ns;
+  end process;
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::warning(
+                code.s1(":= '0'").s1("'0'"),
+                "Initial value of signal 'state' is not a reset value in synthesis",
+            )
+            .with_code(ErrorCode::SynthesisInitialValue),
+            Diagnostic::warning(code.s1("1 ns"), "Delay is ignored by synthesis")
+                .with_code(ErrorCode::SynthesisDelay),
+            Diagnostic::warning(
+                code.s1("num / den").s1("den"),
+                "Operator '/' is only synthesizable with a constant divisor that is a power of 2",
+            )
+            .with_code(ErrorCode::SynthesisDivision),
+            Diagnostic::warning(
+                code.s1("mod words").s1("words"),
+                "Operator 'mod' is only synthesizable with a constant divisor that is a power of 2",
+            )
+            .with_code(ErrorCode::SynthesisDivision),
+            Diagnostic::warning(
+                code.s1("5 ns"),
+                "Wait statement with a timeout is not synthesizable",
+            )
+            .with_code(ErrorCode::SynthesisWaitFor),
+        ],
+    );
+}
+
+#[test]
+fn libraries_without_synthesis_lint_are_not_checked() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("rtl_lib");
+    builder.code(
+        "tb_lib",
+        "
+entity ent is
+  port (
+    clk : in bit;
+    num : in natural;
+    den : in natural;
+    quotient : out natural;
+    remainder : out natural;
+    q : out bit);
+end entity;
+
+architecture a of ent is
+  constant words : natural := 3;
+  signal state : bit := '0';
+begin
+  process (clk)
+  begin
+    if clk'event and clk = '1' then
+      state <= not state after 1 ns;
+    end if;
+  end process;
+
+  quotient <= num / 4 + num / den;
+  remainder <= num mod words;
+  q <= state;
+end architecture;
+
+entity tb is
+end entity;
+
+architecture a of tb is
+  signal clk : bit;
+begin
+  process
+  begin
+    clk <= not clk;
+    wait for 5 ns;
+  end process;
+end architecture;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn divisor_given_by_generic_is_not_reported() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    builder.code(
+        "libname",
+        "
+entity ent is
+  generic (divisor : positive);
+  port (
+    num : in natural;
+    quotient : out natural);
+end entity;
+
+architecture a of ent is
+begin
+  quotient <= num / divisor + num rem (divisor * 2) + num / num'low;
+end architecture;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn shared_variable_of_non_protected_type_before_vhdl_2002() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_synthesis_lint("libname");
+    let code = builder.code_with_standard(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  shared variable count : natural;
+begin
+end architecture;
+",
+        VHDLStandard::V1993,
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::warning(
+            code.s1("natural"),
+            "Shared variable 'count' of a non-protected type is not synthesizable",
+        )
+        .with_code(ErrorCode::SynthesisSharedVariable)],
+    );
+}
@@ -0,0 +1,211 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::TypeDescription;
+
+fn type_at<'a>(root: &'a DesignRoot, code: &Code) -> TypeDescription<'a> {
+    root.type_at(code.source(), code.start()).unwrap()
+}
+
+#[test]
+fn type_of_slice() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    field : natural;
+  end record;
+
+  subtype byte_t is std_logic_vector(7 downto 0);
+
+  function fun(arg : natural) return byte_t is
+  begin
+    return (others => '0');
+  end function;
+
+  signal data : std_logic_vector(15 downto 0);
+  signal rec : rec_t;
+  signal nibble : std_logic_vector(3 downto 0);
+  signal num : integer;
+  signal byte : byte_t;
+begin
+  nibble <= data(3 downto 0);
+  num <= rec.field;
+  byte <= fun(num);
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let typ = type_at(&root, &code.s1("data(3 downto 0)").s1("3"));
+    // The innermost expression is the literal of the slice range
+    assert_eq!(typ.type_mark.designator().to_string(), "INTEGER");
+
+    // A slice has the base type of its prefix rather than the subtype
+    let typ = type_at(&root, &code.s1("data(3 downto 0)"));
+    assert_eq!(typ.type_mark.designator().to_string(), "STD_ULOGIC_VECTOR");
+    assert!(!typ.is_subtype);
+    assert!(!typ.is_alias);
+    assert_eq!(typ.base_type.designator().to_string(), "STD_ULOGIC_VECTOR");
+    assert_eq!(
+        typ.element_type.map(|typ| typ.designator().to_string()),
+        Some("STD_ULOGIC".to_owned())
+    );
+    assert_eq!(typ.index_constraint.as_deref(), Some("(NATURAL range <>)"));
+}
+
+#[test]
+fn type_of_record_field() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    field : natural;
+  end record;
+
+  subtype byte_t is std_logic_vector(7 downto 0);
+
+  function fun(arg : natural) return byte_t is
+  begin
+    return (others => '0');
+  end function;
+
+  signal data : std_logic_vector(15 downto 0);
+  signal rec : rec_t;
+  signal nibble : std_logic_vector(3 downto 0);
+  signal num : integer;
+  signal byte : byte_t;
+begin
+  nibble <= data(3 downto 0);
+  num <= rec.field;
+  byte <= fun(num);
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let typ = type_at(&root, &code.s1("rec.field").s1("field"));
+    assert_eq!(typ.type_mark.designator().to_string(), "NATURAL");
+    assert!(typ.is_subtype);
+    assert_eq!(typ.base_type.designator().to_string(), "INTEGER");
+    assert!(typ.element_type.is_none());
+    assert!(typ.index_constraint.is_none());
+}
+
+#[test]
+fn type_of_function_call() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    field : natural;
+  end record;
+
+  subtype byte_t is std_logic_vector(7 downto 0);
+
+  function fun(arg : natural) return byte_t is
+  begin
+    return (others => '0');
+  end function;
+
+  signal data : std_logic_vector(15 downto 0);
+  signal rec : rec_t;
+  signal nibble : std_logic_vector(3 downto 0);
+  signal num : integer;
+  signal byte : byte_t;
+begin
+  nibble <= data(3 downto 0);
+  num <= rec.field;
+  byte <= fun(num);
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let typ = type_at(&root, &code.s1("fun(num)"));
+    assert_eq!(typ.type_mark.designator().to_string(), "byte_t");
+    assert!(typ.is_subtype);
+    assert_eq!(typ.base_type.designator().to_string(), "STD_ULOGIC_VECTOR");
+    assert_eq!(typ.index_constraint.as_deref(), Some("(7 downto 0)"));
+
+    // The argument is an expression of its own
+    let typ = type_at(&root, &code.s1("fun(num)").s1("num"));
+    assert_eq!(typ.type_mark.designator().to_string(), "INTEGER");
+}
+
+#[test]
+fn no_type_outside_of_expressions() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    field : natural;
+  end record;
+
+  subtype byte_t is std_logic_vector(7 downto 0);
+
+  function fun(arg : natural) return byte_t is
+  begin
+    return (others => '0');
+  end function;
+
+  signal data : std_logic_vector(15 downto 0);
+  signal rec : rec_t;
+  signal nibble : std_logic_vector(3 downto 0);
+  signal num : integer;
+  signal byte : byte_t;
+begin
+  nibble <= data(3 downto 0);
+  num <= rec.field;
+  byte <= fun(num);
+end architecture;
+",
+    );
+    let (root, _) = builder.get_analyzed_root();
+    assert!(root
+        .type_at(code.source(), code.s1("architecture").start())
+        .is_none());
+}
@@ -5,6 +5,7 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 use super::*;
+use crate::VHDLStandard;
 
 #[test]
 fn test_integer_literal_expression_typecheck() {
@@ -186,6 +187,9 @@ constant c: bit_vector := 8SX\"0FF\";
 constant d: bit_vector := X\"G\";
 constant e: bit_vector := X\"F\"; -- this is Ok
 constant f: bit_vector := 2SX\"\";
+constant g: bit_vector := O\"778\";
+constant h: bit_vector(11 downto 0) := 12X\"FFF\"; -- this is Ok
+constant i: bit_vector(7 downto 0) := 12X\"FFF\";
         ",
     );
 
@@ -206,6 +210,11 @@ constant f: bit_vector := 2SX\"\";
                 code.s1("2SX\"\""),
                 "Cannot expand an empty signed bit string",
             ),
+            Diagnostic::error(code.s1("O\"778\""), "Illegal digit '8' for base 8"),
+            Diagnostic::error(
+                code.s("12X\"FFF\"", 2),
+                "Expected an array of length 8, got length 12",
+            ),
         ],
     )
 }
@@ -757,11 +766,11 @@ constant bad5 : arr0_t := x\"6\";
         vec![
             Diagnostic::error(
                 code.s1("x\"2\""),
-                "string literal does not match integer type 'INTEGER'",
+                "bit string literal does not match integer type 'INTEGER'",
             ),
             Diagnostic::error(
                 code.s1("x\"3\""),
-                "string literal does not match array type 'INTEGER_VECTOR'",
+                "bit string literal does not match array type 'INTEGER_VECTOR'",
             ),
             Diagnostic::error(
                 code.s1("x\"4\""),
@@ -955,6 +964,167 @@ constant bad : rec_t := (field => 0);
     );
 }
 
+#[test]
+fn record_aggregate_with_target_type() {
+    let mut builder = LibraryBuilder::new();
+    builder.in_declarative_region(
+        "
+type bus_t is record
+    addr : natural;
+    data : bit_vector(7 downto 0);
+    valid, ready : boolean;
+end record;
+
+type wrapper_t is record
+    payload : bus_t;
+    id : character;
+end record;
+
+constant c1 : bus_t := (addr => 0, data => (others => '0'), valid => false, ready => true);
+constant c2 : bus_t := (1, x\"00\", others => false);
+constant c3 : bus_t := (valid | ready => true, addr => 0, data => x\"00\");
+constant c4 : wrapper_t := (payload => (2, x\"ff\", true, true), id => 'a');
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn record_aggregate_misspelled_and_missing_field() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type bus_t is record
+    addr : natural;
+    data : bit;
+end record;
+
+constant bad1 : bus_t := (adr => 0, data => '0');
+constant bad2 : bus_t := (data => '0');
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("adr"),
+                "No declaration of 'adr' within record type 'bus_t'",
+            )
+            .related(
+                code.sa("type ", "bus_t"),
+                "Record type 'bus_t' declared here",
+            ),
+            Diagnostic::error(
+                code.s1("(data => '0')"),
+                "Missing association of record element 'addr'",
+            )
+            .related(code.s1("addr"), "Record element 'addr' defined here"),
+        ],
+    );
+}
+
+#[test]
+fn record_aggregate_multiple_choices() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec_t is record
+    f1, f2 : natural;
+    f3 : character;
+end record;
+
+constant good : rec_t := (f1 | f2 => 0, f3 => 'a');
+constant bad1 : rec_t := (f1 | f2 => 'b', f3 => 'a');
+constant bad2 : rec_t := (f1 | f3 => 0, f2 => 1);
+constant bad3 : rec_t := (f1 | f1 => 0, f2 => 1, f3 => 'a');
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("'b'"),
+                "character literal does not match integer type 'INTEGER'",
+            ),
+            Diagnostic::error(
+                code.s1("f1 | f3"),
+                "Elements of record 'rec_t' associated with the same expression are not of the same type",
+            )
+            .related(code.s1("f1"), "Element 'f1' has subtype 'NATURAL'")
+            .related(code.s1("f3"), "Element 'f3' has type 'CHARACTER'"),
+            Diagnostic::error(
+                code.s1("f1 | f1").s("f1", 2),
+                "Record element 'f1' has already been associated",
+            )
+            .related(code.s1("f1 | f1").s1("f1"), "Previously associated here"),
+        ],
+    );
+}
+
+#[test]
+fn array_of_record_aggregate() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type bus_t is record
+    addr : natural;
+    data : bit;
+end record;
+
+type bus_arr_t is array (0 to 1) of bus_t;
+
+constant good1 : bus_arr_t := ((addr => 0, data => '0'), (1, '1'));
+constant good2 : bus_arr_t := (0 => (addr => 0, data => '0'), others => (1, '1'));
+constant bad1 : bus_arr_t := ((adr => 0, data => '0'), (1, '1'));
+constant bad2 : bus_arr_t := (others => (addr => 'c', data => '0'));
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("adr"),
+                "No declaration of 'adr' within record type 'bus_t'",
+            )
+            .related(
+                code.sa("type ", "bus_t"),
+                "Record type 'bus_t' declared here",
+            ),
+            Diagnostic::error(
+                code.s1("'c'"),
+                "character literal does not match integer type 'INTEGER'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn array_aggregate_others_must_be_alone() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+constant bad : integer_vector(0 to 3) := (0 | others => 1);
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::error(
+            code.s1("others"),
+            "'others' must be the only choice of an element association",
+        )],
+    );
+}
+
 #[test]
 fn record_others() {
     let mut builder = LibraryBuilder::new();
@@ -984,7 +1154,7 @@ constant bad3 : rec_t := ('a', 0, 0, others => 3);
             ),
             Diagnostic::error(
                 code.s1("(others => 0)").s1("others"),
-                "Other elements of record 'rec_t' are not of the same type",
+                "Other elements of record 'rec_t' are not of the same type, this requires VHDL-2019",
             )
             .related(code.s1("f1"), "Element 'f1' has type 'CHARACTER'")
             .related(code.s1("f2"), "Element 'f2' has integer type 'INTEGER'")
@@ -998,6 +1168,68 @@ constant bad3 : rec_t := ('a', 0, 0, others => 3);
     );
 }
 
+#[test]
+fn record_aggregate_others_must_be_of_the_same_type_before_vhdl_2019() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type rec_t is record
+    f1 : bit_vector(0 to 3);
+    f2 : string(1 to 4);
+  end record;
+
+  constant good : rec_t := (others => (others => '0'));
+  constant bad : rec_t := (others => 0);
+end package;
+",
+        VHDLStandard::V2008,
+    );
+    let not_same_type = |occurrence| {
+        Diagnostic::error(
+            code.s("others", occurrence),
+            "Other elements of record 'rec_t' are not of the same type, this requires VHDL-2019",
+        )
+        .related(code.s1("f1"), "Element 'f1' has array type 'BIT_VECTOR'")
+        .related(code.s1("f2"), "Element 'f2' has array type 'STRING'")
+    };
+    check_diagnostics(builder.analyze(), vec![not_same_type(1), not_same_type(3)]);
+}
+
+#[test]
+fn record_aggregate_others_is_analyzed_for_each_type_since_vhdl_2019() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type rec_t is record
+    f1 : bit_vector(0 to 3);
+    f2 : string(1 to 4);
+  end record;
+
+  constant good : rec_t := (others => (others => '0'));
+  constant bad : rec_t := (others => 0);
+end package;
+",
+        VHDLStandard::V2019,
+    );
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("=> 0)").s1("0"),
+                "integer literal does not match array type 'BIT_VECTOR'",
+            ),
+            Diagnostic::error(
+                code.s1("=> 0)").s1("0"),
+                "integer literal does not match array type 'STRING'",
+            ),
+        ],
+    );
+}
+
 #[test]
 fn typecheck_aggregate_element_association_expr() {
     let mut builder = LibraryBuilder::new();
@@ -1740,6 +1972,10 @@ subtype bad3_t is integer(bad('a' to 'b'));
             Diagnostic::error(
                 code.s1("missing"),
                 "No declaration of 'missing' within record type 'rec_t'",
+            )
+            .related(
+                code.sa("type ", "rec_t"),
+                "Record type 'rec_t' declared here",
             ),
             Diagnostic::error(
                 code.s1("integer(").s1("integer"),
@@ -1865,3 +2101,181 @@ end package;
     let diagnostics = builder.analyze();
     check_no_diagnostics(&diagnostics);
 }
+
+#[test]
+fn user_defined_physical_type_units() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type distance is range 0 to 1e9
+    units
+      um;
+      mm = 1000 um;
+      cm = 10 mm;
+    end units;
+
+  constant c1 : distance := 10 mm;
+  constant c2 : distance := cm;
+  constant c3 : distance := 2.5 cm + c1;
+  constant c4 : integer := c3 / mm;
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("10 mm;").s1("mm").start()),
+        Some(code.s1("mm = 1000 um").s1("mm").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1(":= cm").s1("cm").start()),
+        Some(code.s1("cm = 10 mm").s1("cm").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("2.5 cm").s1("cm").start()),
+        Some(code.s1("cm = 10 mm").s1("cm").pos())
+    );
+}
+
+#[test]
+fn error_on_invalid_secondary_physical_units() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type distance is range 0 to 1e9
+    units
+      mm;
+      cm = 10 mm;
+    end units;
+
+  type weight is range 0 to 1e9
+    units
+      g;
+      kg = 1000 g;
+      g = 1 kg;
+      bad = 10 cm;
+      early = 10 late;
+      late = 10 g;
+    end units;
+
+  constant c1 : distance := 10 kg;
+end package;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s1("g = 1 kg").s1("g"), "Duplicate declaration of 'g'")
+                .related(code.s1("g;").s1("g"), "Previously defined here"),
+            Diagnostic::error(
+                code.s1("10 cm").s1("cm"),
+                "Physical unit of type 'distance' does not match physical type 'weight'",
+            ),
+            Diagnostic::error(code.s1("10 late").s1("late"), "No declaration of 'late'"),
+            Diagnostic::error(
+                code.s1("10 kg"),
+                "physical type 'weight' does not match physical type 'distance'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn qualified_expression_and_type_conversion_operands() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+package pkg is
+  type rec_t is record
+    a : bit;
+  end record;
+  type unsigned is array (natural range <>) of std_ulogic;
+
+  constant x : integer := 1;
+  constant good_qualified : integer := integer'(x + 1);
+  constant bad_qualified : integer := integer'(x = 1);
+
+  constant slv : std_logic_vector(7 downto 0) := (others => '0');
+  constant rec : rec_t := (a => '0');
+  constant good_conversion : unsigned(7 downto 0) := unsigned(slv);
+  constant bad_conversion : unsigned(7 downto 0) := unsigned(rec);
+  constant good_numeric : integer := integer(1.5);
+  constant bad_numeric : integer := integer(rec);
+end package;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::error(
+                code.s1("(x = 1)"),
+                "type 'BOOLEAN' does not match integer type 'INTEGER'",
+            )
+            .with_code(ErrorCode::TypeMismatch),
+            Diagnostic::error(
+                code.s1("unsigned(rec)").s1("rec"),
+                "record type 'rec_t' cannot be converted to array type 'unsigned', \
+                 only abstract numeric types and arrays with closely related elements can be converted",
+            )
+            .with_code(ErrorCode::InvalidConversion),
+            Diagnostic::error(
+                code.s1("integer(rec)").s1("rec"),
+                "record type 'rec_t' cannot be converted to integer type 'INTEGER', \
+                 only abstract numeric types and arrays with closely related elements can be converted",
+            )
+            .with_code(ErrorCode::InvalidConversion),
+        ],
+    );
+}
+
+#[test]
+fn matching_relational_operators_in_conditions() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    builder.code_with_standard(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal a, b, y : std_ulogic;
+begin
+  y <= '1' when a ?= b else '0';
+  assert a ?/= b;
+
+  process
+  begin
+    if a ?= b then
+    elsif a ?< b then
+    end if;
+    while a ?<= b loop
+      next when a ?> b;
+      exit when a ?>= b;
+    end loop;
+    wait until a ?= '1';
+  end process;
+end architecture;
+",
+        VHDLStandard::V2019,
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
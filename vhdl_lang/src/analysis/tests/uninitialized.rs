@@ -0,0 +1,210 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+fn read_before_assignment(code: Code, name: &str) -> Diagnostic {
+    Diagnostic::warning(
+        code,
+        format!("Variable '{name}' may be read before it is assigned"),
+    )
+}
+
+#[test]
+fn read_before_write() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_uninitialized_variable_lint();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : natural;
+begin
+  process
+    variable var : natural;
+  begin
+    sig <= var;
+    var := 0;
+    wait;
+  end process;
+end architecture;",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![read_before_assignment(code.s1("<= var").s1("var"), "var")],
+    );
+}
+
+#[test]
+fn write_in_one_branch_only() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_uninitialized_variable_lint();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : natural;
+begin
+  process
+    variable var1, var2 : natural;
+  begin
+    if sig = 0 then
+      var1 := 0;
+    elsif sig = 1 then
+      var1 := 1;
+      var2 := 1;
+    end if;
+    case sig is
+      when 0 => var2 := 0;
+      when others => null;
+    end case;
+    sig <= var1 + var2;
+    wait;
+  end process;
+end architecture;",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            read_before_assignment(code.s1("<= var1").s1("var1"), "var1"),
+            read_before_assignment(code.s1("+ var2").s1("var2"), "var2"),
+        ],
+    );
+}
+
+#[test]
+fn write_then_read() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_uninitialized_variable_lint();
+    builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : natural;
+begin
+  process
+    variable var1, var2, var3 : natural;
+    variable init : natural := 0;
+  begin
+    var1 := init;
+    sig <= var1;
+
+    if sig = 0 then
+      var2 := 0;
+    else
+      var2 := 1;
+    end if;
+    sig <= var2;
+
+    case sig is
+      when 0 => var3 := 0;
+      when others => var3 := 1;
+    end case;
+    sig <= var3;
+    wait;
+  end process;
+end architecture;",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn loop_body_with_iteration_scheme_may_not_run() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_uninitialized_variable_lint();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : natural;
+begin
+  process
+    variable var1, var2, var3 : natural;
+  begin
+    for i in 0 to 3 loop
+      var1 := i;
+      sig <= var1;
+    end loop;
+    loop
+      var2 := sig;
+      exit when var2 = 0;
+    end loop;
+    while sig > 0 loop
+      next when sig = 1;
+      var3 := sig;
+    end loop;
+    sig <= var1 + var2 + var3;
+    wait;
+  end process;
+end architecture;",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            read_before_assignment(code.s1("var1 +").s1("var1"), "var1"),
+            read_before_assignment(code.s1("+ var3").s1("var3"), "var3"),
+        ],
+    );
+}
+
+#[test]
+fn read_in_subprogram_before_write() {
+    let mut builder = LibraryBuilder::new();
+    builder.enable_uninitialized_variable_lint();
+    let code = builder.in_declarative_region(
+        "
+function fun(arg : natural) return natural is
+  variable var : natural;
+begin
+  if arg = 0 then
+    return var;
+  end if;
+  var := arg;
+  return var;
+end function;
+
+procedure proc(arg : out natural) is
+begin
+  arg := 0;
+end procedure;
+
+procedure proc2 is
+  variable var : natural;
+  variable vec : bit_vector(0 to 3);
+begin
+  proc(var);
+  for i in vec'range loop
+    vec(i) := '0';
+  end loop;
+  assert var = 0;
+end procedure;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![read_before_assignment(
+            code.s1("return var").s1("var"),
+            "var",
+        )],
+    );
+}
@@ -4,10 +4,12 @@
 //
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
-use crate::analysis::DesignRoot;
+use crate::analysis::{AnalysisProgress, DesignRoot};
 use crate::data::*;
 use crate::syntax::test::*;
 use crate::syntax::Symbols;
+use crate::VHDLStandard;
+use fnv::FnvHashSet;
 use pretty_assertions::assert_eq;
 use std::collections::{hash_map::Entry, HashMap};
 use std::sync::Arc;
@@ -15,6 +17,11 @@ use std::sync::Arc;
 pub struct LibraryBuilder {
     code_builder: CodeBuilder,
     libraries: HashMap<Symbol, Vec<Code>>,
+    synthesis_lint_libraries: FnvHashSet<Symbol>,
+    lint_static_assertions: bool,
+    lint_constant_conditions: bool,
+    lint_missing_return: bool,
+    lint_uninitialized_variables: bool,
 }
 
 impl LibraryBuilder {
@@ -22,6 +29,11 @@ impl LibraryBuilder {
         LibraryBuilder {
             code_builder: CodeBuilder::new(),
             libraries: HashMap::default(),
+            synthesis_lint_libraries: FnvHashSet::default(),
+            lint_static_assertions: false,
+            lint_constant_conditions: false,
+            lint_missing_return: false,
+            lint_uninitialized_variables: false,
         }
     }
 
@@ -43,6 +55,18 @@ impl LibraryBuilder {
         code
     }
 
+    /// Add code that is parsed and analyzed according to another VHDL revision than the default
+    pub fn code_with_standard(
+        &mut self,
+        library_name: &str,
+        code: &str,
+        standard: VHDLStandard,
+    ) -> Code {
+        let code = self.code_builder.code(code).with_standard(standard);
+        self.add_code(library_name, code.clone());
+        code
+    }
+
     /// Just get a Code object using the same symbol table but without adding it to any library
     pub fn snippet(&mut self, code: &str) -> Code {
         self.code_builder.code(code)
@@ -64,23 +88,61 @@ end architecture;"
         )
     }
 
+    /// Warn about constructs that are not synthesizable in the units of the library
+    pub fn enable_synthesis_lint(&mut self, library_name: &str) {
+        let library_name = self.code_builder.symbol(library_name);
+        self.synthesis_lint_libraries.insert(library_name);
+    }
+
+    /// Warn about assertions whose condition is locally static
+    pub fn enable_static_assertion_lint(&mut self) {
+        self.lint_static_assertions = true;
+    }
+
+    /// Warn about conditions and case selectors that are constant
+    pub fn enable_constant_condition_lint(&mut self) {
+        self.lint_constant_conditions = true;
+    }
+
+    /// Warn about functions that can reach their end without a return statement
+    pub fn enable_missing_return_lint(&mut self) {
+        self.lint_missing_return = true;
+    }
+
+    /// Warn about variables that may be read before they are assigned
+    pub fn enable_uninitialized_variable_lint(&mut self) {
+        self.lint_uninitialized_variables = true;
+    }
+
     pub fn add_std_logic_1164(&mut self) {
         let std_logic_1164 = self.code_builder.code_from_source(std_logic_1164_package());
         self.add_code("ieee", std_logic_1164);
     }
 
     pub fn get_analyzed_root(&self) -> (DesignRoot, Vec<Diagnostic>) {
+        self.get_analyzed_root_with_progress(None)
+    }
+
+    pub fn get_analyzed_root_with_progress(
+        &self,
+        progress: Option<&dyn AnalysisProgress>,
+    ) -> (DesignRoot, Vec<Diagnostic>) {
         let mut root = DesignRoot::new(self.code_builder.symbols.clone());
         let mut diagnostics = Vec::new();
 
         add_standard_library(self.symbols(), &mut root);
+        root.set_synthesis_lint_libraries(self.synthesis_lint_libraries.clone());
+        root.set_lint_static_assertions(self.lint_static_assertions);
+        root.set_lint_constant_conditions(self.lint_constant_conditions);
+        root.set_lint_missing_return(self.lint_missing_return);
+        root.set_lint_uninitialized_variables(self.lint_uninitialized_variables);
 
         for (library_name, codes) in self.libraries.iter() {
             for code in codes {
                 root.add_design_file(library_name.clone(), code.design_file());
             }
         }
-        root.analyze(&mut diagnostics);
+        root.analyze_with_progress(&mut diagnostics, progress);
 
         (root, diagnostics)
     }
@@ -185,6 +247,14 @@ pub fn duplicate_in_two_files(code1: &Code, code2: &Code, names: &[&str]) -> Vec
     diagnostics
 }
 
+/// The error for a design unit named by `name2` that has the same name as the design unit
+/// named by `name1` in another file
+pub fn duplicate_unit_in_two_files(name1: &Code, name2: &Code, message: &str) -> Diagnostic {
+    Diagnostic::error(name2, message)
+        .related(name1, "Previously defined here")
+        .with_code(ErrorCode::DuplicateDecl)
+}
+
 pub fn check_missing(contents: &str) {
     let mut builder = LibraryBuilder::new();
     let code = builder.code("libname", contents);
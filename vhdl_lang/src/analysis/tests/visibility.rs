@@ -304,7 +304,7 @@ end package;
 #[test]
 fn duplicate_identifer_is_directly_visible_when_it_is_the_same_named_entitty() {
     let mut builder = LibraryBuilder::new();
-    builder.code(
+    let code = builder.code(
         "libname",
         "
 package pkg1 is
@@ -321,8 +321,12 @@ end package;
         ",
     );
 
+    // The second use clause is redundant but the name is not hidden
     let diagnostics = builder.analyze();
-    check_no_diagnostics(&diagnostics);
+    check_diagnostics(
+        diagnostics,
+        vec![redundant_use_clause(&code, "work.pkg1.name", 2, 1)],
+    );
 }
 
 #[test]
@@ -397,6 +401,161 @@ end package;
     check_no_diagnostics(&diagnostics);
 }
 
+#[test]
+fn names_of_packages_used_with_all_are_ambiguous() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package a is
+  constant foo : natural := 0;
+end package;
+
+package b is
+  constant foo : natural := 1;
+end package;
+
+use work.a.all;
+use work.b.all;
+
+package user is
+  constant bar : natural := foo;
+end package;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![hidden_error(
+            &code,
+            "foo",
+            3,
+            &[
+                (&code, "work.a.all", 1, false),
+                (&code, "foo", 1, true),
+                (&code, "work.b.all", 1, false),
+                (&code, "foo", 2, true),
+            ],
+        )],
+    );
+}
+
+#[test]
+fn immediate_declaration_hides_names_of_packages_used_with_all() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package a is
+  constant foo : natural := 0;
+end package;
+
+package b is
+  constant foo : natural := 1;
+end package;
+
+use work.a.all;
+use work.b.all;
+
+entity ent is
+end entity;
+
+architecture rtl of ent is
+  constant foo : natural := 2;
+  constant bar : natural := foo;
+begin
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+pub fn redundant_use_clause(code: &Code, name: &str, occ: usize, prev_occ: usize) -> Diagnostic {
+    Diagnostic::warning(
+        code.s(name, occ),
+        format!("Use clause is redundant, '{name}' is already made visible in this region"),
+    )
+    .related(code.s(name, prev_occ), "Previously made visible here")
+}
+
+#[test]
+fn redundant_use_clauses_of_the_same_region() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  constant foo : natural := 0;
+  function fun return natural;
+  function fun(arg : natural) return natural;
+end package;
+
+use work.pkg.all;
+use work.pkg.all;
+
+package user is
+  use work.pkg.fun;
+  use work.pkg.fun;
+end package;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            redundant_use_clause(&code, "work.pkg.all", 2, 1),
+            redundant_use_clause(&code, "work.pkg.fun", 2, 1),
+        ],
+    );
+}
+
+#[test]
+fn use_clauses_of_other_regions_are_not_redundant() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  constant foo : natural := 0;
+end package;
+
+use work.pkg.all;
+entity ent is
+end entity;
+
+-- The secondary unit repeats the use clause of its primary unit
+use work.pkg.all;
+architecture rtl of ent is
+  use work.pkg.foo;
+begin
+  process
+    use work.pkg.foo;
+  begin
+    wait;
+  end process;
+end architecture;
+
+-- A different kind of use clause is not exactly redundant
+use work.pkg.all;
+use work.pkg.foo;
+package user is
+end package;
+
+use work.pkg.all;
+package body user is
+  use work.pkg.all;
+end package body;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
 #[test]
 fn mixed_overloaded_names_require_disambiguation() {
     let mut builder = LibraryBuilder::new();
@@ -510,7 +669,7 @@ pub fn hidden_error(
 ) -> Diagnostic {
     let mut error = Diagnostic::error(
         code.s(name, occ),
-        format!("Name '{name}' is hidden by conflicting use clause"),
+        format!("Name '{name}' is ambiguous, it is hidden by conflicting use clauses"),
     );
 
     for (code, substr, occ, declared) in related.iter() {
@@ -778,6 +937,7 @@ fn generics_are_visible_in_procedures_but_not_outside() {
     let (_, diagnostics) = builder.get_analyzed_root();
     assert_eq!(
         diagnostics,
-        vec![Diagnostic::error(code.s("T", 4), "No declaration of 'T'")]
+        vec![Diagnostic::error(code.s("T", 4), "No declaration of 'T'")
+            .with_code(ErrorCode::MissingDecl)]
     )
 }
@@ -4,6 +4,7 @@
 //
 // Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
 
+use super::names::ResolvedName;
 use super::*;
 use crate::ast::*;
 use crate::data::*;
@@ -17,15 +18,16 @@ impl<'a> AnalyzeContext<'a> {
         subtype_indication: &mut SubtypeIndication,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<Subtype<'a>> {
-        // @TODO more
         let SubtypeIndication {
+            resolution,
             type_mark,
             constraint,
-            ..
         } = subtype_indication;
 
         let base_type = self.resolve_type_mark(scope, type_mark, diagnostics)?;
+        self.analyze_resolution_indication(scope, resolution, base_type, diagnostics)?;
 
+        let mut static_range = None;
         if let Some(constraint) = constraint {
             self.analyze_subtype_constraint(
                 scope,
@@ -34,9 +36,181 @@ impl<'a> AnalyzeContext<'a> {
                 &mut constraint.item,
                 diagnostics,
             )?;
+            static_range = self.static_constraint_range(&constraint.item, diagnostics);
         }
 
-        Ok(Subtype::new(base_type))
+        let subtype = if matches!(resolution, ResolutionIndication::Unresolved) {
+            Subtype::new(base_type)
+        } else {
+            Subtype::new_resolved(base_type)
+        };
+        Ok(subtype.with_static_range(static_range))
+    }
+
+    /// LRM 6.3 The resolution indication of a subtype of type `typ`
+    fn analyze_resolution_indication(
+        &self,
+        scope: &Scope<'a>,
+        resolution: &mut ResolutionIndication,
+        typ: TypeEnt<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        match resolution {
+            ResolutionIndication::Unresolved => {}
+            ResolutionIndication::FunctionName(name) => {
+                self.analyze_resolution_function(scope, name, typ, diagnostics)?;
+            }
+            ResolutionIndication::ArrayElement(name) => {
+                if let Type::Array { elem_type, .. } = typ.base().kind() {
+                    self.analyze_resolution_function(scope, name, *elem_type, diagnostics)?;
+                } else {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!(
+                                "Element resolution requires an array type, got {}",
+                                typ.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::InvalidResolution),
+                    );
+                }
+            }
+            ResolutionIndication::Record(elements) => {
+                if let Type::Record(region) = typ.base().kind() {
+                    for element in elements.iter_mut() {
+                        let designator = element.ident.item.clone().into();
+                        if let Some(elem) = region.lookup(&designator) {
+                            self.analyze_resolution_indication(
+                                scope,
+                                &mut element.resolution,
+                                elem.type_mark(),
+                                diagnostics,
+                            )?;
+                        } else {
+                            diagnostics.push(Diagnostic::no_declaration_within(
+                                &typ,
+                                &element.ident.pos,
+                                &designator,
+                            ));
+                        }
+                    }
+                } else if let Some(element) = elements.first() {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &element.ident.pos,
+                            format!(
+                                "Record element resolution requires a record type, got {}",
+                                typ.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::InvalidResolution),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// LRM 4.6 A resolution function is a pure function with a single parameter
+    /// of a one-dimensional array of the resolved type that returns the resolved type
+    fn analyze_resolution_function(
+        &self,
+        scope: &Scope<'a>,
+        name: &mut WithPos<Name>,
+        typ: TypeEnt<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let Some(resolved) =
+            as_fatal(self.name_resolve(scope, &name.pos, &mut name.item, diagnostics))?
+        else {
+            return Ok(());
+        };
+
+        let overloaded = match resolved {
+            ResolvedName::Overloaded(_, overloaded) => overloaded,
+            other => {
+                diagnostics.push(other.kind_error(&name.pos, "resolution function"));
+                return Ok(());
+            }
+        };
+
+        let candidates: Vec<_> = overloaded
+            .entities()
+            .filter(|ent| is_resolution_function_of(ent, typ))
+            .collect();
+
+        if candidates.len() > 1 {
+            let mut diagnostic = Diagnostic::error(
+                &name.pos,
+                format!(
+                    "Ambiguous resolution function '{}'",
+                    overloaded.designator()
+                ),
+            )
+            .with_code(ErrorCode::Ambiguous);
+            diagnostic.add_subprogram_candidates("might be", candidates);
+            diagnostics.push(diagnostic);
+        } else if let Some(ent) = candidates.first().copied() {
+            name.set_unique_reference(&ent);
+            if !ent.signature().is_pure() {
+                let mut diagnostic = Diagnostic::error(
+                    &name.pos,
+                    format!("Resolution function '{}' must be pure", ent.designator()),
+                )
+                .with_code(ErrorCode::InvalidResolution);
+                if let Some(decl_pos) = ent.decl_pos() {
+                    diagnostic.add_related(decl_pos, "Declared as impure here");
+                }
+                diagnostics.push(diagnostic);
+            }
+        } else {
+            let mut diagnostic = Diagnostic::error(
+                &name.pos,
+                format!(
+                    "No resolution function '{}' for {}",
+                    overloaded.designator(),
+                    typ.describe()
+                ),
+            )
+            .with_code(ErrorCode::InvalidResolution);
+            diagnostic.add_subprogram_candidates("Does not match", overloaded.entities());
+            diagnostics.push(diagnostic);
+        }
+
+        Ok(())
+    }
+
+    /// A type is not visible within its own declaration, a subtype indication that denotes
+    /// the type being declared is reported as circular rather than as a missing declaration.
+    /// Returns true if the subtype indication is circular.
+    fn check_circular_type(
+        &self,
+        scope: &Scope<'a>,
+        ident: &Ident,
+        subtype_indication: &SubtypeIndication,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> bool {
+        let name = &subtype_indication.type_mark.item.name;
+        let Name::Designator(ref designator) = name.item else {
+            return false;
+        };
+        if !matches!(&designator.item, Designator::Identifier(sym) if sym == &ident.item)
+            || scope.lookup(&name.pos, &designator.item).is_ok()
+        {
+            return false;
+        }
+        diagnostics.push(
+            Diagnostic::error(
+                &name.pos,
+                format!(
+                    "Found circular type declaration, '{}' is used within its own declaration",
+                    ident.item
+                ),
+            )
+            .with_code(ErrorCode::CircularDependency),
+        );
+        true
     }
 
     pub(crate) fn analyze_type_declaration(
@@ -146,17 +320,23 @@ impl<'a> AnalyzeContext<'a> {
                         };
 
                         if !is_ok {
-                            diagnostics.push(Diagnostic::error(
-                                type_decl.ident.pos(),
-                                format!("'{}' is not a protected type", &type_decl.ident),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    type_decl.ident.pos(),
+                                    format!("'{}' is not a protected type", &type_decl.ident),
+                                )
+                                .with_code(ErrorCode::MismatchedKinds),
+                            );
                         }
                     }
                     None => {
-                        diagnostics.push(Diagnostic::error(
-                            type_decl.ident.pos(),
-                            format!("No declaration of protected type '{}'", &type_decl.ident),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                type_decl.ident.pos(),
+                                format!("No declaration of protected type '{}'", &type_decl.ident),
+                            )
+                            .with_code(ErrorCode::MissingDecl),
+                        );
                     }
                 };
             }
@@ -226,6 +406,14 @@ impl<'a> AnalyzeContext<'a> {
                 let mut elems = RecordRegion::default();
                 let mut region = Region::default();
                 for elem_decl in element_decls.iter_mut() {
+                    if self.check_circular_type(
+                        scope,
+                        &type_decl.ident.tree,
+                        &elem_decl.subtype,
+                        diagnostics,
+                    ) {
+                        continue;
+                    }
                     let subtype =
                         self.resolve_subtype_indication(scope, &mut elem_decl.subtype, diagnostics);
                     if let Some(subtype) = as_fatal(subtype)? {
@@ -255,6 +443,14 @@ impl<'a> AnalyzeContext<'a> {
                 }
             }
             TypeDefinition::Access(ref mut subtype_indication) => {
+                if self.check_circular_type(
+                    scope,
+                    &type_decl.ident.tree,
+                    subtype_indication,
+                    diagnostics,
+                ) {
+                    return Ok(());
+                }
                 let subtype =
                     self.resolve_subtype_indication(scope, subtype_indication, diagnostics);
                 if let Some(subtype) = as_fatal(subtype)? {
@@ -287,6 +483,14 @@ impl<'a> AnalyzeContext<'a> {
                     ))?);
                 }
 
+                if self.check_circular_type(
+                    scope,
+                    &type_decl.ident.tree,
+                    subtype_indication,
+                    diagnostics,
+                ) {
+                    return Ok(());
+                }
                 let elem_type = match as_fatal(self.resolve_subtype_indication(
                     scope,
                     subtype_indication,
@@ -317,6 +521,14 @@ impl<'a> AnalyzeContext<'a> {
                 }
             }
             TypeDefinition::Subtype(ref mut subtype_indication) => {
+                if self.check_circular_type(
+                    scope,
+                    &type_decl.ident.tree,
+                    subtype_indication,
+                    diagnostics,
+                ) {
+                    return Ok(());
+                }
                 if let Some(subtype) = as_fatal(self.resolve_subtype_indication(
                     scope,
                     subtype_indication,
@@ -366,13 +578,16 @@ impl<'a> AnalyzeContext<'a> {
                     match self.resolve_physical_unit(scope, &mut value.unit) {
                         Ok(secondary_unit_type) => {
                             if secondary_unit_type.base_type() != phys_type {
-                                diagnostics.error(
-                                    &value.unit.item.pos,
-                                    format!(
-                                        "Physical unit of type '{}' does not match {}",
-                                        secondary_unit_type.designator(),
-                                        phys_type.describe()
-                                    ),
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &value.unit.item.pos,
+                                        format!(
+                                            "Physical unit of type '{}' does not match {}",
+                                            secondary_unit_type.designator(),
+                                            phys_type.describe()
+                                        ),
+                                    )
+                                    .with_code(ErrorCode::TypeMismatch),
                                 )
                             }
                         }
@@ -412,7 +627,10 @@ impl<'a> AnalyzeContext<'a> {
                     } else if range_typ.is_any_real() {
                         UniversalType::Real
                     } else {
-                        diagnostics.error(&range.pos(), "Expected real or integer range");
+                        diagnostics.push(
+                            Diagnostic::error(&range.pos(), "Expected real or integer range")
+                                .with_code(ErrorCode::InvalidRange),
+                        );
                         return Ok(());
                     }
                 } else {
@@ -514,23 +732,32 @@ impl<'a> AnalyzeContext<'a> {
                                 self.drange_unknown_type(scope, drange, diagnostics)?;
                             }
                         } else {
-                            diagnostics.error(
-                                drange.pos(),
-                                format!("Got extra index constraint for {}", base_type.describe()),
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    drange.pos(),
+                                    format!(
+                                        "Got extra index constraint for {}",
+                                        base_type.describe()
+                                    ),
+                                )
+                                .with_code(ErrorCode::IllegalConstraint),
                             );
                         }
                     }
 
                     // empty dranges means (open)
                     if dranges.len() < indexes.len() && !dranges.is_empty() {
-                        diagnostics.error(
-                            pos,
-                            format!(
-                                "Too few index constraints for {}. Got {} but expected {}",
-                                base_type.describe(),
-                                dranges.len(),
-                                indexes.len()
-                            ),
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pos,
+                                format!(
+                                    "Too few index constraints for {}. Got {} but expected {}",
+                                    base_type.describe(),
+                                    dranges.len(),
+                                    indexes.len()
+                                ),
+                            )
+                            .with_code(ErrorCode::IllegalConstraint),
                         );
                     }
 
@@ -544,12 +771,15 @@ impl<'a> AnalyzeContext<'a> {
                         )?;
                     }
                 } else {
-                    diagnostics.error(
-                        pos,
-                        format!(
-                            "Array constraint cannot be used for {}",
-                            base_type.describe()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pos,
+                            format!(
+                                "Array constraint cannot be used for {}",
+                                base_type.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::IllegalConstraint),
                     );
                 }
             }
@@ -557,12 +787,15 @@ impl<'a> AnalyzeContext<'a> {
                 if base_type.is_scalar() {
                     self.range_with_ttyp(scope, base_type.into(), range, diagnostics)?;
                 } else {
-                    diagnostics.error(
-                        pos,
-                        format!(
-                            "Scalar constraint cannot be used for {}",
-                            base_type.describe()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pos,
+                            format!(
+                                "Scalar constraint cannot be used for {}",
+                                base_type.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::IllegalConstraint),
                     );
                 }
             }
@@ -586,12 +819,15 @@ impl<'a> AnalyzeContext<'a> {
                         }
                     }
                 } else {
-                    diagnostics.error(
-                        pos,
-                        format!(
-                            "Record constraint cannot be used for {}",
-                            base_type.describe()
-                        ),
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pos,
+                            format!(
+                                "Record constraint cannot be used for {}",
+                                base_type.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::IllegalConstraint),
                     );
                 }
             }
@@ -609,3 +845,19 @@ impl<'a> AnalyzeContext<'a> {
             .map(|_| ())
     }
 }
+
+fn is_resolution_function_of(ent: &OverloadedEnt, typ: TypeEnt) -> bool {
+    if !ent.is_function() || !ent.signature().match_return_type(Some(typ)) {
+        return false;
+    }
+    let mut formals = ent.formals().iter();
+    let (Some(formal), None) = (formals.next(), formals.next()) else {
+        return false;
+    };
+    match formal.base_type().kind() {
+        Type::Array { indexes, elem_type } => {
+            indexes.len() == 1 && elem_type.base_type() == typ.base_type()
+        }
+        _ => false,
+    }
+}
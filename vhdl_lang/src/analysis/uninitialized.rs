@@ -0,0 +1,525 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Variables of a process or subprogram that may be read before they are assigned
+//!
+//! Only variables declared without an initial value expression are checked. The statements
+//! are visited in order while keeping the set of variables that may not have been assigned
+//! yet. A variable is only considered to be assigned after an if or case statement when
+//! every branch assigns it. The body of a loop is assumed to be executed at least once, so a
+//! variable is assigned after a loop when it is assigned before every exit, next or end of
+//! the loop body. Assigning an element or slice of a variable assigns the whole variable
+//! and a variable passed to a procedure is assumed to be assigned by it. The prefix of an
+//! attribute name such as `var'length` does not read the variable.
+
+use super::analyze::*;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use fnv::FnvHashSet;
+
+/// The variables that may not have been assigned when reaching a statement,
+/// None when the statement cannot be reached
+type Unassigned = Option<FnvHashSet<EntityId>>;
+
+fn join(lhs: Unassigned, rhs: Unassigned) -> Unassigned {
+    match (lhs, rhs) {
+        (Some(mut lhs), Some(rhs)) => {
+            lhs.extend(rhs);
+            Some(lhs)
+        }
+        (lhs, None) => lhs,
+        (None, rhs) => rhs,
+    }
+}
+
+struct LoopState {
+    has_iteration_scheme: bool,
+    leaving: Unassigned,
+}
+
+struct ReadChecker<'a, 'b> {
+    arena: &'a Arena,
+    /// The variables that have already been reported
+    reported: FnvHashSet<EntityId>,
+    /// The states when leaving each of the enclosing loops
+    loops: Vec<LoopState>,
+    diagnostics: &'b mut dyn DiagnosticHandler,
+}
+
+impl<'a, 'b> ReadChecker<'a, 'b> {
+    fn statements(
+        &mut self,
+        statements: &[LabeledSequentialStatement],
+        mut state: Unassigned,
+    ) -> Unassigned {
+        for statement in statements.iter() {
+            if state.is_none() {
+                break;
+            }
+            state = self.statement(&statement.statement.item, state);
+        }
+        state
+    }
+
+    fn statement(&mut self, statement: &SequentialStatement, mut state: Unassigned) -> Unassigned {
+        let unassigned = state.as_mut()?;
+
+        match statement {
+            SequentialStatement::Wait(wait_stmt) => {
+                self.opt_expr(&wait_stmt.condition_clause, unassigned);
+                self.opt_expr(&wait_stmt.timeout_clause, unassigned);
+            }
+            SequentialStatement::Assert(assert) => {
+                self.expr(&assert.condition, unassigned);
+                self.opt_expr(&assert.report, unassigned);
+                self.opt_expr(&assert.severity, unassigned);
+            }
+            SequentialStatement::Report(report) => {
+                self.expr(&report.report, unassigned);
+                self.opt_expr(&report.severity, unassigned);
+            }
+            SequentialStatement::VariableAssignment(assign) => {
+                self.rhs(&assign.rhs, unassigned, |checker, expr, unassigned| {
+                    checker.expr(expr, unassigned)
+                });
+                self.assign_target(&assign.target, unassigned);
+            }
+            SequentialStatement::SignalAssignment(assign) => {
+                self.target_indexes(&assign.target, unassigned);
+                if let Some(DelayMechanism::Inertial { ref reject }) = assign.delay_mechanism {
+                    self.opt_expr(reject, unassigned);
+                }
+                self.rhs(&assign.rhs, unassigned, |checker, waveform, unassigned| {
+                    if let Waveform::Elements(elements) = waveform {
+                        for element in elements.iter() {
+                            checker.expr(&element.value, unassigned);
+                            checker.opt_expr(&element.after, unassigned);
+                        }
+                    }
+                });
+            }
+            SequentialStatement::SignalForceAssignment(assign) => {
+                self.target_indexes(&assign.target, unassigned);
+                self.rhs(&assign.rhs, unassigned, |checker, expr, unassigned| {
+                    checker.expr(expr, unassigned)
+                });
+            }
+            SequentialStatement::SignalReleaseAssignment(assign) => {
+                self.target_indexes(&assign.target, unassigned);
+            }
+            SequentialStatement::ProcedureCall(pcall) => {
+                self.name(&pcall.item.name.pos, &pcall.item.name.item, unassigned);
+                for param in pcall.item.parameters.iter() {
+                    if let ActualPart::Expression(ref expr) = param.actual.item {
+                        match expr {
+                            Expression::Name(name) if self.variable_of(name).is_some() => {
+                                self.assign_name(name, unassigned)
+                            }
+                            _ => self.expression(&param.actual.pos, expr, unassigned),
+                        }
+                    }
+                }
+            }
+            SequentialStatement::If(ifstmt) => {
+                let Conditionals {
+                    conditionals,
+                    else_item,
+                } = &ifstmt.conds;
+                for conditional in conditionals.iter() {
+                    self.expr(&conditional.condition, unassigned);
+                }
+                let incoming = state.clone();
+                let mut outgoing = None;
+                for conditional in conditionals.iter() {
+                    outgoing = join(
+                        outgoing,
+                        self.statements(&conditional.item, incoming.clone()),
+                    );
+                }
+                return match else_item {
+                    Some(else_item) => join(outgoing, self.statements(else_item, incoming)),
+                    None => join(outgoing, incoming),
+                };
+            }
+            SequentialStatement::Case(case) => {
+                self.expr(&case.expression, unassigned);
+                let mut outgoing = None;
+                for alternative in case.alternatives.iter() {
+                    outgoing = join(outgoing, self.statements(&alternative.item, state.clone()));
+                }
+                return outgoing;
+            }
+            SequentialStatement::Loop(loop_stmt) => {
+                match loop_stmt.iteration_scheme {
+                    Some(IterationScheme::While(ref cond)) => self.expr(cond, unassigned),
+                    Some(IterationScheme::For(_, ref drange)) => {
+                        self.discrete_range(drange, unassigned)
+                    }
+                    None => {}
+                }
+
+                let has_iteration_scheme = loop_stmt.iteration_scheme.is_some();
+                self.loops.push(LoopState {
+                    has_iteration_scheme,
+                    leaving: None,
+                });
+                // The body of a loop with an iteration scheme may not run at all
+                let skipped = if has_iteration_scheme {
+                    state.clone()
+                } else {
+                    None
+                };
+                // Later iterations start with fewer unassigned variables than the first one
+                let completed = self.statements(&loop_stmt.statements, state);
+                let leaving = self.loops.pop().and_then(|state| state.leaving);
+
+                return if has_iteration_scheme {
+                    join(join(leaving, completed), skipped)
+                } else {
+                    leaving
+                };
+            }
+            SequentialStatement::Next(next) => {
+                self.opt_expr(&next.condition, unassigned);
+                // The next iteration of a loop with an iteration scheme may not happen
+                let has_label = next.loop_label.is_some();
+                self.leave_loops(
+                    has_label,
+                    |state| has_label || state.has_iteration_scheme,
+                    &state,
+                );
+                // The statements after an unconditional next are not reached
+                next.condition.as_ref()?;
+            }
+            SequentialStatement::Exit(exit) => {
+                self.opt_expr(&exit.condition, unassigned);
+                self.leave_loops(exit.loop_label.is_some(), |_| true, &state);
+                exit.condition.as_ref()?;
+            }
+            SequentialStatement::Return(ret) => {
+                self.opt_expr(&ret.expression, unassigned);
+                return None;
+            }
+            SequentialStatement::Null => {}
+            SequentialStatement::Error => return None,
+        }
+        state
+    }
+
+    /// A next or exit statement with a label may leave any of the enclosing loops
+    fn leave_loops(
+        &mut self,
+        has_label: bool,
+        is_leaving: impl Fn(&LoopState) -> bool,
+        state: &Unassigned,
+    ) {
+        let loops = self.loops.len();
+        let outermost = if has_label {
+            0
+        } else {
+            loops.saturating_sub(1)
+        };
+        for loop_state in self.loops[outermost..].iter_mut() {
+            if is_leaving(loop_state) {
+                loop_state.leaving = join(loop_state.leaving.take(), state.clone());
+            }
+        }
+    }
+
+    fn rhs<T>(
+        &mut self,
+        rhs: &AssignmentRightHand<T>,
+        unassigned: &mut FnvHashSet<EntityId>,
+        mut item: impl FnMut(&mut Self, &T, &mut FnvHashSet<EntityId>),
+    ) {
+        match rhs {
+            AssignmentRightHand::Simple(value) => item(self, value, unassigned),
+            AssignmentRightHand::Conditional(conds) => {
+                for conditional in conds.conditionals.iter() {
+                    self.expr(&conditional.condition, unassigned);
+                    item(self, &conditional.item, unassigned);
+                }
+                if let Some(ref else_item) = conds.else_item {
+                    item(self, else_item, unassigned);
+                }
+            }
+            AssignmentRightHand::Selected(selection) => {
+                self.expr(&selection.expression, unassigned);
+                for alternative in selection.alternatives.iter() {
+                    item(self, &alternative.item, unassigned);
+                }
+            }
+        }
+    }
+
+    fn assign_target(&mut self, target: &WithPos<Target>, unassigned: &mut FnvHashSet<EntityId>) {
+        match target.item {
+            Target::Name(ref name) => self.assign_name(name, unassigned),
+            Target::Aggregate(ref assocs) => {
+                for assoc in assocs.iter() {
+                    let expr = match assoc {
+                        ElementAssociation::Positional(expr) => expr,
+                        ElementAssociation::Named(_, expr) => expr,
+                    };
+                    if let Expression::Name(ref name) = expr.item {
+                        self.assign_name(name, unassigned);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The indexes of a target are read before the target is assigned
+    fn assign_name(&mut self, name: &Name, unassigned: &mut FnvHashSet<EntityId>) {
+        self.indexes(name, unassigned);
+        if let Some(id) = self.variable_of(name) {
+            unassigned.remove(&id);
+        }
+    }
+
+    fn target_indexes(&mut self, target: &WithPos<Target>, unassigned: &mut FnvHashSet<EntityId>) {
+        if let Target::Name(ref name) = target.item {
+            self.indexes(name, unassigned);
+        }
+    }
+
+    fn indexes(&mut self, name: &Name, unassigned: &mut FnvHashSet<EntityId>) {
+        match name {
+            Name::Selected(prefix, _) => self.indexes(&prefix.item, unassigned),
+            Name::Slice(prefix, drange) => {
+                self.indexes(&prefix.item, unassigned);
+                self.discrete_range(drange, unassigned);
+            }
+            Name::CallOrIndexed(call) => {
+                self.indexes(&call.name.item, unassigned);
+                self.parameters(&call.parameters, unassigned);
+            }
+            _ => {}
+        }
+    }
+
+    /// The variable denoted by the longest prefix of a name
+    fn variable_of(&self, name: &Name) -> Option<EntityId> {
+        match name {
+            Name::Designator(designator) => designator
+                .reference
+                .get()
+                .filter(|id| is_variable(self.arena.get(*id))),
+            Name::Selected(prefix, suffix) => suffix
+                .item
+                .reference
+                .get()
+                .filter(|id| is_variable(self.arena.get(*id)))
+                .or_else(|| self.variable_of(&prefix.item)),
+            Name::Slice(prefix, _) => self.variable_of(&prefix.item),
+            Name::CallOrIndexed(call) => self.variable_of(&call.name.item),
+            Name::SelectedAll(..) | Name::Attribute(..) | Name::External(..) => None,
+        }
+    }
+
+    fn read(&mut self, pos: &SrcPos, reference: &Reference, unassigned: &FnvHashSet<EntityId>) {
+        let Some(id) = reference.get() else {
+            return;
+        };
+        if unassigned.contains(&id) && self.reported.insert(id) {
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    pos,
+                    format!(
+                        "Variable '{}' may be read before it is assigned",
+                        self.arena.get(id).designator()
+                    ),
+                )
+                .with_code(ErrorCode::ReadBeforeAssignment),
+            );
+        }
+    }
+
+    fn opt_expr(
+        &mut self,
+        expr: &Option<WithPos<Expression>>,
+        unassigned: &mut FnvHashSet<EntityId>,
+    ) {
+        if let Some(ref expr) = expr {
+            self.expr(expr, unassigned);
+        }
+    }
+
+    fn expr(&mut self, expr: &WithPos<Expression>, unassigned: &mut FnvHashSet<EntityId>) {
+        self.expression(&expr.pos, &expr.item, unassigned);
+    }
+
+    fn expression(
+        &mut self,
+        pos: &SrcPos,
+        expr: &Expression,
+        unassigned: &mut FnvHashSet<EntityId>,
+    ) {
+        match expr {
+            Expression::Binary(_, left, right) => {
+                self.expr(left, unassigned);
+                self.expr(right, unassigned);
+            }
+            Expression::Unary(_, expr) => self.expr(expr, unassigned),
+            Expression::Aggregate(assocs) => {
+                for assoc in assocs.iter() {
+                    match assoc {
+                        ElementAssociation::Positional(expr) => self.expr(expr, unassigned),
+                        ElementAssociation::Named(choices, expr) => {
+                            for choice in choices.iter() {
+                                match choice.item {
+                                    Choice::Expression(ref expr) => {
+                                        self.expression(&choice.pos, expr, unassigned)
+                                    }
+                                    Choice::DiscreteRange(ref drange) => {
+                                        self.discrete_range(drange, unassigned)
+                                    }
+                                    Choice::Others => {}
+                                }
+                            }
+                            self.expr(expr, unassigned);
+                        }
+                    }
+                }
+            }
+            Expression::Qualified(qexpr) => self.expr(&qexpr.expr, unassigned),
+            Expression::Name(name) => self.name(pos, name, unassigned),
+            Expression::Literal(_) => {}
+            Expression::New(alloc) => {
+                if let Allocator::Qualified(ref qexpr) = alloc.item {
+                    self.expr(&qexpr.expr, unassigned);
+                }
+            }
+        }
+    }
+
+    fn name(&mut self, pos: &SrcPos, name: &Name, unassigned: &mut FnvHashSet<EntityId>) {
+        match name {
+            Name::Designator(designator) => self.read(pos, &designator.reference, unassigned),
+            Name::Selected(prefix, suffix) => {
+                self.read(&suffix.pos, &suffix.item.reference, unassigned);
+                self.name(&prefix.pos, &prefix.item, unassigned);
+            }
+            Name::SelectedAll(prefix) => self.name(&prefix.pos, &prefix.item, unassigned),
+            Name::Slice(prefix, drange) => {
+                self.name(&prefix.pos, &prefix.item, unassigned);
+                self.discrete_range(drange, unassigned);
+            }
+            Name::Attribute(attr) => {
+                if let Some(ref expr) = attr.expr {
+                    self.expr(expr, unassigned);
+                }
+            }
+            Name::CallOrIndexed(call) => {
+                self.name(&call.name.pos, &call.name.item, unassigned);
+                self.parameters(&call.parameters, unassigned);
+            }
+            Name::External(..) => {}
+        }
+    }
+
+    fn parameters(
+        &mut self,
+        parameters: &[AssociationElement],
+        unassigned: &mut FnvHashSet<EntityId>,
+    ) {
+        for param in parameters.iter() {
+            if let ActualPart::Expression(ref expr) = param.actual.item {
+                self.expression(&param.actual.pos, expr, unassigned);
+            }
+        }
+    }
+
+    fn discrete_range(&mut self, drange: &DiscreteRange, unassigned: &mut FnvHashSet<EntityId>) {
+        match drange {
+            DiscreteRange::Discrete(_, Some(range)) | DiscreteRange::Range(range) => {
+                self.range(range, unassigned)
+            }
+            DiscreteRange::Discrete(_, None) => {}
+        }
+    }
+
+    fn range(&mut self, range: &crate::ast::Range, unassigned: &mut FnvHashSet<EntityId>) {
+        match range {
+            crate::ast::Range::Range(constraint) => {
+                self.expr(&constraint.left_expr, unassigned);
+                self.expr(&constraint.right_expr, unassigned);
+            }
+            crate::ast::Range::Attribute(attr) => {
+                if let Some(ref expr) = attr.expr {
+                    self.expr(expr, unassigned);
+                }
+            }
+        }
+    }
+}
+
+fn is_variable(ent: EntRef) -> bool {
+    matches!(
+        ent.kind(),
+        AnyEntKind::Object(Object {
+            class: ObjectClass::Variable,
+            ..
+        })
+    )
+}
+
+impl<'a> AnalyzeContext<'a> {
+    /// Warn about variables of a process or subprogram that may be read before they are assigned
+    pub fn check_uninitialized_variables(
+        &self,
+        declarations: &[Declaration],
+        statements: &[LabeledSequentialStatement],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !self.root.lint_uninitialized_variables {
+            return;
+        }
+
+        // A local subprogram may assign any of the variables when it is called
+        if declarations
+            .iter()
+            .any(|decl| matches!(decl, Declaration::SubprogramBody(..)))
+        {
+            return;
+        }
+
+        let unassigned: FnvHashSet<EntityId> = declarations
+            .iter()
+            .filter_map(|decl| match decl {
+                Declaration::Object(object)
+                    if object.class == ObjectClass::Variable && object.expression.is_none() =>
+                {
+                    object.ident.decl.get()
+                }
+                _ => None,
+            })
+            .filter(|id| {
+                // Access values are null and protected types need no value
+                matches!(
+                    self.arena.get(*id).kind(),
+                    AnyEntKind::Object(object) if !matches!(
+                        object.subtype.type_mark().base_type().kind(),
+                        Type::Access(..) | Type::Protected(..) | Type::File
+                    )
+                )
+            })
+            .collect();
+
+        if unassigned.is_empty() {
+            return;
+        }
+
+        let mut checker = ReadChecker {
+            arena: self.arena,
+            reported: FnvHashSet::default(),
+            loops: Vec::new(),
+            diagnostics,
+        };
+        checker.statements(statements, Some(unassigned));
+    }
+}
@@ -22,10 +22,12 @@ pub(crate) use any_design_unit::*;
 
 use crate::data::*;
 use crate::named_entity::{EntityId, Reference};
+use crate::standard::VHDLStandard;
 use crate::syntax::{Token, TokenAccess, TokenId};
 
 /// LRM 15.8 Bit string literals
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum BaseSpecifier {
     B,
     O,
@@ -40,6 +42,7 @@ pub enum BaseSpecifier {
 }
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operator {
     And,
     Or,
@@ -85,6 +88,7 @@ pub enum Operator {
 
 /// LRM 8.6 Attribute names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeName {
     pub name: WithPos<Name>,
     pub signature: Option<WithPos<Signature>>,
@@ -93,18 +97,21 @@ pub struct AttributeName {
 }
 
 #[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeAttribute {
     Subtype,
     Element,
 }
 
 #[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum RangeAttribute {
     Range,
     ReverseRange,
 }
 
 #[derive(PartialEq, Debug, Clone, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttributeDesignator {
     Type(TypeAttribute),
     Range(RangeAttribute),
@@ -131,6 +138,7 @@ pub enum AttributeDesignator {
 }
 
 #[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SignalAttribute {
     Delayed,
     Stable,
@@ -147,6 +155,7 @@ pub enum SignalAttribute {
 
 /// LRM 8.7 External names
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExternalObjectClass {
     Constant,
     Signal,
@@ -165,6 +174,7 @@ impl From<ExternalObjectClass> for ObjectClass {
 
 /// LRM 8.7 External names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExternalPath {
     Package(WithPos<Name>),
     Absolute(WithPos<Name>),
@@ -175,6 +185,7 @@ pub enum ExternalPath {
 
 /// LRM 8.7 External names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExternalName {
     pub class: ExternalObjectClass,
     pub path: WithPos<ExternalPath>,
@@ -183,6 +194,7 @@ pub struct ExternalName {
 
 /// LRM 8. Names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Name {
     Designator(WithRef<Designator>),
     Selected(Box<WithPos<Name>>, WithPos<WithRef<Designator>>),
@@ -195,6 +207,7 @@ pub enum Name {
 
 /// LRM 9.3.4 Function calls
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallOrIndexed {
     pub name: WithPos<Name>,
     pub parameters: Vec<AssociationElement>,
@@ -202,6 +215,7 @@ pub struct CallOrIndexed {
 
 /// LRM 9.3.3 Aggregates
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Choice {
     Expression(Expression),
     DiscreteRange(DiscreteRange),
@@ -210,6 +224,7 @@ pub enum Choice {
 
 /// LRM 9.3.3 Aggregates
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ElementAssociation {
     Positional(WithPos<Expression>),
     Named(Vec<WithPos<Choice>>, WithPos<Expression>),
@@ -217,6 +232,7 @@ pub enum ElementAssociation {
 
 /// LRM 6.5.7 Association Lists
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActualPart {
     Expression(Expression),
     Open,
@@ -224,6 +240,7 @@ pub enum ActualPart {
 
 /// LRM 6.5.7 Association Lists
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssociationElement {
     pub formal: Option<WithPos<Name>>,
     pub actual: WithPos<ActualPart>,
@@ -231,6 +248,7 @@ pub struct AssociationElement {
 
 /// LRM 15.5 Abstract literals
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AbstractLiteral {
     Integer(u64),
     Real(f64),
@@ -238,6 +256,7 @@ pub enum AbstractLiteral {
 
 /// LRM 15.8 Bit string literals
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitString {
     pub length: Option<u32>,
     pub base: BaseSpecifier,
@@ -245,6 +264,7 @@ pub struct BitString {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PhysicalLiteral {
     pub value: AbstractLiteral,
     pub unit: WithRef<Ident>,
@@ -252,6 +272,7 @@ pub struct PhysicalLiteral {
 
 /// LRM 9.3.2 Literals
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Literal {
     String(Latin1String),
     BitString(BitString),
@@ -263,6 +284,7 @@ pub enum Literal {
 
 /// LRM 9.3.7 Allocators
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Allocator {
     Qualified(QualifiedExpression),
     Subtype(SubtypeIndication),
@@ -270,6 +292,7 @@ pub enum Allocator {
 
 /// LRM 9.3.5 Qualified expressions
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct QualifiedExpression {
     pub type_mark: WithPos<TypeMark>,
     pub expr: WithPos<Expression>,
@@ -277,6 +300,7 @@ pub struct QualifiedExpression {
 
 /// LRM 9. Expressions
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression {
     Binary(
         WithPos<WithRef<Operator>>,
@@ -305,6 +329,7 @@ pub enum Expression {
 pub type Ident = WithPos<Symbol>;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Ascending,
     Descending,
@@ -317,12 +342,14 @@ pub enum Direction {
 ///   | simple_expression direction simple_expression
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum DiscreteRange {
     Discrete(WithPos<TypeMark>, Option<Range>),
     Range(Range),
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RangeConstraint {
     pub direction: Direction,
     pub left_expr: Box<WithPos<Expression>>,
@@ -330,6 +357,7 @@ pub struct RangeConstraint {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Range {
     Range(RangeConstraint),
     Attribute(Box<AttributeName>),
@@ -337,12 +365,14 @@ pub enum Range {
 
 /// LRM: record_element_constraint
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ElementConstraint {
     pub ident: Ident,
     pub constraint: Box<WithPos<SubtypeConstraint>>,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SubtypeConstraint {
     Range(Range),
     /// Empty Vec means Open
@@ -352,6 +382,7 @@ pub enum SubtypeConstraint {
 
 /// LRM 6.3 Subtype declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RecordElementResolution {
     pub ident: Ident,
     pub resolution: Box<ResolutionIndication>,
@@ -359,6 +390,7 @@ pub struct RecordElementResolution {
 
 /// LRM 6.3 Subtype declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResolutionIndication {
     FunctionName(WithPos<Name>),
     ArrayElement(WithPos<Name>),
@@ -367,6 +399,7 @@ pub enum ResolutionIndication {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeMark {
     pub name: WithPos<Name>,
     pub attr: Option<TypeAttribute>,
@@ -374,6 +407,7 @@ pub struct TypeMark {
 
 /// LRM 6.3 Subtype declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubtypeIndication {
     pub resolution: ResolutionIndication,
     pub type_mark: WithPos<TypeMark>,
@@ -382,6 +416,7 @@ pub struct SubtypeIndication {
 
 /// LRM 5.3 Array Types
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArrayIndex {
     /// Unbounded
     /// {identifier} range <>
@@ -393,6 +428,7 @@ pub enum ArrayIndex {
 
 /// LRM 5.3.3 Record types
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ElementDeclaration {
     pub ident: WithDecl<Ident>,
     pub subtype: SubtypeIndication,
@@ -400,11 +436,13 @@ pub struct ElementDeclaration {
 
 /// LRM 5.6.2 Protected type declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProtectedTypeDeclarativeItem {
     Subprogram(SubprogramDeclaration),
 }
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Designator {
     Identifier(Symbol),
     OperatorSymbol(Operator),
@@ -414,8 +452,10 @@ pub enum Designator {
 
 /// An item which has a reference to a declaration
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WithRef<T> {
     pub item: T,
+    #[cfg_attr(feature = "serialize", serde(skip, default = "Reference::undefined"))]
     pub reference: Reference,
 }
 
@@ -430,8 +470,10 @@ impl<T> WithRef<T> {
 
 /// An item which declares a named entity
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WithDecl<T> {
     pub tree: T,
+    #[cfg_attr(feature = "serialize", serde(skip, default = "Reference::undefined"))]
     pub decl: Reference,
 }
 
@@ -457,6 +499,7 @@ impl<T: AsRef<SrcPos>> AsRef<SrcPos> for WithDecl<T> {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WithToken<T> {
     item: T,
     token: TokenId,
@@ -471,6 +514,7 @@ impl<T> WithToken<T> {
 /// LRM 6.6 Alias declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AliasDeclaration {
     pub designator: WithDecl<WithPos<Designator>>,
     pub subtype_indication: Option<SubtypeIndication>,
@@ -481,6 +525,7 @@ pub struct AliasDeclaration {
 /// LRM 6.7 Attribute declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeDeclaration {
     pub ident: WithDecl<Ident>,
     pub type_mark: WithPos<TypeMark>,
@@ -488,6 +533,7 @@ pub struct AttributeDeclaration {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityTag {
     pub designator: WithPos<WithRef<Designator>>,
     pub signature: Option<WithPos<Signature>>,
@@ -495,6 +541,7 @@ pub struct EntityTag {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntityName {
     Name(EntityTag),
     All,
@@ -504,6 +551,7 @@ pub enum EntityName {
 /// LRM 7.2 Attribute specification
 // @TODO there are more classes
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntityClass {
     Entity,
     Architecture,
@@ -529,6 +577,7 @@ pub enum EntityClass {
 /// LRM 7.2 Attribute specification
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeSpecification {
     pub ident: WithRef<Ident>,
     pub entity_name: EntityName,
@@ -538,6 +587,7 @@ pub struct AttributeSpecification {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Attribute {
     Specification(AttributeSpecification),
     Declaration(AttributeDeclaration),
@@ -545,18 +595,21 @@ pub enum Attribute {
 
 /// LRM 5.6.2 Protected type declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProtectedTypeDeclaration {
     pub items: Vec<ProtectedTypeDeclarativeItem>,
 }
 
 /// LRM 5.6.3 Protected type bodies
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProtectedTypeBody {
     pub decl: Vec<Declaration>,
 }
 
 /// LRM 5.4.2 Physical type declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PhysicalTypeDeclaration {
     pub range: Range,
     pub primary_unit: WithDecl<Ident>,
@@ -565,6 +618,7 @@ pub struct PhysicalTypeDeclaration {
 
 /// LRM 5.2.2 Enumeration types
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum EnumerationLiteral {
     Identifier(Symbol),
     Character(u8),
@@ -572,6 +626,7 @@ pub enum EnumerationLiteral {
 
 /// LRM 5 Types
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeDefinition {
     /// LRM 5.2 Scalar Types
     /// LRM 5.2.2 Enumeration types
@@ -603,6 +658,7 @@ pub enum TypeDefinition {
 /// LRM 6.2 Type declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeDeclaration {
     pub ident: WithDecl<Ident>,
     pub def: TypeDefinition,
@@ -611,6 +667,7 @@ pub struct TypeDeclaration {
 
 /// LRM 6.4.2 Object Declarations
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjectClass {
     Signal,
     Constant,
@@ -618,7 +675,17 @@ pub enum ObjectClass {
     SharedVariable,
 }
 
+/// LRM 6.4.2.3 Signal declarations
+/// The kind of a guarded signal
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SignalKind {
+    Register,
+    Bus,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterfaceType {
     Port,
     Generic,
@@ -627,15 +694,18 @@ pub enum InterfaceType {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ObjectDeclaration {
     pub class: ObjectClass,
     pub ident: WithDecl<Ident>,
     pub subtype_indication: SubtypeIndication,
+    pub signal_kind: Option<SignalKind>,
     pub expression: Option<WithPos<Expression>>,
 }
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileDeclaration {
     pub ident: WithDecl<Ident>,
     pub subtype_indication: SubtypeIndication,
@@ -644,6 +714,7 @@ pub struct FileDeclaration {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SubprogramDesignator {
     Identifier(Symbol),
     OperatorSymbol(Operator),
@@ -651,6 +722,7 @@ pub enum SubprogramDesignator {
 
 /// LRM 4.2 Subprogram declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcedureSpecification {
     pub designator: WithDecl<WithPos<SubprogramDesignator>>,
     pub header: Option<SubprogramHeader>,
@@ -661,6 +733,7 @@ pub struct ProcedureSpecification {
 
 /// LRM 4.2 Subprogram declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionSpecification {
     pub pure: bool,
     pub designator: WithDecl<WithPos<SubprogramDesignator>>,
@@ -668,12 +741,15 @@ pub struct FunctionSpecification {
     // The `parameter` token, if such a token exists
     pub param_tok: Option<TokenId>,
     pub parameter_list: Vec<InterfaceDeclaration>,
+    // The name of the return subtype such as `r` in `return r of t`
+    pub return_identifier: Option<WithDecl<Ident>>,
     pub return_type: WithPos<TypeMark>,
 }
 
 /// LRM 4.3 Subprogram bodies
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubprogramBody {
     pub specification: SubprogramSpecification,
     pub declarations: Vec<Declaration>,
@@ -686,6 +762,7 @@ pub struct SubprogramBody {
 /// Instead, the element that contains the header (e.g., procedure specifications)
 /// mark this element as optional.
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubprogramHeader {
     pub generic_tok: TokenId,
     pub generic_list: Vec<InterfaceDeclaration>,
@@ -693,6 +770,7 @@ pub struct SubprogramHeader {
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SubprogramKind {
     Function,
     Procedure,
@@ -701,6 +779,7 @@ pub enum SubprogramKind {
 /// LRM 4.4 Subprogram Instantiation Statement
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubprogramInstantiation {
     pub kind: SubprogramKind,
     pub ident: WithDecl<Ident>,
@@ -711,12 +790,14 @@ pub struct SubprogramInstantiation {
 
 /// LRM 4.5.3 Signatures
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Signature {
     Function(Vec<WithPos<TypeMark>>, WithPos<TypeMark>),
     Procedure(Vec<WithPos<TypeMark>>),
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SubprogramSpecification {
     Procedure(ProcedureSpecification),
     Function(FunctionSpecification),
@@ -725,11 +806,13 @@ pub enum SubprogramSpecification {
 /// LRM 4.2 Subprogram declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubprogramDeclaration {
     pub specification: SubprogramSpecification,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterfaceFileDeclaration {
     pub ident: WithDecl<Ident>,
     pub subtype_indication: SubtypeIndication,
@@ -737,6 +820,7 @@ pub struct InterfaceFileDeclaration {
 
 /// LRM 6.5.2 Interface object declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterfaceObjectDeclaration {
     pub list_type: InterfaceType,
     pub class: ObjectClass,
@@ -747,12 +831,14 @@ pub struct InterfaceObjectDeclaration {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SubprogramDefault {
     Name(WithPos<Name>),
     Box,
 }
 /// LRM 6.5.5 Interface package declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterfacePackageGenericMapAspect {
     Map(SeparatedList<AssociationElement>),
     Box,
@@ -761,6 +847,7 @@ pub enum InterfacePackageGenericMapAspect {
 
 /// LRM 6.5.5 Interface package declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterfacePackageDeclaration {
     pub ident: WithDecl<Ident>,
     pub package_name: WithPos<Name>,
@@ -768,6 +855,7 @@ pub struct InterfacePackageDeclaration {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterfaceDeclaration {
     Object(InterfaceObjectDeclaration),
     File(InterfaceFileDeclaration),
@@ -779,6 +867,7 @@ pub enum InterfaceDeclaration {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mode {
     In,
     Out,
@@ -788,6 +877,7 @@ pub enum Mode {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortClause {
     pub port_list: Vec<InterfaceDeclaration>,
 }
@@ -795,6 +885,7 @@ pub struct PortClause {
 /// LRM 6.8 Component declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComponentDeclaration {
     pub ident: WithDecl<Ident>,
     pub generic_list: Vec<InterfaceDeclaration>,
@@ -802,7 +893,17 @@ pub struct ComponentDeclaration {
     pub end_ident_pos: Option<SrcPos>,
 }
 
+/// A declaration that could not be parsed
+#[with_token_span]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorDeclaration {
+    /// The position of the skipped tokens
+    pub pos: SrcPos,
+}
+
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Declaration {
     Object(ObjectDeclaration),
     File(FileDeclaration),
@@ -816,10 +917,14 @@ pub enum Declaration {
     Use(UseClause),
     Package(PackageInstantiation),
     Configuration(ConfigurationSpecification),
+    Disconnection(DisconnectionSpecification),
+    Psl(PslDeclaration),
+    Error(ErrorDeclaration),
 }
 
 /// LRM 10.2 Wait statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WaitStatement {
     pub sensitivity_clause: Vec<WithPos<Name>>,
     pub condition_clause: Option<WithPos<Expression>>,
@@ -828,6 +933,7 @@ pub struct WaitStatement {
 
 /// LRM 10.3 Assertion statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssertStatement {
     pub condition: WithPos<Expression>,
     pub report: Option<WithPos<Expression>>,
@@ -836,6 +942,7 @@ pub struct AssertStatement {
 
 /// LRM 10.4 Report statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReportStatement {
     pub report: WithPos<Expression>,
     pub severity: Option<WithPos<Expression>>,
@@ -843,6 +950,7 @@ pub struct ReportStatement {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Target {
     Name(Name),
     Aggregate(Vec<ElementAssociation>),
@@ -850,6 +958,7 @@ pub enum Target {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WaveformElement {
     pub value: WithPos<Expression>,
     pub after: Option<WithPos<Expression>>,
@@ -857,6 +966,7 @@ pub struct WaveformElement {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Waveform {
     Elements(Vec<WaveformElement>),
     Unaffected,
@@ -864,6 +974,7 @@ pub enum Waveform {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum DelayMechanism {
     Transport,
     Inertial { reject: Option<WithPos<Expression>> },
@@ -871,6 +982,7 @@ pub enum DelayMechanism {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SignalAssignment {
     pub target: WithPos<Target>,
     pub delay_mechanism: Option<DelayMechanism>,
@@ -878,12 +990,14 @@ pub struct SignalAssignment {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ForceMode {
     In,
     Out,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SignalForceAssignment {
     pub target: WithPos<Target>,
     pub force_mode: Option<ForceMode>,
@@ -891,6 +1005,7 @@ pub struct SignalForceAssignment {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SignalReleaseAssignment {
     pub target: WithPos<Target>,
     pub force_mode: Option<ForceMode>,
@@ -898,6 +1013,7 @@ pub struct SignalReleaseAssignment {
 
 /// LRM 10.6 Variable assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariableAssignment {
     pub target: WithPos<Target>,
     pub rhs: AssignmentRightHand<WithPos<Expression>>,
@@ -906,6 +1022,7 @@ pub struct VariableAssignment {
 /// LRM 10.5 Signal assignment statement
 /// LRM 10.6 Variable assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AssignmentRightHand<T> {
     Simple(T),
     Conditional(Conditionals<T>),
@@ -913,12 +1030,14 @@ pub enum AssignmentRightHand<T> {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Conditional<T> {
     pub condition: WithPos<Expression>,
     pub item: T,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Conditionals<T> {
     pub conditionals: Vec<Conditional<T>>,
     pub else_item: Option<T>,
@@ -926,18 +1045,21 @@ pub struct Conditionals<T> {
 
 /// LRM 10.8 If statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct IfStatement {
     pub conds: Conditionals<Vec<LabeledSequentialStatement>>,
     pub end_label_pos: Option<SrcPos>,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Alternative<T> {
     pub choices: Vec<WithPos<Choice>>,
     pub item: T,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Selection<T> {
     pub expression: WithPos<Expression>,
     pub alternatives: Vec<Alternative<T>>,
@@ -945,6 +1067,7 @@ pub struct Selection<T> {
 
 /// LRM 10.9 Case statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct CaseStatement {
     pub is_matching: bool,
     pub expression: WithPos<Expression>,
@@ -954,6 +1077,7 @@ pub struct CaseStatement {
 
 /// LRM 10.10 Loop statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum IterationScheme {
     While(WithPos<Expression>),
     For(WithDecl<Ident>, DiscreteRange),
@@ -961,6 +1085,7 @@ pub enum IterationScheme {
 
 /// LRM 10.10 Loop statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct LoopStatement {
     pub iteration_scheme: Option<IterationScheme>,
     pub statements: Vec<LabeledSequentialStatement>,
@@ -969,6 +1094,7 @@ pub struct LoopStatement {
 
 /// LRM 10.11 Next statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct NextStatement {
     pub loop_label: Option<WithRef<Ident>>,
     pub condition: Option<WithPos<Expression>>,
@@ -976,6 +1102,7 @@ pub struct NextStatement {
 
 /// LRM 10.12 Exit statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExitStatement {
     pub loop_label: Option<WithRef<Ident>>,
     pub condition: Option<WithPos<Expression>>,
@@ -983,12 +1110,14 @@ pub struct ExitStatement {
 
 /// LRM 10.13 Return statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReturnStatement {
     pub expression: Option<WithPos<Expression>>,
 }
 
 /// LRM 10. Sequential statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SequentialStatement {
     Wait(WaitStatement),
     Assert(AssertStatement),
@@ -1005,10 +1134,13 @@ pub enum SequentialStatement {
     Exit(ExitStatement),
     Return(ReturnStatement),
     Null,
+    /// A statement that could not be parsed, the position of the statement covers the skipped tokens
+    Error,
 }
 
 /// LRM 10. Sequential statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct LabeledSequentialStatement {
     pub label: WithDecl<Option<Ident>>,
     pub statement: WithPos<SequentialStatement>,
@@ -1017,6 +1149,7 @@ pub struct LabeledSequentialStatement {
 /// LRM 11.2 Block statement
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockStatement {
     pub guard_condition: Option<WithPos<Expression>>,
     pub header: BlockHeader,
@@ -1027,6 +1160,7 @@ pub struct BlockStatement {
 
 /// LRM 11.2 Block statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockHeader {
     pub generic_clause: Option<Vec<InterfaceDeclaration>>,
     pub generic_map: Option<MapAspect>,
@@ -1035,6 +1169,7 @@ pub struct BlockHeader {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SensitivityList {
     Names(Vec<WithPos<Name>>),
     All,
@@ -1043,6 +1178,7 @@ pub enum SensitivityList {
 /// LRM 11.3 Process statement
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcessStatement {
     pub postponed: bool,
     pub sensitivity_list: Option<SensitivityList>,
@@ -1053,6 +1189,7 @@ pub struct ProcessStatement {
 
 /// LRM 11.4 Concurrent procedure call statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConcurrentProcedureCall {
     pub postponed: bool,
     pub call: WithPos<CallOrIndexed>,
@@ -1060,13 +1197,187 @@ pub struct ConcurrentProcedureCall {
 
 /// LRM 11.5 Concurrent assertion statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConcurrentAssertStatement {
     pub postponed: bool,
     pub statement: AssertStatement,
 }
 
+/// IEEE 1850 invariance and occurrence operators of PSL properties
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslUnaryOperator {
+    Always,
+    Never,
+    /// `eventually!`
+    Eventually,
+    /// `next` or `next!` when strong
+    Next {
+        strong: bool,
+    },
+}
+
+/// IEEE 1850 implication and bounding operators of PSL properties
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslBinaryOperator {
+    /// `->`
+    Implies,
+    /// `<->`
+    Iff,
+    /// `until` or `until!` when strong
+    Until { strong: bool },
+    /// `before` or `before!` when strong
+    Before { strong: bool },
+}
+
+/// IEEE 1850 6.2 Properties of the PSL foundation language
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslProperty {
+    Boolean(WithPos<Expression>),
+    /// A sequence that must hold, `{a; b}` or `{a; b}!` when strong
+    Sequence {
+        sequence: WithPos<PslSequence>,
+        strong: bool,
+    },
+    Unary(PslUnaryOperator, Box<WithPos<PslProperty>>),
+    Binary(
+        PslBinaryOperator,
+        Box<WithPos<PslProperty>>,
+        Box<WithPos<PslProperty>>,
+    ),
+    /// `sequence |-> property` or `sequence |=> property` when not overlapping
+    SuffixImplication {
+        sequence: WithPos<PslSequence>,
+        overlapping: bool,
+        property: Box<WithPos<PslProperty>>,
+    },
+    /// `property abort condition`
+    Abort(Box<WithPos<PslProperty>>, WithPos<Expression>),
+    /// `property @ clock`
+    Clocked(Box<WithPos<PslProperty>>, WithPos<Expression>),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslSequenceOperator {
+    /// `;`
+    Concatenation,
+    /// `:`
+    Fusion,
+    /// `|`
+    Or,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslRepetitionKind {
+    /// `[*]`
+    Consecutive,
+    /// `[+]`
+    OneOrMore,
+    /// `[=]`
+    NonConsecutive,
+    /// `[->]`
+    Goto,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslBound {
+    Expression(WithPos<Expression>),
+    /// `inf`
+    Inf,
+}
+
+/// The number of repetitions, `n`, `low to high` or `low : high`
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PslCount {
+    pub low: WithPos<Expression>,
+    pub high: Option<PslBound>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PslRepetition {
+    pub kind: PslRepetitionKind,
+    pub count: Option<PslCount>,
+}
+
+/// IEEE 1850 6.1 Sequential extended regular expressions
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslSequence {
+    Boolean(WithPos<Expression>),
+    Binary(
+        PslSequenceOperator,
+        Box<WithPos<PslSequence>>,
+        Box<WithPos<PslSequence>>,
+    ),
+    /// A repeated sequence, the sequence is absent for a repetition of any value such as `[*]`
+    Repeated(Option<Box<WithPos<PslSequence>>>, PslRepetition),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslDirectiveKind {
+    Assert,
+    Assume,
+    Restrict,
+    Cover,
+}
+
+/// LRM 11.1 PSL verification directive
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PslDirective {
+    pub kind: PslDirectiveKind,
+    pub property: WithPos<PslProperty>,
+    pub report: Option<WithPos<Expression>>,
+    pub severity: Option<WithPos<Expression>>,
+}
+
+/// IEEE 1850 7.2.1 Default clock declaration, `default clock is clock;`
+#[with_token_span]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PslDefaultClock {
+    pub clock: WithPos<Expression>,
+}
+
+/// IEEE 1850 6.3 Named property declaration
+#[with_token_span]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PslPropertyDeclaration {
+    pub ident: WithDecl<Ident>,
+    pub property: WithPos<PslProperty>,
+}
+
+/// IEEE 1850 6.3 Named sequence declaration
+#[with_token_span]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PslSequenceDeclaration {
+    pub ident: WithDecl<Ident>,
+    pub sequence: WithPos<PslSequence>,
+}
+
+/// LRM 3.3.2 / 11.1 PSL declarations, they may appear both in block declarative parts
+/// and among the concurrent statements
+#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PslDeclaration {
+    DefaultClock(PslDefaultClock),
+    Property(PslPropertyDeclaration),
+    Sequence(PslSequenceDeclaration),
+}
+
 /// 11.6 Concurrent signal assignment statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConcurrentSignalAssignment {
     pub postponed: bool,
     pub guarded: bool,
@@ -1077,6 +1388,7 @@ pub struct ConcurrentSignalAssignment {
 
 /// 11.7 Component instantiation statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum InstantiatedUnit {
     Component(WithPos<Name>),
     Entity(WithPos<Name>, Option<WithRef<Ident>>),
@@ -1095,6 +1407,7 @@ impl InstantiatedUnit {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapAspect {
     pub start: TokenId, // `generic` or `map`
     pub list: SeparatedList<AssociationElement>,
@@ -1116,6 +1429,7 @@ impl MapAspect {
 /// 11.7 Component instantiation statements
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstantiationStatement {
     pub unit: InstantiatedUnit,
     pub generic_map: Option<MapAspect>,
@@ -1131,6 +1445,7 @@ impl InstantiationStatement {
 
 /// 11.8 Generate statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenerateBody {
     pub alternative_label: Option<WithDecl<Ident>>,
     pub decl: Option<Vec<Declaration>>,
@@ -1141,6 +1456,7 @@ pub struct GenerateBody {
 /// 11.8 Generate statements
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ForGenerateStatement {
     pub index_name: WithDecl<Ident>,
     pub discrete_range: DiscreteRange,
@@ -1151,6 +1467,7 @@ pub struct ForGenerateStatement {
 /// 11.8 Generate statements
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct IfGenerateStatement {
     pub conds: Conditionals<GenerateBody>,
     pub end_label_pos: Option<SrcPos>,
@@ -1158,6 +1475,7 @@ pub struct IfGenerateStatement {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct CaseGenerateStatement {
     pub sels: Selection<GenerateBody>,
     pub end_label_pos: Option<SrcPos>,
@@ -1165,6 +1483,7 @@ pub struct CaseGenerateStatement {
 
 /// LRM 11. Concurrent statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConcurrentStatement {
     ProcedureCall(ConcurrentProcedureCall),
     Block(BlockStatement),
@@ -1175,10 +1494,15 @@ pub enum ConcurrentStatement {
     ForGenerate(ForGenerateStatement),
     IfGenerate(IfGenerateStatement),
     CaseGenerate(CaseGenerateStatement),
+    PslDirective(PslDirective),
+    PslDeclaration(PslDeclaration),
+    /// A statement that could not be parsed, the position of the statement covers the skipped tokens
+    Error,
 }
 
 /// LRM 11. Concurrent statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct LabeledConcurrentStatement {
     pub label: WithDecl<Option<Ident>>,
     pub statement: WithPos<ConcurrentStatement>,
@@ -1187,12 +1511,14 @@ pub struct LabeledConcurrentStatement {
 /// LRM 13. Design units and their analysis
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct LibraryClause {
     pub name_list: IdentList,
 }
 
 /// Represents a token-separated list of some generic type `T`
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SeparatedList<T> {
     pub items: Vec<T>,
     pub tokens: Vec<TokenId>,
@@ -1226,6 +1552,7 @@ pub type NameList = SeparatedList<WithPos<Name>>;
 /// LRM 12.4. Use clauses
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct UseClause {
     pub name_list: NameList,
 }
@@ -1233,12 +1560,14 @@ pub struct UseClause {
 /// LRM 13.4 Context clauses
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContextReference {
     pub name_list: NameList,
 }
 
 /// LRM 13.4 Context clauses
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ContextItem {
     Use(UseClause),
     Library(LibraryClause),
@@ -1248,6 +1577,7 @@ pub enum ContextItem {
 /// LRM 13.4 Context clauses
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContextDeclaration {
     pub ident: WithDecl<Ident>,
     pub items: ContextClause,
@@ -1257,6 +1587,7 @@ pub struct ContextDeclaration {
 /// LRM 4.9 Package instatiation declaration
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PackageInstantiation {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1266,22 +1597,25 @@ pub struct PackageInstantiation {
 
 /// LRM 7.3 Configuration specification
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum InstantiationList {
-    Labels(Vec<Ident>),
+    Labels(Vec<WithRef<Ident>>),
     Others,
     All,
 }
 
 /// LRM 7.3.2 Binding indication
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntityAspect {
-    Entity(WithPos<Name>, Option<Ident>),
+    Entity(WithPos<Name>, Option<WithRef<Ident>>),
     Configuration(WithPos<Name>),
     Open,
 }
 
 /// LRM 7.3.2 Binding indication
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct BindingIndication {
     pub entity_aspect: Option<EntityAspect>,
     pub generic_map: Option<MapAspect>,
@@ -1290,6 +1624,7 @@ pub struct BindingIndication {
 
 /// LRM 7.3 Configuration specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComponentSpecification {
     pub instantiation_list: InstantiationList,
     pub component_name: WithPos<Name>,
@@ -1297,6 +1632,7 @@ pub struct ComponentSpecification {
 
 /// LRM 7.3.4 Verification unit binding indication
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct VUnitBindingIndication {
     pub vunit_list: Vec<WithPos<Name>>,
 }
@@ -1304,14 +1640,35 @@ pub struct VUnitBindingIndication {
 /// LRM 7.3 Configuration specification
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConfigurationSpecification {
     pub spec: ComponentSpecification,
     pub bind_ind: BindingIndication,
     pub vunit_bind_inds: Vec<VUnitBindingIndication>,
 }
 
+/// LRM 7.4 Disconnection specification
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GuardedSignalList {
+    Names(Vec<WithPos<Name>>),
+    Others,
+    All,
+}
+
+/// LRM 7.4 Disconnection specification
+#[with_token_span]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisconnectionSpecification {
+    pub signal_list: GuardedSignalList,
+    pub type_mark: WithPos<TypeMark>,
+    pub after: WithPos<Expression>,
+}
+
 /// LRM 3.4 Configuration declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConfigurationDeclarativeItem {
     Use(UseClause),
     // @TODO attribute
@@ -1319,6 +1676,7 @@ pub enum ConfigurationDeclarativeItem {
 }
 /// LRM 3.4 Configuration declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComponentConfiguration {
     pub spec: ComponentSpecification,
     pub bind_ind: Option<BindingIndication>,
@@ -1328,6 +1686,7 @@ pub struct ComponentConfiguration {
 
 /// LRM 3.4 Configuration declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConfigurationItem {
     Block(BlockConfiguration),
     Component(ComponentConfiguration),
@@ -1335,6 +1694,7 @@ pub enum ConfigurationItem {
 
 /// LRM 3.4 Configuration declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockConfiguration {
     pub block_spec: WithPos<Name>,
     pub use_clauses: Vec<UseClause>,
@@ -1344,6 +1704,7 @@ pub struct BlockConfiguration {
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConfigurationDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1357,6 +1718,7 @@ pub struct ConfigurationDeclaration {
 /// LRM 3.2 Entity declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1370,6 +1732,7 @@ pub struct EntityDeclaration {
 /// LRM 3.3 Architecture bodies
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ArchitectureBody {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1383,6 +1746,7 @@ pub struct ArchitectureBody {
 /// LRM 4.7 Package declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PackageDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1394,6 +1758,7 @@ pub struct PackageDeclaration {
 /// LRM 4.8 Package bodies
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PackageBody {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1403,6 +1768,7 @@ pub struct PackageBody {
 
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnyPrimaryUnit {
     /// LRM 3.2 Entity declaration
     Entity(EntityDeclaration),
@@ -1422,6 +1788,7 @@ pub enum AnyPrimaryUnit {
 
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnySecondaryUnit {
     /// LRM 3.3 Architecture bodies
     Architecture(ArchitectureBody),
@@ -1434,6 +1801,7 @@ pub type ContextClause = Vec<ContextItem>;
 
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnyDesignUnit {
     Primary(AnyPrimaryUnit),
     Secondary(AnySecondaryUnit),
@@ -1446,6 +1814,11 @@ impl AnyDesignUnit {
 }
 
 #[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct DesignFile {
     pub design_units: Vec<(Vec<Token>, AnyDesignUnit)>,
+    /// The VHDL revision the file was parsed according to
+    pub standard: VHDLStandard,
+    /// The revision of the source contents that the file was parsed from
+    pub source_revision: u64,
 }
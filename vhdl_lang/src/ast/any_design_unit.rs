@@ -76,6 +76,10 @@ impl UnitId {
         &self.library_name
     }
 
+    pub fn kind(&self) -> AnyKind {
+        self.kind
+    }
+
     /// For a secondary unit, returns the name of the associated primary unit;
     /// for a primary unit, returns its own name.
     pub fn primary_name(&self) -> &Symbol {
@@ -93,6 +97,20 @@ impl UnitId {
     }
 }
 
+impl std::fmt::Display for UnitId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.key {
+            UnitKey::Primary(ref name) => write!(f, "{}.{}", self.library_name, name),
+            UnitKey::Secondary(ref primary_name, ref name) => match self.kind {
+                AnyKind::Secondary(SecondaryKind::PackageBody) => {
+                    write!(f, "{}.{} body", self.library_name, primary_name)
+                }
+                _ => write!(f, "{}.{}({})", self.library_name, primary_name, name),
+            },
+        }
+    }
+}
+
 pub trait HasUnitId {
     fn unit_id(&self) -> &UnitId;
     fn kind(&self) -> AnyKind {
@@ -162,7 +180,7 @@ macro_rules! delegate_any {
 }
 
 impl AnyKind {
-    pub fn describe(&self) -> &str {
+    pub fn describe(&self) -> &'static str {
         match self {
             AnyKind::Primary(kind) => kind.describe(),
             AnyKind::Secondary(kind) => kind.describe(),
@@ -181,7 +199,7 @@ impl PrimaryKind {
         }
     }
 
-    pub fn describe(&self) -> &str {
+    pub fn describe(&self) -> &'static str {
         match self {
             PrimaryKind::Entity => "entity",
             PrimaryKind::Configuration => "configuration",
@@ -200,7 +218,7 @@ impl SecondaryKind {
         }
     }
 
-    pub fn describe(&self) -> &str {
+    pub fn describe(&self) -> &'static str {
         match self {
             SecondaryKind::Architecture => "architecture",
             SecondaryKind::PackageBody => "package body",
@@ -216,6 +234,23 @@ impl AnyDesignUnit {
             None
         }
     }
+
+    /// The context clause of the unit, a context declaration has none
+    pub fn context_clause(&self) -> Option<&ContextClause> {
+        match self {
+            AnyDesignUnit::Primary(primary) => match primary {
+                AnyPrimaryUnit::Entity(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::Configuration(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::Package(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::PackageInstance(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::Context(_) => None,
+            },
+            AnyDesignUnit::Secondary(secondary) => match secondary {
+                AnySecondaryUnit::Architecture(unit) => Some(&unit.context_clause),
+                AnySecondaryUnit::PackageBody(unit) => Some(&unit.context_clause),
+            },
+        }
+    }
 }
 
 /// Upper case first letter
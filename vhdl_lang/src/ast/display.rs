@@ -165,7 +165,7 @@ impl Display for EntityClass {
         match self {
             EntityClass::Entity => write!(f, "entity"),
             EntityClass::Architecture => write!(f, "architecture"),
-            EntityClass::Configuration => write!(f, "ponfiguration"),
+            EntityClass::Configuration => write!(f, "configuration"),
             EntityClass::Procedure => write!(f, "procedure"),
             EntityClass::Function => write!(f, "function"),
             EntityClass::Package => write!(f, "package"),
@@ -309,7 +309,17 @@ impl Display for AbstractLiteral {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
             AbstractLiteral::Integer(val) => write!(f, "{val}"),
-            AbstractLiteral::Real(val) => write!(f, "{val}"),
+            AbstractLiteral::Real(val) => {
+                // A real literal must contain a decimal point to not be parsed as an integer
+                let val = format!("{val:?}");
+                match val.find('e') {
+                    Some(idx) if !val.contains('.') => {
+                        write!(f, "{}.0{}", &val[..idx], &val[idx..])
+                    }
+                    None if !val.contains('.') => write!(f, "{val}.0"),
+                    _ => write!(f, "{val}"),
+                }
+            }
         }
     }
 }
@@ -332,7 +342,7 @@ impl Display for PhysicalLiteral {
 impl Display for Literal {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
-            Literal::String(ref val) => write!(f, "\"{val}\""),
+            Literal::String(ref val) => write!(f, "\"{}\"", val.to_string().replace('"', "\"\"")),
             Literal::BitString(ref val) => write!(f, "{val}"),
             Literal::Character(byte) => write!(f, "'{}'", *byte as char),
             Literal::AbstractLiteral(ref val) => write!(f, "{val}"),
@@ -360,6 +370,16 @@ impl Display for QualifiedExpression {
     }
 }
 
+/// Only the associative logical operators may be chained without parentheses,
+/// relational, shift and exponentiation operators can only be used once
+fn can_chain(op: &Operator, lhs_op: &Operator) -> bool {
+    match op {
+        Operator::And | Operator::Or | Operator::Xor | Operator::Xnor => op == lhs_op,
+        Operator::Nand | Operator::Nor => false,
+        op => !matches!(op.binary_precedence(), Some(2..=4) | Some(8)),
+    }
+}
+
 impl Display for Expression {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
@@ -367,8 +387,12 @@ impl Display for Expression {
                 // Add parentheses as necessary to satisfy order of precedence.
                 let precedence = op.item.item.binary_precedence().unwrap();
                 match &lhs.item {
-                    Expression::Binary(op, ..) => {
-                        if precedence <= op.item.item.binary_precedence().unwrap() {
+                    Expression::Binary(lhs_op, ..) => {
+                        let lhs_precedence = lhs_op.item.item.binary_precedence().unwrap();
+                        if precedence < lhs_precedence
+                            || (precedence == lhs_precedence
+                                && can_chain(&op.item.item, &lhs_op.item.item))
+                        {
                             write!(f, "{lhs}")?;
                         } else {
                             write!(f, "({lhs})")?;
@@ -753,6 +777,15 @@ impl Display for ObjectClass {
     }
 }
 
+impl Display for SignalKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SignalKind::Register => write!(f, "register"),
+            SignalKind::Bus => write!(f, "bus"),
+        }
+    }
+}
+
 impl Display for ObjectDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
@@ -760,6 +793,9 @@ impl Display for ObjectDeclaration {
             "{} {} : {}",
             self.class, self.ident, self.subtype_indication,
         )?;
+        if let Some(signal_kind) = self.signal_kind {
+            write!(f, " {signal_kind}")?;
+        }
         match self.expression {
             Some(ref expr) => write!(f, " := {expr};"),
             None => write!(f, ";"),
@@ -767,6 +803,37 @@ impl Display for ObjectDeclaration {
     }
 }
 
+impl Display for GuardedSignalList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            GuardedSignalList::Names(names) => {
+                let mut first = true;
+                for name in names {
+                    if first {
+                        write!(f, "{name}")?;
+                    } else {
+                        write!(f, ", {name}")?;
+                    }
+                    first = false;
+                }
+                Ok(())
+            }
+            GuardedSignalList::Others => write!(f, "others"),
+            GuardedSignalList::All => write!(f, "all"),
+        }
+    }
+}
+
+impl Display for DisconnectionSpecification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "disconnect {} : {} after {};",
+            self.signal_list, self.type_mark, self.after
+        )
+    }
+}
+
 impl Display for FileDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "file {} : {}", self.ident, self.subtype_indication)?;
@@ -827,7 +894,11 @@ impl Display for FunctionSpecification {
         if !first {
             write!(f, "\n)")?;
         }
-        write!(f, " return {}", self.return_type)
+        write!(f, " return ")?;
+        if let Some(ref ident) = self.return_identifier {
+            write!(f, "{ident} of ")?;
+        }
+        write!(f, "{}", self.return_type)
     }
 }
 
@@ -1168,6 +1239,7 @@ impl Display for PackageDeclaration {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::standard::VHDLStandard;
     use crate::syntax::test::Code;
     use assert_matches::assert_matches;
 
@@ -1378,6 +1450,16 @@ mod tests {
         assert_format("1 - -1", Code::expr);
     }
 
+    #[test]
+    fn test_expression_logical_and_relational_chains() {
+        assert_format("a and b and c", Code::expr);
+        assert_format("(a and b) or c", Code::expr);
+        assert_format("(a nand b) nand c", Code::expr);
+        assert_format("(a = b) = c", Code::expr);
+        assert_format("(a sll 1) sll 2", Code::expr);
+        assert_format("(2 ** 3) ** 4", Code::expr);
+    }
+
     #[test]
     fn test_expression_aggregate_positional() {
         assert_format("(1, 2)", Code::expr);
@@ -1460,6 +1542,13 @@ mod tests {
     #[test]
     fn test_expression_literal_real() {
         assert_format("12.3", Code::expr);
+        assert_format("1.0", Code::expr);
+        assert_format_eq("1.0e20", "1.0e20", Code::expr);
+    }
+
+    #[test]
+    fn test_expression_literal_string_with_quote() {
+        assert_format("\"a\"\"b\"", Code::expr);
     }
 
     #[test]
@@ -1797,6 +1886,11 @@ end units;",
         assert_format("constant foo : natural := 0;", Code::object_decl);
     }
 
+    #[test]
+    fn test_object_declaration_signal_kind() {
+        assert_format("signal foo : natural bus := 0;", Code::object_decl);
+    }
+
     #[test]
     fn test_file_declaration() {
         assert_format("file foo : text;", Code::file_decl);
@@ -1815,6 +1909,14 @@ end units;",
         );
     }
 
+    #[test]
+    fn test_disconnection_specification() {
+        assert_format(
+            "disconnect s1, s2 : bit after 10 ns;",
+            Code::disconnection_specification,
+        );
+    }
+
     #[test]
     fn test_alias_declaration() {
         assert_format("alias foo is name;", Code::alias_decl);
@@ -1853,6 +1955,16 @@ end units;",
         );
     }
 
+    #[test]
+    pub fn test_function_specification_with_return_identifier() {
+        let code = "function foo return r of bit_vector";
+        assert_format_eq(code, code, |code| {
+            code.clone()
+                .with_standard(VHDLStandard::V2019)
+                .subprogram_specification()
+        });
+    }
+
     #[test]
     pub fn test_function_specification_operator() {
         assert_format(
@@ -7,7 +7,9 @@
 
 use super::*;
 use crate::analysis::DesignRoot;
-use crate::named_entity::{EntRef, HasEntityId, Reference, Related};
+use crate::named_entity::{
+    AnyEntKind, Design, EntRef, EntityId, HasEntityId, Object, ObjectInterface, Reference, Related,
+};
 use crate::syntax::{HasTokenSpan, TokenAccess};
 
 #[must_use]
@@ -62,10 +64,13 @@ pub enum FoundDeclaration<'a> {
     Architecture(&'a ArchitectureBody),
     Context(&'a ContextDeclaration),
     ForIndex(&'a WithDecl<Ident>, &'a DiscreteRange),
+    ReturnIdentifier(&'a WithDecl<Ident>, &'a WithPos<TypeMark>),
     ForGenerateIndex(Option<&'a Ident>, &'a ForGenerateStatement),
     GenerateBody(&'a WithDecl<Ident>),
     ConcurrentStatement(&'a LabeledConcurrentStatement),
     SequentialStatement(&'a LabeledSequentialStatement),
+    PslProperty(&'a PslPropertyDeclaration),
+    PslSequence(&'a PslSequenceDeclaration),
 }
 
 pub trait Searcher {
@@ -102,6 +107,11 @@ pub trait Searcher {
     fn search_with_pos(&mut self, _ctx: &dyn TokenAccess, _pos: &SrcPos) -> SearchState {
         NotFinished
     }
+
+    /// Search the target of an assignment before the names within it are searched
+    fn search_target(&mut self, _ctx: &dyn TokenAccess, _target: &WithPos<Target>) -> SearchState {
+        NotFinished
+    }
 }
 
 pub trait Search {
@@ -258,6 +268,7 @@ impl Search for WithPos<Choice> {
 
 impl Search for WithPos<Target> {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_finished!(searcher.search_target(ctx, self));
         match self.item {
             Target::Name(ref name) => search_pos_name(&self.pos, name, searcher, ctx),
             Target::Aggregate(ref assocs) => assocs.search(ctx, searcher),
@@ -390,7 +401,7 @@ impl Search for LabeledSequentialStatement {
                 } = assign;
                 return_if_found!(target.search(ctx, searcher));
             }
-            SequentialStatement::Null => {}
+            SequentialStatement::Null | SequentialStatement::Error => {}
         }
 
         if let Some(end_label_pos) = self.statement.item.end_label_pos() {
@@ -546,6 +557,13 @@ impl Search for LabeledConcurrentStatement {
                 return_if_found!(report.search(ctx, searcher));
                 return_if_found!(severity.search(ctx, searcher));
             }
+            ConcurrentStatement::PslDirective(ref directive) => {
+                return_if_found!(directive.search(ctx, searcher));
+            }
+            ConcurrentStatement::PslDeclaration(ref decl) => {
+                return_if_found!(decl.search(ctx, searcher));
+            }
+            ConcurrentStatement::Error => {}
         };
 
         if let Some(end_label_pos) = self.statement.item.end_label_pos() {
@@ -663,18 +681,35 @@ impl Search for WithPos<SubtypeConstraint> {
 
 impl Search for SubtypeIndication {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
-        // @TODO more
         let SubtypeIndication {
+            resolution,
             type_mark,
             constraint,
-            ..
         } = self;
+        return_if_found!(resolution.search(ctx, searcher));
         return_if_found!(type_mark.search(ctx, searcher));
         return_if_found!(constraint.search(ctx, searcher));
         NotFound
     }
 }
 
+impl Search for ResolutionIndication {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            ResolutionIndication::FunctionName(name) | ResolutionIndication::ArrayElement(name) => {
+                name.search(ctx, searcher)
+            }
+            ResolutionIndication::Record(elements) => {
+                for element in elements.iter() {
+                    return_if_found!(element.resolution.search(ctx, searcher));
+                }
+                NotFound
+            }
+            ResolutionIndication::Unresolved => NotFound,
+        }
+    }
+}
+
 impl Search for WithPos<TypeMark> {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
         return_if_finished!(searcher.search_with_pos(ctx, &self.pos));
@@ -1069,10 +1104,152 @@ impl Search for Declaration {
                 return_if_found!(package_instance.search(ctx, searcher));
             }
 
-            Declaration::Configuration(_) => {
-                // @TODO
+            Declaration::Configuration(ref config) => {
+                return_if_found!(config.search(ctx, searcher));
+            }
+            Declaration::Disconnection(DisconnectionSpecification {
+                signal_list,
+                type_mark,
+                after,
+                span: _,
+            }) => {
+                if let GuardedSignalList::Names(names) = signal_list {
+                    return_if_found!(names.search(ctx, searcher));
+                }
+                return_if_found!(type_mark.search(ctx, searcher));
+                return_if_found!(after.search(ctx, searcher));
+            }
+            Declaration::Psl(ref decl) => {
+                return_if_found!(decl.search(ctx, searcher));
+            }
+            Declaration::Error(_) => {}
+        }
+        NotFound
+    }
+}
+
+impl Search for PslDirective {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        let PslDirective {
+            kind: _,
+            property,
+            report,
+            severity,
+        } = self;
+        return_if_found!(property.item.search(ctx, searcher));
+        return_if_found!(report.search(ctx, searcher));
+        severity.search(ctx, searcher)
+    }
+}
+
+impl Search for PslDeclaration {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            PslDeclaration::DefaultClock(default_clock) => {
+                default_clock.clock.search(ctx, searcher)
+            }
+            PslDeclaration::Property(decl) => {
+                return_if_found!(searcher
+                    .search_decl(ctx, FoundDeclaration::PslProperty(decl))
+                    .or_not_found());
+                decl.property.item.search(ctx, searcher)
+            }
+            PslDeclaration::Sequence(decl) => {
+                return_if_found!(searcher
+                    .search_decl(ctx, FoundDeclaration::PslSequence(decl))
+                    .or_not_found());
+                decl.sequence.item.search(ctx, searcher)
+            }
+        }
+    }
+}
+
+impl Search for PslProperty {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            PslProperty::Boolean(expr) => expr.search(ctx, searcher),
+            PslProperty::Sequence { sequence, .. } => sequence.item.search(ctx, searcher),
+            PslProperty::Unary(_, operand) => operand.item.search(ctx, searcher),
+            PslProperty::Binary(_, lhs, rhs) => {
+                return_if_found!(lhs.item.search(ctx, searcher));
+                rhs.item.search(ctx, searcher)
+            }
+            PslProperty::SuffixImplication {
+                sequence, property, ..
+            } => {
+                return_if_found!(sequence.item.search(ctx, searcher));
+                property.item.search(ctx, searcher)
+            }
+            PslProperty::Abort(property, expr) | PslProperty::Clocked(property, expr) => {
+                return_if_found!(property.item.search(ctx, searcher));
+                expr.search(ctx, searcher)
+            }
+        }
+    }
+}
+
+impl Search for PslSequence {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            PslSequence::Boolean(expr) => expr.search(ctx, searcher),
+            PslSequence::Binary(_, lhs, rhs) => {
+                return_if_found!(lhs.item.search(ctx, searcher));
+                rhs.item.search(ctx, searcher)
+            }
+            PslSequence::Repeated(sequence, repetition) => {
+                if let Some(sequence) = sequence {
+                    return_if_found!(sequence.item.search(ctx, searcher));
+                }
+                if let Some(PslCount { low, high }) = &repetition.count {
+                    return_if_found!(low.search(ctx, searcher));
+                    if let Some(PslBound::Expression(high)) = high {
+                        return_if_found!(high.search(ctx, searcher));
+                    }
+                }
+                NotFound
+            }
+        }
+    }
+}
+
+impl Search for ConfigurationSpecification {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_found!(self.spec.search(ctx, searcher));
+        self.bind_ind.search(ctx, searcher)
+    }
+}
+
+impl Search for ComponentSpecification {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        if let InstantiationList::Labels(ref labels) = self.instantiation_list {
+            for label in labels.iter() {
+                return_if_found!(searcher.search_ident_ref(ctx, label).or_not_found());
             }
         }
+        self.component_name.search(ctx, searcher)
+    }
+}
+
+impl Search for BindingIndication {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match self.entity_aspect {
+            Some(EntityAspect::Entity(ref ent_name, ref architecture_name)) => {
+                return_if_found!(ent_name.search(ctx, searcher));
+                if let Some(ref architecture_name) = architecture_name {
+                    return_if_found!(searcher
+                        .search_pos_with_ref(
+                            ctx,
+                            &architecture_name.item.pos,
+                            &architecture_name.reference
+                        )
+                        .or_not_found());
+                }
+            }
+            Some(EntityAspect::Configuration(ref config_name)) => {
+                return_if_found!(config_name.search(ctx, searcher));
+            }
+            Some(EntityAspect::Open) | None => {}
+        }
         NotFound
     }
 }
@@ -1153,6 +1330,14 @@ fn search_subpgm_inner(
         SubprogramSpecification::Function(ref decl) => {
             return_if_found!(decl.header.search(ctx, searcher));
             return_if_found!(decl.parameter_list.search(ctx, searcher));
+            if let Some(ref ident) = decl.return_identifier {
+                return_if_found!(searcher
+                    .search_decl(
+                        ctx,
+                        FoundDeclaration::ReturnIdentifier(ident, &decl.return_type)
+                    )
+                    .or_not_found());
+            }
             decl.return_type.search(ctx, searcher)
         }
         SubprogramSpecification::Procedure(ref decl) => {
@@ -1281,7 +1466,27 @@ impl Search for ConfigurationDeclaration {
         return_if_found!(searcher
             .search_decl(ctx, FoundDeclaration::Configuration(self))
             .or_not_found());
-        self.entity_name.search(ctx, searcher)
+        return_if_found!(self.entity_name.search(ctx, searcher));
+        self.block_config.search(ctx, searcher)
+    }
+}
+
+impl Search for BlockConfiguration {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_found!(self.block_spec.search(ctx, searcher));
+        for item in self.items.iter() {
+            match item {
+                ConfigurationItem::Block(block_config) => {
+                    return_if_found!(block_config.search(ctx, searcher));
+                }
+                ConfigurationItem::Component(component_config) => {
+                    return_if_found!(component_config.spec.search(ctx, searcher));
+                    return_if_found!(component_config.bind_ind.search(ctx, searcher));
+                    return_if_found!(component_config.block_config.search(ctx, searcher));
+                }
+            }
+        }
+        NotFound
     }
 }
 
@@ -1374,6 +1579,11 @@ impl<'a> Searcher for ItemAtCursor<'a> {
         if let Some(id) = decl.ent_id() {
             let ent = self.root.get_ent(id);
 
+            // The position of an anonymous label is the whole statement
+            if ent.is_anonymous() {
+                return NotFinished;
+            }
+
             if let Some(decl_pos) = ent.decl_pos() {
                 if let Finished(res) = self.search_decl_pos(decl_pos, ent) {
                     return Finished(res);
@@ -1466,6 +1676,34 @@ impl<'a, T: FnMut(EntRef<'a>) -> bool> Searcher for FindAllEnt<'a, T> {
     }
 }
 
+// Find the components of all component instantiations
+#[derive(Default)]
+pub struct FindComponentInstances {
+    pub result: Vec<EntityId>,
+}
+
+impl Searcher for FindComponentInstances {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if let FoundDeclaration::ConcurrentStatement(stmt) = decl {
+            match stmt.statement.item {
+                ConcurrentStatement::Instance(ref instance) => {
+                    if let InstantiatedUnit::Component(ref name) = instance.unit {
+                        self.result.extend(name.item.get_suffix_reference());
+                    }
+                }
+                // An instantiation without generic or port map is parsed as a procedure call
+                ConcurrentStatement::ProcedureCall(ref pcall) => {
+                    self.result
+                        .extend(pcall.call.item.name.item.get_suffix_reference());
+                }
+                _ => {}
+            }
+        }
+
+        SearchState::NotFinished
+    }
+}
+
 // Search for a declaration/definition and format it
 pub struct FormatDeclaration<'a> {
     ent: EntRef<'a>,
@@ -1503,11 +1741,195 @@ impl<'a> Searcher for FormatDeclaration<'a> {
     }
 }
 
+/// The declaration of an entity as written in the source such as `signal clk : std_logic;`.
+/// Declarations without a span of their own, such as interface objects, and declarations
+/// that span a whole body, such as design units and subprograms, are not included.
+pub struct DeclarationText {
+    id: EntityId,
+    pub result: Option<String>,
+}
+
+impl DeclarationText {
+    pub fn new(id: EntityId) -> DeclarationText {
+        DeclarationText { id, result: None }
+    }
+}
+
+impl Searcher for DeclarationText {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if decl.ent_id() != Some(self.id) {
+            return NotFinished;
+        }
+        let span = match decl {
+            FoundDeclaration::Object(decl) => decl.get_span(ctx),
+            FoundDeclaration::File(decl) => decl.get_span(ctx),
+            FoundDeclaration::Type(decl) => decl.get_span(ctx),
+            FoundDeclaration::Component(decl) => decl.get_span(ctx),
+            FoundDeclaration::Attribute(decl) => decl.get_span(ctx),
+            FoundDeclaration::Alias(decl) => decl.get_span(ctx),
+            FoundDeclaration::SubprogramInstantiation(decl) => decl.get_span(ctx),
+            FoundDeclaration::PackageInstance(decl) => decl.get_span(ctx),
+            _ => return Finished(NotFound),
+        };
+        self.result = Some(span.text());
+        Finished(Found)
+    }
+}
+
+/// The index constraint of an array type or subtype declaration as written in the source
+pub struct FindIndexConstraint {
+    id: EntityId,
+    /// None if the declaration was not found, Some(None) if it has no index constraint
+    pub result: Option<Option<String>>,
+    /// True if the declaration is of an array type with an unbounded index such as `natural range <>`
+    pub is_unbounded: bool,
+}
+
+impl FindIndexConstraint {
+    pub fn new(id: EntityId) -> FindIndexConstraint {
+        FindIndexConstraint {
+            id,
+            result: None,
+            is_unbounded: false,
+        }
+    }
+}
+
+impl Searcher for FindIndexConstraint {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        let FoundDeclaration::Type(type_decl) = decl else {
+            return NotFinished;
+        };
+        if type_decl.ident.decl.get() != Some(self.id) {
+            return NotFinished;
+        }
+        if let TypeDefinition::Array(ref indexes, _) = type_decl.def {
+            self.is_unbounded = indexes
+                .iter()
+                .any(|index| matches!(index, ArrayIndex::IndexSubtypeDefintion(..)));
+        }
+        self.result = Some(match type_decl.def {
+            TypeDefinition::Array(ref indexes, _) => Some(format!(
+                "({})",
+                indexes
+                    .iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            TypeDefinition::Subtype(ref subtype) => match subtype.constraint {
+                Some(
+                    ref constraint @ WithPos {
+                        item: SubtypeConstraint::Array(..),
+                        ..
+                    },
+                ) => Some(constraint.to_string()),
+                _ => None,
+            },
+            _ => None,
+        });
+        Finished(Found)
+    }
+}
+
+/// How a declaration is referenced at a position found by find all references
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ReferenceKind {
+    /// The designator of the declaration itself
+    Declaration,
+    /// A name that refers to the declaration without assigning it, such as a read
+    Use,
+    /// A name that is assigned as, or as a part of, the target of an assignment,
+    /// or that is associated with an `out` or `buffer` port of an instance
+    Write,
+    /// A name that is both read and written, such as the actual of an `inout` port of an instance
+    ReadWrite,
+    /// The label or designator that repeats the declaration at its end, such as `end entity foo;`
+    EndLabel,
+}
+
 // Search for all references to declaration/definition
 pub struct FindAllReferences<'a> {
     root: &'a DesignRoot,
     ent: EntRef<'a>,
-    pub references: Vec<SrcPos>,
+    /// The positions of the references within assignment targets and port map actuals
+    /// that are written
+    writes: Vec<(SrcPos, ReferenceKind)>,
+    pub references: Vec<(SrcPos, ReferenceKind)>,
+}
+
+/// The designators that are written by an assignment to the name,
+/// the indexes and slice ranges within the name are read
+fn assigned_designators<'a>(
+    pos: &'a SrcPos,
+    name: &'a Name,
+    result: &mut Vec<(&'a SrcPos, &'a Reference)>,
+) {
+    match name {
+        Name::Designator(ref designator) => result.push((pos, &designator.reference)),
+        Name::Selected(ref prefix, ref designator) => {
+            result.push((&designator.pos, &designator.item.reference));
+            assigned_designators(&prefix.pos, &prefix.item, result);
+        }
+        Name::Slice(ref prefix, _) => assigned_designators(&prefix.pos, &prefix.item, result),
+        Name::CallOrIndexed(ref fcall) => {
+            assigned_designators(&fcall.name.pos, &fcall.name.item, result)
+        }
+        Name::SelectedAll(_) | Name::Attribute(_) | Name::External(_) => {}
+    }
+}
+
+/// The designators of the actuals in the port map of an instance that are written by the
+/// instance together with how they are referenced, such as the actual of an `out` port
+fn port_map_designators<'a>(
+    root: &DesignRoot,
+    inst: &'a InstantiationStatement,
+    result: &mut Vec<(&'a SrcPos, &'a Reference, ReferenceKind)>,
+) {
+    let Some(ref port_map) = inst.port_map else {
+        return;
+    };
+    let Some(id) = inst.entity_reference() else {
+        return;
+    };
+    let region = match root.get_ent(id).kind() {
+        AnyEntKind::Component(region) => region,
+        AnyEntKind::Design(Design::Entity(_, region)) => region,
+        _ => return,
+    };
+    let (ports, _) = region.ports_and_generics();
+
+    for (idx, elem) in port_map.list.items.iter().enumerate() {
+        let port = if let Some(ref formal) = elem.formal {
+            formal
+                .item
+                .get_formal_reference()
+                .map(|id| root.get_ent(id))
+        } else {
+            ports.get(idx).map(|port| port.inner())
+        };
+        let kind = match port.map(|port| port.kind()) {
+            Some(AnyEntKind::Object(Object {
+                iface: Some(ObjectInterface::Port(mode)),
+                ..
+            })) => match mode {
+                Mode::Out | Mode::Buffer => ReferenceKind::Write,
+                Mode::InOut => ReferenceKind::ReadWrite,
+                Mode::In | Mode::Linkage => continue,
+            },
+            _ => continue,
+        };
+
+        if let ActualPart::Expression(Expression::Name(ref name)) = elem.actual.item {
+            let mut designators = Vec::new();
+            assigned_designators(&elem.actual.pos, name, &mut designators);
+            result.extend(
+                designators
+                    .into_iter()
+                    .map(|(pos, reference)| (pos, reference, kind)),
+            );
+        }
+    }
 }
 
 fn is_instance_of(ent: EntRef, other: EntRef) -> bool {
@@ -1543,7 +1965,7 @@ fn is_implicit_of(ent: EntRef, id: EntityId) -> bool {
     }
 }
 
-fn is_reference(ent: EntRef, other: EntRef) -> bool {
+pub(crate) fn is_reference(ent: EntRef, other: EntRef) -> bool {
     if ent.id() == other.id() {
         return true;
     }
@@ -1563,9 +1985,21 @@ impl<'a> FindAllReferences<'a> {
         FindAllReferences {
             root,
             ent,
+            writes: Vec::new(),
             references: Vec::new(),
         }
     }
+
+    /// Only the references to the searched declaration are kept
+    fn add_writes(&mut self, designators: Vec<(&SrcPos, &Reference, ReferenceKind)>) {
+        for (pos, reference, kind) in designators {
+            if let Some(id) = reference.get() {
+                if is_reference(self.ent, self.root.get_ent(id)) {
+                    self.writes.push((pos.clone(), kind));
+                }
+            }
+        }
+    }
 }
 
 impl<'a> Searcher for FindAllReferences<'a> {
@@ -1575,13 +2009,22 @@ impl<'a> Searcher for FindAllReferences<'a> {
 
             if is_reference(self.ent, other) {
                 if let Some(decl_pos) = other.decl_pos() {
-                    self.references.push(decl_pos.clone());
+                    self.references
+                        .push((decl_pos.clone(), ReferenceKind::Declaration));
                 }
                 if let Some(pos) = decl.end_ident_pos() {
-                    self.references.push(pos.clone());
+                    self.references.push((pos.clone(), ReferenceKind::EndLabel));
                 }
             }
         }
+
+        if let FoundDeclaration::ConcurrentStatement(stmt) = decl {
+            if let ConcurrentStatement::Instance(ref inst) = stmt.statement.item {
+                let mut designators = Vec::new();
+                port_map_designators(self.root, inst, &mut designators);
+                self.add_writes(designators);
+            }
+        }
         NotFinished
     }
 
@@ -1594,18 +2037,49 @@ impl<'a> Searcher for FindAllReferences<'a> {
         if let Some(id) = reference.get() {
             let other = self.root.get_ent(id);
             if is_reference(self.ent, other) {
-                self.references.push(pos.clone());
+                let kind = self
+                    .writes
+                    .iter()
+                    .find(|(write_pos, _)| write_pos == pos)
+                    .map(|(_, kind)| *kind)
+                    .unwrap_or(ReferenceKind::Use);
+                self.references.push((pos.clone(), kind));
             }
         };
         NotFinished
     }
+
+    fn search_target(&mut self, _ctx: &dyn TokenAccess, target: &WithPos<Target>) -> SearchState {
+        let mut designators = Vec::new();
+        match target.item {
+            Target::Name(ref name) => assigned_designators(&target.pos, name, &mut designators),
+            Target::Aggregate(ref assocs) => {
+                for assoc in assocs.iter() {
+                    let (ElementAssociation::Positional(ref expr)
+                    | ElementAssociation::Named(_, ref expr)) = assoc;
+                    if let Expression::Name(ref name) = expr.item {
+                        assigned_designators(&expr.pos, name, &mut designators);
+                    }
+                }
+            }
+        }
+
+        self.add_writes(
+            designators
+                .into_iter()
+                .map(|(pos, reference)| (pos, reference, ReferenceKind::Write))
+                .collect(),
+        );
+        NotFinished
+    }
 }
 
 impl<'a> FoundDeclaration<'a> {
-    fn end_ident_pos(&self) -> Option<&SrcPos> {
+    pub(crate) fn end_ident_pos(&self) -> Option<&SrcPos> {
         match self {
             FoundDeclaration::InterfaceObject(_) => None,
             FoundDeclaration::ForIndex(..) => None,
+            FoundDeclaration::ReturnIdentifier(..) => None,
             FoundDeclaration::ForGenerateIndex(..) => None,
             FoundDeclaration::Subprogram(value) => value.end_ident_pos.as_ref(),
             FoundDeclaration::SubprogramDecl(..) => None,
@@ -1633,6 +2107,8 @@ impl<'a> FoundDeclaration<'a> {
             FoundDeclaration::ConcurrentStatement(..) => None,
             FoundDeclaration::SequentialStatement(..) => None,
             FoundDeclaration::SubprogramInstantiation(_) => None,
+            FoundDeclaration::PslProperty(_) => None,
+            FoundDeclaration::PslSequence(_) => None,
         }
     }
 
@@ -1640,6 +2116,7 @@ impl<'a> FoundDeclaration<'a> {
         match self {
             FoundDeclaration::InterfaceObject(value) => &value.ident.decl,
             FoundDeclaration::ForIndex(ident, _) => &ident.decl,
+            FoundDeclaration::ReturnIdentifier(ident, _) => &ident.decl,
             FoundDeclaration::ForGenerateIndex(_, value) => &value.index_name.decl,
             FoundDeclaration::Subprogram(value) => value.specification.ent_id_ref(),
             FoundDeclaration::SubprogramDecl(value) => value.ent_id_ref(),
@@ -1667,6 +2144,8 @@ impl<'a> FoundDeclaration<'a> {
             FoundDeclaration::GenerateBody(value) => &value.decl,
             FoundDeclaration::ConcurrentStatement(value) => &value.label.decl,
             FoundDeclaration::SequentialStatement(value) => &value.label.decl,
+            FoundDeclaration::PslProperty(value) => &value.ident.decl,
+            FoundDeclaration::PslSequence(value) => &value.ident.decl,
         }
     }
 }
@@ -1697,6 +2176,9 @@ impl std::fmt::Display for FoundDeclaration<'_> {
             FoundDeclaration::ForIndex(ref ident, ref drange) => {
                 write!(f, "for {ident} in {drange} loop")
             }
+            FoundDeclaration::ReturnIdentifier(ref ident, ref type_mark) => {
+                write!(f, "return {ident} of {type_mark}")
+            }
             FoundDeclaration::ForGenerateIndex(ref ident, ref value) => match ident {
                 Some(ident) => write!(f, "{ident}: {value}"),
                 None => write!(f, "{value}"),
@@ -1788,6 +2270,12 @@ impl std::fmt::Display for FoundDeclaration<'_> {
                     write!(f, "<anonymous statement>")
                 }
             }
+            FoundDeclaration::PslProperty(value) => {
+                write!(f, "property {}", value.ident.tree)
+            }
+            FoundDeclaration::PslSequence(value) => {
+                write!(f, "sequence {}", value.ident.tree)
+            }
         }
     }
 }
@@ -327,6 +327,19 @@ impl Name {
         }
     }
 
+    /// The reference of the port or parameter denoted by a formal part,
+    /// which may select an element or slice of it such as `port(0)`
+    pub fn get_formal_reference(&self) -> Option<EntityId> {
+        match self {
+            Name::Designator(designator) => designator.reference.get(),
+            Name::Selected(prefix, _) | Name::Slice(prefix, _) => {
+                prefix.item.get_formal_reference()
+            }
+            Name::CallOrIndexed(call) => call.name.item.get_formal_reference(),
+            _ => None,
+        }
+    }
+
     pub fn prefix(&self) -> Option<&Designator> {
         match self {
             Self::Attribute(attr) => attr.name.item.prefix(),
@@ -478,6 +491,8 @@ impl ConcurrentStatement {
             Assignment(_) => None,
             Instance(_) => Some(Concurrent::Instance),
             ForGenerate(_) | IfGenerate(_) | CaseGenerate(_) => Some(Concurrent::Generate),
+            PslDirective(_) | PslDeclaration(_) => None,
+            Error => None,
         }
     }
 
@@ -494,6 +509,8 @@ impl ConcurrentStatement {
             ForGenerate(value) => value.end_label_pos.as_ref(),
             IfGenerate(value) => value.end_label_pos.as_ref(),
             CaseGenerate(value) => value.end_label_pos.as_ref(),
+            PslDirective(_) | PslDeclaration(_) => None,
+            Error => None,
         }
     }
 
@@ -521,6 +538,7 @@ impl SequentialStatement {
             Exit(_) => None,
             Return(_) => None,
             Null => None,
+            Error => None,
         }
     }
 
@@ -542,6 +560,7 @@ impl SequentialStatement {
             Exit(_) => None,
             Return(_) => None,
             Null => None,
+            Error => None,
         }
     }
 
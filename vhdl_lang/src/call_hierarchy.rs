@@ -0,0 +1,343 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! The calls of subprograms from subprogram bodies, processes and concurrent procedure calls
+//!
+//! A call is a reference to a subprogram by a function call, a procedure call statement or
+//! an operator that resolves to a function declared by the user. The reference is set by
+//! overload resolution so calls that could not be resolved are not included. The calls
+//! within a subprogram body nested in the declarative part of a subprogram or process
+//! belong to the nested subprogram.
+//!
+//! The calls are found when a unit is analyzed and kept with its analysis result. The caller
+//! of a concurrent procedure call is its label, or the architecture, block or generate
+//! statement that contains it when it has no label.
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{is_reference, NotFinished, Search, SearchState, Searcher};
+use crate::ast::Declaration;
+use crate::data::{HasSource, SrcPos};
+use crate::named_entity::{AnyEntKind, Arena, EntRef, Overloaded, Reference};
+use crate::syntax::TokenAccess;
+
+/// A call of `callee` at `pos` from the subprogram, process or concurrent procedure call
+/// `caller`
+struct Call<'a> {
+    pos: SrcPos,
+    caller: EntRef<'a>,
+    callee: EntRef<'a>,
+}
+
+/// The subprograms referenced within a single subprogram body, process or concurrent
+/// procedure call
+struct CalleeSearcher<'a> {
+    arena: &'a Arena,
+    callees: Vec<(SrcPos, EntRef<'a>)>,
+}
+
+impl<'a> Searcher for CalleeSearcher<'a> {
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        let Some(id) = reference.get() else {
+            return NotFinished;
+        };
+        // An alias denotes the subprogram it is an alias of, within a package body a call
+        // may refer to the body of a subprogram that is declared in the package
+        let ent = self.arena.get(id).as_actual().declaration();
+        let is_subprogram = matches!(
+            ent.kind(),
+            AnyEntKind::Overloaded(
+                Overloaded::SubprogramDecl(..)
+                    | Overloaded::Subprogram(..)
+                    | Overloaded::UninstSubprogramDecl(..)
+                    | Overloaded::UninstSubprogram(..)
+                    | Overloaded::InterfaceSubprogram(..)
+            )
+        );
+        // Implicit operators such as the `=` of a type are not declared by the user
+        if is_subprogram && !ent.is_implicit() {
+            self.callees.push((pos.clone(), ent));
+        }
+        NotFinished
+    }
+}
+
+/// The positions of the calls within the declarations and statements of a caller that has
+/// just been analyzed and the subprograms they call, nested subprogram bodies are skipped
+pub(crate) fn find_callees<'a>(
+    arena: &'a Arena,
+    ctx: &dyn TokenAccess,
+    declarations: &[Declaration],
+    statements: &impl Search,
+) -> Vec<(SrcPos, EntRef<'a>)> {
+    let mut searcher = CalleeSearcher {
+        arena,
+        callees: Vec::new(),
+    };
+    for decl in declarations.iter() {
+        if !matches!(decl, Declaration::SubprogramBody(..)) {
+            let _ = decl.search(ctx, &mut searcher);
+        }
+    }
+    let _ = statements.search(ctx, &mut searcher);
+    searcher.callees
+}
+
+impl DesignRoot {
+    /// The calls of all analyzed units as the position of the call, the caller and the callee
+    fn calls(&self) -> Vec<Call<'_>> {
+        let mut calls = Vec::new();
+        for unit in self.libraries().flat_map(|library| library.units()) {
+            let data = unit.unit.expect_analyzed();
+            calls.extend(
+                data.result()
+                    .calls
+                    .iter()
+                    .map(|(pos, caller, callee)| Call {
+                        pos: pos.clone(),
+                        caller: self.get_ent(*caller),
+                        callee: self.get_ent(*callee),
+                    }),
+            );
+        }
+        calls
+    }
+
+    /// The calls of the subprogram declared at `decl` as pairs of the position of the call
+    /// and the declaration of the calling subprogram or process. The declaration of an
+    /// unlabeled process is the whole process statement, the process is then named by its
+    /// synthesized designator such as `<anonymous 0>`.
+    pub fn incoming_calls(&self, decl: &SrcPos) -> Vec<(SrcPos, SrcPos)> {
+        let Some(ent) = self.search_reference(decl.source(), decl.start()) else {
+            return Vec::new();
+        };
+        self.calls()
+            .into_iter()
+            .filter(|call| is_reference(ent, call.callee))
+            .filter_map(|call| Some((call.pos, call.caller.decl_pos()?.clone())))
+            .collect()
+    }
+
+    /// The calls within the body of the subprogram or the process declared at `decl` as pairs
+    /// of the position of the call and the declaration of the called subprogram. A subprogram
+    /// with a separate declaration, such as in a package, is represented by that declaration.
+    /// An unlabeled process is given by the position of the whole statement as returned by
+    /// [`incoming_calls`](Self::incoming_calls).
+    pub fn outgoing_calls(&self, decl: &SrcPos) -> Vec<(SrcPos, SrcPos)> {
+        let ent = self.search_reference(decl.source(), decl.start());
+        self.calls()
+            .into_iter()
+            .filter(|call| {
+                ent.is_some_and(|ent| is_reference(ent, call.caller))
+                    || call.caller.decl_pos() == Some(decl)
+            })
+            .filter_map(|call| Some((call.pos, call.callee.decl_pos()?.clone())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::tests::{check_diagnostics, LibraryBuilder};
+    use crate::data::{Diagnostic, SrcPos};
+    use pretty_assertions::assert_eq;
+
+    /// The calls ordered by their position, the order of the sources is arbitrary
+    fn sorted(mut calls: Vec<(SrcPos, SrcPos)>) -> Vec<(SrcPos, SrcPos)> {
+        calls.sort_by_key(|(pos, _)| (pos.source.file_name().to_owned(), pos.start()));
+        calls
+    }
+
+    #[test]
+    fn incoming_and_outgoing_calls_of_package_function() {
+        let mut builder = LibraryBuilder::new();
+        let pkg = builder.code(
+            "libname",
+            "
+package pkg is
+  type word_t is record
+    value : natural;
+  end record;
+  function \"+\"(left, right : word_t) return word_t;
+  function inc(value : natural) return natural;
+  function twice(value : natural) return natural;
+  procedure log(value : natural);
+end package;
+
+package body pkg is
+  function \"+\"(left, right : word_t) return word_t is
+  begin
+    return (value => inc(left.value) + right.value);
+  end function;
+
+  function inc(value : natural) return natural is
+  begin
+    return value + 1;
+  end function;
+
+  function twice(value : natural) return natural is
+    function nested return natural is
+    begin
+      return inc(0);
+    end function;
+  begin
+    return inc(inc(value)) + nested + missing(value);
+  end function;
+
+  procedure log(value : natural) is
+  begin
+  end procedure;
+end package body;
+",
+        );
+        let arch1 = builder.code(
+            "libname",
+            "
+use work.pkg.all;
+
+entity ent1 is
+end entity;
+
+architecture a of ent1 is
+  signal word : word_t;
+begin
+  counter: process (word)
+    variable count : natural := 0;
+  begin
+    count := inc(count);
+    word <= word + word;
+  end process;
+end architecture;
+",
+        );
+        let arch2 = builder.code(
+            "libname",
+            "
+use work.pkg.all;
+
+entity ent2 is
+end entity;
+
+architecture a of ent2 is
+begin
+  process
+  begin
+    log(inc(1));
+    wait;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::error(
+                pkg.s1("missing"),
+                "No declaration of 'missing'",
+            )],
+        );
+
+        let inc = pkg.s1("inc").pos();
+        let add = pkg.s1("\"+\"").pos();
+        let twice = pkg.s("twice", 2).pos();
+        let nested = pkg.s1("nested").pos();
+        let counter = arch1.s1("counter").pos();
+        let process = arch2
+            .s1("process\n  begin\n    log(inc(1));\n    wait;\n  end process;")
+            .pos();
+
+        assert_eq!(
+            sorted(root.incoming_calls(&inc)),
+            sorted(vec![
+                (pkg.s("inc", 2).pos(), pkg.s("\"+\"", 2).pos()),
+                (pkg.s("inc", 4).pos(), nested.clone()),
+                (pkg.s("inc", 5).pos(), twice.clone()),
+                (pkg.s("inc", 6).pos(), twice.clone()),
+                (arch1.sa("count := ", "inc").pos(), counter.clone()),
+                (arch2.s1("inc").pos(), process.clone()),
+            ])
+        );
+        // A call of an operator declared by the user is a call of the operator function
+        assert_eq!(
+            root.incoming_calls(&add),
+            vec![(arch1.s1("word + word").s1("+").pos(), counter.clone())]
+        );
+        assert_eq!(root.incoming_calls(&pkg.s1("twice").pos()), vec![]);
+
+        // The calls within the nested function and the unresolved call are not included
+        let twice_calls = vec![
+            (pkg.s("inc", 5).pos(), inc.clone()),
+            (pkg.s("inc", 6).pos(), inc.clone()),
+            (pkg.s("nested", 2).pos(), nested.clone()),
+        ];
+        assert_eq!(sorted(root.outgoing_calls(&twice)), twice_calls);
+        assert_eq!(
+            sorted(root.outgoing_calls(&pkg.s1("twice").pos())),
+            twice_calls
+        );
+        assert_eq!(
+            sorted(root.outgoing_calls(&counter)),
+            vec![
+                (arch1.sa("count := ", "inc").pos(), inc.clone()),
+                (arch1.s1("word + word").s1("+").pos(), add),
+            ]
+        );
+        assert_eq!(
+            sorted(root.outgoing_calls(&process)),
+            vec![
+                (arch2.s1("log").pos(), pkg.s1("log").pos()),
+                (arch2.s1("inc").pos(), inc),
+            ]
+        );
+    }
+
+    #[test]
+    fn calls_of_concurrent_procedure_calls() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  procedure log(value : natural) is
+  begin
+  end procedure;
+begin
+  log(1);
+  logger: log(2);
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_diagnostics(diagnostics, vec![]);
+
+        let log = code.s1("log").pos();
+        let arch = code.sa("architecture ", "a").pos();
+        let logger = code.s1("logger").pos();
+        // An unlabeled call belongs to the architecture that contains it
+        assert_eq!(
+            sorted(root.incoming_calls(&log)),
+            vec![
+                (code.s("log", 2).pos(), arch.clone()),
+                (code.s("log", 4).pos(), logger.clone()),
+            ]
+        );
+        assert_eq!(
+            root.outgoing_calls(&arch),
+            vec![(code.s("log", 2).pos(), log.clone())]
+        );
+        assert_eq!(
+            root.outgoing_calls(&logger),
+            vec![(code.s("log", 4).pos(), log)]
+        );
+    }
+}
@@ -0,0 +1,393 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! C interface to parse files and analyze projects, enabled by the `capi` feature.
+//!
+//! A shared library is built with `cargo rustc -p vhdl_lang --features capi --crate-type cdylib`
+//! and the declarations are found in `include/vhdl_lang.h`.
+//!
+//! * Every function returns `VHDL_OK` or one of the `VHDL_ERROR_*` codes,
+//!   results are written through out pointers.
+//! * A panic is caught at each function and returned as `VHDL_ERROR_PANIC`.
+//! * All strings are null terminated UTF-8. Any null character within a path or message
+//!   is replaced by a space.
+//! * The strings of a diagnostic remain valid until the handle is freed with `vhdl_free`.
+
+use crate::data::*;
+use crate::{Config, Project, VHDLParser};
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+pub const VHDL_OK: c_int = 0;
+/// A pointer argument was null
+pub const VHDL_ERROR_NULL_POINTER: c_int = 1;
+/// A string argument was not valid UTF-8
+pub const VHDL_ERROR_INVALID_UTF8: c_int = 2;
+/// A file could not be read
+pub const VHDL_ERROR_IO: c_int = 3;
+/// The index of a diagnostic was not less than the number of diagnostics
+pub const VHDL_ERROR_OUT_OF_RANGE: c_int = 4;
+/// The configuration file could not be read or contains an error
+pub const VHDL_ERROR_CONFIG: c_int = 5;
+/// The library panicked, the out arguments are not written
+pub const VHDL_ERROR_PANIC: c_int = 6;
+
+pub const VHDL_SEVERITY_HINT: c_int = 0;
+pub const VHDL_SEVERITY_INFO: c_int = 1;
+pub const VHDL_SEVERITY_WARNING: c_int = 2;
+pub const VHDL_SEVERITY_ERROR: c_int = 3;
+
+/// A diagnostic as seen from C, the line and column are one-based
+#[repr(C)]
+#[derive(Debug)]
+pub struct VhdlDiagnostic {
+    pub file: *const c_char,
+    pub line: u32,
+    pub column: u32,
+    pub severity: c_int,
+    pub message: *const c_char,
+}
+
+struct OwnedDiagnostic {
+    file: CString,
+    line: u32,
+    column: u32,
+    severity: c_int,
+    message: CString,
+}
+
+/// The result of parsing a file or analyzing a project, opaque to C
+pub struct VhdlHandle {
+    diagnostics: Vec<OwnedDiagnostic>,
+}
+
+fn c_string(string: &str) -> CString {
+    CString::new(string.replace('\0', " ")).unwrap()
+}
+
+fn severity_code(severity: Severity) -> c_int {
+    match severity {
+        Severity::Hint => VHDL_SEVERITY_HINT,
+        Severity::Info => VHDL_SEVERITY_INFO,
+        Severity::Warning => VHDL_SEVERITY_WARNING,
+        Severity::Error => VHDL_SEVERITY_ERROR,
+    }
+}
+
+impl VhdlHandle {
+    fn new(diagnostics: Vec<Diagnostic>) -> VhdlHandle {
+        VhdlHandle {
+            diagnostics: diagnostics
+                .into_iter()
+                .map(|diagnostic| {
+                    let start = diagnostic.pos.start();
+                    OwnedDiagnostic {
+                        file: c_string(&diagnostic.pos.file_name().to_string_lossy()),
+                        line: start.line + 1,
+                        column: start.character + 1,
+                        severity: severity_code(diagnostic.severity),
+                        message: c_string(&diagnostic.message),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Run `f` with the path behind a C string and write the resulting handle to `out_handle`
+unsafe fn with_path(
+    path: *const c_char,
+    out_handle: *mut *mut VhdlHandle,
+    f: impl FnOnce(&Path) -> Result<VhdlHandle, c_int>,
+) -> c_int {
+    if path.is_null() || out_handle.is_null() {
+        return VHDL_ERROR_NULL_POINTER;
+    }
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        return VHDL_ERROR_INVALID_UTF8;
+    };
+    match catch_unwind(AssertUnwindSafe(|| f(Path::new(path)))) {
+        Ok(Ok(handle)) => {
+            *out_handle = Box::into_raw(Box::new(handle));
+            VHDL_OK
+        }
+        Ok(Err(code)) => code,
+        Err(_) => VHDL_ERROR_PANIC,
+    }
+}
+
+/// Parse a single file and keep its syntax errors.
+///
+/// # Safety
+///
+/// `path` must be a null terminated string and `out_handle` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vhdl_parse_file(
+    path: *const c_char,
+    out_handle: *mut *mut VhdlHandle,
+) -> c_int {
+    with_path(path, out_handle, |path| {
+        let mut diagnostics = Vec::new();
+        VHDLParser::default()
+            .parse_design_file(path, &mut diagnostics)
+            .map_err(|_| VHDL_ERROR_IO)?;
+        Ok(VhdlHandle::new(diagnostics))
+    })
+}
+
+/// Load a configuration file and analyze all files of its libraries.
+/// Only the libraries of the configuration file are analyzed, it must also define std and ieee.
+///
+/// # Safety
+///
+/// `config_path` must be a null terminated string and `out_handle` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vhdl_analyze_project(
+    config_path: *const c_char,
+    out_handle: *mut *mut VhdlHandle,
+) -> c_int {
+    with_path(config_path, out_handle, |path| {
+        let config = Config::read_file_path(path).map_err(|_| VHDL_ERROR_CONFIG)?;
+        let mut project = Project::from_config(config, &mut NullMessages);
+        Ok(VhdlHandle::new(project.analyse()))
+    })
+}
+
+/// Write the number of diagnostics of a handle to `out_count`.
+///
+/// # Safety
+///
+/// `handle` must be null or returned by this library and not freed,
+/// `out_count` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vhdl_get_diagnostic_count(
+    handle: *const VhdlHandle,
+    out_count: *mut usize,
+) -> c_int {
+    if handle.is_null() || out_count.is_null() {
+        return VHDL_ERROR_NULL_POINTER;
+    }
+    let handle = &*handle;
+    match catch_unwind(AssertUnwindSafe(|| handle.diagnostics.len())) {
+        Ok(count) => {
+            *out_count = count;
+            VHDL_OK
+        }
+        Err(_) => VHDL_ERROR_PANIC,
+    }
+}
+
+/// Write the diagnostic at `index` of a handle to `out_diagnostic`.
+///
+/// # Safety
+///
+/// `handle` must be null or returned by this library and not freed,
+/// `out_diagnostic` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vhdl_get_diagnostic(
+    handle: *const VhdlHandle,
+    index: usize,
+    out_diagnostic: *mut VhdlDiagnostic,
+) -> c_int {
+    if handle.is_null() || out_diagnostic.is_null() {
+        return VHDL_ERROR_NULL_POINTER;
+    }
+    let handle = &*handle;
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        handle
+            .diagnostics
+            .get(index)
+            .map(|diagnostic| VhdlDiagnostic {
+                file: diagnostic.file.as_ptr(),
+                line: diagnostic.line,
+                column: diagnostic.column,
+                severity: diagnostic.severity,
+                message: diagnostic.message.as_ptr(),
+            })
+    }));
+    match result {
+        Ok(Some(diagnostic)) => {
+            *out_diagnostic = diagnostic;
+            VHDL_OK
+        }
+        Ok(None) => VHDL_ERROR_OUT_OF_RANGE,
+        Err(_) => VHDL_ERROR_PANIC,
+    }
+}
+
+/// Free a handle and the strings of its diagnostics, a null handle is ignored.
+///
+/// # Safety
+///
+/// `handle` must be null or returned by this library and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn vhdl_free(handle: *mut VhdlHandle) {
+    if !handle.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(handle))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn c_path(path: &Path) -> CString {
+        CString::new(path.to_str().unwrap()).unwrap()
+    }
+
+    unsafe fn diagnostics_of(handle: *const VhdlHandle) -> Vec<(String, u32, u32, c_int, String)> {
+        let mut count = 0;
+        assert_eq!(vhdl_get_diagnostic_count(handle, &mut count), VHDL_OK);
+        (0..count)
+            .map(|index| {
+                let mut diagnostic = VhdlDiagnostic {
+                    file: ptr::null(),
+                    line: 0,
+                    column: 0,
+                    severity: 0,
+                    message: ptr::null(),
+                };
+                assert_eq!(vhdl_get_diagnostic(handle, index, &mut diagnostic), VHDL_OK);
+                (
+                    CStr::from_ptr(diagnostic.file).to_str().unwrap().to_owned(),
+                    diagnostic.line,
+                    diagnostic.column,
+                    diagnostic.severity,
+                    CStr::from_ptr(diagnostic.message)
+                        .to_str()
+                        .unwrap()
+                        .to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_file_round_trip() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let file_name = tempdir.path().join("file.vhd");
+        std::fs::write(
+            &file_name,
+            "\
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  x <= ;
+end architecture;
+",
+        )
+        .unwrap();
+
+        unsafe {
+            let mut handle = ptr::null_mut();
+            assert_eq!(
+                vhdl_parse_file(c_path(&file_name).as_ptr(), &mut handle),
+                VHDL_OK
+            );
+            let diagnostics = diagnostics_of(handle);
+            assert_eq!(diagnostics.len(), 1);
+            let (file, line, column, severity, _) = &diagnostics[0];
+            assert_eq!(file, file_name.to_str().unwrap());
+            assert_eq!((*line, *column), (6, 8));
+            assert_eq!(*severity, VHDL_SEVERITY_ERROR);
+
+            let mut diagnostic = std::mem::MaybeUninit::uninit();
+            assert_eq!(
+                vhdl_get_diagnostic(handle, 1, diagnostic.as_mut_ptr()),
+                VHDL_ERROR_OUT_OF_RANGE
+            );
+            vhdl_free(handle);
+        }
+    }
+
+    #[test]
+    fn analyze_project_round_trip() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(
+            root.join("ent.vhd"),
+            "\
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+  signal s : bit;
+begin
+end architecture;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("vhdl_ls.toml"),
+            format!(
+                "
+[libraries]
+std.files = ['{}/../vhdl_libraries/std/standard.vhd']
+lib.files = ['ent.vhd']
+",
+                env!("CARGO_MANIFEST_DIR")
+            ),
+        )
+        .unwrap();
+
+        unsafe {
+            let mut handle = ptr::null_mut();
+            assert_eq!(
+                vhdl_analyze_project(c_path(&root.join("vhdl_ls.toml")).as_ptr(), &mut handle),
+                VHDL_OK
+            );
+            assert_eq!(
+                diagnostics_of(handle),
+                vec![(
+                    root.join("ent.vhd").to_str().unwrap().to_owned(),
+                    6,
+                    10,
+                    VHDL_SEVERITY_ERROR,
+                    "Duplicate declaration of 's'".to_owned()
+                )]
+            );
+            vhdl_free(handle);
+        }
+    }
+
+    #[test]
+    fn failures_are_returned_as_error_codes() {
+        unsafe {
+            let mut handle = ptr::null_mut();
+            assert_eq!(
+                vhdl_parse_file(ptr::null(), &mut handle),
+                VHDL_ERROR_NULL_POINTER
+            );
+            let missing = c_path(Path::new("/missing/file.vhd"));
+            assert_eq!(
+                vhdl_parse_file(missing.as_ptr(), &mut handle),
+                VHDL_ERROR_IO
+            );
+            assert_eq!(
+                vhdl_analyze_project(missing.as_ptr(), &mut handle),
+                VHDL_ERROR_CONFIG
+            );
+            let invalid = CString::new(vec![0xff]).unwrap();
+            assert_eq!(
+                vhdl_parse_file(invalid.as_ptr(), &mut handle),
+                VHDL_ERROR_INVALID_UTF8
+            );
+            assert!(handle.is_null());
+
+            let mut count = 0;
+            assert_eq!(
+                vhdl_get_diagnostic_count(ptr::null(), &mut count),
+                VHDL_ERROR_NULL_POINTER
+            );
+            vhdl_free(ptr::null_mut());
+        }
+    }
+}
@@ -10,10 +10,14 @@ use crate::ast::{
     AnyDesignUnit, AnyPrimaryUnit, ConcurrentStatement, Designator, MapAspect, ObjectClass,
 };
 use crate::data::{ContentReader, Symbol};
-use crate::named_entity::{self, AsUnique, DesignEnt, HasEntityId, NamedEntities, Region};
+use crate::named_entity::{
+    self, AsUnique, DesignEnt, HasEntityId, NamedEntities, Region, Related, Type, TypeEnt,
+};
 use crate::syntax::Kind::*;
 use crate::syntax::{Kind, Symbols, Token, TokenAccess, Tokenizer, Value};
-use crate::{AnyEntKind, Design, EntRef, EntityId, HasTokenSpan, Overloaded, Position, Source};
+use crate::{
+    AnyEntKind, Design, EntRef, EntityId, HasTokenSpan, Overloaded, Position, Source, SrcPos,
+};
 use std::collections::HashSet;
 use std::default::Default;
 use std::iter::once;
@@ -241,7 +245,7 @@ impl<'a> Searcher for MapAspectSearcher<'a> {
 /// `tokenize_input(input)` -> {USE, ieee, DOT, std_logic_1164, DOT, a}
 ///
 /// On error, or if the source is empty, returns an empty vector.
-fn tokenize_input(symbols: &Symbols, source: &Source, cursor: Position) -> Vec<Token> {
+pub(crate) fn tokenize_input(symbols: &Symbols, source: &Source, cursor: Position) -> Vec<Token> {
     let contents = source.contents();
     let mut tokenizer = Tokenizer::new(symbols, source, ContentReader::new(&contents));
     let mut tokens = Vec::new();
@@ -423,39 +427,337 @@ impl DesignRoot {
     }
 }
 
-/// Main entry point for completion. Given a source-file and a cursor position,
-/// lists available completion options at the cursor position.
-pub fn list_completion_options<'a>(
+/// Collects the declarations that are visible at the cursor position.
+/// These are the objects, types, components and aliases of all scopes
+/// that enclose the cursor, including the declarations of the primary unit
+/// when the cursor is inside an architecture or a package body.
+struct VisibleDeclarationsSearcher<'a> {
     root: &'a DesignRoot,
-    source: &Source,
     cursor: Position,
-) -> Vec<CompletionItem<'a>> {
-    let tokens = tokenize_input(root.symbols(), source, cursor);
-    match &tokens[..] {
-        [.., kind!(Library)] | [.., kind!(Use)] | [.., kind!(Use), kind!(Identifier)] => {
-            list_all_libraries(root)
+    /// Declarations whose inner declarations are only visible within them,
+    /// such as design units, subprograms and statements.
+    scopes: HashSet<EntityId>,
+    /// The subset of `scopes` that contain the cursor
+    enclosing: HashSet<EntityId>,
+    declarations: Vec<EntRef<'a>>,
+}
+
+impl<'a> VisibleDeclarationsSearcher<'a> {
+    pub fn new(root: &'a DesignRoot, cursor: Position) -> VisibleDeclarationsSearcher<'a> {
+        VisibleDeclarationsSearcher {
+            root,
+            cursor,
+            scopes: HashSet::new(),
+            enclosing: HashSet::new(),
+            declarations: Vec::new(),
+        }
+    }
+
+    fn add_scope(&mut self, id: EntityId, span: Option<SrcPos>) {
+        self.scopes.insert(id);
+        if span.is_some_and(|span| span.contains(self.cursor)) {
+            self.enclosing.insert(id);
+        }
+    }
+
+    /// A declaration is visible when every scope that it is declared in encloses the cursor.
+    /// Scopes are always found before the declarations inside them.
+    fn is_visible(&self, ent: EntRef) -> bool {
+        let mut parent = ent.parent;
+        while let Some(ent) = parent {
+            if self.scopes.contains(&ent.id()) && !self.enclosing.contains(&ent.id()) {
+                return false;
+            }
+            parent = ent.parent;
+        }
+        true
+    }
+
+    /// Adds the declarations of a primary unit that are visible in its secondary units.
+    fn add_primary_unit_declarations(&mut self, primary: EntRef<'a>) {
+        let region = match primary.kind() {
+            AnyEntKind::Design(Design::Entity(_, region))
+            | AnyEntKind::Design(Design::Package(_, region))
+            | AnyEntKind::Design(Design::UninstPackage(_, region)) => region,
+            _ => return,
+        };
+        self.declarations.extend(
+            region
+                .entities
+                .values()
+                .filter_map(|named_ent| match named_ent {
+                    NamedEntities::Single(ent) => Some(*ent),
+                    NamedEntities::Overloaded(_) => None,
+                })
+                .filter(|ent| is_completable_declaration(ent)),
+        );
+    }
+}
+
+/// Whether an entity can be completed from the scope it is declared in.
+fn is_completable_declaration(ent: EntRef) -> bool {
+    matches!(
+        ent.kind(),
+        AnyEntKind::Object(_)
+            | AnyEntKind::DeferredConstant(_)
+            | AnyEntKind::ObjectAlias { .. }
+            | AnyEntKind::File(_)
+            | AnyEntKind::InterfaceFile(_)
+            | AnyEntKind::LoopParameter(_)
+            | AnyEntKind::Type(_)
+            | AnyEntKind::Component(_)
+    )
+}
+
+impl<'a> Searcher for VisibleDeclarationsSearcher<'a> {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        let Some(id) = decl.ent_id() else {
+            return NotFinished;
+        };
+        let ent = self.root.get_ent(id);
+        if is_completable_declaration(ent) && self.is_visible(ent) {
+            self.declarations.push(ent);
         }
-        [.., kind!(Use), ident!(library), kind!(Dot)]
-        | [.., kind!(Use), ident!(library), kind!(Dot), kind!(Identifier)] => {
-            list_primaries_for_lib(root, library)
+
+        match decl {
+            FoundDeclaration::Entity(unit) => self.add_scope(id, Some(unit.get_span(ctx))),
+            FoundDeclaration::Package(unit) => self.add_scope(id, Some(unit.get_span(ctx))),
+            FoundDeclaration::Architecture(unit) => {
+                let span = unit.get_span(ctx);
+                if span.contains(self.cursor) {
+                    if let Some(primary) = unit.entity_name.reference.get() {
+                        // The entity is the parent of the architecture
+                        self.enclosing.insert(primary);
+                        self.add_primary_unit_declarations(self.root.get_ent(primary));
+                    }
+                }
+                self.add_scope(id, Some(span));
+            }
+            FoundDeclaration::PackageBody(unit) => {
+                let span = unit.get_span(ctx);
+                if span.contains(self.cursor) {
+                    if let Related::DeclaredBy(primary) = ent.related {
+                        self.add_primary_unit_declarations(primary);
+                    }
+                }
+                self.add_scope(id, Some(span));
+            }
+            FoundDeclaration::Component(component) => {
+                self.add_scope(id, Some(component.get_span(ctx)))
+            }
+            FoundDeclaration::Type(typ) => self.add_scope(id, Some(typ.get_span(ctx))),
+            FoundDeclaration::Subprogram(body) => self.add_scope(id, Some(body.get_span(ctx))),
+            // The parameters of a subprogram declaration without body are never visible
+            FoundDeclaration::SubprogramDecl(_) => self.add_scope(id, None),
+            FoundDeclaration::ConcurrentStatement(stmt) => {
+                self.add_scope(id, Some(stmt.statement.pos.clone()))
+            }
+            FoundDeclaration::SequentialStatement(stmt) => {
+                self.add_scope(id, Some(stmt.statement.pos.clone()))
+            }
+            _ => {}
         }
-        [.., kind!(Use), ident!(library), kind!(Dot), ident!(selected), kind!(Dot)]
-        | [.., kind!(Use), ident!(library), kind!(Dot), ident!(selected), kind!(Dot), kind!(StringLiteral | Identifier)] => {
-            list_available_declarations(root, library, selected)
+        NotFinished
+    }
+}
+
+/// Returns the prefix of a selected name that ends right before the cursor
+/// along with the number of times each name of the prefix is indexed.
+///
+/// Examples:
+///
+/// input = "foo.bar.|"
+/// `selected_name_prefix(input)` -> \[(foo, 0), (bar, 0)\]
+///
+/// input = "foo(0).ba|"
+/// `selected_name_prefix(input)` -> \[(foo, 1)\]
+///
+/// input = "foo|"
+/// `selected_name_prefix(input)` -> None
+fn selected_name_prefix(tokens: &[Token]) -> Option<Vec<(&Symbol, usize)>> {
+    let mut rest = match tokens {
+        [rest @ .., kind!(Dot)] | [rest @ .., kind!(Dot), kind!(Identifier | StringLiteral)] => {
+            rest
+        }
+        _ => return None,
+    };
+    let mut prefix = Vec::new();
+    loop {
+        let (before, indexes) = strip_indexes(rest)?;
+        let [before @ .., ident!(symbol)] = before else {
+            return None;
+        };
+        prefix.push((symbol, indexes));
+        match before {
+            [before @ .., kind!(Dot)] => rest = before,
+            _ => break,
+        }
+    }
+    prefix.reverse();
+    Some(prefix)
+}
+
+/// Removes the parenthesized groups at the end of the tokens such as `(0)(i + 1)`.
+/// Returns the remaining tokens and the number of removed groups
+/// or None if the parentheses are not balanced.
+fn strip_indexes(mut tokens: &[Token]) -> Option<(&[Token], usize)> {
+    let mut indexes = 0;
+    while let [.., kind!(RightPar)] = tokens {
+        let mut depth = 0;
+        loop {
+            let [before @ .., last] = tokens else {
+                return None;
+            };
+            match last.kind {
+                RightPar => depth += 1,
+                LeftPar => depth -= 1,
+                _ => {}
+            }
+            tokens = before;
+            if depth == 0 {
+                break;
+            }
+        }
+        indexes += 1;
+    }
+    Some((tokens, indexes))
+}
+
+/// Returns the type of an object-like entity, i.e., an entity that can be the prefix
+/// of a selected name that selects a record element.
+fn type_of_object(ent: EntRef) -> Option<TypeEnt> {
+    match ent.kind() {
+        AnyEntKind::Object(obj) => Some(obj.subtype.type_mark()),
+        AnyEntKind::ObjectAlias { type_mark, .. } => Some(*type_mark),
+        AnyEntKind::ElementDeclaration(subtype) => Some(subtype.type_mark()),
+        _ => None,
+    }
+}
+
+/// Returns the element type after indexing an object-like entity a number of times,
+/// such as the record type of `arr(0)` for an array of records.
+fn indexed_type_of_object(ent: EntRef, indexes: usize) -> Option<TypeEnt> {
+    let mut typ = type_of_object(ent)?;
+    for _ in 0..indexes {
+        let Type::Array { elem_type, .. } = typ.base_type().kind() else {
+            return None;
+        };
+        typ = *elem_type;
+    }
+    Some(typ)
+}
+
+impl DesignRoot {
+    /// Main entry point for completion. Given a source-file and a cursor position,
+    /// lists available completion options at the cursor position.
+    pub fn completions<'a>(&'a self, source: &Source, cursor: Position) -> Vec<CompletionItem<'a>> {
+        let tokens = tokenize_input(self.symbols(), source, cursor);
+        match &tokens[..] {
+            [.., kind!(Library)] | [.., kind!(Use)] | [.., kind!(Use), kind!(Identifier)] => {
+                return list_all_libraries(self)
+            }
+            [.., kind!(LeftPar | Comma)] | [.., kind!(LeftPar | Comma), kind!(Identifier)] => {
+                let mut searcher = MapAspectSearcher::new(self, cursor);
+                if let Found = self.search_source(source, &mut searcher) {
+                    return searcher.completions;
+                }
+            }
+            _ => {
+                if let Some(prefix) = selected_name_prefix(&tokens) {
+                    return self.list_selected_completions(source, cursor, &prefix);
+                }
+            }
+        }
+
+        let mut searcher = CompletionSearcher::new(cursor, self);
+        let _ = self.search_source(source, &mut searcher);
+        let mut completions = searcher.completions;
+        completions.extend(
+            self.visible_declarations(source, cursor)
+                .into_iter()
+                .map(CompletionItem::Simple),
+        );
+        completions
+    }
+
+    /// Lists the declarations that are visible at the cursor position.
+    pub(crate) fn visible_declarations<'a>(
+        &'a self,
+        source: &Source,
+        cursor: Position,
+    ) -> Vec<EntRef<'a>> {
+        let mut searcher = VisibleDeclarationsSearcher::new(self, cursor);
+        let _ = self.search_source(source, &mut searcher);
+        searcher.declarations
+    }
+
+    /// Lists the completions of a selected name with a given prefix.
+    /// The prefix either starts with a library name, where the primary units of that library
+    /// or the declarations within a primary unit are listed,
+    /// or the prefix is an object of record type, where the record elements are listed.
+    fn list_selected_completions<'a>(
+        &'a self,
+        source: &Source,
+        cursor: Position,
+        prefix: &[(&Symbol, usize)],
+    ) -> Vec<CompletionItem<'a>> {
+        let [(first, first_indexes), suffixes @ ..] = prefix else {
+            return vec![];
+        };
+
+        let library = if *first_indexes > 0 {
+            None
+        } else if **first == self.symbol_utf8("work") {
+            self.library_of_source(source).map(|lib| lib.name())
+        } else {
+            self.get_lib(first).map(|lib| lib.name())
+        };
+        if let Some(library) = library {
+            return match suffixes {
+                [] => list_primaries_for_lib(self, library),
+                [(primary_unit, 0)] => list_available_declarations(self, library, primary_unit),
+                _ => vec![],
+            };
         }
-        [.., kind!(LeftPar | Comma)] | [.., kind!(LeftPar | Comma), kind!(Identifier)] => {
-            let mut searcher = MapAspectSearcher::new(root, cursor);
-            let _ = root.search_source(source, &mut searcher);
-            searcher.completions
+
+        let visible = self.visible_declarations(source, cursor);
+        let Some(ent) = visible
+            .into_iter()
+            .find(|ent| matches!(ent.designator(), Designator::Identifier(sym) if sym == *first))
+        else {
+            return vec![];
+        };
+        let mut typ = indexed_type_of_object(ent, *first_indexes);
+        for (suffix, indexes) in suffixes {
+            let Some(Type::Record(region)) = typ.map(|typ| typ.base_type().kind()) else {
+                return vec![];
+            };
+            let Some(elem) = region.lookup(&Designator::Identifier((*suffix).clone())) else {
+                return vec![];
+            };
+            typ = indexed_type_of_object(elem.into(), *indexes);
         }
-        _ => {
-            let mut searcher = CompletionSearcher::new(cursor, root);
-            let _ = root.search_source(source, &mut searcher);
-            searcher.completions
+        match typ.map(|typ| typ.base_type().kind()) {
+            Some(Type::Record(region)) => region
+                .iter()
+                .map(|elem| CompletionItem::Simple(elem.into()))
+                .collect(),
+            _ => vec![],
         }
     }
 }
 
+/// Given a source-file and a cursor position,
+/// lists available completion options at the cursor position.
+/// See [`DesignRoot::completions`].
+pub fn list_completion_options<'a>(
+    root: &'a DesignRoot,
+    source: &Source,
+    cursor: Position,
+) -> Vec<CompletionItem<'a>> {
+    root.completions(source, cursor)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -626,15 +928,33 @@ mod test {
             clk =>")
             .pos()
             .end();
+        // The actual part lists the visible declarations instead of the formals
+        let visible = [
+            CompletionItem::Simple(
+                root.search_reference(
+                    code.source(),
+                    code.s1("component comp").s("comp", 2).start(),
+                )
+                .unwrap(),
+            ),
+            CompletionItem::Simple(
+                root.search_reference(code.source(), code.s1("signal clk").s1("clk").start())
+                    .unwrap(),
+            ),
+            CompletionItem::Simple(
+                root.search_reference(code.source(), code.s1("clk, rst").s1("rst").start())
+                    .unwrap(),
+            ),
+        ];
         let options = list_completion_options(&root, code.source(), cursor);
-        assert_eq!(options.len(), 0);
+        assert_eq_unordered(&options, &visible);
         let cursor = code
             .s1("port map (
             clk => c")
             .pos()
             .end();
         let options = list_completion_options(&root, code.source(), cursor);
-        assert_eq!(options.len(), 0);
+        assert_eq_unordered(&options, &visible);
     }
 
     #[test]
@@ -797,4 +1117,215 @@ end arch;
             ],
         );
     }
+
+    #[test]
+    fn complete_visible_declarations() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity ent is
+  port (clk : in bit);
+end entity;
+
+architecture arch of ent is
+  signal sig1 : bit;
+  constant const1 : natural := 0;
+
+  procedure proc(param : natural) is
+  begin
+  end procedure;
+begin
+  process
+    variable var1 : natural;
+  begin
+    wait;
+  end process;
+
+  other: process
+    variable hidden : natural;
+  begin
+    wait;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        let decl = |name: &str| {
+            root.search_reference(code.source(), code.s1(name).start())
+                .unwrap()
+        };
+
+        let options = root.completions(code.source(), code.s1("wait").start());
+        assert_eq_unordered(
+            &options,
+            &[
+                CompletionItem::Simple(decl("clk")),
+                CompletionItem::Simple(decl("sig1")),
+                CompletionItem::Simple(decl("const1")),
+                CompletionItem::Simple(decl("var1")),
+            ],
+        );
+
+        let options = root.completions(code.source(), code.s1("end procedure").start());
+        assert_eq_unordered(
+            &options,
+            &[
+                CompletionItem::Simple(decl("clk")),
+                CompletionItem::Simple(decl("sig1")),
+                CompletionItem::Simple(decl("const1")),
+                CompletionItem::Simple(decl("param")),
+            ],
+        );
+    }
+
+    #[test]
+    fn complete_selected_names() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  type rec_t is record
+    field1 : natural;
+    field2 : bit;
+  end record;
+end package;
+
+entity sub is
+end entity;
+
+use work.pkg.all;
+
+entity top is
+end entity;
+
+architecture arch of top is
+  signal rec : rec_t;
+begin
+  rec.field1 <= 0;
+  inst: entity work.sub;
+  inst2: entity Work.sub;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        let decl = |code: Code| root.search_reference(code.source(), code.start()).unwrap();
+
+        let options = root.completions(code.source(), code.s1("rec.").end());
+        assert_eq_unordered(
+            &options,
+            &[
+                CompletionItem::Simple(decl(code.s1("field1"))),
+                CompletionItem::Simple(decl(code.s1("field2"))),
+            ],
+        );
+
+        let options = root.completions(code.source(), code.s1("entity work.").end());
+        assert_eq_unordered(
+            &options,
+            &[
+                CompletionItem::Simple(decl(code.s1("package pkg").s1("pkg"))),
+                CompletionItem::Simple(decl(code.s1("entity sub").s1("sub"))),
+                CompletionItem::Simple(decl(code.s1("entity top").s1("top"))),
+            ],
+        );
+
+        let options = root.completions(code.source(), code.s1("entity Work.").end());
+        assert_eq!(options.len(), 3);
+
+        let options = root.completions(code.source(), code.s1("use work.pkg.").end());
+        assert!(options.contains(&CompletionItem::Simple(decl(code.s1("rec_t")))));
+        assert!(options.contains(&CompletionItem::Keyword(All)));
+    }
+
+    #[test]
+    fn complete_nested_selected_names_with_indexing() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity ent is
+end entity;
+
+architecture arch of ent is
+  type master_t is record
+    addr : bit_vector(7 downto 0);
+    valid : bit;
+  end record;
+  type master_arr_t is array (0 to 3) of master_t;
+  type bus_t is record
+    master : master_t;
+    slaves : master_arr_t;
+  end record;
+  type bus_arr_t is array (0 to 1) of bus_t;
+
+  signal buses : bus_arr_t;
+begin
+  buses(0).master.addr <= (others => '0');
+  buses(1).slaves(2).valid <= '1';
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        let decl = |code: Code| root.search_reference(code.source(), code.start()).unwrap();
+        let bus_elements = [
+            CompletionItem::Simple(decl(code.s1("master :"))),
+            CompletionItem::Simple(decl(code.s1("slaves :"))),
+        ];
+        let master_elements = [
+            CompletionItem::Simple(decl(code.s1("addr :"))),
+            CompletionItem::Simple(decl(code.s1("valid :"))),
+        ];
+
+        let options = root.completions(code.source(), code.s1("buses(0).").end());
+        assert_eq_unordered(&options, &bus_elements);
+
+        let options = root.completions(code.source(), code.s1("buses(0).master.").end());
+        assert_eq_unordered(&options, &master_elements);
+
+        let options = root.completions(code.source(), code.s1("buses(1).slaves(2).").end());
+        assert_eq_unordered(&options, &master_elements);
+
+        // A partially written element is completed with the elements of the prefix
+        let options = root.completions(code.source(), code.s1("buses(1).sl").end());
+        assert_eq_unordered(&options, &bus_elements);
+    }
+
+    #[test]
+    fn complete_formals_in_entity_port_map() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity sub is
+  port (
+    din : in bit;
+    dout : out bit
+  );
+end entity;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  signal sig1 : bit;
+begin
+  inst: entity work.sub
+    port map (
+      din => sig1
+    );
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let options = root.completions(code.source(), code.s1("port map (").end());
+        let dout = root
+            .search_reference(code.source(), code.s1("dout").start())
+            .unwrap();
+        assert_eq!(options, vec![CompletionItem::Formal(dout)]);
+    }
 }
@@ -7,77 +7,127 @@
 //! Configuration of the design hierarchy and other settings
 
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use fnv::FnvHashMap;
+use serde::de::{Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
 use std::env;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
 use std::path::Path;
-use toml::Value;
+use toml::{Spanned, Value};
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct Config {
-    // A map from library name to file name
-    libraries: FnvHashMap<String, LibraryConfig>,
+    // The libraries in the order they are configured
+    libraries: Vec<LibraryConfig>,
+    // Forced encoding of all source files, detected automatically when not set
+    encoding: Option<Encoding>,
+    // The VHDL revision of all source files, VHDL-2008 when not set
+    standard: Option<VHDLStandard>,
+    // Do not warn about case statements on std_logic that do not cover all values
+    ignore_incomplete_std_logic_case: Option<bool>,
+    // Warn about assertions whose condition is always true or always false
+    lint_static_assertions: Option<bool>,
+    // Warn about if and while conditions and case selectors that are constant
+    lint_constant_conditions: Option<bool>,
+    // Warn about functions that can reach their end without a return statement
+    lint_missing_return: Option<bool>,
+    // Warn about variables that may be read before they are assigned
+    lint_uninitialized_variables: Option<bool>,
+    // The severity of diagnostics by error code, None to not report them at all
+    severity_overrides: FnvHashMap<ErrorCode, Option<Severity>>,
+    // The values of the identifiers tested by conditional analysis directives
+    conditional_analysis: FnvHashMap<String, String>,
 }
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct LibraryConfig {
     name: String,
-    patterns: Vec<String>,
+    patterns: Vec<PathBuf>,
+    // Patterns of files to remove from the files matched by patterns
+    exclude_patterns: Vec<PathBuf>,
     pub(crate) is_third_party: bool,
+    pub(crate) ignore_unused: bool,
+    // Warn about constructs that are not synthesizable
+    pub(crate) synthesis_lint: bool,
+    // The VHDL revision of the files in this library, the global standard when not set
+    pub(crate) standard: Option<VHDLStandard>,
 }
 
 impl LibraryConfig {
     /// Return a vector of file names
     /// Only include files that exists
     /// Files that do not exist produce a warning message
+    /// Files that match an exclusion pattern are removed from the result
     pub fn file_names(&self, messages: &mut dyn MessageHandler) -> Vec<PathBuf> {
         let mut result = Vec::new();
         for pattern in self.patterns.iter() {
-            let stripped_pattern = if cfg!(windows) {
-                pattern.strip_prefix("\\\\?\\").unwrap_or(pattern.as_str())
-            } else {
-                pattern.as_str()
-            };
-
-            if is_literal(stripped_pattern) {
-                let file_path = Path::new(pattern).to_owned();
-
-                if file_path.exists() {
-                    result.push(file_path);
+            if is_literal(strip_verbatim_prefix(&pattern.to_string_lossy())) {
+                // Literal file names do not need to be valid UTF-8
+                if pattern.exists() {
+                    result.push(pattern.clone());
                 } else {
-                    messages.push(Message::warning(format! {"File {pattern} does not exist"}));
+                    messages.push(Message::warning(
+                        format! {"File {} does not exist", pattern.to_string_lossy()},
+                    ));
                 }
-            } else {
-                match glob::glob(stripped_pattern) {
-                    Ok(paths) => {
-                        let mut empty_pattern = true;
-
-                        for file_path_or_error in paths {
-                            empty_pattern = false;
-                            match file_path_or_error {
-                                Ok(file_path) => {
-                                    result.push(file_path);
-                                }
-                                Err(err) => {
-                                    messages.push(Message::error(err.to_string()));
-                                }
-                            }
-                        }
+                continue;
+            }
 
-                        if empty_pattern {
-                            messages.push(Message::warning(format!(
-                                "Pattern '{stripped_pattern}' did not match any file"
-                            )));
+            let Some(stripped_pattern) = pattern_str(pattern, messages) else {
+                continue;
+            };
+            match glob::glob(stripped_pattern) {
+                Ok(paths) => {
+                    let mut empty_pattern = true;
+
+                    for file_path_or_error in paths {
+                        empty_pattern = false;
+                        match file_path_or_error {
+                            Ok(file_path) => {
+                                result.push(file_path);
+                            }
+                            Err(err) => {
+                                messages.push(Message::error(err.to_string()));
+                            }
                         }
                     }
-                    Err(err) => {
-                        messages.push(Message::error(format!("Invalid pattern '{pattern}' {err}")));
+
+                    if empty_pattern {
+                        messages.push(Message::warning(format!(
+                            "Pattern '{stripped_pattern}' did not match any file"
+                        )));
                     }
                 }
+                Err(err) => {
+                    messages.push(Message::error(format!(
+                        "Invalid pattern '{stripped_pattern}' {err}"
+                    )));
+                }
             }
         }
+
+        let mut exclude_patterns = Vec::new();
+        for pattern in self.exclude_patterns.iter() {
+            let Some(stripped_pattern) = pattern_str(pattern, messages) else {
+                continue;
+            };
+            match glob::Pattern::new(stripped_pattern) {
+                Ok(pattern) => exclude_patterns.push(pattern),
+                Err(err) => {
+                    messages.push(Message::error(format!(
+                        "Invalid pattern '!{stripped_pattern}' {err}"
+                    )));
+                }
+            }
+        }
+        result.retain(|file_path| {
+            !exclude_patterns
+                .iter()
+                .any(|pattern| pattern.matches_path(file_path))
+        });
+
         Self::remove_duplicates(result)
     }
 
@@ -98,12 +148,126 @@ impl LibraryConfig {
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
+
+    /// Returns the file name patterns of the library as written in the configuration
+    pub fn patterns(&self) -> &[PathBuf] {
+        &self.patterns
+    }
+
+    /// Returns true if the library contains third-party code
+    /// whose warnings should not be reported
+    pub fn is_third_party(&self) -> bool {
+        self.is_third_party
+    }
+
+    /// Returns true if constructs that are not synthesizable are reported for the library
+    pub fn synthesis_lint(&self) -> bool {
+        self.synthesis_lint
+    }
 }
 
 impl Config {
     pub fn from_str(string: &str, parent: &Path) -> Result<Config, String> {
         let config = string.parse::<Value>().map_err(|err| err.to_string())?;
-        let mut libraries = FnvHashMap::default();
+        let mut libraries = Vec::new();
+
+        let encoding = if let Some(encoding) = config.get("encoding") {
+            let name = encoding.as_str().ok_or("encoding must be a string")?;
+            Some(Encoding::from_name(name).ok_or_else(|| {
+                format!("Unknown encoding '{name}', expected one of 'auto', 'utf-8' or 'latin-1'")
+            })?)
+        } else {
+            None
+        };
+
+        let standard = if let Some(standard) = config.get("standard") {
+            let name = standard.as_str().ok_or("standard must be a string")?;
+            Some(VHDLStandard::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown standard '{name}', expected one of '1993', '2002', '2008' or '2019'"
+                )
+            })?)
+        } else {
+            None
+        };
+
+        let ignore_incomplete_std_logic_case = config
+            .get("ignore_incomplete_std_logic_case")
+            .map(|ignore| {
+                ignore
+                    .as_bool()
+                    .ok_or("ignore_incomplete_std_logic_case must be a boolean")
+            })
+            .transpose()?;
+
+        let lint_static_assertions = config
+            .get("lint_static_assertions")
+            .map(|lint| {
+                lint.as_bool()
+                    .ok_or("lint_static_assertions must be a boolean")
+            })
+            .transpose()?;
+
+        let lint_constant_conditions = config
+            .get("lint_constant_conditions")
+            .map(|lint| {
+                lint.as_bool()
+                    .ok_or("lint_constant_conditions must be a boolean")
+            })
+            .transpose()?;
+
+        let lint_missing_return = config
+            .get("lint_missing_return")
+            .map(|lint| {
+                lint.as_bool()
+                    .ok_or("lint_missing_return must be a boolean")
+            })
+            .transpose()?;
+
+        let lint_uninitialized_variables = config
+            .get("lint_uninitialized_variables")
+            .map(|lint| {
+                lint.as_bool()
+                    .ok_or("lint_uninitialized_variables must be a boolean")
+            })
+            .transpose()?;
+
+        let mut severity_overrides = FnvHashMap::default();
+        if let Some(diagnostics) = config.get("diagnostics") {
+            let diagnostics = diagnostics
+                .as_table()
+                .ok_or("diagnostics must be a table")?;
+            for (key, severity) in [("hint", Some(Severity::Hint)), ("ignore", None)] {
+                let Some(codes) = diagnostics.get(key) else {
+                    continue;
+                };
+                let codes = codes
+                    .as_array()
+                    .ok_or_else(|| format!("diagnostics.{key} must be an array"))?;
+                for code in codes.iter() {
+                    let name = code
+                        .as_str()
+                        .ok_or_else(|| format!("diagnostics.{key} must only contain strings"))?;
+                    let code = ErrorCode::from_name(name).ok_or_else(|| {
+                        format!("Unknown error code '{name}' in diagnostics.{key}")
+                    })?;
+                    severity_overrides.insert(code, severity);
+                }
+            }
+        }
+
+        let mut conditional_analysis = FnvHashMap::default();
+        if let Some(identifiers) = config.get("conditional_analysis") {
+            let identifiers = identifiers
+                .as_table()
+                .ok_or("conditional_analysis must be a table")?;
+            for (name, value) in identifiers.iter() {
+                let value = value
+                    .as_str()
+                    .ok_or_else(|| format!("conditional_analysis.{name} must be a string"))?;
+                conditional_analysis.insert(name.clone(), value.to_owned());
+            }
+        }
 
         let libs = config
             .get("libraries")
@@ -126,17 +290,17 @@ impl Config {
                 .ok_or_else(|| format!("files for library {name} is not array"))?;
 
             let mut patterns = Vec::new();
+            let mut exclude_patterns = Vec::new();
             for file in file_arr.iter() {
                 let file = file
                     .as_str()
                     .ok_or_else(|| format!("not a string {file}"))?;
 
-                let path = parent.join(file);
-                let path = path
-                    .to_str()
-                    .ok_or_else(|| format!("Could not convert {path:?} to string"))?
-                    .to_owned();
-                patterns.push(path);
+                if let Some(file) = file.strip_prefix('!') {
+                    exclude_patterns.push(parent.join(file));
+                } else {
+                    patterns.push(parent.join(file));
+                }
             }
 
             let mut is_third_party = false;
@@ -150,43 +314,194 @@ impl Config {
                 }
             }
 
-            libraries.insert(
-                name.to_owned(),
-                LibraryConfig {
-                    name: name.to_owned(),
-                    patterns,
-                    is_third_party,
-                },
-            );
+            let mut ignore_unused = false;
+            if let Some(opt) = lib.get("ignore_unused") {
+                if let Some(opt) = opt.as_bool() {
+                    ignore_unused = opt;
+                } else {
+                    return Err(format!(
+                        "Expected ignore_unused to be boolean for library {name}"
+                    ));
+                }
+            }
+
+            let mut synthesis_lint = false;
+            if let Some(opt) = lib.get("synthesis_lint") {
+                if let Some(opt) = opt.as_bool() {
+                    synthesis_lint = opt;
+                } else {
+                    return Err(format!(
+                        "Expected synthesis_lint to be boolean for library {name}"
+                    ));
+                }
+            }
+
+            let standard = if let Some(standard) = lib.get("standard") {
+                let standard_name = standard
+                    .as_str()
+                    .ok_or_else(|| format!("Expected standard to be string for library {name}"))?;
+                Some(VHDLStandard::from_name(standard_name).ok_or_else(|| {
+                    format!("Unknown standard '{standard_name}' for library {name}, expected one of '1993', '2002', '2008' or '2019'")
+                })?)
+            } else {
+                None
+            };
+
+            libraries.push(LibraryConfig {
+                name: name.to_owned(),
+                patterns,
+                exclude_patterns,
+                is_third_party,
+                ignore_unused,
+                synthesis_lint,
+                standard,
+            });
         }
 
-        Ok(Config { libraries })
+        Ok(Config {
+            libraries,
+            encoding,
+            standard,
+            ignore_incomplete_std_logic_case,
+            lint_static_assertions,
+            lint_constant_conditions,
+            lint_missing_return,
+            lint_uninitialized_variables,
+            severity_overrides,
+            conditional_analysis,
+        })
     }
 
     pub fn read_file_path(file_name: &Path) -> io::Result<Config> {
+        Config::read_file_path_with_messages(file_name, &mut NullMessages)
+    }
+
+    /// Read a configuration file and warn about keys that are not recognized
+    pub fn read_file_path_with_messages(
+        file_name: &Path,
+        messages: &mut dyn MessageHandler,
+    ) -> io::Result<Config> {
         let mut file = File::open(file_name)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
         let parent = file_name.parent().unwrap();
 
-        Config::from_str(&contents, parent).map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))
+        let config = Config::from_str(&contents, parent)
+            .map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))?;
+
+        for (key, line) in unknown_keys(&contents) {
+            messages.push(Message::warning(format!(
+                "Unknown key '{key}' (In file {}:{line})",
+                file_name.to_string_lossy()
+            )));
+        }
+
+        Ok(config)
     }
 
     pub fn get_library<'a>(&'a self, name: &str) -> Option<&'a LibraryConfig> {
-        self.libraries.get(name)
+        self.libraries.iter().find(|library| library.name == name)
     }
 
+    /// Iterates over the libraries in the order they are configured
     pub fn iter_libraries(&self) -> impl Iterator<Item = &LibraryConfig> {
-        self.libraries.values()
+        self.libraries.iter()
+    }
+
+    /// Returns the encoding used to read source files
+    pub fn encoding(&self) -> Encoding {
+        self.encoding.unwrap_or_default()
+    }
+
+    /// Returns the VHDL revision used to parse source files
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard.unwrap_or_default()
+    }
+
+    /// Returns true if incomplete case statements on std_logic selectors are not reported
+    pub fn ignore_incomplete_std_logic_case(&self) -> bool {
+        self.ignore_incomplete_std_logic_case.unwrap_or(false)
+    }
+
+    /// Returns true if assertions whose condition is a locally static `true`,
+    /// or `false` with a severity below error, are reported
+    pub fn lint_static_assertions(&self) -> bool {
+        self.lint_static_assertions.unwrap_or(false)
+    }
+
+    /// Returns true if if and while conditions and case selectors that are constant are reported
+    pub fn lint_constant_conditions(&self) -> bool {
+        self.lint_constant_conditions.unwrap_or(false)
+    }
+
+    /// Returns true if functions that can reach their end without a return statement are reported
+    pub fn lint_missing_return(&self) -> bool {
+        self.lint_missing_return.unwrap_or(false)
+    }
+
+    /// Returns true if variables without an initial value that may be read
+    /// before they are assigned are reported
+    pub fn lint_uninitialized_variables(&self) -> bool {
+        self.lint_uninitialized_variables.unwrap_or(false)
+    }
+
+    /// Returns the severity that diagnostics with an error code are reported with
+    /// instead of their own, `None` when they are not reported at all
+    pub fn severity_overrides(&self) -> &FnvHashMap<ErrorCode, Option<Severity>> {
+        &self.severity_overrides
+    }
+
+    /// Returns the values of the identifiers tested by conditional analysis directives
+    /// such as `` `if TOOL_TYPE = "SIMULATION" `then ``
+    pub fn conditional_analysis(&self) -> &FnvHashMap<String, String> {
+        &self.conditional_analysis
+    }
+
+    /// Returns the VHDL revision used to parse the source files of a library,
+    /// falling back to the global standard when the library does not set one
+    pub fn library_standard(&self, name: &str) -> VHDLStandard {
+        self.get_library(name)
+            .and_then(|library| library.standard)
+            .unwrap_or_else(|| self.standard())
     }
 
     /// Append another config to self
     ///
     /// In case of conflict the appended config takes precedence
     pub fn append(&mut self, config: &Config, messages: &mut dyn MessageHandler) {
+        if config.encoding.is_some() {
+            self.encoding = config.encoding;
+        }
+        if config.standard.is_some() {
+            self.standard = config.standard;
+        }
+        if config.ignore_incomplete_std_logic_case.is_some() {
+            self.ignore_incomplete_std_logic_case = config.ignore_incomplete_std_logic_case;
+        }
+        if config.lint_static_assertions.is_some() {
+            self.lint_static_assertions = config.lint_static_assertions;
+        }
+        if config.lint_constant_conditions.is_some() {
+            self.lint_constant_conditions = config.lint_constant_conditions;
+        }
+        if config.lint_missing_return.is_some() {
+            self.lint_missing_return = config.lint_missing_return;
+        }
+        if config.lint_uninitialized_variables.is_some() {
+            self.lint_uninitialized_variables = config.lint_uninitialized_variables;
+        }
+        self.severity_overrides
+            .extend(config.severity_overrides.clone());
+        self.conditional_analysis
+            .extend(config.conditional_analysis.clone());
+
         for library in config.iter_libraries() {
-            if let Some(parent_library) = self.libraries.get_mut(&library.name) {
+            if let Some(parent_library) = self
+                .libraries
+                .iter_mut()
+                .find(|parent_library| parent_library.name == library.name)
+            {
                 *parent_library = library.clone();
 
                 messages.push(Message::warning(format!(
@@ -194,7 +509,7 @@ impl Config {
                     &library.name
                 )));
             } else {
-                self.libraries.insert(library.name.clone(), library.clone());
+                self.libraries.push(library.clone());
             }
         }
     }
@@ -252,7 +567,7 @@ impl Config {
 
     /// Load and append configuration file
     fn load_config(&mut self, file_name: &Path, desc: &str, messages: &mut dyn MessageHandler) {
-        match Config::read_file_path(Path::new(&file_name)) {
+        match Config::read_file_path_with_messages(Path::new(&file_name), messages) {
             Ok(env_config) => {
                 messages.push(Message::log(format!(
                     "Loaded {} configuration file: {}",
@@ -278,6 +593,149 @@ impl Config {
     }
 }
 
+const KNOWN_KEYS: &[&str] = &[
+    "encoding",
+    "standard",
+    "ignore_incomplete_std_logic_case",
+    "lint_static_assertions",
+    "lint_constant_conditions",
+    "lint_missing_return",
+    "lint_uninitialized_variables",
+    "diagnostics",
+    "conditional_analysis",
+    "libraries",
+];
+const KNOWN_DIAGNOSTICS_KEYS: &[&str] = &["hint", "ignore"];
+const KNOWN_LIBRARY_KEYS: &[&str] = &[
+    "files",
+    "is_third_party",
+    "ignore_unused",
+    "synthesis_lint",
+    "standard",
+];
+
+/// The keys of a TOML table and their byte offsets, nested tables are kept as children
+struct KeyTree(Vec<(Spanned<String>, Option<KeyTree>)>);
+
+impl<'de> Deserialize<'de> for KeyTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_map(KeyTreeVisitor)?
+            .ok_or_else(|| serde::de::Error::custom("expected a table"))
+    }
+}
+
+/// Any TOML value, only tables have keys
+struct KeyTreeNode(Option<KeyTree>);
+
+impl<'de> Deserialize<'de> for KeyTreeNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(KeyTreeVisitor)
+            .map(KeyTreeNode)
+    }
+}
+
+struct KeyTreeVisitor;
+
+impl<'de> Visitor<'de> for KeyTreeVisitor {
+    type Value = Option<KeyTree>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a TOML value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut keys = Vec::new();
+        while let Some(key) = map.next_key::<Spanned<String>>()? {
+            let node = map.next_value::<KeyTreeNode>()?;
+            keys.push((key, node.0));
+        }
+        Ok(Some(KeyTree(keys)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(None)
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+}
+
+/// Returns the keys of a configuration file that are not recognized together with their line number
+fn unknown_keys(contents: &str) -> Vec<(String, usize)> {
+    let Ok(tree) = toml::from_str::<KeyTree>(contents) else {
+        // Syntax errors are reported when parsing the configuration
+        return Vec::new();
+    };
+    let line_of = |key: &Spanned<String>| contents[..key.span().start].matches('\n').count() + 1;
+
+    let mut unknown = Vec::new();
+    for (key, children) in tree.0.iter() {
+        if !KNOWN_KEYS.contains(&key.get_ref().as_str()) {
+            unknown.push((key.get_ref().clone(), line_of(key)));
+        } else if key.get_ref() == "diagnostics" {
+            for (key, _) in children.iter().flat_map(|tree| tree.0.iter()) {
+                if !KNOWN_DIAGNOSTICS_KEYS.contains(&key.get_ref().as_str()) {
+                    unknown.push((format!("diagnostics.{}", key.get_ref()), line_of(key)));
+                }
+            }
+        } else if key.get_ref() == "libraries" {
+            for (library, children) in children.iter().flat_map(|tree| tree.0.iter()) {
+                for (key, _) in children.iter().flat_map(|tree| tree.0.iter()) {
+                    if !KNOWN_LIBRARY_KEYS.contains(&key.get_ref().as_str()) {
+                        unknown.push((
+                            format!("libraries.{}.{}", library.get_ref(), key.get_ref()),
+                            line_of(key),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    unknown.sort_by_key(|(_, line)| *line);
+    unknown
+}
+
+/// Returns the pattern as a string for glob matching
+/// Glob patterns must be valid UTF-8, an error message is produced otherwise
+fn pattern_str<'a>(pattern: &'a Path, messages: &mut dyn MessageHandler) -> Option<&'a str> {
+    let Some(pattern_str) = pattern.to_str() else {
+        messages.push(Message::error(format!(
+            "Could not convert pattern {pattern:?} to string"
+        )));
+        return None;
+    };
+    Some(strip_verbatim_prefix(pattern_str))
+}
+
+/// Remove the \\?\ prefix of verbatim paths on Windows since '?' is a glob wildcard
+fn strip_verbatim_prefix(pattern: &str) -> &str {
+    if cfg!(windows) {
+        pattern.strip_prefix("\\\\?\\").unwrap_or(pattern)
+    } else {
+        pattern
+    }
+}
+
 /// Returns true if the pattern is a plain file name and not a glob pattern
 fn is_literal(pattern: &str) -> bool {
     for chr in pattern.chars() {
@@ -501,6 +959,100 @@ lib.files = [
         assert_files_eq(&file_names, &[file1, file2]);
         assert_eq!(messages, vec![]);
     }
+
+    #[test]
+    fn test_recursive_pattern_with_exclusion() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::create_dir_all(parent.join("src").join("sub")).unwrap();
+        std::fs::create_dir_all(parent.join("src").join("generated").join("sub")).unwrap();
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  'src/**/*.vhd',
+  'src/sub/*.vhd',
+  '!src/generated/**',
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        let file1 = touch(parent, "src/file1.vhd");
+        let file2 = touch(parent, "src/sub/file2.vhd");
+        touch(parent, "src/generated/gen1.vhd");
+        touch(parent, "src/generated/sub/gen2.vhd");
+
+        let mut messages = vec![];
+        let file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        assert_files_eq(&file_names, &[file1, file2]);
+        assert_eq!(messages, vec![]);
+    }
+
+    #[test]
+    fn test_exclusion_of_literal_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  '*.vhd',
+  '!file2.vhd',
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        let file1 = touch(parent, "file1.vhd");
+        touch(parent, "file2.vhd");
+
+        let mut messages = vec![];
+        let file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        assert_files_eq(&file_names, &[file1]);
+        assert_eq!(messages, vec![]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_parent_folder() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path().join(OsStr::from_bytes(b"fold\xe9r"));
+        if std::fs::create_dir(&parent).is_err() {
+            // The file system does not support non UTF-8 file names
+            return;
+        }
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  'file1.vhd',
+  '*.vhd',
+]
+",
+            &parent,
+        )
+        .unwrap();
+
+        let file1 = touch(&parent, "file1.vhd");
+
+        let mut messages = vec![];
+        let file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        assert_files_eq(&file_names, &[file1]);
+        assert_eq!(
+            messages,
+            vec![Message::error(format!(
+                "Could not convert pattern {:?} to string",
+                parent.join("*.vhd")
+            ))]
+        );
+    }
+
     #[test]
     fn test_warning_on_emtpy_glob_pattern() {
         let parent = Path::new("parent_folder");
@@ -527,6 +1079,281 @@ lib.files = [
         );
     }
 
+    #[test]
+    fn encoding_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+encoding = 'latin-1'
+
+[libraries]
+lib.files = ['a.vhd']
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(config.encoding(), Encoding::Latin1);
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert_eq!(config.encoding(), Encoding::Auto);
+
+        let config = Config::from_str(
+            "
+encoding = 'ebcdic'
+
+[libraries]
+",
+            parent,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Unknown encoding 'ebcdic', expected one of 'auto', 'utf-8' or 'latin-1'"
+        );
+    }
+
+    #[test]
+    fn appended_encoding_takes_precedence() {
+        let parent = Path::new("parent_folder");
+        let mut config = Config::from_str("encoding = 'utf-8'\n[libraries]", parent).unwrap();
+        config.append(
+            &Config::from_str("[libraries]", parent).unwrap(),
+            &mut Vec::new(),
+        );
+        assert_eq!(config.encoding(), Encoding::Utf8);
+        config.append(
+            &Config::from_str("encoding = 'latin-1'\n[libraries]", parent).unwrap(),
+            &mut Vec::new(),
+        );
+        assert_eq!(config.encoding(), Encoding::Latin1);
+    }
+
+    #[test]
+    fn standard_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str("standard = '2019'\n[libraries]", parent).unwrap();
+        assert_eq!(config.standard(), VHDLStandard::V2019);
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert_eq!(config.standard(), VHDLStandard::V2008);
+
+        let config = Config::from_str("standard = '2000'\n[libraries]", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Unknown standard '2000', expected one of '1993', '2002', '2008' or '2019'"
+        );
+    }
+
+    #[test]
+    fn ignore_incomplete_std_logic_case_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "ignore_incomplete_std_logic_case = true\n[libraries]",
+            parent,
+        )
+        .unwrap();
+        assert!(config.ignore_incomplete_std_logic_case());
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert!(!config.ignore_incomplete_std_logic_case());
+
+        let config = Config::from_str(
+            "ignore_incomplete_std_logic_case = 'yes'\n[libraries]",
+            parent,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "ignore_incomplete_std_logic_case must be a boolean"
+        );
+    }
+
+    #[test]
+    fn lint_static_assertions_from_str() {
+        let parent = Path::new("parent_folder");
+        let config =
+            Config::from_str("lint_static_assertions = true\n[libraries]", parent).unwrap();
+        assert!(config.lint_static_assertions());
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert!(!config.lint_static_assertions());
+
+        let config = Config::from_str("lint_static_assertions = 1\n[libraries]", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "lint_static_assertions must be a boolean"
+        );
+    }
+
+    #[test]
+    fn lint_constant_conditions_from_str() {
+        let parent = Path::new("parent_folder");
+        let config =
+            Config::from_str("lint_constant_conditions = true\n[libraries]", parent).unwrap();
+        assert!(config.lint_constant_conditions());
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert!(!config.lint_constant_conditions());
+    }
+
+    #[test]
+    fn lint_missing_return_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str("lint_missing_return = true\n[libraries]", parent).unwrap();
+        assert!(config.lint_missing_return());
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert!(!config.lint_missing_return());
+    }
+
+    #[test]
+    fn lint_uninitialized_variables_from_str() {
+        let parent = Path::new("parent_folder");
+        let config =
+            Config::from_str("lint_uninitialized_variables = true\n[libraries]", parent).unwrap();
+        assert!(config.lint_uninitialized_variables());
+
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert!(!config.lint_uninitialized_variables());
+    }
+
+    #[test]
+    fn severity_overrides_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+[diagnostics]
+hint = ['unused_declaration']
+ignore = ['unnecessary_work_library', 'component_mismatch']
+[libraries]
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.severity_overrides(),
+            &FnvHashMap::from_iter([
+                (ErrorCode::UnusedDeclaration, Some(Severity::Hint)),
+                (ErrorCode::UnnecessaryWorkLibrary, None),
+                (ErrorCode::ComponentMismatch, None),
+            ])
+        );
+
+        let config = Config::from_str("[diagnostics]\nhint = ['unused']\n[libraries]", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Unknown error code 'unused' in diagnostics.hint"
+        );
+    }
+
+    #[test]
+    fn conditional_analysis_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+[conditional_analysis]
+TOOL_TYPE = 'SIMULATION'
+TOOL_VENDOR = 'ghdl'
+[libraries]
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.conditional_analysis(),
+            &FnvHashMap::from_iter([
+                ("TOOL_TYPE".to_owned(), "SIMULATION".to_owned()),
+                ("TOOL_VENDOR".to_owned(), "ghdl".to_owned()),
+            ])
+        );
+
+        let config = Config::from_str("[conditional_analysis]\nTOOL_TYPE = 1\n[libraries]", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "conditional_analysis.TOOL_TYPE must be a string"
+        );
+    }
+
+    #[test]
+    fn library_standard_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+standard = '1993'
+
+[libraries]
+lib1.files = []
+lib2.files = []
+lib2.standard = '2019'
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(config.library_standard("lib1"), VHDLStandard::V1993);
+        assert_eq!(config.library_standard("lib2"), VHDLStandard::V2019);
+    }
+
+    #[test]
+    fn synthesis_lint_from_str() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+[libraries]
+rtl.files = []
+rtl.synthesis_lint = true
+tb.files = []
+",
+            parent,
+        )
+        .unwrap();
+        assert!(config.get_library("rtl").unwrap().synthesis_lint());
+        assert!(!config.get_library("tb").unwrap().synthesis_lint());
+
+        let config = Config::from_str(
+            "[libraries]\nrtl.files = []\nrtl.synthesis_lint = 1",
+            parent,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Expected synthesis_lint to be boolean for library rtl"
+        );
+    }
+
+    #[test]
+    fn warns_about_unknown_keys() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let file_name = tempdir.path().join("vhdl_ls.toml");
+        std::fs::write(
+            &file_name,
+            "
+encodin = 'utf-8'
+
+[libraries]
+lib.files = []
+lib.is_thrid_party = true
+
+[libraries.other]
+files = []
+standart = '2008'
+",
+        )
+        .unwrap();
+
+        let mut messages = Vec::new();
+        Config::read_file_path_with_messages(&file_name, &mut messages).unwrap();
+        let file_name = file_name.to_string_lossy();
+        assert_eq!(
+            messages,
+            vec![
+                Message::warning(format!("Unknown key 'encodin' (In file {file_name}:2)")),
+                Message::warning(format!(
+                    "Unknown key 'libraries.lib.is_thrid_party' (In file {file_name}:6)"
+                )),
+                Message::warning(format!(
+                    "Unknown key 'libraries.other.standart' (In file {file_name}:10)"
+                )),
+            ]
+        );
+    }
+
     #[test]
     fn the_work_library_is_an_illegal_library() {
         let parent = Path::new("parent_folder");
@@ -0,0 +1,241 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Tags file of the public symbols in the exuberant ctags format for editors without LSP.
+//!
+//! Every line is `name<TAB>file<TAB>line;"<TAB>kind<TAB>line:N` followed by a `scope:name`
+//! field for declarations within a design unit or protected type. The kind letters are:
+//!
+//! | Kind | Declaration      | Kind | Declaration         |
+//! |------|------------------|------|---------------------|
+//! | `e`  | entity           | `c`  | constant            |
+//! | `a`  | architecture     | `s`  | signal              |
+//! | `p`  | package          | `v`  | variable            |
+//! | `b`  | package body     | `F`  | file                |
+//! | `n`  | configuration    | `g`  | generic             |
+//! | `x`  | context          | `q`  | port                |
+//! | `t`  | type             | `f`  | function            |
+//! | `T`  | subtype          | `r`  | procedure           |
+//! | `C`  | component        | `l`  | enumeration literal |
+//! | `A`  | alias            | `k`  | attribute           |
+//! | `u`  | physical unit    |      |                     |
+
+use crate::ast::{Designator, ObjectClass};
+use crate::named_entity::*;
+use crate::Project;
+use std::path::Path;
+
+/// The relative path of a file if it is within the base directory
+pub(crate) fn file_path(file_name: &Path, base_dir: &Path) -> String {
+    let path = file_name.strip_prefix(base_dir).unwrap_or(file_name);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// The ctags kind letter of a declaration or None if it is not tagged
+fn kind_letter(ent: EntRef) -> Option<char> {
+    let letter = match ent.kind() {
+        AnyEntKind::Design(design) => match design {
+            Design::Entity(..) => 'e',
+            Design::Architecture(..) => 'a',
+            Design::Package(..) | Design::UninstPackage(..) | Design::PackageInstance(..) => 'p',
+            Design::PackageBody => 'b',
+            Design::Configuration => 'n',
+            Design::Context(..) => 'x',
+            Design::InterfacePackageInstance(..) => 'g',
+        },
+        AnyEntKind::Type(Type::Subtype(..)) => 'T',
+        AnyEntKind::Type(..) => 't',
+        AnyEntKind::Component(..) => 'C',
+        AnyEntKind::Object(object) => match (object.class, object.iface.is_some()) {
+            (ObjectClass::Constant, true) => 'g',
+            (ObjectClass::Constant, false) => 'c',
+            (ObjectClass::Signal, true) => 'q',
+            (ObjectClass::Signal, false) => 's',
+            (ObjectClass::Variable | ObjectClass::SharedVariable, _) => 'v',
+        },
+        AnyEntKind::DeferredConstant(..) => 'c',
+        AnyEntKind::File(..) | AnyEntKind::InterfaceFile(..) => 'F',
+        AnyEntKind::Overloaded(Overloaded::EnumLiteral(..)) => 'l',
+        AnyEntKind::Overloaded(Overloaded::Alias(..))
+        | AnyEntKind::ObjectAlias { .. }
+        | AnyEntKind::ExternalAlias { .. } => 'A',
+        AnyEntKind::Overloaded(overloaded) => {
+            if overloaded.signature().return_type().is_some() {
+                'f'
+            } else {
+                'r'
+            }
+        }
+        AnyEntKind::Attribute(..) => 'k',
+        AnyEntKind::PhysicalLiteral(..) => 'u',
+        AnyEntKind::ElementDeclaration(..)
+        | AnyEntKind::Concurrent(..)
+        | AnyEntKind::Sequential(..)
+        | AnyEntKind::LoopParameter(..)
+        | AnyEntKind::Library => return None,
+    };
+    Some(letter)
+}
+
+/// The name of the scope of a declaration within a design unit or protected type
+fn scope_kind(ent: EntRef) -> Option<&'static str> {
+    match ent.kind() {
+        AnyEntKind::Design(design) => Some(match design {
+            Design::Entity(..) => "entity",
+            Design::Architecture(..) => "architecture",
+            Design::Package(..)
+            | Design::UninstPackage(..)
+            | Design::PackageInstance(..)
+            | Design::InterfacePackageInstance(..) => "package",
+            Design::PackageBody => "package_body",
+            Design::Configuration => "configuration",
+            Design::Context(..) => "context",
+        }),
+        AnyEntKind::Type(Type::Protected(..)) => Some("protected"),
+        _ => None,
+    }
+}
+
+impl Project {
+    /// The public symbols as a sorted tags file where file paths below the base directory
+    /// are written relative to it
+    pub fn to_ctags(&self, base_dir: &Path) -> String {
+        let mut lines: Vec<String> = self
+            .public_symbols()
+            .filter(|ent| !ent.is_implicit())
+            .filter_map(|ent| {
+                let name = match ent.designator() {
+                    Designator::Anonymous(_) | Designator::Character(_) => return None,
+                    designator => designator.to_string(),
+                };
+                let kind = kind_letter(ent)?;
+                let pos = ent.decl_pos()?;
+                let lineno = pos.range().start.line + 1;
+                let mut line = format!(
+                    "{name}\t{}\t{lineno};\"\t{kind}\tline:{lineno}",
+                    file_path(pos.file_name(), base_dir)
+                );
+                if let Some(parent) = ent.parent {
+                    if let Some(scope) = scope_kind(parent) {
+                        line.push_str(&format!("\t{scope}:{}", parent.designator()));
+                    }
+                }
+                Some(line)
+            })
+            .collect();
+        lines.sort();
+        lines.dedup();
+
+        let mut tags = format!(
+            "\
+!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/
+!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/
+!_TAG_PROGRAM_NAME\tvhdl_lang\t//
+!_TAG_PROGRAM_URL\thttps://github.com/VHDL-LS/rust_hdl\t//
+!_TAG_PROGRAM_VERSION\t{}\t//
+",
+            env!("CARGO_PKG_VERSION")
+        );
+        for line in lines {
+            tags.push_str(&line);
+            tags.push('\n');
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Project};
+    use std::path::Path;
+
+    fn analyze_fixture(root: &Path) -> Project {
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src").join("pkg.vhd"),
+            "\
+package pkg is
+  type state_t is (idle, busy);
+  constant width : natural := 8;
+  function f(x : natural) return natural;
+end package;
+
+package body pkg is
+  function f(x : natural) return natural is
+  begin
+    return x;
+  end function;
+end package body;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src").join("ent.vhd"),
+            "\
+entity ent is
+  generic (g : natural);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of ent is
+  signal s : bit;
+begin
+end architecture;
+",
+        )
+        .unwrap();
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{}/../vhdl_libraries/std/standard.vhd']
+lib.files = ['src/*.vhd']
+",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let config = Config::from_str(&config_str, root).unwrap();
+        let mut project = Project::from_config(config, &mut Vec::new());
+        project.analyse();
+        project
+    }
+
+    #[test]
+    fn ctags_of_fixture() {
+        let root = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(root.path()).unwrap();
+        let project = analyze_fixture(&root);
+        let tags = project.to_ctags(&root);
+        let lines: Vec<&str> = tags.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/"
+        );
+        assert_eq!(
+            lines[1],
+            "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/"
+        );
+
+        let tag_lines = &lines[5..];
+        assert!(tag_lines.windows(2).all(|pair| pair[0] <= pair[1]));
+        for expected in [
+            "ent\tsrc/ent.vhd\t1;\"\te\tline:1",
+            "g\tsrc/ent.vhd\t2;\"\tg\tline:2\tentity:ent",
+            "clk\tsrc/ent.vhd\t3;\"\tq\tline:3\tentity:ent",
+            "rtl\tsrc/ent.vhd\t6;\"\ta\tline:6\tentity:ent",
+            "pkg\tsrc/pkg.vhd\t1;\"\tp\tline:1",
+            "pkg\tsrc/pkg.vhd\t7;\"\tb\tline:7",
+            "state_t\tsrc/pkg.vhd\t2;\"\tt\tline:2\tpackage:pkg",
+            "idle\tsrc/pkg.vhd\t2;\"\tl\tline:2",
+            "width\tsrc/pkg.vhd\t3;\"\tc\tline:3\tpackage:pkg",
+            "f\tsrc/pkg.vhd\t4;\"\tf\tline:4\tpackage:pkg",
+        ] {
+            assert!(
+                tag_lines.contains(&expected),
+                "Missing tag line {expected:?} in\n{tags}"
+            );
+        }
+    }
+}
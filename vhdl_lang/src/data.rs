@@ -7,6 +7,7 @@
 
 mod contents;
 mod diagnostic;
+mod error_codes;
 mod latin_1;
 mod message;
 mod source;
@@ -14,6 +15,7 @@ mod symbol_table;
 
 pub use contents::*;
 pub use diagnostic::*;
+pub use error_codes::*;
 pub use latin_1::*;
 pub use message::*;
 pub use source::*;
@@ -4,31 +4,84 @@
 //
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
-use super::latin_1::{char_to_latin1, Latin1String, Utf8ToLatin1Error};
+use super::latin_1::{char_to_latin1, iso_8859_1_to_utf8, Latin1String, Utf8ToLatin1Error};
 use super::source::{Position, Range};
 use std::fs::File;
 use std::io;
 use std::io::prelude::Read;
 use std::path::Path;
 
+/// The character encoding used when reading a source file from disk.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Encoding {
+    /// Use UTF-8 if the file starts with a byte order mark or is valid UTF-8,
+    /// otherwise assume Latin-1.
+    #[default]
+    Auto,
+    Utf8,
+    Latin1,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        match name.to_lowercase().as_str() {
+            "auto" => Some(Encoding::Auto),
+            "utf-8" | "utf8" => Some(Encoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Some(Encoding::Latin1),
+            _ => None,
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+
+/// Decode the raw bytes of a file into an UTF-8 string.
+///
+/// Characters outside of the Latin-1 range are kept as is,
+/// they are reported as errors by the tokenizer.
+fn decode(bytes: Vec<u8>, encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Auto => {
+            if let Some(bytes) = bytes.strip_prefix(UTF8_BOM) {
+                String::from_utf8_lossy(bytes).into_owned()
+            } else {
+                String::from_utf8(bytes).unwrap_or_else(|err| iso_8859_1_to_utf8(err.as_bytes()))
+            }
+        }
+        Encoding::Utf8 => {
+            let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(&bytes);
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+        Encoding::Latin1 => iso_8859_1_to_utf8(&bytes),
+    }
+}
+
 pub struct Contents {
     lines: Vec<String>,
+    /// The byte offset of the start of every line within the UTF-8 text,
+    /// kept in sync with the lines so offsets do not need to sum up all previous lines
+    line_starts: Vec<usize>,
 }
 
 impl Contents {
     pub fn from_latin1_file(file_name: &Path) -> io::Result<Contents> {
+        Self::from_file(file_name, Encoding::Latin1)
+    }
+
+    pub fn from_file(file_name: &Path, encoding: Encoding) -> io::Result<Contents> {
         let mut file = File::open(file_name)?;
         let mut bytes = Vec::new();
         file.read_to_end(&mut bytes)?;
-        Ok(Contents::from_str(
-            &Latin1String::from_vec(bytes).to_string(),
-        ))
+        Ok(Contents::from_str(&decode(bytes, encoding)))
     }
 
     pub fn from_str(code: &str) -> Contents {
-        Contents {
-            lines: split_lines(code),
-        }
+        Contents::from_lines(split_lines(code))
+    }
+
+    fn from_lines(lines: Vec<String>) -> Contents {
+        let line_starts = line_starts(0, &lines).collect();
+        Contents { lines, line_starts }
     }
 
     pub fn start(&self) -> Position {
@@ -65,9 +118,7 @@ impl Contents {
             }
         }
 
-        Contents {
-            lines: split_lines(&result),
-        }
+        Contents::from_str(&result)
     }
 
     pub fn num_lines(&self) -> usize {
@@ -78,57 +129,204 @@ impl Contents {
         self.lines.get(lineno).map(|string| string.as_str())
     }
 
-    pub fn change(&mut self, range: &Range, content: &str) {
+    /// The number of lines, a trailing newline does not start another line
+    pub fn line_count(&self) -> usize {
+        self.num_lines()
+    }
+
+    /// The length of the line in UTF-16 code units excluding the newline,
+    /// zero for lines past the end of the contents
+    pub fn line_len(&self, lineno: usize) -> usize {
+        self.get_line(lineno)
+            .map(|line| {
+                let line = line.strip_suffix('\n').unwrap_or(line);
+                line.chars().map(|chr| chr.len_utf16()).sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /// The UTF-8 text covered by the range including any newlines within it.
+    /// Positions are clamped like in `position_to_offset` and a reversed range is empty.
+    pub fn range_str(&self, range: &Range) -> String {
+        let mut result = String::new();
         if self.lines.is_empty() {
-            self.lines = split_lines(content);
-            return;
+            return result;
         }
 
-        let Range { start, end } = range;
-
-        let start_char = start.character as usize;
-        let end_char = end.character as usize;
-        let start_line = start.line as usize;
-        let end_line = end.line as usize;
-        let mut merged_content = String::new();
+        let (start_line, start_idx) = self.line_offset(range.start);
+        let (end_line, end_idx) = self.line_offset(range.end);
+        if (start_line, start_idx) >= (end_line, end_idx) {
+            return result;
+        }
 
-        if let Some(line) = self.lines.get(start_line) {
-            let mut i = 0;
-            for chr in line.chars() {
-                if i < start_char {
-                    merged_content.push(chr);
-                } else {
-                    break;
-                };
-                i += chr.len_utf16();
+        if start_line == end_line {
+            result.push_str(&self.lines[start_line][start_idx..end_idx]);
+        } else {
+            result.push_str(&self.lines[start_line][start_idx..]);
+            for line in &self.lines[start_line + 1..end_line] {
+                result.push_str(line);
             }
+            result.push_str(&self.lines[end_line][..end_idx]);
+        }
+        result
+    }
+
+    /// The text covered by the range as Latin-1, see `range_str`.
+    /// Characters that cannot be represented in Latin-1 are replaced by `?`.
+    pub fn range_text(&self, range: &Range) -> Latin1String {
+        Latin1String::from_vec(
+            self.range_str(range)
+                .chars()
+                .map(|chr| char_to_latin1(chr).unwrap_or(b'?'))
+                .collect(),
+        )
+    }
+
+    /// The byte offset of the position within the UTF-8 text of the contents.
+    /// Positions past the end of a line or past the last line are clamped.
+    pub fn position_to_offset(&self, pos: Position) -> usize {
+        if self.lines.is_empty() {
+            return 0;
+        }
+        let (lineno, idx) = self.line_offset(pos);
+        self.line_starts[lineno] + idx
+    }
+
+    /// The position of a byte offset within the UTF-8 text of the contents.
+    /// An offset within a multibyte character refers to the start of that character
+    /// and offsets past the end refer to the end of the contents.
+    pub fn offset_to_position(&self, offset: usize) -> Position {
+        if offset < self.len() {
+            let lineno = self.line_starts.partition_point(|start| *start <= offset) - 1;
+            let line = &self.lines[lineno];
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            return Position::new(
+                lineno as u32,
+                utf16_len_before(line, offset - self.line_starts[lineno]),
+            );
         }
-        merged_content.push_str(content);
 
-        if let Some(line) = self.lines.get(end_line) {
-            let mut i = 0;
-            for chr in line.chars() {
-                if i >= end_char {
-                    merged_content.push(chr);
-                };
-                i += chr.len_utf16();
+        match self.lines.last() {
+            Some(line) if line.ends_with('\n') => Position::new(self.lines.len() as u32, 0),
+            Some(line) => Position::new(
+                self.lines.len() as u32 - 1,
+                utf16_len_before(line, line.len()),
+            ),
+            None => self.start(),
+        }
+    }
+
+    pub fn range_to_offsets(&self, range: &Range) -> (usize, usize) {
+        (
+            self.position_to_offset(range.start),
+            self.position_to_offset(range.end),
+        )
+    }
+
+    /// The column of the position counted in UTF-8 bytes from the start of its line
+    pub fn utf8_column(&self, pos: Position) -> usize {
+        let line_start = self.position_to_offset(Position::new(pos.line, 0));
+        self.position_to_offset(pos) - line_start
+    }
+
+    /// The position of a column counted in UTF-8 bytes from the start of the line
+    pub fn position_from_utf8_column(&self, line: u32, column: usize) -> Position {
+        let line_start = self.position_to_offset(Position::new(line, 0));
+        match self.get_line(line as usize) {
+            Some(text) => {
+                let text = text.strip_suffix('\n').unwrap_or(text);
+                Position::new(line, utf16_len_before(text, column.min(text.len())))
             }
+            None => self.offset_to_position(line_start),
         }
+    }
 
-        let last_line_index = self.lines.len() - 1;
-        if (end.line as usize) < last_line_index
-            && merged_content.chars().last().unwrap_or('\0') != '\n'
-        {
-            merged_content.push('\n');
+    /// Replace the text within the range with the content.
+    /// Positions past the end of a line refer to the end of that line and positions past the
+    /// last line refer to the end of the contents, a reversed range is treated as if
+    /// the start and end were swapped.
+    pub fn change(&mut self, range: &Range, content: &str) {
+        if self.lines.is_empty() {
+            *self = Contents::from_str(content);
+            return;
         }
 
-        let end_line = std::cmp::min(self.lines.len().saturating_sub(1), end_line);
-        self.lines
-            .splice(start_line..=end_line, split_lines(&merged_content))
+        let mut start = self.line_offset(range.start);
+        let mut end = self.line_offset(range.end);
+        if start > end {
+            std::mem::swap(&mut start, &mut end);
+        }
+        let (start_line, start_idx) = start;
+        let (end_line, end_idx) = end;
+
+        let mut merged_content = String::new();
+        merged_content.push_str(&self.lines[start_line][..start_idx]);
+        merged_content.push_str(content);
+        merged_content.push_str(&self.lines[end_line][end_idx..]);
+
+        let old_len =
+            self.line_starts[end_line] + self.lines[end_line].len() - self.line_starts[start_line];
+        let new_lines = split_lines(&merged_content);
+        let new_starts: Vec<usize> =
+            line_starts(self.line_starts[start_line], &new_lines).collect();
+        let first_after = start_line + new_lines.len();
+
+        self.lines.splice(start_line..=end_line, new_lines).count();
+        self.line_starts
+            .splice(start_line..=end_line, new_starts)
             .count();
+
+        // The lines after the change keep their text and only move by the change in length
+        for line_start in &mut self.line_starts[first_after..] {
+            *line_start = *line_start - old_len + merged_content.len();
+        }
+    }
+
+    /// The length in bytes of the UTF-8 text
+    fn len(&self) -> usize {
+        match (self.line_starts.last(), self.lines.last()) {
+            (Some(start), Some(line)) => start + line.len(),
+            _ => 0,
+        }
+    }
+
+    /// The line index and byte offset within that line of the position.
+    /// Requires the contents to be non-empty.
+    fn line_offset(&self, pos: Position) -> (usize, usize) {
+        let Some(line) = self.lines.get(pos.line as usize) else {
+            let last_line = self.lines.len() - 1;
+            return (last_line, self.lines[last_line].len());
+        };
+
+        let content = line.strip_suffix('\n').unwrap_or(line);
+        let mut character = 0;
+        for (idx, chr) in content.char_indices() {
+            if character >= pos.character as usize {
+                return (pos.line as usize, idx);
+            }
+            character += chr.len_utf16();
+        }
+        (pos.line as usize, content.len())
     }
 }
 
+/// The number of UTF-16 code units of the characters that end before the byte offset
+fn utf16_len_before(line: &str, offset: usize) -> u32 {
+    line.char_indices()
+        .take_while(|(idx, chr)| idx + chr.len_utf8() <= offset)
+        .map(|(_, chr)| chr.len_utf16() as u32)
+        .sum()
+}
+
+/// The byte offsets of the starts of the lines when the first line starts at the offset
+fn line_starts(offset: usize, lines: &[String]) -> impl Iterator<Item = usize> + '_ {
+    lines.iter().scan(offset, |start, line| {
+        let line_start = *start;
+        *start += line.len();
+        Some(line_start)
+    })
+}
+
 /// Split code into several lines
 fn split_lines(code: &str) -> Vec<String> {
     let mut lines = Vec::new();
@@ -346,6 +544,65 @@ mod tests {
         Contents::from_str(code)
     }
 
+    fn text(contents: &Contents, start: (u32, u32), end: (u32, u32)) -> String {
+        let range = Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1));
+        contents.range_text(&range).to_string()
+    }
+
+    #[test]
+    fn range_text_within_line() {
+        let contents = new("hello world\nfoo\n");
+        assert_eq!(text(&contents, (0, 6), (0, 11)), "world");
+        assert_eq!(text(&contents, (1, 0), (1, 3)), "foo");
+        // The end is clamped to the end of the line
+        assert_eq!(text(&contents, (1, 1), (1, 100)), "oo");
+    }
+
+    #[test]
+    fn range_text_across_lines() {
+        let contents = new("abc\r\ndef\nghi");
+        assert_eq!(text(&contents, (0, 1), (2, 2)), "bc\ndef\ngh");
+        // The end of a line includes its newline when the range continues
+        assert_eq!(text(&contents, (0, 3), (1, 0)), "\n");
+    }
+
+    #[test]
+    fn range_text_until_end_of_contents() {
+        let contents = new("abc\ndef");
+        let end = contents.end();
+        assert_eq!(text(&contents, (1, 1), (end.line, end.character)), "ef");
+        // Positions past the last line are clamped to the end
+        assert_eq!(text(&contents, (0, 2), (10, 0)), "c\ndef");
+
+        let contents = new("abc\n");
+        assert_eq!(text(&contents, (0, 1), (1, 0)), "bc\n");
+    }
+
+    #[test]
+    fn range_text_empty_range() {
+        let contents = new("abc\ndef");
+        assert_eq!(text(&contents, (1, 1), (1, 1)), "");
+        assert_eq!(text(&contents, (1, 2), (0, 1)), "");
+        assert_eq!(text(&new(""), (0, 0), (0, 1)), "");
+    }
+
+    #[test]
+    fn range_text_non_latin1() {
+        let contents = new("å€b");
+        assert_eq!(text(&contents, (0, 0), (0, 3)), "å?b");
+        assert_eq!(contents.range_str(&contents.range()), "å€b");
+    }
+
+    #[test]
+    fn line_count_and_len() {
+        let contents = new("ab€\r\n\nlast");
+        assert_eq!(contents.line_count(), 3);
+        assert_eq!(contents.line_len(0), 3);
+        assert_eq!(contents.line_len(1), 0);
+        assert_eq!(contents.line_len(2), 4);
+        assert_eq!(contents.line_len(3), 0);
+    }
+
     fn reader(contents: &Contents) -> ContentReader {
         ContentReader::new(contents)
     }
@@ -446,6 +703,28 @@ mod tests {
         assert_eq!(reader.pop_char(), None);
     }
 
+    #[test]
+    fn crlf_and_cr_line_endings() {
+        for code in ["1\r\n2\r\n3", "1\r2\r3", "1\r\n2\r3", "1\n2\r\n3"] {
+            let contents = new(code);
+            assert_eq!(contents.num_lines(), 3);
+            assert_eq!(contents.get_line(0), Some("1\n"));
+            assert_eq!(contents.get_line(1), Some("2\n"));
+            assert_eq!(contents.get_line(2), Some("3"));
+            assert_eq!(contents.end(), Position::new(2, 1));
+        }
+    }
+
+    #[test]
+    fn change_with_crlf_line_endings() {
+        let mut contents = new("hello\r\nworld\r\n");
+        contents.change(
+            &Range::new(Position::new(0, 5), Position::new(1, 0)),
+            "\r\nnew\r\n",
+        );
+        assert_eq!(flatten(&contents), "hello\nnew\nworld\n");
+    }
+
     #[test]
     fn matches() {
         let contents = new("abc");
@@ -479,6 +758,42 @@ mod tests {
         result
     }
 
+    #[test]
+    fn decode_utf8_with_bom() {
+        let bytes = [UTF8_BOM, "åäö".as_bytes()].concat();
+        assert_eq!(decode(bytes.clone(), Encoding::Auto), "åäö");
+        assert_eq!(decode(bytes, Encoding::Utf8), "åäö");
+    }
+
+    #[test]
+    fn decode_utf8_without_bom() {
+        let bytes = "signal åäö".as_bytes().to_vec();
+        assert_eq!(decode(bytes.clone(), Encoding::Auto), "signal åäö");
+        assert_eq!(decode(bytes, Encoding::Utf8), "signal åäö");
+    }
+
+    #[test]
+    fn decode_latin1() {
+        let bytes = vec![b'a', 0xe5, b'b'];
+        assert_eq!(decode(bytes.clone(), Encoding::Auto), "aåb");
+        assert_eq!(decode(bytes, Encoding::Latin1), "aåb");
+    }
+
+    #[test]
+    fn decode_forced_latin1_of_utf8() {
+        // å encoded as UTF-8 is two Latin-1 characters
+        let bytes = "å".as_bytes().to_vec();
+        assert_eq!(decode(bytes, Encoding::Latin1), "Ã¥");
+    }
+
+    #[test]
+    fn encoding_from_name() {
+        assert_eq!(Encoding::from_name("auto"), Some(Encoding::Auto));
+        assert_eq!(Encoding::from_name("UTF-8"), Some(Encoding::Utf8));
+        assert_eq!(Encoding::from_name("latin-1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("ebcdic"), None);
+    }
+
     #[test]
     fn change_first() {
         let mut contents = new("hello");
@@ -607,4 +922,158 @@ mod tests {
         assert_eq!(contents.num_lines(), 1);
         assert_eq!(contents.get_line(0).unwrap().to_string(), "a\n");
     }
+
+    #[test]
+    fn change_end_beyond_last_line() {
+        let mut contents = new("hello\nworld\n");
+        contents.change(&Range::new(Position::new(1, 2), Position::new(5, 3)), "");
+        assert_eq!(flatten(&contents), "hello\nwo");
+    }
+
+    #[test]
+    fn change_start_beyond_last_line_appends() {
+        let mut contents = new("hello\nworld");
+        contents.change(&Range::new(Position::new(7, 1), Position::new(9, 0)), "!");
+        assert_eq!(flatten(&contents), "hello\nworld!");
+    }
+
+    #[test]
+    fn change_reversed_range() {
+        let mut contents = new("hello\nworld");
+        contents.change(&Range::new(Position::new(1, 2), Position::new(0, 3)), "_");
+        assert_eq!(flatten(&contents), "hel_rld");
+    }
+
+    /// Minimal xorshift generator to get reproducible random edits without dependencies
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, max: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % max as u64) as usize
+        }
+
+        fn text(&mut self) -> String {
+            const ALPHABET: [&str; 6] = ["a", "b", "ä", "\n", "\u{1F4A3}", "\n\n"];
+            (0..self.next(12))
+                .map(|_| ALPHABET[self.next(ALPHABET.len())])
+                .collect()
+        }
+
+        fn position(&mut self) -> Position {
+            Position::new(self.next(6) as u32, self.next(8) as u32)
+        }
+    }
+
+    /// Reference implementation of a change using a single string
+    fn reference_change(text: &str, range: &Range, content: &str) -> String {
+        let offset = |pos: Position| {
+            let mut line_start = 0;
+            for (lineno, line) in text.split_inclusive('\n').enumerate() {
+                if lineno == pos.line as usize {
+                    let line = line.strip_suffix('\n').unwrap_or(line);
+                    let mut character = 0;
+                    for (idx, chr) in line.char_indices() {
+                        if character >= pos.character as usize {
+                            return line_start + idx;
+                        }
+                        character += chr.len_utf16();
+                    }
+                    return line_start + line.len();
+                }
+                line_start += line.len();
+            }
+            text.len()
+        };
+
+        let start = offset(range.start);
+        let end = offset(range.end);
+        let (start, end) = (start.min(end), start.max(end));
+        format!("{}{}{}", &text[..start], content, &text[end..])
+    }
+
+    #[test]
+    fn random_changes_match_reference() {
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+        for _ in 0..2000 {
+            let mut text = random.text();
+            let mut contents = new(&text);
+            for _ in 0..5 {
+                let range = Range::new(random.position(), random.position());
+                let content = random.text();
+                contents.change(&range, &content);
+                text = reference_change(&text, &range, &content);
+                assert_eq!(flatten(&contents), text, "after change of {range:?}");
+                assert_eq!(
+                    contents.line_starts,
+                    line_starts(0, &contents.lines).collect::<Vec<_>>(),
+                    "after change of {range:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn offsets_with_multibyte_characters() {
+        // In UTF-8 'å', 'ä' and 'ö' take two bytes while they are one UTF-16 code unit
+        // and one byte in Latin-1, the bomb emojii takes four bytes and two UTF-16 code units
+        let bomb = '\u{1F4A3}';
+        let contents = new(&format!("aåb\näö{bomb}c\n"));
+
+        let positions = [
+            (0, Position::new(0, 0)),
+            (1, Position::new(0, 1)),
+            (3, Position::new(0, 2)),
+            (4, Position::new(0, 3)),
+            (5, Position::new(1, 0)),
+            (7, Position::new(1, 1)),
+            (9, Position::new(1, 2)),
+            (13, Position::new(1, 4)),
+            (14, Position::new(1, 5)),
+            (15, Position::new(2, 0)),
+        ];
+        for (offset, pos) in positions {
+            assert_eq!(contents.offset_to_position(offset), pos);
+            assert_eq!(contents.position_to_offset(pos), offset);
+        }
+
+        // Within a multibyte character
+        assert_eq!(contents.offset_to_position(2), Position::new(0, 1));
+        assert_eq!(contents.offset_to_position(11), Position::new(1, 2));
+    }
+
+    #[test]
+    fn offsets_are_clamped() {
+        let contents = new("aå\nb");
+        assert_eq!(contents.offset_to_position(100), Position::new(1, 1));
+        assert_eq!(contents.position_to_offset(Position::new(0, 10)), 3);
+        assert_eq!(contents.position_to_offset(Position::new(5, 0)), 5);
+        assert_eq!(
+            contents.range_to_offsets(&Range::new(Position::new(0, 1), Position::new(9, 9))),
+            (1, 5)
+        );
+
+        let empty = new("");
+        assert_eq!(empty.offset_to_position(3), Position::new(0, 0));
+        assert_eq!(empty.position_to_offset(Position::new(1, 1)), 0);
+    }
+
+    #[test]
+    fn utf8_columns() {
+        let contents = new("x\nsignal åäö : bit;");
+        // The UTF-16 and Latin-1 column of ':' is 11 while the UTF-8 column is 14
+        let colon = Position::new(1, 11);
+        assert_eq!(contents.utf8_column(colon), 14);
+        assert_eq!(contents.position_from_utf8_column(1, 14), colon);
+        assert_eq!(
+            contents.position_from_utf8_column(1, 100),
+            Position::new(1, 17)
+        );
+        assert_eq!(
+            contents.position_from_utf8_column(5, 0),
+            Position::new(1, 17)
+        );
+    }
 }
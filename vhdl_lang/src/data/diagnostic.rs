@@ -4,10 +4,13 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::SrcPos;
+use super::{ErrorCode, Source, SrcPos};
+use fnv::{FnvHashMap, FnvHashSet};
 use std::convert::{AsRef, Into};
+use std::fmt;
 
-#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash)]
+/// The severity of a diagnostic, ordered from least to most severe.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash, PartialOrd, Ord)]
 pub enum Severity {
     Hint,
     Info,
@@ -15,6 +18,23 @@ pub enum Severity {
     Error,
 }
 
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 #[must_use]
 #[derive(PartialEq, Debug, Clone, Eq, Hash)]
 pub struct Diagnostic {
@@ -22,6 +42,7 @@ pub struct Diagnostic {
     pub message: String,
     pub severity: Severity,
     pub related: Vec<(SrcPos, String)>,
+    pub code: Option<ErrorCode>,
 }
 
 impl Diagnostic {
@@ -31,6 +52,7 @@ impl Diagnostic {
             message: msg.into(),
             severity,
             related: vec![],
+            code: None,
         }
     }
 
@@ -56,9 +78,23 @@ impl Diagnostic {
             pos: self.pos,
             severity: self.severity,
             related: vec![],
+            code: self.code,
+        }
+    }
+
+    pub fn with_code(self, code: ErrorCode) -> Diagnostic {
+        Diagnostic {
+            code: Some(code),
+            ..self
         }
     }
 
+    /// True if the diagnostic is within a `translate_off` region, such code is ignored by
+    /// synthesis tools
+    pub fn in_translate_off(&self) -> bool {
+        self.pos.source.is_translated_off(self.pos.start())
+    }
+
     pub fn related(self, item: impl AsRef<SrcPos>, message: impl Into<String>) -> Diagnostic {
         let mut diagnostic = self;
         diagnostic.add_related(item, message);
@@ -95,20 +131,25 @@ impl Diagnostic {
         diagnostics
     }
 
+    /// The first line of the diagnostic such as `error[duplicate_decl]: Duplicate declaration of 'x'`
+    fn headline(&self) -> String {
+        if let Some(code) = self.code {
+            format!("{}[{}]: {}", self.severity, code, self.message)
+        } else {
+            format!("{}: {}", self.severity, self.message)
+        }
+    }
+
+    /// The diagnostic with the context lines of its position followed by the related
+    /// positions and their messages
     pub fn show(&self) -> String {
-        let mut result = String::new();
-        for (pos, message) in self.related.iter() {
-            result.push_str(&pos.show(&format!("related: {message}")));
-            result.push('\n');
-        }
-        let severity = match self.severity {
-            Severity::Error => &"error",
-            Severity::Warning => &"warning",
-            Severity::Info => &"info",
-            Severity::Hint => &"hint",
-        };
-        result.push_str(&self.pos.show(&format!("{}: {}", severity, self.message)));
-        result
+        self.pos.show_with_related(&self.headline(), &self.related)
+    }
+
+    /// Same as show but with ANSI colors for printing to a terminal
+    pub fn show_colored(&self) -> String {
+        self.pos
+            .show_with_related_colored(&self.headline(), &self.related, self.severity)
     }
 }
 
@@ -168,6 +209,216 @@ impl DiagnosticHandler for NullDiagnostics {
     }
 }
 
+/// Wraps a diagnostic handler and drops diagnostics that are identical in position,
+/// message and severity to a diagnostic that was already pushed.
+/// Optionally limits the number of diagnostics per source file, the suppressed diagnostics
+/// are summarized by a single warning per source file when calling `finish`.
+pub struct DeduplicatingDiagnosticHandler<'a> {
+    inner: &'a mut dyn DiagnosticHandler,
+    max_per_source: Option<usize>,
+    seen: FnvHashSet<(SrcPos, String, Severity)>,
+    num_per_source: FnvHashMap<Source, usize>,
+    /// The first suppressed position and the number of suppressed diagnostics per source
+    suppressed: FnvHashMap<Source, (SrcPos, usize)>,
+}
+
+impl<'a> DeduplicatingDiagnosticHandler<'a> {
+    pub fn new(inner: &'a mut dyn DiagnosticHandler) -> DeduplicatingDiagnosticHandler<'a> {
+        DeduplicatingDiagnosticHandler {
+            inner,
+            max_per_source: None,
+            seen: FnvHashSet::default(),
+            num_per_source: FnvHashMap::default(),
+            suppressed: FnvHashMap::default(),
+        }
+    }
+
+    pub fn with_max_per_source(self, max_per_source: usize) -> DeduplicatingDiagnosticHandler<'a> {
+        DeduplicatingDiagnosticHandler {
+            max_per_source: Some(max_per_source),
+            ..self
+        }
+    }
+
+    /// Push a summary of the diagnostics that were suppressed due to the limit per source file
+    pub fn finish(self) {
+        let mut suppressed: Vec<_> = self.suppressed.into_values().collect();
+        suppressed.sort_by(|(pos1, _), (pos2, _)| pos1.cmp(pos2));
+
+        for (pos, num_suppressed) in suppressed {
+            self.inner.push(Diagnostic::warning(
+                pos,
+                format!("{num_suppressed} more diagnostics suppressed"),
+            ));
+        }
+    }
+}
+
+impl<'a> DiagnosticHandler for DeduplicatingDiagnosticHandler<'a> {
+    fn push(&mut self, diagnostic: Diagnostic) {
+        let key = (
+            diagnostic.pos.clone(),
+            diagnostic.message.clone(),
+            diagnostic.severity,
+        );
+        if !self.seen.insert(key) {
+            return;
+        }
+
+        let source = &diagnostic.pos.source;
+        if let Some(max_per_source) = self.max_per_source {
+            let num = self.num_per_source.entry(source.clone()).or_default();
+            if *num >= max_per_source {
+                self.suppressed
+                    .entry(source.clone())
+                    .or_insert_with(|| (diagnostic.pos.clone(), 0))
+                    .1 += 1;
+                return;
+            }
+            *num += 1;
+        }
+
+        self.inner.push(diagnostic);
+    }
+}
+
+/// Wraps a diagnostic handler and changes the severity of diagnostics by their error code.
+/// Diagnostics whose code maps to `None` are dropped.
+pub struct SeverityOverridingDiagnosticHandler<'a> {
+    inner: &'a mut dyn DiagnosticHandler,
+    overrides: &'a FnvHashMap<ErrorCode, Option<Severity>>,
+}
+
+impl<'a> SeverityOverridingDiagnosticHandler<'a> {
+    pub fn new(
+        inner: &'a mut dyn DiagnosticHandler,
+        overrides: &'a FnvHashMap<ErrorCode, Option<Severity>>,
+    ) -> SeverityOverridingDiagnosticHandler<'a> {
+        SeverityOverridingDiagnosticHandler { inner, overrides }
+    }
+}
+
+impl DiagnosticHandler for SeverityOverridingDiagnosticHandler<'_> {
+    fn push(&mut self, diagnostic: Diagnostic) {
+        let Some(severity) = diagnostic.code.and_then(|code| self.overrides.get(&code)) else {
+            self.inner.push(diagnostic);
+            return;
+        };
+        if let Some(severity) = severity {
+            self.inner.push(Diagnostic {
+                severity: *severity,
+                ..diagnostic
+            });
+        }
+    }
+}
+
+/// Diagnostics grouped by the source file of their position.
+/// The files are ordered by file name.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct DiagnosticReport {
+    files: Vec<(Source, Vec<Diagnostic>)>,
+}
+
+impl DiagnosticReport {
+    /// Group the diagnostics by file, keeping their order within each file
+    pub fn new(diagnostics: impl IntoIterator<Item = Diagnostic>) -> DiagnosticReport {
+        let mut diagnostics: Vec<_> = diagnostics.into_iter().collect();
+        diagnostics.sort_by(|diag1, diag2| diag1.pos.source.cmp(&diag2.pos.source));
+
+        let mut files: Vec<(Source, Vec<Diagnostic>)> = Vec::new();
+        for diagnostic in diagnostics {
+            match files.last_mut() {
+                Some((source, file_diagnostics)) if *source == diagnostic.pos.source => {
+                    file_diagnostics.push(diagnostic)
+                }
+                _ => files.push((diagnostic.pos.source.clone(), vec![diagnostic])),
+            }
+        }
+        DiagnosticReport { files }
+    }
+
+    pub fn by_file(&self) -> impl Iterator<Item = (&Source, &[Diagnostic])> {
+        self.files
+            .iter()
+            .map(|(source, diagnostics)| (source, diagnostics.as_slice()))
+    }
+
+    /// Order the diagnostics within each file by start position and then by end position
+    pub fn sorted(mut self) -> DiagnosticReport {
+        for (_, diagnostics) in self.files.iter_mut() {
+            diagnostics.sort_by_key(|diagnostic| {
+                let range = diagnostic.pos.range();
+                (range.start, range.end)
+            });
+        }
+        self
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.files
+            .iter()
+            .flat_map(|(_, diagnostics)| diagnostics.iter())
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    /// The exit code of a command line tool, which only fails when there are errors
+    pub fn exit_code(&self) -> i32 {
+        if self.error_count() > 0 {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Summarize the number of errors and warnings such as `1 error, 2 warnings`
+    pub fn summary(&self) -> String {
+        format!(
+            "{}, {}",
+            pluralize(self.error_count(), "error"),
+            pluralize(self.warning_count(), "warning")
+        )
+    }
+
+    /// Summarize the number of errors and warnings of a single file
+    pub fn file_summary(diagnostics: &[Diagnostic]) -> String {
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        format!(
+            "{}, {}",
+            pluralize(count(Severity::Error), "error"),
+            pluralize(count(Severity::Warning), "warning")
+        )
+    }
+}
+
+impl From<Vec<Diagnostic>> for DiagnosticReport {
+    fn from(diagnostics: Vec<Diagnostic>) -> DiagnosticReport {
+        DiagnosticReport::new(diagnostics)
+    }
+}
+
+fn pluralize(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
 #[cfg(test)]
 pub struct NoDiagnostics;
 
@@ -218,6 +469,118 @@ error: Greetings
         );
     }
 
+    #[test]
+    fn show_error_code() {
+        let code = Code::new_with_file_name(Path::new("{unknown file}"), "hello\nworld\nline\n");
+        assert_eq!(
+            Diagnostic::error(code.s1("world"), "Greetings")
+                .with_code(ErrorCode::DuplicateDecl)
+                .show(),
+            "\
+error[duplicate_decl]: Greetings
+  --> {unknown file}:2
+   |
+1  |  hello
+2 --> world
+   |  ~~~~~
+3  |  line
+"
+        );
+    }
+
+    #[test]
+    fn deduplicate_identical_diagnostics() {
+        let code = Code::new("hello\nworld\n");
+        let mut diagnostics = Vec::new();
+        let mut handler = DeduplicatingDiagnosticHandler::new(&mut diagnostics);
+        handler.push(Diagnostic::error(code.s1("hello"), "msg"));
+        handler.push(Diagnostic::error(code.s1("hello"), "msg"));
+        handler.push(Diagnostic::warning(code.s1("hello"), "msg"));
+        handler.push(Diagnostic::error(code.s1("hello"), "other msg"));
+        handler.push(Diagnostic::error(code.s1("world"), "msg"));
+        handler.push(Diagnostic::error(code.s1("world"), "msg"));
+        handler.finish();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("hello"), "msg"),
+                Diagnostic::warning(code.s1("hello"), "msg"),
+                Diagnostic::error(code.s1("hello"), "other msg"),
+                Diagnostic::error(code.s1("world"), "msg"),
+            ]
+        );
+    }
+
+    #[test]
+    fn deduplicate_with_max_per_source() {
+        let code = Code::new("a b c d\n");
+        let other = Code::new("e\n");
+        let mut diagnostics = Vec::new();
+        let mut handler =
+            DeduplicatingDiagnosticHandler::new(&mut diagnostics).with_max_per_source(2);
+        for name in ["a", "a", "b", "c", "d"] {
+            handler.push(Diagnostic::error(code.s1(name), "msg"));
+        }
+        handler.push(Diagnostic::error(other.s1("e"), "msg"));
+        handler.finish();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("a"), "msg"),
+                Diagnostic::error(code.s1("b"), "msg"),
+                Diagnostic::error(other.s1("e"), "msg"),
+                Diagnostic::warning(code.s1("c"), "2 more diagnostics suppressed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn override_severity_by_code() {
+        let code = Code::new("a b c\n");
+        let overrides = FnvHashMap::from_iter([
+            (ErrorCode::UnusedDeclaration, Some(Severity::Hint)),
+            (ErrorCode::MissingDecl, None),
+        ]);
+        let mut diagnostics = Vec::new();
+        let mut handler = SeverityOverridingDiagnosticHandler::new(&mut diagnostics, &overrides);
+        handler.push(
+            Diagnostic::warning(code.s1("a"), "unused").with_code(ErrorCode::UnusedDeclaration),
+        );
+        handler.push(Diagnostic::error(code.s1("b"), "missing").with_code(ErrorCode::MissingDecl));
+        handler
+            .push(Diagnostic::error(code.s1("c"), "duplicate").with_code(ErrorCode::DuplicateDecl));
+        handler.push(Diagnostic::error(code.s1("c"), "no code"));
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::hint(code.s1("a"), "unused").with_code(ErrorCode::UnusedDeclaration),
+                Diagnostic::error(code.s1("c"), "duplicate").with_code(ErrorCode::DuplicateDecl),
+                Diagnostic::error(code.s1("c"), "no code"),
+            ]
+        );
+    }
+
+    #[test]
+    fn show_info_and_hint() {
+        let code = Code::new_with_file_name(Path::new("{unknown file}"), "hello\n");
+        assert!(Diagnostic::info(code.s1("hello"), "Greetings")
+            .show()
+            .starts_with("info: Greetings\n"));
+        assert!(Diagnostic::hint(code.s1("hello"), "Greetings")
+            .show()
+            .starts_with("hint: Greetings\n"));
+    }
+
+    #[test]
+    fn severity_is_ordered() {
+        assert!(Severity::Hint < Severity::Info);
+        assert!(Severity::Info < Severity::Warning);
+        assert!(Severity::Warning < Severity::Error);
+    }
+
     #[test]
     fn show_related() {
         let code = Code::new_with_file_name(Path::new("{unknown file}"), "hello\nworld\nline\n");
@@ -228,22 +591,180 @@ error: Greetings
         assert_eq!(
             err.show(),
             "\
-related: From here
-  --> {unknown file}:1
-   |
-1 --> hello
-   |  ~~~~~
-2  |  world
-3  |  line
-
 error: Greetings
   --> {unknown file}:3
    |
 1  |  hello
+   |  ----- From here
 2  |  world
 3 --> line
    |  ~~~~
 "
         );
     }
+
+    #[test]
+    fn show_duplicate_declaration_in_one_file() {
+        let code = Code::new_with_file_name(
+            Path::new("pkg.vhd"),
+            "\
+package pkg is
+  constant x : natural := 0;
+  constant y : natural := 1;
+  constant z : natural := 2;
+  constant w : natural := 3;
+  constant x : natural := 4;
+end package;
+",
+        );
+
+        let err = Diagnostic::error(code.s("x", 2), "Duplicate declaration of 'x'")
+            .related(code.s1("x"), "Previously defined here")
+            .with_code(ErrorCode::DuplicateDecl);
+
+        assert_eq!(
+            err.show(),
+            "\
+error[duplicate_decl]: Duplicate declaration of 'x'
+  --> pkg.vhd:6
+   |
+4  |    constant z : natural := 2;
+5  |    constant w : natural := 3;
+6 -->   constant x : natural := 4;
+   |             ~
+7  |  end package;
+
+related: Previously defined here
+  --> pkg.vhd:2
+   |
+1  |  package pkg is
+2 -->   constant x : natural := 0;
+   |             ~
+3  |    constant y : natural := 1;
+4  |    constant z : natural := 2;
+"
+        );
+    }
+
+    #[test]
+    fn show_duplicate_declaration_in_two_files() {
+        let decl = Code::new_with_file_name(
+            Path::new("decl.vhd"),
+            &format!("{}entity ent is\nend entity;\n", "--\n".repeat(9)),
+        );
+        let dup = Code::new_with_file_name(
+            Path::new("dup.vhd"),
+            "\
+entity ent is
+end entity;
+",
+        );
+
+        let err = Diagnostic::error(dup.sa("entity ", "ent"), "Duplicate declaration of 'ent'")
+            .related(decl.sa("entity ", "ent"), "Previously defined here")
+            .with_code(ErrorCode::DuplicateDecl);
+
+        // The line numbers of both files are aligned
+        assert_eq!(
+            err.show(),
+            "\
+error[duplicate_decl]: Duplicate declaration of 'ent'
+   --> dup.vhd:1
+    |
+ 1 --> entity ent is
+    |         ~~~
+ 2  |  end entity;
+
+related: Previously defined here
+   --> decl.vhd:10
+    |
+ 8  |  --
+ 9  |  --
+10 --> entity ent is
+    |         ~~~
+11  |  end entity;
+"
+        );
+    }
+
+    #[test]
+    fn report_groups_diagnostics_by_file() {
+        let code1 = Code::new_with_file_name(Path::new("b.vhd"), "hello world");
+        let code2 = Code::new_with_file_name(Path::new("a.vhd"), "hello world");
+
+        let report = DiagnosticReport::new(vec![
+            Diagnostic::error(code1.s1("world"), "first"),
+            Diagnostic::warning(code2.s1("hello"), "second"),
+            Diagnostic::error(code1.s1("hello"), "third"),
+        ]);
+
+        let files: Vec<_> = report
+            .by_file()
+            .map(|(source, diagnostics)| {
+                (
+                    source.clone(),
+                    diagnostics
+                        .iter()
+                        .map(|diag| diag.message.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (code2.source().clone(), vec!["second"]),
+                (code1.source().clone(), vec!["first", "third"]),
+            ]
+        );
+        assert_eq!(report.error_count(), 2);
+        assert_eq!(report.warning_count(), 1);
+        assert_eq!(report.summary(), "2 errors, 1 warning");
+    }
+
+    #[test]
+    fn sorted_report_orders_by_start_then_end() {
+        let code = Code::new("hello world");
+
+        let report = DiagnosticReport::new(vec![
+            Diagnostic::error(code.s1("world"), "world"),
+            Diagnostic::error(code.s1("hello world"), "hello world"),
+            Diagnostic::error(code.s1("hello"), "hello"),
+            Diagnostic::error(code.s1("lo wo"), "lo wo"),
+        ])
+        .sorted();
+
+        let (_, diagnostics) = report.by_file().next().unwrap();
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diag| diag.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hello", "hello world", "lo wo", "world"]
+        );
+    }
+
+    #[test]
+    fn report_exit_code_only_fails_on_errors() {
+        let code = Code::new("hello world");
+
+        assert_eq!(DiagnosticReport::default().exit_code(), 0);
+        assert_eq!(
+            DiagnosticReport::default().summary(),
+            "0 errors, 0 warnings"
+        );
+
+        let warnings = DiagnosticReport::new(vec![
+            Diagnostic::warning(code.s1("hello"), "warning"),
+            Diagnostic::hint(code.s1("world"), "hint"),
+        ]);
+        assert_eq!(warnings.exit_code(), 0);
+        assert_eq!(warnings.summary(), "0 errors, 1 warning");
+
+        let errors = DiagnosticReport::new(vec![
+            Diagnostic::warning(code.s1("hello"), "warning"),
+            Diagnostic::error(code.s1("world"), "error"),
+        ]);
+        assert_eq!(errors.exit_code(), 1);
+    }
 }
@@ -0,0 +1,169 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use std::fmt;
+
+macro_rules! error_codes {
+    ($($(#[doc = $doc:literal])+ $variant:ident => $name:literal,)+) => {
+        /// A stable identifier of the kind of a diagnostic such as `duplicate_decl`.
+        /// The identifiers are used to demote or silence diagnostics in the configuration.
+        #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, PartialOrd, Ord)]
+        #[non_exhaustive]
+        pub enum ErrorCode {
+            $($(#[doc = $doc])+ $variant,)+
+        }
+
+        impl ErrorCode {
+            /// All error codes in the order they are documented
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)+];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $name,)+
+                }
+            }
+
+            /// A one line description of the diagnostics with this code
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => concat!($($doc),+).trim_ascii(),)+
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<ErrorCode> {
+                match name {
+                    $($name => Some(ErrorCode::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// The source text is not legal VHDL syntax
+    SyntaxError => "syntax_error",
+    /// The identifier or label after `end` does not match the name of the construct
+    EndIdentifierMismatch => "end_identifier_mismatch",
+    /// A design unit depends on itself
+    CircularDependency => "circular_dependency",
+    /// A declaration or design unit is declared twice in the same region
+    DuplicateDecl => "duplicate_decl",
+    /// A name does not denote any visible declaration
+    MissingDecl => "missing_decl",
+    /// A name is made invisible by two use clauses that make different declarations visible
+    ConflictingUseClause => "conflicting_use_clause",
+    /// A name, call or expression has more than one possible meaning
+    Ambiguous => "ambiguous",
+    /// An expression does not have the expected type
+    TypeMismatch => "type_mismatch",
+    /// A name denotes the wrong kind of entity, such as a type where an object is expected
+    MismatchedKinds => "mismatched_kinds",
+    /// The operand of a type conversion does not have a type closely related to the type mark
+    InvalidConversion => "invalid_conversion",
+    /// An interface object is read or assigned against its mode
+    ModeViolation => "mode_violation",
+    /// The formal of an association does not denote a formal of the callee
+    InvalidFormal => "invalid_formal",
+    /// The actuals of a call or map aspect do not match the formals of any candidate
+    InvalidCall => "invalid_call",
+    /// The number of indexes does not match the number of array dimensions
+    DimensionMismatch => "dimension_mismatch",
+    /// The length of an array is known not to match the expected length
+    LengthMismatch => "length_mismatch",
+    /// An attribute does not exist, is applied to an illegal prefix or given illegal arguments
+    IllegalAttribute => "illegal_attribute",
+    /// A signature is missing, superfluous or does not match
+    IllegalSignature => "illegal_signature",
+    /// A declaration is not allowed where it is written or lacks a required completion
+    IllegalDeclaration => "illegal_declaration",
+    /// A constraint is not allowed for the subtype it constrains
+    IllegalConstraint => "illegal_constraint",
+    /// The resolution function of a subtype does not have the profile of a resolution function
+    InvalidResolution => "invalid_resolution",
+    /// A range has illegal bounds or is used where a range is not allowed
+    InvalidRange => "invalid_range",
+    /// A choice or association of an aggregate is illegal
+    InvalidAggregate => "invalid_aggregate",
+    /// A return statement is not allowed or does not match the subprogram kind
+    IllegalReturn => "illegal_return",
+    /// A function can reach its end without returning a value
+    MissingReturn => "missing_return",
+    /// A pure function references an object declared outside of it or calls an impure function
+    PurityViolation => "purity_violation",
+    /// An exit or next statement is outside of a loop or names a label that is not a loop
+    IllegalLoopControl => "illegal_loop_control",
+    /// The choices of a case statement or selected assignment do not cover all values
+    MissingChoice => "missing_choice",
+    /// A choice covers values that are covered by another choice
+    RedundantChoice => "redundant_choice",
+    /// A static expression could not be evaluated
+    StaticEvaluation => "static_evaluation",
+    /// An instantiation names something that cannot be instantiated
+    InvalidInstantiation => "invalid_instantiation",
+    /// A configuration or binding does not match the design it configures
+    InvalidConfiguration => "invalid_configuration",
+    /// A component declaration does not match the entity it is bound to by default
+    ComponentMismatch => "component_mismatch",
+    /// A signal is assigned by more than one process
+    MultipleDrivers => "multiple_drivers",
+    /// A signal of a combinational process is not assigned in all branches and infers a latch
+    InferredLatch => "inferred_latch",
+    /// A variable may be read before it is assigned
+    ReadBeforeAssignment => "read_before_assignment",
+    /// A sensitivity list contains something that is not a signal
+    SensitivityList => "sensitivity_list",
+    /// A declaration is never used
+    UnusedDeclaration => "unused_declaration",
+    /// A use clause makes the same names visible as an earlier use clause of the same region
+    RedundantUseClause => "redundant_use_clause",
+    /// A library clause names the working library which is always visible
+    UnnecessaryWorkLibrary => "unnecessary_work_library",
+    /// A wait statement with a timeout in a library checked for synthesis
+    SynthesisWaitFor => "synthesis_wait_for",
+    /// A signal initial value in a library checked for synthesis, it is not a reset value
+    SynthesisInitialValue => "synthesis_initial_value",
+    /// An `after` delay in a library checked for synthesis, it is ignored
+    SynthesisDelay => "synthesis_delay",
+    /// A shared variable of a non-protected type in a library checked for synthesis
+    SynthesisSharedVariable => "synthesis_shared_variable",
+    /// Division, `mod` or `rem` by a value that is not a constant power of 2 in a library checked for synthesis
+    SynthesisDivision => "synthesis_division",
+    /// An assertion whose condition is always true, or always false with a severity below error
+    StaticAssertion => "static_assertion",
+    /// An if or while condition that is always true or false, or a case selector that is constant
+    ConstantCondition => "constant_condition",
+    /// A conditional analysis directive tests an identifier that is not defined by the tool or the configuration
+    UnknownToolIdentifier => "unknown_tool_identifier",
+    /// A `translate_off` pragma is not closed by a `translate_on` pragma
+    UnbalancedPragma => "unbalanced_pragma",
+    /// An error within the analyzer itself
+    Internal => "internal",
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_unique_and_round_trip() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_name(code.as_str()), Some(*code));
+            assert!(!code.description().is_empty());
+        }
+        assert_eq!(ErrorCode::from_name("no_such_code"), None);
+        assert_eq!(
+            ErrorCode::DuplicateDecl.description(),
+            "A declaration or design unit is declared twice in the same region"
+        );
+    }
+}
@@ -4,7 +4,8 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::contents::Contents;
+use super::contents::{Contents, Encoding};
+use super::diagnostic::Severity;
 use parking_lot::{RwLock, RwLockReadGuard};
 use std::cmp::{max, min};
 use std::collections::hash_map::DefaultHasher;
@@ -14,6 +15,7 @@ use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 use std::io;
 pub use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 struct FileId {
@@ -53,6 +55,12 @@ fn hash(value: &Path) -> u64 {
 struct UniqueSource {
     file_id: FileId,
     contents: RwLock<Contents>,
+    /// Incremented by every change of the contents while the contents are locked for writing
+    revision: AtomicU64,
+    /// The regions between `translate_off` and `translate_on` pragmas found when tokenizing
+    translate_off: RwLock<Vec<Range>>,
+    /// The branches of conditional analysis directives that are not selected
+    inactive: RwLock<Vec<Range>>,
 }
 
 impl fmt::Debug for UniqueSource {
@@ -67,14 +75,20 @@ impl UniqueSource {
         Self {
             file_id: FileId::new(file_name),
             contents: RwLock::new(Contents::from_str(contents)),
+            revision: AtomicU64::new(0),
+            translate_off: RwLock::default(),
+            inactive: RwLock::default(),
         }
     }
 
-    fn from_latin1_file(file_name: &Path) -> io::Result<Self> {
-        let contents = Contents::from_latin1_file(file_name)?;
+    fn from_file(file_name: &Path, encoding: Encoding) -> io::Result<Self> {
+        let contents = Contents::from_file(file_name, encoding)?;
         Ok(Self {
             file_id: FileId::new(file_name),
             contents: RwLock::new(contents),
+            revision: AtomicU64::new(0),
+            translate_off: RwLock::default(),
+            inactive: RwLock::default(),
         })
     }
 
@@ -83,6 +97,9 @@ impl UniqueSource {
         Self {
             file_id: FileId::new(file_name),
             contents: RwLock::new(contents),
+            revision: AtomicU64::new(0),
+            translate_off: RwLock::default(),
+            inactive: RwLock::default(),
         }
     }
 
@@ -90,6 +107,13 @@ impl UniqueSource {
         self.contents.read()
     }
 
+    fn contents_with_revision(&self) -> (RwLockReadGuard<'_, Contents>, u64) {
+        let contents = self.contents.read();
+        // The revision cannot change while the contents are locked for reading
+        let revision = self.revision.load(Ordering::Acquire);
+        (contents, revision)
+    }
+
     fn file_name(&self) -> &Path {
         self.file_id.name.as_ref()
     }
@@ -144,8 +168,17 @@ impl Source {
     }
 
     pub fn from_latin1_file(file_name: &Path) -> io::Result<Source> {
+        Self::from_file_with_encoding(file_name, Encoding::Latin1)
+    }
+
+    /// Read a source file from disk detecting whether it is encoded as UTF-8 or Latin-1.
+    pub fn from_file(file_name: &Path) -> io::Result<Source> {
+        Self::from_file_with_encoding(file_name, Encoding::Auto)
+    }
+
+    pub fn from_file_with_encoding(file_name: &Path, encoding: Encoding) -> io::Result<Source> {
         Ok(Source {
-            source: Arc::new(UniqueSource::from_latin1_file(file_name)?),
+            source: Arc::new(UniqueSource::from_file(file_name, encoding)?),
         })
     }
 
@@ -160,6 +193,17 @@ impl Source {
         self.source.contents()
     }
 
+    /// The contents together with the revision they belong to,
+    /// the revision always matches the text of the contents
+    pub fn contents_with_revision(&self) -> (RwLockReadGuard<'_, Contents>, u64) {
+        self.source.contents_with_revision()
+    }
+
+    /// The number of changes made to the contents since the source was created
+    pub fn revision(&self) -> u64 {
+        self.source.contents_with_revision().1
+    }
+
     pub fn file_name(&self) -> &Path {
         self.source.file_name()
     }
@@ -168,6 +212,35 @@ impl Source {
         self.source.file_path()
     }
 
+    pub(crate) fn set_translate_off_regions(&self, regions: Vec<Range>) {
+        *self.source.translate_off.write() = regions;
+    }
+
+    /// The regions from a `translate_off` pragma up to and including the matching
+    /// `translate_on` pragma, available once the source has been tokenized
+    pub fn translate_off_regions(&self) -> Vec<Range> {
+        self.source.translate_off.read().clone()
+    }
+
+    /// True if the position is within a `translate_off` region
+    pub fn is_translated_off(&self, pos: Position) -> bool {
+        self.source
+            .translate_off
+            .read()
+            .iter()
+            .any(|range| range.start <= pos && pos < range.end)
+    }
+
+    pub(crate) fn set_inactive_regions(&self, regions: Vec<Range>) {
+        *self.source.inactive.write() = regions;
+    }
+
+    /// The regions between conditional analysis directives such as `` `if `` and `` `end ``
+    /// that are excluded from parsing, available once the source has been tokenized
+    pub fn inactive_regions(&self) -> Vec<Range> {
+        self.source.inactive.read().clone()
+    }
+
     pub fn pos(&self, start: Position, end: Position) -> SrcPos {
         SrcPos {
             source: self.clone(),
@@ -175,6 +248,31 @@ impl Source {
         }
     }
 
+    /// The byte offset of the position within the UTF-8 text of the source.
+    /// Note that the text uses `\n` line endings and unlike a Latin-1 encoded file
+    /// non-ASCII characters such as `å` take two bytes.
+    pub fn position_to_offset(&self, pos: Position) -> usize {
+        self.contents().position_to_offset(pos)
+    }
+
+    /// The position of a byte offset within the UTF-8 text of the source.
+    pub fn offset_to_position(&self, offset: usize) -> Position {
+        self.contents().offset_to_position(offset)
+    }
+
+    pub fn range_to_offsets(&self, range: &Range) -> (usize, usize) {
+        self.contents().range_to_offsets(range)
+    }
+
+    /// The column of the position counted in UTF-8 bytes instead of UTF-16 code units.
+    pub fn utf8_column(&self, pos: Position) -> usize {
+        self.contents().utf8_column(pos)
+    }
+
+    pub fn position_from_utf8_column(&self, line: u32, column: usize) -> Position {
+        self.contents().position_from_utf8_column(line, column)
+    }
+
     pub fn change(&self, range: Option<&Range>, content: &str) {
         let mut contents = self.source.contents.write();
         if let Some(range) = range {
@@ -182,15 +280,17 @@ impl Source {
         } else {
             *contents = Contents::from_str(content);
         }
+        self.source.revision.fetch_add(1, Ordering::Release);
     }
 }
 
 /// A lexical position (line, column) in a source.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     /// Line (zero-based).
     pub line: u32,
-    /// Column (zero-based).
+    /// Column (zero-based) counted in UTF-16 code units as in the language server protocol.
     pub character: u32,
 }
 
@@ -234,6 +334,7 @@ impl Position {
 
 /// A lexical range in a source.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range {
     /// Start of the range (inclusive).
     pub start: Position,
@@ -278,6 +379,7 @@ impl PartialOrd for SrcPos {
 
 /// A generic object with an associated source file and lexical range.
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WithPos<T> {
     pub item: T,
     pub pos: SrcPos,
@@ -344,14 +446,56 @@ impl<T> From<WithPos<T>> for SrcPos {
     }
 }
 
+/// ANSI escape sequences used when pretty printing a source position
+struct Colors {
+    underline: &'static str,
+    lineno: &'static str,
+    message: &'static str,
+    reset: &'static str,
+}
+
+impl Colors {
+    const PLAIN: Colors = Colors {
+        underline: "",
+        lineno: "",
+        message: "",
+        reset: "",
+    };
+
+    fn for_severity(severity: Severity) -> Colors {
+        Colors {
+            underline: match severity {
+                Severity::Error => "\x1b[31m",
+                Severity::Warning => "\x1b[33m",
+                Severity::Info => "\x1b[34m",
+                Severity::Hint => "\x1b[32m",
+            },
+            lineno: "\x1b[36m",
+            message: "\x1b[1m",
+            reset: "\x1b[0m",
+        }
+    }
+}
+
 impl SrcPos {
     const LINE_CONTEXT: u32 = 2;
+    /// Longer lines are truncated to a window around the range when shown
+    const MAX_LINE_WIDTH: usize = 240;
 
     pub fn new(source: Source, range: Range) -> SrcPos {
         SrcPos { source, range }
     }
 
-    fn get_line_context(&self, context_lines: u32, contents: &Contents) -> Vec<(u32, String)> {
+    /// The source text covered by this position
+    pub fn text(&self) -> String {
+        self.source.contents().range_str(&self.range)
+    }
+
+    fn get_line_context<'c>(
+        &self,
+        context_lines: u32,
+        contents: &'c Contents,
+    ) -> Vec<(u32, &'c str)> {
         let mut lines = Vec::new();
 
         let start = self.range.start.line.saturating_sub(context_lines);
@@ -359,16 +503,86 @@ impl SrcPos {
 
         for lineno in start..=end {
             if let Some(line) = contents.get_line(lineno as usize) {
-                lines.push((lineno, line.to_owned()));
+                lines.push((lineno, line));
             }
         }
 
         if lines.is_empty() {
-            lines.push((self.range.start.line, String::new()));
+            lines.push((self.range.start.line, ""));
         }
         lines
     }
 
+    /// The index of the character at the utf-16 column of a line
+    fn char_index(line: &str, character: u32) -> usize {
+        let mut utf16_len = 0;
+        for (idx, chr) in line.chars().enumerate() {
+            if utf16_len >= character {
+                return idx;
+            }
+            utf16_len += chr.len_utf16() as u32;
+        }
+        line.chars().count()
+    }
+
+    /// The window of at most max_width characters of a line to show.
+    /// The window is centered on the part of the range within the line.
+    /// Returns the sub string, the utf-16 column where it starts and if the end was truncated.
+    fn line_window<'l>(
+        &self,
+        lineno: u32,
+        line: &'l str,
+        max_width: usize,
+    ) -> (&'l str, u32, bool) {
+        if line.len() <= max_width {
+            return (line, 0, false);
+        }
+        let len = line.chars().count();
+        if len <= max_width {
+            return (line, 0, false);
+        }
+
+        let start = if self.range.start.line < lineno && lineno <= self.range.end.line {
+            0
+        } else {
+            Self::char_index(line, self.range.start.character)
+        };
+        let end = if lineno == self.range.end.line {
+            Self::char_index(line, self.range.end.character)
+        } else if lineno < self.range.start.line || lineno > self.range.end.line {
+            start
+        } else {
+            len
+        };
+
+        let first = if end - start >= max_width {
+            start
+        } else {
+            ((start + end) / 2).saturating_sub(max_width / 2)
+        }
+        .min(len - max_width);
+
+        let mut character = 0;
+        let mut first_offset = line.len();
+        let mut last_offset = line.len();
+        for (idx, (offset, chr)) in line.char_indices().enumerate() {
+            if idx == first {
+                first_offset = offset;
+            } else if idx == first + max_width {
+                last_offset = offset;
+                break;
+            }
+            if idx < first {
+                character += chr.len_utf16() as u32;
+            }
+        }
+        (
+            &line[first_offset..last_offset],
+            character,
+            last_offset < line.len(),
+        )
+    }
+
     fn push_replicate(line: &mut String, chr: char, times: usize) {
         for _ in 0..times {
             line.push(chr);
@@ -383,8 +597,22 @@ impl SrcPos {
         }
     }
 
-    /// Write ~~~ to underline symbol
-    fn underline(&self, lineno_len: usize, lineno: u32, line: &str, into: &mut String) {
+    /// Write ~~~ to underline symbol, or another marker such as --- for a related position
+    ///
+    /// The line is a window starting at the utf-16 column start_character
+    /// which is prefixed and suffixed by an ellipsis when truncated.
+    #[allow(clippy::too_many_arguments)]
+    fn underline(
+        &self,
+        lineno_len: usize,
+        lineno: u32,
+        line: &str,
+        start_character: u32,
+        truncated_end: bool,
+        marker: char,
+        colors: &Colors,
+        into: &mut String,
+    ) {
         const NEWLINE_SIZE: usize = 1;
         into.reserve("  |  ".len() + lineno_len + line.len() + NEWLINE_SIZE);
 
@@ -393,96 +621,280 @@ impl SrcPos {
             into.push(' ');
         }
         into.push_str("  |  ");
+        if start_character > 0 {
+            into.push(' ');
+        }
 
         let mut pos = Position {
             line: lineno,
-            character: 0,
+            character: start_character,
         };
+        let mut is_underlining = false;
         // Padding before underline
         for chr in line.chars() {
             if pos < self.range.start {
                 Self::push_replicate(into, ' ', Self::visual_width(chr));
             } else if pos < self.range.end {
-                Self::push_replicate(into, '~', Self::visual_width(chr));
+                if !is_underlining {
+                    into.push_str(colors.underline);
+                    is_underlining = true;
+                }
+                Self::push_replicate(into, marker, Self::visual_width(chr));
             } else {
                 break;
             }
             pos.character += chr.len_utf16() as u32;
         }
 
-        if lineno == self.range.end.line {
+        if truncated_end {
+            if pos < self.range.end {
+                if !is_underlining {
+                    into.push_str(colors.underline);
+                    is_underlining = true;
+                }
+                into.push(marker);
+            }
+        } else if lineno == self.range.end.line {
             while pos < self.range.end {
-                into.push('~');
+                if !is_underlining {
+                    into.push_str(colors.underline);
+                    is_underlining = true;
+                }
+                into.push(marker);
                 pos.character += 1;
             }
         }
 
-        // Newline
-        into.push('\n');
+        if is_underlining {
+            into.push_str(colors.reset);
+        }
+    }
+
+    /// The width of the line numbers of the context lines
+    fn lineno_len(&self, context_lines: u32) -> usize {
+        // +1 since lines are shown with 1-index
+        (self.range.start.line + context_lines + 1)
+            .to_string()
+            .len()
+    }
+
+    /// True if the lines of the other position are among the context lines of this position
+    fn shows_lines_of(&self, other: &SrcPos, context_lines: u32) -> bool {
+        self.source == other.source
+            && self.range.start.line.saturating_sub(context_lines) <= other.range.start.line
+            && other.range.end.line <= self.range.end.line + context_lines
     }
 
     fn code_context_from_contents(
         &self,
         contents: &Contents,
         context_lines: u32,
+        max_line_width: usize,
+        colors: &Colors,
     ) -> (usize, String) {
+        let lineno_len = self.lineno_len(context_lines);
+        let context = self.write_code_context(
+            contents,
+            context_lines,
+            max_line_width,
+            lineno_len,
+            &[],
+            colors,
+            colors,
+        );
+        (lineno_len, context)
+    }
+
+    /// The context lines with this position underlined. The related positions within the
+    /// context lines are underlined on separate rows followed by their messages.
+    #[allow(clippy::too_many_arguments)]
+    fn write_code_context(
+        &self,
+        contents: &Contents,
+        context_lines: u32,
+        max_line_width: usize,
+        lineno_len: usize,
+        related: &[(&SrcPos, &str)],
+        colors: &Colors,
+        related_colors: &Colors,
+    ) -> String {
         let lines = self.get_line_context(context_lines, contents);
         use pad::{Alignment, PadStr};
-        // +1 since lines are shown with 1-index
-        let lineno_len = (self.range.start.line + context_lines + 1)
-            .to_string()
-            .len();
 
         let mut result = String::new();
 
         for (lineno, line) in lines.iter() {
-            let line = line.to_string();
-            let line = line.trim_matches('\n');
+            let full_line = line.trim_matches('\n');
+            let (line, start_character, truncated_end) =
+                self.line_window(*lineno, full_line, max_line_width);
             let lineno_str = (lineno + 1)
                 .to_string()
                 .pad_to_width_with_alignment(lineno_len, Alignment::Right);
             let overlaps = self.range.start.line <= *lineno && *lineno <= self.range.end.line;
 
+            let (color, reset) = (colors.lineno, colors.reset);
             if overlaps {
-                write!(result, "{lineno_str} --> ").unwrap();
+                write!(result, "{color}{lineno_str}{reset} --> ").unwrap();
             } else {
-                write!(result, "{lineno_str}  |  ").unwrap();
+                write!(result, "{color}{lineno_str}{reset}  |  ").unwrap();
             }
 
-            for chr in line.trim_end().chars() {
+            if start_character > 0 {
+                result.push('…');
+            }
+            let shown = if truncated_end { line } else { line.trim_end() };
+            for chr in shown.chars() {
                 if chr == '\t' {
                     Self::push_replicate(&mut result, ' ', Self::visual_width(chr));
                 } else {
                     result.push(chr);
                 }
             }
+            if truncated_end {
+                result.push('…');
+            }
             result.push('\n');
 
             if overlaps {
-                self.underline(lineno_len, *lineno, line, &mut result);
+                self.underline(
+                    lineno_len,
+                    *lineno,
+                    line,
+                    start_character,
+                    truncated_end,
+                    '~',
+                    colors,
+                    &mut result,
+                );
+                result.push('\n');
+            }
+
+            for (pos, message) in related.iter() {
+                if pos.range.start.line <= *lineno && *lineno <= pos.range.end.line {
+                    pos.underline(
+                        lineno_len,
+                        *lineno,
+                        line,
+                        start_character,
+                        truncated_end,
+                        '-',
+                        related_colors,
+                        &mut result,
+                    );
+                    if *lineno == pos.range.end.line {
+                        write!(result, " {message}").unwrap();
+                    }
+                    result.push('\n');
+                }
             }
         }
 
-        (lineno_len, result)
+        result
     }
 
     /// Create a string for pretty printing.
     pub fn code_context(&self) -> String {
-        self.lineno_len_and_code_context().1
+        self.lineno_len_and_code_context(&Colors::PLAIN).1
     }
 
-    fn lineno_len_and_code_context(&self) -> (usize, String) {
+    fn lineno_len_and_code_context(&self, colors: &Colors) -> (usize, String) {
         let contents = self.source.contents();
-        self.code_context_from_contents(&contents, Self::LINE_CONTEXT)
+        self.code_context_from_contents(&contents, Self::LINE_CONTEXT, Self::MAX_LINE_WIDTH, colors)
     }
 
     pub fn show(&self, message: &str) -> String {
-        let (lineno_len, pretty_str) = self.lineno_len_and_code_context();
+        self.show_with_colors(message, &Colors::PLAIN)
+    }
+
+    /// Same as show but with ANSI colors for printing to a terminal.
+    /// The underline is colored according to the severity.
+    pub fn show_colored(&self, message: &str, severity: Severity) -> String {
+        self.show_with_colors(message, &Colors::for_severity(severity))
+    }
+
+    fn show_with_colors(&self, message: &str, colors: &Colors) -> String {
+        let lineno_len = self.lineno_len(Self::LINE_CONTEXT);
+        self.show_block(message, lineno_len, &[], colors, colors)
+    }
+
+    /// Same as show but also shows the related positions and their messages.
+    /// A related position within the lines shown for this position is underlined among
+    /// them, the other related positions are shown beneath with their own lines.
+    pub fn show_with_related(&self, message: &str, related: &[(SrcPos, String)]) -> String {
+        self.show_with_related_colors(message, related, &Colors::PLAIN, &Colors::PLAIN)
+    }
+
+    /// Same as show_with_related but with ANSI colors for printing to a terminal
+    pub fn show_with_related_colored(
+        &self,
+        message: &str,
+        related: &[(SrcPos, String)],
+        severity: Severity,
+    ) -> String {
+        self.show_with_related_colors(
+            message,
+            related,
+            &Colors::for_severity(severity),
+            &Colors::for_severity(Severity::Hint),
+        )
+    }
+
+    fn show_with_related_colors(
+        &self,
+        message: &str,
+        related: &[(SrcPos, String)],
+        colors: &Colors,
+        related_colors: &Colors,
+    ) -> String {
+        let (inline, separate): (Vec<_>, Vec<_>) = related
+            .iter()
+            .map(|(pos, message)| (pos, message.as_str()))
+            .partition(|(pos, _)| self.shows_lines_of(pos, Self::LINE_CONTEXT));
+
+        // The line numbers of all blocks are aligned
+        let lineno_len = separate
+            .iter()
+            .map(|(pos, _)| pos.lineno_len(Self::LINE_CONTEXT))
+            .fold(self.lineno_len(Self::LINE_CONTEXT), max);
+
+        let mut result = self.show_block(message, lineno_len, &inline, colors, related_colors);
+        for (pos, message) in separate {
+            result.push('\n');
+            result.push_str(&pos.show_block(
+                &format!("related: {message}"),
+                lineno_len,
+                &[],
+                related_colors,
+                related_colors,
+            ));
+        }
+        result
+    }
+
+    /// The message followed by the file name, line and context lines of this position
+    fn show_block(
+        &self,
+        message: &str,
+        lineno_len: usize,
+        related: &[(&SrcPos, &str)],
+        colors: &Colors,
+        related_colors: &Colors,
+    ) -> String {
+        let contents = self.source.contents();
+        let pretty_str = self.write_code_context(
+            &contents,
+            Self::LINE_CONTEXT,
+            Self::MAX_LINE_WIDTH,
+            lineno_len,
+            related,
+            colors,
+            related_colors,
+        );
         let file_name = self.source.file_name();
         let mut result = String::new();
 
         let lineno = self.range.start.line;
-        writeln!(result, "{}", &message).unwrap();
+        writeln!(result, "{}{}{}", colors.message, &message, colors.reset).unwrap();
         for _ in 0..lineno_len {
             result.push(' ');
         }
@@ -626,7 +1038,7 @@ impl FilePath {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::Latin1String;
+    use crate::data::{Diagnostic, Latin1String};
     use crate::syntax::test::{Code, CodeBuilder};
     use pretty_assertions::assert_eq;
 
@@ -644,6 +1056,14 @@ mod tests {
         assert_eq!(code.s1("d").pos().combine(&code.s1("h").pos()), code.pos());
     }
 
+    #[test]
+    fn srcpos_text() {
+        let code = Code::new("entity ent is\nend entity;");
+        assert_eq!(code.s1("ent").pos().text(), "ent");
+        assert_eq!(code.s1("is\nend").pos().text(), "is\nend");
+        assert_eq!(code.pos().text(), "entity ent is\nend entity;");
+    }
+
     fn with_code_from_file<F, R>(contents: &str, fun: F) -> R
     where
         F: Fn(Code) -> R,
@@ -656,6 +1076,50 @@ mod tests {
         fun(CodeBuilder::new().code_from_source(Source::from_latin1_file(&file_name).unwrap()))
     }
 
+    fn with_code_from_bytes<F, R>(bytes: &[u8], fun: F) -> R
+    where
+        F: Fn(Code) -> R,
+    {
+        use std::io::Write;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = file.path().to_owned();
+        file.write_all(bytes).unwrap();
+        fun(CodeBuilder::new().code_from_source(Source::from_file(&file_name).unwrap()))
+    }
+
+    #[test]
+    fn from_file_with_utf8_bom() {
+        with_code_from_bytes(b"\xef\xbb\xbfsignal \xc3\xa5;", |code: Code| {
+            assert_eq!(code.source().contents().get_line(0), Some("signal å;"));
+        });
+    }
+
+    #[test]
+    fn from_file_with_utf8() {
+        with_code_from_bytes("signal åäö;".as_bytes(), |code: Code| {
+            assert_eq!(code.source().contents().get_line(0), Some("signal åäö;"));
+            assert_eq!(code.s1("åäö").end().character, 10);
+        });
+    }
+
+    #[test]
+    fn from_file_with_latin1() {
+        with_code_from_bytes(b"signal \xe5;", |code: Code| {
+            assert_eq!(code.source().contents().get_line(0), Some("signal å;"));
+        });
+    }
+
+    #[test]
+    fn from_file_with_non_latin1_character() {
+        with_code_from_bytes("c := \"€\";".as_bytes(), |code: Code| {
+            let (tokens, _) = code.tokenize_result();
+            assert!(tokens.contains(&Err(Diagnostic::error(
+                code.s1("€"),
+                "Found invalid latin-1 character '€'"
+            ))));
+        });
+    }
+
     #[test]
     fn code_context_pos_from_filename() {
         with_code_from_file("hello\nworld\n", |code: Code| {
@@ -741,6 +1205,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn code_context_with_crlf_and_cr() {
+        for code in [
+            "hello\r\nworld\r\nline\r\n",
+            "hello\rworld\rline\r",
+            "hello\r\nworld\rline\n",
+        ] {
+            let code = Code::new(code);
+            let pos = code.s1("world").pos();
+            assert_eq!(
+                pos.range,
+                Range::new(Position::new(1, 0), Position::new(1, 5))
+            );
+            assert_eq!(
+                pos.code_context(),
+                "\
+1  |  hello
+2 --> world
+   |  ~~~~~
+3  |  line
+",
+            );
+        }
+    }
+
     #[test]
     fn code_context_non_ascii() {
         let code = Code::new("åäö\nåäö\n__å_ä_ö__");
@@ -788,6 +1277,68 @@ mod tests {
         });
     }
 
+    #[test]
+    fn code_context_truncates_long_line_at_start() {
+        let code = Code::new(&format!("{}hello{}", "x".repeat(9990), "y".repeat(5)));
+        let pos = code.s1("hello").pos();
+        assert_eq!(
+            pos.code_context_from_contents(
+                &pos.source.contents(),
+                SrcPos::LINE_CONTEXT,
+                20,
+                &Colors::PLAIN
+            )
+            .1,
+            "\
+1 --> …xxxxxxxxxxhelloyyyyy
+   |             ~~~~~
+",
+        );
+    }
+
+    #[test]
+    fn code_context_truncates_long_line_around_range() {
+        let code = Code::new(&format!(
+            "before\n{}hello{}\nafter",
+            "x".repeat(5000),
+            "y".repeat(4995)
+        ));
+        let pos = code.s1("hello").pos();
+        assert_eq!(
+            pos.code_context(),
+            format!(
+                "\
+1  |  before
+2 --> …{}hello{}…
+   |   {}~~~~~
+3  |  after
+",
+                "x".repeat(118),
+                "y".repeat(117),
+                " ".repeat(118)
+            ),
+        );
+    }
+
+    #[test]
+    fn code_context_truncated_range_continues_past_window() {
+        let code = Code::new(&format!("{}{}", "x".repeat(10), "y".repeat(9990)));
+        let pos = code.s1(&"y".repeat(9990)).pos();
+        assert_eq!(
+            pos.code_context_from_contents(
+                &pos.source.contents(),
+                SrcPos::LINE_CONTEXT,
+                20,
+                &Colors::PLAIN
+            )
+            .1,
+            "\
+1 --> …yyyyyyyyyyyyyyyyyyyy…
+   |   ~~~~~~~~~~~~~~~~~~~~~
+",
+        );
+    }
+
     #[test]
     fn code_context_with_full_context() {
         let code = Code::new(
@@ -841,6 +1392,51 @@ Greetings
         });
     }
 
+    #[test]
+    fn show_colored_single_line() {
+        let code = Code::new("hello\nworld\nline\n");
+        assert_eq!(
+            code.s1("world")
+                .pos()
+                .show_colored("Greetings", Severity::Error),
+            format!(
+                "\
+\x1b[1mGreetings\x1b[0m
+  --> {}:2
+   |
+\x1b[36m1\x1b[0m  |  hello
+\x1b[36m2\x1b[0m --> world
+   |  \x1b[31m~~~~~\x1b[0m
+\x1b[36m3\x1b[0m  |  line
+",
+                code.source().file_name().to_string_lossy()
+            )
+        );
+    }
+
+    #[test]
+    fn show_colored_multi_line() {
+        let code = Code::new("hello\nworld\nline\n");
+        assert_eq!(
+            code.s1("llo\nwor")
+                .pos()
+                .show_colored("Greetings", Severity::Warning),
+            format!(
+                "\
+\x1b[1mGreetings\x1b[0m
+  --> {}:1
+   |
+\x1b[36m1\x1b[0m --> hello
+   |    \x1b[33m~~~\x1b[0m
+\x1b[36m2\x1b[0m --> world
+   |  \x1b[33m~~~\x1b[0m
+\x1b[36m3\x1b[0m  |  line
+",
+                code.source().file_name().to_string_lossy()
+            )
+        );
+    }
+
     #[test]
     fn show_contents() {
         let code = Code::new("hello\nworld\nline\n");
@@ -860,4 +1456,42 @@ Greetings
             )
         );
     }
+
+    #[test]
+    fn change_increments_revision() {
+        let source = Source::inline(Path::new("file.vhd"), "rev0");
+        assert_eq!(source.revision(), 0);
+
+        source.change(None, "rev1");
+        source.change(
+            Some(&Range::new(Position::new(0, 3), Position::new(0, 4))),
+            "2",
+        );
+        let (contents, revision) = source.contents_with_revision();
+        assert_eq!(revision, 2);
+        assert_eq!(contents.get_line(0), Some("rev2"));
+    }
+
+    #[test]
+    fn concurrent_reader_sees_revision_of_text() {
+        let source = Source::inline(Path::new("file.vhd"), "rev0");
+        std::thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                let mut last_revision = 0;
+                while last_revision < 100 {
+                    let (contents, revision) = source.contents_with_revision();
+                    assert_eq!(
+                        contents.get_line(0),
+                        Some(format!("rev{revision}").as_str())
+                    );
+                    assert!(revision >= last_revision);
+                    last_revision = revision;
+                }
+            });
+            for revision in 1..=100 {
+                source.change(None, &format!("rev{revision}"));
+            }
+            reader.join().unwrap();
+        });
+    }
 }
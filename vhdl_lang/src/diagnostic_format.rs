@@ -0,0 +1,301 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Machine readable output of diagnostics for continuous integration.
+//!
+//! Every location of the JSON output has two representations of its range:
+//! * `range` is zero-based with the character counted in UTF-16 code units,
+//!   the same as the language server protocol.
+//! * `region` is one-based with `startLine`, `startColumn`, `endLine` and `endColumn`,
+//!   the same as a SARIF region. The end column is exclusive.
+//!
+//! File paths below the base directory are written relative to it with `/` as separator.
+
+use crate::ctags::file_path;
+use crate::data::*;
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn region(range: Range) -> Value {
+    json!({
+        "startLine": range.start.line + 1,
+        "startColumn": range.start.character + 1,
+        "endLine": range.end.line + 1,
+        "endColumn": range.end.character + 1,
+    })
+}
+
+fn location(pos: &SrcPos, base_dir: &Path) -> Value {
+    let range = pos.range();
+    json!({
+        "file": file_path(pos.file_name(), base_dir),
+        "range": {
+            "start": { "line": range.start.line, "character": range.start.character },
+            "end": { "line": range.end.line, "character": range.end.character },
+        },
+        "region": region(range),
+    })
+}
+
+fn sarif_location(pos: &SrcPos, base_dir: &Path) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": file_path(pos.file_name(), base_dir) },
+            "region": region(pos.range()),
+        }
+    })
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Hint => "note",
+    }
+}
+
+impl DiagnosticReport {
+    /// The diagnostics as a JSON document with the error and warning counts
+    pub fn to_json(&self, base_dir: &Path) -> Value {
+        let diagnostics: Vec<Value> = self
+            .by_file()
+            .flat_map(|(_, diagnostics)| diagnostics.iter())
+            .map(|diagnostic| {
+                json!({
+                    "location": location(&diagnostic.pos, base_dir),
+                    "severity": diagnostic.severity.as_str(),
+                    "code": diagnostic.code.map(|code| code.as_str()),
+                    "message": diagnostic.message,
+                    "related": diagnostic
+                        .related
+                        .iter()
+                        .map(|(pos, message)| json!({
+                            "location": location(pos, base_dir),
+                            "message": message,
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        json!({
+            "diagnostics": diagnostics,
+            "errors": self.error_count(),
+            "warnings": self.warning_count(),
+        })
+    }
+
+    /// The diagnostics as a SARIF 2.1.0 log with a rule for each error code
+    pub fn to_sarif(&self, base_dir: &Path) -> Value {
+        let rules: Vec<Value> = ErrorCode::ALL
+            .iter()
+            .map(|code| {
+                json!({
+                    "id": code.as_str(),
+                    "shortDescription": { "text": code.description() },
+                })
+            })
+            .collect();
+        let results: Vec<Value> = self
+            .by_file()
+            .flat_map(|(_, diagnostics)| diagnostics.iter())
+            .map(|diagnostic| {
+                let mut result = json!({
+                    "level": sarif_level(diagnostic.severity),
+                    "message": { "text": diagnostic.message },
+                    "locations": [sarif_location(&diagnostic.pos, base_dir)],
+                });
+                if let Some(code) = diagnostic.code {
+                    result["ruleId"] = json!(code.as_str());
+                    result["ruleIndex"] = json!(ErrorCode::ALL
+                        .iter()
+                        .position(|other| *other == code)
+                        .unwrap());
+                }
+                if !diagnostic.related.is_empty() {
+                    result["relatedLocations"] = diagnostic
+                        .related
+                        .iter()
+                        .enumerate()
+                        .map(|(id, (pos, message))| {
+                            let mut location = sarif_location(pos, base_dir);
+                            location["id"] = json!(id);
+                            location["message"] = json!({ "text": message });
+                            location
+                        })
+                        .collect();
+                }
+                result
+            })
+            .collect();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "vhdl_lang",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/VHDL-LS/rust_hdl",
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, DiagnosticReport, Project};
+    use serde_json::json;
+    use std::path::Path;
+
+    /// Analyze a project with a misspelled package name and a duplicate declaration
+    fn analyze_fixture(root: &Path) -> DiagnosticReport {
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src").join("pkg.vhd"),
+            "\
+package pkg is
+  constant c : natural := 0;
+  constant c : natural := 1;
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src").join("ent.vhd"),
+            "\
+use work.pkgg.all;
+entity ent is
+end entity;
+",
+        )
+        .unwrap();
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{}/../vhdl_libraries/std/standard.vhd']
+lib.files = ['src/*.vhd']
+",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let config = Config::from_str(&config_str, root).unwrap();
+        let mut project = Project::from_config(config, &mut Vec::new());
+        DiagnosticReport::new(project.analyse()).sorted()
+    }
+
+    #[test]
+    fn diagnostics_to_json() {
+        let root = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(root.path()).unwrap();
+        let report = analyze_fixture(&root);
+        assert_eq!(
+            report.to_json(&root),
+            json!({
+                "diagnostics": [{
+                    "location": {
+                        "file": "src/ent.vhd",
+                        "range": {
+                            "start": { "line": 0, "character": 9 },
+                            "end": { "line": 0, "character": 13 },
+                        },
+                        "region": { "startLine": 1, "startColumn": 10, "endLine": 1, "endColumn": 14 },
+                    },
+                    "severity": "error",
+                    "code": "missing_decl",
+                    "message": "No primary unit 'pkgg' within library 'lib'",
+                    "related": [],
+                }, {
+                    "location": {
+                        "file": "src/pkg.vhd",
+                        "range": {
+                            "start": { "line": 2, "character": 11 },
+                            "end": { "line": 2, "character": 12 },
+                        },
+                        "region": { "startLine": 3, "startColumn": 12, "endLine": 3, "endColumn": 13 },
+                    },
+                    "severity": "error",
+                    "code": "duplicate_decl",
+                    "message": "Duplicate declaration of 'c'",
+                    "related": [{
+                        "location": {
+                            "file": "src/pkg.vhd",
+                            "range": {
+                                "start": { "line": 1, "character": 11 },
+                                "end": { "line": 1, "character": 12 },
+                            },
+                            "region": { "startLine": 2, "startColumn": 12, "endLine": 2, "endColumn": 13 },
+                        },
+                        "message": "Previously defined here",
+                    }],
+                }],
+                "errors": 2,
+                "warnings": 0,
+            })
+        );
+    }
+
+    #[test]
+    fn diagnostics_to_sarif() {
+        let root = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(root.path()).unwrap();
+        let report = analyze_fixture(&root);
+        let sarif = report.to_sarif(&root);
+        assert_eq!(sarif["version"], "2.1.0");
+
+        let driver = &sarif["runs"][0]["tool"]["driver"];
+        assert_eq!(driver["name"], "vhdl_lang");
+        let rule_index = sarif["runs"][0]["results"][0]["ruleIndex"]
+            .as_u64()
+            .unwrap();
+        assert_eq!(
+            driver["rules"][rule_index as usize],
+            json!({
+                "id": "missing_decl",
+                "shortDescription": { "text": "A name does not denote any visible declaration" },
+            })
+        );
+
+        let mut results = sarif["runs"][0]["results"].clone();
+        for result in results.as_array_mut().unwrap() {
+            result.as_object_mut().unwrap().remove("ruleIndex").unwrap();
+        }
+        assert_eq!(
+            results,
+            json!([{
+                "ruleId": "missing_decl",
+                "level": "error",
+                "message": { "text": "No primary unit 'pkgg' within library 'lib'" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/ent.vhd" },
+                        "region": { "startLine": 1, "startColumn": 10, "endLine": 1, "endColumn": 14 },
+                    }
+                }],
+            }, {
+                "ruleId": "duplicate_decl",
+                "level": "error",
+                "message": { "text": "Duplicate declaration of 'c'" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/pkg.vhd" },
+                        "region": { "startLine": 3, "startColumn": 12, "endLine": 3, "endColumn": 13 },
+                    }
+                }],
+                "relatedLocations": [{
+                    "id": 0,
+                    "message": { "text": "Previously defined here" },
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/pkg.vhd" },
+                        "region": { "startLine": 2, "startColumn": 12, "endLine": 2, "endColumn": 13 },
+                    }
+                }],
+            }])
+        );
+    }
+}
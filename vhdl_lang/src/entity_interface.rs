@@ -0,0 +1,358 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{Finished, Found, FoundDeclaration, NotFinished, SearchState, Searcher};
+use crate::ast::{
+    EntityDeclaration, InterfaceDeclaration, Mode, ObjectClass, SubprogramDefault,
+    SubtypeIndication,
+};
+use crate::data::{SrcPos, Symbol};
+use crate::named_entity::{AnyEntKind, TypeEnt};
+use crate::syntax::TokenAccess;
+
+/// The generics and ports of an analyzed entity in declaration order
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EntityInterface {
+    pub name: String,
+    /// The position of the name of the entity
+    pub pos: SrcPos,
+    pub generics: Vec<InterfaceElement>,
+    pub ports: Vec<InterfaceElement>,
+}
+
+/// The kind of a generic or port
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum InterfaceElementKind {
+    Constant,
+    Signal,
+    Variable,
+    File,
+    Type,
+    Subprogram,
+    Package,
+}
+
+/// A generic or port of an entity. The text of the type mark, constraint and default
+/// is as written in the source.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct InterfaceElement {
+    pub name: String,
+    pub kind: InterfaceElementKind,
+    /// The position of the name of the element
+    pub pos: SrcPos,
+    /// The mode of a generic constant or a port
+    pub mode: Option<Mode>,
+    /// The type mark of an object or file such as `bit_vector`
+    pub type_mark: Option<String>,
+    /// The constraint of the subtype indication such as `(width - 1 downto 0)`
+    pub constraint: Option<String>,
+    /// The name of the resolved base type of an object or file such as `BIT_VECTOR`
+    pub base_type: Option<String>,
+    /// The default value of a generic constant, the default signal value of a port or
+    /// the default subprogram of a generic subprogram
+    pub default: Option<String>,
+    /// True for an object of an array type without an index constraint,
+    /// the bounds then come from the actual of the association
+    pub constraint_from_association: bool,
+}
+
+impl InterfaceElement {
+    fn new(name: String, kind: InterfaceElementKind, pos: &SrcPos) -> InterfaceElement {
+        InterfaceElement {
+            name,
+            kind,
+            pos: pos.clone(),
+            mode: None,
+            type_mark: None,
+            constraint: None,
+            base_type: None,
+            default: None,
+            constraint_from_association: false,
+        }
+    }
+
+    fn with_subtype(
+        mut self,
+        root: &DesignRoot,
+        subtype_indication: &SubtypeIndication,
+        type_mark: Option<TypeEnt>,
+    ) -> InterfaceElement {
+        self.type_mark = Some(subtype_indication.type_mark.pos.text());
+        self.constraint = subtype_indication
+            .constraint
+            .as_ref()
+            .map(|constraint| constraint.pos.text());
+        if let Some(type_mark) = type_mark {
+            self.base_type = Some(type_mark.base_type().designator().to_string());
+            self.constraint_from_association =
+                self.constraint.is_none() && root.is_unconstrained_array(type_mark);
+        }
+        self
+    }
+}
+
+fn interface_element(root: &DesignRoot, decl: &InterfaceDeclaration) -> InterfaceElement {
+    match decl {
+        InterfaceDeclaration::Object(object) => {
+            let kind = match object.class {
+                ObjectClass::Constant => InterfaceElementKind::Constant,
+                ObjectClass::Signal => InterfaceElementKind::Signal,
+                ObjectClass::Variable | ObjectClass::SharedVariable => {
+                    InterfaceElementKind::Variable
+                }
+            };
+            let type_mark = match object.ident.decl.get().map(|id| root.get_ent(id).kind()) {
+                Some(AnyEntKind::Object(obj)) => Some(obj.subtype.type_mark()),
+                _ => None,
+            };
+            let mut element = InterfaceElement::new(
+                object.ident.tree.item.name_utf8(),
+                kind,
+                &object.ident.tree.pos,
+            )
+            .with_subtype(root, &object.subtype_indication, type_mark);
+            element.mode = Some(object.mode);
+            element.default = object.expression.as_ref().map(|expr| expr.pos.text());
+            element
+        }
+        InterfaceDeclaration::File(file) => {
+            let type_mark = match file.ident.decl.get().map(|id| root.get_ent(id).kind()) {
+                Some(AnyEntKind::InterfaceFile(typ)) => Some(*typ),
+                _ => None,
+            };
+            InterfaceElement::new(
+                file.ident.tree.item.name_utf8(),
+                InterfaceElementKind::File,
+                &file.ident.tree.pos,
+            )
+            .with_subtype(root, &file.subtype_indication, type_mark)
+        }
+        InterfaceDeclaration::Type(ident) => InterfaceElement::new(
+            ident.tree.item.name_utf8(),
+            InterfaceElementKind::Type,
+            &ident.tree.pos,
+        ),
+        InterfaceDeclaration::Subprogram(spec, default) => {
+            let designator = spec.subpgm_designator();
+            let mut element = InterfaceElement::new(
+                designator.item.to_string(),
+                InterfaceElementKind::Subprogram,
+                &designator.pos,
+            );
+            element.default = default.as_ref().map(|default| match default {
+                SubprogramDefault::Name(name) => name.pos.text(),
+                SubprogramDefault::Box => "<>".to_owned(),
+            });
+            element
+        }
+        InterfaceDeclaration::Package(package) => InterfaceElement::new(
+            package.ident.tree.item.name_utf8(),
+            InterfaceElementKind::Package,
+            &package.ident.tree.pos,
+        ),
+    }
+}
+
+fn entity_interface(root: &DesignRoot, entity: &EntityDeclaration) -> EntityInterface {
+    let elements = |clause: &Option<Vec<InterfaceDeclaration>>| {
+        clause
+            .iter()
+            .flatten()
+            .map(|decl| interface_element(root, decl))
+            .collect()
+    };
+    EntityInterface {
+        name: entity.ident.tree.item.name_utf8(),
+        pos: entity.ident.tree.pos.clone(),
+        generics: elements(&entity.generic_clause),
+        ports: elements(&entity.port_clause),
+    }
+}
+
+struct EntityInterfaceSearcher<'a> {
+    root: &'a DesignRoot,
+    entity_name: &'a Symbol,
+    result: Option<EntityInterface>,
+}
+
+impl<'a> Searcher for EntityInterfaceSearcher<'a> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if let FoundDeclaration::Entity(entity) = decl {
+            if &entity.ident.tree.item == self.entity_name {
+                self.result = Some(entity_interface(self.root, entity));
+                return Finished(Found);
+            }
+        }
+        NotFinished
+    }
+}
+
+impl DesignRoot {
+    /// The generics and ports of the entity `entity_name` of a library
+    pub fn entity_interface(
+        &self,
+        library_name: &Symbol,
+        entity_name: &Symbol,
+    ) -> Option<EntityInterface> {
+        let mut searcher = EntityInterfaceSearcher {
+            root: self,
+            entity_name,
+            result: None,
+        };
+        let _ = self.search_library(library_name, &mut searcher);
+        searcher.result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{check_no_diagnostics, LibraryBuilder};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn interface_with_generic_dependent_port_width() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  generic (
+    width : natural := 8;
+    type data_t);
+  port (
+    clk : in bit;
+    data_out : out bit_vector(width - 1 downto 0);
+    data_in : in bit_vector;
+    valid : buffer boolean := false);
+end entity;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let interface = root
+            .entity_interface(&root.symbol_utf8("libname"), &root.symbol_utf8("ent"))
+            .unwrap();
+        assert_eq!(interface.name, "ent");
+        assert_eq!(interface.pos, code.sa("entity ", "ent").pos());
+
+        let object = |name: &str,
+                      kind: InterfaceElementKind,
+                      mode: Mode,
+                      type_mark: &str,
+                      base_type: &str| InterfaceElement {
+            name: name.to_owned(),
+            kind,
+            pos: code.s1(&format!("{name} :")).s1(name).pos(),
+            mode: Some(mode),
+            type_mark: Some(type_mark.to_owned()),
+            constraint: None,
+            base_type: Some(base_type.to_owned()),
+            default: None,
+            constraint_from_association: false,
+        };
+
+        assert_eq!(
+            interface.generics,
+            vec![
+                InterfaceElement {
+                    default: Some("8".to_owned()),
+                    ..object(
+                        "width",
+                        InterfaceElementKind::Constant,
+                        Mode::In,
+                        "natural",
+                        "INTEGER"
+                    )
+                },
+                InterfaceElement::new(
+                    "data_t".to_owned(),
+                    InterfaceElementKind::Type,
+                    &code.s1("data_t").pos()
+                ),
+            ]
+        );
+        assert_eq!(
+            interface.ports,
+            vec![
+                object("clk", InterfaceElementKind::Signal, Mode::In, "bit", "BIT"),
+                InterfaceElement {
+                    constraint: Some("(width - 1 downto 0)".to_owned()),
+                    ..object(
+                        "data_out",
+                        InterfaceElementKind::Signal,
+                        Mode::Out,
+                        "bit_vector",
+                        "BIT_VECTOR"
+                    )
+                },
+                InterfaceElement {
+                    constraint_from_association: true,
+                    ..object(
+                        "data_in",
+                        InterfaceElementKind::Signal,
+                        Mode::In,
+                        "bit_vector",
+                        "BIT_VECTOR"
+                    )
+                },
+                InterfaceElement {
+                    default: Some("false".to_owned()),
+                    ..object(
+                        "valid",
+                        InterfaceElementKind::Signal,
+                        Mode::Buffer,
+                        "boolean",
+                        "BOOLEAN"
+                    )
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn constrained_array_type_is_not_constrained_by_association() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "
+package pkg is
+  type word_t is array (0 to 7) of bit;
+  subtype byte_t is bit_vector(7 downto 0);
+end package;
+
+use work.pkg.all;
+
+entity ent is
+  port (
+    word : in word_t;
+    byte : in byte_t;
+    bits : in bit_vector);
+end entity;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let interface = root
+            .entity_interface(&root.symbol_utf8("libname"), &root.symbol_utf8("ent"))
+            .unwrap();
+        assert_eq!(
+            interface
+                .ports
+                .iter()
+                .map(|port| (port.name.as_str(), port.constraint_from_association))
+                .collect::<Vec<_>>(),
+            vec![("word", false), ("byte", false), ("bits", true)]
+        );
+        assert_eq!(
+            root.entity_interface(&root.symbol_utf8("libname"), &root.symbol_utf8("missing")),
+            None
+        );
+    }
+}
@@ -0,0 +1,768 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::*;
+use crate::ast::*;
+use crate::data::{Contents, Diagnostic, HasSource, Position, Range, Source, SrcPos, WithPos};
+use crate::named_entity::{AnyEntKind, EntRef, EntityId, Reference, Type, TypeEnt, UniversalType};
+use crate::syntax::{HasTokenSpan, Kind, Token, TokenAccess};
+use crate::use_clause_fix::indentation;
+
+/// The kind of declaration that a selected expression is extracted into
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ExtractKind {
+    /// A constant in the nearest declarative part, the expression must not read
+    /// signals, variables or files nor call impure functions
+    Constant,
+    /// A signal in the architecture that is driven by a new concurrent assignment
+    Signal,
+    /// A variable in the process that is assigned before the statement
+    Variable,
+}
+
+impl ExtractKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            ExtractKind::Constant => "constant",
+            ExtractKind::Signal => "signal",
+            ExtractKind::Variable => "variable",
+        }
+    }
+}
+
+/// True if the inner range is within the outer range
+fn covers(outer: Range, inner: Range) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// The line where a labeled statement starts
+fn statement_line(label: &Option<Ident>, pos: &SrcPos) -> u32 {
+    label.as_ref().map_or(pos, |label| &label.pos).start().line
+}
+
+/// The innermost sequential statement that contains the range
+fn innermost_statement(
+    statements: &[LabeledSequentialStatement],
+    range: Range,
+) -> Option<&LabeledSequentialStatement> {
+    let statement = statements
+        .iter()
+        .find(|statement| covers(statement.statement.pos.range(), range))?;
+    let nested: Vec<&[LabeledSequentialStatement]> = match statement.statement.item {
+        SequentialStatement::If(ref ifstmt) => ifstmt
+            .conds
+            .conditionals
+            .iter()
+            .map(|conditional| conditional.item.as_slice())
+            .chain(ifstmt.conds.else_item.as_deref())
+            .collect(),
+        SequentialStatement::Case(ref case) => case
+            .alternatives
+            .iter()
+            .map(|alternative| alternative.item.as_slice())
+            .collect(),
+        SequentialStatement::Loop(ref lstmt) => vec![lstmt.statements.as_slice()],
+        _ => Vec::new(),
+    };
+    nested
+        .into_iter()
+        .find_map(|statements| innermost_statement(statements, range))
+        .or(Some(statement))
+}
+
+/// Where a new declaration is inserted in the declarative part and its indentation.
+/// It is added after the last declaration or before `begin` if there are none.
+fn declaration_position(
+    tokens: &dyn TokenAccess,
+    contents: &Contents,
+    decl: &[Declaration],
+    begin: &Token,
+) -> (Position, String) {
+    match decl.last() {
+        Some(last) => {
+            let span = last.get_span(tokens);
+            (
+                Position::new(span.end().line + 1, 0),
+                indentation(contents, span.start().line),
+            )
+        }
+        None => {
+            let line = begin.pos.start().line;
+            (
+                Position::new(line, 0),
+                format!("{}  ", indentation(contents, line)),
+            )
+        }
+    }
+}
+
+/// True if the expression at pos is the selection itself or the selection within parentheses
+fn is_selected(pos: &SrcPos, selection: &SrcPos) -> bool {
+    if pos.source() != selection.source() || !covers(pos.range(), selection.range()) {
+        return false;
+    }
+    pos.range() == selection.range()
+        || pos
+            .text()
+            .strip_prefix('(')
+            .and_then(|text| text.strip_suffix(')'))
+            .is_some_and(|inner| inner.trim() == selection.text())
+}
+
+/// True if the value of the named entity may be used for the value of a constant
+fn is_static(ent: EntRef) -> bool {
+    match ent.actual_kind() {
+        AnyEntKind::Object(object) => object.class == ObjectClass::Constant,
+        AnyEntKind::Overloaded(overloaded) => overloaded.signature().is_pure(),
+        AnyEntKind::File(..)
+        | AnyEntKind::InterfaceFile(..)
+        | AnyEntKind::LoopParameter(..)
+        | AnyEntKind::ExternalAlias { .. } => false,
+        _ => true,
+    }
+}
+
+/// Searches the selected expression for references that are not static
+/// and whether it is within the target of an assignment
+struct ExtractSearcher<'a> {
+    root: &'a DesignRoot,
+    selection: &'a SrcPos,
+    in_target: bool,
+    non_static: Option<EntRef<'a>>,
+}
+
+impl<'a> Searcher for ExtractSearcher<'a> {
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        if self.non_static.is_none()
+            && pos.source() == self.selection.source()
+            && covers(self.selection.range(), pos.range())
+        {
+            if let Some(ent) = reference.get().map(|id| self.root.get_ent(id)) {
+                if !is_static(ent) {
+                    self.non_static = Some(ent);
+                }
+            }
+        }
+        NotFinished
+    }
+
+    fn search_target(&mut self, _ctx: &dyn TokenAccess, target: &WithPos<Target>) -> SearchState {
+        if target.pos.source() == self.selection.source()
+            && covers(target.pos.range(), self.selection.range())
+        {
+            self.in_target = true;
+            return Finished(Found);
+        }
+        NotFinished
+    }
+}
+
+impl DesignRoot {
+    /// Compute the text edits that extract the expression selected by range within an
+    /// architecture into a new declaration named new_name. The declaration is added to the
+    /// declarative part of the process or architecture and has the type of the expression,
+    /// the selection is replaced by the name and signals and variables are assigned
+    /// the expression just before the statement.
+    ///
+    /// Fails if the range does not cover exactly one expression, if new_name is not a
+    /// legal identifier, if the kind of declaration is not allowed at the selection or if
+    /// a signal or variable would be declared with an unconstrained array type.
+    pub fn extract(
+        &self,
+        source: &Source,
+        range: Range,
+        new_name: &str,
+        kind: ExtractKind,
+    ) -> Result<Vec<(SrcPos, String)>, Diagnostic> {
+        let selection = SrcPos::new(source.clone(), range);
+        let new_designator = self
+            .parse_identifier(new_name)
+            .map(Designator::Identifier)
+            .ok_or_else(|| {
+                Diagnostic::error(
+                    &selection,
+                    format!("'{new_name}' is not a legal identifier"),
+                )
+            })?;
+        let not_extractable = |reason: &str| {
+            Diagnostic::error(
+                &selection,
+                format!("Cannot extract into a {}, {reason}", kind.describe()),
+            )
+        };
+
+        let unit = self
+            .units_by_source(source)
+            .find(|unit| {
+                if let Some(first) = unit.tokens.first() {
+                    let last = unit.tokens.last().unwrap();
+                    covers(first.pos.combine(&last.pos).range(), range)
+                } else {
+                    false
+                }
+            })
+            .ok_or_else(|| not_extractable("the selection is not within a design unit"))?;
+        let tokens = &unit.tokens;
+        let design_unit = unit.unit.expect_analyzed();
+        let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(ref arch)) = *design_unit
+        else {
+            return Err(not_extractable(
+                "the selection is not within an architecture",
+            ));
+        };
+
+        // A later type of the same expression is the one after disambiguation
+        let typ = design_unit
+            .result()
+            .expression_types
+            .iter()
+            .rev()
+            .find(|(pos, _)| is_selected(pos, &selection))
+            .and_then(|(_, id)| TypeEnt::from_any(self.get_ent(*id)))
+            .ok_or_else(|| not_extractable("the selection is not a complete expression"))?;
+        let type_name = type_name(typ)
+            .ok_or_else(|| not_extractable("the type of the expression has no name"))?;
+        // The bounds of a constant come from its value but signals and variables
+        // must be declared with a constrained subtype
+        if kind != ExtractKind::Constant && self.is_unconstrained_array(declared_type(typ)) {
+            return Err(not_extractable(&format!(
+                "the type '{type_name}' of the expression is unconstrained"
+            )));
+        }
+
+        let mut searcher = ExtractSearcher {
+            root: self,
+            selection: &selection,
+            in_target: false,
+            non_static: None,
+        };
+        let _ = self.search_source(source, &mut searcher);
+        if searcher.in_target {
+            return Err(not_extractable(
+                "the selection is the target of an assignment",
+            ));
+        }
+        if kind == ExtractKind::Constant {
+            if let Some(ent) = searcher.non_static {
+                return Err(not_extractable(&format!(
+                    "the expression is not static since it references {}",
+                    ent.describe()
+                )));
+            }
+        }
+
+        let statement = arch
+            .statements
+            .iter()
+            .find(|statement| covers(statement.statement.pos.range(), range))
+            .ok_or_else(|| not_extractable("the selection is not within a statement"))?;
+        let contents = source.contents();
+
+        let (scope_id, decl_position, indent, assignment) = match statement.statement.item {
+            ConcurrentStatement::Process(ref process) => {
+                if kind == ExtractKind::Signal {
+                    return Err(not_extractable("the selection is within a process"));
+                }
+                let inner = innermost_statement(&process.statements, range)
+                    .ok_or_else(|| not_extractable("the selection is not within a statement"))?;
+                if let SequentialStatement::Loop(LoopStatement {
+                    iteration_scheme: Some(IterationScheme::While(ref condition)),
+                    ..
+                }) = inner.statement.item
+                {
+                    if covers(condition.pos.range(), range) {
+                        return Err(not_extractable(
+                            "the condition of a while loop is evaluated in every iteration",
+                        ));
+                    }
+                }
+
+                let begin = tokens
+                    .get_token_slice(process.span.start_token, process.span.end_token)
+                    .iter()
+                    .find(|token| token.kind == Kind::Begin)
+                    .ok_or_else(|| not_extractable("the process has no begin"))?;
+                let (decl_position, indent) =
+                    declaration_position(tokens, &contents, &process.decl, begin);
+                let line = statement_line(&inner.label.tree, &inner.statement.pos);
+                let assignment = (kind == ExtractKind::Variable).then(|| {
+                    (
+                        line,
+                        format!(
+                            "{}{new_name} := {};\n",
+                            indentation(&contents, line),
+                            selection.text()
+                        ),
+                    )
+                });
+                (
+                    statement.label.decl.get(),
+                    decl_position,
+                    indent,
+                    assignment,
+                )
+            }
+            ConcurrentStatement::Block(..)
+            | ConcurrentStatement::ForGenerate(..)
+            | ConcurrentStatement::IfGenerate(..)
+            | ConcurrentStatement::CaseGenerate(..) => {
+                return Err(not_extractable(
+                    "the selection is within a block or generate statement",
+                ));
+            }
+            _ => {
+                if kind == ExtractKind::Variable {
+                    return Err(not_extractable("the selection is not within a process"));
+                }
+                let (decl_position, indent) = declaration_position(
+                    tokens,
+                    &contents,
+                    &arch.decl,
+                    tokens.get_token(arch.begin_token),
+                );
+                let line = statement_line(&statement.label.tree, &statement.statement.pos);
+                let assignment = (kind == ExtractKind::Signal).then(|| {
+                    (
+                        line,
+                        format!(
+                            "{}{new_name} <= {};\n",
+                            indentation(&contents, line),
+                            selection.text()
+                        ),
+                    )
+                });
+                (arch.ident.decl.get(), decl_position, indent, assignment)
+            }
+        };
+
+        self.check_extract_collision(source, scope_id, &new_designator, &selection)?;
+
+        let declaration = match kind {
+            ExtractKind::Constant => format!(
+                "{indent}constant {new_name} : {type_name} := {};\n",
+                selection.text()
+            ),
+            ExtractKind::Signal | ExtractKind::Variable => {
+                format!("{indent}{} {new_name} : {type_name};\n", kind.describe())
+            }
+        };
+        let insert_at =
+            |position: Position| SrcPos::new(source.clone(), position.range_to(position));
+
+        let mut edits = vec![
+            (insert_at(decl_position), declaration),
+            (selection.clone(), new_name.to_owned()),
+        ];
+        if let Some((line, text)) = assignment {
+            edits.push((insert_at(Position::new(line, 0)), text));
+        }
+        edits.sort_by(|(pos1, _), (pos2, _)| pos1.cmp(pos2));
+        Ok(edits)
+    }
+
+    fn check_extract_collision(
+        &self,
+        source: &Source,
+        scope_id: Option<EntityId>,
+        new_designator: &Designator,
+        selection: &SrcPos,
+    ) -> Result<(), Diagnostic> {
+        let mut searcher = FindEnt::new(self, |other| {
+            scope_id.is_some()
+                && other.parent.map(|parent| parent.id()) == scope_id
+                && other.designator() == new_designator
+        });
+        let _ = self.search_source(source, &mut searcher);
+
+        if let Some(other) = searcher.result {
+            let mut diagnostic = Diagnostic::error(
+                selection,
+                format!(
+                    "Cannot extract since it would collide with {}",
+                    other.describe()
+                ),
+            );
+            if let Some(pos) = other.decl_pos() {
+                diagnostic.add_related(pos, "Previously defined here");
+            }
+            Err(diagnostic)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The name of the type used in the new declaration. Universal types are declared
+/// with the standard types and anonymous subtypes with their base type.
+fn type_name(typ: TypeEnt) -> Option<String> {
+    let typ = match typ.kind() {
+        Type::Universal(UniversalType::Integer) => return Some("INTEGER".to_owned()),
+        Type::Universal(UniversalType::Real) => return Some("REAL".to_owned()),
+        _ => declared_type(typ),
+    };
+    (!typ.is_anonymous()).then(|| typ.designator().to_string())
+}
+
+/// The type of the new declaration, anonymous subtypes are replaced by their base type
+fn declared_type(typ: TypeEnt) -> TypeEnt {
+    if typ.is_anonymous() {
+        typ.base_type()
+    } else {
+        typ
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{check_diagnostics, Code, LibraryBuilder};
+    use pretty_assertions::assert_eq;
+
+    fn text(contents: &Contents) -> String {
+        (0..contents.num_lines())
+            .filter_map(|lineno| contents.get_line(lineno))
+            .collect()
+    }
+
+    /// The code after applying the edits
+    fn apply(code: &Code, edits: Vec<(SrcPos, String)>) -> String {
+        let mut edited = Contents::from_str(&text(&code.source().contents()));
+        for (pos, new_text) in edits.iter().rev() {
+            edited.change(&pos.range(), new_text);
+        }
+        text(&edited)
+    }
+
+    fn extract(
+        builder: &LibraryBuilder,
+        code: &Code,
+        pos: &SrcPos,
+        new_name: &str,
+        kind: ExtractKind,
+    ) -> Result<String, Diagnostic> {
+        let (root, _) = builder.get_analyzed_root();
+        root.extract(code.source(), pos.range(), new_name, kind)
+            .map(|edits| apply(code, edits))
+    }
+
+    #[test]
+    fn extract_static_expression_to_constant() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  generic (width : natural);
+  port (data : out bit_vector(width * 2 - 1 downto 0));
+end entity;
+
+architecture a of ent is
+  signal s : natural;
+begin
+  s <= width * 2 - 1;
+end architecture;
+",
+        );
+
+        assert_eq!(
+            extract(
+                &builder,
+                &code,
+                &code.s("width * 2", 2).pos(),
+                "size",
+                ExtractKind::Constant
+            ),
+            Ok("
+entity ent is
+  generic (width : natural);
+  port (data : out bit_vector(width * 2 - 1 downto 0));
+end entity;
+
+architecture a of ent is
+  signal s : natural;
+  constant size : INTEGER := width * 2;
+begin
+  s <= size - 1;
+end architecture;
+"
+            .to_owned())
+        );
+    }
+
+    #[test]
+    fn extract_dynamic_expression_in_process_to_variable() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (clk : in bit; d : in natural);
+end entity;
+
+architecture a of ent is
+begin
+  process (clk)
+    variable acc : natural;
+  begin
+    if clk = '1' then
+      acc := acc + d * 2;
+    end if;
+  end process;
+end architecture;
+",
+        );
+
+        assert_eq!(
+            extract(
+                &builder,
+                &code,
+                &code.s1("d * 2").pos(),
+                "scaled",
+                ExtractKind::Variable
+            ),
+            Ok("
+entity ent is
+  port (clk : in bit; d : in natural);
+end entity;
+
+architecture a of ent is
+begin
+  process (clk)
+    variable acc : natural;
+    variable scaled : INTEGER;
+  begin
+    if clk = '1' then
+      scaled := d * 2;
+      acc := acc + scaled;
+    end if;
+  end process;
+end architecture;
+"
+            .to_owned())
+        );
+    }
+
+    #[test]
+    fn extract_signal_in_architecture_without_declarations() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (a, b : in bit; q : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  q <= not (a and b);
+end architecture;
+",
+        );
+
+        assert_eq!(
+            extract(
+                &builder,
+                &code,
+                &code.s1("a and b").pos(),
+                "both",
+                ExtractKind::Signal
+            ),
+            Ok("
+entity ent is
+  port (a, b : in bit; q : out bit);
+end entity;
+
+architecture a of ent is
+  signal both : BIT;
+begin
+  both <= a and b;
+  q <= not (both);
+end architecture;
+"
+            .to_owned())
+        );
+    }
+
+    #[test]
+    fn extract_rejects_half_expression() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  generic (width : natural);
+end entity;
+
+architecture a of ent is
+  signal s : natural;
+begin
+  s <= width * 2 - 1;
+end architecture;
+",
+        );
+
+        let pos = code.s1("2 - 1").pos();
+        check_diagnostics(
+            extract(&builder, &code, &pos, "size", ExtractKind::Constant)
+                .err()
+                .into_iter()
+                .collect(),
+            vec![Diagnostic::error(
+                &pos,
+                "Cannot extract into a constant, the selection is not a complete expression",
+            )],
+        );
+    }
+
+    #[test]
+    fn extract_rejects_non_static_constant_and_misplaced_kinds() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (a : in natural);
+end entity;
+
+architecture a of ent is
+  signal s : natural;
+begin
+  s <= a + 1;
+
+  process
+    variable v : natural;
+  begin
+    v := v + 1;
+  end process;
+end architecture;
+",
+        );
+
+        let concurrent = code.s1("a + 1").pos();
+        let sequential = code.s1("v + 1").pos();
+        check_diagnostics(
+            [
+                extract(&builder, &code, &concurrent, "x", ExtractKind::Constant),
+                extract(&builder, &code, &concurrent, "x", ExtractKind::Variable),
+                extract(&builder, &code, &sequential, "x", ExtractKind::Signal),
+                extract(&builder, &code, &concurrent, "s", ExtractKind::Signal),
+                extract(&builder, &code, &concurrent, "1x", ExtractKind::Signal),
+            ]
+            .into_iter()
+            .filter_map(Result::err)
+            .collect(),
+            vec![
+                Diagnostic::error(
+                    &concurrent,
+                    "Cannot extract into a constant, the expression is not static since it references port 'a' : in",
+                ),
+                Diagnostic::error(
+                    &concurrent,
+                    "Cannot extract into a variable, the selection is not within a process",
+                ),
+                Diagnostic::error(
+                    &sequential,
+                    "Cannot extract into a signal, the selection is within a process",
+                ),
+                Diagnostic::error(&concurrent, "Cannot extract since it would collide with signal 's'")
+                    .related(code.sa("signal ", "s"), "Previously defined here"),
+                Diagnostic::error(&concurrent, "'1x' is not a legal identifier"),
+            ],
+        );
+    }
+
+    #[test]
+    fn extract_rejects_signal_and_variable_of_unconstrained_type() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (a, b : in bit_vector(3 downto 0));
+end entity;
+
+architecture a of ent is
+  subtype word_t is bit_vector(3 downto 0);
+  signal s : bit_vector(3 downto 0);
+  signal w1, w2 : word_t;
+begin
+  s <= a and b;
+  w1 <= w2;
+
+  process
+    variable v : bit_vector(3 downto 0);
+  begin
+    v := not a;
+    wait;
+  end process;
+end architecture;
+",
+        );
+
+        let concurrent = code.s1("a and b").pos();
+        let sequential = code.s1("not a").pos();
+        check_diagnostics(
+            [
+                extract(&builder, &code, &concurrent, "x", ExtractKind::Signal),
+                extract(&builder, &code, &sequential, "x", ExtractKind::Variable),
+            ]
+            .into_iter()
+            .filter_map(Result::err)
+            .collect(),
+            vec![
+                Diagnostic::error(
+                    &concurrent,
+                    "Cannot extract into a signal, the type 'BIT_VECTOR' of the expression is unconstrained",
+                ),
+                Diagnostic::error(
+                    &sequential,
+                    "Cannot extract into a variable, the type 'BIT_VECTOR' of the expression is unconstrained",
+                ),
+            ],
+        );
+
+        // A named subtype has the constraint
+        assert_eq!(
+            extract(
+                &builder,
+                &code,
+                &code.s1("<= w2").s1("w2").pos(),
+                "x",
+                ExtractKind::Signal
+            ),
+            Ok("
+entity ent is
+  port (a, b : in bit_vector(3 downto 0));
+end entity;
+
+architecture a of ent is
+  subtype word_t is bit_vector(3 downto 0);
+  signal s : bit_vector(3 downto 0);
+  signal w1, w2 : word_t;
+  signal x : word_t;
+begin
+  s <= a and b;
+  x <= w2;
+  w1 <= x;
+
+  process
+    variable v : bit_vector(3 downto 0);
+  begin
+    v := not a;
+    wait;
+  end process;
+end architecture;
+"
+            .to_owned())
+        );
+    }
+}
@@ -0,0 +1,2519 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Pretty-printing of parsed design files
+
+use crate::ast::*;
+use crate::data::{ContentReader, Position, Range, Source, SrcPos, WithPos};
+use crate::syntax::{kind_str, HasTokenSpan, Kind, Symbols, Token, TokenAccess, Tokenizer};
+use fnv::FnvHashMap;
+use std::fmt::Display;
+use std::path::Path;
+
+/// The case that keywords are written in
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum KeywordCase {
+    #[default]
+    Lower,
+    Upper,
+    /// Keep the case that is used for the keyword in the formatted file
+    Preserve,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FormatOptions {
+    /// The number of spaces per indentation level
+    pub indent_width: usize,
+    pub keyword_case: KeywordCase,
+    /// Align the `:` of declarations and interface lists and the `=>` of association lists
+    pub align: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 2,
+            keyword_case: KeywordCase::default(),
+            align: true,
+        }
+    }
+}
+
+/// One-line subprogram specifications longer than this are split to one parameter per line
+const MAX_INLINE_WIDTH: usize = 100;
+
+/// Format a design file.
+/// Comments are kept at the nearest declaration, statement or list element
+/// and blank lines between such items are preserved.
+pub fn format_design_file(design_file: &DesignFile, options: &FormatOptions) -> String {
+    let mut printer = Printer::new(design_file, options);
+    for (idx, (tokens, unit)) in design_file.design_units.iter().enumerate() {
+        if idx > 0 {
+            printer.blank_line();
+        }
+        printer.tokens = tokens;
+        printer.design_unit(unit);
+    }
+    printer.final_comments(design_file);
+
+    let mut result = printer.lines.join("\n");
+    result.push('\n');
+    match design_file
+        .design_units
+        .first()
+        .and_then(|(tokens, _)| tokens.first())
+    {
+        Some(token) => apply_keyword_case(
+            &result,
+            token.pos.source.file_name(),
+            options.keyword_case,
+            &keyword_spellings(design_file),
+        ),
+        None => result,
+    }
+}
+
+struct PendingComment {
+    start: Position,
+    end: Position,
+    text: String,
+}
+
+/// An element of an aligned list such as `name : type` or `formal => actual`
+struct ListItem {
+    start: Position,
+    end: Position,
+    /// The part before the separator, None if the item is not aligned
+    left: Option<String>,
+    right: String,
+}
+
+struct Printer<'a> {
+    options: &'a FormatOptions,
+    tokens: &'a Vec<Token>,
+    comments: Vec<PendingComment>,
+    next_comment: usize,
+    indent: usize,
+    lines: Vec<String>,
+    /// The source line of the last printed item, None at the start of a block
+    last_line: Option<u32>,
+}
+
+impl<'a> Printer<'a> {
+    fn new(design_file: &'a DesignFile, options: &'a FormatOptions) -> Printer<'a> {
+        let mut comments = Vec::new();
+        for (tokens, _) in design_file.design_units.iter() {
+            for token in tokens.iter() {
+                if let Some(ref token_comments) = token.comments {
+                    for comment in token_comments
+                        .leading
+                        .iter()
+                        .chain(token_comments.trailing.iter())
+                    {
+                        let text = if comment.multi_line {
+                            format!("/*{}*/", comment.value)
+                        } else {
+                            format!("--{}", comment.value.trim_end())
+                        };
+                        comments.push(PendingComment {
+                            start: comment.range.start,
+                            end: comment.range.end,
+                            text,
+                        });
+                    }
+                }
+            }
+        }
+        comments.sort_by_key(|comment| comment.start);
+
+        static NO_TOKENS: Vec<Token> = Vec::new();
+        Printer {
+            options,
+            tokens: design_file
+                .design_units
+                .first()
+                .map(|(tokens, _)| tokens)
+                .unwrap_or(&NO_TOKENS),
+            comments,
+            next_comment: 0,
+            indent: 0,
+            lines: Vec::new(),
+            last_line: None,
+        }
+    }
+
+    fn indentation(&self) -> String {
+        " ".repeat(self.indent * self.options.indent_width)
+    }
+
+    /// One level of indentation for continuation lines within a single item
+    fn indent_unit(&self) -> String {
+        " ".repeat(self.options.indent_width)
+    }
+
+    fn line(&mut self, text: impl AsRef<str>) {
+        let indentation = self.indentation();
+        for line in text.as_ref().split('\n') {
+            if line.is_empty() {
+                self.lines.push(String::new());
+            } else {
+                self.lines.push(format!("{indentation}{line}"));
+            }
+        }
+    }
+
+    /// The original text of tokens that could not be parsed
+    fn verbatim(&mut self, pos: &SrcPos) {
+        let contents = pos.source.contents();
+        let range = pos.range();
+        for lineno in range.start.line..=range.end.line {
+            let Some(line) = contents.get_line(lineno as usize) else {
+                continue;
+            };
+            let mut text: String = line.trim_end().chars().collect();
+            if lineno == range.end.line {
+                text = text.chars().take(range.end.character as usize).collect();
+            }
+            if lineno == range.start.line {
+                text = text.chars().skip(range.start.character as usize).collect();
+            }
+            let text = text.trim();
+            if !text.is_empty() {
+                self.line(text);
+            }
+        }
+    }
+
+    /// PSL is printed verbatim, including the `-- psl` prefix when it is embedded in a comment
+    fn psl_verbatim(&mut self, start: &SrcPos, end: Position) {
+        let contents = start.source.contents();
+        let mut first = start.start();
+        if let Some(line) = contents.get_line(first.line as usize) {
+            let prefix: String = line.chars().take(first.character as usize).collect();
+            let prefix = prefix.trim_end().to_lowercase();
+            if let Some(before) = prefix.strip_suffix("psl") {
+                let before = before.trim_end();
+                if before.ends_with("--") {
+                    first.character = (before.chars().count() - 2) as u32;
+                }
+            }
+        }
+        self.verbatim(&SrcPos::new(start.source.clone(), Range::new(first, end)));
+    }
+
+    fn blank_line(&mut self) {
+        if self.lines.last().map(|l| !l.is_empty()).unwrap_or(false) {
+            self.lines.push(String::new());
+        }
+        self.last_line = None;
+    }
+
+    fn blank_line_if_gap(&mut self, line: u32) {
+        if let Some(last_line) = self.last_line {
+            if line > last_line + 1 && self.lines.last().map(|l| !l.is_empty()).unwrap_or(false) {
+                self.lines.push(String::new());
+            }
+        }
+    }
+
+    /// Emit all comments that occur before the position on their own lines
+    fn comments_before(&mut self, pos: Position) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.start >= pos {
+                break;
+            }
+            let (start, end) = (comment.start, comment.end);
+            let text = comment.text.clone();
+            self.next_comment += 1;
+
+            if self.last_line == Some(start.line) && !self.lines.is_empty() {
+                self.trailing(&text);
+            } else {
+                self.blank_line_if_gap(start.line);
+                // The continuation lines of multi-line comments are kept as is
+                let indentation = self.indentation();
+                self.lines.push(format!("{indentation}{text}"));
+            }
+            self.last_line = Some(end.line);
+        }
+    }
+
+    fn has_comments_before(&self, pos: Position) -> bool {
+        self.comments
+            .get(self.next_comment)
+            .map(|comment| comment.start < pos)
+            .unwrap_or(false)
+    }
+
+    fn trailing(&mut self, text: &str) {
+        if let Some(last) = self.lines.last_mut() {
+            last.push(' ');
+            last.push_str(text);
+        }
+    }
+
+    /// Must be called before printing an item that starts at the position
+    fn begin_item(&mut self, start: Position) {
+        self.comments_before(start);
+        self.blank_line_if_gap(start.line);
+    }
+
+    /// Must be called after printing an item that ends at the position
+    fn end_item(&mut self, end: Position) {
+        self.last_line = Some(end.line);
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.start.line != end.line || comment.start < end {
+                break;
+            }
+            let text = comment.text.clone();
+            self.last_line = Some(comment.end.line);
+            self.next_comment += 1;
+            self.trailing(&text);
+        }
+    }
+
+    fn open(&mut self) {
+        self.indent += 1;
+        self.last_line = None;
+    }
+
+    /// Close a block that ends at the position
+    fn close(&mut self, end: Position) {
+        self.comments_before(end);
+        self.indent -= 1;
+        self.last_line = None;
+    }
+
+    fn span_start(&self, item: &impl HasTokenSpan) -> Position {
+        self.tokens.get_pos(item.get_start_token()).start()
+    }
+
+    fn span_end(&self, item: &impl HasTokenSpan) -> Position {
+        self.tokens.get_pos(item.get_end_token()).end()
+    }
+
+    fn final_comments(&mut self, design_file: &DesignFile) {
+        // Comments after the last token are not attached to any token
+        let Some(last) = design_file
+            .design_units
+            .last()
+            .and_then(|(tokens, _)| tokens.last())
+        else {
+            return;
+        };
+        let start = last.pos.end();
+        self.comments_before(start);
+
+        let contents = last.pos.source.contents();
+        let mut remaining = Vec::new();
+        for lineno in (start.line + 1) as usize..contents.num_lines() {
+            if let Some(line) = contents.get_line(lineno) {
+                remaining.push(line.trim_end().to_owned());
+            }
+        }
+        while remaining.last().map(|l| l.is_empty()).unwrap_or(false) {
+            remaining.pop();
+        }
+        let first = remaining.iter().position(|l| !l.is_empty());
+        if let Some(first) = first {
+            self.last_line = Some(start.line);
+            self.blank_line_if_gap(start.line + 1 + first as u32);
+            for line in remaining[first..].iter() {
+                self.lines.push(line.clone());
+            }
+        }
+    }
+
+    /// Print the items of a list, aligning the separators if enabled
+    fn list(&mut self, items: Vec<ListItem>, separator: &str) {
+        let width = if self.options.align {
+            items
+                .iter()
+                .filter_map(|item| item.left.as_ref().map(|left| left.len()))
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        for item in items {
+            self.begin_item(item.start);
+            if let Some(left) = item.left {
+                self.line(format!("{left:width$} {separator} {}", item.right));
+            } else {
+                self.line(item.right);
+            }
+            self.end_item(item.end);
+        }
+    }
+
+    fn design_unit(&mut self, unit: &AnyDesignUnit) {
+        match unit {
+            AnyDesignUnit::Primary(primary) => match primary {
+                AnyPrimaryUnit::Entity(entity) => self.entity(entity),
+                AnyPrimaryUnit::Configuration(config) => self.configuration(config),
+                AnyPrimaryUnit::Package(package) => self.package(package),
+                AnyPrimaryUnit::PackageInstance(instance) => {
+                    self.context_clause(&instance.context_clause);
+                    self.begin_item(self.span_start(instance));
+                    self.package_instance(instance);
+                    self.end_item(self.span_end(instance));
+                }
+                AnyPrimaryUnit::Context(context) => self.context(context),
+            },
+            AnyDesignUnit::Secondary(secondary) => match secondary {
+                AnySecondaryUnit::Architecture(arch) => self.architecture(arch),
+                AnySecondaryUnit::PackageBody(body) => self.package_body(body),
+            },
+        }
+    }
+
+    fn context_clause(&mut self, items: &[ContextItem]) {
+        for item in items {
+            self.begin_item(self.span_start(item));
+            self.line(context_item(item));
+            self.end_item(self.span_end(item));
+        }
+    }
+
+    /// Print the header line of a design unit with the identifier at `ident`
+    fn unit_header(&mut self, start: Position, header: String, ident: &Ident) {
+        self.begin_item(start);
+        self.line(header);
+        self.end_item(ident.pos.end());
+    }
+
+    fn unit_end(&mut self, end: Position, text: String) {
+        self.close(end);
+        self.line(text);
+        self.end_item(end);
+    }
+
+    fn entity(&mut self, entity: &EntityDeclaration) {
+        self.context_clause(&entity.context_clause);
+        let ident = &entity.ident.tree;
+        self.unit_header(self.span_start(entity), format!("entity {ident} is"), ident);
+        self.open();
+        if let Some(ref generics) = entity.generic_clause {
+            self.interface_clause("generic", generics);
+        }
+        if let Some(ref ports) = entity.port_clause {
+            self.interface_clause("port", ports);
+        }
+        self.declarations(&entity.decl);
+        if !entity.statements.is_empty() {
+            self.begin(&entity.decl, ident.pos.end());
+            self.concurrent_statements(&entity.statements);
+        }
+        self.unit_end(
+            self.span_end(entity),
+            format!("end entity{};", end_name(ident, &entity.end_ident_pos)),
+        );
+    }
+
+    fn architecture(&mut self, arch: &ArchitectureBody) {
+        self.context_clause(&arch.context_clause);
+        let ident = &arch.ident.tree;
+        self.unit_header(
+            self.span_start(arch),
+            format!("architecture {ident} of {} is", arch.entity_name),
+            &arch.entity_name.item,
+        );
+        self.open();
+        self.declarations(&arch.decl);
+        self.close(self.tokens.get_pos(arch.begin_token).start());
+        self.line("begin");
+        self.open();
+        self.concurrent_statements(&arch.statements);
+        self.unit_end(
+            self.span_end(arch),
+            format!("end architecture{};", end_name(ident, &arch.end_ident_pos)),
+        );
+    }
+
+    fn package(&mut self, package: &PackageDeclaration) {
+        self.context_clause(&package.context_clause);
+        let ident = &package.ident.tree;
+        self.unit_header(
+            self.span_start(package),
+            format!("package {ident} is"),
+            ident,
+        );
+        self.open();
+        if let Some(ref generics) = package.generic_clause {
+            self.interface_clause("generic", generics);
+        }
+        self.declarations(&package.decl);
+        self.unit_end(
+            self.span_end(package),
+            format!("end package{};", end_name(ident, &package.end_ident_pos)),
+        );
+    }
+
+    fn package_body(&mut self, body: &PackageBody) {
+        self.context_clause(&body.context_clause);
+        let ident = &body.ident.tree;
+        self.unit_header(
+            self.span_start(body),
+            format!("package body {ident} is"),
+            ident,
+        );
+        self.open();
+        self.declarations(&body.decl);
+        self.unit_end(
+            self.span_end(body),
+            format!("end package body{};", end_name(ident, &body.end_ident_pos)),
+        );
+    }
+
+    fn package_instance(&mut self, instance: &PackageInstantiation) {
+        let head = format!(
+            "package {} is new {}",
+            instance.ident, instance.package_name
+        );
+        self.with_map_aspects(head, &[("generic", &instance.generic_map)], ";");
+    }
+
+    fn context(&mut self, context: &ContextDeclaration) {
+        let ident = &context.ident.tree;
+        self.unit_header(
+            self.span_start(context),
+            format!("context {ident} is"),
+            ident,
+        );
+        self.open();
+        self.context_clause(&context.items);
+        self.unit_end(
+            self.span_end(context),
+            format!("end context{};", end_name(ident, &context.end_ident_pos)),
+        );
+    }
+
+    fn configuration(&mut self, config: &ConfigurationDeclaration) {
+        self.context_clause(&config.context_clause);
+        let ident = &config.ident.tree;
+        self.unit_header(
+            self.span_start(config),
+            format!("configuration {ident} of {} is", config.entity_name),
+            ident,
+        );
+        self.open();
+        for item in config.decl.iter() {
+            match item {
+                ConfigurationDeclarativeItem::Use(use_clause) => {
+                    self.begin_item(self.span_start(use_clause));
+                    self.line(use_clause_text(use_clause));
+                    self.end_item(self.span_end(use_clause));
+                }
+            }
+        }
+        for vunit in config.vunit_bind_inds.iter() {
+            self.line(vunit_binding(vunit));
+        }
+        self.block_configuration(&config.block_config);
+        self.unit_end(
+            self.span_end(config),
+            format!(
+                "end configuration{};",
+                end_name(ident, &config.end_ident_pos)
+            ),
+        );
+    }
+
+    fn block_configuration(&mut self, config: &BlockConfiguration) {
+        self.begin_item(config.block_spec.pos.start());
+        self.line(format!("for {}", config.block_spec));
+        self.end_item(config.block_spec.pos.end());
+        self.open();
+        for use_clause in config.use_clauses.iter() {
+            self.begin_item(self.span_start(use_clause));
+            self.line(use_clause_text(use_clause));
+            self.end_item(self.span_end(use_clause));
+        }
+        for item in config.items.iter() {
+            match item {
+                ConfigurationItem::Block(block) => self.block_configuration(block),
+                ConfigurationItem::Component(component) => self.component_configuration(component),
+            }
+        }
+        self.indent -= 1;
+        self.line("end for;");
+        self.last_line = None;
+    }
+
+    fn component_configuration(&mut self, config: &ComponentConfiguration) {
+        let name_pos = &config.spec.component_name.pos;
+        self.begin_item(name_pos.start());
+        self.line(format!("for {}", component_specification(&config.spec)));
+        self.end_item(name_pos.end());
+        self.open();
+        if let Some(ref bind_ind) = config.bind_ind {
+            self.binding_indication(None, bind_ind, ";");
+        }
+        for vunit in config.vunit_bind_inds.iter() {
+            self.line(vunit_binding(vunit));
+        }
+        if let Some(ref block) = config.block_config {
+            self.block_configuration(block);
+        }
+        self.indent -= 1;
+        self.line("end for;");
+        self.last_line = None;
+    }
+
+    /// Print `[head] use entity_aspect` followed by the generic map and port map
+    fn binding_indication(
+        &mut self,
+        head: Option<String>,
+        bind_ind: &BindingIndication,
+        terminator: &str,
+    ) {
+        let mut first = head.unwrap_or_default();
+        if let Some(ref aspect) = bind_ind.entity_aspect {
+            if !first.is_empty() {
+                first.push(' ');
+            }
+            first.push_str("use ");
+            first.push_str(&entity_aspect(aspect));
+        }
+        self.with_map_aspects(
+            first,
+            &[
+                ("generic", &bind_ind.generic_map),
+                ("port", &bind_ind.port_map),
+            ],
+            terminator,
+        );
+    }
+
+    /// Print a line followed by map aspects on their own lines
+    fn with_map_aspects(
+        &mut self,
+        head: String,
+        aspects: &[(&str, &Option<MapAspect>)],
+        terminator: &str,
+    ) {
+        let aspects: Vec<_> = aspects
+            .iter()
+            .filter_map(|(kind, aspect)| aspect.as_ref().map(|aspect| (*kind, aspect)))
+            .collect();
+
+        if aspects.is_empty() {
+            self.line(format!("{head}{terminator}"));
+            return;
+        }
+
+        if head.is_empty() {
+            self.map_aspects(&aspects, terminator);
+        } else {
+            self.line(head);
+            self.open();
+            self.map_aspects(&aspects, terminator);
+            self.indent -= 1;
+        }
+    }
+
+    fn map_aspects(&mut self, aspects: &[(&str, &MapAspect)], terminator: &str) {
+        for (idx, (kind, aspect)) in aspects.iter().enumerate() {
+            let last = idx + 1 == aspects.len();
+            self.line(format!("{kind} map ("));
+            self.open();
+            let items = aspect
+                .list
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, elem)| {
+                    let comma = if idx + 1 < aspect.list.items.len() {
+                        ","
+                    } else {
+                        ""
+                    };
+                    let start = elem
+                        .formal
+                        .as_ref()
+                        .map(|formal| formal.pos.start())
+                        .unwrap_or_else(|| elem.actual.pos.start());
+                    ListItem {
+                        start,
+                        end: elem.actual.pos.end(),
+                        left: elem.formal.as_ref().map(|formal| formal.to_string()),
+                        right: format!("{}{comma}", elem.actual),
+                    }
+                })
+                .collect();
+            self.list(items, "=>");
+            self.close(self.tokens.get_pos(aspect.closing_paren).start());
+            self.line(if last {
+                format!("){terminator}")
+            } else {
+                ")".to_owned()
+            });
+        }
+    }
+
+    /// Print a `generic (...);` or `port (...);` clause with one element per line
+    fn interface_clause(&mut self, keyword: &str, list: &[InterfaceDeclaration]) {
+        self.line(format!("{keyword} ("));
+        self.open();
+        self.interface_list(list, InterfaceContext::Clause);
+        self.indent -= 1;
+        self.last_line = None;
+        self.line(");");
+    }
+
+    fn interface_list(&mut self, list: &[InterfaceDeclaration], context: InterfaceContext) {
+        let groups = group_interface_list(list);
+        let items = groups
+            .iter()
+            .enumerate()
+            .map(|(idx, group)| {
+                let separator = if idx + 1 < groups.len() { ";" } else { "" };
+                let (left, right) = self.interface_declaration(group, context);
+                ListItem {
+                    start: interface_start(group[0]),
+                    end: interface_end(group[0]),
+                    left,
+                    right: format!("{right}{separator}"),
+                }
+            })
+            .collect();
+        self.list(items, ":");
+    }
+
+    /// Returns the part before and after the `:` of the interface declaration
+    fn interface_declaration(
+        &self,
+        group: &[&InterfaceDeclaration],
+        context: InterfaceContext,
+    ) -> (Option<String>, String) {
+        match group[0] {
+            InterfaceDeclaration::Object(object) => {
+                let idents = join(group.iter().filter_map(|decl| match decl {
+                    InterfaceDeclaration::Object(object) => Some(&object.ident),
+                    _ => None,
+                }));
+                let mut right = match object.list_type {
+                    InterfaceType::Port => format!("{} {}", object.mode, object.subtype_indication),
+                    InterfaceType::Generic => object.subtype_indication.to_string(),
+                    InterfaceType::Parameter => {
+                        if object.mode == Mode::In && context == InterfaceContext::Function {
+                            object.subtype_indication.to_string()
+                        } else {
+                            format!("{} {}", object.mode, object.subtype_indication)
+                        }
+                    }
+                };
+                if let Some(ref expr) = object.expression {
+                    right.push_str(&format!(" := {expr}"));
+                }
+
+                let left = match object.list_type {
+                    InterfaceType::Parameter if !is_default_class(object) => {
+                        format!("{} {idents}", object.class)
+                    }
+                    _ => idents,
+                };
+                (Some(left), right)
+            }
+            InterfaceDeclaration::File(file) => {
+                let idents = join(group.iter().filter_map(|decl| match decl {
+                    InterfaceDeclaration::File(file) => Some(&file.ident),
+                    _ => None,
+                }));
+                (
+                    Some(format!("file {idents}")),
+                    file.subtype_indication.to_string(),
+                )
+            }
+            InterfaceDeclaration::Type(ident) => (None, format!("type {ident}")),
+            InterfaceDeclaration::Subprogram(spec, default) => {
+                let mut text = self.inline_subprogram_specification(spec);
+                match default {
+                    Some(SubprogramDefault::Name(name)) => text.push_str(&format!(" is {name}")),
+                    Some(SubprogramDefault::Box) => text.push_str(" is <>"),
+                    None => {}
+                }
+                (None, text)
+            }
+            InterfaceDeclaration::Package(package) => {
+                let map = match package.generic_map {
+                    InterfacePackageGenericMapAspect::Map(ref list) => join(list.items.iter()),
+                    InterfacePackageGenericMapAspect::Box => "<>".to_owned(),
+                    InterfacePackageGenericMapAspect::Default => "default".to_owned(),
+                };
+                (
+                    None,
+                    format!(
+                        "package {} is new {} generic map ({map})",
+                        package.ident, package.package_name
+                    ),
+                )
+            }
+        }
+    }
+
+    fn inline_subprogram_specification(&self, spec: &SubprogramSpecification) -> String {
+        let (prefix, header, params, suffix, context) = subprogram_parts(spec);
+        let mut text = prefix;
+        if let Some(header) = header {
+            text.push_str(&self.inline_subprogram_header(header));
+        }
+        if !params.is_empty() {
+            if spec_param_tok(spec).is_some() {
+                text.push_str(" parameter");
+            }
+            let params: Vec<String> = group_interface_list(params)
+                .iter()
+                .map(|group| {
+                    let (left, right) = self.interface_declaration(group, context);
+                    match left {
+                        Some(left) => format!("{left} : {right}"),
+                        None => right,
+                    }
+                })
+                .collect();
+            if header.is_some() || spec_param_tok(spec).is_some() {
+                text.push(' ');
+            }
+            text.push('(');
+            text.push_str(&params.join("; "));
+            text.push(')');
+        }
+        text.push_str(&suffix);
+        text
+    }
+
+    fn inline_subprogram_header(&self, header: &SubprogramHeader) -> String {
+        let generics: Vec<String> = group_interface_list(&header.generic_list)
+            .iter()
+            .map(|group| {
+                let (left, right) = self.interface_declaration(group, InterfaceContext::Clause);
+                match left {
+                    Some(left) => format!("{left} : {right}"),
+                    None => right,
+                }
+            })
+            .collect();
+        let mut text = format!(" generic ({})", generics.join("; "));
+        if let Some(ref map) = header.map_aspect {
+            text.push_str(&format!(" generic map ({})", join(map.list.items.iter())));
+        }
+        text
+    }
+
+    /// Print a subprogram specification followed by `terminator`,
+    /// splitting the parameters to multiple lines if it is too long
+    fn subprogram_specification(&mut self, spec: &SubprogramSpecification, terminator: &str) {
+        let inline = format!("{}{terminator}", self.inline_subprogram_specification(spec));
+        let (prefix, header, params, suffix, context) = subprogram_parts(spec);
+
+        let params_end = params.last().map(interface_end);
+        let has_comments = params_end
+            .map(|end| self.has_comments_before(end))
+            .unwrap_or(false);
+
+        if params.is_empty()
+            || (!has_comments && self.indentation().len() + inline.len() <= MAX_INLINE_WIDTH)
+        {
+            self.line(inline);
+            return;
+        }
+
+        let mut first = prefix;
+        if let Some(header) = header {
+            first.push_str(&self.inline_subprogram_header(header));
+        }
+        if spec_param_tok(spec).is_some() {
+            first.push_str(" parameter");
+        }
+        if header.is_some() || spec_param_tok(spec).is_some() {
+            first.push(' ');
+        }
+        first.push('(');
+        self.line(first);
+        self.open();
+        self.interface_list(params, context);
+        self.indent -= 1;
+        self.last_line = None;
+        self.line(format!("){suffix}{terminator}"));
+    }
+
+    fn declarations(&mut self, decls: &[Declaration]) {
+        let groups = self.group_declarations(decls);
+        let widths = self.alignment_widths(&groups);
+
+        for (group, width) in groups.iter().zip(widths) {
+            let decl = group[0];
+            self.begin_item(self.span_start(decl));
+            self.declaration(group, width);
+            self.end_item(self.span_end(decl));
+        }
+    }
+
+    /// Group declarations that originate from a single declaration with an identifier list
+    fn group_declarations<'d>(&self, decls: &'d [Declaration]) -> Vec<Vec<&'d Declaration>> {
+        let mut groups: Vec<Vec<&Declaration>> = Vec::new();
+        for decl in decls.iter() {
+            if let Some(group) = groups.last_mut() {
+                let prev = group[0];
+                let mergeable = matches!(
+                    (prev, decl),
+                    (Declaration::Object(..), Declaration::Object(..))
+                        | (Declaration::File(..), Declaration::File(..))
+                        | (
+                            Declaration::Attribute(Attribute::Specification(..)),
+                            Declaration::Attribute(Attribute::Specification(..))
+                        )
+                );
+                if mergeable
+                    && prev.get_start_token() == decl.get_start_token()
+                    && prev.get_end_token() == decl.get_end_token()
+                {
+                    group.push(decl);
+                    continue;
+                }
+            }
+            groups.push(vec![decl]);
+        }
+        groups
+    }
+
+    /// The alignment width of each declaration.
+    /// Object declarations on consecutive lines are aligned on the `:`.
+    fn alignment_widths(&self, groups: &[Vec<&Declaration>]) -> Vec<usize> {
+        let mut widths = vec![0; groups.len()];
+        if !self.options.align {
+            return widths;
+        }
+
+        let mut run: Vec<(usize, usize)> = Vec::new();
+        for (idx, group) in groups.iter().enumerate() {
+            let adjacent = run
+                .last()
+                .map(|(prev, _)| self.is_adjacent(groups[*prev][0], group[0]))
+                .unwrap_or(true);
+
+            let left = object_left(group);
+            if left.is_none() || !adjacent {
+                set_widths(&mut widths, &run);
+                run.clear();
+            }
+            if let Some(left) = left {
+                run.push((idx, left.len()));
+            }
+        }
+        set_widths(&mut widths, &run);
+        widths
+    }
+
+    /// Returns true if the declarations are printed on consecutive lines
+    fn is_adjacent(&self, prev: &Declaration, next: &Declaration) -> bool {
+        let prev_start = self.span_start(prev);
+        let prev_end = self.span_end(prev);
+        let next_start = self.span_start(next);
+
+        // Comments within or after the previous declaration are printed on their own lines
+        // unless they are trailing comments on its last line
+        let first = self
+            .comments
+            .partition_point(|comment| comment.start < prev_start);
+        let has_comments = self.comments[first..]
+            .iter()
+            .take_while(|comment| comment.start < next_start)
+            .any(|comment| comment.start.line != prev_end.line);
+
+        !has_comments && next_start.line <= prev_end.line + 1
+    }
+
+    fn declaration(&mut self, group: &[&Declaration], width: usize) {
+        match group[0] {
+            Declaration::Object(object) => {
+                let left = object_left(group).unwrap_or_default();
+                let mut text = format!("{left:width$} : {}", object.subtype_indication);
+                if let Some(signal_kind) = object.signal_kind {
+                    text.push_str(&format!(" {signal_kind}"));
+                }
+                if let Some(ref expr) = object.expression {
+                    text.push_str(&format!(" := {expr}"));
+                }
+                text.push(';');
+                self.line(text);
+            }
+            Declaration::File(file) => {
+                let idents = join(group.iter().filter_map(|decl| match decl {
+                    Declaration::File(file) => Some(&file.ident),
+                    _ => None,
+                }));
+                let mut text = format!("file {idents} : {}", file.subtype_indication);
+                if let Some(ref expr) = file.open_info {
+                    text.push_str(&format!(" open {expr}"));
+                }
+                if let Some(ref expr) = file.file_name {
+                    text.push_str(&format!(" is {expr}"));
+                }
+                text.push(';');
+                self.line(text);
+            }
+            Declaration::Type(typ) => self.type_declaration(typ),
+            Declaration::Component(component) => self.component(component),
+            Declaration::Attribute(Attribute::Declaration(attr)) => self.line(attr.to_string()),
+            Declaration::Attribute(Attribute::Specification(spec)) => {
+                let names = join(group.iter().filter_map(|decl| match decl {
+                    Declaration::Attribute(Attribute::Specification(spec)) => {
+                        Some(entity_name(&spec.entity_name))
+                    }
+                    _ => None,
+                }));
+                self.line(format!(
+                    "attribute {} of {names} : {} is {};",
+                    spec.ident, spec.entity_class, spec.expr
+                ));
+            }
+            Declaration::Alias(alias) => self.line(alias.to_string()),
+            Declaration::Disconnection(spec) => self.line(spec.to_string()),
+            Declaration::SubprogramDeclaration(decl) => {
+                self.subprogram_specification(&decl.specification, ";")
+            }
+            Declaration::SubprogramInstantiation(inst) => {
+                let kind = match inst.kind {
+                    SubprogramKind::Function => "function",
+                    SubprogramKind::Procedure => "procedure",
+                };
+                let mut head = format!("{kind} {} is new {}", inst.ident, inst.subprogram_name);
+                if let Some(ref signature) = inst.signature {
+                    head.push_str(&format!(" {signature}"));
+                }
+                self.with_map_aspects(head, &[("generic", &inst.generic_map)], ";");
+            }
+            Declaration::SubprogramBody(body) => self.subprogram_body(body),
+            Declaration::Use(use_clause) => self.line(use_clause_text(use_clause)),
+            Declaration::Package(instance) => self.package_instance(instance),
+            Declaration::Error(error) => self.verbatim(&error.pos),
+            Declaration::Psl(psl) => {
+                let start = self.tokens.get_pos(psl.get_start_token()).clone();
+                self.psl_verbatim(&start, self.span_end(psl));
+            }
+            Declaration::Configuration(config) => {
+                let head = format!("for {}", component_specification(&config.spec));
+                if config.vunit_bind_inds.is_empty() {
+                    self.binding_indication(Some(head), &config.bind_ind, ";");
+                } else {
+                    self.line(head);
+                    self.open();
+                    self.binding_indication(None, &config.bind_ind, ";");
+                    for vunit in config.vunit_bind_inds.iter() {
+                        self.line(vunit_binding(vunit));
+                    }
+                    self.indent -= 1;
+                    self.line("end for;");
+                }
+            }
+        }
+    }
+
+    fn type_declaration(&mut self, typ: &TypeDeclaration) {
+        let ident = &typ.ident.tree;
+        let end = self.span_end(typ);
+        match typ.def {
+            TypeDefinition::Record(ref elements) => {
+                self.line(format!("type {ident} is record"));
+                self.end_item(ident.pos.end());
+                self.open();
+                let groups = group_elements(elements);
+                let items = groups
+                    .iter()
+                    .map(|group| ListItem {
+                        start: group[0].ident.tree.pos.start(),
+                        end: subtype_end(&group[0].subtype),
+                        left: Some(join(group.iter().map(|elem| &elem.ident))),
+                        right: format!("{};", group[0].subtype),
+                    })
+                    .collect();
+                self.list(items, ":");
+                self.close(end);
+                self.line(format!(
+                    "end record{};",
+                    end_name(ident, &typ.end_ident_pos)
+                ));
+            }
+            TypeDefinition::Physical(ref physical) => {
+                self.line(format!("type {ident} is range {} units", physical.range));
+                self.end_item(ident.pos.end());
+                self.open();
+                let primary = &physical.primary_unit.tree;
+                self.begin_item(primary.pos.start());
+                self.line(format!("{primary};"));
+                self.end_item(primary.pos.end());
+                for (secondary, literal) in physical.secondary_units.iter() {
+                    self.begin_item(secondary.tree.pos.start());
+                    self.line(format!("{secondary} = {literal};"));
+                    self.end_item(secondary.tree.pos.end());
+                }
+                self.close(end);
+                self.line(format!("end units{};", end_name(ident, &typ.end_ident_pos)));
+            }
+            TypeDefinition::Protected(ref protected) => {
+                self.line(format!("type {ident} is protected"));
+                self.end_item(ident.pos.end());
+                self.open();
+                for item in protected.items.iter() {
+                    match item {
+                        ProtectedTypeDeclarativeItem::Subprogram(decl) => {
+                            self.begin_item(self.span_start(decl));
+                            self.subprogram_specification(&decl.specification, ";");
+                            self.end_item(self.span_end(decl));
+                        }
+                    }
+                }
+                self.close(end);
+                self.line(format!(
+                    "end protected{};",
+                    end_name(ident, &typ.end_ident_pos)
+                ));
+            }
+            TypeDefinition::ProtectedBody(ref body) => {
+                self.line(format!("type {ident} is protected body"));
+                self.end_item(ident.pos.end());
+                self.open();
+                self.declarations(&body.decl);
+                self.close(end);
+                self.line(format!(
+                    "end protected body{};",
+                    end_name(ident, &typ.end_ident_pos)
+                ));
+            }
+            _ => self.line(typ.to_string()),
+        }
+    }
+
+    fn component(&mut self, component: &ComponentDeclaration) {
+        let ident = &component.ident.tree;
+        self.line(format!("component {ident} is"));
+        self.end_item(ident.pos.end());
+        self.open();
+        if !component.generic_list.is_empty() {
+            self.interface_clause("generic", &component.generic_list);
+        }
+        if !component.port_list.is_empty() {
+            self.interface_clause("port", &component.port_list);
+        }
+        self.close(self.span_end(component));
+        self.line(format!(
+            "end component{};",
+            end_name(ident, &component.end_ident_pos)
+        ));
+    }
+
+    fn subprogram_body(&mut self, body: &SubprogramBody) {
+        self.subprogram_specification(&body.specification, " is");
+        self.end_item(body.specification.subpgm_designator().pos.end());
+        self.open();
+        self.declarations(&body.declarations);
+        self.begin(
+            &body.declarations,
+            body.specification.subpgm_designator().pos.end(),
+        );
+        self.sequential_statements(&body.statements);
+        self.close(self.span_end(body));
+
+        let kind = match body.specification {
+            SubprogramSpecification::Procedure(..) => "procedure",
+            SubprogramSpecification::Function(..) => "function",
+        };
+        let designator = body.specification.subpgm_designator();
+        let name = if body.end_ident_pos.is_some() {
+            format!(" {designator}")
+        } else {
+            String::new()
+        };
+        self.line(format!("end {kind}{name};"));
+    }
+
+    /// Print the `begin` keyword that follows the declarations of a block that starts at `start`
+    fn begin(&mut self, decls: &[Declaration], start: Position) {
+        let after = decls
+            .last()
+            .map(|decl| self.span_end(decl))
+            .unwrap_or(start);
+        let begin = self
+            .tokens
+            .iter()
+            .find(|token| token.kind == Kind::Begin && token.pos.start() >= after);
+        if let Some(begin) = begin {
+            self.comments_before(begin.pos.start());
+        }
+        self.indent -= 1;
+        self.line("begin");
+        self.open();
+    }
+
+    fn concurrent_statements(&mut self, statements: &[LabeledConcurrentStatement]) {
+        for statement in statements.iter() {
+            self.begin_item(concurrent_start(statement));
+            self.concurrent_statement(statement);
+            self.end_item(statement.statement.pos.end());
+        }
+    }
+
+    fn concurrent_statement(&mut self, statement: &LabeledConcurrentStatement) {
+        let label = label_prefix(&statement.label.tree);
+        let end = statement.statement.pos.end();
+        let end_label =
+            |end_label_pos: &Option<SrcPos>| end_label(&statement.label.tree, end_label_pos);
+
+        match statement.statement.item {
+            ConcurrentStatement::Error => self.verbatim(&statement.statement.pos),
+            ConcurrentStatement::PslDirective(_) | ConcurrentStatement::PslDeclaration(_) => {
+                let start = match statement.label.tree {
+                    Some(ref label) => &label.pos,
+                    None => &statement.statement.pos,
+                };
+                self.psl_verbatim(start, end);
+            }
+            ConcurrentStatement::ProcedureCall(ref pcall) => {
+                let postponed = if pcall.postponed { "postponed " } else { "" };
+                self.line(format!("{label}{postponed}{};", pcall.call));
+            }
+            ConcurrentStatement::Block(ref block) => {
+                let guard = block
+                    .guard_condition
+                    .as_ref()
+                    .map(|cond| format!(" ({cond})"))
+                    .unwrap_or_default();
+                self.line(format!("{label}block{guard} is"));
+                self.open();
+                let header = &block.header;
+                if let Some(ref generics) = header.generic_clause {
+                    self.interface_clause("generic", generics);
+                }
+                if let Some(ref map) = header.generic_map {
+                    self.map_aspects(&[("generic", map)], ";");
+                }
+                if let Some(ref ports) = header.port_clause {
+                    self.interface_clause("port", ports);
+                }
+                if let Some(ref map) = header.port_map {
+                    self.map_aspects(&[("port", map)], ";");
+                }
+                self.declarations(&block.decl);
+                self.begin(&block.decl, concurrent_start(statement));
+                self.concurrent_statements(&block.statements);
+                self.close(end);
+                self.line(format!("end block{};", end_label(&block.end_label_pos)));
+            }
+            ConcurrentStatement::Process(ref process) => {
+                let postponed = if process.postponed { "postponed " } else { "" };
+                let sensitivity = match process.sensitivity_list {
+                    Some(SensitivityList::All) => " (all)".to_owned(),
+                    Some(SensitivityList::Names(ref names)) => format!(" ({})", join(names.iter())),
+                    None => String::new(),
+                };
+                self.line(format!("{label}{postponed}process{sensitivity} is"));
+                self.open();
+                self.declarations(&process.decl);
+                self.begin(&process.decl, concurrent_start(statement));
+                self.sequential_statements(&process.statements);
+                self.close(end);
+                self.line(format!(
+                    "end {postponed}process{};",
+                    end_label(&process.end_label_pos)
+                ));
+            }
+            ConcurrentStatement::Assert(ref assert) => {
+                let postponed = if assert.postponed { "postponed " } else { "" };
+                self.line(format!(
+                    "{label}{postponed}{};",
+                    assert_text(&assert.statement)
+                ));
+            }
+            ConcurrentStatement::Assignment(ref assign) => {
+                let postponed = if assign.postponed { "postponed " } else { "" };
+                let guarded = if assign.guarded { "guarded " } else { "" };
+                let target = target_text(&assign.target);
+                let delay = delay_text(&assign.delay_mechanism);
+                self.assignment(
+                    &format!("{label}{postponed}"),
+                    &target,
+                    &format!("<= {guarded}{delay}"),
+                    &assign.rhs,
+                    waveform_text,
+                );
+            }
+            ConcurrentStatement::Instance(ref inst) => {
+                let unit = match inst.unit {
+                    InstantiatedUnit::Component(ref name) => name.to_string(),
+                    InstantiatedUnit::Entity(ref name, ref arch) => match arch {
+                        Some(arch) => format!("entity {name}({arch})"),
+                        None => format!("entity {name}"),
+                    },
+                    InstantiatedUnit::Configuration(ref name) => format!("configuration {name}"),
+                };
+                self.with_map_aspects(
+                    format!("{label}{unit}"),
+                    &[("generic", &inst.generic_map), ("port", &inst.port_map)],
+                    ";",
+                );
+            }
+            ConcurrentStatement::ForGenerate(ref gen) => {
+                self.line(format!(
+                    "{label}for {} in {} generate",
+                    gen.index_name, gen.discrete_range
+                ));
+                self.generate_body(&gen.body, concurrent_start(statement));
+                self.close(end);
+                self.line(format!("end generate{};", end_label(&gen.end_label_pos)));
+            }
+            ConcurrentStatement::IfGenerate(ref gen) => {
+                for (idx, cond) in gen.conds.conditionals.iter().enumerate() {
+                    let keyword = if idx == 0 {
+                        format!("{label}if")
+                    } else {
+                        self.begin_item(cond.condition.pos.start());
+                        "elsif".to_owned()
+                    };
+                    self.line(format!(
+                        "{keyword} {}{} generate",
+                        alternative_label(&cond.item),
+                        cond.condition
+                    ));
+                    self.generate_body(&cond.item, cond.condition.pos.end());
+                    self.indent -= 1;
+                }
+                if let Some(ref else_item) = gen.conds.else_item {
+                    self.line(format!("else {}generate", alternative_label(else_item)));
+                    // The else branch starts after the statements of the last condition
+                    let start = gen
+                        .conds
+                        .conditionals
+                        .last()
+                        .and_then(|cond| cond.item.statements.last())
+                        .map(|stmt| stmt.statement.pos.end())
+                        .unwrap_or_else(|| concurrent_start(statement));
+                    self.generate_body(else_item, start);
+                    self.indent -= 1;
+                }
+                self.indent += 1;
+                self.close(end);
+                self.line(format!("end generate{};", end_label(&gen.end_label_pos)));
+            }
+            ConcurrentStatement::CaseGenerate(ref gen) => {
+                self.line(format!("{label}case {} generate", gen.sels.expression));
+                self.open();
+                for alternative in gen.sels.alternatives.iter() {
+                    let start = alternative
+                        .choices
+                        .first()
+                        .map(|choice| choice.pos.start())
+                        .unwrap_or(end);
+                    self.begin_item(start);
+                    self.line(format!(
+                        "when {}{} =>",
+                        alternative_label(&alternative.item),
+                        choices_text(&alternative.choices)
+                    ));
+                    self.generate_body(&alternative.item, start);
+                    self.indent -= 1;
+                    self.last_line = None;
+                }
+                self.close(end);
+                self.line(format!("end generate{};", end_label(&gen.end_label_pos)));
+            }
+        }
+    }
+
+    /// Print the body of a generate statement and leave the indentation inside the body
+    fn generate_body(&mut self, body: &GenerateBody, start: Position) {
+        self.open();
+        if let Some(ref decl) = body.decl {
+            self.declarations(decl);
+            self.begin(decl, start);
+        }
+        self.concurrent_statements(&body.statements);
+        if let (Some(label), Some(_)) = (&body.alternative_label, &body.end_label_pos) {
+            self.indent -= 1;
+            self.line(format!("end {label};"));
+            self.indent += 1;
+        }
+    }
+
+    fn sequential_statements(&mut self, statements: &[LabeledSequentialStatement]) {
+        for statement in statements.iter() {
+            self.begin_item(sequential_start(statement));
+            self.sequential_statement(statement);
+            self.end_item(statement.statement.pos.end());
+        }
+    }
+
+    fn sequential_statement(&mut self, statement: &LabeledSequentialStatement) {
+        let label = label_prefix(&statement.label.tree);
+        let end = statement.statement.pos.end();
+        let end_label =
+            |end_label_pos: &Option<SrcPos>| end_label(&statement.label.tree, end_label_pos);
+
+        match statement.statement.item {
+            SequentialStatement::Wait(ref wait) => {
+                let mut text = format!("{label}wait");
+                if !wait.sensitivity_clause.is_empty() {
+                    text.push_str(&format!(" on {}", join(wait.sensitivity_clause.iter())));
+                }
+                if let Some(ref cond) = wait.condition_clause {
+                    text.push_str(&format!(" until {cond}"));
+                }
+                if let Some(ref timeout) = wait.timeout_clause {
+                    text.push_str(&format!(" for {timeout}"));
+                }
+                text.push(';');
+                self.line(text);
+            }
+            SequentialStatement::Assert(ref assert) => {
+                self.line(format!("{label}{};", assert_text(assert)));
+            }
+            SequentialStatement::Report(ref report) => {
+                let mut text = format!("{label}report {}", report.report);
+                if let Some(ref severity) = report.severity {
+                    text.push_str(&format!(" severity {severity}"));
+                }
+                text.push(';');
+                self.line(text);
+            }
+            SequentialStatement::VariableAssignment(ref assign) => {
+                self.assignment(
+                    &label,
+                    &target_text(&assign.target),
+                    ":=",
+                    &assign.rhs,
+                    |expr| expr.to_string(),
+                );
+            }
+            SequentialStatement::SignalAssignment(ref assign) => {
+                let delay = delay_text(&assign.delay_mechanism);
+                self.assignment(
+                    &label,
+                    &target_text(&assign.target),
+                    &format!("<= {delay}"),
+                    &assign.rhs,
+                    waveform_text,
+                );
+            }
+            SequentialStatement::SignalForceAssignment(ref assign) => {
+                self.assignment(
+                    &label,
+                    &target_text(&assign.target),
+                    &format!("<= force{}", force_mode_text(&assign.force_mode)),
+                    &assign.rhs,
+                    |expr| expr.to_string(),
+                );
+            }
+            SequentialStatement::SignalReleaseAssignment(ref assign) => {
+                self.line(format!(
+                    "{label}{} <= release{};",
+                    target_text(&assign.target),
+                    force_mode_text(&assign.force_mode)
+                ));
+            }
+            SequentialStatement::ProcedureCall(ref call) => {
+                self.line(format!("{label}{call};"));
+            }
+            SequentialStatement::If(ref ifstmt) => {
+                for (idx, cond) in ifstmt.conds.conditionals.iter().enumerate() {
+                    if idx == 0 {
+                        self.line(format!("{label}if {} then", cond.condition));
+                    } else {
+                        self.begin_item(cond.condition.pos.start());
+                        self.line(format!("elsif {} then", cond.condition));
+                    }
+                    self.end_item(cond.condition.pos.end());
+                    self.open();
+                    self.sequential_statements(&cond.item);
+                    self.indent -= 1;
+                }
+                if let Some(ref else_item) = ifstmt.conds.else_item {
+                    if let Some(start) = else_item.first().map(sequential_start) {
+                        self.comments_before(start);
+                    }
+                    self.line("else");
+                    self.last_line = None;
+                    self.open();
+                    self.sequential_statements(else_item);
+                    self.indent -= 1;
+                }
+                self.indent += 1;
+                self.close(end);
+                self.line(format!("end if{};", end_label(&ifstmt.end_label_pos)));
+            }
+            SequentialStatement::Case(ref case) => {
+                let matching = if case.is_matching { "?" } else { "" };
+                self.line(format!("{label}case{matching} {} is", case.expression));
+                self.end_item(case.expression.pos.end());
+                self.open();
+                for alternative in case.alternatives.iter() {
+                    if let Some(choice) = alternative.choices.first() {
+                        self.begin_item(choice.pos.start());
+                    }
+                    self.line(format!("when {} =>", choices_text(&alternative.choices)));
+                    self.last_line = None;
+                    self.open();
+                    self.sequential_statements(&alternative.item);
+                    self.indent -= 1;
+                }
+                self.close(end);
+                self.line(format!(
+                    "end case{matching}{};",
+                    end_label(&case.end_label_pos)
+                ));
+            }
+            SequentialStatement::Loop(ref lstmt) => {
+                let scheme = match lstmt.iteration_scheme {
+                    Some(IterationScheme::While(ref cond)) => format!("while {cond} "),
+                    Some(IterationScheme::For(ref index, ref drange)) => {
+                        format!("for {index} in {drange} ")
+                    }
+                    None => String::new(),
+                };
+                self.line(format!("{label}{scheme}loop"));
+                self.open();
+                self.sequential_statements(&lstmt.statements);
+                self.close(end);
+                self.line(format!("end loop{};", end_label(&lstmt.end_label_pos)));
+            }
+            SequentialStatement::Next(ref next) => {
+                self.line(format!(
+                    "{label}next{};",
+                    loop_control(&next.loop_label, &next.condition)
+                ));
+            }
+            SequentialStatement::Exit(ref exit) => {
+                self.line(format!(
+                    "{label}exit{};",
+                    loop_control(&exit.loop_label, &exit.condition)
+                ));
+            }
+            SequentialStatement::Return(ref ret) => match ret.expression {
+                Some(ref expr) => self.line(format!("{label}return {expr};")),
+                None => self.line(format!("{label}return;")),
+            },
+            SequentialStatement::Null => self.line(format!("{label}null;")),
+            SequentialStatement::Error => self.verbatim(&statement.statement.pos),
+        }
+    }
+
+    /// Print a simple, conditional or selected assignment
+    fn assignment<T>(
+        &mut self,
+        prefix: &str,
+        target: &str,
+        operator: &str,
+        rhs: &AssignmentRightHand<T>,
+        item: impl Fn(&T) -> String,
+    ) {
+        let operator = operator.trim_end();
+        match rhs {
+            AssignmentRightHand::Simple(value) => {
+                self.line(format!("{prefix}{target} {operator} {};", item(value)));
+            }
+            AssignmentRightHand::Conditional(conds) => {
+                let mut parts: Vec<String> = conds
+                    .conditionals
+                    .iter()
+                    .map(|cond| format!("{} when {}", item(&cond.item), cond.condition))
+                    .collect();
+                if let Some(ref else_item) = conds.else_item {
+                    parts.push(item(else_item));
+                }
+                if parts.len() <= 2 {
+                    self.line(format!(
+                        "{prefix}{target} {operator} {};",
+                        parts.join(" else ")
+                    ));
+                } else {
+                    let indent = self.indent_unit();
+                    let last = parts.len() - 1;
+                    let mut text = format!("{prefix}{target} {operator} {} else", parts[0]);
+                    for (idx, part) in parts.iter().enumerate().skip(1) {
+                        let terminator = if idx == last { ";" } else { " else" };
+                        text.push_str(&format!("\n{indent}{part}{terminator}"));
+                    }
+                    self.line(text);
+                }
+            }
+            AssignmentRightHand::Selected(selection) => {
+                let indent = self.indent_unit();
+                let mut text = format!(
+                    "{prefix}with {} select {target} {operator}",
+                    selection.expression
+                );
+                let last = selection.alternatives.len().saturating_sub(1);
+                for (idx, alternative) in selection.alternatives.iter().enumerate() {
+                    let terminator = if idx == last { ";" } else { "," };
+                    text.push_str(&format!(
+                        "\n{indent}{} when {}{terminator}",
+                        item(&alternative.item),
+                        choices_text(&alternative.choices)
+                    ));
+                }
+                self.line(text);
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum InterfaceContext {
+    /// Generic and port clauses
+    Clause,
+    Procedure,
+    Function,
+}
+
+fn join<T: Display>(items: impl Iterator<Item = T>) -> String {
+    items
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn end_name(ident: &Ident, end_ident_pos: &Option<SrcPos>) -> String {
+    if end_ident_pos.is_some() {
+        format!(" {ident}")
+    } else {
+        String::new()
+    }
+}
+
+fn end_label(label: &Option<Ident>, end_label_pos: &Option<SrcPos>) -> String {
+    match (label, end_label_pos) {
+        (Some(label), Some(_)) => format!(" {label}"),
+        _ => String::new(),
+    }
+}
+
+fn label_prefix(label: &Option<Ident>) -> String {
+    match label {
+        Some(label) => format!("{label}: "),
+        None => String::new(),
+    }
+}
+
+fn concurrent_start(statement: &LabeledConcurrentStatement) -> Position {
+    match statement.label.tree {
+        Some(ref label) => label.pos.start(),
+        None => statement.statement.pos.start(),
+    }
+}
+
+fn sequential_start(statement: &LabeledSequentialStatement) -> Position {
+    match statement.label.tree {
+        Some(ref label) => label.pos.start(),
+        None => statement.statement.pos.start(),
+    }
+}
+
+fn alternative_label(body: &GenerateBody) -> String {
+    match body.alternative_label {
+        Some(ref label) => format!("{label}: "),
+        None => String::new(),
+    }
+}
+
+fn choices_text(choices: &[WithPos<Choice>]) -> String {
+    choices
+        .iter()
+        .map(|choice| choice.to_string())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn context_item(item: &ContextItem) -> String {
+    match item {
+        ContextItem::Use(use_clause) => use_clause_text(use_clause),
+        ContextItem::Library(library) => {
+            format!("library {};", join(library.name_list.items.iter()))
+        }
+        ContextItem::Context(context) => {
+            format!("context {};", join(context.name_list.items.iter()))
+        }
+    }
+}
+
+fn use_clause_text(use_clause: &UseClause) -> String {
+    format!("use {};", join(use_clause.name_list.items.iter()))
+}
+
+fn vunit_binding(vunit: &VUnitBindingIndication) -> String {
+    format!("use vunit {};", join(vunit.vunit_list.iter()))
+}
+
+fn component_specification(spec: &ComponentSpecification) -> String {
+    let list = match spec.instantiation_list {
+        InstantiationList::Labels(ref labels) => join(labels.iter()),
+        InstantiationList::Others => "others".to_owned(),
+        InstantiationList::All => "all".to_owned(),
+    };
+    format!("{list} : {}", spec.component_name)
+}
+
+fn entity_aspect(aspect: &EntityAspect) -> String {
+    match aspect {
+        EntityAspect::Entity(name, Some(arch)) => format!("entity {name}({arch})"),
+        EntityAspect::Entity(name, None) => format!("entity {name}"),
+        EntityAspect::Configuration(name) => format!("configuration {name}"),
+        EntityAspect::Open => "open".to_owned(),
+    }
+}
+
+fn entity_name(name: &EntityName) -> String {
+    match name {
+        EntityName::Name(tag) => match tag.signature {
+            Some(ref signature) => format!("{}{signature}", tag.designator),
+            None => tag.designator.to_string(),
+        },
+        EntityName::All => "all".to_owned(),
+        EntityName::Others => "others".to_owned(),
+    }
+}
+
+fn assert_text(assert: &AssertStatement) -> String {
+    let mut text = format!("assert {}", assert.condition);
+    if let Some(ref report) = assert.report {
+        text.push_str(&format!(" report {report}"));
+    }
+    if let Some(ref severity) = assert.severity {
+        text.push_str(&format!(" severity {severity}"));
+    }
+    text
+}
+
+fn target_text(target: &WithPos<Target>) -> String {
+    match target.item {
+        Target::Name(ref name) => name.to_string(),
+        Target::Aggregate(ref assocs) => format!("({})", join(assocs.iter())),
+    }
+}
+
+fn delay_text(delay: &Option<DelayMechanism>) -> String {
+    match delay {
+        Some(DelayMechanism::Transport) => "transport ".to_owned(),
+        Some(DelayMechanism::Inertial {
+            reject: Some(ref reject),
+        }) => format!("reject {reject} inertial "),
+        Some(DelayMechanism::Inertial { reject: None }) => "inertial ".to_owned(),
+        None => String::new(),
+    }
+}
+
+fn force_mode_text(mode: &Option<ForceMode>) -> String {
+    match mode {
+        Some(ForceMode::In) => " in".to_owned(),
+        Some(ForceMode::Out) => " out".to_owned(),
+        None => String::new(),
+    }
+}
+
+fn waveform_text(waveform: &Waveform) -> String {
+    match waveform {
+        Waveform::Elements(elements) => elements
+            .iter()
+            .map(|elem| match elem.after {
+                Some(ref after) => format!("{} after {after}", elem.value),
+                None => elem.value.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        Waveform::Unaffected => "unaffected".to_owned(),
+    }
+}
+
+fn loop_control(label: &Option<WithRef<Ident>>, condition: &Option<WithPos<Expression>>) -> String {
+    let mut text = String::new();
+    if let Some(label) = label {
+        text.push_str(&format!(" {}", label.item));
+    }
+    if let Some(condition) = condition {
+        text.push_str(&format!(" when {condition}"));
+    }
+    text
+}
+
+/// The class and identifiers of a group of object declarations
+fn object_left(group: &[&Declaration]) -> Option<String> {
+    let Declaration::Object(object) = group[0] else {
+        return None;
+    };
+    let idents = join(group.iter().filter_map(|decl| match decl {
+        Declaration::Object(object) => Some(&object.ident),
+        _ => None,
+    }));
+    Some(format!("{} {idents}", object.class))
+}
+
+fn set_widths(widths: &mut [usize], run: &[(usize, usize)]) {
+    let width = run.iter().map(|(_, width)| *width).max().unwrap_or(0);
+    for (idx, _) in run {
+        widths[*idx] = width;
+    }
+}
+
+fn is_default_class(object: &InterfaceObjectDeclaration) -> bool {
+    matches!(
+        (object.class, object.mode),
+        (ObjectClass::Constant, Mode::In) | (ObjectClass::Variable, Mode::Out | Mode::InOut)
+    )
+}
+
+/// Group interface declarations that originate from a single declaration with an identifier list
+fn group_interface_list(list: &[InterfaceDeclaration]) -> Vec<Vec<&InterfaceDeclaration>> {
+    let mut groups: Vec<Vec<&InterfaceDeclaration>> = Vec::new();
+    for decl in list.iter() {
+        if let Some(group) = groups.last_mut() {
+            let same = match (group[0], decl) {
+                (InterfaceDeclaration::Object(prev), InterfaceDeclaration::Object(next)) => {
+                    prev.subtype_indication.type_mark.pos == next.subtype_indication.type_mark.pos
+                        && prev.mode == next.mode
+                        && prev.class == next.class
+                }
+                (InterfaceDeclaration::File(prev), InterfaceDeclaration::File(next)) => {
+                    prev.subtype_indication.type_mark.pos == next.subtype_indication.type_mark.pos
+                }
+                _ => false,
+            };
+            if same {
+                group.push(decl);
+                continue;
+            }
+        }
+        groups.push(vec![decl]);
+    }
+    groups
+}
+
+fn group_elements(elements: &[ElementDeclaration]) -> Vec<Vec<&ElementDeclaration>> {
+    let mut groups: Vec<Vec<&ElementDeclaration>> = Vec::new();
+    for elem in elements.iter() {
+        if let Some(group) = groups.last_mut() {
+            if group[0].subtype.type_mark.pos == elem.subtype.type_mark.pos {
+                group.push(elem);
+                continue;
+            }
+        }
+        groups.push(vec![elem]);
+    }
+    groups
+}
+
+fn subtype_end(subtype: &SubtypeIndication) -> Position {
+    match subtype.constraint {
+        Some(ref constraint) => constraint.pos.end(),
+        None => subtype.type_mark.pos.end(),
+    }
+}
+
+fn interface_start(decl: &InterfaceDeclaration) -> Position {
+    match decl {
+        InterfaceDeclaration::Object(object) => object.ident.tree.pos.start(),
+        InterfaceDeclaration::File(file) => file.ident.tree.pos.start(),
+        InterfaceDeclaration::Type(ident) => ident.tree.pos.start(),
+        InterfaceDeclaration::Subprogram(spec, _) => spec.subpgm_designator().pos.start(),
+        InterfaceDeclaration::Package(package) => package.ident.tree.pos.start(),
+    }
+}
+
+fn interface_end(decl: &InterfaceDeclaration) -> Position {
+    match decl {
+        InterfaceDeclaration::Object(object) => match object.expression {
+            Some(ref expr) => expr.pos.end(),
+            None => subtype_end(&object.subtype_indication),
+        },
+        InterfaceDeclaration::File(file) => subtype_end(&file.subtype_indication),
+        InterfaceDeclaration::Type(ident) => ident.tree.pos.end(),
+        InterfaceDeclaration::Subprogram(spec, default) => match default {
+            Some(SubprogramDefault::Name(name)) => name.pos.end(),
+            _ => match spec {
+                SubprogramSpecification::Function(function) => function.return_type.pos.end(),
+                SubprogramSpecification::Procedure(procedure) => procedure
+                    .parameter_list
+                    .last()
+                    .map(interface_end)
+                    .unwrap_or_else(|| procedure.designator.tree.pos.end()),
+            },
+        },
+        InterfaceDeclaration::Package(package) => package.package_name.pos.end(),
+    }
+}
+
+fn spec_param_tok(spec: &SubprogramSpecification) -> Option<crate::syntax::TokenId> {
+    match spec {
+        SubprogramSpecification::Procedure(procedure) => procedure.param_tok,
+        SubprogramSpecification::Function(function) => function.param_tok,
+    }
+}
+
+/// Returns the text before the parameter list, the header, the parameters,
+/// the text after the parameter list and the kind of parameters
+fn subprogram_parts(
+    spec: &SubprogramSpecification,
+) -> (
+    String,
+    Option<&SubprogramHeader>,
+    &[InterfaceDeclaration],
+    String,
+    InterfaceContext,
+) {
+    match spec {
+        SubprogramSpecification::Procedure(procedure) => (
+            format!("procedure {}", procedure.designator),
+            procedure.header.as_ref(),
+            &procedure.parameter_list,
+            String::new(),
+            InterfaceContext::Procedure,
+        ),
+        SubprogramSpecification::Function(function) => {
+            let purity = if function.pure { "" } else { "impure " };
+            (
+                format!("{purity}function {}", function.designator),
+                function.header.as_ref(),
+                &function.parameter_list,
+                match function.return_identifier {
+                    Some(ref ident) => format!(" return {ident} of {}", function.return_type),
+                    None => format!(" return {}", function.return_type),
+                },
+                InterfaceContext::Function,
+            )
+        }
+    }
+}
+
+/// The spelling of each keyword in the design file, used to preserve the keyword case
+fn keyword_spellings(design_file: &DesignFile) -> FnvHashMap<&'static str, String> {
+    let mut spellings = FnvHashMap::default();
+    for (tokens, _) in design_file.design_units.iter() {
+        for token in tokens.iter() {
+            if !is_keyword(token.kind) {
+                continue;
+            }
+            let keyword = kind_str(token.kind);
+            if spellings.contains_key(keyword) {
+                continue;
+            }
+            let range = token.pos.range();
+            let contents = token.pos.source.contents();
+            if let Some(line) = contents.get_line(range.start.line as usize) {
+                let spelling: String = line
+                    .chars()
+                    .skip(range.start.character as usize)
+                    .take(keyword.len())
+                    .collect();
+                if spelling.eq_ignore_ascii_case(keyword) {
+                    spellings.insert(keyword, spelling);
+                }
+            }
+        }
+    }
+    spellings
+}
+
+/// Returns true if the kind may be a keyword, the spelling must also be checked
+fn is_keyword(kind: Kind) -> bool {
+    kind_str(kind).chars().all(|chr| chr.is_ascii_alphabetic())
+}
+
+/// Change the case of all keywords of the formatted text
+fn apply_keyword_case(
+    text: &str,
+    file_name: &Path,
+    case: KeywordCase,
+    spellings: &FnvHashMap<&'static str, String>,
+) -> String {
+    if case == KeywordCase::Lower {
+        return text.to_owned();
+    }
+
+    // Keywords that do not occur in the original file use the majority case
+    let upper = spellings
+        .values()
+        .filter(|spelling| spelling.chars().all(|chr| chr.is_ascii_uppercase()))
+        .count();
+    let default_upper = case == KeywordCase::Upper || 2 * upper > spellings.len();
+
+    let mut lines: Vec<Vec<char>> = text
+        .split('\n')
+        .map(|line| line.chars().collect())
+        .collect();
+    let symbols = Symbols::default();
+    let source = Source::inline(file_name, text);
+    let contents = source.contents();
+    let mut tokenizer = Tokenizer::new(&symbols, &source, ContentReader::new(&contents));
+
+    while let Ok(Some(token)) = tokenizer.pop() {
+        if !is_keyword(token.kind) {
+            continue;
+        }
+        let keyword = kind_str(token.kind);
+        let spelling = match (case, spellings.get(keyword)) {
+            (KeywordCase::Preserve, Some(spelling)) => spelling.clone(),
+            _ if default_upper => keyword.to_ascii_uppercase(),
+            _ => keyword.to_owned(),
+        };
+        let range = token.pos.range();
+        let start = range.start.character as usize;
+        if let Some(line) = lines.get_mut(range.start.line as usize) {
+            let Some(text) = line.get(start..start + keyword.len()) else {
+                continue;
+            };
+            // Operators such as `-` have an alphabetic name but are not keywords
+            if !text
+                .iter()
+                .collect::<String>()
+                .eq_ignore_ascii_case(keyword)
+            {
+                continue;
+            }
+            for (target, chr) in line[start..].iter_mut().zip(spelling.chars()) {
+                *target = chr;
+            }
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    const CORPUS: &str = "\
+-- A leading comment
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+  generic (
+    width  : natural := 8; -- the data width
+    g_name : string := \"a\"\"b\"
+  );
+  port (
+    clk, rst : in std_logic;
+    data_out : out std_logic_vector(width - 1 downto 0)
+  );
+end entity top;
+
+architecture rtl of top is
+  constant c_real : real := 1.5e-9;
+  signal a, b     : std_logic;
+
+  -- The counter
+  signal counter : natural range 0 to 15;
+  type state_t is (idle, running);
+  type rec_t is record
+    field1, field2 : natural;
+    field3         : bit;
+  end record;
+  component sub is
+    generic (
+      g : natural
+    );
+    port (
+      x : in bit;
+      y : out bit
+    );
+  end component;
+  function add(lhs, rhs : natural) return natural is
+  begin
+    return lhs + rhs;
+  end function;
+begin
+  main: process (clk) is
+    variable v : natural;
+  begin
+    if rising_edge(clk) then
+      -- Reset handling
+      if rst = '1' then
+        counter <= 0;
+      elsif counter = 15 then
+        counter <= 0;
+      else
+        counter <= counter + 1;
+      end if;
+    end if;
+    case counter is
+      when 0 | 1 =>
+        v := 1;
+      when others =>
+        null;
+    end case;
+    for i in 0 to 3 loop
+      exit when i = 2;
+    end loop;
+  end process main;
+
+  gen: for i in 0 to width - 1 generate
+    data_out(i) <= a when counter = i else b;
+  end generate gen;
+
+  gen2: if g1: width > 4 generate
+    a <= '1';
+  elsif width > 2 generate
+    a <= '0';
+  else generate
+    a <= 'Z';
+  end generate;
+
+  gen3: case width generate
+    when 8 =>
+      b <= '1';
+    when others =>
+      b <= '0';
+  end generate;
+
+  inst: sub
+    generic map (
+      g => 1
+    )
+    port map (
+      x => '0',
+      y => open
+    );
+
+  with counter select b <=
+    '1' when 0,
+    '0' when others;
+end architecture;
+
+configuration cfg of top is
+  for rtl
+    for inst : sub
+      use entity work.sub(rtl)
+        generic map (
+          g => 2
+        );
+    end for;
+    for gen
+    end for;
+  end for;
+end configuration;
+
+package pkg is
+  generic (
+    type t;
+    function f(x : t) return t is <>
+  );
+  procedure proc(signal s : out bit; v : inout natural; c : in natural := 0);
+end package;
+
+package body pkg is
+  procedure proc(signal s : out bit; v : inout natural; c : in natural := 0) is
+  begin
+    s <= '1' after 1 ns, '0' after 2 ns;
+    wait until s = '0' for 10 ns;
+    assert v > 0 report \"fail\" severity error;
+  end procedure;
+end package body;
+-- A final comment
+";
+
+    const MORE_CONSTRUCTS: &str = "
+context ctx is
+  library ieee;
+  use ieee.numeric_std.all;
+end context;
+
+package gpkg is new work.pkg
+  generic map (
+    t => integer
+  );
+
+package types is
+  type time_t is range 0 to 1000 units
+    ps;
+    ns = 1000 ps;
+  end units;
+  type prot_t is protected
+    procedure inc;
+    impure function get return natural;
+  end protected prot_t;
+  type arr_t is array (natural range <>) of bit;
+  subtype word_t is arr_t(0 to 31);
+  alias w_t is word_t;
+  attribute keep : boolean;
+  constant c1, c2 : natural := 0;
+  attribute keep of c1, c2 : constant is true;
+  file f : text open read_mode is \"file.txt\";
+  function id generic (type t) parameter (x : t) return t;
+  function id_int is new id
+    generic map (
+      t => integer
+    );
+end package;
+
+package body types is
+  type prot_t is protected body
+    variable count : natural := 0;
+
+    procedure inc is
+    begin
+      count := count + 1;
+    end procedure;
+    impure function get return natural is
+    begin
+      return count;
+    end function;
+  end protected body;
+end package body;
+
+entity checked is
+  port (
+    clk : in bit
+  );
+begin
+  assert clk = '0' or clk = '1';
+end entity;
+
+architecture a of checked is
+  signal s, t : bit_vector(0 to 3);
+  for all : sub use entity work.sub;
+begin
+  blk: block (clk = '1') is
+    signal inner : bit;
+  begin
+    inner <= '1';
+  end block blk;
+
+  postponed assert s(0) = '0' report \"bad\";
+
+  t <= transport s after 1 ns when clk = '1' else
+    (others => '0') when clk = '0' else
+    unaffected;
+
+  process is
+    variable i : natural := 0;
+  begin
+    while i < 10 loop
+      i := i + 1;
+      next when i = 5;
+    end loop;
+    s <= force x\"F\";
+    s <= force in x\"0\";
+    s <= release out;
+    (s(0), s(1)) <= t(0 to 1);
+    report \"done\" severity note;
+    wait on clk;
+  end process;
+end architecture;
+";
+
+    fn parse(code: &str) -> DesignFile {
+        Code::new(code).design_file()
+    }
+
+    fn format(code: &str, options: &FormatOptions) -> String {
+        format_design_file(&parse(code), options)
+    }
+
+    /// The debug representation of the design file without source positions and token ids
+    fn without_positions(design_file: &DesignFile) -> String {
+        let units: Vec<&AnyDesignUnit> = design_file
+            .design_units
+            .iter()
+            .map(|(_, unit)| unit)
+            .collect();
+        let text = format!("{units:?}");
+        let mut result = String::new();
+        let mut rest = text.as_str();
+        while let Some(idx) = rest.find("SrcPos {") {
+            result.push_str(&rest[..idx]);
+            let mut depth = 0;
+            let mut end = idx;
+            for (offset, chr) in rest[idx..].char_indices() {
+                match chr {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = idx + offset + 1;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            rest = &rest[end..];
+        }
+        result.push_str(rest);
+
+        let result = remove_numbers(&result, "TokenId(", ")");
+        // Symbols of separately parsed files have different ids
+        remove_numbers(&result, "Symbol { id: ", ", ")
+    }
+
+    /// Remove the digits between all occurrences of the prefix and suffix
+    fn remove_numbers(text: &str, prefix: &str, suffix: &str) -> String {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(idx) = rest.find(prefix) {
+            result.push_str(&rest[..idx]);
+            let end = idx + rest[idx..].find(suffix).unwrap() + suffix.len();
+            rest = &rest[end..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn assert_round_trip(code: &str, options: &FormatOptions) {
+        let formatted = format(code, options);
+        assert_eq!(
+            without_positions(&parse(code)),
+            without_positions(&parse(&formatted)),
+            "Formatted code is not equivalent:\n{formatted}"
+        );
+    }
+
+    #[test]
+    fn formatting_keeps_the_design_file_equivalent() {
+        assert_round_trip(CORPUS, &FormatOptions::default());
+        assert_round_trip(
+            CORPUS,
+            &FormatOptions {
+                indent_width: 4,
+                keyword_case: KeywordCase::Upper,
+                align: false,
+            },
+        );
+    }
+
+    #[test]
+    fn formatting_keeps_more_constructs_equivalent() {
+        assert_round_trip(MORE_CONSTRUCTS, &FormatOptions::default());
+        assert_eq!(
+            format(MORE_CONSTRUCTS, &FormatOptions::default()),
+            MORE_CONSTRUCTS.trim_start()
+        );
+    }
+
+    #[test]
+    fn reindents_and_aligns() {
+        let code = "
+ENTITY ent IS PORT(a,b:IN BIT;result:OUT BIT_VECTOR(0 TO 1));END ENTITY;
+architecture a of ent is
+signal s:bit;   signal long_name:bit := '0';
+
+
+    signal other : bit;
+begin
+  inst : entity work.sub port map(x=>a, long_formal=>b);
+result(0)<=a and b;end;
+";
+        assert_eq!(
+            format(code, &FormatOptions::default()),
+            "\
+entity ent is
+  port (
+    a, b   : in BIT;
+    result : out BIT_VECTOR(0 to 1)
+  );
+end entity;
+
+architecture a of ent is
+  signal s         : bit;
+  signal long_name : bit := '0';
+
+  signal other : bit;
+begin
+  inst: entity work.sub
+    port map (
+      x           => a,
+      long_formal => b
+    );
+  result(0) <= a and b;
+end architecture;
+"
+        );
+    }
+
+    #[test]
+    fn keeps_comments_at_the_nearest_item() {
+        let code = "\
+-- The entity
+entity ent is
+end entity;
+
+architecture a of ent is
+  -- Declarations
+  signal s : bit; -- after s
+begin
+  process is
+  begin
+    -- Before the statement
+    s <= '1'; -- after the statement
+    /* a multi-line
+       comment */
+    -- At the end of the process
+  end process;
+end architecture;
+
+-- Trailing comment
+--   with indentation
+";
+        let options = FormatOptions::default();
+        assert_eq!(format(code, &options), code);
+    }
+
+    #[test]
+    fn changes_keyword_case() {
+        let code = "\
+ENTITY ent IS
+END ENTITY;
+
+Architecture a Of ent Is
+  SIGNAL s : bit;
+Begin
+End Architecture;
+";
+        assert_eq!(
+            format(
+                code,
+                &FormatOptions {
+                    keyword_case: KeywordCase::Lower,
+                    ..FormatOptions::default()
+                }
+            ),
+            "\
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+begin
+end architecture;
+"
+        );
+        assert_eq!(
+            format(
+                code,
+                &FormatOptions {
+                    keyword_case: KeywordCase::Upper,
+                    ..FormatOptions::default()
+                }
+            ),
+            "\
+ENTITY ent IS
+END ENTITY;
+
+ARCHITECTURE a OF ent IS
+  SIGNAL s : bit;
+BEGIN
+END ARCHITECTURE;
+"
+        );
+        assert_eq!(
+            format(
+                code,
+                &FormatOptions {
+                    keyword_case: KeywordCase::Preserve,
+                    ..FormatOptions::default()
+                }
+            ),
+            "\
+ENTITY ent IS
+END ENTITY;
+
+Architecture a Of ent IS
+  SIGNAL s : bit;
+Begin
+END Architecture;
+"
+        );
+    }
+
+    #[test]
+    fn alignment_and_indentation_are_configurable() {
+        let code = "\
+entity ent is
+  generic (
+    width  : natural;
+    g : boolean
+  );
+end entity;
+";
+        assert_eq!(
+            format(
+                code,
+                &FormatOptions {
+                    indent_width: 4,
+                    align: false,
+                    ..FormatOptions::default()
+                }
+            ),
+            "\
+entity ent is
+    generic (
+        width : natural;
+        g : boolean
+    );
+end entity;
+"
+        );
+    }
+
+    #[test]
+    fn splits_long_parameter_lists() {
+        let code = "\
+package pkg is
+  procedure short(a : natural);
+  procedure long_procedure_name(signal first_parameter : out bit_vector; variable second : inout natural; third : natural := 0);
+end package;
+";
+        assert_eq!(
+            format(code, &FormatOptions::default()),
+            "\
+package pkg is
+  procedure short(a : in natural);
+  procedure long_procedure_name(
+    signal first_parameter : out bit_vector;
+    second                 : inout natural;
+    third                  : in natural := 0
+  );
+end package;
+"
+        );
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let options = FormatOptions::default();
+        let formatted = format(CORPUS, &options);
+        assert_eq!(format(&formatted, &options), formatted);
+    }
+
+    #[test]
+    fn formatted_corpus_is_unchanged() {
+        assert_eq!(format(CORPUS, &FormatOptions::default()), CORPUS);
+    }
+
+    #[test]
+    fn keeps_psl_verbatim() {
+        let code = "\
+architecture a of ent is
+  default clock is rising_edge(clk);
+begin
+  -- psl lbl: assert always
+  --   req -> next ack;
+  cover {req; ack};
+end architecture;
+";
+        assert_eq!(format(code, &FormatOptions::default()), code);
+    }
+
+    #[test]
+    fn keeps_comment_after_last_token() {
+        let code = "entity ent is\nend entity; -- the end\n";
+        assert_eq!(format(code, &FormatOptions::default()), code);
+    }
+}
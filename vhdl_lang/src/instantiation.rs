@@ -0,0 +1,369 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{Found, FoundDeclaration, NotFinished, SearchState, Searcher};
+use crate::ast::{EntityDeclaration, InterfaceDeclaration};
+use crate::data::Symbol;
+use crate::syntax::TokenAccess;
+use vhdl_lang::ast::search::Finished;
+
+/// Whether the instantiation refers to the entity directly or through a component
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum InstantiationStyle {
+    /// `inst : entity lib.ent port map (...);`
+    #[default]
+    Entity,
+    /// A component declaration followed by `inst : component ent port map (...);`
+    Component,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct InstantiationOptions {
+    pub style: InstantiationStyle,
+    /// The number of spaces per indentation level
+    pub indent_width: usize,
+    /// Prepended to the name of a generic or port to form the name of its actual
+    pub actual_prefix: String,
+    /// Appended to the name of a generic or port to form the name of its actual
+    pub actual_suffix: String,
+    /// Align the `=>` of the generic and port maps
+    pub align: bool,
+    /// Also associate generics that have a default value
+    pub generics_with_default: bool,
+}
+
+impl Default for InstantiationOptions {
+    fn default() -> Self {
+        InstantiationOptions {
+            style: InstantiationStyle::default(),
+            indent_width: 2,
+            actual_prefix: String::new(),
+            actual_suffix: String::new(),
+            align: true,
+            generics_with_default: true,
+        }
+    }
+}
+
+/// The name that is used as formal of an interface element in a map aspect
+/// and whether the element has a default value
+fn formal_of(decl: &InterfaceDeclaration) -> (String, bool) {
+    match decl {
+        InterfaceDeclaration::Object(object) => (
+            object.ident.tree.item.name_utf8(),
+            object.expression.is_some(),
+        ),
+        InterfaceDeclaration::File(file) => (file.ident.tree.item.name_utf8(), false),
+        InterfaceDeclaration::Type(ident) => (ident.tree.item.name_utf8(), false),
+        InterfaceDeclaration::Subprogram(spec, default) => {
+            (spec.subpgm_designator().item.to_string(), default.is_some())
+        }
+        InterfaceDeclaration::Package(package) => (package.ident.tree.item.name_utf8(), false),
+    }
+}
+
+struct Template<'o> {
+    options: &'o InstantiationOptions,
+    lines: Vec<String>,
+}
+
+impl<'o> Template<'o> {
+    fn indent(&self, level: usize) -> String {
+        " ".repeat(level * self.options.indent_width)
+    }
+
+    /// A component declaration with the same generics and ports as the entity
+    fn component_declaration(&mut self, entity: &EntityDeclaration) {
+        let name = entity.ident.tree.item.name_utf8();
+        self.lines.push(format!("component {name} is"));
+        let clauses = [
+            ("generic", &entity.generic_clause),
+            ("port", &entity.port_clause),
+        ];
+        for (keyword, clause) in clauses {
+            let Some(clause) = clause.as_ref().filter(|clause| !clause.is_empty()) else {
+                continue;
+            };
+            self.lines.push(format!("{}{keyword} (", self.indent(1)));
+            for (idx, decl) in clause.iter().enumerate() {
+                let separator = if idx + 1 < clause.len() { ";" } else { "" };
+                self.lines
+                    .push(format!("{}{decl}{separator}", self.indent(2)));
+            }
+            self.lines.push(format!("{});", self.indent(1)));
+        }
+        self.lines.push("end component;".to_owned());
+    }
+
+    /// A generic or port map where each formal is associated with the same-named actual
+    fn map_aspect(&mut self, keyword: &str, formals: &[String]) {
+        let width = if self.options.align {
+            formals.iter().map(|formal| formal.len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+        self.lines
+            .push(format!("{}{keyword} map (", self.indent(1)));
+        for (idx, formal) in formals.iter().enumerate() {
+            let separator = if idx + 1 < formals.len() { "," } else { "" };
+            self.lines.push(format!(
+                "{}{formal:width$} => {}{formal}{}{separator}",
+                self.indent(2),
+                self.options.actual_prefix,
+                self.options.actual_suffix,
+            ));
+        }
+        self.lines.push(format!("{})", self.indent(1)));
+    }
+
+    fn instantiation(&mut self, library_name: &Symbol, entity: &EntityDeclaration) {
+        let name = entity.ident.tree.item.name_utf8();
+        let instantiated = match self.options.style {
+            InstantiationStyle::Entity => format!("entity {}.{name}", library_name.name_utf8()),
+            InstantiationStyle::Component => format!("component {name}"),
+        };
+        self.lines.push(format!("{name}_inst : {instantiated}"));
+
+        let generics: Vec<String> = entity
+            .generic_clause
+            .iter()
+            .flatten()
+            .map(formal_of)
+            .filter(|(_, has_default)| self.options.generics_with_default || !has_default)
+            .map(|(formal, _)| formal)
+            .collect();
+        let ports: Vec<String> = entity
+            .port_clause
+            .iter()
+            .flatten()
+            .map(|decl| formal_of(decl).0)
+            .collect();
+
+        if !generics.is_empty() {
+            self.map_aspect("generic", &generics);
+        }
+        if !ports.is_empty() {
+            self.map_aspect("port", &ports);
+        }
+        self.lines.last_mut().unwrap().push(';');
+    }
+}
+
+/// Finds the declaration of an entity by name
+struct EntitySearcher<'o> {
+    library_name: &'o Symbol,
+    entity_name: &'o Symbol,
+    template: Template<'o>,
+}
+
+impl<'o> Searcher for EntitySearcher<'o> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if let FoundDeclaration::Entity(entity) = decl {
+            if &entity.ident.tree.item == self.entity_name {
+                if self.template.options.style == InstantiationStyle::Component {
+                    self.template.component_declaration(entity);
+                    self.template.lines.push(String::new());
+                }
+                self.template.instantiation(self.library_name, entity);
+                return Finished(Found);
+            }
+        }
+        NotFinished
+    }
+}
+
+impl DesignRoot {
+    /// A template for instantiating the entity `entity_name` of a library where all
+    /// generics and ports are associated with actuals of the same name.
+    /// The generics and ports of a component declaration are those of the entity as written
+    /// in the source.
+    pub fn instantiation_template(
+        &self,
+        library_name: &Symbol,
+        entity_name: &Symbol,
+        options: &InstantiationOptions,
+    ) -> Option<String> {
+        let mut searcher = EntitySearcher {
+            library_name,
+            entity_name,
+            template: Template {
+                options,
+                lines: Vec::new(),
+            },
+        };
+        let _ = self.search_library(library_name, &mut searcher);
+        if searcher.template.lines.is_empty() {
+            None
+        } else {
+            Some(searcher.template.lines.join("\n"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{check_no_diagnostics, LibraryBuilder};
+    use pretty_assertions::assert_eq;
+
+    fn template(code: &str, options: &InstantiationOptions) -> Option<String> {
+        let mut builder = LibraryBuilder::new();
+        builder.code("libname", code);
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        root.instantiation_template(
+            &root.symbol_utf8("libname"),
+            &root.symbol_utf8("ent"),
+            options,
+        )
+    }
+
+    #[test]
+    fn entity_instantiation_with_generics() {
+        let code = "
+entity ent is
+  generic (
+    width : natural := 8;
+    g : boolean);
+  port (
+    clk : in bit;
+    data_out : out bit_vector(width - 1 downto 0));
+end entity;
+";
+        assert_eq!(
+            template(code, &InstantiationOptions::default()).unwrap(),
+            "\
+ent_inst : entity libname.ent
+  generic map (
+    width => width,
+    g     => g
+  )
+  port map (
+    clk      => clk,
+    data_out => data_out
+  );"
+        );
+    }
+
+    #[test]
+    fn omits_generics_with_default() {
+        let code = "
+entity ent is
+  generic (
+    width : natural := 8;
+    g : boolean);
+  port (
+    clk : in bit;
+    data_out : out bit_vector(width - 1 downto 0));
+end entity;
+";
+        let options = InstantiationOptions {
+            generics_with_default: false,
+            align: false,
+            actual_prefix: "s_".to_owned(),
+            actual_suffix: "_i".to_owned(),
+            ..InstantiationOptions::default()
+        };
+        assert_eq!(
+            template(code, &options).unwrap(),
+            "\
+ent_inst : entity libname.ent
+  generic map (
+    g => s_g_i
+  )
+  port map (
+    clk => s_clk_i,
+    data_out => s_data_out_i
+  );"
+        );
+    }
+
+    #[test]
+    fn component_instantiation_without_generics() {
+        let code = "
+entity ent is
+  port (
+    a : in bit_vector;
+    b : inout bit);
+end entity;
+";
+        let options = InstantiationOptions {
+            style: InstantiationStyle::Component,
+            ..InstantiationOptions::default()
+        };
+        assert_eq!(
+            template(code, &options).unwrap(),
+            "\
+component ent is
+  port (
+    a : in bit_vector;
+    b : inout bit
+  );
+end component;
+
+ent_inst : component ent
+  port map (
+    a => a,
+    b => b
+  );"
+        );
+    }
+
+    #[test]
+    fn component_declaration_with_generics() {
+        let code = "
+entity ent is
+  generic (
+    width : natural := 8;
+    g : boolean);
+  port (
+    clk : in bit;
+    data_out : out bit_vector(width - 1 downto 0));
+end entity;
+";
+        let options = InstantiationOptions {
+            style: InstantiationStyle::Component,
+            generics_with_default: false,
+            ..InstantiationOptions::default()
+        };
+        let template = template(code, &options).unwrap();
+        assert!(template.starts_with(
+            "\
+component ent is
+  generic (
+    width : natural := 8;
+    g : boolean
+  );
+  port (
+    clk : in bit;
+    data_out : out bit_vector(width - 1 downto 0)
+  );
+end component;"
+        ));
+    }
+
+    #[test]
+    fn entity_without_interface() {
+        let code = "
+entity ent is
+end entity;
+";
+        assert_eq!(
+            template(code, &InstantiationOptions::default()).unwrap(),
+            "ent_inst : entity libname.ent;"
+        );
+    }
+
+    #[test]
+    fn unknown_entity() {
+        let code = "
+entity other is
+end entity;
+";
+        assert_eq!(template(code, &InstantiationOptions::default()), None);
+    }
+}
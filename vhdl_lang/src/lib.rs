@@ -18,25 +18,58 @@ mod data;
 mod lint;
 mod named_entity;
 mod project;
+mod standard;
 mod syntax;
 
+mod call_hierarchy;
+#[cfg(feature = "capi")]
+pub mod capi;
 mod completion;
+mod ctags;
+#[cfg(feature = "json")]
+mod diagnostic_format;
+mod entity_interface;
+mod extract;
+mod formatting;
+mod instantiation;
+mod semantic_tokens;
+#[cfg(feature = "serialize")]
+mod serialize;
+mod signature_help;
+mod use_clause_fix;
 
 pub use crate::config::Config;
 pub use crate::data::{
-    Diagnostic, Latin1String, Message, MessageHandler, MessagePrinter, MessageType,
-    NullDiagnostics, NullMessages, Position, Range, Severity, Source, SrcPos,
+    DeduplicatingDiagnosticHandler, Diagnostic, DiagnosticHandler, DiagnosticReport, Encoding,
+    ErrorCode, Latin1String, Message, MessageHandler, MessagePrinter, MessageType, NullDiagnostics,
+    NullMessages, Position, Range, Severity, SeverityOverridingDiagnosticHandler, Source, SrcPos,
+    Symbol,
 };
 
-pub use crate::analysis::EntHierarchy;
+pub use crate::analysis::{
+    AnalysisPhase, AnalysisProgress, CycleError, DependencyGraph, DependencyNode, EntHierarchy,
+    FileStatus, HoverInfo, SecondaryUnitDescription, TypeDescription, UnitDescription,
+};
+pub use crate::ast::search::ReferenceKind;
 pub use crate::named_entity::{
     AnyEnt, AnyEntKind, Concurrent, Design, EntRef, EntityId, HasEntityId, InterfaceEnt, Object,
-    Overloaded, Reference, Related, Sequential, Type,
+    Overloaded, Reference, Related, Sequential, StaticRange, Type, Value,
 };
 
 pub use crate::project::{Project, SourceFile};
+pub use crate::standard::VHDLStandard;
 pub use crate::syntax::{
-    kind_str, HasTokenSpan, ParserResult, Token, TokenAccess, TokenId, TokenSpan, VHDLParser,
+    kind_str, tokenize, Comment, HasTokenSpan, Kind, ParserResult, Token, TokenAccess,
+    TokenComments, TokenId, TokenSpan, ToolIdentifiers, VHDLParser, Value as TokenValue,
 };
 
 pub use completion::{list_completion_options, CompletionItem};
+pub use entity_interface::{EntityInterface, InterfaceElement, InterfaceElementKind};
+pub use extract::ExtractKind;
+pub use formatting::{format_design_file, FormatOptions, KeywordCase};
+pub use instantiation::{InstantiationOptions, InstantiationStyle};
+pub use semantic_tokens::{SemanticToken, SemanticTokenModifier, SemanticTokenType};
+#[cfg(feature = "serialize")]
+pub use serialize::parse_to_json;
+pub use signature_help::{SignatureHelp, SignatureInformation};
+pub use use_clause_fix::UseClauseFix;
@@ -12,6 +12,7 @@ use crate::ast::search::SearchState;
 use crate::ast::search::Searcher;
 use crate::ast::UnitId;
 use crate::data::DiagnosticHandler;
+use crate::data::ErrorCode;
 use crate::data::Symbol;
 use crate::named_entity::{HasEntityId, Reference, Related};
 use crate::syntax::TokenAccess;
@@ -64,6 +65,15 @@ impl<'a> Searcher for DeadCodeSearcher<'a> {
         _ctx: &dyn TokenAccess,
         decl: crate::ast::search::FoundDeclaration,
     ) -> SearchState {
+        // Generate parameters are constants but are commonly unused like loop parameters,
+        // the return identifier of a subprogram declaration is only used within the body
+        if matches!(
+            decl,
+            crate::ast::search::FoundDeclaration::ForGenerateIndex(..)
+                | crate::ast::search::FoundDeclaration::ReturnIdentifier(..)
+        ) {
+            return SearchState::NotFinished;
+        }
         if let Some(id) = decl.ent_id() {
             self.declarations.insert(self.root.get_ent(id));
         }
@@ -140,6 +150,11 @@ fn can_be_locally_unused(ent: EntRef) -> bool {
             return false;
         }
 
+        // Entity ports and generics are part of the public interface
+        if matches!(parent.kind(), AnyEntKind::Design(Design::Entity(..))) && is_interface(ent) {
+            return false;
+        }
+
         // Everything in protected types inside of package header
         if matches!(parent.kind(), AnyEntKind::Type(crate::Type::Protected(..))) {
             if let Some(grand_parent) = parent.parent {
@@ -233,10 +248,13 @@ impl UnusedDeclarationsLinter {
                     find_unused_declarations(root, library, unit.primary_name())
                         .into_iter()
                         .filter_map(|ent| {
-                            Some(Diagnostic::warning(
-                                ent.decl_pos()?,
-                                format!("Unused declaration of {}", ent.describe()),
-                            ))
+                            Some(
+                                Diagnostic::warning(
+                                    ent.decl_pos()?,
+                                    format!("Unused declaration of {}", ent.describe()),
+                                )
+                                .with_code(ErrorCode::UnusedDeclaration),
+                            )
                         })
                         .collect_vec()
                 });
@@ -245,7 +263,7 @@ impl UnusedDeclarationsLinter {
 
         for ((library_name, _), unit_diagnostics) in self.diagnostics.iter() {
             if let Some(library_config) = config.get_library(&library_name.name_utf8()) {
-                if !library_config.is_third_party {
+                if !library_config.is_third_party && !library_config.ignore_unused {
                     diagnostics.append(unit_diagnostics.iter().cloned());
                 }
             }
@@ -257,6 +275,7 @@ impl UnusedDeclarationsLinter {
 mod tests {
     use super::*;
     use crate::analysis::tests::LibraryBuilder;
+    use crate::ast::HasUnitId;
     use crate::syntax::test::check_no_diagnostics;
     use crate::syntax::test::Code;
 
@@ -319,13 +338,16 @@ end architecture;",
     }
 
     #[test]
-    fn unused_ports() {
+    fn entity_ports_and_generics_are_public_and_will_never_be_unused() {
         let mut builder = LibraryBuilder::new();
 
-        let code = builder.code(
+        builder.code(
             "libname",
             "
 entity ent is
+  generic (
+    unused_generic : natural
+  );
   port (
     used : out boolean;
     unused : out boolean
@@ -342,14 +364,125 @@ end architecture;",
         check_no_diagnostics(&diagnostics);
 
         let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
-        let ent = get_ent(&root, code.s1("unused"));
 
         check_unused(
             find_unused_declarations(&root, lib, &root.symbol_utf8("ent")),
-            FnvHashSet::from_iter(vec![ent]),
+            FnvHashSet::default(),
+        )
+    }
+
+    #[test]
+    fn signal_used_in_generate_statement() {
+        let mut builder = LibraryBuilder::new();
+
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal used_in_gen : boolean;
+  signal unused : boolean;
+begin
+  gen: if true generate
+    signal unused_in_gen : boolean;
+  begin
+    used_in_gen <= true;
+  end generate;
+end architecture;",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
+
+        check_unused(
+            find_unused_declarations(&root, lib, &root.symbol_utf8("ent")),
+            FnvHashSet::from_iter(vec![
+                get_ent(&root, code.s1("unused")),
+                get_ent(&root, code.s1("unused_in_gen")),
+            ]),
+        )
+    }
+
+    /// An attribute specification is a use of the declaration
+    /// which can be used to silence the lint for a single declaration
+    #[test]
+    fn attribute_specification_is_use() {
+        let mut builder = LibraryBuilder::new();
+
+        builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  attribute keep : boolean;
+  signal generated_net : boolean;
+  attribute keep of generated_net : signal is true;
+begin
+end architecture;",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
+
+        check_unused(
+            find_unused_declarations(&root, lib, &root.symbol_utf8("ent")),
+            FnvHashSet::default(),
         )
     }
 
+    fn lint_with_config(config: &str) -> Vec<Diagnostic> {
+        let mut builder = LibraryBuilder::new();
+
+        builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal unused : boolean;
+begin
+end architecture;",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = Config::from_str(config, std::path::Path::new("")).unwrap();
+        let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
+        let units = lib.units().map(|unit| unit.unit_id().clone()).collect_vec();
+
+        let mut diagnostics = Vec::new();
+        UnusedDeclarationsLinter::default().lint(&root, &config, &units, &mut diagnostics);
+        diagnostics
+    }
+
+    #[test]
+    fn unused_declarations_are_warnings() {
+        let diagnostics = lint_with_config("[libraries]\nlibname.files = []");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, crate::Severity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unused declaration of signal 'unused'"
+        );
+    }
+
+    #[test]
+    fn unused_declarations_can_be_ignored_by_config() {
+        check_no_diagnostics(&lint_with_config(
+            "[libraries]\nlibname.files = []\nlibname.ignore_unused = true",
+        ));
+    }
+
     /// Since the focus of the unused declaration lint is local declarations
     /// we have to assume that a package header declaration could be used somewhere else.
     #[test]
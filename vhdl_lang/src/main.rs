@@ -4,10 +4,14 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use std::io::IsTerminal;
 use std::path::Path;
 use std::time::SystemTime;
-use vhdl_lang::{Config, Diagnostic, MessagePrinter, NullMessages, Project, Severity};
+use vhdl_lang::{
+    Config, DeduplicatingDiagnosticHandler, DiagnosticHandler, DiagnosticReport, Message,
+    MessageHandler, MessagePrinter, NullMessages, Project, Severity,
+};
 
 /// Run vhdl analysis
 #[derive(Parser, Debug)]
@@ -29,9 +33,23 @@ struct Args {
     #[arg(long, default_value_t = false)]
     no_hint: bool,
 
+    /// Use colors when printing diagnostics, by default colors are used when writing to a terminal
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// The format of the diagnostics, the machine readable formats write file paths
+    /// relative to the current directory and print other messages to stderr,
+    /// they are available with the json feature
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    format: OutputFormat,
+
     /// Config file in TOML format containing libraries and settings
-    #[arg(short, long)]
-    config: String,
+    #[cfg_attr(not(feature = "serialize"), arg(short, long, required = true))]
+    #[cfg_attr(
+        feature = "serialize",
+        arg(short, long, required_unless_present = "dump_ast")
+    )]
+    config: Option<String>,
 
     /// Dump items that are not resolved into an unique reference
     /// This is used for development to test where the language server is blind
@@ -42,22 +60,87 @@ struct Args {
     /// This is used for development to test where the language server is blind
     #[arg(long)]
     count_unresolved: bool,
+
+    /// Print the design units that are not used by a top-level unit given as library.unit,
+    /// can be given multiple times for several top-level units
+    #[arg(long, value_name = "LIBRARY.UNIT")]
+    top: Vec<String>,
+
+    /// Write the declared symbols as a tags file in the exuberant ctags format
+    #[arg(long, value_name = "FILE")]
+    ctags: Option<String>,
+
+    /// Parse a single file and print its AST as JSON instead of analyzing a project
+    #[cfg(feature = "serialize")]
+    #[arg(long, value_name = "FILE")]
+    dump_ast: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ColorChoice {
+    Always,
+    Never,
+    Auto,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Diagnostics with the source code context
+    Human,
+    /// A JSON document with the positions, severity, code and message of every diagnostic
+    #[cfg(feature = "json")]
+    Json,
+    /// A SARIF 2.1.0 log such as for GitHub code scanning
+    #[cfg(feature = "json")]
+    Sarif,
+}
+
+/// Prints messages to stderr to keep stdout machine readable
+struct StderrMessagePrinter;
+
+impl MessageHandler for StderrMessagePrinter {
+    fn push(&mut self, message: Message) {
+        eprintln!("{message}");
+    }
+}
+
+impl ColorChoice {
+    fn use_colors(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => std::io::stdout().is_terminal(),
+        }
+    }
 }
 
 fn main() {
     let args = Args::parse();
+
+    #[cfg(feature = "serialize")]
+    if let Some(ref file_name) = args.dump_ast {
+        dump_ast(Path::new(file_name), args.color.use_colors());
+        return;
+    }
+
     rayon::ThreadPoolBuilder::new()
         .num_threads(args.num_threads.unwrap_or(0))
         .build_global()
         .unwrap();
 
     let mut config = Config::default();
-    let mut msg_printer = MessagePrinter::default();
-    config.load_external_config(&mut msg_printer);
-    config.append(
-        &Config::read_file_path(Path::new(&args.config)).expect("Failed to read config file"),
-        &mut msg_printer,
-    );
+    let mut msg_printer: Box<dyn MessageHandler> = if args.format == OutputFormat::Human {
+        Box::new(MessagePrinter::default())
+    } else {
+        Box::new(StderrMessagePrinter)
+    };
+    config.load_external_config(msg_printer.as_mut());
+    let project_config = Config::read_file_path_with_messages(
+        Path::new(args.config.as_deref().unwrap()),
+        msg_printer.as_mut(),
+    )
+    .expect("Failed to read config file");
+    config.append(&project_config, msg_printer.as_mut());
 
     let start = SystemTime::now();
 
@@ -73,15 +156,40 @@ fn main() {
         1
     };
 
-    let mut project = Project::from_config(config, &mut msg_printer);
-    let mut diagnostics = project.analyse();
+    let mut project = Project::from_config(config, msg_printer.as_mut());
+    let diagnostics = project.analyse();
     let duration = start.elapsed().unwrap() / iterations;
 
+    // The same diagnostic can be reported for each library a file is mapped to
+    let mut diagnostics = {
+        let mut deduplicated = Vec::with_capacity(diagnostics.len());
+        let mut handler = DeduplicatingDiagnosticHandler::new(&mut deduplicated);
+        for diagnostic in diagnostics {
+            handler.push(diagnostic);
+        }
+        handler.finish();
+        deduplicated
+    };
+
     if args.no_hint {
         diagnostics.retain(|diag| diag.severity != Severity::Hint);
     }
 
-    show_diagnostics(&diagnostics);
+    let report = DiagnosticReport::new(diagnostics).sorted();
+    match args.format {
+        OutputFormat::Human => show_report(&report, args.color.use_colors()),
+        #[cfg(feature = "json")]
+        OutputFormat::Json | OutputFormat::Sarif => {
+            let base_dir = std::env::current_dir().expect("Failed to get current directory");
+            let base_dir = dunce::canonicalize(&base_dir).unwrap_or(base_dir);
+            let json = if args.format == OutputFormat::Json {
+                report.to_json(&base_dir)
+            } else {
+                report.to_sarif(&base_dir)
+            };
+            println!("{json:#}");
+        }
+    }
 
     if args.perf || args.bench {
         let mut num_files = 0;
@@ -114,16 +222,90 @@ fn main() {
         }
     }
 
+    if !args.top.is_empty() {
+        show_unused_units(&project, &args.top);
+    }
+
+    if let Some(ref file_name) = args.ctags {
+        let base_dir = Path::new(file_name)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory"));
+        let base_dir = dunce::canonicalize(&base_dir).unwrap_or(base_dir);
+        if let Err(err) = std::fs::write(file_name, project.to_ctags(&base_dir)) {
+            eprintln!("Failed to write {file_name}: {err}");
+            std::process::exit(1);
+        }
+    }
+
     // Exit without running Drop on entire allocated AST
-    std::process::exit(0);
+    std::process::exit(report.exit_code());
 }
 
-fn show_diagnostics(diagnostics: &[Diagnostic]) {
-    for diagnostic in diagnostics {
-        println!("{}", diagnostic.show());
+#[cfg(feature = "serialize")]
+fn dump_ast(file_name: &Path, colors: bool) {
+    let source = match vhdl_lang::Source::from_file(file_name) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("Failed to read {}: {err}", file_name.to_string_lossy());
+            std::process::exit(1);
+        }
+    };
+
+    match vhdl_lang::parse_to_json(&source) {
+        Ok(json) => println!("{json}"),
+        Err(diagnostic) => {
+            show_report(&DiagnosticReport::new([diagnostic]), colors);
+            std::process::exit(1);
+        }
     }
+}
 
-    if !diagnostics.is_empty() {
-        println!("Found {} diagnostics", diagnostics.len());
+fn show_unused_units(project: &Project, tops: &[String]) {
+    let mut roots = Vec::with_capacity(tops.len());
+    for top in tops {
+        let Some((library_name, name)) = top.split_once('.') else {
+            eprintln!("Invalid top-level unit '{top}', expected library.unit");
+            std::process::exit(1);
+        };
+        let root = (project.symbol_utf8(library_name), project.symbol_utf8(name));
+        if project.find_unit(&root.0, &root.1).is_none() {
+            eprintln!("Top-level unit '{top}' not found");
+            std::process::exit(1);
+        }
+        roots.push(root);
     }
+
+    let unused = project.unused_units(&roots);
+    println!("{} unused design unit(s)", unused.len());
+    for unit in unused {
+        println!(
+            "{}.{} ({}) in {}:{}",
+            unit.library_name,
+            unit.name,
+            unit.kind.describe(),
+            unit.source.file_name().to_string_lossy(),
+            unit.pos.range().start.line + 1
+        );
+    }
+}
+
+fn show_report(report: &DiagnosticReport, use_colors: bool) {
+    for (source, diagnostics) in report.by_file() {
+        for diagnostic in diagnostics {
+            if use_colors {
+                println!("{}", diagnostic.show_colored());
+            } else {
+                println!("{}", diagnostic.show());
+            }
+        }
+        println!(
+            "{}: {}",
+            source.file_name().to_string_lossy(),
+            DiagnosticReport::file_summary(diagnostics)
+        );
+    }
+
+    println!("{}", report.summary());
 }
@@ -8,8 +8,8 @@ use crate::ast::{
     AliasDeclaration, AnyDesignUnit, AnyPrimaryUnit, AnySecondaryUnit, Attribute,
     AttributeDeclaration, AttributeSpecification, ComponentDeclaration, Declaration, Designator,
     FileDeclaration, HasIdent, Ident, InterfaceFileDeclaration, InterfacePackageDeclaration,
-    ObjectClass, ObjectDeclaration, PackageInstantiation, SubprogramBody, SubprogramInstantiation,
-    SubprogramSpecification, TypeDeclaration, WithDecl,
+    ObjectClass, ObjectDeclaration, PackageInstantiation, PslDeclaration, SubprogramBody,
+    SubprogramInstantiation, SubprogramSpecification, TypeDeclaration, WithDecl,
 };
 use crate::ast::{ExternalObjectClass, InterfaceDeclaration, InterfaceObjectDeclaration};
 use crate::data::*;
@@ -24,6 +24,9 @@ pub use overloaded::{Overloaded, OverloadedEnt, Signature, SignatureKey, Subprog
 mod object;
 pub use object::{Object, ObjectEnt, ObjectInterface};
 
+mod value;
+pub use value::{StaticRange, Value};
+
 mod design;
 pub use design::{Design, DesignEnt};
 
@@ -38,7 +41,7 @@ pub use visibility::{Visibility, Visible};
 
 mod region;
 pub(crate) use region::RegionKind;
-pub use region::{AsUnique, NamedEntities, OverloadedName, Region, SetReference};
+pub use region::{AsUnique, NamedEntities, OverloadedName, Region, RegionData, SetReference};
 
 mod formal_region;
 pub use formal_region::{
@@ -54,6 +57,8 @@ pub enum AnyEntKind<'a> {
     ObjectAlias {
         base_object: ObjectEnt<'a>,
         type_mark: TypeEnt<'a>,
+        /// True if the alias denotes the whole base object rather than an element or slice of it
+        whole_object: bool,
     },
     File(Subtype<'a>),
     InterfaceFile(TypeEnt<'a>),
@@ -393,7 +398,8 @@ impl<'a> AnyEnt<'a> {
 
     pub fn error(&self, diagnostics: &mut dyn DiagnosticHandler, message: impl Into<String>) {
         if let Some(ref pos) = self.decl_pos {
-            diagnostics.push(Diagnostic::error(pos, message));
+            diagnostics
+                .push(Diagnostic::error(pos, message).with_code(ErrorCode::IllegalDeclaration));
         }
     }
 
@@ -417,6 +423,16 @@ impl<'a> AnyEnt<'a> {
         }
     }
 
+    /// The entity that is directly denoted by an alias declaration
+    pub fn aliased(&self) -> Option<EntRef<'a>> {
+        match self.kind {
+            AnyEntKind::Overloaded(Overloaded::Alias(ref ent)) => Some((*ent).into()),
+            AnyEntKind::Type(Type::Alias(ref ent)) => Some((*ent).into()),
+            AnyEntKind::ObjectAlias { base_object, .. } => Some(base_object.ent),
+            _ => None,
+        }
+    }
+
     pub(crate) fn add_implicit(&mut self, ent: EntRef<'a>) {
         self.implicits.push(ent);
     }
@@ -438,6 +454,7 @@ impl<'a> AnyEnt<'a> {
                         self.describe()
                     ),
                 )
+                .with_code(ErrorCode::DuplicateDecl)
                 .related(last_pos, "Previously specified here"))
             }
             Entry::Vacant(entry) => {
@@ -476,6 +493,13 @@ impl<'a> AnyEnt<'a> {
             AnyEntKind::Overloaded(_) => OverloadedEnt::from_any(self).unwrap().describe(),
 
             AnyEntKind::Type(_) => TypeEnt::from_any(self).unwrap().describe(),
+            _ if self.is_anonymous() => {
+                if let Some(pos) = self.decl_pos() {
+                    format!("{} (line {})", self.kind.describe(), pos.start().line + 1)
+                } else {
+                    self.kind.describe().to_owned()
+                }
+            }
             _ => format!("{} '{}'", self.kind.describe(), self.designator),
         }
     }
@@ -610,6 +634,19 @@ impl HasEntityId for Declaration {
             Declaration::Package(pkg) => pkg.ent_id(),
             Declaration::Use(_) => None,
             Declaration::Configuration(_) => None,
+            Declaration::Disconnection(_) => None,
+            Declaration::Psl(decl) => decl.ent_id(),
+            Declaration::Error(_) => None,
+        }
+    }
+}
+
+impl HasEntityId for PslDeclaration {
+    fn ent_id(&self) -> Option<EntityId> {
+        match self {
+            PslDeclaration::DefaultClock(_) => None,
+            PslDeclaration::Property(decl) => decl.ident.decl.get(),
+            PslDeclaration::Sequence(decl) => decl.ident.decl.get(),
         }
     }
 }
@@ -729,6 +766,10 @@ pub enum Concurrent {
     Process,
     Generate,
     Instance,
+    /// A named PSL property
+    Property,
+    /// A named PSL sequence
+    Sequence,
 }
 
 impl Concurrent {
@@ -738,6 +779,8 @@ impl Concurrent {
             Concurrent::Process => "process",
             Concurrent::Generate => "generate",
             Concurrent::Instance => "instance",
+            Concurrent::Property => "property",
+            Concurrent::Sequence => "sequence",
         }
     }
 }
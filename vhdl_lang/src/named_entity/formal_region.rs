@@ -76,6 +76,13 @@ impl<'a> InterfaceEnt<'a> {
         self.interface_class() == InterfaceClass::Signal
     }
 
+    pub fn mode(&self) -> Option<Mode> {
+        match self.ent.kind() {
+            AnyEntKind::Object(obj) => obj.mode(),
+            _ => None,
+        }
+    }
+
     pub fn is_out_or_inout_signal(&self) -> bool {
         match self.ent.kind() {
             AnyEntKind::Object(obj) => {
@@ -159,6 +166,8 @@ impl<'a> std::ops::Deref for GpkgInterfaceEnt<'a> {
 pub struct FormalRegion<'a> {
     pub(crate) typ: InterfaceType,
     pub(crate) entities: Vec<InterfaceEnt<'a>>,
+    /// The entity or component that the ports or generics belong to
+    pub(crate) owner: Option<EntRef<'a>>,
 }
 
 impl<'a> FormalRegion<'a> {
@@ -166,18 +175,50 @@ impl<'a> FormalRegion<'a> {
         Self {
             typ,
             entities: Default::default(),
+            owner: None,
         }
     }
 
     pub fn new_params() -> Self {
+        Self::new(InterfaceType::Parameter)
+    }
+
+    pub fn new_with(typ: InterfaceType, entities: Vec<InterfaceEnt<'a>>) -> Self {
         Self {
-            typ: InterfaceType::Parameter,
-            entities: Default::default(),
+            typ,
+            entities,
+            owner: None,
         }
     }
 
-    pub fn new_with(typ: InterfaceType, entities: Vec<InterfaceEnt<'a>>) -> Self {
-        Self { typ, entities }
+    pub fn with_owner(self, owner: EntRef<'a>) -> Self {
+        Self {
+            owner: Some(owner),
+            ..self
+        }
+    }
+
+    /// The kind of interface elements such as 'port'
+    pub fn describe_kind(&self) -> &'static str {
+        match self.typ {
+            InterfaceType::Port => "port",
+            InterfaceType::Generic => "generic",
+            InterfaceType::Parameter => "parameter",
+        }
+    }
+
+    /// Describe the entity or component that the interface elements belong to,
+    /// an entity is described with its library such as entity 'work.ent'
+    fn describe_owner(owner: EntRef<'a>) -> String {
+        match (owner.kind(), owner.library_name()) {
+            (AnyEntKind::Design(Design::Entity(..)), Some(library_name)) => format!(
+                "{} '{}.{}'",
+                owner.kind().describe(),
+                library_name,
+                owner.designator()
+            ),
+            _ => owner.describe(),
+        }
     }
     pub fn lookup(
         &self,
@@ -189,10 +230,38 @@ impl<'a> FormalRegion<'a> {
                 return Ok((idx, *ent));
             }
         }
-        Err(Diagnostic::error(
+
+        let Some(owner) = self.owner else {
+            return Err(
+                Diagnostic::error(pos, format!("No declaration of '{designator}'"))
+                    .with_code(ErrorCode::MissingDecl),
+            );
+        };
+
+        let owner_name = Self::describe_owner(owner);
+        let mut diagnostic = Diagnostic::error(
             pos,
-            format!("No declaration of '{designator}'"),
-        ))
+            format!("No {} '{designator}' on {owner_name}", self.describe_kind()),
+        )
+        .with_code(ErrorCode::MissingDecl);
+        if let Some(decl_pos) = self.entities.first().and_then(|ent| ent.decl_pos()) {
+            diagnostic.add_related(
+                decl_pos,
+                format!(
+                    "The {}s of {owner_name} are declared here",
+                    self.describe_kind()
+                ),
+            );
+        } else if let Some(decl_pos) = owner.decl_pos() {
+            diagnostic.add_related(
+                decl_pos,
+                format!(
+                    "Declaration of {owner_name} without any {}s",
+                    self.describe_kind()
+                ),
+            );
+        }
+        Err(diagnostic)
     }
 
     pub fn is_empty(&self) -> bool {
@@ -325,13 +394,17 @@ impl<'a> GpkgRegion<'a> {
                 return Ok((idx, *ent));
             }
         }
-        Err(Diagnostic::error(
-            pos,
-            format!("No declaration of '{designator}'"),
-        ))
+        Err(
+            Diagnostic::error(pos, format!("No declaration of '{designator}'"))
+                .with_code(ErrorCode::MissingDecl),
+        )
     }
 
     pub fn nth(&self, idx: usize) -> Option<GpkgInterfaceEnt<'a>> {
         self.entities.get(idx).cloned()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = GpkgInterfaceEnt<'a>> + '_ {
+        self.entities.iter().cloned()
+    }
 }
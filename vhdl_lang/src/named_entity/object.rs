@@ -7,9 +7,11 @@
 use std::ops::Deref;
 
 use super::*;
+use crate::ast::DiscreteRange;
 use crate::ast::InterfaceType;
 use crate::ast::Mode;
 use crate::ast::ObjectClass;
+use crate::ast::SignalKind;
 
 // A named entity that is known to be an object
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -119,6 +121,16 @@ pub struct Object<'a> {
     pub iface: Option<ObjectInterface>,
     pub subtype: Subtype<'a>,
     pub has_default: bool,
+    /// The value of a constant with a locally static initial value
+    pub static_value: Option<Value>,
+    /// The kind of a guarded signal
+    pub signal_kind: Option<SignalKind>,
+    /// The index constraint of a one-dimensional array port that depends on generics
+    /// such as `(width - 1 downto 0)`, its length is known for an instance with static generics
+    pub index_range: Option<Box<DiscreteRange>>,
+    /// The value of a generic with a locally static default such as `depth : natural := 4`,
+    /// it is used for the length of a port of an instance that leaves the generic unassociated
+    pub default_value: Option<Value>,
 }
 
 impl<'a> Object<'a> {
@@ -128,6 +140,10 @@ impl<'a> Object<'a> {
             iface: Some(ObjectInterface::Parameter(Mode::In)),
             subtype,
             has_default: false,
+            static_value: None,
+            signal_kind: None,
+            index_range: None,
+            default_value: None,
         }
     }
 
@@ -151,6 +167,10 @@ impl<'a> Object<'a> {
     pub fn mode(&self) -> Option<Mode> {
         self.iface.map(|i| i.mode())
     }
+
+    pub fn is_guarded(&self) -> bool {
+        self.signal_kind.is_some()
+    }
 }
 
 impl ObjectClass {
@@ -71,6 +71,8 @@ pub struct Signature<'a> {
     /// Vector of InterfaceObject or InterfaceFile
     pub(crate) formals: FormalRegion<'a>,
     pub(crate) return_type: Option<TypeEnt<'a>>,
+    /// False for functions declared impure
+    pub(crate) pure: bool,
 }
 
 impl<'a> Signature<'a> {
@@ -78,9 +80,19 @@ impl<'a> Signature<'a> {
         Signature {
             formals,
             return_type: return_type.as_ref().map(TypeEnt::to_owned),
+            pure: true,
         }
     }
 
+    pub(crate) fn with_pure(mut self, pure: bool) -> Signature<'a> {
+        self.pure = pure;
+        self
+    }
+
+    pub fn is_pure(&self) -> bool {
+        self.pure
+    }
+
     pub fn key(&self) -> SignatureKey<'a> {
         let formals = self.formals.iter().map(|formal| formal.base()).collect();
         let return_type = self.return_type.as_ref().map(|ent| ent.base());
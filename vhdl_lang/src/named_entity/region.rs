@@ -9,17 +9,26 @@ use crate::ast::*;
 use crate::named_entity::overloaded::SubprogramKey;
 use fnv::FnvHashMap;
 use std::collections::hash_map::Entry;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// A declarative region.
+///
+/// The data is shared between copies of the region, such as the region of an uninstantiated
+/// package used by its instances, and is only copied when a copy is modified.
+#[derive(Clone, Default)]
+pub struct Region<'a>(Arc<RegionData<'a>>);
 
 #[derive(Clone)]
-pub struct Region<'a> {
+pub struct RegionData<'a> {
     pub(crate) visibility: Visibility<'a>,
     pub(crate) entities: FnvHashMap<Designator, NamedEntities<'a>>,
     pub(crate) kind: RegionKind,
 }
 
-impl<'a> Default for Region<'a> {
-    fn default() -> Region<'a> {
-        Region {
+impl<'a> Default for RegionData<'a> {
+    fn default() -> RegionData<'a> {
+        RegionData {
             visibility: Visibility::default(),
             entities: FnvHashMap::default(),
             kind: RegionKind::Other,
@@ -27,12 +36,40 @@ impl<'a> Default for Region<'a> {
     }
 }
 
+impl<'a> Deref for Region<'a> {
+    type Target = RegionData<'a>;
+
+    fn deref(&self) -> &RegionData<'a> {
+        &self.0
+    }
+}
+
+impl<'a> DerefMut for Region<'a> {
+    fn deref_mut(&mut self) -> &mut RegionData<'a> {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
 impl<'a> Region<'a> {
-    pub(crate) fn with_visibility(visibility: Visibility<'a>) -> Self {
-        Self {
+    pub(crate) fn new(
+        visibility: Visibility<'a>,
+        entities: FnvHashMap<Designator, NamedEntities<'a>>,
+        kind: RegionKind,
+    ) -> Self {
+        Self(Arc::new(RegionData {
             visibility,
-            ..Default::default()
-        }
+            entities,
+            kind,
+        }))
+    }
+
+    pub(crate) fn with_visibility(visibility: Visibility<'a>) -> Self {
+        Self::new(visibility, FnvHashMap::default(), RegionKind::Other)
+    }
+
+    /// The visibility of the region, copied only if the region is shared
+    pub(crate) fn into_visibility(self) -> Visibility<'a> {
+        Arc::unwrap_or_clone(self.0).visibility
     }
 
     pub(crate) fn in_package_declaration(mut self) -> Region<'a> {
@@ -135,7 +172,8 @@ impl<'a> Region<'a> {
     }
 
     pub fn add(&mut self, ent: EntRef<'a>, diagnostics: &mut dyn DiagnosticHandler) {
-        if ent.kind().is_deferred_constant() && self.kind != RegionKind::PackageDeclaration {
+        let kind = self.kind;
+        if ent.kind().is_deferred_constant() && kind != RegionKind::PackageDeclaration {
             ent.error(
                 diagnostics,
                 "Deferred constants are only allowed in package declarations (not body)",
@@ -153,7 +191,7 @@ impl<'a> Region<'a> {
                             // Updated definition of previous entity
                             *prev_ent = ent;
                         } else if ent.is_declared_by(prev_ent) {
-                            if self.kind != RegionKind::PackageBody
+                            if kind != RegionKind::PackageBody
                                 && ent.kind().is_non_deferred_constant()
                             {
                                 ent.error(
@@ -220,22 +258,36 @@ impl<'a> Region<'a> {
 /// A non-empty collection of overloaded entities
 pub struct OverloadedName<'a> {
     entities: FnvHashMap<SubprogramKey<'a>, OverloadedEnt<'a>>,
+    /// Explicit declarations that were made visible with the same signature as
+    /// one of the entities, such as by use clauses of two different packages
+    homographs: Vec<OverloadedEnt<'a>>,
 }
 
 impl<'a> OverloadedName<'a> {
     pub fn new(entities: Vec<OverloadedEnt>) -> OverloadedName {
         debug_assert!(!entities.is_empty());
         let mut map = FnvHashMap::default();
+        let mut homographs = Vec::new();
         for ent in entities.into_iter() {
-            map.insert(ent.subprogram_key(), ent);
+            if let Some(old_ent) = map.insert(ent.subprogram_key(), ent) {
+                if old_ent.is_explicit() && ent.is_explicit() {
+                    homographs.push(old_ent);
+                }
+            }
+        }
+        OverloadedName {
+            entities: map,
+            homographs,
         }
-        OverloadedName { entities: map }
     }
 
     pub fn single(ent: OverloadedEnt) -> OverloadedName {
         let mut map = FnvHashMap::default();
         map.insert(ent.subprogram_key(), ent);
-        OverloadedName { entities: map }
+        OverloadedName {
+            entities: map,
+            homographs: Vec::new(),
+        }
     }
 
     pub fn first(&self) -> OverloadedEnt<'a> {
@@ -269,6 +321,22 @@ impl<'a> OverloadedName<'a> {
         self.entities.get(key).cloned()
     }
 
+    /// All visible explicit declarations with the signature of the key,
+    /// there is more than one when the declarations are homographs of each other
+    pub fn get_homographs(&self, key: &SubprogramKey) -> Vec<OverloadedEnt<'a>> {
+        let Some(ent) = self.get(key) else {
+            return Vec::new();
+        };
+        std::iter::once(ent)
+            .chain(
+                self.homographs
+                    .iter()
+                    .filter(|other| &other.subprogram_key() == key)
+                    .cloned(),
+            )
+            .collect()
+    }
+
     #[allow(clippy::if_same_then_else)]
     fn insert(&mut self, ent: OverloadedEnt<'a>) -> Result<(), Diagnostic> {
         match self.entities.entry(ent.subprogram_key()) {
@@ -297,7 +365,8 @@ impl<'a> OverloadedName<'a> {
                         ent.designator(),
                         ent.signature().describe()
                     ),
-                );
+                )
+                .with_code(ErrorCode::DuplicateDecl);
                 if let Some(old_pos) = old_ent.decl_pos() {
                     diagnostic.add_related(old_pos, "Previously defined here");
                 }
@@ -314,12 +383,24 @@ impl<'a> OverloadedName<'a> {
     // Merge overloaded names where self is overloaded names from an
     // immediate/enclosing region and visible are overloaded names that have been made visible
     pub(crate) fn with_visible(mut self, visible: Self) -> Self {
+        let mut hidden = Vec::new();
         for (signature, visible_entity) in visible.entities.into_iter() {
             // Ignore visible entites that conflict with those in the enclosing region
-            if let Entry::Vacant(entry) = self.entities.entry(signature) {
-                entry.insert(visible_entity);
+            match self.entities.entry(signature) {
+                Entry::Vacant(entry) => {
+                    entry.insert(visible_entity);
+                }
+                Entry::Occupied(entry) => {
+                    hidden.push(entry.key().clone());
+                }
             }
         }
+        self.homographs.extend(
+            visible
+                .homographs
+                .into_iter()
+                .filter(|homograph| !hidden.contains(&homograph.subprogram_key())),
+        );
         self
     }
 }
@@ -358,7 +439,7 @@ impl<'a> NamedEntities<'a> {
         match self {
             Self::Single(ent) => Ok(ent),
             Self::Overloaded(overloaded) => {
-                let mut error = Diagnostic::error(pos, message());
+                let mut error = Diagnostic::error(pos, message()).with_code(ErrorCode::Ambiguous);
                 for ent in overloaded.entities() {
                     if let Some(decl_pos) = ent.decl_pos() {
                         error.add_related(decl_pos, "Defined here");
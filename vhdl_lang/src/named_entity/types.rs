@@ -160,6 +160,24 @@ impl<'a> TypeEnt<'a> {
         BaseType::from(*self)
     }
 
+    /// Whether the type has a resolution function or is a composite type
+    /// where all subelements have a resolution function
+    pub fn is_resolved(&self) -> bool {
+        match self.kind() {
+            Type::Subtype(subtype) => subtype.is_resolved(),
+            Type::Alias(alias) => alias.is_resolved(),
+            Type::Array { elem_type, .. } => elem_type.is_resolved(),
+            Type::Record(region) => {
+                !region.is_empty()
+                    && region.iter().all(|elem| match elem.kind() {
+                        AnyEntKind::ElementDeclaration(subtype) => subtype.is_resolved(),
+                        _ => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+
     pub fn accessed_type(&self) -> Option<TypeEnt<'a>> {
         self.base().accessed_type()
     }
@@ -204,7 +222,8 @@ impl<'a> TypeEnt<'a> {
                                 "Protected type selection must be a method, got {}",
                                 ent.describe()
                             ),
-                        )),
+                        )
+                        .with_code(ErrorCode::MismatchedKinds)),
                         NamedEntities::Overloaded(overloaded) => {
                             Ok(TypedSelection::ProtectedMethod(overloaded.clone()))
                         }
@@ -220,7 +239,8 @@ impl<'a> TypeEnt<'a> {
             Type::Incomplete => Err(Diagnostic::error(
                 prefix_pos,
                 "Cannot select incomplete type before full type definition",
-            )),
+            )
+            .with_code(ErrorCode::MismatchedKinds)),
             Type::Subtype(subtype) => subtype.type_mark().selected(prefix_pos, suffix),
             Type::Access(subtype, ..) => subtype.type_mark().selected(prefix_pos, suffix),
             Type::Alias(alias) => alias.selected(prefix_pos, suffix),
@@ -440,11 +460,58 @@ impl<'a> Deref for BaseType<'a> {
 #[derive(Clone, Copy)]
 pub struct Subtype<'a> {
     pub(crate) type_mark: TypeEnt<'a>,
+    /// The subtype indication has a resolution function
+    pub(crate) resolved: bool,
+    /// The range of a scalar subtype or the index range of a one-dimensional array subtype
+    /// when it has locally static bounds
+    pub(crate) static_range: Option<StaticRange>,
 }
 
 impl<'a> Subtype<'a> {
     pub fn new(type_mark: TypeEnt<'a>) -> Subtype<'a> {
-        Subtype { type_mark }
+        Subtype {
+            type_mark,
+            resolved: false,
+            static_range: None,
+        }
+    }
+
+    pub fn new_resolved(type_mark: TypeEnt<'a>) -> Subtype<'a> {
+        Subtype {
+            type_mark,
+            resolved: true,
+            static_range: None,
+        }
+    }
+
+    pub fn with_static_range(self, static_range: Option<StaticRange>) -> Subtype<'a> {
+        Subtype {
+            static_range,
+            ..self
+        }
+    }
+
+    /// The range of the subtype if it is known during analysis,
+    /// a subtype without a constraint has the range of its type mark
+    pub fn static_range(&self) -> Option<StaticRange> {
+        self.static_range.or_else(|| match self.type_mark.kind() {
+            Type::Subtype(subtype) => subtype.static_range(),
+            _ => None,
+        })
+    }
+
+    /// The length of a one-dimensional array subtype if it is known during analysis
+    pub fn static_length(&self) -> Option<u64> {
+        if matches!(self.base_type().kind(), Type::Array { .. }) {
+            self.static_range().map(|range| range.length())
+        } else {
+            None
+        }
+    }
+
+    /// Signals of a resolved subtype may have multiple drivers
+    pub fn is_resolved(&self) -> bool {
+        self.resolved || self.type_mark.is_resolved()
     }
 
     pub fn type_mark(&self) -> TypeEnt<'a> {
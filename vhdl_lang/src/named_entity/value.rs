@@ -0,0 +1,77 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::ast::{AbstractLiteral, Designator, Direction};
+use crate::data::{Latin1String, Symbol};
+
+/// The value of a locally static expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Real(f64),
+    /// A physical value given as a multiple of a unit such as `10 ns`
+    Physical(AbstractLiteral, Symbol),
+    /// An enumeration literal such as `true` or `'1'`
+    Enumeration(Designator),
+    String(Latin1String),
+}
+
+impl Value {
+    pub fn as_integer(&self) -> Option<i64> {
+        if let Value::Integer(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+}
+
+/// A range with locally static bounds such as `7 downto 0`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticRange {
+    pub left: i64,
+    pub direction: Direction,
+    pub right: i64,
+}
+
+impl StaticRange {
+    pub fn new(left: i64, direction: Direction, right: i64) -> StaticRange {
+        StaticRange {
+            left,
+            direction,
+            right,
+        }
+    }
+
+    pub fn low(&self) -> i64 {
+        self.left.min(self.right)
+    }
+
+    pub fn high(&self) -> i64 {
+        self.left.max(self.right)
+    }
+
+    pub fn reversed(&self) -> StaticRange {
+        let direction = match self.direction {
+            Direction::Ascending => Direction::Descending,
+            Direction::Descending => Direction::Ascending,
+        };
+        StaticRange::new(self.right, direction, self.left)
+    }
+
+    /// The number of values within the range, a null range has length zero
+    pub fn length(&self) -> u64 {
+        let (low, high) = match self.direction {
+            Direction::Ascending => (self.left, self.right),
+            Direction::Descending => (self.right, self.left),
+        };
+        if low > high {
+            0
+        } else {
+            (high as i128 - low as i128 + 1) as u64
+        }
+    }
+}
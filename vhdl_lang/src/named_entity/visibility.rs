@@ -17,6 +17,14 @@ struct VisibleEntity<'a> {
 }
 
 impl<'a> VisibleEntity<'a> {
+    /// The position of the use clause when made visible directly by a use clause of the region
+    fn use_clause_pos(&self) -> Option<&SrcPos> {
+        match self.visible_pos.as_slice() {
+            [Some(pos)] => Some(pos),
+            _ => None,
+        }
+    }
+
     fn clone_with_more_visiblity(&self, visible_pos: Option<&SrcPos>) -> VisibleEntity<'a> {
         let mut more = self.clone();
         more.visible_pos.push(visible_pos.cloned());
@@ -32,6 +40,13 @@ struct VisibleRegion<'a> {
 }
 
 impl<'a> VisibleRegion<'a> {
+    fn use_clause_pos(&self) -> Option<&SrcPos> {
+        match self.visible_pos.as_slice() {
+            [Some(pos)] => Some(pos),
+            _ => None,
+        }
+    }
+
     /// Add more visiblity so that when using a context we can follow the visibility chain
     fn clone_with_more_visiblity(&self, visible_pos: Option<&SrcPos>) -> VisibleRegion<'a> {
         let mut more = self.clone();
@@ -59,6 +74,48 @@ impl<'a> Visibility<'a> {
         });
     }
 
+    /// The visibility as inherited by an extending region such as a secondary unit.
+    /// The visibility chain is extended by an unknown position so that the use clauses
+    /// of the extended region are not considered to be in the same region.
+    pub fn extended(&self) -> Visibility<'a> {
+        Visibility {
+            all_in_regions: self
+                .all_in_regions
+                .iter()
+                .map(|visible_region| visible_region.clone_with_more_visiblity(None))
+                .collect(),
+            visible: self
+                .visible
+                .iter()
+                .map(|(designator, visible)| {
+                    let visible = visible
+                        .iter()
+                        .map(|(id, ent)| (*id, ent.clone_with_more_visiblity(None)))
+                        .collect();
+                    (designator.clone(), visible)
+                })
+                .collect(),
+        }
+    }
+
+    /// The position of an earlier use clause of this region that made all names
+    /// within the region potentially visible
+    pub fn all_made_visible_by(&self, region: &'a Region<'a>) -> Option<&SrcPos> {
+        self.all_in_regions
+            .iter()
+            .filter(|visible_region| std::ptr::eq(visible_region.region, region))
+            .find_map(|visible_region| visible_region.use_clause_pos())
+    }
+
+    /// The position of an earlier use clause of this region that made the entity
+    /// potentially visible
+    pub fn made_visible_by(&self, ent: EntRef<'a>) -> Option<&SrcPos> {
+        self.visible
+            .get(ent.designator())?
+            .get(&ent.id())?
+            .use_clause_pos()
+    }
+
     pub fn visible(&self) -> impl Iterator<Item = EntRef<'a>> + '_ {
         self.visible.values().flatten().map(|entry| entry.1.entity)
     }
@@ -201,8 +258,11 @@ impl<'a> Visible<'a> {
             // Duplicate visible items hide each other
             let mut error = Diagnostic::error(
                 pos,
-                format!("Name '{designator}' is hidden by conflicting use clause"),
-            );
+                format!(
+                    "Name '{designator}' is ambiguous, it is hidden by conflicting use clauses"
+                ),
+            )
+            .with_code(ErrorCode::ConflictingUseClause);
 
             fn last_visible_pos(visible_entity: &VisibleEntity) -> u32 {
                 if let Some(pos) = visible_entity.visible_pos.iter().rev().flatten().next() {
@@ -5,17 +5,29 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use crate::analysis::DesignRoot;
-use crate::ast::search::Searcher;
-use crate::ast::DesignFile;
+use crate::ast::search::{ReferenceKind, Searcher};
+use crate::ast::{AnyDesignUnit, AnyPrimaryUnit, DesignFile};
 use crate::completion::{list_completion_options, CompletionItem};
 use crate::config::Config;
+use crate::formatting::{format_design_file, FormatOptions};
+use crate::instantiation::InstantiationOptions;
 use crate::lint::dead_code::UnusedDeclarationsLinter;
 use crate::named_entity::{AnyEnt, EntRef};
-use crate::syntax::VHDLParser;
-use crate::{data::*, EntHierarchy, EntityId};
+use crate::semantic_tokens::SemanticToken;
+use crate::signature_help::SignatureHelp;
+use crate::standard::VHDLStandard;
+use crate::syntax::{ToolIdentifiers, VHDLParser};
+use crate::{
+    data::*, AnalysisPhase, AnalysisProgress, CycleError, DependencyGraph, DependencyNode,
+    EntHierarchy, EntityId, FileStatus, HoverInfo, TypeDescription, UnitDescription, UseClauseFix,
+};
 use fnv::{FnvHashMap, FnvHashSet};
 use std::collections::hash_map::Entry;
 use std::path::Path;
+use std::sync::Arc;
+
+/// The standard package that is analyzed when the files of library std do not declare it
+const FALLBACK_STANDARD_PACKAGE: &str = include_str!("analysis/fallback_standard.vhd");
 
 pub struct Project {
     parser: VHDLParser,
@@ -24,6 +36,8 @@ pub struct Project {
     files: FnvHashMap<FilePath, SourceFile>,
     empty_libraries: FnvHashSet<Symbol>,
     lint: Option<UnusedDeclarationsLinter>,
+    // The missing standard package is only reported once until it is found
+    reported_missing_standard: bool,
 }
 
 impl Project {
@@ -36,6 +50,7 @@ impl Project {
             parser,
             lint: None,
             config: Config::default(),
+            reported_missing_standard: false,
         }
     }
 
@@ -46,10 +61,25 @@ impl Project {
     /// Create instance from given configuration.
     /// Files referred by configuration are parsed into corresponding libraries.
     pub fn from_config(config: Config, messages: &mut dyn MessageHandler) -> Project {
+        Project::from_config_with_progress(config, messages, None)
+    }
+
+    /// Create instance from given configuration while reporting the parsed files to `progress`
+    pub fn from_config_with_progress(
+        config: Config,
+        messages: &mut dyn MessageHandler,
+        progress: Option<&dyn AnalysisProgress>,
+    ) -> Project {
         let mut project = Project::new();
         let files = project.load_files_from_config(&config, messages);
-        project.parse_and_add_files(files, messages);
+        project.parser.tool_identifiers =
+            Arc::new(ToolIdentifiers::new(config.conditional_analysis()));
         project.config = config;
+        if let Some(progress) = progress {
+            progress.phase(AnalysisPhase::Parsing);
+        }
+        project.parse_and_add_files(files, messages, progress);
+        project.report_missing_standard_package(messages);
         project
     }
 
@@ -57,7 +87,19 @@ impl Project {
     /// The design state is reset, new files are added and parsed. Existing source files will be
     /// kept and parsed from in-memory source (required for incremental document updates).
     pub fn update_config(&mut self, config: Config, messages: &mut dyn MessageHandler) {
+        self.update_config_with_progress(config, messages, None)
+    }
+
+    /// Replace active project configuration while reporting the parsed files to `progress`
+    pub fn update_config_with_progress(
+        &mut self,
+        config: Config,
+        messages: &mut dyn MessageHandler,
+        progress: Option<&dyn AnalysisProgress>,
+    ) {
         self.parser = VHDLParser::default();
+        self.parser.tool_identifiers =
+            Arc::new(ToolIdentifiers::new(config.conditional_analysis()));
         self.root = DesignRoot::new(self.parser.symbols.clone());
 
         // Reset library associations for known files,
@@ -73,26 +115,79 @@ impl Project {
             .into_iter()
             .partition(|(file_name, _library_names)| self.files.contains_key(file_name));
 
+        if let Some(progress) = progress {
+            progress.phase(AnalysisPhase::Parsing);
+        }
         for (file_name, library_names) in known_files {
             if let Some(source_file) = self.files.get_mut(&file_name) {
                 source_file.parser_diagnostics.clear();
-                source_file.library_names = library_names;
                 source_file.design_file = self
                     .parser
+                    .with_standard(standard_of(&config, &library_names))
                     .parse_design_source(&source_file.source, &mut source_file.parser_diagnostics);
+                source_file.library_names = library_names;
+                if let Some(progress) = progress {
+                    progress.file_parsed(&file_name.to_string_lossy());
+                }
             }
         }
 
         self.config = config;
-        self.parse_and_add_files(new_files, messages);
+        self.parse_and_add_files(new_files, messages, progress);
+        self.report_missing_standard_package(messages);
+    }
+
+    /// Report that the standard package is not declared by the files of library std, the
+    /// fallback standard package is then analyzed instead. This is only reported once until
+    /// the files declare it.
+    fn report_missing_standard_package(&mut self, messages: &mut dyn MessageHandler) {
+        let std_lib_name = self.root.symbol_utf8("std");
+        let standard = self.root.symbol_utf8("standard");
+        let has_standard = self
+            .files
+            .values()
+            .filter(|source_file| source_file.library_names.contains(&std_lib_name))
+            .flat_map(|source_file| source_file.design_file.design_units.iter())
+            .any(|(_, unit)| {
+                matches!(unit, AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package))
+                    if package.ident.tree.item == standard)
+            });
+        if has_standard || self.reported_missing_standard {
+            self.reported_missing_standard &= !has_standard;
+            return;
+        }
+        self.reported_missing_standard = true;
+
+        let Some(library) = self.config.get_library("std") else {
+            messages.push(Message::error(
+                "The library 'std' is not configured, a fallback standard package is used \
+                 and missing declarations of std and ieee are not reported",
+            ));
+            return;
+        };
+        let patterns: Vec<_> = library
+            .patterns()
+            .iter()
+            .map(|pattern| format!("'{}'", pattern.to_string_lossy()))
+            .collect();
+        messages.push(Message::error(format!(
+            "The standard package was not found in library 'std' with files {}, \
+             a fallback is used and missing declarations of std and ieee are not reported",
+            patterns.join(", ")
+        )));
     }
 
+    /// The files of the libraries of the configuration. A file that is added to more than
+    /// one library, or to the same library by different paths, is reported and only added
+    /// to the first of the libraries in the order of the configuration. Files are identified
+    /// by their canonical path so that a symbolic link to a file is the same file.
     fn load_files_from_config(
         &mut self,
         config: &Config,
         messages: &mut dyn MessageHandler,
     ) -> FnvHashMap<FilePath, FnvHashSet<Symbol>> {
         let mut files: FnvHashMap<FilePath, FnvHashSet<Symbol>> = FnvHashMap::default();
+        let mut added_to: FnvHashMap<FilePath, Vec<Symbol>> = FnvHashMap::default();
         self.empty_libraries.clear();
 
         for library in config.iter_libraries() {
@@ -102,17 +197,16 @@ impl Project {
 
             let mut empty_library = true;
             for file_name in library.file_names(messages) {
-                empty_library = false;
-
-                match files.entry(FilePath::new(&file_name)) {
-                    Entry::Occupied(mut entry) => {
-                        entry.get_mut().insert(library_name.clone());
-                    }
-                    Entry::Vacant(entry) => {
-                        let mut set = FnvHashSet::default();
-                        set.insert(library_name.clone());
-                        entry.insert(set);
-                    }
+                let file_path = FilePath::new(&file_name);
+                added_to
+                    .entry(file_path.clone())
+                    .or_default()
+                    .push(library_name.clone());
+                if let Entry::Vacant(entry) = files.entry(file_path) {
+                    empty_library = false;
+                    let mut set = FnvHashSet::default();
+                    set.insert(library_name.clone());
+                    entry.insert(set);
                 }
             }
 
@@ -120,6 +214,32 @@ impl Project {
                 self.empty_libraries.insert(library_name);
             }
         }
+
+        let mut duplicates: Vec<_> = added_to
+            .into_iter()
+            .filter(|(_, library_names)| library_names.len() > 1)
+            .collect();
+        duplicates.sort_by(|(lhs, _), (rhs, _)| Path::cmp(lhs, rhs));
+        for (file_path, mut library_names) in duplicates {
+            library_names.dedup();
+            let file_name = file_path.to_string_lossy();
+            if let [library_name] = library_names.as_slice() {
+                messages.push(Message::warning(format!(
+                    "File '{file_name}' is added to library '{library_name}' more than once"
+                )));
+            } else {
+                let names: Vec<_> = library_names
+                    .iter()
+                    .map(|library_name| format!("'{library_name}'"))
+                    .collect();
+                messages.push(Message::warning(format!(
+                    "File '{file_name}' is added to libraries {}, \
+                     it is only analyzed in library '{}'",
+                    names.join(", "),
+                    library_names[0]
+                )));
+            }
+        }
         files
     }
 
@@ -127,16 +247,27 @@ impl Project {
         &mut self,
         files_to_parse: FnvHashMap<FilePath, FnvHashSet<Symbol>>,
         messages: &mut dyn MessageHandler,
+        progress: Option<&dyn AnalysisProgress>,
     ) {
         use rayon::prelude::*;
 
+        let config = &self.config;
+        let encoding = config.encoding();
         let parsed: Vec<_> = files_to_parse
             .into_par_iter()
             .map_init(
                 || &self.parser,
                 |parser, (file_name, library_names)| {
                     let mut diagnostics = Vec::new();
-                    let result = parser.parse_design_file(&file_name, &mut diagnostics);
+                    let parser = parser.with_standard(standard_of(config, &library_names));
+                    let result = parser.parse_design_file_with_encoding(
+                        &file_name,
+                        encoding,
+                        &mut diagnostics,
+                    );
+                    if let Some(progress) = progress {
+                        progress.file_parsed(&file_name.to_string_lossy());
+                    }
                     (file_name, library_names, diagnostics, result)
                 },
             )
@@ -209,12 +340,47 @@ impl Project {
         source_file.parser_diagnostics.clear();
         source_file.design_file = self
             .parser
+            .with_standard(standard_of(&self.config, &source_file.library_names))
             .parse_design_source(source, &mut source_file.parser_diagnostics);
         self.files
             .insert(source.file_path().to_owned(), source_file);
     }
 
+    /// Re-read files that were changed, created or deleted on disk outside of the editor,
+    /// for example by a code generator or a version control checkout.
+    /// The file patterns of the configuration are expanded again when a file was created or
+    /// deleted since the files that match them may have changed.
+    pub fn reload_files<'a>(
+        &mut self,
+        file_names: impl IntoIterator<Item = &'a Path>,
+        messages: &mut dyn MessageHandler,
+    ) {
+        let mut created_or_deleted = false;
+        for file_name in file_names {
+            if !file_name.exists() || self.get_source(file_name).is_none() {
+                created_or_deleted = true;
+                continue;
+            }
+            match Source::from_file_with_encoding(file_name, self.config.encoding()) {
+                Ok(source) => self.update_source(&source),
+                Err(err) => messages.push(Message::file_error(err.to_string(), file_name)),
+            }
+        }
+
+        if created_or_deleted {
+            self.update_config(self.config.clone(), messages);
+        }
+    }
+
     pub fn analyse(&mut self) -> Vec<Diagnostic> {
+        self.analyse_with_progress(None)
+    }
+
+    /// Analyze the project while reporting the progress of the analysis to `progress`
+    pub fn analyse_with_progress(
+        &mut self,
+        progress: Option<&dyn AnalysisProgress>,
+    ) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
         for source_file in self.files.values_mut() {
@@ -224,7 +390,12 @@ impl Project {
 
             for library_name in source_file.library_names.iter() {
                 let design_file = design_files.pop().unwrap();
-                self.root.add_design_file(library_name.clone(), design_file);
+                self.root.add_source_file(
+                    library_name.clone(),
+                    &source_file.source,
+                    design_file,
+                    &source_file.parser_diagnostics,
+                );
             }
 
             for diagnostic in source_file.parser_diagnostics.iter().cloned() {
@@ -236,15 +407,80 @@ impl Project {
             self.root.ensure_library(library_name.clone());
         }
 
-        let analyzed_units = self.root.analyze(&mut diagnostics);
+        let parser = &self.parser;
+        self.root.update_fallback_standard_package(|| {
+            let source = Source::inline(Path::new("standard.vhd"), FALLBACK_STANDARD_PACKAGE);
+            let design_file = parser.parse_design_source(&source, &mut NullDiagnostics);
+            (source, design_file)
+        });
+
+        self.root
+            .set_ignore_incomplete_std_logic_case(self.config.ignore_incomplete_std_logic_case());
+        self.root
+            .set_lint_static_assertions(self.config.lint_static_assertions());
+        self.root
+            .set_lint_constant_conditions(self.config.lint_constant_conditions());
+        self.root
+            .set_lint_missing_return(self.config.lint_missing_return());
+        self.root
+            .set_lint_uninitialized_variables(self.config.lint_uninitialized_variables());
+        let synthesis_lint_libraries = self
+            .config
+            .iter_libraries()
+            .filter(|library| library.synthesis_lint())
+            .map(|library| self.root.symbol_utf8(library.name()))
+            .collect();
+        self.root
+            .set_synthesis_lint_libraries(synthesis_lint_libraries);
+        let third_party_libraries = self
+            .config
+            .iter_libraries()
+            .filter(|library| library.is_third_party())
+            .map(|library| self.root.symbol_utf8(library.name()))
+            .collect();
+        self.root.set_third_party_libraries(third_party_libraries);
+        let analyzed_units = self.root.analyze_with_progress(&mut diagnostics, progress);
 
         if let Some(ref mut lint) = self.lint {
             lint.lint(&self.root, &self.config, &analyzed_units, &mut diagnostics);
         }
 
+        let mut diagnostics = {
+            let mut overridden = Vec::with_capacity(diagnostics.len());
+            let mut handler = SeverityOverridingDiagnosticHandler::new(
+                &mut overridden,
+                self.config.severity_overrides(),
+            );
+            for diagnostic in diagnostics {
+                handler.push(diagnostic);
+            }
+            overridden
+        };
+
+        // Third-party libraries are analyzed so that references into them resolve,
+        // but only their errors are reported
+        diagnostics.retain(|diagnostic| {
+            diagnostic.severity == Severity::Error || !self.is_third_party(&diagnostic.pos.source)
+        });
+
+        // Files are parsed and analyzed in parallel, sort to get a deterministic order
+        diagnostics.sort_by(|lhs, rhs| lhs.pos.cmp(&rhs.pos));
         diagnostics
     }
 
+    /// Returns true if all libraries that a source file is mapped to are third-party libraries
+    fn is_third_party(&self, source: &Source) -> bool {
+        let Some(file) = self.files.get(source.file_path()) else {
+            return false;
+        };
+        !file.library_names.is_empty()
+            && file.library_names.iter().all(|library_name| {
+                self.config
+                    .get_library(&library_name.name_utf8())
+                    .is_some_and(|library| library.is_third_party())
+            })
+    }
+
     /// Search for reference at position
     /// Character offset on a line in a document (zero-based). Assuming that the line is
     /// represented as a string, the `character` value represents the gap between the
@@ -253,8 +489,7 @@ impl Project {
     /// If the character value is greater than the line length it defaults back to the
     /// line length.
     pub fn find_definition<'a>(&'a self, source: &Source, cursor: Position) -> Option<EntRef<'a>> {
-        let ent = self.root.search_reference(source, cursor)?;
-        self.root.find_definition_of(ent)
+        self.root.find_definition(source, cursor)
     }
 
     pub fn find_declaration<'a>(&'a self, source: &Source, cursor: Position) -> Option<EntRef<'a>> {
@@ -279,6 +514,11 @@ impl Project {
         self.root.public_symbols()
     }
 
+    /// Find public symbols matching the query, best matches first
+    pub fn workspace_symbols<'a>(&'a self, query: &str, limit: usize) -> Vec<EntRef<'a>> {
+        self.root.workspace_symbols(query, limit)
+    }
+
     // Find symbols that are public such as primary design units and their interfaces
     pub fn document_symbols<'a>(
         &'a self,
@@ -296,6 +536,53 @@ impl Project {
         }
     }
 
+    pub fn find_implementations(&self, source: &Source, cursor: Position) -> Vec<SrcPos> {
+        self.root.find_implementations(source, cursor)
+    }
+
+    pub fn hover<'a>(&'a self, source: &Source, cursor: Position) -> Option<HoverInfo<'a>> {
+        self.root.hover(source, cursor)
+    }
+
+    pub fn type_at<'a>(&'a self, source: &Source, cursor: Position) -> Option<TypeDescription<'a>> {
+        self.root.type_at(source, cursor)
+    }
+
+    /// Lists the signatures of the call or map aspect at the cursor position
+    pub fn signature_help<'a>(
+        &'a self,
+        source: &Source,
+        cursor: Position,
+    ) -> Option<SignatureHelp<'a>> {
+        self.root.signature_help(source, cursor)
+    }
+
+    /// A template for instantiating an entity with all generics and ports associated
+    pub fn instantiation_template(
+        &self,
+        library_name: &Symbol,
+        entity_name: &Symbol,
+        options: &InstantiationOptions,
+    ) -> Option<String> {
+        self.root
+            .instantiation_template(library_name, entity_name, options)
+    }
+
+    /// Use clauses that make the missing name at the position visible,
+    /// one for each package that declares the name
+    pub fn use_clause_fixes(&self, pos: &SrcPos) -> Vec<UseClauseFix> {
+        self.root.use_clause_fixes(pos)
+    }
+
+    /// Classifies the keywords and identifiers of the source for syntax highlighting
+    pub fn semantic_tokens(&self, source: &Source) -> Vec<SemanticToken> {
+        self.root.semantic_tokens(source)
+    }
+
+    pub fn semantic_tokens_in_range(&self, source: &Source, range: Range) -> Vec<SemanticToken> {
+        self.root.semantic_tokens_in_range(source, range)
+    }
+
     /// Search for the declaration at decl_pos and format it
     pub fn format_declaration(&self, ent: &AnyEnt) -> Option<String> {
         self.root.format_declaration(ent)
@@ -311,16 +598,143 @@ impl Project {
         self.root.find_all_references(ent)
     }
 
+    /// Search for all references to the declaration at decl_pos together with their kind
+    pub fn find_all_references_with_kind(&self, ent: &AnyEnt) -> Vec<(SrcPos, ReferenceKind)> {
+        self.root.find_all_references_with_kind(ent)
+    }
+
+    /// The positions where the signal declared at `decl` is driven
+    pub fn signal_drivers(&self, decl: &SrcPos) -> Vec<SrcPos> {
+        self.root.signal_drivers(decl)
+    }
+
+    /// The positions where the signal declared at `decl` is read
+    pub fn signal_readers(&self, decl: &SrcPos) -> Vec<SrcPos> {
+        self.root.signal_readers(decl)
+    }
+
+    /// The calls of the subprogram declared at `decl` together with the declaration of the
+    /// calling subprogram or process
+    pub fn incoming_calls(&self, decl: &SrcPos) -> Vec<(SrcPos, SrcPos)> {
+        self.root.incoming_calls(decl)
+    }
+
+    /// The calls within the subprogram or process declared at `decl` together with the
+    /// declaration of the called subprogram
+    pub fn outgoing_calls(&self, decl: &SrcPos) -> Vec<(SrcPos, SrcPos)> {
+        self.root.outgoing_calls(decl)
+    }
+
+    /// The references to the item at the cursor within the same source together with their kind
+    pub fn document_references(
+        &self,
+        source: &Source,
+        cursor: Position,
+    ) -> Vec<(SrcPos, ReferenceKind)> {
+        self.root.document_references(source, cursor)
+    }
+
+    /// Compute the text edits needed to rename the declaration at decl_pos and all its references
+    pub fn rename(
+        &self,
+        decl_pos: &SrcPos,
+        new_name: &str,
+    ) -> Result<Vec<(SrcPos, String)>, Diagnostic> {
+        self.root.rename(decl_pos, new_name)
+    }
+
     /// Get source positions that are not resolved to a declaration
     /// This is used for development to test where the language server is blind
     pub fn find_all_unresolved(&self) -> (usize, Vec<SrcPos>) {
         self.root.find_all_unresolved()
     }
 
+    /// The names of all libraries of the project
+    pub fn libraries(&self) -> impl Iterator<Item = &Symbol> {
+        self.root.available_libraries()
+    }
+
+    /// The primary units of a library together with their secondary units
+    pub fn units_in_library(&self, library_name: &Symbol) -> Vec<UnitDescription> {
+        self.root.units_in_library(library_name)
+    }
+
+    /// Find the primary unit `name` of a library, for example to find the file defining `work.foo`
+    pub fn find_unit(&self, library_name: &Symbol, name: &Symbol) -> Option<UnitDescription> {
+        self.root.find_unit(library_name, name)
+    }
+
+    /// The primary units that are not used by any of the top-level units given as library and
+    /// unit name. Units of third-party libraries are not included.
+    pub fn unused_units(&self, roots: &[(Symbol, Symbol)]) -> Vec<UnitDescription> {
+        self.root
+            .unused_units(roots)
+            .into_iter()
+            .filter(|unit| !self.is_third_party(&unit.source))
+            .collect()
+    }
+
+    /// The dependencies between all design units
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        self.root.dependency_graph()
+    }
+
+    /// All design units in an order where each unit comes after the units it depends on
+    pub fn compile_order(&self) -> Result<Vec<DependencyNode>, CycleError> {
+        self.root.compile_order()
+    }
+
+    pub fn symbol_utf8(&self, name: &str) -> Symbol {
+        self.root.symbol_utf8(name)
+    }
+
     pub fn files(&self) -> impl Iterator<Item = &SourceFile> {
         self.files.values()
     }
 
+    /// The parse and analysis status of every file in each of its libraries
+    pub fn file_statuses(&self) -> Vec<FileStatus> {
+        self.root.files()
+    }
+
+    /// Format the source file.
+    /// Returns None if the file contains syntax errors or tool directives
+    /// since they would be lost when printing the parsed design file.
+    pub fn format_source(&self, source: &Source, options: &FormatOptions) -> Option<String> {
+        let standard = match self.files.get(source.file_path()) {
+            Some(file) => standard_of(&self.config, &file.library_names),
+            None => self.config.standard(),
+        };
+
+        {
+            let contents = source.contents();
+            let has_tool_directive = (0..contents.num_lines()).any(|lineno| {
+                contents
+                    .get_line(lineno)
+                    .map(|line| line.trim_start().starts_with('`'))
+                    .unwrap_or(false)
+            });
+            if has_tool_directive {
+                return None;
+            }
+        }
+
+        let mut diagnostics = Vec::new();
+        let design_file = self
+            .parser
+            .with_standard(standard)
+            .parse_design_source(source, &mut diagnostics);
+
+        if design_file.design_units.is_empty()
+            || diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity == Severity::Error)
+        {
+            return None;
+        }
+        Some(format_design_file(&design_file, options))
+    }
+
     pub fn list_completion_options(
         &self,
         source: &Source,
@@ -353,6 +767,15 @@ impl Default for Project {
     }
 }
 
+/// The standard of a file that is mapped to several libraries is the newest of their standards
+fn standard_of(config: &Config, library_names: &FnvHashSet<Symbol>) -> VHDLStandard {
+    library_names
+        .iter()
+        .map(|library_name| config.library_standard(&library_name.name_utf8()))
+        .max()
+        .unwrap_or_else(|| config.standard())
+}
+
 pub struct SourceFile {
     library_names: FnvHashSet<Symbol>,
     source: Source,
@@ -373,7 +796,14 @@ impl SourceFile {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::syntax::test::check_no_diagnostics;
+    use crate::syntax::test::{assert_eq_unordered, check_no_diagnostics};
+
+    fn std_not_configured() -> Message {
+        Message::error(
+            "The library 'std' is not configured, a fallback standard package is used \
+             and missing declarations of std and ieee are not reported",
+        )
+    }
 
     /// Test that an empty library is created
     /// Thus test case was added when fixing a bug
@@ -402,155 +832,927 @@ lib.files = ['file.vhd']
         let config = Config::from_str(config_str, root.path()).unwrap();
         let mut messages = Vec::new();
         let mut project = Project::from_config(config, &mut messages);
-        assert_eq!(messages, vec![]);
+        assert_eq!(messages, vec![std_not_configured()]);
         check_no_diagnostics(&project.analyse());
     }
 
     #[test]
-    fn unmapped_libraries_are_analyzed() {
-        let mut messages = Vec::new();
-        let mut project = Project::from_config(Config::default(), &mut messages);
-        assert_eq!(messages, vec![]);
-        let diagnostics = project.analyse();
-        check_no_diagnostics(&diagnostics);
-
+    fn config_standard_is_used_when_parsing() {
         let root = tempfile::tempdir().unwrap();
         let vhdl_file_path = root.path().join("file.vhd");
         std::fs::write(
-            &vhdl_file_path,
+            vhdl_file_path,
             "
 entity ent is
-end ent;
-
-architecture rtl of ent is
-begin
-end architecture;
-
-architecture rtl of ent is
-begin
-end architecture;
-",
+  port (a : bit;);
+end entity;
+        ",
         )
         .unwrap();
-        let source = Source::from_latin1_file(&vhdl_file_path).unwrap();
 
-        project.update_source(&source);
-        let diagnostics = project.analyse();
-        assert_eq!(diagnostics.len(), 1);
-        let diag = diagnostics.first().unwrap();
-        assert_eq!(diag.message, "Duplicate architecture 'rtl' of entity 'ent'")
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{}/../vhdl_libraries/std/standard.vhd']
+lib.files = ['file.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let config = Config::from_str(&config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let has_standard_error = |diagnostics: Vec<Diagnostic>| {
+            diagnostics.iter().any(|diagnostic| {
+                diagnostic.message == "Last interface element may not end with ';' before VHDL-2019"
+            })
+        };
+        assert!(has_standard_error(project.analyse()));
+
+        let config =
+            Config::from_str(&format!("standard = '2019'{config_str}"), root.path()).unwrap();
+        project.update_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        assert!(!has_standard_error(project.analyse()));
     }
 
-    /// Test that the same file can be added to several libraries
     #[test]
-    fn test_same_file_in_multiple_libraries() {
+    fn only_errors_are_reported_for_third_party_libraries() {
         let root = tempfile::tempdir().unwrap();
-        let vhdl_file_path1 = root.path().join("file.vhd");
         std::fs::write(
-            vhdl_file_path1,
+            root.path().join("vendor.vhd"),
             "
 package pkg is
 end package;
+
+library unused;
         ",
         )
         .unwrap();
-
-        let vhdl_file_path2 = root.path().join("use_file.vhd");
         std::fs::write(
-            vhdl_file_path2,
+            root.path().join("file.vhd"),
             "
-library lib1;
-use lib1.pkg.all;
-
-package use_pkg1 is
-end package;
+library vendor;
+use vendor.pkg;
 
-library lib2;
-use lib2.pkg.all;
+entity ent is
+end entity;
 
-package use_pkg2 is
-end package;
+library unused;
         ",
         )
         .unwrap();
 
         let config_str = "
 [libraries]
-lib1.files = ['file.vhd']
-lib2.files = ['file.vhd']
-use_lib.files = ['use_file.vhd']
+vendor.files = ['vendor.vhd']
+vendor.is_third_party = true
+lib.files = ['file.vhd']
         ";
 
         let config = Config::from_str(config_str, root.path()).unwrap();
         let mut messages = Vec::new();
         let mut project = Project::from_config(config, &mut messages);
-        assert_eq!(messages, vec![]);
-        check_no_diagnostics(&project.analyse());
+        assert_eq!(messages, vec![std_not_configured()]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].pos.source.file_name(),
+            root.path().join("file.vhd")
+        );
     }
 
-    fn update(project: &mut Project, source: &mut Source, contents: &str) {
-        std::fs::write(std::path::Path::new(source.file_name()), contents).unwrap();
-        *source = Source::from_latin1_file(source.file_name()).unwrap();
-        project.update_source(source);
+    #[test]
+    fn bundled_libraries_have_no_new_diagnostics() {
+        let config = Config::read_file_path(Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../vhdl_libraries/vhdl_ls.toml"
+        )))
+        .unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+
+        // Pure functions of VITAL call `now`, which is only an error for the user's own code
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.code != Some(ErrorCode::PurityViolation)));
+        // The remaining errors are known false positives in the fixed and floating point
+        // packages, any new diagnostic is reported by every project that uses the libraries
+        assert_eq!(diagnostics.len(), 110);
     }
 
-    /// Test that the same file can be added to several libraries
     #[test]
-    fn test_re_analyze_after_update() {
-        let tempdir = tempfile::tempdir().unwrap();
-        let root = dunce::canonicalize(tempdir.path()).unwrap();
-
-        let path1 = root.join("file1.vhd");
-        let path2 = root.join("file2.vhd");
+    fn config_can_ignore_incomplete_std_logic_case() {
+        let root = tempfile::tempdir().unwrap();
         std::fs::write(
-            &path1,
+            root.path().join("std_logic_1164.vhd"),
             "
-package pkg is
+package std_logic_1164 is
+  type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
 end package;
         ",
         )
         .unwrap();
-        let mut source1 = Source::from_latin1_file(&path1).unwrap();
-
         std::fs::write(
-            &path2,
+            root.path().join("file.vhd"),
             "
-library lib1;
-use lib1.pkg.all;
+library ieee;
+use ieee.std_logic_1164.all;
 
 package pkg is
+  procedure proc(value : std_ulogic);
 end package;
+
+package body pkg is
+  procedure proc(value : std_ulogic) is
+  begin
+    case value is
+      when '0' | '1' => null;
+    end case;
+  end procedure;
+end package body;
         ",
         )
         .unwrap();
-        let mut source2 = Source::from_latin1_file(&path2).unwrap();
 
-        let config_str = "
+        let config_str = format!(
+            "
 [libraries]
-lib1.files = ['file1.vhd']
-lib2.files = ['file2.vhd']
-        ";
-
-        let config = Config::from_str(config_str, &root).unwrap();
+std.files = ['{}/../vhdl_libraries/std/standard.vhd']
+ieee.files = ['std_logic_1164.vhd']
+lib.files = ['file.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let config = Config::from_str(&config_str, root.path()).unwrap();
         let mut messages = Vec::new();
         let mut project = Project::from_config(config, &mut messages);
         assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "Missing choice(s): 'U', 'X', 'Z', 'W', 'L', 'H', '-'"
+        );
+
+        let config = Config::from_str(
+            &format!("ignore_incomplete_std_logic_case = true{config_str}"),
+            root.path(),
+        )
+        .unwrap();
+        project.update_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
         check_no_diagnostics(&project.analyse());
+    }
 
-        // Add syntax error
-        update(
-            &mut project,
-            &mut source1,
+    #[test]
+    fn synthesis_lint_is_enabled_per_library() {
+        let root = tempfile::tempdir().unwrap();
+        let code = "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal clk : bit;
+begin
+  process
+  begin
+    clk <= not clk;
+    wait for 5 ns;
+  end process;
+end architecture;
+        ";
+        std::fs::write(root.path().join("rtl.vhd"), code).unwrap();
+        std::fs::write(root.path().join("tb.vhd"), code).unwrap();
+
+        let config_str = format!(
             "
-package is
+[libraries]
+std.files = ['{}/../vhdl_libraries/std/standard.vhd']
+rtl.files = ['rtl.vhd']
+rtl.synthesis_lint = true
+tb.files = ['tb.vhd']
         ",
+            env!("CARGO_MANIFEST_DIR")
         );
+        let config = Config::from_str(&config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
         let diagnostics = project.analyse();
-        assert_eq!(diagnostics.len(), 3);
-        // Syntax error comes first
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some(ErrorCode::SynthesisWaitFor));
+        assert_eq!(
+            diagnostics[0].pos.source.file_name(),
+            root.path().join("rtl.vhd")
+        );
+    }
+
+    #[test]
+    fn conditional_analysis_identifiers_are_taken_from_config() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("file.vhd"),
+            "
+`if TOOL_TYPE = \"SIMULATION\" `then
+use work.sim_pkg.all;
+`else
+use work.synth_pkg.all;
+`end if
+entity ent is
+end entity;
+",
+        )
+        .unwrap();
+
+        let missing_package = |tool_type: &str| {
+            let config_str = format!(
+                "
+[conditional_analysis]
+TOOL_TYPE = '{tool_type}'
+[libraries]
+lib.files = ['file.vhd']
+"
+            );
+            let config = Config::from_str(&config_str, root.path()).unwrap();
+            let mut messages = Vec::new();
+            let mut project = Project::from_config(config, &mut messages);
+            assert_eq!(messages, vec![std_not_configured()]);
+            let diagnostics = project.analyse();
+            assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+            let source = project.get_source(&root.path().join("file.vhd")).unwrap();
+            assert_eq!(source.inactive_regions().len(), 1);
+            diagnostics[0].message.clone()
+        };
+        assert!(missing_package("SIMULATION").contains("sim_pkg"));
+        assert!(missing_package("SYNTHESIS").contains("synth_pkg"));
+    }
+
+    #[test]
+    fn config_can_demote_and_ignore_diagnostics_by_code() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("file.vhd"),
+            "
+library work;
+
+entity ent is
+end entity;
+
+entity ent is
+end entity;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        let codes = |diagnostics: Vec<Diagnostic>| {
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| (diagnostic.code, diagnostic.severity))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            codes(project.analyse()),
+            vec![
+                (Some(ErrorCode::UnnecessaryWorkLibrary), Severity::Hint),
+                (Some(ErrorCode::DuplicateDecl), Severity::Error),
+            ]
+        );
+
+        let config = Config::from_str(
+            &format!(
+                "
+[diagnostics]
+hint = ['duplicate_decl']
+ignore = ['unnecessary_work_library']
+{config_str}"
+            ),
+            root.path(),
+        )
+        .unwrap();
+        project.update_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        assert_eq!(
+            codes(project.analyse()),
+            vec![(Some(ErrorCode::DuplicateDecl), Severity::Hint)]
+        );
+    }
+
+    #[test]
+    fn reload_files_changed_on_disk() {
+        let root = tempfile::tempdir().unwrap();
+        let file1 = root.path().join("file1.vhd");
+        let file2 = root.path().join("file2.vhd");
+        std::fs::write(&file1, "architecture a of missing is begin end;").unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['*.vhd']
+        ";
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        let messages_of = |diagnostics: Vec<Diagnostic>| {
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages_of(project.analyse()),
+            vec!["No entity 'missing' within library 'lib'"]
+        );
+
+        // Changed file
+        std::fs::write(&file1, "entity ent is end;").unwrap();
+        project.reload_files([file1.as_path()], &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        check_no_diagnostics(&project.analyse());
+
+        // Created file that matches the pattern of the library
+        std::fs::write(&file2, "architecture a of other is begin end;").unwrap();
+        project.reload_files([file2.as_path()], &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        assert_eq!(
+            messages_of(project.analyse()),
+            vec!["No entity 'other' within library 'lib'"]
+        );
+
+        // Deleted file
+        std::fs::remove_file(&file2).unwrap();
+        project.reload_files([file2.as_path()], &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        check_no_diagnostics(&project.analyse());
+    }
+
+    /// Records the reported phases and parsed files
+    #[derive(Default)]
+    struct ParseProgress {
+        phases: std::sync::Mutex<Vec<AnalysisPhase>>,
+        files: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl AnalysisProgress for ParseProgress {
+        fn phase(&self, phase: AnalysisPhase) {
+            self.phases.lock().unwrap().push(phase);
+        }
+
+        fn file_parsed(&self, file_name: &str) {
+            self.files.lock().unwrap().push(file_name.to_owned());
+        }
+
+        fn unit_started(&self, _library_name: &str, _unit_name: &str) {}
+
+        fn unit_finished(&self, _library_name: &str, _unit_name: &str) {}
+    }
+
+    #[test]
+    fn progress_reports_every_parsed_file_once() {
+        let root = tempfile::tempdir().unwrap();
+        for file_name in ["file1.vhd", "file2.vhd"] {
+            std::fs::write(
+                root.path().join(file_name),
+                "
+entity ent is
+end entity;
+            ",
+            )
+            .unwrap();
+        }
+
+        let config_str = "
+[libraries]
+lib1.files = ['file1.vhd']
+lib2.files = ['file2.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let progress = ParseProgress::default();
+        let mut project =
+            Project::from_config_with_progress(config.clone(), &mut messages, Some(&progress));
+        project.analyse_with_progress(Some(&progress));
+        assert_eq!(
+            progress.phases.lock().unwrap().as_slice(),
+            [
+                AnalysisPhase::Parsing,
+                AnalysisPhase::Preparing,
+                AnalysisPhase::Analyzing,
+                AnalysisPhase::Finished
+            ]
+        );
+        let file_names = |progress: &ParseProgress| {
+            let mut files = std::mem::take(&mut *progress.files.lock().unwrap());
+            files.sort();
+            files
+        };
+        let expected: Vec<_> = ["file1.vhd", "file2.vhd"]
+            .into_iter()
+            .map(|file_name| root.path().join(file_name).to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(file_names(&progress), expected);
+
+        // Files that are already part of the project are parsed again
+        project.update_config_with_progress(config, &mut messages, Some(&progress));
+        assert_eq!(file_names(&progress), expected);
+    }
+
+    #[test]
+    fn library_standard_is_used_when_parsing() {
+        let root = tempfile::tempdir().unwrap();
+        for file_name in ["old.vhd", "new.vhd"] {
+            std::fs::write(
+                root.path().join(file_name),
+                "
+entity ent is
+  port (a : bit;);
+end entity;
+            ",
+            )
+            .unwrap();
+        }
+
+        let config_str = "
+[libraries]
+old.files = ['old.vhd']
+new.files = ['new.vhd']
+new.standard = '2019'
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        let standard_errors: Vec<_> = project
+            .analyse()
+            .into_iter()
+            .filter(|diagnostic| {
+                diagnostic.message == "Last interface element may not end with ';' before VHDL-2019"
+            })
+            .collect();
+        assert_eq!(standard_errors.len(), 1);
+        assert_eq!(
+            standard_errors[0].pos.source.file_name(),
+            root.path().join("old.vhd")
+        );
+    }
+
+    #[test]
+    fn unmapped_libraries_are_analyzed() {
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(Config::default(), &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        let diagnostics = project.analyse();
+        check_no_diagnostics(&diagnostics);
+
+        let root = tempfile::tempdir().unwrap();
+        let vhdl_file_path = root.path().join("file.vhd");
+        std::fs::write(
+            &vhdl_file_path,
+            "
+entity ent is
+end ent;
+
+architecture rtl of ent is
+begin
+end architecture;
+
+architecture rtl of ent is
+begin
+end architecture;
+",
+        )
+        .unwrap();
+        let source = Source::from_latin1_file(&vhdl_file_path).unwrap();
+
+        project.update_source(&source);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        let diag = diagnostics.first().unwrap();
+        assert_eq!(diag.message, "Duplicate architecture 'rtl' of entity 'ent'")
+    }
+
+    #[test]
+    fn missing_standard_package_is_reported_once() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(
+            root.join("file.vhd"),
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+  port (
+    clk : in std_logic;
+    count : out natural);
+end entity;
+
+architecture a of ent is
+  signal s : boolean;
+  signal s : boolean;
+begin
+  count <= 0 when s else 1;
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+std.files = ['missing/std/*.vhd']
+ieee.files = ['missing/ieee/*.vhd']
+lib.files = ['file.vhd']
+";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        let errors: Vec<_> = messages
+            .iter()
+            .filter(|message| message.message_type == MessageType::Error)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![&Message::error(format!(
+                "The standard package was not found in library 'std' with files '{}', \
+                 a fallback is used and missing declarations of std and ieee are not reported",
+                root.join("missing/std/*.vhd").to_string_lossy()
+            ))]
+        );
+
+        // Only the local duplicate declaration is reported
+        let diagnostics = project.analyse();
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Duplicate declaration of 's'"]
+        );
+    }
+
+    #[test]
+    fn fallback_standard_package_is_used_when_std_is_not_configured() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(
+            root.join("file.vhd"),
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity ent is
+  port (count : out natural);
+end entity;
+
+architecture a of ent is
+  signal data : std_logic_vector(7 downto 0);
+  signal x : bolean;
+begin
+  count <= to_integer(x\"ff\");
+end architecture;
+
+entity ent2 is
+end entity;
+
+architecture a of ent2 is
+  signal w : width;
+begin
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+
+        // The names of the used packages of std and ieee are not reported, a local typo
+        // and a name of a package that is not used are
+        let diagnostics = project.analyse();
+        assert_eq_unordered(
+            &diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            &["No declaration of 'bolean'", "No declaration of 'width'"],
+        );
+    }
+
+    #[test]
+    fn fallback_standard_package_is_removed_when_std_declares_it() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        let std_file = root.join("standard.vhd");
+        std::fs::write(&std_file, "package other is end package;").unwrap();
+        std::fs::write(
+            root.join("file.vhd"),
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+  port (clk : in std_logic);
+end entity;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+std.files = ['standard.vhd']
+lib.files = ['file.vhd']
+";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages.len(), 1);
+        check_no_diagnostics(&project.analyse());
+        assert!(project.root.uses_fallback_standard_package());
+
+        // The standard package replaces the fallback, the missing names of ieee are reported
+        std::fs::copy(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../vhdl_libraries/std/standard.vhd"
+            ),
+            &std_file,
+        )
+        .unwrap();
+        project.update_source(&Source::from_latin1_file(&std_file).unwrap());
+        let diagnostics = project.analyse();
+        assert!(!project.root.uses_fallback_standard_package());
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "No such library 'ieee'",
+                "No declaration of 'ieee'",
+                "No declaration of 'std_logic'"
+            ]
+        );
+    }
+
+    /// The file is only analyzed in the first library of the configuration so that lib1.pkg
+    /// is not declared
+    #[test]
+    fn test_same_file_in_multiple_libraries() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        let vhdl_file_path1 = root.join("file.vhd");
+        std::fs::write(
+            &vhdl_file_path1,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let vhdl_file_path2 = root.join("use_file.vhd");
+        std::fs::write(
+            vhdl_file_path2,
+            "
+library lib1;
+use lib1.pkg.all;
+
+package use_pkg1 is
+end package;
+
+library lib2;
+use lib2.pkg.all;
+
+package use_pkg2 is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib2.files = ['file.vhd']
+lib1.files = ['file.vhd']
+use_lib.files = ['use_file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(
+            messages,
+            vec![
+                Message::warning(format!(
+                    "File '{}' is added to libraries 'lib2', 'lib1', \
+                 it is only analyzed in library 'lib2'",
+                    vhdl_file_path1.to_string_lossy()
+                )),
+                std_not_configured(),
+            ]
+        );
+        let source = project.get_source(&vhdl_file_path1).unwrap();
+        assert_eq!(
+            project.library_mapping_of(&source),
+            vec![project.root.symbol_utf8("lib2")]
+        );
+        assert_eq!(
+            project
+                .analyse()
+                .iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["No primary unit 'pkg' within library 'lib1'"]
+        );
+    }
+
+    #[test]
+    fn same_file_twice_in_one_library_is_reported() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        let vhdl_file_path = root.join("file.vhd");
+        std::fs::write(
+            &vhdl_file_path,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        std::fs::create_dir(root.join("sub")).unwrap();
+
+        // Identical paths such as those matched by overlapping patterns are not reported
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd', '*.vhd', 'sub/../file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(
+            messages,
+            vec![
+                Message::warning(format!(
+                    "File '{}' is added to library 'lib' more than once",
+                    vhdl_file_path.to_string_lossy()
+                )),
+                std_not_configured(),
+            ]
+        );
+        check_no_diagnostics(&project.analyse());
+    }
+
+    #[test]
+    fn files_with_same_name_in_different_directories_are_not_reported() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        for dir in ["a", "b"] {
+            std::fs::create_dir(root.join(dir)).unwrap();
+            std::fs::write(
+                root.join(dir).join("file.vhd"),
+                "
+package pkg is
+end package;
+        ",
+            )
+            .unwrap();
+        }
+
+        let config_str = "
+[libraries]
+lib1.files = ['a/file.vhd']
+lib2.files = ['b/file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        check_no_diagnostics(&project.analyse());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_to_file_is_the_same_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        let vhdl_file_path = root.join("file.vhd");
+        std::fs::write(
+            &vhdl_file_path,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&vhdl_file_path, root.join("link.vhd")).unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['file.vhd']
+lib2.files = ['link.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(
+            messages,
+            vec![
+                Message::warning(format!(
+                    "File '{}' is added to libraries 'lib1', 'lib2', \
+                 it is only analyzed in library 'lib1'",
+                    vhdl_file_path.to_string_lossy()
+                )),
+                std_not_configured(),
+            ]
+        );
+        check_no_diagnostics(&project.analyse());
+    }
+
+    fn update(project: &mut Project, source: &mut Source, contents: &str) {
+        std::fs::write(std::path::Path::new(source.file_name()), contents).unwrap();
+        *source = Source::from_latin1_file(source.file_name()).unwrap();
+        project.update_source(source);
+    }
+
+    /// Test that the same file can be added to several libraries
+    #[test]
+    fn test_re_analyze_after_update() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path1 = root.join("file1.vhd");
+        let path2 = root.join("file2.vhd");
+        std::fs::write(
+            &path1,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        let mut source1 = Source::from_latin1_file(&path1).unwrap();
+
+        std::fs::write(
+            &path2,
+            "
+library lib1;
+use lib1.pkg.all;
+
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        let mut source2 = Source::from_latin1_file(&path2).unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['file1.vhd']
+lib2.files = ['file2.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        check_no_diagnostics(&project.analyse());
+
+        // Add syntax error
+        update(
+            &mut project,
+            &mut source1,
+            "
+package is
+        ",
+        );
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 2);
+        // Syntax error comes first
         assert_eq!(diagnostics[0].pos.source, source1);
-        assert_eq!(diagnostics[1].pos.source, source1);
-        assert_eq!(diagnostics[2].pos.source, source2);
+        assert_eq!(diagnostics[1].pos.source, source2);
 
         // Make it good again
         update(
@@ -591,7 +1793,174 @@ end package;
         check_no_diagnostics(&project.analyse());
     }
 
+    #[test]
+    fn units_after_syntax_error_are_analyzed() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("broken.vhd"),
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  process
+  begin
+    wait;
+end architecture;
+
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("user.vhd"),
+            "
+use work.pkg.all;
+
+entity user is
+end entity;
+
+architecture a of user is
+begin
+end architecture;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['broken.vhd', 'user.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        let diagnostics = project.analyse();
+        assert!(!diagnostics.is_empty());
+        for diagnostic in diagnostics.iter() {
+            assert_eq!(
+                diagnostic.pos.source.file_name(),
+                root.path().join("broken.vhd")
+            );
+        }
+    }
+
     /// Test that the configuration can be updated
+    #[test]
+    fn file_statuses_after_analysis() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(
+            root.join("pkg.vhd"),
+            "
+package pkg is
+end package;
+
+package body pkg is
+end package body;
+        ",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("ent.vhd"),
+            "
+use work.pkg.all;
+use work.missing.all;
+
+entity ent is
+end entity;
+        ",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("broken.vhd"),
+            "
+entity broken is
+  port (
+end entity;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['*.vhd']
+        ";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![std_not_configured()]);
+        // The syntax error is reported along with the missing end of the file
+        assert_eq!(project.analyse().len(), 3);
+
+        let lib = project.symbol_utf8("lib");
+        let status =
+            |project: &Project, file_name: &str, num_units, parsed, num_errors, revision| {
+                FileStatus {
+                    source: project.get_source(&root.join(file_name)).unwrap(),
+                    library_name: lib.clone(),
+                    num_units,
+                    parsed,
+                    num_errors,
+                    num_warnings: 0,
+                    num_infos: 0,
+                    num_hints: 0,
+                    revision,
+                    source_revision: 0,
+                }
+            };
+        assert_eq!(
+            project.file_statuses(),
+            vec![
+                status(&project, "broken.vhd", 0, false, 2, 1),
+                status(&project, "ent.vhd", 1, true, 1, 1),
+                status(&project, "pkg.vhd", 2, true, 0, 1),
+            ]
+        );
+
+        // Only the changed file is analyzed again
+        let mut source = project.get_source(&root.join("ent.vhd")).unwrap();
+        update(
+            &mut project,
+            &mut source,
+            "
+use work.pkg.all;
+
+entity ent is
+end entity;
+        ",
+        );
+        assert_eq!(project.analyse().len(), 2);
+        assert_eq!(
+            project.file_statuses(),
+            vec![
+                status(&project, "broken.vhd", 0, false, 2, 1),
+                status(&project, "ent.vhd", 1, true, 0, 2),
+                status(&project, "pkg.vhd", 2, true, 0, 1),
+            ]
+        );
+
+        // An edit of the source is reported with the revision of its contents
+        source.change(None, "entity ent is\nend entity;");
+        project.update_source(&source);
+        assert_eq!(project.analyse().len(), 2);
+        let statuses = project.file_statuses();
+        assert_eq!(
+            statuses
+                .iter()
+                .map(|status| (status.source.file_name(), status.source_revision))
+                .collect::<Vec<_>>(),
+            vec![
+                (root.join("broken.vhd").as_path(), 0),
+                (root.join("ent.vhd").as_path(), 1),
+                (root.join("pkg.vhd").as_path(), 0),
+            ]
+        );
+    }
+
     #[test]
     fn test_config_update() {
         let tempdir = tempfile::tempdir().unwrap();
@@ -639,7 +2008,7 @@ lib.files = ['file2.vhd']
 
         let mut messages = Vec::new();
         let mut project = Project::from_config(config1, &mut messages);
-        assert_eq!(messages, vec![]);
+        assert_eq!(messages, vec![std_not_configured()]);
 
         // Invalid library should only be reported in source1
         let diagnostics = project.analyse();
@@ -649,7 +2018,7 @@ lib.files = ['file2.vhd']
 
         // Change configuration file
         project.update_config(config2, &mut messages);
-        assert_eq!(messages, vec![]);
+        assert_eq!(messages, vec![std_not_configured()]);
 
         // Invalid library should only be reported in source2
         let diagnostics = project.analyse();
@@ -657,4 +2026,25 @@ lib.files = ['file2.vhd']
         assert_eq!(diagnostics[0].pos.source, source2); // No such library
         assert_eq!(diagnostics[1].pos.source, source2); // No declaration
     }
+
+    #[test]
+    fn format_source_requires_valid_syntax() {
+        let project = Project::new();
+        let options = FormatOptions::default();
+
+        let source = Source::inline(Path::new("file.vhd"), "ENTITY ent IS END ENTITY;");
+        assert_eq!(
+            project.format_source(&source, &options),
+            Some("entity ent is\nend entity;\n".to_owned())
+        );
+
+        let source = Source::inline(Path::new("file.vhd"), "entity ent is");
+        assert_eq!(project.format_source(&source, &options), None);
+
+        let source = Source::inline(
+            Path::new("file.vhd"),
+            "`protect begin\nentity ent is end entity;",
+        );
+        assert_eq!(project.format_source(&source, &options), None);
+    }
 }
@@ -0,0 +1,403 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{FoundDeclaration, NotFinished, Search, SearchState, Searcher};
+use crate::ast::{Mode, ObjectClass};
+use crate::data::{Position, Range, SrcPos};
+use crate::named_entity::{HasEntityId, ObjectInterface, Reference};
+use crate::syntax::{kind_str, Kind, Token, TokenAccess};
+use crate::{AnyEntKind, EntRef, Object, Overloaded, Source};
+use fnv::FnvHashMap;
+
+/// The classification of a token for syntax highlighting.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum SemanticTokenType {
+    Keyword,
+    Type,
+    Signal,
+    Variable,
+    Constant,
+    /// A function, procedure or operator
+    Function,
+    /// A library or a design unit such as an entity or a package
+    Namespace,
+    /// A subprogram parameter
+    Parameter,
+    /// A name that refers to something without a more specific class,
+    /// or that could not be resolved
+    Identifier,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum SemanticTokenModifier {
+    /// The token is the name of a declaration
+    Declaration,
+    /// The token denotes an object that cannot be assigned, such as a constant
+    /// or an input port
+    Readonly,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SemanticToken {
+    pub range: Range,
+    pub token_type: SemanticTokenType,
+    pub modifiers: Vec<SemanticTokenModifier>,
+}
+
+/// Collects the classification of all identifiers of a design unit,
+/// keyed by the start position of the identifier.
+struct ClassifySearcher<'a> {
+    root: &'a DesignRoot,
+    classes: FnvHashMap<Position, (SemanticTokenType, Vec<SemanticTokenModifier>)>,
+}
+
+impl<'a> ClassifySearcher<'a> {
+    fn add(&mut self, pos: &SrcPos, ent: EntRef, is_declaration: bool) {
+        let (token_type, readonly) = classify(ent);
+        let mut modifiers = Vec::new();
+        if is_declaration {
+            modifiers.push(SemanticTokenModifier::Declaration);
+        }
+        if readonly {
+            modifiers.push(SemanticTokenModifier::Readonly);
+        }
+        self.classes
+            .entry(pos.start())
+            .or_insert((token_type, modifiers));
+    }
+}
+
+impl<'a> Searcher for ClassifySearcher<'a> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if let Some(id) = decl.ent_id() {
+            let ent = self.root.get_ent(id);
+            if let Some(decl_pos) = ent.decl_pos() {
+                self.add(decl_pos, ent, true);
+            }
+            if let Some(end_pos) = decl.end_ident_pos() {
+                self.add(end_pos, ent, false);
+            }
+        }
+        NotFinished
+    }
+
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        if let Some(id) = reference.get() {
+            self.add(pos, self.root.get_ent(id), false);
+        }
+        NotFinished
+    }
+}
+
+/// Returns the token type of the named entity and whether it is read-only
+fn classify(ent: EntRef) -> (SemanticTokenType, bool) {
+    match ent.actual_kind() {
+        AnyEntKind::Object(Object { class, iface, .. }) => match iface {
+            Some(ObjectInterface::Parameter(_)) => (
+                SemanticTokenType::Parameter,
+                *class == ObjectClass::Constant,
+            ),
+            Some(ObjectInterface::Generic) => (SemanticTokenType::Constant, true),
+            Some(ObjectInterface::Port(mode)) => (SemanticTokenType::Signal, *mode == Mode::In),
+            None => match class {
+                ObjectClass::Signal => (SemanticTokenType::Signal, false),
+                ObjectClass::Constant => (SemanticTokenType::Constant, true),
+                ObjectClass::Variable | ObjectClass::SharedVariable => {
+                    (SemanticTokenType::Variable, false)
+                }
+            },
+        },
+        AnyEntKind::DeferredConstant(_)
+        | AnyEntKind::LoopParameter(_)
+        | AnyEntKind::PhysicalLiteral(_) => (SemanticTokenType::Constant, true),
+        AnyEntKind::File(_) | AnyEntKind::InterfaceFile(_) => (SemanticTokenType::Variable, false),
+        AnyEntKind::Overloaded(overloaded) => {
+            if matches!(overloaded, Overloaded::EnumLiteral(_)) {
+                (SemanticTokenType::Constant, true)
+            } else {
+                (SemanticTokenType::Function, false)
+            }
+        }
+        AnyEntKind::Type(_) => (SemanticTokenType::Type, false),
+        AnyEntKind::Library | AnyEntKind::Design(_) | AnyEntKind::Component(_) => {
+            (SemanticTokenType::Namespace, false)
+        }
+        AnyEntKind::ExternalAlias { .. }
+        | AnyEntKind::ObjectAlias { .. }
+        | AnyEntKind::Attribute(_)
+        | AnyEntKind::ElementDeclaration(_)
+        | AnyEntKind::Concurrent(_)
+        | AnyEntKind::Sequential(_) => (SemanticTokenType::Identifier, false),
+    }
+}
+
+/// Keyword tokens, excluding operators such as `+` whose kind is spelled as a word
+fn is_keyword(token: &Token) -> bool {
+    let keyword = kind_str(token.kind);
+    let range = token.pos.range();
+    keyword.chars().all(|chr| chr.is_ascii_alphabetic())
+        && range.start.line == range.end.line
+        && (range.end.character - range.start.character) as usize == keyword.len()
+}
+
+fn overlaps(lhs: &Range, rhs: &Range) -> bool {
+    lhs.start < rhs.end && rhs.start < lhs.end
+}
+
+impl DesignRoot {
+    /// Classifies the keywords and identifiers of the source for syntax highlighting.
+    /// The tokens are ordered by position.
+    pub fn semantic_tokens(&self, source: &Source) -> Vec<SemanticToken> {
+        self.classify_tokens(source, None)
+    }
+
+    /// Like `semantic_tokens`, but only includes the tokens that overlap the range.
+    /// Design units outside of the range are not searched.
+    pub fn semantic_tokens_in_range(&self, source: &Source, range: Range) -> Vec<SemanticToken> {
+        self.classify_tokens(source, Some(range))
+    }
+
+    fn classify_tokens(&self, source: &Source, range: Option<Range>) -> Vec<SemanticToken> {
+        let mut result = Vec::new();
+        for unit in self.units_by_source(source) {
+            let (Some(first), Some(last)) = (unit.tokens.first(), unit.tokens.last()) else {
+                continue;
+            };
+            let unit_range = Range::new(first.pos.start(), last.pos.end());
+            if let Some(ref range) = range {
+                if !overlaps(&unit_range, range) {
+                    continue;
+                }
+            }
+
+            let mut searcher = ClassifySearcher {
+                root: self,
+                classes: FnvHashMap::default(),
+            };
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+
+            for token in unit.tokens.iter() {
+                let token_range = token.pos.range();
+                if let Some(ref range) = range {
+                    if !overlaps(&token_range, range) {
+                        continue;
+                    }
+                }
+
+                let (token_type, modifiers) = if token.kind == Kind::Identifier {
+                    searcher
+                        .classes
+                        .remove(&token_range.start)
+                        .unwrap_or((SemanticTokenType::Identifier, Vec::new()))
+                } else if is_keyword(token) {
+                    (SemanticTokenType::Keyword, Vec::new())
+                } else {
+                    continue;
+                };
+
+                result.push(SemanticToken {
+                    range: token_range,
+                    token_type,
+                    modifiers,
+                });
+            }
+        }
+        result.sort_by_key(|token| token.range.start);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::tests::{check_no_diagnostics, LibraryBuilder};
+    use crate::syntax::test::Code;
+    use SemanticTokenModifier::*;
+    use SemanticTokenType::*;
+
+    fn token_at<'t>(tokens: &'t [SemanticToken], code: &Code) -> &'t SemanticToken {
+        let range = code.pos().range();
+        tokens
+            .iter()
+            .find(|token| token.range == range)
+            .unwrap_or_else(|| panic!("No token at {range:?}"))
+    }
+
+    fn class_at(
+        tokens: &[SemanticToken],
+        code: &Code,
+    ) -> (SemanticTokenType, Vec<SemanticTokenModifier>) {
+        let token = token_at(tokens, code);
+        (token.token_type, token.modifiers.clone())
+    }
+
+    #[test]
+    fn classifies_names_of_architecture() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  function fun(arg : natural) return natural;
+end package;
+
+package body pkg is
+  function fun(arg : natural) return natural is
+  begin
+    return arg;
+  end function;
+end package body;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  constant c0 : natural := 1;
+  signal s0 : natural;
+begin
+  s0 <= work.pkg.fun(c0);
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let tokens = root.semantic_tokens(code.source());
+        assert_eq!(
+            class_at(&tokens, &code.s1("constant c0").s1("c0")),
+            (Constant, vec![Declaration, Readonly])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("fun(c0)").s1("c0")),
+            (Constant, vec![Readonly])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("signal s0").s1("s0")),
+            (Signal, vec![Declaration])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("s0 <=").s1("s0")),
+            (Signal, vec![])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("pkg.fun(c0)").s1("fun")),
+            (Function, vec![])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("work.pkg").s1("work")),
+            (Namespace, vec![])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("work.pkg").s1("pkg")),
+            (Namespace, vec![])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("c0 : natural").s1("natural")),
+            (Type, vec![])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("return arg").s1("arg")),
+            (Parameter, vec![Readonly])
+        );
+        assert_eq!(
+            class_at(
+                &tokens,
+                &code.s1("architecture arch of ent").s1("architecture")
+            ),
+            (Keyword, vec![])
+        );
+        assert_eq!(
+            class_at(&tokens, &code.s1("end architecture").s1("end")),
+            (Keyword, vec![])
+        );
+
+        // Operators and literals are not classified
+        assert!(!tokens
+            .iter()
+            .any(|token| token.range == code.s1("<=").pos().range()));
+
+        let mut sorted = tokens.clone();
+        sorted.sort_by_key(|token| token.range.start);
+        assert_eq!(tokens, sorted);
+    }
+
+    #[test]
+    fn unresolved_names_are_identifiers() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity ent is
+end entity;
+
+architecture arch of ent is
+  signal s0 : natural;
+begin
+  s0 <= missing;
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let tokens = root.semantic_tokens(code.source());
+        assert_eq!(class_at(&tokens, &code.s1("missing")), (Identifier, vec![]));
+    }
+
+    #[test]
+    fn range_limited_semantic_tokens() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  function fun(arg : natural) return natural;
+end package;
+
+package body pkg is
+  function fun(arg : natural) return natural is
+  begin
+    return arg;
+  end function;
+end package body;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  constant c0 : natural := 1;
+  signal s0 : natural;
+begin
+  s0 <= work.pkg.fun(c0);
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+
+        let all = root.semantic_tokens(code.source());
+        let range = code.s1("s0 <= work.pkg.fun(c0);").pos().range();
+        let in_range = root.semantic_tokens_in_range(code.source(), range);
+        let expected: Vec<_> = all
+            .into_iter()
+            .filter(|token| range.contains(token.range.start))
+            .collect();
+        assert_eq!(in_range, expected);
+        assert_eq!(
+            in_range
+                .iter()
+                .map(|token| token.token_type)
+                .collect::<Vec<_>>(),
+            vec![Signal, Namespace, Namespace, Function, Constant]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Conversion of the AST to and from JSON.
+//!
+//! Most types derive their implementation when the `serialize` feature is enabled.
+//! The types in this file refer to shared state which cannot be part of the JSON:
+//! * A `SrcPos` is written as `[file_name, start_line, start_character, end_line, end_character]`
+//!   and read back by looking up the source with the same file name.
+//! * A `Symbol` is written as its name and is read back by inserting it into the symbol table
+//!   of the parser.
+//! * A `Reference` is the result of analysis and is not written at all.
+
+use crate::ast::DesignFile;
+use crate::data::*;
+use crate::named_entity::Reference;
+use crate::syntax::{Symbols, VHDLParser};
+use serde::de::{self, IgnoredAny};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The state that is needed to create symbols and source positions while deserializing
+struct DeserializeContext {
+    symbols: Arc<Symbols>,
+    sources: Vec<Source>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<DeserializeContext>> = const { RefCell::new(None) };
+}
+
+fn with_context<T, E: de::Error>(
+    f: impl FnOnce(&DeserializeContext) -> Result<T, E>,
+) -> Result<T, E> {
+    CONTEXT.with(|context| match context.borrow().as_ref() {
+        Some(context) => f(context),
+        None => Err(E::custom(
+            "symbols and source positions can only be deserialized by VHDLParser::design_file_from_json",
+        )),
+    })
+}
+
+impl Serialize for Latin1String {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Latin1String {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Latin1String::from_utf8(&string).map_err(|err| de::Error::custom(err.message()))
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.name().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = Latin1String::deserialize(deserializer)?;
+        with_context(|context| {
+            let symtab = context.symbols.symtab();
+            Ok(if name.bytes.first() == Some(&b'\\') {
+                symtab.insert_extended(&name)
+            } else {
+                symtab.insert(&name)
+            })
+        })
+    }
+}
+
+impl Serialize for SrcPos {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Range { start, end } = self.range;
+        let mut tuple = serializer.serialize_tuple(5)?;
+        tuple.serialize_element(&self.source.file_name().to_string_lossy())?;
+        tuple.serialize_element(&start.line)?;
+        tuple.serialize_element(&start.character)?;
+        tuple.serialize_element(&end.line)?;
+        tuple.serialize_element(&end.character)?;
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for SrcPos {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (file_name, start_line, start_character, end_line, end_character) =
+            <(String, u32, u32, u32, u32)>::deserialize(deserializer)?;
+        with_context(|context| {
+            let source = context
+                .sources
+                .iter()
+                .find(|source| source.file_name() == Path::new(&file_name))
+                .ok_or_else(|| de::Error::custom(format!("Unknown source file '{file_name}'")))?;
+            Ok(source.pos(
+                Position::new(start_line, start_character),
+                Position::new(end_line, end_character),
+            ))
+        })
+    }
+}
+
+impl Serialize for Reference {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de> Deserialize<'de> for Reference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(Reference::undefined())
+    }
+}
+
+impl VHDLParser {
+    /// Read a design file that was written as JSON.
+    /// All source positions must refer to one of the sources.
+    pub fn design_file_from_json(
+        &self,
+        json: &str,
+        sources: &[Source],
+    ) -> serde_json::Result<DesignFile> {
+        CONTEXT.with(|context| {
+            *context.borrow_mut() = Some(DeserializeContext {
+                symbols: self.symbols.clone(),
+                sources: sources.to_vec(),
+            })
+        });
+        let result = serde_json::from_str(json);
+        CONTEXT.with(|context| *context.borrow_mut() = None);
+        result
+    }
+}
+
+/// Parse the source and write the AST of the design file as JSON.
+/// Fails with the first syntax error of the source.
+pub fn parse_to_json(source: &Source) -> Result<String, Diagnostic> {
+    let parser = VHDLParser::default();
+    let mut diagnostics = Vec::new();
+    let design_file = parser.parse_design_source(source, &mut diagnostics);
+    if let Some(error) = diagnostics
+        .into_iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        return Err(error);
+    }
+    Ok(serde_json::to_string(&design_file).expect("The AST can always be serialized"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn design_file_round_trip() {
+        let code = Code::new(
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- An entity with generics and ports
+entity ent is
+  generic (
+    width : natural := 8
+  );
+  port (
+    clk : in std_logic;
+    \\data out\\ : out std_logic_vector(width - 1 downto 0)
+  );
+end entity;
+
+architecture rtl of ent is
+  constant factor : real := 2.5e-3;
+  signal counter : integer range 0 to 2**width - 1;
+begin
+  gen: for i in 0 to width - 1 generate
+    \\data out\\(i) <= '1' when (counter / 2**i) mod 2 = 1 else '0';
+  end generate;
+
+  cond: if width > 4 generate
+    process (clk)
+    begin
+      if rising_edge(clk) then
+        counter <= (counter + 1) mod 2**width;
+      end if;
+    end process;
+  else generate
+  end generate;
+end architecture;
+",
+        );
+
+        let parser = VHDLParser::default();
+        let source = code.source();
+        let mut diagnostics = Vec::new();
+        let design_file = parser.parse_design_source(source, &mut diagnostics);
+        assert_eq!(diagnostics, Vec::new());
+
+        let json = serde_json::to_string(&design_file).unwrap();
+        let result = parser
+            .design_file_from_json(&json, std::slice::from_ref(source))
+            .unwrap();
+        assert_eq!(result, design_file);
+    }
+
+    #[test]
+    fn source_positions_are_arrays() {
+        let code = Code::new("entity ent is end entity;");
+        let json = parse_to_json(code.source()).unwrap();
+        let file_name = code.source().file_name().to_string_lossy().into_owned();
+        let position = serde_json::to_string(&code.s("ent", 2).pos()).unwrap();
+        assert_eq!(
+            position,
+            format!("[{},0,7,0,10]", serde_json::json!(file_name))
+        );
+        assert!(json.contains(&position));
+    }
+
+    #[test]
+    fn parse_to_json_fails_on_syntax_error() {
+        let code = Code::new("entity ent is");
+        let error = parse_to_json(code.source()).unwrap_err();
+        assert_eq!(error.message, "Unexpected EOF");
+    }
+
+    #[test]
+    fn deserialization_requires_known_sources() {
+        let code = Code::new("entity ent is end entity;");
+        let json = parse_to_json(code.source()).unwrap();
+        assert!(VHDLParser::default()
+            .design_file_from_json(&json, &[])
+            .is_err());
+    }
+}
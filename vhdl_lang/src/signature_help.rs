@@ -0,0 +1,613 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{Found, FoundDeclaration, NotFinished, SearchState, Searcher};
+use crate::ast::{
+    ConcurrentStatement, Designator, InterfaceDeclaration, InterfaceType, SubprogramSpecification,
+};
+use crate::completion::tokenize_input;
+use crate::data::Symbol;
+use crate::named_entity::HasEntityId;
+use crate::syntax::Kind::*;
+use crate::syntax::{Token, TokenAccess, Value};
+use crate::{AnyEntKind, EntRef, EntityId, Position, Source};
+use vhdl_lang::ast::search::Finished;
+
+/// A candidate signature for the call or instantiation at the cursor.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SignatureInformation<'a> {
+    /// The subprogram, entity or component that the signature belongs to
+    pub ent: EntRef<'a>,
+    /// The formatted signature such as
+    /// `function foo(constant x : in natural := 0; constant y : in bit) return bit`
+    pub label: String,
+    /// The formatted interface elements. Each of them is a substring of the label.
+    pub parameters: Vec<String>,
+    /// The index of the parameter that the cursor is on.
+    /// `None` when the cursor is on an argument that has no matching parameter.
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SignatureHelp<'a> {
+    /// All overloaded candidates for a subprogram call,
+    /// or the port or generic list of an instantiated entity or component
+    pub signatures: Vec<SignatureInformation<'a>>,
+    /// The index of the signature that the call resolves to
+    pub active_signature: usize,
+}
+
+/// The call or map aspect that the cursor is inside of.
+enum CallContext<'t> {
+    /// A subprogram call, where the token is the name of the subprogram
+    Subprogram(&'t Token),
+    /// A generic or port map
+    MapAspect(InterfaceType),
+}
+
+/// Finds the innermost opening parenthesis that has not been closed before the cursor
+/// and returns the call context in front of it, as well as the formals of the association
+/// elements between the parenthesis and the cursor.
+/// The last formal belongs to the association element that the cursor is on.
+/// Positional association elements have no formal.
+fn find_call_context(tokens: &[Token]) -> Option<(CallContext<'_>, Vec<Option<&Symbol>>)> {
+    let mut depth = 0;
+    let mut open = None;
+    for (idx, token) in tokens.iter().enumerate().rev() {
+        match token.kind {
+            RightPar => depth += 1,
+            LeftPar if depth == 0 => {
+                open = Some(idx);
+                break;
+            }
+            LeftPar => depth -= 1,
+            SemiColon => return None,
+            _ => {}
+        }
+    }
+    let open = open?;
+
+    let context = match &tokens[..open] {
+        [.., Token { kind: Port, .. }, Token { kind: Map, .. }] => {
+            CallContext::MapAspect(InterfaceType::Port)
+        }
+        [.., Token { kind: Generic, .. }, Token { kind: Map, .. }] => {
+            CallContext::MapAspect(InterfaceType::Generic)
+        }
+        [.., name @ Token {
+            kind: Identifier, ..
+        }] => CallContext::Subprogram(name),
+        _ => return None,
+    };
+
+    let mut elements = vec![&tokens[open + 1..open + 1]];
+    let mut depth = 0;
+    let mut start = open + 1;
+    for (idx, token) in tokens.iter().enumerate().skip(open + 1) {
+        match token.kind {
+            LeftPar => depth += 1,
+            RightPar => depth -= 1,
+            Comma if depth == 0 => {
+                start = idx + 1;
+                elements.push(&tokens[start..start]);
+                continue;
+            }
+            _ => {}
+        }
+        *elements.last_mut().unwrap() = &tokens[start..=idx];
+    }
+
+    let formals = elements
+        .into_iter()
+        .map(|element| {
+            let has_arrow = element.iter().any(|token| token.kind == RightArrow);
+            match element {
+                [Token {
+                    kind: Identifier,
+                    value: Value::Identifier(formal),
+                    ..
+                }, ..]
+                    if has_arrow =>
+                {
+                    Some(formal)
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    Some((context, formals))
+}
+
+/// Returns the index of the parameter that the last association element is associated with.
+/// Named elements are associated by the formal name, positional elements by position.
+/// As named association is not followed by positional association,
+/// an element without formal after named elements is the first parameter that has not been
+/// associated yet.
+fn active_parameter(parameters: &[Designator], formals: &[Option<&Symbol>]) -> Option<usize> {
+    let is_formal = |param: &Designator, formal: &Symbol| matches!(param, Designator::Identifier(sym) if sym == formal);
+    let (current, previous) = formals.split_last()?;
+    if let Some(formal) = current {
+        return parameters.iter().position(|param| is_formal(param, formal));
+    }
+
+    let positional = previous
+        .iter()
+        .take_while(|formal| formal.is_none())
+        .count();
+    (positional..parameters.len()).find(|idx| {
+        !previous
+            .iter()
+            .flatten()
+            .any(|formal| is_formal(&parameters[*idx], formal))
+    })
+}
+
+fn interface_designator(decl: &InterfaceDeclaration) -> Designator {
+    match decl {
+        InterfaceDeclaration::Object(obj) => Designator::Identifier(obj.ident.tree.item.clone()),
+        InterfaceDeclaration::File(file) => Designator::Identifier(file.ident.tree.item.clone()),
+        InterfaceDeclaration::Type(ident) => Designator::Identifier(ident.tree.item.clone()),
+        InterfaceDeclaration::Subprogram(spec, _) => {
+            spec.subpgm_designator().item.clone().into_designator()
+        }
+        InterfaceDeclaration::Package(pkg) => Designator::Identifier(pkg.ident.tree.item.clone()),
+    }
+}
+
+/// Creates the signature from the interface list of a declaration.
+/// The label is `{prefix}({interface list}){suffix}`
+/// where the parenthesis are omitted for an empty interface list.
+fn signature_information<'a>(
+    ent: EntRef<'a>,
+    prefix: String,
+    interface_list: &[InterfaceDeclaration],
+    suffix: String,
+    formals: &[Option<&Symbol>],
+) -> SignatureInformation<'a> {
+    let parameters: Vec<String> = interface_list.iter().map(|decl| decl.to_string()).collect();
+    let label = if parameters.is_empty() {
+        format!("{prefix}{suffix}")
+    } else {
+        format!("{prefix}({}){suffix}", parameters.join("; "))
+    };
+    let designators: Vec<Designator> = interface_list.iter().map(interface_designator).collect();
+
+    SignatureInformation {
+        ent,
+        label,
+        parameters,
+        active_parameter: active_parameter(&designators, formals),
+    }
+}
+
+fn subprogram_signature<'a>(
+    ent: EntRef<'a>,
+    spec: &SubprogramSpecification,
+    formals: &[Option<&Symbol>],
+) -> SignatureInformation<'a> {
+    match spec {
+        SubprogramSpecification::Function(function) => signature_information(
+            ent,
+            format!(
+                "{}function {}",
+                if function.pure { "" } else { "impure " },
+                function.designator.tree.item
+            ),
+            &function.parameter_list,
+            format!(" return {}", function.return_type),
+            formals,
+        ),
+        SubprogramSpecification::Procedure(procedure) => signature_information(
+            ent,
+            format!("procedure {}", procedure.designator.tree.item),
+            &procedure.parameter_list,
+            String::new(),
+            formals,
+        ),
+    }
+}
+
+/// Collects the signatures of all subprograms that overload the called subprogram,
+/// i.e., that have the same designator and are declared in the same region.
+struct SubprogramSignatureSearcher<'a, 'f> {
+    root: &'a DesignRoot,
+    called: EntRef<'a>,
+    formals: &'f [Option<&'f Symbol>],
+    signatures: Vec<SignatureInformation<'a>>,
+}
+
+impl<'a, 'f> SubprogramSignatureSearcher<'a, 'f> {
+    fn is_overload(&self, ent: EntRef) -> bool {
+        // Bodies are represented by their declaration
+        ent.declaration().id() == ent.id()
+            && ent.designator() == self.called.designator()
+            && ent.parent.map(|parent| parent.id()) == self.called.parent.map(|parent| parent.id())
+    }
+
+    fn add_signature(&mut self, id: EntityId, spec: &SubprogramSpecification) {
+        let ent = self.root.get_ent(id);
+        if self.is_overload(ent) {
+            self.signatures
+                .push(subprogram_signature(ent, spec, self.formals));
+        }
+    }
+}
+
+impl<'a, 'f> Searcher for SubprogramSignatureSearcher<'a, 'f> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        let Some(id) = decl.ent_id() else {
+            return NotFinished;
+        };
+        match decl {
+            FoundDeclaration::SubprogramDecl(spec) => self.add_signature(id, spec),
+            FoundDeclaration::Subprogram(body) => self.add_signature(id, &body.specification),
+            _ => {}
+        }
+        NotFinished
+    }
+}
+
+/// Finds the instantiated entity or component of the map aspect that contains the cursor.
+struct InstanceSearcher {
+    cursor: Position,
+    kind: InterfaceType,
+    instantiated: Option<EntityId>,
+}
+
+impl Searcher for InstanceSearcher {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if let FoundDeclaration::ConcurrentStatement(stmt) = decl {
+            if let ConcurrentStatement::Instance(inst) = &stmt.statement.item {
+                let map = match self.kind {
+                    InterfaceType::Generic => &inst.generic_map,
+                    _ => &inst.port_map,
+                };
+                if map
+                    .as_ref()
+                    .is_some_and(|map| map.span(ctx).contains(self.cursor))
+                {
+                    self.instantiated = inst.entity_reference();
+                    return Finished(Found);
+                }
+            }
+        }
+        NotFinished
+    }
+}
+
+/// Creates the signature from the generic or port list of an entity or component.
+struct InstanceSignatureSearcher<'a, 'f> {
+    root: &'a DesignRoot,
+    instantiated: EntityId,
+    kind: InterfaceType,
+    formals: &'f [Option<&'f Symbol>],
+    signature: Option<SignatureInformation<'a>>,
+}
+
+impl<'a, 'f> InstanceSignatureSearcher<'a, 'f> {
+    fn set_signature(
+        &mut self,
+        keyword: &str,
+        generic_list: Option<&[InterfaceDeclaration]>,
+        port_list: Option<&[InterfaceDeclaration]>,
+    ) {
+        let ent = self.root.get_ent(self.instantiated);
+        let (list_keyword, interface_list) = match self.kind {
+            InterfaceType::Generic => ("generic", generic_list),
+            _ => ("port", port_list),
+        };
+        self.signature = Some(signature_information(
+            ent,
+            format!("{keyword} {} {list_keyword} ", ent.designator()),
+            interface_list.unwrap_or_default(),
+            String::new(),
+            self.formals,
+        ));
+    }
+}
+
+impl<'a, 'f> Searcher for InstanceSignatureSearcher<'a, 'f> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration) -> SearchState {
+        if decl.ent_id() != Some(self.instantiated) {
+            return NotFinished;
+        }
+        match decl {
+            FoundDeclaration::Entity(entity) => self.set_signature(
+                "entity",
+                entity.generic_clause.as_deref(),
+                entity.port_clause.as_deref(),
+            ),
+            FoundDeclaration::Component(component) => self.set_signature(
+                "component",
+                Some(&component.generic_list),
+                Some(&component.port_list),
+            ),
+            _ => return NotFinished,
+        }
+        Finished(Found)
+    }
+}
+
+impl DesignRoot {
+    /// Lists the signatures of the subprogram call, or the generic or port map
+    /// that the cursor is inside of.
+    pub fn signature_help<'a>(
+        &'a self,
+        source: &Source,
+        cursor: Position,
+    ) -> Option<SignatureHelp<'a>> {
+        let tokens = tokenize_input(self.symbols(), source, cursor);
+        let (context, formals) = find_call_context(&tokens)?;
+
+        match context {
+            CallContext::Subprogram(name) => {
+                let called = self
+                    .search_reference(source, name.pos.start())?
+                    .declaration();
+                if !matches!(called.kind(), AnyEntKind::Overloaded(_)) {
+                    return None;
+                }
+                let mut searcher = SubprogramSignatureSearcher {
+                    root: self,
+                    called,
+                    formals: &formals,
+                    signatures: Vec::new(),
+                };
+                let _ = self.search(&mut searcher);
+                if searcher.signatures.is_empty() {
+                    return None;
+                }
+                let active_signature = searcher
+                    .signatures
+                    .iter()
+                    .position(|signature| signature.ent.id() == called.id())
+                    .unwrap_or_default();
+                Some(SignatureHelp {
+                    signatures: searcher.signatures,
+                    active_signature,
+                })
+            }
+            CallContext::MapAspect(kind) => {
+                let mut searcher = InstanceSearcher {
+                    cursor,
+                    kind,
+                    instantiated: None,
+                };
+                let _ = self.search_source(source, &mut searcher);
+                let instantiated = self.get_ent(searcher.instantiated?).declaration().id();
+
+                let mut searcher = InstanceSignatureSearcher {
+                    root: self,
+                    instantiated,
+                    kind,
+                    formals: &formals,
+                    signature: None,
+                };
+                let _ = self.search(&mut searcher);
+                Some(SignatureHelp {
+                    signatures: vec![searcher.signature?],
+                    active_signature: 0,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::tests::{check_no_diagnostics, LibraryBuilder};
+
+    #[test]
+    fn signature_help_for_positional_arguments() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  function fun(a : natural) return natural;
+  function fun(a : natural; b : boolean; c : bit := '0') return natural;
+end package;
+
+package body pkg is
+  function fun(a : natural) return natural is
+  begin
+    return a;
+  end function;
+
+  function fun(a : natural; b : boolean; c : bit := '0') return natural is
+  begin
+    return a;
+  end function;
+end package body;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  signal s1 : natural := fun(1, true, '1');
+  signal s2 : natural := fun(c => '1', a => 1, b => false);
+begin
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let help = root
+            .signature_help(code.source(), code.s1("fun(1, ").end())
+            .unwrap();
+        assert_eq!(help.signatures.len(), 2);
+
+        let unary = &help.signatures[0];
+        assert_eq!(
+            unary.label,
+            "function fun(constant a : in natural) return natural"
+        );
+        assert_eq!(unary.parameters, vec!["constant a : in natural"]);
+        assert_eq!(unary.active_parameter, None);
+
+        let ternary = &help.signatures[help.active_signature];
+        assert_eq!(
+            ternary.parameters,
+            vec![
+                "constant a : in natural",
+                "constant b : in boolean",
+                "constant c : in bit := '0'"
+            ]
+        );
+        assert_eq!(ternary.active_parameter, Some(1));
+        assert_eq!(
+            ternary.ent.decl_pos(),
+            Some(&code.s1("fun(a : natural; b").s1("fun").pos())
+        );
+
+        let help = root
+            .signature_help(code.source(), code.s1("fun(1").end())
+            .unwrap();
+        assert_eq!(help.signatures[0].active_parameter, Some(0));
+        assert_eq!(help.signatures[1].active_parameter, Some(0));
+    }
+
+    #[test]
+    fn signature_help_for_named_arguments_out_of_order() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  function fun(a : natural) return natural;
+  function fun(a : natural; b : boolean; c : bit := '0') return natural;
+end package;
+
+package body pkg is
+  function fun(a : natural) return natural is
+  begin
+    return a;
+  end function;
+
+  function fun(a : natural; b : boolean; c : bit := '0') return natural is
+  begin
+    return a;
+  end function;
+end package body;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  signal s1 : natural := fun(1, true, '1');
+  signal s2 : natural := fun(c => '1', a => 1, b => false);
+begin
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+
+        let active_parameter = |prefix: &str| {
+            let help = root
+                .signature_help(code.source(), code.s1(prefix).end())
+                .unwrap();
+            help.signatures[help.active_signature].active_parameter
+        };
+        assert_eq!(active_parameter("fun(c => "), Some(2));
+        assert_eq!(active_parameter("fun(c => '1', a =>"), Some(0));
+        assert_eq!(active_parameter("fun(c => '1', a => 1, "), Some(1));
+    }
+
+    #[test]
+    fn signature_help_for_port_map() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity sub is
+  generic (width : natural := 8);
+  port (
+    din : in bit;
+    dout : out bit
+  );
+end entity;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  signal sig1, sig2 : bit;
+begin
+  inst: entity work.sub
+    generic map (width => 4)
+    port map (dout => sig2, din => sig1);
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let help = root
+            .signature_help(code.source(), code.s1("port map (dout => sig2, ").end())
+            .unwrap();
+        assert_eq!(help.signatures.len(), 1);
+        let signature = &help.signatures[0];
+        assert_eq!(
+            signature.label,
+            "entity sub port (din : in bit; dout : out bit)"
+        );
+        assert_eq!(signature.active_parameter, Some(0));
+
+        let help = root
+            .signature_help(code.source(), code.s1("generic map (").end())
+            .unwrap();
+        assert_eq!(help.signatures[0].parameters, vec!["width : natural := 8"]);
+        assert_eq!(help.signatures[0].active_parameter, Some(0));
+    }
+
+    #[test]
+    fn no_signature_help_outside_of_calls() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  function fun(a : natural) return natural;
+  function fun(a : natural; b : boolean; c : bit := '0') return natural;
+end package;
+
+package body pkg is
+  function fun(a : natural) return natural is
+  begin
+    return a;
+  end function;
+
+  function fun(a : natural; b : boolean; c : bit := '0') return natural is
+  begin
+    return a;
+  end function;
+end package body;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture arch of ent is
+  signal s1 : natural := fun(1, true, '1');
+  signal s2 : natural := fun(c => '1', a => 1, b => false);
+begin
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        assert_eq!(
+            root.signature_help(code.source(), code.s1("signal s1").end()),
+            None
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use std::fmt::{Display, Formatter};
+
+/// The revision of the VHDL language that source files are parsed according to.
+/// Constructs that were introduced in a later revision are reported as errors.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum VHDLStandard {
+    V1993,
+    V2002,
+    #[default]
+    V2008,
+    V2019,
+}
+
+impl VHDLStandard {
+    pub fn from_name(name: &str) -> Option<VHDLStandard> {
+        match name.to_lowercase().trim_start_matches("vhdl-") {
+            "1993" | "93" => Some(VHDLStandard::V1993),
+            "2002" | "02" => Some(VHDLStandard::V2002),
+            "2008" | "08" => Some(VHDLStandard::V2008),
+            "2019" | "19" => Some(VHDLStandard::V2019),
+            _ => None,
+        }
+    }
+}
+
+impl Display for VHDLStandard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VHDLStandard::V1993 => write!(f, "VHDL-1993"),
+            VHDLStandard::V2002 => write!(f, "VHDL-2002"),
+            VHDLStandard::V2008 => write!(f, "VHDL-2008"),
+            VHDLStandard::V2019 => write!(f, "VHDL-2019"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name() {
+        assert_eq!(VHDLStandard::from_name("1993"), Some(VHDLStandard::V1993));
+        assert_eq!(VHDLStandard::from_name("08"), Some(VHDLStandard::V2008));
+        assert_eq!(
+            VHDLStandard::from_name("VHDL-2019"),
+            Some(VHDLStandard::V2019)
+        );
+        assert_eq!(VHDLStandard::from_name("2000"), None);
+    }
+
+    #[test]
+    fn standards_are_ordered() {
+        assert!(VHDLStandard::V1993 < VHDLStandard::V2002);
+        assert!(VHDLStandard::V2008 < VHDLStandard::V2019);
+    }
+}
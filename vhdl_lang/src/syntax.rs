@@ -16,11 +16,13 @@ mod configuration;
 mod context;
 mod declarative_part;
 mod design_unit;
+mod disconnection;
 mod expression;
 mod interface_declaration;
 mod names;
 mod object_declaration;
 mod parser;
+mod psl;
 mod range;
 mod separated_list;
 mod sequential_statement;
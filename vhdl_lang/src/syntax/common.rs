@@ -8,6 +8,7 @@ use super::tokens::{Kind, TokenStream};
 use crate::ast::Ident;
 use crate::data::Diagnostic;
 use crate::data::DiagnosticHandler;
+use crate::data::ErrorCode;
 use crate::data::WithPos;
 use crate::SrcPos;
 
@@ -31,6 +32,8 @@ where
     Ok(optional)
 }
 
+/// Check that the optional identifier after `end` matches the name of the declaration.
+/// Returns the position of a matching identifier which is then a reference to the declaration.
 pub fn check_end_identifier_mismatch<T: std::fmt::Display + std::cmp::PartialEq>(
     ident: &WithPos<T>,
     end_ident: Option<WithPos<T>>,
@@ -40,15 +43,24 @@ pub fn check_end_identifier_mismatch<T: std::fmt::Display + std::cmp::PartialEq>
         if ident.item == end_ident.item {
             return Some(end_ident.pos);
         } else {
-            diagnostics.error(
-                &end_ident.pos,
-                format!("End identifier mismatch, expected {}", ident.item),
+            diagnostics.push(
+                Diagnostic::error(
+                    &end_ident.pos,
+                    format!(
+                        "End identifier '{}' does not match '{}'",
+                        end_ident.item, ident.item
+                    ),
+                )
+                .related(&ident.pos, format!("'{}' declared here", ident.item))
+                .with_code(ErrorCode::EndIdentifierMismatch),
             );
         }
     }
     None
 }
 
+/// Check that the optional label after `end` matches the label of the statement.
+/// Returns the position of a matching label which is then a reference to the statement.
 pub fn check_label_identifier_mismatch(
     label: Option<&Ident>,
     end_ident: Option<Ident>,
@@ -59,19 +71,29 @@ pub fn check_label_identifier_mismatch(
             if ident.item == end_ident.item {
                 return Some(end_ident.pos);
             } else {
-                diagnostics.error(
-                    &end_ident.pos,
-                    format!("End label mismatch, expected {}", ident.item),
+                diagnostics.push(
+                    Diagnostic::error(
+                        &end_ident.pos,
+                        format!(
+                            "End label '{}' does not match '{}'",
+                            end_ident.item, ident.item
+                        ),
+                    )
+                    .related(&ident.pos, format!("Label '{}' declared here", ident.item))
+                    .with_code(ErrorCode::EndIdentifierMismatch),
                 );
             }
         }
     } else if let Some(end_ident) = end_ident {
-        diagnostics.error(
-            &end_ident.pos,
-            format!(
-                "End label '{}' found for unlabeled statement",
-                end_ident.item
-            ),
+        diagnostics.push(
+            Diagnostic::error(
+                &end_ident.pos,
+                format!(
+                    "End label '{}' found for unlabeled statement",
+                    end_ident.item
+                ),
+            )
+            .with_code(ErrorCode::EndIdentifierMismatch),
         );
     }
     None
@@ -12,6 +12,9 @@ use super::expression::{parse_choices, parse_expression};
 use super::interface_declaration::{parse_generic_interface_list, parse_port_interface_list};
 use super::names::parse_name;
 use super::names::{expression_to_ident, parse_association_list, parse_selected_name};
+use super::psl::{
+    is_psl_declaration_start, parse_psl_declaration, parse_psl_directive, psl_directive_kind,
+};
 use super::range::parse_discrete_range;
 use super::sequential_statement::{
     parse_assert_statement, parse_labeled_sequential_statements, parse_selection,
@@ -21,6 +24,7 @@ use super::tokens::{Kind::*, TokenStream};
 use super::waveform::{parse_delay_mechanism, parse_waveform};
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::{Kind, TokenAccess};
 use crate::TokenId;
 use vhdl_lang::TokenSpan;
@@ -183,6 +187,11 @@ pub fn parse_process_statement(
         peek_token!(stream, token,
         All => {
             stream.skip();
+            if stream.standard() < VHDLStandard::V2008 {
+                diagnostics.push(
+                    Diagnostic::error(token, format!("Sensitivity list 'all' requires {}", VHDLStandard::V2008))
+                );
+            }
             stream.expect_kind(RightPar)?;
             Some(SensitivityList::All)
         },
@@ -275,8 +284,7 @@ fn parse_assignment_known_target(
 ) -> ParseResult<ConcurrentStatement> {
     // @TODO postponed
     let postponed = false;
-    // @TODO guarded
-    let guarded = false;
+    let guarded = stream.skip_if_kind(Guarded);
     let delay_mechanism = parse_delay_mechanism(stream)?;
     Ok(ConcurrentStatement::Assignment(
         ConcurrentSignalAssignment {
@@ -311,8 +319,7 @@ fn parse_selected_signal_assignment(
     stream.expect_kind(Select)?;
     let target = parse_target(stream)?;
     stream.expect_kind(LTE)?;
-    // @TODO guarded
-    let guarded = false;
+    let guarded = stream.skip_if_kind(Guarded);
     let delay_mechanism = parse_delay_mechanism(stream)?;
     let rhs = AssignmentRightHand::Selected(parse_selection(stream, expression, parse_waveform)?);
     Ok(ConcurrentSignalAssignment {
@@ -334,6 +341,22 @@ pub fn parse_concurrent_assert_statement(
     })
 }
 
+/// An assertion whose condition is not a VHDL expression is a PSL assert directive,
+/// the error of the alternative that got furthest is reported
+fn parse_assert_or_psl_directive(stream: &TokenStream) -> ParseResult<ConcurrentStatement> {
+    let state = stream.state();
+    let vhdl_err = match parse_concurrent_assert_statement(stream, false) {
+        Ok(assert) => return Ok(ConcurrentStatement::Assert(assert)),
+        Err(err) => err,
+    };
+    stream.set_state(state);
+    match parse_psl_directive(stream, PslDirectiveKind::Assert) {
+        Ok(directive) => Ok(ConcurrentStatement::PslDirective(directive)),
+        Err(psl_err) if psl_err.pos.start() > vhdl_err.pos.start() => Err(psl_err),
+        Err(_) => Err(vhdl_err),
+    }
+}
+
 pub fn parse_map_aspect(
     stream: &TokenStream,
     aspect_kind: Kind,
@@ -586,6 +609,15 @@ pub fn parse_concurrent_statement(
 ) -> ParseResult<ConcurrentStatement> {
     let token = stream.peek_expect()?;
     let start_tok = stream.get_current_token_id();
+    if is_psl_declaration_start(stream) {
+        return Ok(ConcurrentStatement::PslDeclaration(parse_psl_declaration(
+            stream,
+        )?));
+    } else if let Some(kind) = psl_directive_kind(stream) {
+        return Ok(ConcurrentStatement::PslDirective(parse_psl_directive(
+            stream, kind,
+        )?));
+    }
     let statement = {
         try_init_token_kind!(
             token,
@@ -623,7 +655,7 @@ pub fn parse_concurrent_statement(
             For => ConcurrentStatement::ForGenerate(parse_for_generate_statement(stream, label, diagnostics)?),
             If => ConcurrentStatement::IfGenerate(parse_if_generate_statement(stream, label, diagnostics)?),
             Case => ConcurrentStatement::CaseGenerate(parse_case_generate_statement(stream, label, diagnostics)?),
-            Assert => ConcurrentStatement::Assert(parse_concurrent_assert_statement(stream, false)?),
+            Assert => parse_assert_or_psl_directive(stream)?,
             Postponed => {
                 let tok = stream.get_current_token_id();
                 stream.skip();
@@ -679,28 +711,79 @@ pub fn parse_labeled_concurrent_statements(
             End | Elsif | Else | When => {
                 break Ok(statements);
             }
-            _ => match parse_labeled_concurrent_statement(stream, diagnostics) {
-                Ok(stmt) => {
-                    statements.push(stmt);
-                }
-                Err(diagnostic) => {
-                    diagnostics.push(diagnostic);
-                    stream.skip_until(|kind| {
-                        matches!(kind, SemiColon | End | Process | Block | Assert)
-                    })?;
-                    stream.pop_if_kind(SemiColon);
+            _ if stream.next_is_design_unit_start() => {
+                // The end of the design unit is missing, the unit itself cannot be recovered
+                return Err(token.kinds_error(&[End]));
+            }
+            _ => {
+                let is_process = [
+                    &[Process][..],
+                    &[Postponed, Process],
+                    &[Identifier, Colon, Process],
+                    &[Identifier, Colon, Postponed, Process],
+                ]
+                .iter()
+                .any(|kinds| stream.next_kinds_are(kinds));
+
+                match parse_labeled_concurrent_statement(stream, diagnostics) {
+                    Ok(stmt) => {
+                        statements.push(stmt);
+                    }
+                    Err(diagnostic) => {
+                        diagnostics.push(diagnostic);
+                        if !(is_process && skip_past_end_process(stream)) {
+                            stream.skip_until(|kind| {
+                                matches!(kind, SemiColon | End | Process | Block | Assert)
+                            })?;
+                            stream.pop_if_kind(SemiColon);
+                        }
+                        statements.push(LabeledConcurrentStatement {
+                            label: WithDecl::new(None),
+                            statement: WithPos::new(
+                                ConcurrentStatement::Error,
+                                stream.pos_since(token),
+                            ),
+                        });
+                    }
                 }
-            },
+            }
         }
     }
 }
 
+/// Skip the rest of a process statement with a syntax error until after its `end process ... ;`.
+/// Returns false without skipping anything when there is no `end process` before the next
+/// design unit.
+fn skip_past_end_process(stream: &TokenStream) -> bool {
+    let state = stream.state();
+    while stream.peek().is_some() && !stream.next_is_design_unit_start() {
+        let end_length = if stream.next_kinds_are(&[End, Process]) {
+            2
+        } else if stream.next_kinds_are(&[End, Postponed, Process]) {
+            3
+        } else {
+            0
+        };
+        if end_length > 0 {
+            stream.set_state(stream.state() + end_length);
+            stream.pop_optional_ident();
+            stream.pop_if_kind(SemiColon);
+            return true;
+        }
+        stream.skip();
+    }
+    stream.set_state(state);
+    false
+}
+
 pub fn parse_labeled_concurrent_statement(
     stream: &TokenStream,
     diagnostics: &mut dyn DiagnosticHandler,
 ) -> ParseResult<LabeledConcurrentStatement> {
     let start = stream.peek_expect()?;
-    if stream.next_kind_is(Identifier) {
+    // PSL keywords are identifiers that do not start a label or a name
+    let is_psl = is_psl_declaration_start(stream) || psl_directive_kind(stream).is_some();
+    if stream.next_kind_is(Identifier) && !is_psl {
         let name = parse_name(stream)?;
         if stream.skip_if_kind(Colon) {
             let label = Some(to_simple_name(name)?);
@@ -1184,6 +1267,28 @@ end process;",
         );
     }
 
+    #[test]
+    fn process_all_requires_vhdl_2008() {
+        let code = Code::new(
+            "\
+process (all) is
+begin
+end process;",
+        );
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(diagnostics, vec![]);
+
+        let code = code.with_standard(VHDLStandard::V1993);
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("all"),
+                "Sensitivity list 'all' requires VHDL-2008"
+            )]
+        );
+    }
+
     #[test]
     fn test_concurrent_assert() {
         let code = Code::new("assert cond = true;");
@@ -1240,6 +1345,23 @@ end process;",
         );
     }
 
+    #[test]
+    fn test_guarded_concurrent_signal_assignment() {
+        let code = Code::new("foo <= guarded transport bar after 1 ns;");
+        let assign = ConcurrentSignalAssignment {
+            postponed: false,
+            guarded: true,
+            target: code.s1("foo").name().map_into(Target::Name),
+            delay_mechanism: Some(DelayMechanism::Transport),
+            rhs: AssignmentRightHand::Simple(code.s1("bar after 1 ns").waveform()),
+        };
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            stmt.statement,
+            WithPos::new(ConcurrentStatement::Assignment(assign), code.pos())
+        );
+    }
+
     #[test]
     fn test_concurrent_signal_assignment_external_name() {
         let code = Code::new("<< signal dut.foo : std_logic >> <= bar(2 to 3);");
@@ -1888,8 +2010,11 @@ end generate;",
                 Diagnostic::error(
                     code.s1("alt2"),
                     "End label 'alt2' found for unlabeled statement"
-                ),
-                Diagnostic::error(code.s1("alt4"), "End label mismatch, expected alt3")
+                )
+                .with_code(ErrorCode::EndIdentifierMismatch),
+                Diagnostic::error(code.s1("alt4"), "End label 'alt4' does not match 'alt3'")
+                    .related(code.s1("alt3"), "Label 'alt3' declared here")
+                    .with_code(ErrorCode::EndIdentifierMismatch)
             ]
         );
     }
@@ -1997,6 +2122,29 @@ end generate;",
         );
     }
 
+    #[test]
+    fn test_case_generate_end_label_mismatch() {
+        let code = Code::new(
+            "\
+gen: case expr generate
+  when alt1: others =>
+  end alt2;
+end generate gen2;",
+        );
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("alt2"), "End label 'alt2' does not match 'alt1'")
+                    .related(code.s1("alt1"), "Label 'alt1' declared here")
+                    .with_code(ErrorCode::EndIdentifierMismatch),
+                Diagnostic::error(code.s1("gen2"), "End label 'gen2' does not match 'gen'")
+                    .related(code.s1("gen"), "Label 'gen' declared here")
+                    .with_code(ErrorCode::EndIdentifierMismatch)
+            ]
+        );
+    }
+
     #[test]
     fn test_case_alternative_label() {
         let code = Code::new(
@@ -26,7 +26,7 @@ fn parse_entity_aspect(stream: &TokenStream) -> ParseResult<EntityAspect> {
                 if stream.skip_if_kind(LeftPar) {
                     let ident = stream.expect_ident()?;
                     stream.expect_kind(RightPar)?;
-                    Some(ident)
+                    Some(WithRef::new(ident))
                 } else {
                     None
                 }
@@ -152,15 +152,15 @@ fn parse_component_specification_or_name(
                     let ident = to_simple_name(name)?;
                     let component_name = parse_selected_name(stream)?;
                     Ok(ComponentSpecificationOrName::ComponentSpec(ComponentSpecification {
-                        instantiation_list: InstantiationList::Labels(vec![ident]),
+                        instantiation_list: InstantiationList::Labels(vec![WithRef::new(ident)]),
                         component_name,
                     }))
                 }
                 Comma => {
                     stream.skip();
-                    let mut idents = vec![to_simple_name(name)?];
+                    let mut idents = vec![WithRef::new(to_simple_name(name)?)];
                     loop {
-                        idents.push(stream.expect_ident()?);
+                        idents.push(WithRef::new(stream.expect_ident()?));
                         expect_token!(
                             stream,
                             next_token,
@@ -589,9 +589,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             component_name: code.s1("lib.pkg.comp").name()
                         },
                         bind_ind: None,
@@ -638,9 +638,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             component_name: code.s1("lib.pkg.comp").name()
                         },
                         bind_ind: Some(BindingIndication {
@@ -693,9 +693,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             component_name: code.s1("lib.pkg.comp").name()
                         },
                         bind_ind: Some(BindingIndication {
@@ -748,9 +748,9 @@ end configuration cfg;
                     items: vec![
                         ConfigurationItem::Component(ComponentConfiguration {
                             spec: ComponentSpecification {
-                                instantiation_list: InstantiationList::Labels(vec![code
-                                    .s1("inst")
-                                    .ident()]),
+                                instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                    code.s1("inst").ident()
+                                )]),
                                 component_name: code.s1("lib.pkg.comp").name()
                             },
                             bind_ind: None,
@@ -760,9 +760,9 @@ end configuration cfg;
                         ConfigurationItem::Component(ComponentConfiguration {
                             spec: ComponentSpecification {
                                 instantiation_list: InstantiationList::Labels(vec![
-                                    code.s1("inst1").ident(),
-                                    code.s1("inst2").ident(),
-                                    code.s1("inst3").ident()
+                                    WithRef::new(code.s1("inst1").ident()),
+                                    WithRef::new(code.s1("inst2").ident()),
+                                    WithRef::new(code.s1("inst3").ident())
                                 ]),
                                 component_name: code.s1("lib2.pkg.comp").name()
                             },
@@ -811,7 +811,7 @@ end configuration cfg;
             code.with_stream(parse_entity_aspect),
             EntityAspect::Entity(
                 code.s1("lib.foo.name").name(),
-                Some(code.s1("arch").ident())
+                Some(WithRef::new(code.s1("arch").ident()))
             )
         );
     }
@@ -846,7 +846,7 @@ end configuration cfg;
                 bind_ind: BindingIndication {
                     entity_aspect: Some(EntityAspect::Entity(
                         code.s1("work.foo").name(),
-                        Some(code.s1("rtl").ident())
+                        Some(WithRef::new(code.s1("rtl").ident()))
                     )),
                     generic_map: None,
                     port_map: None
@@ -871,7 +871,7 @@ end configuration cfg;
                 bind_ind: BindingIndication {
                     entity_aspect: Some(EntityAspect::Entity(
                         code.s1("work.foo").name(),
-                        Some(code.s1("rtl").ident())
+                        Some(WithRef::new(code.s1("rtl").ident()))
                     )),
                     generic_map: None,
                     port_map: None
@@ -898,7 +898,7 @@ end configuration cfg;
                 bind_ind: BindingIndication {
                     entity_aspect: Some(EntityAspect::Entity(
                         code.s1("work.foo").name(),
-                        Some(code.s1("rtl").ident())
+                        Some(WithRef::new(code.s1("rtl").ident()))
                     )),
                     generic_map: None,
                     port_map: None
@@ -233,8 +233,10 @@ end context ident2;
             diagnostics,
             vec![Diagnostic::error(
                 code.s1("ident2"),
-                "End identifier mismatch, expected ident",
-            )]
+                "End identifier 'ident2' does not match 'ident'",
+            )
+            .related(code.s1("ident"), "'ident' declared here")
+            .with_code(ErrorCode::EndIdentifierMismatch)]
         );
         assert_eq!(
             context,
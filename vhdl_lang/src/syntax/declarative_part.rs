@@ -10,12 +10,14 @@ use super::common::ParseResult;
 use super::component_declaration::parse_component_declaration;
 use super::configuration::parse_configuration_specification;
 use super::context::parse_use_clause;
+use super::disconnection::parse_disconnection_specification;
 use super::names::parse_selected_name;
 use super::object_declaration::{parse_file_declaration, parse_object_declaration};
+use super::psl::{is_psl_declaration_start, parse_psl_declaration};
 use super::subprogram::parse_subprogram;
 use super::tokens::{Kind::*, *};
 use super::type_declaration::parse_type_declaration;
-use crate::ast::{ContextClause, Declaration, PackageInstantiation};
+use crate::ast::{ContextClause, Declaration, ErrorDeclaration, PackageInstantiation};
 use crate::data::DiagnosticHandler;
 use crate::syntax::concurrent_statement::parse_map_aspect;
 
@@ -47,13 +49,15 @@ pub fn is_declarative_part(stream: &TokenStream, begin_is_end: bool) -> ParseRes
 fn check_declarative_part(token: &Token, may_end: bool, may_begin: bool) -> ParseResult<()> {
     match token.kind {
         Use | Type | Subtype | Shared | Constant | Signal | Variable | File | Component
-        | Attribute | Alias | Impure | Pure | Function | Procedure | Package | For => Ok(()),
+        | Attribute | Alias | Impure | Pure | Function | Procedure | Package | For | Disconnect => {
+            Ok(())
+        }
         Begin if may_begin => Ok(()),
         End if may_end => Ok(()),
         _ => {
             let decl_kinds = [
                 Use, Type, Subtype, Shared, Constant, Signal, Variable, File, Component, Attribute,
-                Alias, Impure, Pure, Function, Procedure, Package, For,
+                Alias, Impure, Pure, Function, Procedure, Package, For, Disconnect,
             ];
 
             Err(token.kinds_error(&decl_kinds))
@@ -86,14 +90,34 @@ pub fn parse_declarative_part(
                 | Attribute
                 | Use
                 | Alias
+                | Disconnect
                 | Begin
                 | End
         )
     }
 
+    // The tokens from `start` that were skipped after an error
+    let error_declaration = |start: &Token, start_id: TokenId| {
+        let end_id = if stream.get_current_token_id() > start_id {
+            stream.get_last_token_id()
+        } else {
+            start_id
+        };
+        Declaration::Error(ErrorDeclaration {
+            pos: stream.pos_since(start),
+            span: TokenSpan::new(start_id, end_id),
+        })
+    };
+
     while let Some(token) = stream.peek() {
+        let start_id = stream.get_current_token_id();
         match token.kind {
             Begin | End => break,
+            // Packages may be declared within declarative parts
+            kind if kind != Package && stream.next_is_design_unit_start() => {
+                // The end of the design unit is missing, the unit itself cannot be recovered
+                return Err(token.kinds_error(&[Begin, End]));
+            }
             Type | Subtype | Component | Impure | Pure | Function | Procedure | Package | For => {
                 let decl = match token.kind {
                     Type | Subtype => {
@@ -125,21 +149,41 @@ pub fn parse_declarative_part(
                     Ok(ref mut decls) => declarations.append(decls),
                     Err(err) => {
                         diagnostics.push(err);
+                        declarations.push(error_declaration(token, start_id));
                         continue;
                     }
                 }
             }
 
-            Use | Alias => {
+            Use | Alias | Disconnect => {
                 let decl: ParseResult<Declaration> = match token.kind {
                     Use => parse_use_clause(stream, diagnostics).map(Declaration::Use),
                     Alias => parse_alias_declaration(stream).map(Declaration::Alias),
+                    Disconnect => {
+                        parse_disconnection_specification(stream).map(Declaration::Disconnection)
+                    }
                     _ => unreachable!(),
                 };
                 match decl.or_recover_until(stream, diagnostics, is_recover_token) {
                     Ok(decl) => declarations.push(decl),
                     Err(err) => {
                         diagnostics.push(err);
+                        declarations.push(error_declaration(token, start_id));
+                        continue;
+                    }
+                }
+            }
+
+            _ if is_psl_declaration_start(stream) => {
+                match parse_psl_declaration(stream).or_recover_until(
+                    stream,
+                    diagnostics,
+                    is_recover_token,
+                ) {
+                    Ok(decl) => declarations.push(Declaration::Psl(decl)),
+                    Err(err) => {
+                        diagnostics.push(err);
+                        declarations.push(error_declaration(token, start_id));
                         continue;
                     }
                 }
@@ -148,9 +192,10 @@ pub fn parse_declarative_part(
             _ => {
                 diagnostics.push(token.kinds_error(&[
                     Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For,
-                    File, Shared, Constant, Signal, Variable, Attribute, Use, Alias,
+                    File, Shared, Constant, Signal, Variable, Attribute, Use, Alias, Disconnect,
                 ]));
                 stream.skip_until(is_recover_token)?;
+                declarations.push(error_declaration(token, start_id));
                 continue;
             }
         }
@@ -223,13 +268,20 @@ constant x: natural := 5;
         let (decls, msgs) = code.with_partial_stream_diagnostics(parse_declarative_part);
         assert_eq!(
             decls,
-            Ok(vec![Declaration::Object(ObjectDeclaration {
-                span: code.s1_to_end("constant").token_span(),
-                class: ObjectClass::Constant,
-                ident: code.s1("x").decl_ident(),
-                subtype_indication: code.s1("natural").subtype_indication(),
-                expression: Some(code.s1("5").expr())
-            })])
+            Ok(vec![
+                Declaration::Error(ErrorDeclaration {
+                    span: code.s1("var invalid: broken;").token_span(),
+                    pos: code.s1("var invalid: broken;").pos(),
+                }),
+                Declaration::Object(ObjectDeclaration {
+                    span: code.s1_to_end("constant").token_span(),
+                    class: ObjectClass::Constant,
+                    ident: code.s1("x").decl_ident(),
+                    subtype_indication: code.s1("natural").subtype_indication(),
+                    signal_kind: None,
+                    expression: Some(code.s1("5").expr())
+                })
+            ])
         );
 
         assert_eq!(
@@ -239,7 +291,7 @@ constant x: natural := 5;
                 "Expected 'type', 'subtype', 'component', 'impure', 'pure', \
                  'function', 'procedure', 'package', 'for', 'file', \
                  'shared', 'constant', 'signal', 'variable', 'attribute', \
-                 'use' or 'alias'"
+                 'use', 'alias' or 'disconnect'"
             )]
         );
     }
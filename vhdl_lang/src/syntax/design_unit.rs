@@ -4,7 +4,7 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::tokens::{HasTokenSpan, Kind::*, TokenSpan, TokenStream};
+use super::tokens::{HasTokenSpan, Kind::*, Token, TokenSpan, TokenStream};
 
 use super::common::check_end_identifier_mismatch;
 use super::common::ParseResult;
@@ -163,111 +163,159 @@ fn context_item_message(context_item: &ContextItem, message: impl AsRef<str>) ->
     format!("{} {}", prefix, message.as_ref())
 }
 
-pub fn parse_design_file(
+/// Parse a library clause, use clause, context reference or design unit
+fn parse_design_unit_or_context_item(
     stream: &TokenStream,
+    context_clause: &mut ContextClause,
+    design_units: &mut Vec<(Vec<Token>, AnyDesignUnit)>,
     diagnostics: &mut dyn DiagnosticHandler,
-) -> ParseResult<DesignFile> {
-    let mut context_clause = vec![];
-    let mut design_units = vec![];
+) -> ParseResult<()> {
+    let token = stream.peek_expect()?;
+    try_init_token_kind!(
+        token,
+        Library => {
+            match parse_library_clause(stream, diagnostics) {
+                Ok(library) => {
+                    context_clause.push(ContextItem::Library(library));
+                },
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        },
+        Use => {
+            match parse_use_clause(stream, diagnostics) {
+                Ok(use_clause) => {
+                    context_clause.push(ContextItem::Use(use_clause));
+                },
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        },
+        Context => match parse_context(stream, diagnostics)? {
+            DeclarationOrReference::Declaration(context_decl) => {
+                if !context_clause.is_empty() {
+                    let mut diagnostic = Diagnostic::error(&context_decl.ident, "Context declaration may not be preceeded by a context clause");
+
+                    for context_item in context_clause.iter() {
+                        diagnostic.add_related(context_item.get_pos(stream), context_item_message(context_item, "may not come before context declaration"));
+                    }
 
-    while let Some(token) = stream.peek() {
-        try_init_token_kind!(
-            token,
-            Library => {
-                match parse_library_clause(stream, diagnostics) {
-                    Ok(library) => {
-                        context_clause.push(ContextItem::Library(library));
-                    },
-                    Err(diagnostic) => diagnostics.push(diagnostic),
-                }
-            },
-            Use => {
-                match parse_use_clause(stream, diagnostics) {
-                    Ok(use_clause) => {
-                        context_clause.push(ContextItem::Use(use_clause));
-                    },
-                    Err(diagnostic) => diagnostics.push(diagnostic),
+                    diagnostics.push(diagnostic);
+                    context_clause.clear();
                 }
-            },
-            Context => match parse_context(stream, diagnostics) {
-                Ok(DeclarationOrReference::Declaration(context_decl)) => {
-                    if !context_clause.is_empty() {
-                        let mut diagnostic = Diagnostic::error(&context_decl.ident, "Context declaration may not be preceeded by a context clause");
-
-                        for context_item in context_clause.iter() {
-                            diagnostic.add_related(context_item.get_pos(stream), context_item_message(context_item, "may not come before context declaration"));
-                        }
-
-                        diagnostics.push(diagnostic);
-                        context_clause.clear();
-                    }
 
-                    let tokens = stream.slice_tokens();
+                let tokens = stream.slice_tokens();
 
-                    design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Context(context_decl))));
-                }
-                Ok(DeclarationOrReference::Reference(context_ref)) => {
-                    context_clause.push(ContextItem::Context(context_ref));
-                }
-                Err(diagnostic) => diagnostics.push(diagnostic),
-            },
-            Entity => match parse_entity_declaration(stream, diagnostics) {
-                Ok(mut entity) => {
-                    let tokens = stream.slice_tokens();
-                    entity.context_clause = take_context_clause(&mut context_clause);
-                    design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(entity))));
-                }
-                Err(diagnostic) => diagnostics.push(diagnostic),
-            },
+                design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Context(context_decl))));
+            }
+            DeclarationOrReference::Reference(context_ref) => {
+                context_clause.push(ContextItem::Context(context_ref));
+            }
+        },
+        Entity => {
+            let mut entity = parse_entity_declaration(stream, diagnostics)?;
+            let tokens = stream.slice_tokens();
+            entity.context_clause = take_context_clause(context_clause);
+            design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(entity))));
+        },
+
+        Architecture => {
+            let mut architecture = parse_architecture_body(stream, diagnostics)?;
+            let tokens = stream.slice_tokens();
+            architecture.context_clause = take_context_clause(context_clause);
+            design_units.push((tokens, AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture))));
+        },
+
+        Configuration => {
+            let mut configuration = parse_configuration_declaration(stream, diagnostics)?;
+            let tokens = stream.slice_tokens();
+            configuration.context_clause = take_context_clause(context_clause);
+            design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(configuration))));
+        },
+        Package => {
+            if stream.next_kinds_are(&[Package, Body]) {
+                let mut package_body = parse_package_body(stream, diagnostics)?;
+                let tokens = stream.slice_tokens();
+                package_body.context_clause = take_context_clause(context_clause);
+                design_units.push((tokens, AnyDesignUnit::Secondary(AnySecondaryUnit::PackageBody(package_body))));
+            } else if stream.next_kinds_are(&[Package, Identifier, Is, New]) {
+                let mut inst = parse_package_instantiation(stream, diagnostics)?;
+                let tokens = stream.slice_tokens();
+                inst.context_clause = take_context_clause(context_clause);
+                design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::PackageInstance(inst))));
+            } else {
+                let mut package = parse_package_declaration(stream, diagnostics)?;
+                let tokens = stream.slice_tokens();
+                package.context_clause = take_context_clause(context_clause);
+                design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package))));
+            }
+        }
+    );
+    Ok(())
+}
 
-            Architecture => match parse_architecture_body(stream, diagnostics) {
-                Ok(mut architecture) => {
-                    let tokens = stream.slice_tokens();
-                    architecture.context_clause = take_context_clause(&mut context_clause);
-                    design_units.push((tokens, AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture))));
-                }
-                Err(diagnostic) => diagnostics.push(diagnostic),
-            },
+/// Skip tokens after a parse error until the start of the next design unit or context clause.
+/// A unit is assumed to start with a keyword that begins a line or follows the `;` of an
+/// `end ... ;`, other occurrences of the keywords may be part of declarations or statements.
+fn skip_to_next_design_unit(stream: &TokenStream, start: usize) {
+    let mut in_end = false;
+    let mut after_end = false;
+    while let Some(token) = stream.peek() {
+        // Always make progress when the error is at the first token
+        if stream.state() != start {
+            let follows_end = after_end
+                && matches!(
+                    token.kind,
+                    Library | Context | Entity | Architecture | Package | Configuration
+                );
+            if follows_end || stream.next_is_design_unit_start() {
+                return;
+            }
+        }
 
-            Configuration => match parse_configuration_declaration(stream, diagnostics) {
-                Ok(mut configuration) => {
-                    let tokens = stream.slice_tokens();
-                    configuration.context_clause = take_context_clause(&mut context_clause);
-                    design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(configuration))));
-                }
-                Err(diagnostic) => diagnostics.push(diagnostic),
-            },
-            Package => {
-                if stream.next_kinds_are(&[Package, Body]) {
-                    match parse_package_body(stream, diagnostics) {
-                        Ok(mut package_body) => {
-                            let tokens = stream.slice_tokens();
-                            package_body.context_clause = take_context_clause(&mut context_clause);
-                            design_units.push((tokens, AnyDesignUnit::Secondary(AnySecondaryUnit::PackageBody(package_body))));
-                        }
-                        Err(diagnostic) => diagnostics.push(diagnostic),
-                    };
-                } else if stream.next_kinds_are(&[Package, Identifier, Is, New]) {
-                    match parse_package_instantiation(stream, diagnostics) {
-                        Ok(mut inst) => {
-                            let tokens = stream.slice_tokens();
-                            inst.context_clause = take_context_clause(&mut context_clause);
-                            design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::PackageInstance(inst))));
-                        },
-                        Err(diagnostic) => diagnostics.push(diagnostic),
-                    }
-                } else {
-                    match parse_package_declaration(stream, diagnostics) {
-                        Ok(mut package) => {
-                            let tokens = stream.slice_tokens();
-                            package.context_clause = take_context_clause(&mut context_clause);
-                            design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package))));
-                        }
-                        Err(diagnostic) => diagnostics.push(diagnostic),
-                    };
-                }
+        match token.kind {
+            End => in_end = true,
+            SemiColon => {
+                after_end = in_end;
+                in_end = false;
             }
-        );
+            _ => after_end = false,
+        }
+        stream.skip();
+    }
+}
+
+/// Same as [`parse_design_file_lenient`] with the signature of the other parse functions
+#[cfg(test)]
+pub fn parse_design_file(
+    stream: &TokenStream,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> ParseResult<DesignFile> {
+    Ok(parse_design_file_lenient(stream, diagnostics))
+}
+
+/// Parse a design file without ever failing.
+/// Statements and declarations that cannot be parsed are kept as error nodes and
+/// design units that cannot be parsed are skipped, all syntax errors are reported as diagnostics.
+pub fn parse_design_file_lenient(
+    stream: &TokenStream,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> DesignFile {
+    let mut context_clause = vec![];
+    let mut design_units = vec![];
+
+    while stream.peek().is_some() {
+        let start = stream.state();
+        if let Err(diagnostic) = parse_design_unit_or_context_item(
+            stream,
+            &mut context_clause,
+            &mut design_units,
+            diagnostics,
+        ) {
+            // Report a single error for the unit and continue with the units that follow it
+            diagnostics.push(diagnostic);
+            skip_to_next_design_unit(stream, start);
+            context_clause.clear();
+            stream.slice_tokens();
+        }
     }
 
     for context_item in context_clause {
@@ -277,7 +325,11 @@ pub fn parse_design_file(
         ));
     }
 
-    Ok(DesignFile { design_units })
+    DesignFile {
+        design_units,
+        standard: stream.standard(),
+        source_revision: 0,
+    }
 }
 
 #[cfg(test)]
@@ -289,6 +341,7 @@ mod tests {
     use crate::data::Diagnostic;
     use crate::syntax::test::{check_diagnostics, check_no_diagnostics, Code};
     use crate::syntax::{HasTokenSpan, TokenAccess};
+    use crate::VHDLStandard;
 
     fn parse_str(code: &str) -> (Code, DesignFile, Vec<Diagnostic>) {
         let code = Code::new(code);
@@ -812,7 +865,9 @@ end entity;
                         statements: vec![],
                         end_ident_pos: None,
                     }))
-                )]
+                )],
+                standard: VHDLStandard::default(),
+                source_revision: 0,
             }
         );
     }
@@ -847,7 +902,9 @@ context lib.ctx;
         assert_eq!(
             design_file,
             DesignFile {
-                design_units: vec![]
+                design_units: vec![],
+                standard: VHDLStandard::default(),
+                source_revision: 0,
             }
         );
     }
@@ -936,4 +993,276 @@ end entity y;
         assert_eq!(tok.kind, Context);
         assert_eq!(tok.pos, code.s1("context").pos());
     }
+
+    fn unit_names(design_file: &DesignFile) -> Vec<String> {
+        design_file
+            .design_units
+            .iter()
+            .map(|(_, unit)| match unit {
+                AnyDesignUnit::Primary(unit) => unit.name().to_string(),
+                AnyDesignUnit::Secondary(unit) => unit.name().to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recover_from_broken_design_unit() {
+        let code = Code::new(
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+begin
+  inst : entity work.foo;
+  process
+  begin
+    wait;
+end architecture;
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+package pkg is
+end package;
+",
+        );
+        let (design_file, diagnostics) = code.with_stream_diagnostics(parse_design_file);
+        check_diagnostics(
+            diagnostics,
+            vec![
+                Diagnostic::error(
+                    code.s1("end architecture").s1("architecture"),
+                    "Expected 'process'",
+                ),
+                Diagnostic::error(code.s1("library"), "Expected 'end'"),
+            ],
+        );
+        assert_eq!(unit_names(&design_file), vec!["ent", "pkg"]);
+
+        let (tokens, unit) = &design_file.design_units[1];
+        let AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package)) = unit else {
+            panic!("Expected package");
+        };
+        assert_eq!(package.context_clause.len(), 2);
+        let library = package.context_clause[0].expect_library_clause();
+        let tok = tokens.get_token(library.get_start_token());
+        assert_eq!(tok.kind, Library);
+        assert_eq!(tok.pos, code.s1("library").pos());
+    }
+
+    #[test]
+    fn recover_from_tokens_between_design_units() {
+        let code = Code::new(
+            "
+entity ent1 is
+end entity;
+
+end begin ;
+
+entity ent2 is
+end entity;
+",
+        );
+        let (design_file, diagnostics) = code.with_stream_diagnostics(parse_design_file);
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s("end", 2),
+                "Expected 'library', 'use', 'context', 'entity', 'architecture', 'configuration' or 'package'",
+            )],
+        );
+        assert_eq!(unit_names(&design_file), vec!["ent1", "ent2"]);
+    }
+
+    #[test]
+    fn recover_from_broken_unit_followed_by_unit_on_same_line() {
+        let code = Code::new(
+            "
+package pkg1 is
+end; end package; package pkg2 is
+end package;
+",
+        );
+        let (design_file, diagnostics) = code.with_stream_diagnostics(parse_design_file);
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s("end", 2),
+                "Expected 'library', 'use', 'context', 'entity', 'architecture', 'configuration' or 'package'",
+            )],
+        );
+        assert_eq!(unit_names(&design_file), vec!["pkg1", "pkg2"]);
+    }
+
+    #[test]
+    fn broken_process_becomes_error_node() {
+        let code = Code::new(
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  first : process
+  begin
+    wait;
+  end process;
+
+  broken : process (clk begin
+    wait;
+  end process broken;
+
+  third : process
+  begin
+    wait;
+  end process;
+end architecture;
+",
+        );
+        let (design_file, diagnostics) =
+            code.with_partial_stream_diagnostics(parse_design_file_lenient);
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+
+        let (_, unit) = &design_file.design_units[1];
+        let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture)) = unit else {
+            panic!("Expected architecture");
+        };
+        let statements: Vec<_> = architecture
+            .statements
+            .iter()
+            .map(|statement| {
+                (
+                    statement.label.tree.as_ref().map(|label| label.to_string()),
+                    matches!(statement.statement.item, ConcurrentStatement::Process(_)),
+                    statement.statement.pos.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            statements,
+            vec![
+                (
+                    Some("first".to_owned()),
+                    true,
+                    code.s1("process\n  begin\n    wait;\n  end process;").pos()
+                ),
+                (
+                    None,
+                    false,
+                    code.s1("broken : process (clk begin\n    wait;\n  end process broken;")
+                        .pos()
+                ),
+                (
+                    Some("third".to_owned()),
+                    true,
+                    code.s("process\n  begin\n    wait;\n  end process;", 2)
+                        .pos()
+                ),
+            ]
+        );
+        assert_eq!(
+            architecture.statements[1].statement.item,
+            ConcurrentStatement::Error
+        );
+    }
+
+    #[test]
+    fn broken_sequential_statement_becomes_error_node() {
+        let code = Code::new(
+            "
+architecture a of ent is
+begin
+  process
+  begin
+    x := ;
+    wait;
+  end process;
+end architecture;
+",
+        );
+        let (design_file, diagnostics) =
+            code.with_partial_stream_diagnostics(parse_design_file_lenient);
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        let (_, unit) = &design_file.design_units[0];
+        let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture)) = unit else {
+            panic!("Expected architecture");
+        };
+        let ConcurrentStatement::Process(ref process) = architecture.statements[0].statement.item
+        else {
+            panic!("Expected process");
+        };
+        let kinds: Vec<_> = process
+            .statements
+            .iter()
+            .map(|statement| &statement.statement.item)
+            .collect();
+        assert_eq!(kinds.len(), 2);
+        assert_eq!(kinds[0], &SequentialStatement::Error);
+        assert!(matches!(kinds[1], SequentialStatement::Wait(_)));
+    }
+
+    #[test]
+    fn mismatching_end_identifiers_refer_to_declaration() {
+        let code = Code::new(
+            "
+entity ent is
+end entity wrong_ent;
+
+architecture rtl of ent is
+  function fun return natural is
+  begin
+    return 0;
+  end function wrong_fun;
+begin
+  proc: process
+  begin
+    lp: loop
+    end loop wrong_lp;
+  end process wrong_proc;
+end architecture wrong_rtl;
+",
+        );
+        let (design_file, diagnostics) = code.with_stream_diagnostics(parse_design_file);
+
+        let ident = |decl: Code, wrong: &str| {
+            let name = decl.pos().text();
+            Diagnostic::error(
+                code.s1(wrong),
+                format!("End identifier '{wrong}' does not match '{name}'"),
+            )
+            .related(decl, format!("'{name}' declared here"))
+            .with_code(ErrorCode::EndIdentifierMismatch)
+        };
+        let label = |name: &str, wrong: &str| {
+            Diagnostic::error(
+                code.s1(wrong),
+                format!("End label '{wrong}' does not match '{name}'"),
+            )
+            .related(
+                code.s1(&format!("{name}:")).s1(name),
+                format!("Label '{name}' declared here"),
+            )
+            .with_code(ErrorCode::EndIdentifierMismatch)
+        };
+        check_diagnostics(
+            diagnostics,
+            vec![
+                ident(code.sa("entity ", "ent"), "wrong_ent"),
+                ident(code.sa("function ", "fun"), "wrong_fun"),
+                label("lp", "wrong_lp"),
+                label("proc", "wrong_proc"),
+                ident(code.sa("architecture ", "rtl"), "wrong_rtl"),
+            ],
+        );
+
+        // A mismatching end identifier is not a reference to the declaration
+        let AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(ref entity)) =
+            design_file.design_units[0].1
+        else {
+            panic!("Expected entity");
+        };
+        assert_eq!(entity.end_ident_pos, None);
+    }
 }
@@ -13,6 +13,7 @@ use super::tokens::{Kind::*, *};
 /// LRM 6.5 Interface declarations
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 
 fn parse_optional_mode(stream: &TokenStream) -> ParseResult<Option<WithPos<Mode>>> {
     let token = stream.peek_expect()?;
@@ -91,6 +92,7 @@ fn parse_interface_file_declaration(
 
 fn parse_interface_object_declaration(
     stream: &TokenStream,
+    diagnostics: &mut dyn DiagnosticHandler,
     list_type: InterfaceType,
 ) -> ParseResult<Vec<InterfaceDeclaration>> {
     let explicit_object_class = parse_optional_object_class(stream, list_type)?;
@@ -122,6 +124,26 @@ fn parse_interface_object_declaration(
     let subtype = parse_subtype_indication(stream)?;
     let expr = parse_optional_assignment(stream)?;
 
+    // Signal parameters and variable parameters of mode out or inout may have a default
+    // value since VHDL-2019
+    if let Some(ref expr) = expr {
+        if list_type == InterfaceType::Parameter
+            && stream.standard() < VHDLStandard::V2019
+            && (object_class == ObjectClass::Signal
+                || object_class == ObjectClass::Variable && mode != Mode::In)
+        {
+            diagnostics.push(Diagnostic::error(
+                &expr.pos,
+                format!(
+                    "Default value of a {} parameter of mode {} requires {}",
+                    object_class,
+                    mode,
+                    VHDLStandard::V2019
+                ),
+            ));
+        }
+    }
+
     // @TODO maybe move this to a semantic check?
     for ident in idents.iter() {
         if object_class == ObjectClass::Constant && mode != Mode::In {
@@ -231,7 +253,7 @@ fn parse_interface_declaration(
     peek_token!(
         stream, token,
         Signal | Constant | Variable | Identifier => {
-            parse_interface_object_declaration(stream, list_type)
+            parse_interface_object_declaration(stream, diagnostics, list_type)
         },
         File => parse_interface_file_declaration(stream),
         Type => {
@@ -258,10 +280,11 @@ fn parse_semicolon_separator(stream: &TokenStream) -> ParseResult<()> {
         stream, token,
         SemiColon => {
             stream.skip();
-            if stream.next_kind_is(RightPar) {
+            // A trailing semicolon is allowed since VHDL-2019
+            if stream.next_kind_is(RightPar) && stream.standard() < VHDLStandard::V2019 {
                 return Err(Diagnostic::error(&token.pos,
-                        format!("Last interface element may not end with {}",
-                        kinds_str(&[SemiColon]))));
+                        format!("Last interface element may not end with {} before {}",
+                        kinds_str(&[SemiColon]), VHDLStandard::V2019)));
             }
         },
         RightPar => {}
@@ -676,11 +699,72 @@ bar : natural)",
             diagnostics,
             vec![Diagnostic::error(
                 code.s(";", 2),
-                "Last interface element may not end with ';'"
+                "Last interface element may not end with ';' before VHDL-2019"
             )]
         );
     }
 
+    #[test]
+    fn trailing_semicolon_is_allowed_in_vhdl_2019() {
+        let code = Code::new(
+            "\
+(a : bit;
+ b : natural;
+)",
+        );
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_parameter_interface_list);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s(";", 2),
+                "Last interface element may not end with ';' before VHDL-2019"
+            )]
+        );
+
+        let code = code.with_standard(VHDLStandard::V2019);
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_parameter_interface_list),
+            vec![
+                code.s1("a : bit").parameter(),
+                code.s1("b : natural").parameter()
+            ]
+        );
+    }
+
+    #[test]
+    fn signal_and_variable_parameter_defaults_are_allowed_in_vhdl_2019() {
+        let code = Code::new(
+            "\
+(signal a : bit := '0';
+ variable b : out natural := 0;
+ variable c : natural := 0)",
+        );
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_parameter_interface_list);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(
+                    code.s1("'0'"),
+                    "Default value of a signal parameter of mode in requires VHDL-2019"
+                ),
+                Diagnostic::error(
+                    code.sa("out natural := ", "0"),
+                    "Default value of a variable parameter of mode out requires VHDL-2019"
+                ),
+            ]
+        );
+
+        let code = code.with_standard(VHDLStandard::V2019);
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_parameter_interface_list),
+            vec![
+                code.s1("signal a : bit := '0'").parameter(),
+                code.s1("variable b : out natural := 0").parameter(),
+                code.s1("variable c : natural := 0").parameter(),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_port_interface_list() {
         let code = Code::new(
@@ -938,6 +1022,7 @@ function foo() return bit;
                     header: None,
                     param_tok: None,
                     parameter_list: vec![],
+                    return_identifier: None,
                     return_type: code.s1("bit").type_mark()
                 })
             }
@@ -395,6 +395,14 @@ fn _parse_name(stream: &TokenStream) -> ParseResult<WithPos<Name>> {
                     }
                 }
             }
+            // PSL repetitions such as a[*2] are not signatures
+            LeftSquare
+                if [Times, Plus, EQ, Implies]
+                    .iter()
+                    .any(|kind| stream.nth_kind_is(1, *kind)) =>
+            {
+                break;
+            }
             LeftSquare => {
                 let state = stream.state();
                 let signature = Some(parse_signature(stream)?);
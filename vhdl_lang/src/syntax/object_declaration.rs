@@ -23,6 +23,17 @@ pub fn parse_optional_assignment(stream: &TokenStream) -> ParseResult<Option<Wit
     }
 }
 
+/// LRM 6.4.2.3 The kind of a guarded signal
+fn parse_optional_signal_kind(stream: &TokenStream) -> Option<SignalKind> {
+    if stream.skip_if_kind(Register) {
+        Some(SignalKind::Register)
+    } else if stream.skip_if_kind(Bus) {
+        Some(SignalKind::Bus)
+    } else {
+        None
+    }
+}
+
 fn parse_object_declaration_kind(
     stream: &TokenStream,
     class: ObjectClass,
@@ -47,6 +58,11 @@ fn parse_object_declaration_kind(
     let idents = parse_identifier_list(stream)?;
     stream.expect_kind(Colon)?;
     let subtype = parse_subtype_indication(stream)?;
+    let signal_kind = if class == ObjectClass::Signal {
+        parse_optional_signal_kind(stream)
+    } else {
+        None
+    };
     let opt_expression = parse_optional_assignment(stream)?;
     let end_token = stream.expect_kind(SemiColon)?;
 
@@ -57,6 +73,7 @@ fn parse_object_declaration_kind(
             class,
             ident: ident.into(),
             subtype_indication: subtype.clone(),
+            signal_kind,
             expression: opt_expression.clone(),
         })
         .collect())
@@ -140,6 +157,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -155,6 +173,7 @@ mod tests {
                 class: ObjectClass::Signal,
                 ident: code.s1("foo").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -170,6 +189,7 @@ mod tests {
                 class: ObjectClass::Variable,
                 ident: code.s1("foo").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -185,6 +205,7 @@ mod tests {
                 class: ObjectClass::SharedVariable,
                 ident: code.s1("foo").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -257,6 +278,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("0").expr())
             }]
         );
@@ -272,6 +294,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("0").expr()),
             },
             ObjectDeclaration {
@@ -279,6 +302,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("bar").decl_ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("0").expr()),
             },
         ];
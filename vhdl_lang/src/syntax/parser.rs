@@ -4,21 +4,50 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::design_unit::parse_design_file;
-use super::tokens::{Symbols, TokenStream, Tokenizer};
+use super::design_unit::parse_design_file_lenient;
+use super::tokens::{Symbols, TokenStream, Tokenizer, ToolIdentifiers};
 use crate::ast::DesignFile;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use std::io;
 use std::sync::Arc;
 
 #[derive(Default)]
 pub struct VHDLParser {
     pub symbols: Arc<Symbols>,
+    pub standard: VHDLStandard,
+    /// The identifiers of conditional analysis directives
+    pub tool_identifiers: Arc<ToolIdentifiers>,
 }
 
 pub type ParserResult = Result<(Source, DesignFile), io::Error>;
 
+/// Marks the diagnostics of the parser that do not have a more specific code as syntax errors
+struct SyntaxDiagnostics<'a> {
+    inner: &'a mut dyn DiagnosticHandler,
+}
+
+impl DiagnosticHandler for SyntaxDiagnostics<'_> {
+    fn push(&mut self, diagnostic: Diagnostic) {
+        if diagnostic.code.is_some() {
+            self.inner.push(diagnostic)
+        } else {
+            self.inner
+                .push(diagnostic.with_code(ErrorCode::SyntaxError))
+        }
+    }
+}
+
 impl VHDLParser {
+    /// Create a parser for another VHDL revision that shares the symbol table
+    pub fn with_standard(&self, standard: VHDLStandard) -> VHDLParser {
+        VHDLParser {
+            symbols: self.symbols.clone(),
+            standard,
+            tool_identifiers: self.tool_identifiers.clone(),
+        }
+    }
+
     pub fn symbol(&self, name: &Latin1String) -> Symbol {
         self.symbols.symtab().insert(name)
     }
@@ -28,16 +57,29 @@ impl VHDLParser {
         source: &Source,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> DesignFile {
-        let contents = source.contents();
+        self.parse_design_file_lenient(source, diagnostics)
+    }
+
+    /// Parse a source into a possibly partial design file that contains error nodes
+    /// for the statements and declarations with syntax errors. Never fails,
+    /// the syntax errors are only reported as diagnostics.
+    pub fn parse_design_file_lenient(
+        &self,
+        source: &Source,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> DesignFile {
+        let (contents, revision) = source.contents_with_revision();
         let tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
-        let stream = TokenStream::new(tokenizer, diagnostics);
-
-        match parse_design_file(&stream, diagnostics) {
-            Ok(design_file) => design_file,
-            Err(diagnostic) => {
-                diagnostics.push(diagnostic);
-                DesignFile::default()
-            }
+        let mut diagnostics = SyntaxDiagnostics { inner: diagnostics };
+        let stream = TokenStream::new_with_tool_identifiers(
+            tokenizer,
+            self.standard,
+            &self.tool_identifiers,
+            &mut diagnostics,
+        );
+        DesignFile {
+            source_revision: revision,
+            ..parse_design_file_lenient(&stream, &mut diagnostics)
         }
     }
 
@@ -46,7 +88,16 @@ impl VHDLParser {
         file_name: &Path,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> ParserResult {
-        let source = Source::from_latin1_file(file_name)?;
+        self.parse_design_file_with_encoding(file_name, Encoding::Auto, diagnostics)
+    }
+
+    pub fn parse_design_file_with_encoding(
+        &self,
+        file_name: &Path,
+        encoding: Encoding,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> ParserResult {
+        let source = Source::from_file_with_encoding(file_name, encoding)?;
         let design_file = self.parse_design_source(&source, diagnostics);
         Ok((source, design_file))
     }
@@ -0,0 +1,685 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! LRM 11.1 PSL directives and declarations in the VHDL flavor of IEEE 1850.
+//! They are written inline (VHDL-2008) or are embedded in `-- psl` comments.
+//! The PSL keywords that are not reserved words of VHDL are recognized by their context.
+
+use super::common::{parse_optional, ParseResult};
+use super::expression::parse_expression;
+use super::tokens::{
+    kind_str, Kind, Kind::*, Token, TokenAccess, TokenId, TokenSpan, TokenStream, Value,
+};
+use crate::ast::*;
+use crate::data::*;
+use crate::standard::VHDLStandard;
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    match token.value {
+        Value::Identifier(ref sym) if token.kind == Identifier => {
+            sym.name_utf8().eq_ignore_ascii_case(keyword)
+        }
+        _ => false,
+    }
+}
+
+fn next_is_keyword(stream: &TokenStream, keyword: &str) -> bool {
+    stream
+        .peek()
+        .is_some_and(|token| is_keyword(token, keyword))
+}
+
+/// The kind of the PSL directive that starts at the next token, other than `assert`
+/// which is ambiguous with the concurrent assertion statement
+pub fn psl_directive_kind(stream: &TokenStream) -> Option<PslDirectiveKind> {
+    let token = stream.peek()?;
+    let kind = if is_keyword(token, "assume") {
+        PslDirectiveKind::Assume
+    } else if is_keyword(token, "restrict") {
+        PslDirectiveKind::Restrict
+    } else if is_keyword(token, "cover") {
+        PslDirectiveKind::Cover
+    } else {
+        return None;
+    };
+
+    // The directive keywords are reserved since VHDL-2008,
+    // before that they may be the names of procedures or signals
+    let is_name = [SemiColon, Colon, Dot, Tick, LTE, ColonEq]
+        .iter()
+        .any(|kind| stream.nth_kind_is(1, *kind))
+        || (stream.nth_kind_is(1, LeftPar) && stream.standard() < VHDLStandard::V2008);
+    if is_name {
+        None
+    } else {
+        Some(kind)
+    }
+}
+
+/// True when the next tokens start a default clock, property or sequence declaration
+pub fn is_psl_declaration_start(stream: &TokenStream) -> bool {
+    let Some(token) = stream.peek() else {
+        return false;
+    };
+    if token.kind == Default {
+        stream
+            .nth(1)
+            .is_some_and(|token| is_keyword(token, "clock"))
+    } else {
+        (is_keyword(token, "property") || is_keyword(token, "sequence"))
+            && stream.next_kinds_are(&[Identifier, Identifier, Is])
+    }
+}
+
+/// Parse a directive whose keyword is the next token
+pub fn parse_psl_directive(
+    stream: &TokenStream,
+    kind: PslDirectiveKind,
+) -> ParseResult<PslDirective> {
+    stream.skip();
+    let property = parse_property(stream)?;
+    let report = parse_optional(stream, Report, parse_expression)?;
+    let severity = parse_optional(stream, Severity, parse_expression)?;
+    stream.expect_kind(SemiColon)?;
+
+    Ok(PslDirective {
+        kind,
+        property,
+        report,
+        severity,
+    })
+}
+
+/// Parse a declaration that starts according to [is_psl_declaration_start]
+pub fn parse_psl_declaration(stream: &TokenStream) -> ParseResult<PslDeclaration> {
+    let start_token = stream.get_current_token_id();
+    if stream.skip_if_kind(Default) {
+        // The clock keyword
+        stream.skip();
+        stream.expect_kind(Is)?;
+        let clock = parse_expression(stream)?;
+        let end_token = stream.expect_kind(SemiColon)?;
+        return Ok(PslDeclaration::DefaultClock(PslDefaultClock {
+            clock,
+            span: TokenSpan::new(start_token, end_token),
+        }));
+    }
+
+    let is_property = next_is_keyword(stream, "property");
+    stream.skip();
+    let ident = WithDecl::new(stream.expect_ident()?);
+    stream.expect_kind(Is)?;
+    if is_property {
+        let property = parse_property(stream)?;
+        let end_token = stream.expect_kind(SemiColon)?;
+        Ok(PslDeclaration::Property(PslPropertyDeclaration {
+            ident,
+            property,
+            span: TokenSpan::new(start_token, end_token),
+        }))
+    } else {
+        let sequence = parse_sequence(stream)?;
+        let end_token = stream.expect_kind(SemiColon)?;
+        Ok(PslDeclaration::Sequence(PslSequenceDeclaration {
+            ident,
+            sequence,
+            span: TokenSpan::new(start_token, end_token),
+        }))
+    }
+}
+
+fn binary_property(
+    op: PslBinaryOperator,
+    lhs: WithPos<PslProperty>,
+    rhs: WithPos<PslProperty>,
+) -> WithPos<PslProperty> {
+    let pos = lhs.pos.combine(&rhs);
+    WithPos::new(PslProperty::Binary(op, Box::new(lhs), Box::new(rhs)), pos)
+}
+
+fn unary_property(
+    op: PslUnaryOperator,
+    token: &Token,
+    operand: WithPos<PslProperty>,
+) -> WithPos<PslProperty> {
+    let pos = token.pos.combine(&operand);
+    WithPos::new(PslProperty::Unary(op, Box::new(operand)), pos)
+}
+
+/// IEEE 1850 6.2 Properties, operators are listed in order of increasing precedence
+///   1. invariance: always | never
+///   2. boolean implication: -> | <->
+///   3. suffix implication: |-> | |=>
+///   4. bounding: until[!] | before[!]
+///   5. occurrence: next[!] | eventually!
+///   6. termination: abort | async_abort | sync_abort
+///   7. clocking: @
+pub fn parse_property(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let token = stream.peek_expect()?;
+    let invariance = if is_keyword(token, "always") {
+        Some(PslUnaryOperator::Always)
+    } else if is_keyword(token, "never") {
+        Some(PslUnaryOperator::Never)
+    } else {
+        None
+    };
+    if let Some(op) = invariance {
+        stream.skip();
+        let operand = parse_property(stream)?;
+        return Ok(unary_property(op, token, operand));
+    }
+
+    let lhs = parse_suffix_implication(stream)?;
+    let op = match stream.peek_kind() {
+        Some(Implies) => PslBinaryOperator::Implies,
+        Some(Iff) => PslBinaryOperator::Iff,
+        _ => return Ok(lhs),
+    };
+    stream.skip();
+    let rhs = parse_property(stream)?;
+    Ok(binary_property(op, lhs, rhs))
+}
+
+fn parse_suffix_implication(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let lhs = parse_bounding(stream)?;
+    let overlapping = match stream.peek_kind() {
+        Some(OverlapImplies) => true,
+        Some(NonOverlapImplies) => false,
+        _ => return Ok(lhs),
+    };
+    let token = stream.peek_expect()?;
+    stream.skip();
+
+    let WithPos { item, pos } = lhs;
+    let sequence = match item {
+        PslProperty::Boolean(expr) => WithPos::new(PslSequence::Boolean(expr), pos),
+        PslProperty::Sequence {
+            sequence,
+            strong: false,
+        } => sequence,
+        _ => {
+            return Err(Diagnostic::error(
+                pos,
+                format!("Expected a sequence before '{}'", kind_str(token.kind)),
+            ))
+        }
+    };
+    let property = parse_property(stream)?;
+    let pos = sequence.pos.combine(&property);
+    Ok(WithPos::new(
+        PslProperty::SuffixImplication {
+            sequence,
+            overlapping,
+            property: Box::new(property),
+        },
+        pos,
+    ))
+}
+
+fn parse_bounding(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let mut lhs = parse_occurrence(stream)?;
+    while let Some(token) = stream.peek() {
+        let is_until = if token.kind == Until {
+            true
+        } else if is_keyword(token, "before") {
+            false
+        } else {
+            break;
+        };
+        stream.skip();
+        let strong = stream.skip_if_kind(Exclamation);
+        let op = if is_until {
+            PslBinaryOperator::Until { strong }
+        } else {
+            PslBinaryOperator::Before { strong }
+        };
+        let rhs = parse_occurrence(stream)?;
+        lhs = binary_property(op, lhs, rhs);
+    }
+    Ok(lhs)
+}
+
+fn parse_occurrence(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let token = stream.peek_expect()?;
+    let op = if is_keyword(token, "always") || is_keyword(token, "never") {
+        return parse_property(stream);
+    } else if token.kind == Next {
+        stream.skip();
+        PslUnaryOperator::Next {
+            strong: stream.skip_if_kind(Exclamation),
+        }
+    } else if is_keyword(token, "eventually") {
+        stream.skip();
+        stream.expect_kind(Exclamation)?;
+        PslUnaryOperator::Eventually
+    } else {
+        return parse_termination(stream);
+    };
+    let operand = parse_occurrence(stream)?;
+    Ok(unary_property(op, token, operand))
+}
+
+fn parse_termination(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let mut property = parse_clocked(stream)?;
+    while stream.peek().is_some_and(|token| {
+        ["abort", "async_abort", "sync_abort"]
+            .iter()
+            .any(|keyword| is_keyword(token, keyword))
+    }) {
+        stream.skip();
+        let condition = parse_expression(stream)?;
+        let pos = property.pos.combine(&condition);
+        property = WithPos::new(PslProperty::Abort(Box::new(property), condition), pos);
+    }
+    Ok(property)
+}
+
+fn parse_clocked(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let property = parse_property_primary(stream)?;
+    if stream.skip_if_kind(CommAt) {
+        let clock = parse_expression(stream)?;
+        let pos = property.pos.combine(&clock);
+        Ok(WithPos::new(
+            PslProperty::Clocked(Box::new(property), clock),
+            pos,
+        ))
+    } else {
+        Ok(property)
+    }
+}
+
+fn parse_property_primary(stream: &TokenStream) -> ParseResult<WithPos<PslProperty>> {
+    let token = stream.peek_expect()?;
+    if token.kind == LeftBrace {
+        let sequence = parse_sequence(stream)?;
+        let (strong, pos) = if let Some(id) = stream.pop_if_kind(Exclamation) {
+            (true, sequence.pos.combine(stream.get_pos(id)))
+        } else {
+            (false, sequence.pos.clone())
+        };
+        return Ok(WithPos::new(
+            PslProperty::Sequence { sequence, strong },
+            pos,
+        ));
+    }
+
+    match parse_expression(stream) {
+        Ok(expr) => {
+            if stream.next_kind_is(LeftSquare) {
+                let boolean = boolean_sequence(expr);
+                let sequence = parse_repetitions(stream, boolean)?;
+                let pos = sequence.pos.clone();
+                Ok(WithPos::new(
+                    PslProperty::Sequence {
+                        sequence,
+                        strong: false,
+                    },
+                    pos,
+                ))
+            } else {
+                let pos = expr.pos.clone();
+                Ok(WithPos::new(PslProperty::Boolean(expr), pos))
+            }
+        }
+        // A parenthesized property that is not a VHDL expression, (always a)
+        Err(_) if token.kind == LeftPar => {
+            stream.skip();
+            let property = parse_property(stream)?;
+            let right_par = stream.expect_kind(RightPar)?;
+            Ok(WithPos::new(
+                property.item,
+                token.pos.combine(stream.get_pos(right_par)),
+            ))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn boolean_sequence(expr: WithPos<Expression>) -> WithPos<PslSequence> {
+    let pos = expr.pos.clone();
+    WithPos::new(PslSequence::Boolean(expr), pos)
+}
+
+/// IEEE 1850 6.1.2 A braced SERE or a possibly repeated boolean
+pub fn parse_sequence(stream: &TokenStream) -> ParseResult<WithPos<PslSequence>> {
+    let Some(start) = stream.pop_if_kind(LeftBrace) else {
+        return parse_sere_item(stream);
+    };
+    let sere = parse_sere(stream, 0)?;
+    let end = stream.expect_kind(RightBrace)?;
+    let pos = stream.get_pos(start).combine(stream.get_pos(end));
+    parse_repetitions(stream, WithPos::new(sere.item, pos))
+}
+
+/// SERE operators in order of increasing precedence
+fn sere_operator(kind: Kind) -> Option<(PslSequenceOperator, usize)> {
+    match kind {
+        SemiColon => Some((PslSequenceOperator::Concatenation, 1)),
+        Colon => Some((PslSequenceOperator::Fusion, 2)),
+        Bar => Some((PslSequenceOperator::Or, 3)),
+        _ => None,
+    }
+}
+
+fn parse_sere(stream: &TokenStream, min_precedence: usize) -> ParseResult<WithPos<PslSequence>> {
+    let mut lhs = parse_sere_item(stream)?;
+    while let Some((op, precedence)) = stream.peek_kind().and_then(sere_operator) {
+        if precedence <= min_precedence {
+            break;
+        }
+        stream.skip();
+        let rhs = parse_sere(stream, precedence)?;
+        let pos = lhs.pos.combine(&rhs);
+        lhs = WithPos::new(PslSequence::Binary(op, Box::new(lhs), Box::new(rhs)), pos);
+    }
+    Ok(lhs)
+}
+
+fn parse_sere_item(stream: &TokenStream) -> ParseResult<WithPos<PslSequence>> {
+    let token = stream.peek_expect()?;
+    let item = match token.kind {
+        LeftBrace => return parse_sequence(stream),
+        LeftSquare => {
+            let (repetition, end) = parse_repetition(stream)?;
+            let pos = token.pos.combine(stream.get_pos(end));
+            WithPos::new(PslSequence::Repeated(None, repetition), pos)
+        }
+        _ => boolean_sequence(parse_expression(stream)?),
+    };
+    parse_repetitions(stream, item)
+}
+
+fn parse_repetitions(
+    stream: &TokenStream,
+    mut sequence: WithPos<PslSequence>,
+) -> ParseResult<WithPos<PslSequence>> {
+    while stream.next_kind_is(LeftSquare) {
+        let (repetition, end) = parse_repetition(stream)?;
+        let pos = sequence.pos.combine(stream.get_pos(end));
+        sequence = WithPos::new(
+            PslSequence::Repeated(Some(Box::new(sequence)), repetition),
+            pos,
+        );
+    }
+    Ok(sequence)
+}
+
+/// `[*count]`, `[+]`, `[=count]` or `[->count]`, returns the closing bracket
+fn parse_repetition(stream: &TokenStream) -> ParseResult<(PslRepetition, TokenId)> {
+    stream.expect_kind(LeftSquare)?;
+    let kind = expect_token!(
+        stream,
+        token,
+        Times => PslRepetitionKind::Consecutive,
+        Plus => PslRepetitionKind::OneOrMore,
+        EQ => PslRepetitionKind::NonConsecutive,
+        Implies => PslRepetitionKind::Goto
+    );
+    let count = if kind == PslRepetitionKind::OneOrMore || stream.next_kind_is(RightSquare) {
+        None
+    } else {
+        Some(parse_count(stream)?)
+    };
+    let end = stream.expect_kind(RightSquare)?;
+    Ok((PslRepetition { kind, count }, end))
+}
+
+fn parse_count(stream: &TokenStream) -> ParseResult<PslCount> {
+    let low = parse_expression(stream)?;
+    let high = if stream.skip_if_kind(To) || stream.skip_if_kind(Colon) {
+        if next_is_keyword(stream, "inf") {
+            stream.skip();
+            Some(PslBound::Inf)
+        } else {
+            Some(PslBound::Expression(parse_expression(stream)?))
+        }
+    } else {
+        None
+    };
+    Ok(PslCount { low, high })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::concurrent_statement::parse_labeled_concurrent_statement;
+    use crate::syntax::test::Code;
+
+    fn boolean(code: &Code) -> WithPos<PslProperty> {
+        WithPos::new(PslProperty::Boolean(code.expr()), code.pos())
+    }
+
+    fn directive(code: &Code) -> PslDirective {
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        match stmt.statement.item {
+            ConcurrentStatement::PslDirective(directive) => directive,
+            other => panic!("Expected a PSL directive, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn assert_directive_with_implication() {
+        let code = Code::new("assert always a -> next b report \"msg\";");
+        let next = WithPos::new(
+            PslProperty::Unary(
+                PslUnaryOperator::Next { strong: false },
+                Box::new(boolean(&code.s1("b"))),
+            ),
+            code.s1("next b").pos(),
+        );
+        let implication = WithPos::new(
+            PslProperty::Binary(
+                PslBinaryOperator::Implies,
+                Box::new(boolean(&code.s1("a ->").s1("a"))),
+                Box::new(next),
+            ),
+            code.s1("a -> next b").pos(),
+        );
+        assert_eq!(
+            directive(&code),
+            PslDirective {
+                kind: PslDirectiveKind::Assert,
+                property: WithPos::new(
+                    PslProperty::Unary(PslUnaryOperator::Always, Box::new(implication)),
+                    code.s1("always a -> next b").pos()
+                ),
+                report: Some(code.s1("\"msg\"").expr()),
+                severity: None,
+            }
+        );
+    }
+
+    #[test]
+    fn vhdl_assertion_is_not_a_directive() {
+        let code = Code::new("assert a = b;");
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert!(matches!(
+            stmt.statement.item,
+            ConcurrentStatement::Assert(_)
+        ));
+    }
+
+    #[test]
+    fn cover_sequence_with_repetitions() {
+        let code = Code::new("cover {req; ack[*2 to inf]; [*]; done};");
+        let ack = WithPos::new(
+            PslSequence::Repeated(
+                Some(Box::new(boolean_sequence(code.s1("ack").expr()))),
+                PslRepetition {
+                    kind: PslRepetitionKind::Consecutive,
+                    count: Some(PslCount {
+                        low: code.s1("2").expr(),
+                        high: Some(PslBound::Inf),
+                    }),
+                },
+            ),
+            code.s1("ack[*2 to inf]").pos(),
+        );
+        let any = WithPos::new(
+            PslSequence::Repeated(
+                None,
+                PslRepetition {
+                    kind: PslRepetitionKind::Consecutive,
+                    count: None,
+                },
+            ),
+            code.s1("[*]").pos(),
+        );
+        let concatenation = |lhs, rhs, pos: &str| {
+            WithPos::new(
+                PslSequence::Binary(
+                    PslSequenceOperator::Concatenation,
+                    Box::new(lhs),
+                    Box::new(rhs),
+                ),
+                code.s1(pos).pos(),
+            )
+        };
+        let sequence = concatenation(
+            boolean_sequence(code.s1("req").expr()),
+            ack,
+            "req; ack[*2 to inf]",
+        );
+        let sequence = concatenation(sequence, any, "req; ack[*2 to inf]; [*]");
+        let sequence = concatenation(
+            sequence,
+            boolean_sequence(code.s1("done").expr()),
+            "req; ack[*2 to inf]; [*]; done",
+        );
+        let sequence = WithPos::new(
+            sequence.item,
+            code.s1("{req; ack[*2 to inf]; [*]; done}").pos(),
+        );
+        assert_eq!(
+            directive(&code),
+            PslDirective {
+                kind: PslDirectiveKind::Cover,
+                property: WithPos::new(
+                    PslProperty::Sequence {
+                        sequence,
+                        strong: false
+                    },
+                    code.s1("{req; ack[*2 to inf]; [*]; done}").pos()
+                ),
+                report: None,
+                severity: None,
+            }
+        );
+    }
+
+    #[test]
+    fn suffix_implication_and_clocking() {
+        let code = Code::new("assume always {a; b} |=> (c until! d) @ rising_edge(clk);");
+        let until = WithPos::new(
+            PslProperty::Binary(
+                PslBinaryOperator::Until { strong: true },
+                Box::new(boolean(&code.s1("c"))),
+                Box::new(boolean(&code.s1("d"))),
+            ),
+            code.s1("(c until! d)").pos(),
+        );
+        let clocked = WithPos::new(
+            PslProperty::Clocked(Box::new(until), code.s1("rising_edge(clk)").expr()),
+            code.s1("(c until! d) @ rising_edge(clk)").pos(),
+        );
+        let sequence = WithPos::new(
+            PslSequence::Binary(
+                PslSequenceOperator::Concatenation,
+                Box::new(boolean_sequence(code.s1("{a").s1("a").expr())),
+                Box::new(boolean_sequence(code.s1("b").expr())),
+            ),
+            code.s1("{a; b}").pos(),
+        );
+        let implication = WithPos::new(
+            PslProperty::SuffixImplication {
+                sequence,
+                overlapping: false,
+                property: Box::new(clocked),
+            },
+            code.s1("{a; b} |=> (c until! d) @ rising_edge(clk)").pos(),
+        );
+        assert_eq!(
+            directive(&code).property,
+            WithPos::new(
+                PslProperty::Unary(PslUnaryOperator::Always, Box::new(implication)),
+                code.s1("always {a; b} |=> (c until! d) @ rising_edge(clk)")
+                    .pos()
+            )
+        );
+    }
+
+    #[test]
+    fn directive_keywords_may_be_names_before_vhdl_2008() {
+        let code = Code::new("cover(a);").with_standard(VHDLStandard::V1993);
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert!(matches!(
+            stmt.statement.item,
+            ConcurrentStatement::ProcedureCall(_)
+        ));
+    }
+
+    #[test]
+    fn default_clock_declaration() {
+        let code = Code::new("default clock is rising_edge(clk);");
+        assert_eq!(
+            code.with_stream(parse_psl_declaration),
+            PslDeclaration::DefaultClock(PslDefaultClock {
+                clock: code.s1("rising_edge(clk)").expr(),
+                span: code.token_span(),
+            })
+        );
+    }
+
+    #[test]
+    fn property_and_sequence_declarations() {
+        let code = Code::new("property p is always a;");
+        assert_eq!(
+            code.with_stream(parse_psl_declaration),
+            PslDeclaration::Property(PslPropertyDeclaration {
+                ident: code.s1("p is").s1("p").decl_ident(),
+                property: WithPos::new(
+                    PslProperty::Unary(
+                        PslUnaryOperator::Always,
+                        Box::new(boolean(&code.s1("a;").s1("a")))
+                    ),
+                    code.s1("always a").pos()
+                ),
+                span: code.token_span(),
+            })
+        );
+
+        let code = Code::new("sequence s is {a[->]};");
+        assert_eq!(
+            code.with_stream(parse_psl_declaration),
+            PslDeclaration::Sequence(PslSequenceDeclaration {
+                ident: code.s1("s is").s1("s").decl_ident(),
+                sequence: WithPos::new(
+                    PslSequence::Repeated(
+                        Some(Box::new(boolean_sequence(code.s1("a").expr()))),
+                        PslRepetition {
+                            kind: PslRepetitionKind::Goto,
+                            count: None,
+                        }
+                    ),
+                    code.s1("{a[->]}").pos()
+                ),
+                span: code.token_span(),
+            })
+        );
+    }
+
+    #[test]
+    fn directive_in_psl_comment() {
+        let code = Code::new(
+            "\
+-- psl assert always
+--   a -> b;",
+        );
+        let directive = directive(&code);
+        assert_eq!(directive.kind, PslDirectiveKind::Assert);
+        assert_eq!(directive.property.pos, code.s1("always\n--   a -> b").pos());
+    }
+}
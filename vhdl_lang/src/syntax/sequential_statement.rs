@@ -76,11 +76,24 @@ pub fn parse_labeled_sequential_statements(
             End | Else | Elsif | When => {
                 break Ok(statements);
             }
+            _ if stream.next_is_design_unit_start() => {
+                // The end of the design unit is missing, the unit itself cannot be recovered
+                return Err(token.kinds_error(&[End]));
+            }
             _ => match parse_sequential_statement(stream, diagnostics) {
                 Ok(stmt) => statements.push(stmt),
                 Err(diag) => {
                     diagnostics.push(diag);
-                    let _ = stream.skip_until(|kind| matches!(kind, End | Else | Elsif | When));
+                    let _ = stream
+                        .skip_until(|kind| matches!(kind, SemiColon | End | Else | Elsif | When));
+                    stream.pop_if_kind(SemiColon);
+                    statements.push(LabeledSequentialStatement {
+                        label: WithDecl::new(None),
+                        statement: WithPos::new(
+                            SequentialStatement::Error,
+                            stream.pos_since(token),
+                        ),
+                    });
                 }
             },
         }
@@ -595,6 +608,7 @@ pub fn parse_sequential_statement(
 mod tests {
     use super::*;
     use crate::ast::{DelayMechanism, Ident};
+    use crate::standard::VHDLStandard;
     use pretty_assertions::assert_eq;
 
     use crate::syntax::test::Code;
@@ -1403,6 +1417,32 @@ end if;",
         );
     }
 
+    #[test]
+    fn matching_operators_in_conditions_require_vhdl_2008() {
+        let code = Code::new(
+            "\
+if a ?= b then
+   exit when ?? c;
+elsif a ?/= b then
+end if;",
+        );
+        let (statement, diagnostics) = code.with_stream_diagnostics(parse_sequential_statement);
+        assert_eq!(diagnostics, vec![]);
+
+        let code = code.with_standard(VHDLStandard::V1993);
+        assert_eq!(
+            code.with_stream_diagnostics(parse_sequential_statement),
+            (
+                statement,
+                vec![
+                    Diagnostic::error(code.s1("?="), "Operator '?=' requires VHDL-2008"),
+                    Diagnostic::error(code.s1("??"), "Operator '??' requires VHDL-2008"),
+                    Diagnostic::error(code.s1("?/="), "Operator '?/=' requires VHDL-2008"),
+                ]
+            )
+        );
+    }
+
     #[test]
     fn parse_labeled_if_else_statement() {
         let (code, statement) = parse(
@@ -1610,6 +1650,33 @@ end loop lbl;",
         );
     }
 
+    #[test]
+    fn parse_loop_statement_with_mismatched_end_label() {
+        let code = Code::new(
+            "\
+lbl: loop
+  stmt1;
+end loop other;",
+        );
+        let (statement, diagnostics) = code.with_stream_diagnostics(parse_sequential_statement);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("other"), "End label 'other' does not match 'lbl'")
+                    .related(code.s1("lbl"), "Label 'lbl' declared here")
+                    .with_code(ErrorCode::EndIdentifierMismatch)
+            ]
+        );
+        assert_eq!(
+            statement.statement.item,
+            SequentialStatement::Loop(LoopStatement {
+                iteration_scheme: None,
+                statements: vec![code.s1("stmt1;").sequential_statement()],
+                end_label_pos: None,
+            })
+        );
+    }
+
     #[test]
     fn parse_while_loop_statement() {
         let (code, statement) = parse(
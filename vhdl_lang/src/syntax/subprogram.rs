@@ -12,6 +12,7 @@ use super::sequential_statement::parse_labeled_sequential_statements;
 use super::tokens::{kinds_error, Kind::*, TokenAccess, TokenId, TokenSpan, TokenStream};
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::concurrent_statement::parse_map_aspect;
 use crate::syntax::interface_declaration::parse_generic_interface_list;
 use crate::syntax::names::parse_name;
@@ -180,6 +181,20 @@ pub fn parse_subprogram_specification(
 
     if is_function {
         stream.expect_kind(Return)?;
+        // The return subtype may be named since VHDL-2019
+        let return_identifier = if stream.next_kinds_are(&[Identifier, Of]) {
+            let ident = stream.expect_ident()?;
+            stream.skip();
+            if stream.standard() < VHDLStandard::V2019 {
+                diagnostics.push(Diagnostic::error(
+                    &ident,
+                    format!("Return identifier requires {}", VHDLStandard::V2019),
+                ));
+            }
+            Some(WithDecl::new(ident))
+        } else {
+            None
+        };
         let return_type = parse_type_mark(stream)?;
         Ok(SubprogramSpecification::Function(FunctionSpecification {
             pure: is_pure,
@@ -187,6 +202,7 @@ pub fn parse_subprogram_specification(
             designator: designator.into(),
             header,
             parameter_list,
+            return_identifier,
             return_type,
         }))
     } else {
@@ -344,12 +360,52 @@ function foo return lib.foo.natural;
                     header: None,
                     param_tok: None,
                     parameter_list: Vec::new(),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
         );
     }
 
+    #[test]
+    pub fn parses_function_specification_with_return_identifier() {
+        let code = Code::new(
+            "\
+function foo return r of bit_vector;
+",
+        );
+        let (decl, diagnostics) = code.with_stream_diagnostics(parse_subprogram_declaration);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.sa("return ", "r"),
+                "Return identifier requires VHDL-2019"
+            )]
+        );
+
+        let code = code.with_standard(VHDLStandard::V2019);
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_subprogram_declaration),
+            decl
+        );
+        assert_eq!(
+            decl.specification,
+            SubprogramSpecification::Function(FunctionSpecification {
+                pure: true,
+                designator: code
+                    .s1("foo")
+                    .ident()
+                    .map_into(SubprogramDesignator::Identifier)
+                    .into(),
+                header: None,
+                param_tok: None,
+                parameter_list: Vec::new(),
+                return_identifier: Some(code.sa("return ", "r").decl_ident()),
+                return_type: code.s1("bit_vector").type_mark()
+            })
+        );
+    }
+
     #[test]
     pub fn parses_function_specification_operator() {
         let code = Code::new(
@@ -371,6 +427,7 @@ function \"+\" return lib.foo.natural;
                     header: None,
                     param_tok: None,
                     parameter_list: Vec::new(),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
@@ -398,6 +455,7 @@ impure function foo return lib.foo.natural;
                     header: None,
                     param_tok: None,
                     parameter_list: Vec::new(),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
@@ -424,6 +482,7 @@ pure function foo return lib.foo.natural;
                     header: None,
                     param_tok: None,
                     parameter_list: Vec::new(),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
@@ -475,6 +534,7 @@ function foo(foo : natural) return lib.foo.natural;
                     header: None,
                     param_tok: None,
                     parameter_list: vec![code.s1("foo : natural").parameter()],
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
@@ -502,6 +562,7 @@ function foo parameter (foo : natural) return lib.foo.natural;
                     header: None,
                     param_tok: Some(code.s1("parameter").token()),
                     parameter_list: vec![code.s1("foo : natural").parameter()],
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
@@ -533,6 +594,7 @@ function foo generic (abc_def: natural) parameter (foo : natural) return lib.foo
                     }),
                     param_tok: Some(code.s1("parameter").token()),
                     parameter_list: vec![code.s1("foo : natural").parameter()],
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark()
                 })
             }
@@ -15,6 +15,7 @@ use super::declarative_part::parse_declarative_part;
 use super::design_unit::{
     parse_architecture_body, parse_design_file, parse_entity_declaration, parse_package_declaration,
 };
+use super::disconnection::parse_disconnection_specification;
 use super::expression::{parse_aggregate, parse_choices, parse_expression};
 use super::interface_declaration::{parse_generic, parse_parameter, parse_port};
 use super::names::{parse_association_list, parse_designator, parse_name, parse_type_mark};
@@ -33,6 +34,7 @@ use crate::ast;
 use crate::ast::*;
 use crate::data::Range;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::concurrent_statement::parse_map_aspect;
 use crate::syntax::context::{parse_context, DeclarationOrReference};
 use crate::syntax::names::parse_association_element;
@@ -89,6 +91,7 @@ impl CodeBuilder {
         let code = Code {
             symbols: self.symbols.clone(),
             pos,
+            standard: VHDLStandard::default(),
         };
 
         // Ensure symbol table is populated
@@ -125,6 +128,7 @@ impl<T> SeparatedList<T> {
 pub struct Code {
     pub symbols: Arc<Symbols>,
     pos: SrcPos,
+    standard: VHDLStandard,
 }
 
 impl Code {
@@ -136,16 +140,24 @@ impl Code {
         CodeBuilder::new().code_with_file_name(file_name, code)
     }
 
+    /// Parse the code according to another VHDL revision than the default
+    pub fn with_standard(mut self, standard: VHDLStandard) -> Code {
+        self.standard = standard;
+        self
+    }
+
     fn in_range(&self, range: Range) -> Code {
         Code {
             symbols: self.symbols.clone(),
             pos: SrcPos::new(self.pos.source.clone(), range),
+            standard: self.standard,
         }
     }
 
     fn pos_to_end(&self, start: Position) -> Code {
         Code {
             symbols: self.symbols.clone(),
+            standard: self.standard,
             pos: SrcPos::new(
                 self.pos.source.clone(),
                 Range {
@@ -159,6 +171,7 @@ impl Code {
     fn start_to_pos(&self, end: Position) -> Code {
         Code {
             symbols: self.symbols.clone(),
+            standard: self.standard,
             pos: SrcPos::new(
                 self.pos.source.clone(),
                 Range {
@@ -317,7 +330,7 @@ impl Code {
         let contents = source.contents();
         let reader = ContentReader::new(&contents);
         let tokenizer = Tokenizer::new(&self.symbols, &source, reader);
-        let stream = TokenStream::new(tokenizer, &mut NoDiagnostics);
+        let stream = TokenStream::new_with_standard(tokenizer, self.standard, &mut NoDiagnostics);
         forward(&stream, self.pos.start());
         stream.peek().expect("No token found");
         stream.get_current_token_id()
@@ -336,7 +349,8 @@ impl Code {
         let contents = source.contents();
         let reader = ContentReader::new(&contents);
         let tokenizer = Tokenizer::new(&self.symbols, &source, reader);
-        let mut stream = TokenStream::new(tokenizer, &mut NoDiagnostics);
+        let mut stream =
+            TokenStream::new_with_standard(tokenizer, self.standard, &mut NoDiagnostics);
         forward(&stream, self.pos.start());
         parse_fun(&mut stream)
     }
@@ -355,17 +369,41 @@ impl Code {
     }
 
     pub fn with_partial_stream<F, R>(&self, parse_fun: F) -> R
+    where
+        F: FnOnce(&TokenStream) -> R,
+    {
+        self.with_tokenized_stream(&mut NoDiagnostics, parse_fun)
+    }
+
+    /// Parse with a stream whose tokenization diagnostics are pushed to `diagnostics`
+    fn with_tokenized_stream<F, R>(
+        &self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        parse_fun: F,
+    ) -> R
     where
         F: FnOnce(&TokenStream) -> R,
     {
         let contents = self.pos.source.contents();
         let reader = ContentReader::new(&contents);
         let tokenizer = Tokenizer::new(&self.symbols, &self.pos.source, reader);
-        let mut stream = TokenStream::new(tokenizer, &mut NoDiagnostics);
+        let mut stream = TokenStream::new_with_standard(tokenizer, self.standard, diagnostics);
         parse_fun(&mut stream)
     }
 
     pub fn with_stream<F, R>(&self, parse_fun: F) -> R
+    where
+        R: Debug,
+        F: FnOnce(&TokenStream) -> ParseResult<R>,
+    {
+        self.with_tokenized_stream_eof(&mut NoDiagnostics, parse_fun)
+    }
+
+    fn with_tokenized_stream_eof<F, R>(
+        &self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        parse_fun: F,
+    ) -> R
     where
         R: Debug,
         F: FnOnce(&TokenStream) -> ParseResult<R>,
@@ -388,7 +426,7 @@ impl Code {
             }
         };
 
-        self.with_partial_stream(parse_fun_eof)
+        self.with_tokenized_stream(diagnostics, parse_fun_eof)
     }
 
     pub fn with_stream_err<F, R>(&self, parse_fun: F) -> Diagnostic
@@ -432,7 +470,11 @@ impl Code {
         F: FnOnce(&TokenStream, &mut dyn DiagnosticHandler) -> ParseResult<R>,
     {
         let mut diagnostics = Vec::new();
-        let result = self.with_stream(|stream: &TokenStream| parse_fun(stream, &mut diagnostics));
+        let mut parse_diagnostics = Vec::new();
+        let result = self.with_tokenized_stream_eof(&mut diagnostics, |stream: &TokenStream| {
+            parse_fun(stream, &mut parse_diagnostics)
+        });
+        diagnostics.append(&mut parse_diagnostics);
         (result, diagnostics)
     }
 
@@ -527,6 +569,10 @@ impl Code {
         self.parse_ok(parse_file_declaration).remove(0)
     }
 
+    pub fn disconnection_specification(&self) -> DisconnectionSpecification {
+        self.parse_ok(parse_disconnection_specification)
+    }
+
     pub fn alias_decl(&self) -> AliasDeclaration {
         self.parse_ok(parse_alias_declaration)
     }
@@ -764,6 +810,17 @@ pub fn without_releated(diagnostics: &[Diagnostic]) -> Vec<Diagnostic> {
 
 /// Check diagnostics are equal without considering order
 pub fn check_diagnostics(got: Vec<Diagnostic>, expected: Vec<Diagnostic>) {
+    // Only compare error codes when the test expects them
+    let got = if expected.iter().any(|diagnostic| diagnostic.code.is_some()) {
+        got
+    } else {
+        got.into_iter()
+            .map(|diagnostic| Diagnostic {
+                code: None,
+                ..diagnostic
+            })
+            .collect()
+    };
     let mut expected = diagnostics_to_map(expected);
     let mut got = diagnostics_to_map(got);
 
@@ -899,6 +956,16 @@ mod tests {
         )
     }
 
+    #[test]
+    #[should_panic]
+    fn check_diagnostics_not_ok_severity_mismatch() {
+        let code = Code::new("foo bar");
+        check_diagnostics(
+            vec![Diagnostic::warning(code.s1("foo"), "hello")],
+            vec![Diagnostic::error(code.s1("foo"), "hello")],
+        )
+    }
+
     #[test]
     #[should_panic]
     fn check_diagnostics_not_ok_count_mismatch() {
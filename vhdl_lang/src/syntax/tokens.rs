@@ -4,9 +4,14 @@
 //
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
+mod conditional;
+mod doc_comment;
+mod pragma;
 #[macro_use]
 mod tokenizer;
 mod tokenstream;
 
+pub use conditional::ToolIdentifiers;
+pub use doc_comment::doc_comment;
 pub use tokenizer::*;
 pub use tokenstream::*;
@@ -0,0 +1,352 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Conditional analysis of VHDL-2019 tool directives such as
+//! ```vhdl
+//! `if TOOL_TYPE = "SIMULATION" `then
+//!   ...
+//! `else
+//!   ...
+//! `end if
+//! ```
+//! The tokens of the branches that are not selected are not parsed,
+//! their regions are tracked so that they can be shown as inactive.
+
+use super::tokenizer::Kind::*;
+use super::tokenizer::{Kind, Token, Value};
+use crate::data::{Diagnostic, DiagnosticHandler, ErrorCode, Position, Range, SrcPos};
+use crate::standard::VHDLStandard;
+use fnv::FnvHashMap;
+
+/// The values of the identifiers that conditional analysis directives compare with strings.
+/// Identifiers are case insensitive like all VHDL identifiers.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct ToolIdentifiers {
+    values: FnvHashMap<String, String>,
+}
+
+impl ToolIdentifiers {
+    pub fn new<'a>(values: impl IntoIterator<Item = (&'a String, &'a String)>) -> ToolIdentifiers {
+        ToolIdentifiers {
+            values: values
+                .into_iter()
+                .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// The value of an identifier that is predefined by the standard unless set explicitly
+    fn predefined(name: &str, standard: VHDLStandard) -> Option<String> {
+        match name.to_ascii_lowercase().as_str() {
+            "vhdl_version" => Some(
+                match standard {
+                    VHDLStandard::V1993 => "1993",
+                    VHDLStandard::V2002 => "2002",
+                    VHDLStandard::V2008 => "2008",
+                    VHDLStandard::V2019 => "2019",
+                }
+                .to_owned(),
+            ),
+            "tool_name" => Some("vhdl_ls".to_owned()),
+            _ => None,
+        }
+    }
+}
+
+/// An `if` directive and the `elsif` and `else` directives that follow it
+struct Branch {
+    /// The position of the `if` directive
+    directive: SrcPos,
+    parent_active: bool,
+    /// The tokens of the current branch are parsed
+    active: bool,
+    /// One of the branches has been selected
+    selected: bool,
+    seen_else: bool,
+}
+
+pub(crate) struct ConditionalAnalysis<'a> {
+    identifiers: &'a ToolIdentifiers,
+    standard: VHDLStandard,
+    branches: Vec<Branch>,
+    inactive_start: Option<Position>,
+    inactive_regions: Vec<Range>,
+}
+
+impl<'a> ConditionalAnalysis<'a> {
+    pub fn new(identifiers: &'a ToolIdentifiers, standard: VHDLStandard) -> Self {
+        ConditionalAnalysis {
+            identifiers,
+            standard,
+            branches: Vec::new(),
+            inactive_start: None,
+            inactive_regions: Vec::new(),
+        }
+    }
+
+    /// True if the tokens at the current position are parsed
+    pub fn is_active(&self) -> bool {
+        self.branches.last().is_none_or(|branch| branch.active)
+    }
+
+    /// Update the inactive regions after the directive at `directive` changed the state
+    fn transition(&mut self, was_active: bool, directive: &SrcPos) {
+        match (was_active, self.is_active()) {
+            (true, false) => self.inactive_start = Some(directive.end()),
+            (false, true) => {
+                if let Some(start) = self.inactive_start.take() {
+                    self.inactive_regions
+                        .push(Range::new(start, directive.start()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// An `if` directive with the tokens of the condition until the `then`,
+    /// `None` for a malformed directive that is false
+    pub fn if_directive(
+        &mut self,
+        directive: SrcPos,
+        condition: Option<&[Token]>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let was_active = self.is_active();
+        let active = was_active && self.evaluate(&directive, condition, diagnostics);
+        self.branches.push(Branch {
+            directive: directive.clone(),
+            parent_active: was_active,
+            active,
+            selected: active,
+            seen_else: false,
+        });
+        self.transition(was_active, &directive);
+    }
+
+    pub fn elsif_directive(
+        &mut self,
+        directive: SrcPos,
+        condition: Option<&[Token]>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let was_active = self.is_active();
+        let Some(branch) = self.branches.last() else {
+            diagnostics.error(&directive, "'`elsif' directive without a matching '`if'");
+            return;
+        };
+        if branch.seen_else {
+            diagnostics.error(&directive, "'`elsif' directive after '`else'");
+        }
+        let active = branch.parent_active
+            && !branch.selected
+            && !branch.seen_else
+            && self.evaluate(&directive, condition, diagnostics);
+        if let Some(branch) = self.branches.last_mut() {
+            branch.active = active;
+            branch.selected |= active;
+        }
+        self.transition(was_active, &directive);
+    }
+
+    pub fn else_directive(&mut self, directive: SrcPos, diagnostics: &mut dyn DiagnosticHandler) {
+        let was_active = self.is_active();
+        let Some(branch) = self.branches.last_mut() else {
+            diagnostics.error(&directive, "'`else' directive without a matching '`if'");
+            return;
+        };
+        if branch.seen_else {
+            diagnostics.error(&directive, "'`else' directive after '`else'");
+        }
+        branch.active = branch.parent_active && !branch.selected;
+        branch.selected = true;
+        branch.seen_else = true;
+        self.transition(was_active, &directive);
+    }
+
+    pub fn end_directive(&mut self, directive: SrcPos, diagnostics: &mut dyn DiagnosticHandler) {
+        let was_active = self.is_active();
+        if self.branches.pop().is_none() {
+            diagnostics.error(&directive, "'`end' directive without a matching '`if'");
+            return;
+        }
+        self.transition(was_active, &directive);
+    }
+
+    /// The inactive regions of the source, an `if` directive that is not closed
+    /// makes the rest of the source inactive
+    pub fn finish(mut self, end: Position, diagnostics: &mut dyn DiagnosticHandler) -> Vec<Range> {
+        for branch in self.branches.iter() {
+            diagnostics.error(
+                &branch.directive,
+                "'`if' directive is not closed by an '`end' directive",
+            );
+        }
+        if let Some(start) = self.inactive_start.take() {
+            self.inactive_regions.push(Range::new(start, end));
+        }
+        self.inactive_regions
+    }
+
+    /// Evaluate a condition, a condition with errors is false
+    fn evaluate(
+        &self,
+        directive: &SrcPos,
+        condition: Option<&[Token]>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> bool {
+        let Some(condition) = condition else {
+            return false;
+        };
+        let mut parser = ConditionParser {
+            analysis: self,
+            tokens: condition,
+            idx: 0,
+            end: condition
+                .last()
+                .map_or_else(|| directive.pos_at_end(), |token| token.pos.pos_at_end()),
+            diagnostics,
+        };
+        let result = parser.expression().and_then(|value| match parser.peek() {
+            Some(token) => Err(Diagnostic::error(token, "Expected '`then'")),
+            None => Ok(value),
+        });
+        match result {
+            Ok(value) => value,
+            Err(err) => {
+                parser.diagnostics.push(err);
+                false
+            }
+        }
+    }
+
+    fn value_of(&self, name: &str) -> Option<String> {
+        self.identifiers
+            .get(name)
+            .map(str::to_owned)
+            .or_else(|| ToolIdentifiers::predefined(name, self.standard))
+    }
+}
+
+/// Recursive descent parser and evaluator of the condition of a directive
+/// ```text
+/// condition ::= relation { logical_operator relation }
+/// relation ::= ( condition ) | not relation | identifier relational_operator string_literal
+/// ```
+struct ConditionParser<'a, 'b> {
+    analysis: &'b ConditionalAnalysis<'a>,
+    tokens: &'b [Token],
+    idx: usize,
+    /// The position to report an unexpected end of the condition at
+    end: SrcPos,
+    diagnostics: &'b mut dyn DiagnosticHandler,
+}
+
+impl ConditionParser<'_, '_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx)
+    }
+
+    fn pop(&mut self, expected: &str) -> Result<&Token, Diagnostic> {
+        let token = self
+            .tokens
+            .get(self.idx)
+            .ok_or_else(|| Diagnostic::error(&self.end, format!("Expected {expected}")))?;
+        self.idx += 1;
+        Ok(token)
+    }
+
+    fn expression(&mut self) -> Result<bool, Diagnostic> {
+        let mut value = self.relation()?;
+        let mut operator: Option<Kind> = None;
+        while let Some(token) = self.peek() {
+            let kind = token.kind;
+            if !matches!(kind, And | Or | Xor | Xnor) {
+                break;
+            }
+            if operator.is_some_and(|operator| operator != kind) {
+                return Err(Diagnostic::error(
+                    token,
+                    "Mixed logical operators must be separated by parentheses",
+                ));
+            }
+            operator = Some(kind);
+            self.idx += 1;
+            let right = self.relation()?;
+            value = match kind {
+                And => value && right,
+                Or => value || right,
+                Xor => value != right,
+                _ => value == right,
+            };
+        }
+        Ok(value)
+    }
+
+    fn relation(&mut self) -> Result<bool, Diagnostic> {
+        let token = self.pop("identifier, '(' or 'not'")?;
+        match token.kind {
+            LeftPar => {
+                let value = self.expression()?;
+                let token = self.pop("')'")?;
+                if token.kind != RightPar {
+                    return Err(Diagnostic::error(token, "Expected ')'"));
+                }
+                Ok(value)
+            }
+            Not => Ok(!self.relation()?),
+            Identifier => {
+                let Value::Identifier(ref symbol) = token.value else {
+                    unreachable!();
+                };
+                let name = symbol.name_utf8();
+                let name_pos = token.pos.clone();
+
+                let token = self.pop("relational operator")?;
+                let operator = token.kind;
+                if !matches!(operator, EQ | NE | LT | LTE | GT | GTE) {
+                    return Err(Diagnostic::error(token, "Expected relational operator"));
+                }
+                let token = self.pop("string literal")?;
+                let Value::String(ref string) = token.value else {
+                    return Err(Diagnostic::error(token, "Expected string literal"));
+                };
+                let string = string.to_string();
+
+                let value = match self.analysis.value_of(&name) {
+                    Some(value) => value,
+                    None => {
+                        self.diagnostics.push(
+                            Diagnostic::warning(
+                                name_pos,
+                                format!("Tool identifier '{name}' is not defined, its value is the empty string"),
+                            )
+                            .with_code(ErrorCode::UnknownToolIdentifier),
+                        );
+                        String::new()
+                    }
+                };
+                Ok(match operator {
+                    EQ => value == string,
+                    NE => value != string,
+                    LT => value < string,
+                    LTE => value <= string,
+                    GT => value > string,
+                    _ => value >= string,
+                })
+            }
+            _ => Err(Diagnostic::error(
+                token,
+                "Expected identifier, '(' or 'not'",
+            )),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Documentation comments are not stored in the AST, they are found on demand from the
+//! leading and trailing comments of the tokens around the designator of a declaration.
+
+use super::tokenizer::{Comment, Kind, Kind::*, Token};
+use crate::data::SrcPos;
+
+/// Keywords that may precede the designator within the same declaration such as
+/// `signal a : bit` or `impure function f return bit`
+fn is_declaration_prefix(kind: Kind) -> bool {
+    matches!(
+        kind,
+        Signal
+            | Constant
+            | Variable
+            | Shared
+            | File
+            | Type
+            | Subtype
+            | Component
+            | Alias
+            | Attribute
+            | Entity
+            | Architecture
+            | Package
+            | Body
+            | Configuration
+            | Context
+            | Function
+            | Procedure
+            | Pure
+            | Impure
+    )
+}
+
+/// Lines that only decorate a comment block, such as a row of dashes
+fn is_decoration(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && line.chars().all(|chr| "-=*#~_+/".contains(chr))
+}
+
+/// The comments on the lines immediately above the token, a blank line ends the block
+fn leading_block(token: &Token) -> &[Comment] {
+    let Some(ref comments) = token.comments else {
+        return &[];
+    };
+    let mut line = token.pos.start().line;
+    let mut first = comments.leading.len();
+    for comment in comments.leading.iter().rev() {
+        if comment.range.end.line + 1 < line {
+            break;
+        }
+        line = comment.range.start.line;
+        first -= 1;
+    }
+    &comments.leading[first..]
+}
+
+/// The documentation comment of the declaration whose designator is at `decl_pos`.
+/// It is the block of comments immediately above the declaration followed by a trailing
+/// comment on the same line as the designator.
+pub fn doc_comment(tokens: &[Token], decl_pos: &SrcPos) -> Option<String> {
+    let idx = tokens
+        .binary_search_by(|token| token.pos.start().cmp(&decl_pos.start()))
+        .ok()?;
+
+    // A comment directly above the designator documents only it, as for each
+    // literal of an enumeration type
+    let mut start = idx;
+    if leading_block(&tokens[idx]).is_empty() {
+        // The identifier list of an object declaration such as `a, b : bit`
+        let in_identifier_list = tokens[idx + 1..]
+            .iter()
+            .find(|token| !matches!(token.kind, Comma | Identifier))
+            .is_some_and(|token| token.kind == Colon);
+        while start > 0 {
+            let kind = tokens[start - 1].kind;
+            if !(is_declaration_prefix(kind)
+                || in_identifier_list && matches!(kind, Comma | Identifier))
+            {
+                break;
+            }
+            start -= 1;
+        }
+    }
+
+    let mut comments: Vec<&Comment> = leading_block(&tokens[start]).iter().collect();
+    let line = decl_pos.end().line;
+    comments.extend(
+        tokens[idx..]
+            .iter()
+            .take_while(|token| token.pos.start().line == line)
+            .filter_map(|token| token.comments.as_ref()?.trailing.as_ref())
+            .take(1),
+    );
+
+    let lines: Vec<&str> = comments
+        .iter()
+        .flat_map(|comment| comment.value.lines())
+        .filter(|line| !is_decoration(line))
+        .map(|line| line.trim_end())
+        .collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()?;
+    let text = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim_matches('\n');
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_owned())
+    }
+}
@@ -0,0 +1,152 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+//! Tool pragmas are comments such as `-- synthesis translate_off` that tell synthesis tools
+//! to ignore the code up to the matching `-- synthesis translate_on`. The code in between is
+//! still legal VHDL and is parsed and analyzed, the regions are only tracked so that the
+//! diagnostics within them can be told apart.
+
+use super::tokenizer::Comment;
+use crate::data::{Position, Range};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Pragma {
+    TranslateOff,
+    TranslateOn,
+}
+
+impl Comment {
+    /// The pragma of a single line comment such as `-- pragma synthesis_off`
+    pub fn pragma(&self) -> Option<Pragma> {
+        if self.multi_line {
+            return None;
+        }
+        let words: Vec<String> = self
+            .value
+            .split_whitespace()
+            .map(|word| word.to_ascii_lowercase())
+            .collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match words.as_slice() {
+            ["synthesis" | "pragma" | "synopsys", "translate_off" | "synthesis_off"]
+            | ["vhdl_comp_off"] => Some(Pragma::TranslateOff),
+            ["synthesis" | "pragma" | "synopsys", "translate_on" | "synthesis_on"]
+            | ["vhdl_comp_on"] => Some(Pragma::TranslateOn),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the regions from a `translate_off` up to and including the matching `translate_on`
+#[derive(Default)]
+pub(crate) struct TranslateOffRegions {
+    /// The range of the `translate_off` pragma of the current region
+    open: Option<Range>,
+    regions: Vec<Range>,
+}
+
+impl TranslateOffRegions {
+    pub fn add_comment(&mut self, comment: &Comment) {
+        match comment.pragma() {
+            // A nested translate_off is ignored
+            Some(Pragma::TranslateOff) if self.open.is_none() => self.open = Some(comment.range),
+            Some(Pragma::TranslateOn) => {
+                if let Some(open) = self.open.take() {
+                    self.regions.push(Range::new(open.start, comment.range.end));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The regions at the end of the source and the range of a `translate_off` that is
+    /// never closed, its region extends to the end of the source
+    pub fn finish(&mut self, end: Position) -> (Vec<Range>, Option<Range>) {
+        let open = self.open.take();
+        if let Some(open) = open {
+            self.regions.push(Range::new(open.start, end));
+        }
+        (std::mem::take(&mut self.regions), open)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{check_diagnostics, LibraryBuilder};
+    use crate::syntax::test::Code;
+    use crate::Diagnostic;
+
+    fn pragmas(code: &str) -> Vec<Option<Pragma>> {
+        let (_, comments) = Code::new(code).tokenize_result();
+        comments.iter().map(|comment| comment.pragma()).collect()
+    }
+
+    #[test]
+    fn recognizes_pragmas() {
+        assert_eq!(
+            pragmas(
+                "
+-- synthesis translate_off
+-- Synthesis Translate_On
+-- pragma synthesis_off
+--pragma synthesis_on
+-- vhdl_comp_off
+-- vhdl_comp_on
+-- synopsys translate_off
+-- translate_off
+-- synthesis translate_off now
+/* synthesis translate_off */
+"
+            ),
+            vec![
+                Some(Pragma::TranslateOff),
+                Some(Pragma::TranslateOn),
+                Some(Pragma::TranslateOff),
+                Some(Pragma::TranslateOn),
+                Some(Pragma::TranslateOff),
+                Some(Pragma::TranslateOn),
+                Some(Pragma::TranslateOff),
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics_within_translate_off_region() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s, t : bit;
+begin
+  -- synthesis translate_off
+  s <= missing1;
+  -- synthesis translate_on
+  t <= missing2;
+end architecture;
+",
+        );
+        let diagnostics = builder.analyze();
+        check_diagnostics(
+            diagnostics.clone(),
+            vec![
+                Diagnostic::error(code.s1("missing1"), "No declaration of 'missing1'"),
+                Diagnostic::error(code.s1("missing2"), "No declaration of 'missing2'"),
+            ],
+        );
+        let mut diagnostics = diagnostics;
+        diagnostics.sort_by_key(|diagnostic| diagnostic.pos.start());
+        assert!(diagnostics[0].in_translate_off());
+        assert!(!diagnostics[1].in_translate_off());
+    }
+}
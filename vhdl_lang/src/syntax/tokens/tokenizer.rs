@@ -4,6 +4,7 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
+use super::pragma::TranslateOffRegions;
 use fnv::FnvHashMap;
 
 use crate::ast::{self, AttributeDesignator, Operator, WithRef};
@@ -11,7 +12,12 @@ use crate::ast::{BaseSpecifier, Ident};
 use crate::data::*;
 
 /// The kind of a Token
+///
+/// The kinds are semi-stable: existing kinds keep their meaning,
+/// but new kinds may be added in any release.
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Kind {
     // Keywords
     Architecture,
@@ -97,6 +103,10 @@ pub enum Kind {
     Vunit,
     Parameter,
     Literal,
+    Guarded,
+    Bus,
+    Register,
+    Disconnect,
 
     // Unary operators
     Abs,
@@ -167,10 +177,31 @@ pub enum Kind {
     ColonEq,
     RightArrow,
     GraveAccent, // `
-    Text,        // Raw text that is not processed (i.e. tokenized) further. Used in tool directives
+
+    // PSL operators
+    LeftBrace,
+    RightBrace,
+    Exclamation,
+    Implies,           // ->
+    Iff,               // <->
+    OverlapImplies,    // |->
+    NonOverlapImplies, // |=>
+
+    Text, // Raw text that is not processed (i.e. tokenized) further. Used in tool directives
+
+    /// Characters that do not form a token, only produced by [tokenize]
+    Illegal,
 }
 use self::Kind::*;
 
+impl Kind {
+    /// True for reserved words such as `entity` and word operators such as `and`
+    pub fn is_keyword(self) -> bool {
+        // The arithmetic operators are named by words but are written as symbols
+        !matches!(self, Plus | Minus) && kind_str(self).chars().all(|chr| chr.is_ascii_alphabetic())
+    }
+}
+
 /// Expect any number of token kind patterns, return on no match with
 /// error diagnostic based on expected kinds
 #[macro_export]
@@ -350,6 +381,10 @@ pub fn kind_str(kind: Kind) -> &'static str {
         Vunit => "vunit",
         Parameter => "parameter",
         Literal => "literal",
+        Guarded => "guarded",
+        Bus => "bus",
+        Register => "register",
+        Disconnect => "disconnect",
 
         // Unary operators
         Abs => "abs",
@@ -420,7 +455,15 @@ pub fn kind_str(kind: Kind) -> &'static str {
         ColonEq => ":=",
         RightArrow => "=>",
         GraveAccent => "`",
+        LeftBrace => "{",
+        RightBrace => "}",
+        Exclamation => "!",
+        Implies => "->",
+        Iff => "<->",
+        OverlapImplies => "|->",
+        NonOverlapImplies => "|=>",
         Text => "{text}",
+        Illegal => "{illegal}",
     }
 }
 
@@ -444,6 +487,7 @@ pub fn kinds_str(kinds: &[Kind]) -> String {
 
 /// The value of a Token
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Identifier(Symbol),
     String(Latin1String),
@@ -457,6 +501,7 @@ pub enum Value {
 
 /// A Token
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
     pub kind: Kind,
     pub value: Value,
@@ -468,6 +513,7 @@ pub struct Token {
 /// A token ID cannot be created directly by the user. Instead, the value must be taken
 /// from the AST.
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Ord, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenId(usize);
 
 /// The TokenId represents an index into an array of tokens.
@@ -548,6 +594,7 @@ pub trait HasTokenSpan {
 /// Since the different pieces may be gathered in different locations,
 /// the fields are gated behind accessor functions which also check some invariants every time they are called.
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenSpan {
     pub start_token: TokenId,
     pub end_token: TokenId,
@@ -631,12 +678,14 @@ impl TokenAccess for [Token] {
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenComments {
     pub leading: Vec<Comment>,
     pub trailing: Option<Comment>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Comment {
     pub value: String,
     pub range: crate::data::Range,
@@ -1123,11 +1172,13 @@ fn parse_abstract_literal(
                 Some(b'e') | Some(b'E') => {
                     reader.skip();
                     let exp = parse_exponent(reader)?;
+                    // Parse the mantissa and exponent together to avoid rounding errors
+                    let real = format!("{buffer}e{exp}")
+                        .parse()
+                        .unwrap_or_else(|_| real * (10.0_f64).powi(exp));
                     Ok((
                         AbstractLiteral,
-                        Value::AbstractLiteral(ast::AbstractLiteral::Real(
-                            real * (10.0_f64).powi(exp),
-                        )),
+                        Value::AbstractLiteral(ast::AbstractLiteral::Real(real)),
                     ))
                 }
                 _ => Ok((
@@ -1345,7 +1396,33 @@ fn read_until_newline(
     Ok(())
 }
 
-fn get_leading_comments(reader: &mut ContentReader) -> Result<Vec<Comment>, TokenError> {
+/// PSL directives may be embedded in comments that start with `-- psl`, all following comment
+/// lines belong to the directive until it ends with a semicolon.
+/// Assumes -- has already been consumed, consumes the `psl` word of the first line.
+fn skip_psl_comment_prefix(reader: &mut ContentReader) -> bool {
+    let state = reader.state();
+    skip_whitespace_in_line(reader);
+    // Comments may contain any character so they are read without latin-1 conversion
+    for expected in "psl".chars() {
+        if reader.pop_char().map(|chr| chr.to_ascii_lowercase()) != Some(expected) {
+            reader.set_state(state);
+            return false;
+        }
+    }
+    if matches!(reader.get_char(), None | Some(' ' | '\t' | '\r' | '\n')) {
+        true
+    } else {
+        reader.set_state(state);
+        false
+    }
+}
+
+/// The leading comments of the next token.
+/// `in_psl_comment` is set when a `-- psl` comment continues as tokens
+fn get_leading_comments(
+    reader: &mut ContentReader,
+    in_psl_comment: &mut bool,
+) -> Result<Vec<Comment>, TokenError> {
     let mut comments: Vec<Comment> = Vec::new();
 
     loop {
@@ -1369,6 +1446,10 @@ fn get_leading_comments(reader: &mut ContentReader) -> Result<Vec<Comment>, Toke
             }
             b'-' => {
                 if reader.pop()? == Some(b'-') {
+                    if *in_psl_comment || skip_psl_comment_prefix(reader) {
+                        *in_psl_comment = true;
+                        continue;
+                    }
                     comments.push(parse_comment(reader));
                 } else {
                     reader.set_state(state);
@@ -1420,6 +1501,11 @@ fn get_trailing_comment(reader: &mut ContentReader) -> Result<Option<Comment>, T
     match reader.pop()? {
         Some(b'-') => {
             if reader.pop()? == Some(b'-') {
+                if skip_psl_comment_prefix(reader) {
+                    // Tokenized as the leading comments of the next token
+                    reader.set_state(state);
+                    return Ok(None);
+                }
                 Ok(Some(parse_comment(reader)))
             } else {
                 reader.set_state(state);
@@ -1556,6 +1642,10 @@ impl std::default::Default for Symbols {
             ("rem", Rem),
             ("vunit", Vunit),
             ("parameter", Parameter),
+            ("guarded", Guarded),
+            ("bus", Bus),
+            ("register", Register),
+            ("disconnect", Disconnect),
         ];
 
         let attributes = [
@@ -1663,6 +1753,13 @@ pub struct Tokenizer<'a> {
     pub source: &'a Source,
     reader: ContentReader<'a>,
     final_comments: Option<Vec<Comment>>,
+    /// Within a PSL directive that is embedded in `-- psl` comments
+    in_psl_comment: bool,
+    /// The `{` nesting of the embedded PSL directive, semicolons within braces separate sequences
+    psl_comment_braces: usize,
+    translate_off: TranslateOffRegions,
+    /// A `translate_off` pragma without a matching `translate_on`
+    unclosed_translate_off: Option<SrcPos>,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -1678,6 +1775,10 @@ impl<'a> Tokenizer<'a> {
             source,
             reader,
             final_comments: None,
+            in_psl_comment: false,
+            psl_comment_braces: 0,
+            translate_off: TranslateOffRegions::default(),
+            unclosed_translate_off: None,
         }
     }
 
@@ -1742,7 +1843,11 @@ impl<'a> Tokenizer<'a> {
             }
             b'-' => {
                 self.reader.skip();
-                (Minus, Value::None)
+                if self.reader.skip_if(b'>')? {
+                    (Implies, Value::None)
+                } else {
+                    (Minus, Value::None)
+                }
             }
             b'"' => {
                 self.reader.skip();
@@ -1800,6 +1905,17 @@ impl<'a> Tokenizer<'a> {
                         self.reader.skip();
                         (LtLt, Value::None)
                     }
+                    Some(b'-') => {
+                        // Only <-> is a token, a < -1 is two tokens
+                        let state = self.reader.state();
+                        self.reader.skip();
+                        if self.reader.skip_if(b'>')? {
+                            (Iff, Value::None)
+                        } else {
+                            self.reader.set_state(state);
+                            (LT, Value::None)
+                        }
+                    }
                     _ => (LT, Value::None),
                 }
             }
@@ -1883,7 +1999,27 @@ impl<'a> Tokenizer<'a> {
             }
             b'|' => {
                 self.reader.skip();
-                (Bar, Value::None)
+                let state = self.reader.state();
+                match self.reader.pop()? {
+                    Some(b'-') if self.reader.skip_if(b'>')? => (OverlapImplies, Value::None),
+                    Some(b'=') if self.reader.skip_if(b'>')? => (NonOverlapImplies, Value::None),
+                    _ => {
+                        self.reader.set_state(state);
+                        (Bar, Value::None)
+                    }
+                }
+            }
+            b'{' => {
+                self.reader.skip();
+                (LeftBrace, Value::None)
+            }
+            b'}' => {
+                self.reader.skip();
+                (RightBrace, Value::None)
+            }
+            b'!' => {
+                self.reader.skip();
+                (Exclamation, Value::None)
             }
             b'[' => {
                 self.reader.skip();
@@ -1913,7 +2049,7 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn pop_raw(&mut self) -> Result<Option<Token>, TokenError> {
-        let leading_comments = get_leading_comments(&mut self.reader)?;
+        let leading_comments = get_leading_comments(&mut self.reader, &mut self.in_psl_comment)?;
         self.state.start = self.reader.state();
 
         match self.parse_token()? {
@@ -1921,7 +2057,20 @@ impl<'a> Tokenizer<'a> {
                 // Parsed a token.
                 let pos_start = self.state.start.pos();
                 let pos_end = self.reader.pos();
+                if self.in_psl_comment {
+                    match kind {
+                        LeftBrace => self.psl_comment_braces += 1,
+                        RightBrace => {
+                            self.psl_comment_braces = self.psl_comment_braces.saturating_sub(1)
+                        }
+                        SemiColon if self.psl_comment_braces == 0 => self.in_psl_comment = false,
+                        _ => {}
+                    }
+                }
                 let trailing_comment = get_trailing_comment(&mut self.reader)?;
+                for comment in leading_comments.iter().chain(trailing_comment.iter()) {
+                    self.translate_off.add_comment(comment);
+                }
                 let token_comments = if (!leading_comments.is_empty()) | trailing_comment.is_some()
                 {
                     Some(Box::new(TokenComments {
@@ -1942,6 +2091,15 @@ impl<'a> Tokenizer<'a> {
             }
             None => {
                 // End of file.
+                if self.final_comments.is_none() {
+                    for comment in leading_comments.iter() {
+                        self.translate_off.add_comment(comment);
+                    }
+                    let (regions, unclosed) = self.translate_off.finish(self.reader.pos());
+                    self.source.set_translate_off_regions(regions);
+                    self.unclosed_translate_off =
+                        unclosed.map(|range| self.source.pos(range.start, range.end));
+                }
                 self.final_comments = Some(leading_comments);
                 Ok(None)
             }
@@ -1961,11 +2119,39 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// The position of a `translate_off` pragma that is not closed before the end of the source
+    pub fn unclosed_translate_off(&self) -> Option<&SrcPos> {
+        self.unclosed_translate_off.as_ref()
+    }
+
     #[allow(dead_code)]
     pub fn get_final_comments(&self) -> Option<Vec<Comment>> {
         self.final_comments.clone()
     }
 
+    /// Skip the lines up to and including the next line that starts with `directive`,
+    /// the lines need not contain valid tokens.
+    pub fn skip_lines_until(&mut self, directive: &str) {
+        loop {
+            let mut line = String::new();
+            let at_end = loop {
+                match self.reader.pop_char() {
+                    Some('\n') => break false,
+                    Some(chr) => line.push(chr),
+                    None => break true,
+                }
+            };
+            if line
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with(directive)
+                || at_end
+            {
+                return;
+            }
+        }
+    }
+
     pub fn text_until_newline(&mut self) -> DiagnosticResult<Token> {
         let start_pos = self.reader.pos();
         if let Err(err) = read_until_newline(&mut self.buffer, &mut self.reader) {
@@ -1986,6 +2172,32 @@ impl<'a> Tokenizer<'a> {
     }
 }
 
+/// Tokenize a complete source file without parsing it.
+///
+/// Tokenization continues after errors, the characters that do not form a token become
+/// [Kind::Illegal] tokens. Comments are the leading and trailing comments of the tokens,
+/// the comments after the last token are dropped. Tool directives are not expanded, their
+/// text after the [Kind::GraveAccent] is tokenized like any other text.
+pub fn tokenize(source: &Source) -> Vec<Token> {
+    let symbols = Symbols::default();
+    let contents = source.contents();
+    let mut tokenizer = Tokenizer::new(&symbols, source, ContentReader::new(&contents));
+    let mut tokens = Vec::new();
+    loop {
+        match tokenizer.pop() {
+            Ok(Some(token)) => tokens.push(token),
+            Ok(None) => break,
+            Err(err) => tokens.push(Token {
+                kind: Illegal,
+                value: Value::None,
+                pos: err.pos,
+                comments: None,
+            }),
+        }
+    }
+    tokens
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2779,7 +2991,7 @@ comment
 
     #[test]
     fn tokenize_illegal() {
-        let code = Code::new("begin!end");
+        let code = Code::new("begin$end");
         let (tokens, _) = code.tokenize_result();
         assert_eq!(
             tokens,
@@ -2790,7 +3002,7 @@ comment
                     pos: code.s1("begin").pos(),
                     comments: None,
                 }),
-                Err(Diagnostic::error(&code.s1("!"), "Illegal token")),
+                Err(Diagnostic::error(&code.s1("$"), "Illegal token")),
                 Ok(Token {
                     kind: End,
                     value: Value::None,
@@ -2801,6 +3013,68 @@ comment
         );
     }
 
+    #[test]
+    fn tokenize_public_api() {
+        let code = Code::new(
+            "\
+-- header
+x := 16#FF# + 1.5e3 & 'a' & \"str\" & 8X\"0F\" $ and;",
+        );
+        let tokens = tokenize(code.source());
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|tok| (tok.kind, tok.pos.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Identifier, code.s1("x").pos()),
+                (ColonEq, code.s1(":=").pos()),
+                (AbstractLiteral, code.s1("16#FF#").pos()),
+                (Plus, code.s1("+").pos()),
+                (AbstractLiteral, code.s1("1.5e3").pos()),
+                (Concat, code.s("&", 1).pos()),
+                (Character, code.s1("'a'").pos()),
+                (Concat, code.s("&", 2).pos()),
+                (StringLiteral, code.s1("\"str\"").pos()),
+                (Concat, code.s("&", 3).pos()),
+                (BitString, code.s1("8X\"0F\"").pos()),
+                (Illegal, code.s1("$").pos()),
+                (And, code.s1("and").pos()),
+                (SemiColon, code.s1(";").pos()),
+            ]
+        );
+
+        assert_eq!(
+            tokens[0].comments.as_ref().unwrap().leading[0].value,
+            " header"
+        );
+        match tokens[0].value {
+            Value::Identifier(ref sym) => assert_eq!(sym.name_utf8(), "x"),
+            ref value => panic!("Expected an identifier, got {value:?}"),
+        }
+        let values: Vec<Value> = [2, 4, 6, 8, 10]
+            .iter()
+            .map(|idx| tokens[*idx].value.clone())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                Value::AbstractLiteral(ast::AbstractLiteral::Integer(255)),
+                Value::AbstractLiteral(ast::AbstractLiteral::Real(1500.0)),
+                Value::Character(b'a'),
+                Value::String(Latin1String::from_utf8_unchecked("str")),
+                Value::BitString(ast::BitString {
+                    length: Some(8),
+                    base: BaseSpecifier::X,
+                    value: Latin1String::from_utf8_unchecked("0F"),
+                }),
+            ]
+        );
+        assert!(And.is_keyword());
+        assert!(!Plus.is_keyword());
+        assert!(!Identifier.is_keyword());
+    }
+
     #[test]
     fn extract_final_comments() {
         let code = Code::new("--final");
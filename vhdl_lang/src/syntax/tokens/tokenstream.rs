@@ -6,10 +6,12 @@
 
 use std::cell::Cell;
 
+use super::conditional::{ConditionalAnalysis, ToolIdentifiers};
 use super::tokenizer::Kind::*;
 use super::tokenizer::*;
 use crate::ast::{AttributeDesignator, Ident, RangeAttribute, TypeAttribute};
-use crate::data::{DiagnosticHandler, DiagnosticResult, WithPos};
+use crate::data::{DiagnosticHandler, DiagnosticResult, ErrorCode, WithPos};
+use crate::standard::VHDLStandard;
 use crate::{Diagnostic, SrcPos};
 
 pub struct TokenStream<'a> {
@@ -20,6 +22,21 @@ pub struct TokenStream<'a> {
     // when getting it via `TokenStream::get_current_token_id()`
     // It is updated in the `slice_tokens` method
     token_offset: Cell<usize>,
+    standard: VHDLStandard,
+}
+
+/// True for the `` `protect begin_protected `` directive that starts a protected envelope
+fn is_begin_protected(identifier: &Token, text: &Token) -> bool {
+    let (Value::Identifier(identifier), Value::Text(text)) = (&identifier.value, &text.value)
+    else {
+        return false;
+    };
+    identifier.name_utf8().eq_ignore_ascii_case("protect")
+        && text
+            .to_string()
+            .trim_start()
+            .to_ascii_lowercase()
+            .starts_with("begin_protected")
 }
 
 impl<'a> TokenStream<'a> {
@@ -28,55 +45,241 @@ impl<'a> TokenStream<'a> {
     /// `identifier { any chars until newline }
     /// ```
     /// This needs special handling as the text that follows the identifier is arbitrary.
+    /// The conditional analysis directives `` `if ``, `` `elsif ``, `` `else `` and `` `end ``
+    /// are evaluated instead. Returns the token after the directive if it had to be read
+    /// to find the end of the directive.
     fn handle_tool_directive(
         grave_accent: Token,
         tokenizer: &mut Tokenizer,
+        conditional: &mut ConditionalAnalysis,
         diagnostics: &mut dyn DiagnosticHandler,
-    ) {
+    ) -> Option<Token> {
         let start_pos = grave_accent.pos.clone();
-        match tokenizer.pop() {
-            Ok(Some(tok)) => {
-                if tok.kind != Identifier {
-                    diagnostics.error(tok, "Expecting identifier");
-                    let _ = tokenizer.text_until_newline(); // skip potentially invalid tokens
-                    return;
-                }
+        let tok = match tokenizer.pop() {
+            Ok(Some(tok)) => tok,
+            Err(err) => {
+                diagnostics.push(err);
+                return None;
             }
-            Err(err) => diagnostics.push(err),
             Ok(None) => {
                 diagnostics.error(start_pos, "Expecting identifier");
-                return;
+                return None;
+            }
+        };
+        match tok.kind {
+            If | Elsif => {
+                let (condition, end_pos, next) =
+                    TokenStream::condition_until_then(&tok, tokenizer, diagnostics);
+                let directive = start_pos.combine(&end_pos);
+                if tok.kind == If {
+                    conditional.if_directive(directive, condition.as_deref(), diagnostics);
+                } else {
+                    conditional.elsif_directive(directive, condition.as_deref(), diagnostics);
+                }
+                next
+            }
+            Else => {
+                conditional.else_directive(start_pos.combine(&tok), diagnostics);
+                None
+            }
+            End => match tokenizer.pop() {
+                // The `if` of `end if is optional
+                Ok(Some(next))
+                    if next.kind == If && next.pos.start().line == tok.pos.start().line =>
+                {
+                    conditional.end_directive(start_pos.combine(&next), diagnostics);
+                    None
+                }
+                next => {
+                    conditional.end_directive(start_pos.combine(&tok), diagnostics);
+                    next.unwrap_or_else(|err| {
+                        diagnostics.push(err);
+                        None
+                    })
+                }
+            },
+            Identifier => {
+                match tokenizer.text_until_newline() {
+                    Ok(text) if is_begin_protected(&tok, &text) => {
+                        // The encrypted data of a protected envelope need not be valid tokens,
+                        // an envelope without an end is skipped until the end of the source
+                        tokenizer.skip_lines_until("`protect end_protected");
+                    }
+                    Ok(_) => {}
+                    Err(err) => diagnostics.push(err),
+                }
+                None
+            }
+            _ => {
+                diagnostics.error(tok, "Expecting identifier");
+                let _ = tokenizer.text_until_newline(); // skip potentially invalid tokens
+                None
             }
         }
-        match tokenizer.text_until_newline() {
-            Ok(_) => {}
-            Err(err) => diagnostics.push(err),
+    }
+
+    /// The tokens of the condition of an `` `if `` or `` `elsif `` directive until the
+    /// `` `then `` on the same line, `None` if the condition is not terminated by `` `then ``.
+    /// Also returns the end of the directive and the token after it if it was read.
+    fn condition_until_then(
+        keyword: &Token,
+        tokenizer: &mut Tokenizer,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> (Option<Vec<Token>>, SrcPos, Option<Token>) {
+        let line = keyword.pos.start().line;
+        let mut condition = Vec::new();
+        let mut end_pos = keyword.pos.clone();
+        loop {
+            match tokenizer.pop() {
+                Ok(Some(token)) if token.pos.start().line != line => {
+                    diagnostics.error(end_pos.pos_at_end(), "Expected '`then'");
+                    return (None, end_pos, Some(token));
+                }
+                Ok(Some(token)) if token.kind == GraveAccent => {
+                    return match tokenizer.pop() {
+                        Ok(Some(then)) if then.kind == Then => (Some(condition), then.pos, None),
+                        Ok(Some(token)) => {
+                            diagnostics.error(&token, "Expected '`then'");
+                            let _ = tokenizer.text_until_newline(); // skip potentially invalid tokens
+                            (None, token.pos, None)
+                        }
+                        Ok(None) => {
+                            diagnostics.error(&token, "Expected '`then'");
+                            (None, token.pos, None)
+                        }
+                        Err(err) => {
+                            diagnostics.push(err);
+                            (None, token.pos, None)
+                        }
+                    };
+                }
+                Ok(Some(token)) => {
+                    end_pos = token.pos.clone();
+                    condition.push(token);
+                }
+                Ok(None) => {
+                    diagnostics.error(end_pos.pos_at_end(), "Expected '`then'");
+                    return (None, end_pos, None);
+                }
+                Err(err) => {
+                    diagnostics.push(err);
+                    return (None, end_pos, None);
+                }
+            }
         }
     }
 
+    #[cfg(test)]
     pub fn new(
+        tokenizer: Tokenizer<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> TokenStream<'a> {
+        TokenStream::new_with_standard(tokenizer, VHDLStandard::default(), diagnostics)
+    }
+
+    #[cfg(test)]
+    pub fn new_with_standard(
+        tokenizer: Tokenizer<'a>,
+        standard: VHDLStandard,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> TokenStream<'a> {
+        TokenStream::new_with_tool_identifiers(
+            tokenizer,
+            standard,
+            &ToolIdentifiers::default(),
+            diagnostics,
+        )
+    }
+
+    /// Tokenize the source, the tokens of inactive conditional analysis branches are
+    /// dropped and the tokenizer errors within them are not reported
+    pub fn new_with_tool_identifiers(
         mut tokenizer: Tokenizer<'a>,
+        standard: VHDLStandard,
+        tool_identifiers: &ToolIdentifiers,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> TokenStream<'a> {
         let mut tokens = Vec::new();
+        let mut conditional = ConditionalAnalysis::new(tool_identifiers, standard);
+        let mut next = None;
         loop {
-            match tokenizer.pop() {
+            let token = match next.take() {
+                Some(token) => Ok(Some(token)),
+                None => tokenizer.pop(),
+            };
+            match token {
                 Ok(Some(token)) if token.kind == GraveAccent => {
-                    TokenStream::handle_tool_directive(token, &mut tokenizer, diagnostics)
+                    next = TokenStream::handle_tool_directive(
+                        token,
+                        &mut tokenizer,
+                        &mut conditional,
+                        diagnostics,
+                    )
+                }
+                Ok(Some(token)) => {
+                    if conditional.is_active() {
+                        TokenStream::check_operator_standard(&token, standard, diagnostics);
+                        tokens.push(token)
+                    }
                 }
-                Ok(Some(token)) => tokens.push(token),
                 Ok(None) => break,
-                Err(err) => diagnostics.push(err),
+                Err(err) => {
+                    if conditional.is_active() {
+                        diagnostics.push(err)
+                    }
+                }
             }
         }
+        let end = tokenizer.source.contents().end();
+        let inactive_regions = conditional.finish(end, diagnostics);
+        tokenizer.source.set_inactive_regions(inactive_regions);
+        if let Some(pos) = tokenizer.unclosed_translate_off() {
+            diagnostics.push(
+                Diagnostic::warning(
+                    pos,
+                    "'translate_off' pragma is not closed by a 'translate_on' pragma",
+                )
+                .with_code(ErrorCode::UnbalancedPragma),
+            );
+        }
         TokenStream {
             tokenizer,
             idx: Cell::new(0),
             tokens,
             token_offset: Cell::new(0),
+            standard,
         }
     }
 
+    /// The matching relational operators such as `?=` and the condition operator `??`
+    /// were added in VHDL-2008
+    fn check_operator_standard(
+        token: &Token,
+        standard: VHDLStandard,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if standard < VHDLStandard::V2008
+            && matches!(
+                token.kind,
+                QueEQ | QueNE | QueLT | QueLTE | QueGT | QueGTE | QueQue
+            )
+        {
+            diagnostics.push(Diagnostic::error(
+                &token.pos,
+                format!(
+                    "Operator {} requires {}",
+                    kinds_str(&[token.kind]),
+                    VHDLStandard::V2008
+                ),
+            ));
+        }
+    }
+
+    /// The VHDL revision that is being parsed
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
     pub fn state(&self) -> usize {
         self.get_idx()
     }
@@ -194,8 +397,12 @@ impl<'a> TokenStream<'a> {
         self.nth_kind_is(0, kind)
     }
 
+    pub fn nth(&self, idx: usize) -> Option<&Token> {
+        self.tokens.get(self.get_idx() + idx)
+    }
+
     pub fn nth_kind_is(&self, idx: usize, kind: Kind) -> bool {
-        if let Some(token) = self.tokens.get(self.get_idx() + idx) {
+        if let Some(token) = self.nth(idx) {
             token.kind == kind
         } else {
             false
@@ -237,6 +444,39 @@ impl<'a> TokenStream<'a> {
         }
     }
 
+    /// The position from `start` to the last consumed token, or of `start` itself when
+    /// no token has been consumed since. Used for the error nodes of skipped tokens.
+    pub fn pos_since(&self, start: &Token) -> SrcPos {
+        match (self.last(), self.idx_of(start)) {
+            (Some(last), Some(start_idx)) if self.get_idx() > start_idx => {
+                start.pos.combine(&last.pos)
+            }
+            _ => start.pos.clone(),
+        }
+    }
+
+    /// True when the next token is a keyword that starts a design unit or its context clause
+    /// at the beginning of a line. Used to stop the error recovery within a design unit
+    /// from consuming the units that follow it.
+    pub fn next_is_design_unit_start(&self) -> bool {
+        let Some(token) = self.peek() else {
+            return false;
+        };
+        if !matches!(
+            token.kind,
+            Library | Context | Entity | Architecture | Package | Configuration
+        ) {
+            return false;
+        }
+        match self.last() {
+            Some(last) => {
+                !matches!(last.kind, End | Colon | Use)
+                    && last.pos.end().line != token.pos.range.start.line
+            }
+            None => true,
+        }
+    }
+
     pub fn pop_optional_ident(&self) -> Option<Ident> {
         self.pop_if_kind(Identifier)
             .map(|id| self.get_token(id).to_identifier_value().unwrap())
@@ -349,7 +589,7 @@ impl<T> Recover<T> for DiagnosticResult<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{ContentReader, Diagnostic, NoDiagnostics};
+    use crate::data::{ContentReader, Diagnostic, NoDiagnostics, Range};
     use crate::syntax::test::Code;
     use itertools::Itertools;
 
@@ -530,6 +770,231 @@ mod tests {
         )
     }
 
+    #[test]
+    fn unclosed_translate_off_pragma() {
+        let code = Code::new(
+            "\
+-- synthesis translate_off
+signal s : bit;
+-- synthesis translate_on
+-- pragma synthesis_off
+signal t : bit;
+",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, _stream, diagnostics);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::warning(
+                code.s1("-- pragma synthesis_off"),
+                "'translate_off' pragma is not closed by a 'translate_on' pragma"
+            )
+            .with_code(ErrorCode::UnbalancedPragma)]
+        );
+        let source = code.source();
+        assert!(source.is_translated_off(code.s1("signal s").start()));
+        assert!(!source.is_translated_off(code.s1("translate_on").end()));
+        // An unclosed region extends to the end of the file
+        assert!(source.is_translated_off(code.s1("signal t").start()));
+    }
+
+    /// The identifiers of the tokens that are not excluded by conditional analysis
+    fn active_identifiers(
+        code: &Code,
+        tool_type: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Vec<String> {
+        let identifiers = ToolIdentifiers::new(&fnv::FnvHashMap::from_iter([(
+            "tool_type".to_owned(),
+            tool_type.to_owned(),
+        )]));
+        let source = code.source();
+        let contents = source.contents();
+        let tokenizer = Tokenizer::new(&code.symbols, source, ContentReader::new(&contents));
+        let stream = TokenStream::new_with_tool_identifiers(
+            tokenizer,
+            VHDLStandard::V2019,
+            &identifiers,
+            diagnostics,
+        );
+        stream
+            .tokens
+            .iter()
+            .filter_map(|token| match token.value {
+                Value::Identifier(ref symbol) => Some(symbol.name_utf8()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn conditional_analysis_selects_branch() {
+        let code = Code::new(
+            "\
+`if TOOL_TYPE = \"SIMULATION\" `then
+sim
+`elsif tool_type = \"SYNTHESIS\" `then
+syn
+`else
+other
+`end if
+always
+",
+        );
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            active_identifiers(&code, "SIMULATION", &mut diagnostics),
+            vec!["sim", "always"]
+        );
+        assert_eq!(
+            code.source().inactive_regions(),
+            vec![Range::new(
+                code.s1("`elsif tool_type = \"SYNTHESIS\" `then").end(),
+                code.s1("`end").start()
+            )]
+        );
+        assert_eq!(
+            active_identifiers(&code, "SYNTHESIS", &mut diagnostics),
+            vec!["syn", "always"]
+        );
+        assert_eq!(
+            code.source().inactive_regions(),
+            vec![
+                Range::new(code.s1("`then").end(), code.s1("`elsif").start()),
+                Range::new(code.s1("`else").end(), code.s1("`end").start())
+            ]
+        );
+        assert_eq!(
+            active_identifiers(&code, "FORMAL", &mut diagnostics),
+            vec!["other", "always"]
+        );
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn conditional_analysis_expressions() {
+        let code = Code::new(
+            "\
+`if VHDL_VERSION >= \"2008\" and not (TOOL_TYPE /= \"SIMULATION\") `then
+a
+`end
+`if (TOOL_TYPE < \"B\" or TOOL_TYPE > \"Y\") xor TOOL_TYPE = \"Z\" `then
+b
+`end if
+",
+        );
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            active_identifiers(&code, "SIMULATION", &mut diagnostics),
+            vec!["a"]
+        );
+        assert_eq!(active_identifiers(&code, "A", &mut diagnostics), vec!["b"]);
+        assert_eq!(
+            active_identifiers(&code, "Z", &mut diagnostics),
+            Vec::<String>::new()
+        );
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn nested_conditional_analysis_directives() {
+        let code = Code::new(
+            "\
+`if TOOL_TYPE = \"SIMULATION\" `then
+  `if UNDEFINED = \"\" `then
+  a
+  `end if
+`else
+  `if TOOL_TYPE = \"SYNTHESIS\" `then
+  b
+  `else
+  c
+  `end if
+`end if
+",
+        );
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            active_identifiers(&code, "SYNTHESIS", &mut diagnostics),
+            vec!["b"]
+        );
+        // Directives within inactive branches are not evaluated
+        assert_eq!(diagnostics, vec![]);
+
+        assert_eq!(
+            active_identifiers(&code, "SIMULATION", &mut diagnostics),
+            vec!["a"]
+        );
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::warning(
+                code.s1("UNDEFINED"),
+                "Tool identifier 'UNDEFINED' is not defined, its value is the empty string"
+            )
+            .with_code(ErrorCode::UnknownToolIdentifier)]
+        );
+    }
+
+    #[test]
+    fn malformed_conditional_analysis_directives() {
+        let code = Code::new(
+            "\
+`if TOOL_TYPE \"SIMULATION\" `then
+a
+`end if
+`if TOOL_TYPE = \"SIMULATION\"
+b
+`end if
+`else
+`if TOOL_TYPE = \"SIMULATION\" `then
+c
+",
+        );
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            active_identifiers(&code, "SIMULATION", &mut diagnostics),
+            vec!["c"]
+        );
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("\"SIMULATION\""), "Expected relational operator"),
+                Diagnostic::error(
+                    code.s("\"SIMULATION\"", 2).pos().pos_at_end(),
+                    "Expected '`then'"
+                ),
+                Diagnostic::error(
+                    code.s1("`else"),
+                    "'`else' directive without a matching '`if'"
+                ),
+                Diagnostic::error(
+                    code.s1("`if TOOL_TYPE = \"SIMULATION\" `then"),
+                    "'`if' directive is not closed by an '`end' directive"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn protected_envelope_is_skipped() {
+        let code = Code::new(
+            "\
+`protect begin_protected
+`protect encoding = (enctype = \"base64\", line_length = 76, bytes = 12)
+`protect data_block
+3q2+7w==`'\"#
+`protect end_protected
+a
+",
+        );
+        let mut diagnostics = Vec::new();
+        assert_eq!(
+            active_identifiers(&code, "SIMULATION", &mut diagnostics),
+            vec!["a"]
+        );
+        assert_eq!(diagnostics, vec![]);
+    }
+
     #[test]
     fn pop_tokens() {
         let code = Code::new(
@@ -0,0 +1,394 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2023, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::{AnyDesignUnit, AnyKind, ContextItem, Designator, HasUnitId};
+use crate::data::{Contents, HasSource, Position, SrcPos, Symbol};
+use crate::named_entity::{AnyEntKind, Design, HasEntityId};
+use crate::syntax::{HasTokenSpan, Token};
+
+/// An edit of the context clause of a design unit that makes a missing name visible
+/// by using all declarations of the package that declares it
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UseClauseFix {
+    pub library_name: Symbol,
+    pub package_name: Symbol,
+    /// The position where the text is inserted, always at the start of a line
+    pub position: Position,
+    /// The use clause preceded by a library clause when the library is not yet visible
+    pub text: String,
+}
+
+/// The whitespace that the line starts with
+pub(crate) fn indentation(contents: &Contents, line: u32) -> String {
+    contents
+        .get_line(line as usize)
+        .unwrap_or_default()
+        .chars()
+        .take_while(|chr| *chr == ' ' || *chr == '\t')
+        .collect()
+}
+
+impl DesignRoot {
+    /// The packages of all libraries that declare the missing name at `pos` and
+    /// the edits that add a use clause for them to the design unit that contains `pos`.
+    pub fn use_clause_fixes(&self, pos: &SrcPos) -> Vec<UseClauseFix> {
+        let Some(symbol) = self.parse_identifier(&pos.text()) else {
+            return Vec::new();
+        };
+        let designator = Designator::Identifier(symbol);
+
+        let Some(work_library) = self.library_of_source(pos.source()) else {
+            return Vec::new();
+        };
+        let Some(unit) = self.units_by_source(pos.source()).find(|unit| {
+            unit_pos(&unit.tokens).is_some_and(|unit_pos| unit_pos.contains(pos.start()))
+        }) else {
+            return Vec::new();
+        };
+        let tokens = &unit.tokens;
+        let design_unit = unit.unit.expect_analyzed();
+        let Some(context_clause) = design_unit.context_clause() else {
+            return Vec::new();
+        };
+        let contents = pos.source().contents();
+
+        // New clauses are added after the existing clauses with the same indentation
+        let (position, indent) = match context_clause.last() {
+            Some(item) => {
+                let item_pos = item.get_span(tokens);
+                (
+                    Position::new(item_pos.end().line + 1, 0),
+                    indentation(&contents, item_pos.start().line),
+                )
+            }
+            None => {
+                let Some(unit_pos) = unit_pos(tokens) else {
+                    return Vec::new();
+                };
+                (
+                    Position::new(unit_pos.start().line, 0),
+                    indentation(&contents, unit_pos.start().line),
+                )
+            }
+        };
+
+        // The library clauses of the primary unit also apply to its secondary units
+        let primary = match unit.kind() {
+            AnyKind::Secondary(_) => work_library
+                .primary_unit(unit.unit_id().primary_name())
+                .and_then(|primary| primary.unit.get()),
+            AnyKind::Primary(_) => None,
+        };
+        let primary_context_clause = primary
+            .as_ref()
+            .and_then(|primary| primary.context_clause())
+            .map(|clause| clause.as_slice())
+            .unwrap_or_default();
+        let has_library_clause = |library_name: &Symbol| {
+            context_clause
+                .iter()
+                .chain(primary_context_clause)
+                .any(|item| match item {
+                    ContextItem::Library(clause) => clause
+                        .name_list
+                        .items
+                        .iter()
+                        .any(|name| &name.item.item == library_name),
+                    _ => false,
+                })
+        };
+
+        let mut fixes = Vec::new();
+        for library in self.libraries() {
+            for primary in library.primary_units() {
+                let primary = primary.unit.expect_analyzed();
+                let AnyDesignUnit::Primary(primary) = &*primary else {
+                    continue;
+                };
+                let Some(ent) = primary.ent_id().map(|id| self.get_ent(id)) else {
+                    continue;
+                };
+                let region = match ent.kind() {
+                    AnyEntKind::Design(Design::Package(_, region))
+                    | AnyEntKind::Design(Design::PackageInstance(region)) => region,
+                    _ => continue,
+                };
+                if region.lookup_immediate(&designator).is_none() {
+                    continue;
+                }
+
+                let package_name = ent.designator().expect_identifier().clone();
+                let is_work = library.name() == work_library.name();
+                let library_text = if is_work {
+                    "work".to_owned()
+                } else {
+                    library.name().name_utf8()
+                };
+
+                let mut text = String::new();
+                if !is_work && !has_library_clause(library.name()) {
+                    text.push_str(&format!("{indent}library {library_text};\n"));
+                }
+                text.push_str(&format!(
+                    "{indent}use {library_text}.{}.all;\n",
+                    package_name.name_utf8()
+                ));
+
+                fixes.push(UseClauseFix {
+                    library_name: library.name().clone(),
+                    package_name,
+                    position,
+                    text,
+                });
+            }
+        }
+
+        fixes.sort_by_key(|fix| (fix.library_name.name_utf8(), fix.package_name.name_utf8()));
+        fixes
+    }
+}
+
+/// The position of the design unit including its context clause
+fn unit_pos(tokens: &[Token]) -> Option<SrcPos> {
+    Some(tokens.first()?.pos.combine(&tokens.last()?.pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{Code, LibraryBuilder};
+    use pretty_assertions::assert_eq;
+
+    fn fixes(builder: &LibraryBuilder, pos: &SrcPos) -> Vec<(String, Position, String)> {
+        let (root, _) = builder.get_analyzed_root();
+        root.use_clause_fixes(pos)
+            .into_iter()
+            .map(|fix| {
+                (
+                    format!("{}.{}", fix.library_name, fix.package_name),
+                    fix.position,
+                    fix.text,
+                )
+            })
+            .collect()
+    }
+
+    fn line_of(code: &Code, substr: &str) -> u32 {
+        code.s1(substr).start().line
+    }
+
+    #[test]
+    fn name_declared_in_one_package() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package numeric_std is
+  type unsigned is array (natural range <>) of bit;
+  function to_unsigned(value, size : natural) return unsigned;
+end package;
+
+package numeric_bit is
+  type unsigned is array (natural range <>) of bit;
+end package;
+",
+        );
+        builder.code(
+            "libname",
+            "
+package pkg is
+  constant data_width : natural := 8;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c0 : natural := data_width;
+  constant c1 : bit_vector(7 downto 0) := bit_vector(to_unsigned(c0, 8));
+begin
+end architecture;
+",
+        );
+
+        // A package of the same library is used through work
+        assert_eq!(
+            fixes(&builder, &code.s1("data_width").pos()),
+            vec![(
+                "libname.pkg".to_owned(),
+                Position::new(line_of(&code, "architecture"), 0),
+                "use work.pkg.all;\n".to_owned()
+            )]
+        );
+        assert_eq!(
+            fixes(&builder, &code.s1("to_unsigned").pos()),
+            vec![(
+                "ieee.numeric_std".to_owned(),
+                Position::new(line_of(&code, "architecture"), 0),
+                "library ieee;\nuse ieee.numeric_std.all;\n".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn name_declared_in_two_packages() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package numeric_std is
+  type unsigned is array (natural range <>) of bit;
+  function to_unsigned(value, size : natural) return unsigned;
+end package;
+
+package numeric_bit is
+  type unsigned is array (natural range <>) of bit;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+  entity ent is
+    port (data : in unsigned(7 downto 0));
+  end entity;
+",
+        );
+
+        let line = line_of(&code, "entity ent");
+        assert_eq!(
+            fixes(&builder, &code.s1("unsigned").pos()),
+            vec![
+                (
+                    "ieee.numeric_bit".to_owned(),
+                    Position::new(line, 0),
+                    "  library ieee;\n  use ieee.numeric_bit.all;\n".to_owned()
+                ),
+                (
+                    "ieee.numeric_std".to_owned(),
+                    Position::new(line, 0),
+                    "  library ieee;\n  use ieee.numeric_std.all;\n".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn use_clause_is_added_after_existing_context_clause() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package numeric_std is
+  type unsigned is array (natural range <>) of bit;
+  function to_unsigned(value, size : natural) return unsigned;
+end package;
+
+package numeric_bit is
+  type unsigned is array (natural range <>) of bit;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+library ieee;
+    use ieee.numeric_bit.all;
+
+entity ent is
+end entity;
+
+library ieee;
+use ieee.numeric_bit.all;
+architecture a of ent is
+  constant c0 : bit_vector(7 downto 0) := bit_vector(to_unsigned(0, 8));
+begin
+end architecture;
+",
+        );
+
+        assert_eq!(
+            fixes(&builder, &code.s1("to_unsigned").pos()),
+            vec![(
+                "ieee.numeric_std".to_owned(),
+                Position::new(code.s("use ieee.numeric_bit.all", 2).start().line + 1, 0),
+                "use ieee.numeric_std.all;\n".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn no_fixes_for_undeclared_name() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package numeric_std is
+  type unsigned is array (natural range <>) of bit;
+  function to_unsigned(value, size : natural) return unsigned;
+end package;
+
+package numeric_bit is
+  type unsigned is array (natural range <>) of bit;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (data : in missing_t);
+end entity;
+",
+        );
+
+        assert_eq!(fixes(&builder, &code.s1("missing_t").pos()), vec![]);
+        // Only identifiers are looked up
+        assert_eq!(fixes(&builder, &code.s1("in missing_t").pos()), vec![]);
+    }
+
+    #[test]
+    fn library_clause_of_primary_unit_is_not_repeated() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package numeric_std is
+  type unsigned is array (natural range <>) of bit;
+  function to_unsigned(value, size : natural) return unsigned;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+library ieee;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c0 : bit_vector(7 downto 0) := bit_vector(to_unsigned(0, 8));
+begin
+end architecture;
+",
+        );
+
+        assert_eq!(
+            fixes(&builder, &code.s1("to_unsigned").pos()),
+            vec![(
+                "ieee.numeric_std".to_owned(),
+                Position::new(line_of(&code, "architecture"), 0),
+                "use ieee.numeric_std.all;\n".to_owned()
+            )]
+        );
+    }
+}
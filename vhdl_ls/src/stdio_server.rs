@@ -83,7 +83,23 @@ impl ConnectionRpcChannel {
     /// Main event loop handling incoming messages from the client.
     fn main_event_loop(&self, mut server: VHDLServer) {
         info!("Language server initialized, waiting for messages ...");
-        while let Ok(message) = self.connection.receiver.recv() {
+        loop {
+            // Changes of watched files are processed once no further messages arrive
+            let message = if let Some(deadline) = server.pending_file_changes_deadline() {
+                match self.connection.receiver.recv_deadline(deadline) {
+                    Ok(message) => message,
+                    Err(err) if err.is_timeout() => {
+                        server.process_pending_file_changes();
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+            } else {
+                match self.connection.receiver.recv() {
+                    Ok(message) => message,
+                    Err(_) => break,
+                }
+            };
             trace!("Received message: {:?}", message);
             match message {
                 lsp_server::Message::Request(request) => {
@@ -198,6 +214,38 @@ impl ConnectionRpcChannel {
             }
             Err(request) => request,
         };
+        let request = match extract::<request::SignatureHelpRequest>(request) {
+            Ok((id, params)) => {
+                let result = server.text_document_signature_help(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<request::Formatting>(request) {
+            Ok((id, params)) => {
+                let result = server.text_document_formatting(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<request::SemanticTokensFullRequest>(request) {
+            Ok((id, params)) => {
+                let result = server.text_document_semantic_tokens_full(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<request::SemanticTokensRangeRequest>(request) {
+            Ok((id, params)) => {
+                let result = server.text_document_semantic_tokens_range(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
         let request = match extract::<request::References>(request) {
             Ok((id, params)) => {
                 let result = server.text_document_references(&params);
@@ -206,6 +254,30 @@ impl ConnectionRpcChannel {
             }
             Err(request) => request,
         };
+        let request = match extract::<request::DocumentHighlightRequest>(request) {
+            Ok((id, params)) => {
+                let result = server.text_document_highlight(&params.text_document_position_params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<request::CodeActionRequest>(request) {
+            Ok((id, params)) => {
+                let result = server.text_document_code_action(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<request::ExecuteCommand>(request) {
+            Ok((id, params)) => {
+                let result = server.workspace_execute_command(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
         let request = match extract::<request::Completion>(request) {
             Ok((id, params)) => {
                 let res = server.request_completion(&params);
@@ -259,6 +331,11 @@ impl ConnectionRpcChannel {
             Ok(params) => return server.text_document_did_open_notification(&params),
             Err(notification) => notification,
         };
+        // textDocument/didClose
+        let notification = match extract::<notification::DidCloseTextDocument>(notification) {
+            Ok(params) => return server.text_document_did_close_notification(&params),
+            Err(notification) => notification,
+        };
         // workspace.didChangeWatchedFiles
         let notification = match extract::<notification::DidChangeWatchedFiles>(notification) {
             Ok(params) => return server.workspace_did_change_watched_files(&params),
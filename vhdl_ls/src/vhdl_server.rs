@@ -6,7 +6,7 @@
 
 use lsp_types::*;
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use vhdl_lang::ast::{Designator, ObjectClass};
@@ -15,12 +15,20 @@ use crate::rpc_channel::SharedRpcChannel;
 use std::io;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use vhdl_lang::{
     kind_str, AnyEntKind, Concurrent, Config, Design, Diagnostic, EntHierarchy, EntRef, EntityId,
-    InterfaceEnt, Message, MessageHandler, Object, Overloaded, Project, Severity, Source, SrcPos,
-    Type,
+    FormatOptions, InstantiationOptions, InstantiationStyle, InterfaceEnt, KeywordCase, Message,
+    MessageHandler, Object, Overloaded, Project, ReferenceKind, Severity, Source, SrcPos, Type,
 };
 
+/// The command that creates an instantiation template for an entity
+const INSTANTIATION_TEMPLATE_COMMAND: &str = "vhdl_ls.instantiationTemplate";
+
+/// Changes of watched files are processed once no further changes arrived for this long,
+/// such that a checkout that touches many files only reloads the project once
+const WATCHED_FILES_DEBOUNCE: Duration = Duration::from_millis(200);
+
 #[derive(Default, Clone)]
 pub struct VHDLServerSettings {
     pub no_lint: bool,
@@ -36,6 +44,11 @@ pub struct VHDLServer {
     files_with_notifications: FnvHashMap<Url, ()>,
     init_params: Option<InitializeParams>,
     config_file: Option<PathBuf>,
+    // Files that are open in the editor, their buffer takes precedence over the disk content
+    open_files: FnvHashSet<PathBuf>,
+    // Changes of watched files that have not been processed yet
+    pending_file_changes: Vec<FileEvent>,
+    pending_file_changes_deadline: Option<Instant>,
 }
 
 impl VHDLServer {
@@ -48,6 +61,9 @@ impl VHDLServer {
             files_with_notifications: FnvHashMap::default(),
             init_params: None,
             config_file: None,
+            open_files: FnvHashSet::default(),
+            pending_file_changes: Vec::new(),
+            pending_file_changes_deadline: None,
         }
     }
 
@@ -61,6 +77,9 @@ impl VHDLServer {
             files_with_notifications: FnvHashMap::default(),
             init_params: None,
             config_file: None,
+            open_files: FnvHashSet::default(),
+            pending_file_changes: Vec::new(),
+            pending_file_changes_deadline: None,
         }
     }
 
@@ -72,7 +91,7 @@ impl VHDLServer {
                 "Workspace root configuration file not set",
             )
         })?;
-        let config = Config::read_file_path(config_file)?;
+        let config = Config::read_file_path_with_messages(config_file, &mut self.message_filter())?;
 
         // Log which file was loaded
         self.message(Message::log(format!(
@@ -129,6 +148,7 @@ impl VHDLServer {
             definition_provider: Some(OneOf::Left(true)),
             hover_provider: Some(HoverProviderCapability::Simple(true)),
             references_provider: Some(OneOf::Left(true)),
+            document_highlight_provider: Some(OneOf::Left(true)),
             implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
             rename_provider: Some(OneOf::Right(RenameOptions {
                 prepare_provider: Some(true),
@@ -144,6 +164,25 @@ impl VHDLServer {
                 }),
                 ..Default::default()
             }),
+            signature_help_provider: Some(SignatureHelpOptions {
+                trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
+                retrigger_characters: None,
+                work_done_progress_options: Default::default(),
+            }),
+            document_formatting_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec![INSTANTIATION_TEMPLATE_COMMAND.to_owned()],
+                work_done_progress_options: Default::default(),
+            }),
+            semantic_tokens_provider: Some(
+                SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+                    legend: semantic_tokens_legend(),
+                    range: Some(true),
+                    full: Some(SemanticTokensFullOptions::Bool(true)),
+                    work_done_progress_options: Default::default(),
+                }),
+            ),
             ..Default::default()
         };
 
@@ -190,13 +229,12 @@ impl VHDLServer {
 
     /// Register capabilities on the client side:
     /// - watch workspace config file for changes
+    /// - watch for created and deleted VHDL files to expand glob patterns again
+    /// - watch for VHDL files that are changed outside of the editor
     fn register_capabilities(&mut self) {
         if self.client_supports_did_change_watched_files() {
             let register_options = DidChangeWatchedFilesRegistrationOptions {
-                watchers: vec![FileSystemWatcher {
-                    glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
-                    kind: None,
-                }],
+                watchers: watched_files(),
             };
             let params = RegistrationParams {
                 registrations: vec![Registration {
@@ -234,6 +272,7 @@ impl VHDLServer {
     pub fn text_document_did_open_notification(&mut self, params: &DidOpenTextDocumentParams) {
         let TextDocumentItem { uri, text, .. } = &params.text_document;
         let file_name = uri_to_file_name(uri);
+        self.open_files.insert(file_name.clone());
         if let Some(source) = self.project.get_source(&file_name) {
             source.change(None, text);
             self.project.update_source(&source);
@@ -249,23 +288,86 @@ impl VHDLServer {
         }
     }
 
+    pub fn text_document_did_close_notification(&mut self, params: &DidCloseTextDocumentParams) {
+        let file_name = uri_to_file_name(&params.text_document.uri);
+        if self.open_files.remove(&file_name) {
+            // Unsaved changes of the editor buffer are discarded
+            self.project
+                .reload_files([file_name.as_path()], &mut self.message_filter());
+            self.publish_diagnostics();
+        }
+    }
+
+    /// The changes are processed by `process_pending_file_changes` after they have settled
     pub fn workspace_did_change_watched_files(&mut self, params: &DidChangeWatchedFilesParams) {
-        if let Some(config_file) = &self.config_file {
-            let config_file_has_changed = params
-                .changes
+        self.pending_file_changes
+            .extend(params.changes.iter().cloned());
+        self.pending_file_changes_deadline = Some(Instant::now() + WATCHED_FILES_DEBOUNCE);
+    }
+
+    /// The time at which pending changes of watched files should be processed
+    /// unless further changes arrive before
+    pub fn pending_file_changes_deadline(&self) -> Option<Instant> {
+        self.pending_file_changes_deadline
+    }
+
+    /// Reload the configuration and the files that changed on disk and publish
+    /// the diagnostics of the reanalyzed project
+    pub fn process_pending_file_changes(&mut self) {
+        self.pending_file_changes_deadline = None;
+        let changes = std::mem::take(&mut self.pending_file_changes);
+
+        let config_file_has_changed = self.config_file.as_ref().is_some_and(|config_file| {
+            changes
                 .iter()
-                .any(|change| uri_to_file_name(&change.uri).as_path() == config_file);
-            if config_file_has_changed {
-                self.message(Message::log(
-                    "Configuration file has changed, reloading project...",
-                ));
-                let config = self.load_config();
+                .any(|change| uri_to_file_name(&change.uri).as_path() == config_file)
+        });
+        // The files matched by glob patterns in the configuration may have changed
+        let vhdl_file_created_or_deleted = changes.iter().any(|change| {
+            (change.typ == FileChangeType::CREATED || change.typ == FileChangeType::DELETED)
+                && is_vhdl_file(&uri_to_file_name(&change.uri))
+        });
+        let mut changed_files: Vec<PathBuf> = changes
+            .iter()
+            .filter(|change| change.typ == FileChangeType::CHANGED)
+            .map(|change| uri_to_file_name(&change.uri))
+            .filter(|file_name| {
+                is_vhdl_file(file_name)
+                    && !self.open_files.contains(file_name)
+                    && self.project.get_source(file_name).is_some()
+            })
+            .collect();
+        changed_files.sort();
+        changed_files.dedup();
+
+        if config_file_has_changed {
+            self.message(Message::log(
+                "Configuration file has changed, reloading project...",
+            ));
+        } else if vhdl_file_created_or_deleted {
+            self.message(Message::log(
+                "VHDL files have been created or deleted, reloading project...",
+            ));
+        } else if changed_files.is_empty() {
+            return;
+        }
 
-                self.project
-                    .update_config(config, &mut self.message_filter());
-                self.publish_diagnostics();
-            }
+        if config_file_has_changed || vhdl_file_created_or_deleted {
+            let config = self.load_config();
+            self.project
+                .update_config(config, &mut self.message_filter());
+        }
+        if !changed_files.is_empty() {
+            self.message(Message::log(format!(
+                "{} VHDL files have changed on disk, reloading them...",
+                changed_files.len()
+            )));
+            self.project.reload_files(
+                changed_files.iter().map(PathBuf::as_path),
+                &mut self.message_filter(),
+            );
         }
+        self.publish_diagnostics();
     }
 
     fn completion_item_to_lsp_item(
@@ -559,14 +661,12 @@ impl VHDLServer {
             .project
             .get_source(&uri_to_file_name(&params.text_document.uri))?;
 
-        let ents = self
+        let positions = self
             .project
-            .find_implementation(&source, from_lsp_pos(params.position));
+            .find_implementations(&source, from_lsp_pos(params.position));
 
         Some(GotoDefinitionResponse::Array(
-            ents.into_iter()
-                .filter_map(|ent| ent.decl_pos().map(srcpos_to_location))
-                .collect(),
+            positions.iter().map(srcpos_to_location).collect(),
         ))
     }
 
@@ -597,18 +697,26 @@ impl VHDLServer {
             &params.text_document_position.text_document.uri,
         ))?;
 
-        let ent = self.project.find_declaration(
+        let (pos, _) = self.project.item_at_cursor(
             &source,
             from_lsp_pos(params.text_document_position.position),
         )?;
 
+        let edits = match self.project.rename(&pos, &params.new_name) {
+            Ok(edits) => edits,
+            Err(diagnostic) => {
+                self.message(Message::error(diagnostic.message));
+                return None;
+            }
+        };
+
         let mut changes: HashMap<Url, Vec<TextEdit>> = Default::default();
 
-        for srcpos in self.project.find_all_references(ent) {
+        for (srcpos, new_text) in edits {
             let loc = srcpos_to_location(&srcpos);
             changes.entry(loc.uri).or_default().push(TextEdit {
                 range: loc.range,
-                new_text: params.new_name.clone(),
+                new_text,
             });
         }
 
@@ -623,38 +731,21 @@ impl VHDLServer {
         params: &WorkspaceSymbolParams,
     ) -> Option<WorkspaceSymbolResponse> {
         let trunc_limit = 200;
-        let query = params.query.to_ascii_lowercase();
-        let mut symbols: Vec<_> = self
-            .project
-            .public_symbols()
-            .filter_map(|ent| match ent.designator() {
-                Designator::Identifier(_) | Designator::Character(_) => {
-                    Some((ent, ent.designator().to_string().to_ascii_lowercase()))
-                }
-                Designator::OperatorSymbol(op) => Some((ent, op.to_string().to_ascii_lowercase())),
-                Designator::Anonymous(_) => None,
-            })
-            .collect();
-        symbols.sort_by(|(_, n1), (_, n2)| n1.cmp(n2));
         Some(WorkspaceSymbolResponse::Nested(
-            symbols
+            self.project
+                .workspace_symbols(&params.query, trunc_limit)
                 .into_iter()
-                .filter_map(|(ent, name)| {
+                .filter_map(|ent| {
                     let decl_pos = ent.decl_pos()?;
-                    if name.starts_with(&query) {
-                        Some(WorkspaceSymbol {
-                            name: ent.describe(),
-                            kind: to_symbol_kind(ent.kind()),
-                            tags: None,
-                            container_name: ent.parent.map(|ent| ent.path_name()),
-                            location: OneOf::Left(srcpos_to_location(decl_pos)),
-                            data: None,
-                        })
-                    } else {
-                        None
-                    }
+                    Some(WorkspaceSymbol {
+                        name: ent.describe(),
+                        kind: to_symbol_kind(ent.kind()),
+                        tags: None,
+                        container_name: ent.parent.map(|ent| ent.path_name()),
+                        location: OneOf::Left(srcpos_to_location(decl_pos)),
+                        data: None,
+                    })
                 })
-                .take(trunc_limit)
                 .collect(),
         ))
     }
@@ -734,21 +825,110 @@ impl VHDLServer {
         let source = self
             .project
             .get_source(&uri_to_file_name(&params.text_document.uri))?;
-        let ent = self
-            .project
-            .find_declaration(&source, from_lsp_pos(params.position))?;
-
-        let value = self.project.format_declaration(ent)?;
+        let hover = self.project.hover(&source, from_lsp_pos(params.position))?;
+        let mut value = format!("```vhdl\n{}\n```", hover.signature);
+        if let Some(doc_comment) = hover.doc_comment {
+            value.push_str(&format!("\n\n{doc_comment}"));
+        }
 
         Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
-                value: format!("```vhdl\n{value}\n```"),
+                value,
             }),
             range: None,
         })
     }
 
+    pub fn text_document_signature_help(
+        &mut self,
+        params: &SignatureHelpParams,
+    ) -> Option<SignatureHelp> {
+        let position = &params.text_document_position_params;
+        let source = self
+            .project
+            .get_source(&uri_to_file_name(&position.text_document.uri))?;
+        let help = self
+            .project
+            .signature_help(&source, from_lsp_pos(position.position))?;
+
+        Some(SignatureHelp {
+            signatures: help
+                .signatures
+                .into_iter()
+                .map(|signature| SignatureInformation {
+                    label: signature.label,
+                    documentation: None,
+                    parameters: Some(
+                        signature
+                            .parameters
+                            .into_iter()
+                            .map(|parameter| ParameterInformation {
+                                label: ParameterLabel::Simple(parameter),
+                                documentation: None,
+                            })
+                            .collect(),
+                    ),
+                    active_parameter: signature
+                        .active_parameter
+                        .and_then(|idx| u32::try_from(idx).ok()),
+                })
+                .collect(),
+            active_signature: u32::try_from(help.active_signature).ok(),
+            active_parameter: None,
+        })
+    }
+
+    pub fn text_document_semantic_tokens_full(
+        &mut self,
+        params: &SemanticTokensParams,
+    ) -> Option<SemanticTokensResult> {
+        let source = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))?;
+        let tokens = self.project.semantic_tokens(&source);
+        Some(SemanticTokensResult::Tokens(to_lsp_semantic_tokens(tokens)))
+    }
+
+    /// Only the tokens of the visible range are computed, which is faster for large files
+    pub fn text_document_semantic_tokens_range(
+        &mut self,
+        params: &SemanticTokensRangeParams,
+    ) -> Option<SemanticTokensRangeResult> {
+        let source = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))?;
+        let tokens = self
+            .project
+            .semantic_tokens_in_range(&source, from_lsp_range(params.range));
+        Some(SemanticTokensRangeResult::Tokens(to_lsp_semantic_tokens(
+            tokens,
+        )))
+    }
+
+    /// Format the whole document, the keyword case of the document is preserved
+    pub fn text_document_formatting(
+        &mut self,
+        params: &DocumentFormattingParams,
+    ) -> Option<Vec<TextEdit>> {
+        let source = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))?;
+        let options = FormatOptions {
+            indent_width: params.options.tab_size as usize,
+            keyword_case: KeywordCase::Preserve,
+            ..FormatOptions::default()
+        };
+        let new_text = self.project.format_source(&source, &options)?;
+        // Replace everything including the final newline
+        let end = lsp_types::Position::new(source.contents().num_lines() as u32, 0);
+
+        Some(vec![TextEdit {
+            range: Range::new(lsp_types::Position::new(0, 0), end),
+            new_text,
+        }])
+    }
+
     pub fn text_document_references(&mut self, params: &ReferenceParams) -> Vec<Location> {
         let ent = self
             .project
@@ -764,15 +944,168 @@ impl VHDLServer {
 
         if let Some(ent) = ent {
             self.project
-                .find_all_references(ent)
+                .find_all_references_with_kind(ent)
                 .iter()
-                .map(srcpos_to_location)
+                .filter(|(_, kind)| {
+                    params.context.include_declaration || *kind != ReferenceKind::Declaration
+                })
+                .map(|(pos, _)| srcpos_to_location(pos))
                 .collect()
         } else {
             Vec::new()
         }
     }
 
+    pub fn text_document_highlight(
+        &mut self,
+        params: &TextDocumentPositionParams,
+    ) -> Vec<DocumentHighlight> {
+        let Some(source) = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Vec::new();
+        };
+
+        self.project
+            .document_references(&source, from_lsp_pos(params.position))
+            .into_iter()
+            .map(|(pos, kind)| DocumentHighlight {
+                range: to_lsp_range(pos.range()),
+                kind: Some(match kind {
+                    ReferenceKind::Write | ReferenceKind::ReadWrite => DocumentHighlightKind::WRITE,
+                    ReferenceKind::Use => DocumentHighlightKind::READ,
+                    ReferenceKind::Declaration | ReferenceKind::EndLabel => {
+                        DocumentHighlightKind::TEXT
+                    }
+                }),
+            })
+            .collect()
+    }
+
+    /// Offers quick fixes that add a use clause for the names of missing declarations,
+    /// and instantiation templates for the entity at the cursor
+    /// or for the entities declared in the file when the cursor is not on an entity
+    pub fn text_document_code_action(
+        &mut self,
+        params: &CodeActionParams,
+    ) -> Vec<CodeActionOrCommand> {
+        let Some(source) = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Vec::new();
+        };
+
+        let entities: Vec<EntRef> = match self
+            .project
+            .find_declaration(&source, from_lsp_pos(params.range.start))
+        {
+            Some(ent) if matches!(ent.kind(), AnyEntKind::Design(Design::Entity(..))) => {
+                vec![ent]
+            }
+            _ => self
+                .project
+                .library_mapping_of(&source)
+                .first()
+                .map(|library_name| self.project.document_symbols(library_name, &source))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|hierarchy| hierarchy.ent)
+                .filter(|ent| matches!(ent.kind(), AnyEntKind::Design(Design::Entity(..))))
+                .collect(),
+        };
+
+        let mut actions = Vec::new();
+        for diagnostic in params.context.diagnostics.iter() {
+            let is_missing_name = diagnostic.code
+                == Some(NumberOrString::String("missing_decl".to_owned()))
+                && diagnostic.message.starts_with("No declaration of '")
+                && !diagnostic.message.contains("' within ");
+            if !is_missing_name {
+                continue;
+            }
+
+            let pos = source.pos(
+                from_lsp_pos(diagnostic.range.start),
+                from_lsp_pos(diagnostic.range.end),
+            );
+            for fix in self.project.use_clause_fixes(&pos) {
+                let position = to_lsp_pos(fix.position);
+                let edit = TextEdit {
+                    range: lsp_types::Range {
+                        start: position,
+                        end: position,
+                    },
+                    new_text: fix.text,
+                };
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Add 'use {}.{}.all'", fix.library_name, fix.package_name),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(
+                            params.text_document.uri.clone(),
+                            vec![edit],
+                        )])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        for ent in entities {
+            let Some(library_name) = ent.library_name() else {
+                continue;
+            };
+            for style in ["entity", "component"] {
+                actions.push(CodeActionOrCommand::Command(Command {
+                    title: format!(
+                        "Create {style} instantiation template of '{}'",
+                        ent.designator()
+                    ),
+                    command: INSTANTIATION_TEMPLATE_COMMAND.to_owned(),
+                    arguments: Some(vec![
+                        serde_json::Value::from(library_name.name_utf8()),
+                        serde_json::Value::from(ent.designator().to_string()),
+                        serde_json::Value::from(style),
+                    ]),
+                }));
+            }
+        }
+        actions
+    }
+
+    /// Runs a command offered by a code action.
+    /// The instantiation template command takes the library name, the entity name and
+    /// the style and returns the template as a string.
+    pub fn workspace_execute_command(
+        &mut self,
+        params: &ExecuteCommandParams,
+    ) -> Option<serde_json::Value> {
+        if params.command != INSTANTIATION_TEMPLATE_COMMAND {
+            return None;
+        }
+        let [library_name, entity_name, style] = params.arguments.as_slice() else {
+            return None;
+        };
+        let style = match style.as_str()? {
+            "component" => InstantiationStyle::Component,
+            _ => InstantiationStyle::Entity,
+        };
+        let options = InstantiationOptions {
+            style,
+            ..InstantiationOptions::default()
+        };
+        let template = self.project.instantiation_template(
+            &self.project.symbol_utf8(library_name.as_str()?),
+            &self.project.symbol_utf8(entity_name.as_str()?),
+            &options,
+        )?;
+        Some(serde_json::Value::from(template))
+    }
+
     fn message_filter(&self) -> MessageFilter {
         MessageFilter {
             silent: self.settings.silent,
@@ -785,6 +1118,28 @@ impl VHDLServer {
     }
 }
 
+fn watched_files() -> Vec<FileSystemWatcher> {
+    vec![
+        FileSystemWatcher {
+            glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
+            kind: None,
+        },
+        FileSystemWatcher {
+            glob_pattern: GlobPattern::String("**/*.{vhd,vhdl}".to_owned()),
+            kind: None,
+        },
+    ]
+}
+
+fn is_vhdl_file(file_name: &Path) -> bool {
+    file_name
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("vhd") || extension.eq_ignore_ascii_case("vhdl")
+        })
+}
+
 fn entity_to_completion_item(ent: EntRef) -> CompletionItem {
     CompletionItem {
         label: ent.designator.to_string(),
@@ -907,6 +1262,82 @@ fn from_lsp_range(range: lsp_types::Range) -> vhdl_lang::Range {
     }
 }
 
+/// The token type of a signal which has no corresponding standard token type
+const SIGNAL: SemanticTokenType = SemanticTokenType::new("signal");
+
+fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: vec![
+            SemanticTokenType::KEYWORD,
+            SemanticTokenType::TYPE,
+            SIGNAL,
+            SemanticTokenType::VARIABLE,
+            SemanticTokenType::FUNCTION,
+            SemanticTokenType::NAMESPACE,
+            SemanticTokenType::PARAMETER,
+        ],
+        token_modifiers: vec![
+            SemanticTokenModifier::DECLARATION,
+            SemanticTokenModifier::READONLY,
+        ],
+    }
+}
+
+/// The index of the token type in the legend.
+/// Constants are read-only variables and identifiers without a class are not highlighted.
+fn semantic_token_type_index(token_type: vhdl_lang::SemanticTokenType) -> Option<u32> {
+    use vhdl_lang::SemanticTokenType::*;
+    match token_type {
+        Keyword => Some(0),
+        Type => Some(1),
+        Signal => Some(2),
+        Variable | Constant => Some(3),
+        Function => Some(4),
+        Namespace => Some(5),
+        Parameter => Some(6),
+        Identifier => None,
+    }
+}
+
+/// Encode the tokens relative to the previous token as required by the protocol
+fn to_lsp_semantic_tokens(tokens: Vec<vhdl_lang::SemanticToken>) -> SemanticTokens {
+    let mut data = Vec::with_capacity(tokens.len());
+    let mut previous = vhdl_lang::Position::default();
+    for token in tokens {
+        let Some(token_type) = semantic_token_type_index(token.token_type) else {
+            continue;
+        };
+        let start = token.range.start;
+        let delta_start = if start.line == previous.line {
+            start.character - previous.character
+        } else {
+            start.character
+        };
+        let token_modifiers_bitset = token
+            .modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                vhdl_lang::SemanticTokenModifier::Declaration => 1,
+                vhdl_lang::SemanticTokenModifier::Readonly => 2,
+            })
+            .sum();
+
+        data.push(SemanticToken {
+            delta_line: start.line - previous.line,
+            delta_start,
+            length: token.range.end.character - start.character,
+            token_type,
+            token_modifiers_bitset,
+        });
+        previous = start;
+    }
+
+    SemanticTokens {
+        result_id: None,
+        data,
+    }
+}
+
 fn diagnostics_by_uri(diagnostics: Vec<Diagnostic>) -> FnvHashMap<Url, Vec<Diagnostic>> {
     let mut map: FnvHashMap<Url, Vec<Diagnostic>> = FnvHashMap::default();
 
@@ -951,6 +1382,13 @@ fn to_lsp_diagnostic(diagnostic: Diagnostic) -> lsp_types::Diagnostic {
         Severity::Hint => DiagnosticSeverity::HINT,
     };
 
+    // Code within a translate_off region is not synthesized, show it as unused
+    let tags = if diagnostic.in_translate_off() {
+        Some(vec![DiagnosticTag::UNNECESSARY])
+    } else {
+        None
+    };
+
     let related_information = if !diagnostic.related.is_empty() {
         let mut related_information = Vec::new();
         for (pos, msg) in diagnostic.related {
@@ -971,10 +1409,13 @@ fn to_lsp_diagnostic(diagnostic: Diagnostic) -> lsp_types::Diagnostic {
     lsp_types::Diagnostic {
         range: to_lsp_range(diagnostic.pos.range()),
         severity: Some(severity),
-        code: None,
+        code: diagnostic
+            .code
+            .map(|code| NumberOrString::String(code.to_string())),
         source: Some("vhdl ls".to_owned()),
         message: diagnostic.message,
         related_information,
+        tags,
         ..Default::default()
     }
 }
@@ -1111,6 +1552,12 @@ mod tests {
         mock.expect_warning_contains(
             "Without library mapping semantic analysis might be incorrect",
         );
+        expect_std_not_configured(mock);
+    }
+
+    /// The library std is not configured when the external configuration is not used
+    fn expect_std_not_configured(mock: &RpcMock) {
+        mock.expect_error_contains("The library 'std' is not configured");
     }
 
     fn expect_erroneous_config(mock: &RpcMock) {
@@ -1160,6 +1607,292 @@ end entity ent;
         server.text_document_did_open_notification(&did_open);
     }
 
+    #[test]
+    fn text_document_formatting() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        expect_missing_config_messages(&mock);
+        initialize_server(&mut server, root_uri.clone());
+
+        let file_url = root_uri.join("ent.vhd").unwrap();
+        let code = "ENTITY ent IS END ENTITY;\nARCHITECTURE a OF ent IS BEGIN\nb: BLOCK BEGIN END BLOCK;\nEND;\n"
+            .to_owned();
+
+        let did_open = DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_url.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: code,
+            },
+        };
+        mock.expect_warning_contains("is not part of the project");
+        server.text_document_did_open_notification(&did_open);
+
+        let edits = server.text_document_formatting(&DocumentFormattingParams {
+            text_document: TextDocumentIdentifier { uri: file_url },
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: true,
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+        });
+
+        assert_eq!(
+            edits,
+            Some(vec![TextEdit {
+                range: Range {
+                    start: lsp_types::Position::new(0, 0),
+                    end: lsp_types::Position::new(4, 0),
+                },
+                new_text: "\
+ENTITY ent IS
+END ENTITY;
+
+ARCHITECTURE a OF ent IS
+BEGIN
+    b: BLOCK IS
+    BEGIN
+    END BLOCK;
+END ARCHITECTURE;
+"
+                .to_owned(),
+            }])
+        );
+    }
+
+    #[test]
+    fn instantiation_template_code_action() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        expect_missing_config_messages(&mock);
+        initialize_server(&mut server, root_uri.clone());
+
+        let file_url = root_uri.join("ent.vhd").unwrap();
+        let code = "entity ent is\nend entity;\n".to_owned();
+
+        let did_open = DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_url.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: code,
+            },
+        };
+        mock.expect_warning_contains("is not part of the project");
+        server.text_document_did_open_notification(&did_open);
+
+        let actions = server.text_document_code_action(&CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: file_url },
+            range: Range::new(
+                lsp_types::Position::new(0, 8),
+                lsp_types::Position::new(0, 8),
+            ),
+            context: Default::default(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let commands: Vec<Command> = actions
+            .into_iter()
+            .filter_map(|action| match action {
+                CodeActionOrCommand::Command(command) => Some(command),
+                CodeActionOrCommand::CodeAction(_) => None,
+            })
+            .collect();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(
+            commands[0].title,
+            "Create entity instantiation template of 'ent'"
+        );
+
+        let template = server.workspace_execute_command(&ExecuteCommandParams {
+            command: commands[0].command.clone(),
+            arguments: commands[0].arguments.clone().unwrap(),
+            work_done_progress_params: Default::default(),
+        });
+        assert_eq!(
+            template,
+            Some(serde_json::Value::from("ent_inst : entity work.ent;"))
+        );
+    }
+
+    #[test]
+    fn use_clause_code_action() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        expect_missing_config_messages(&mock);
+        initialize_server(&mut server, root_uri.clone());
+
+        let file_url = root_uri.join("ent.vhd").unwrap();
+        let code = "\
+package pkg is
+  component comp is
+  end component;
+end package;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  inst : component comp;
+end architecture;
+"
+        .to_owned();
+
+        let range = Range::new(
+            lsp_types::Position::new(10, 19),
+            lsp_types::Position::new(10, 23),
+        );
+        let diagnostic = lsp_types::Diagnostic {
+            range,
+            code: Some(NumberOrString::String("missing_decl".to_owned())),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("vhdl ls".to_owned()),
+            message: "No declaration of 'comp'".to_owned(),
+            ..Default::default()
+        };
+
+        let did_open = DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_url.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: code,
+            },
+        };
+        mock.expect_warning_contains("is not part of the project");
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            PublishDiagnosticsParams {
+                uri: file_url.clone(),
+                diagnostics: vec![diagnostic.clone()],
+                version: None,
+            },
+        );
+        server.text_document_did_open_notification(&did_open);
+
+        let actions = server.text_document_code_action(&CodeActionParams {
+            text_document: TextDocumentIdentifier {
+                uri: file_url.clone(),
+            },
+            range,
+            context: CodeActionContext {
+                diagnostics: vec![diagnostic.clone()],
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let code_actions: Vec<CodeAction> = actions
+            .into_iter()
+            .filter_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => Some(action),
+                CodeActionOrCommand::Command(_) => None,
+            })
+            .collect();
+
+        let position = lsp_types::Position::new(8, 0);
+        assert_eq!(
+            code_actions,
+            vec![CodeAction {
+                title: "Add 'use work.pkg.all'".to_owned(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic]),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(
+                        file_url,
+                        vec![TextEdit {
+                            range: Range::new(position, position),
+                            new_text: "use work.pkg.all;\n".to_owned(),
+                        }]
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn text_document_semantic_tokens() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        expect_missing_config_messages(&mock);
+        initialize_server(&mut server, root_uri.clone());
+
+        let file_url = root_uri.join("ent.vhd").unwrap();
+        let code = "entity ent is\nend entity;\narchitecture a of ent is\n  component comp is\n  end component;\nbegin\nend;\n"
+            .to_owned();
+
+        let did_open = DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_url.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: code,
+            },
+        };
+        mock.expect_warning_contains("is not part of the project");
+        server.text_document_did_open_notification(&did_open);
+
+        let token =
+            |delta_line, delta_start, length, token_type, token_modifiers_bitset| SemanticToken {
+                delta_line,
+                delta_start,
+                length,
+                token_type,
+                token_modifiers_bitset,
+            };
+
+        let result = server.text_document_semantic_tokens_range(&SemanticTokensRangeParams {
+            text_document: TextDocumentIdentifier {
+                uri: file_url.clone(),
+            },
+            range: Range::new(
+                lsp_types::Position::new(3, 0),
+                lsp_types::Position::new(4, 0),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        assert_eq!(
+            result,
+            Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+                result_id: None,
+                data: vec![
+                    // component
+                    token(3, 2, 9, 0, 0),
+                    // comp
+                    token(0, 10, 4, 5, 1),
+                    // is
+                    token(0, 5, 2, 0, 0),
+                ],
+            }))
+        );
+
+        let result = server.text_document_semantic_tokens_full(&SemanticTokensParams {
+            text_document: TextDocumentIdentifier { uri: file_url },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let Some(SemanticTokensResult::Tokens(tokens)) = result else {
+            panic!("Expected semantic tokens");
+        };
+        assert_eq!(
+            &tokens.data[..3],
+            &[
+                // entity
+                token(0, 0, 6, 0, 0),
+                // ent
+                token(0, 7, 3, 5, 1),
+                // is
+                token(0, 4, 2, 0, 0),
+            ]
+        );
+    }
+
     #[test]
     fn did_open_with_diagnostics_and_change_without() {
         let (mock, mut server) = setup_server();
@@ -1186,23 +1919,41 @@ end entity ent2;
 
         let publish_diagnostics = PublishDiagnosticsParams {
             uri: file_url.clone(),
-            diagnostics: vec![lsp_types::Diagnostic {
-                range: Range {
-                    start: lsp_types::Position {
-                        line: 2,
-                        character: "end entity ".len() as u32,
+            diagnostics: vec![
+                lsp_types::Diagnostic {
+                    range: Range {
+                        start: lsp_types::Position {
+                            line: 1,
+                            character: "entity ".len() as u32,
+                        },
+                        end: lsp_types::Position {
+                            line: 1,
+                            character: "entity ent".len() as u32,
+                        },
                     },
-                    end: lsp_types::Position {
-                        line: 2,
-                        character: "end entity ent2".len() as u32,
+                    severity: Some(DiagnosticSeverity::HINT),
+                    source: Some("vhdl ls".to_owned()),
+                    message: "related: 'ent' declared here".to_owned(),
+                    ..Default::default()
+                },
+                lsp_types::Diagnostic {
+                    range: Range {
+                        start: lsp_types::Position {
+                            line: 2,
+                            character: "end entity ".len() as u32,
+                        },
+                        end: lsp_types::Position {
+                            line: 2,
+                            character: "end entity ent2".len() as u32,
+                        },
                     },
+                    code: Some(NumberOrString::String("end_identifier_mismatch".to_owned())),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("vhdl ls".to_owned()),
+                    message: "End identifier 'ent2' does not match 'ent'".to_owned(),
+                    ..Default::default()
                 },
-                code: None,
-                severity: Some(DiagnosticSeverity::ERROR),
-                source: Some("vhdl ls".to_owned()),
-                message: "End identifier mismatch, expected ent".to_owned(),
-                ..Default::default()
-            }],
+            ],
             version: None,
         };
 
@@ -1289,16 +2040,17 @@ lib.files = [
                         character: "architecture rtl of ent2".len() as u32,
                     },
                 },
-                code: None,
+                code: Some(NumberOrString::String("missing_decl".to_owned())),
                 severity: Some(DiagnosticSeverity::ERROR),
                 source: Some("vhdl ls".to_owned()),
-                message: "No primary unit \'ent2\' within library \'lib\'".to_owned(),
+                message: "No entity \'ent2\' within library \'lib\'".to_owned(),
                 ..Default::default()
             }],
             version: None,
         };
 
         expect_loaded_config_messages(&mock, &config_uri);
+        expect_std_not_configured(&mock);
         mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
 
         initialize_server(&mut server, root_uri);
@@ -1317,6 +2069,7 @@ lib.files = [
         );
 
         expect_erroneous_config(&mock);
+        expect_std_not_configured(&mock);
         initialize_server(&mut server, root_uri);
     }
 
@@ -1337,6 +2090,7 @@ lib.files = [
 
         expect_loaded_config_messages(&mock, &config_uri);
         mock.expect_warning_contains("missing_file.vhd");
+        expect_std_not_configured(&mock);
         initialize_server(&mut server, root_uri);
     }
 
@@ -1432,10 +2186,16 @@ lib.files = [
         );
 
         let register_options = DidChangeWatchedFilesRegistrationOptions {
-            watchers: vec![FileSystemWatcher {
-                glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
-                kind: None,
-            }],
+            watchers: vec![
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
+                    kind: None,
+                },
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/*.{vhd,vhdl}".to_owned()),
+                    kind: None,
+                },
+            ],
         };
         let register_capability = RegistrationParams {
             registrations: vec![Registration {
@@ -1446,6 +2206,7 @@ lib.files = [
         };
 
         expect_loaded_config_messages(&mock, &config_uri);
+        expect_std_not_configured(&mock);
         mock.expect_request("client/registerCapability", register_capability);
 
         let capabilities = ClientCapabilities {
@@ -1514,10 +2275,10 @@ lib.files = [
                         character: "architecture rtl of ent".len() as u32,
                     },
                 },
-                code: None,
+                code: Some(NumberOrString::String("missing_decl".to_owned())),
                 severity: Some(DiagnosticSeverity::ERROR),
                 source: Some("vhdl ls".to_owned()),
-                message: "No primary unit \'ent\' within library \'lib\'".to_owned(),
+                message: "No entity \'ent\' within library \'lib\'".to_owned(),
                 ..Default::default()
             }],
             version: None,
@@ -1535,6 +2296,7 @@ lib.files = [
         };
 
         expect_loaded_config_messages(&mock, &config_uri);
+        expect_std_not_configured(&mock);
         mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics1);
         mock.expect_message_contains("Configuration file has changed, reloading project...");
         expect_loaded_config_messages(&mock, &config_uri);
@@ -1558,5 +2320,186 @@ lib.files = [
                 uri: config_uri,
             }],
         });
+        server.process_pending_file_changes();
+    }
+
+    #[test]
+    fn created_file_matched_by_glob_is_added() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        write_file(
+            &root_uri,
+            "file1.vhd",
+            "\
+entity ent is
+end entity;
+",
+        );
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = [
+  '*.vhd'
+]
+",
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        expect_std_not_configured(&mock);
+        mock.expect_message_contains(
+            "VHDL files have been created or deleted, reloading project...",
+        );
+        expect_loaded_config_messages(&mock, &config_uri);
+
+        initialize_server(&mut server, root_uri.clone());
+
+        let file2_uri = write_file(
+            &root_uri,
+            "file2.vhd",
+            "\
+architecture rtl of missing is
+begin
+end;
+",
+        );
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            PublishDiagnosticsParams {
+                uri: file2_uri.clone(),
+                diagnostics: vec![lsp_types::Diagnostic {
+                    range: Range {
+                        start: lsp_types::Position {
+                            line: 0,
+                            character: "architecture rtl of ".len() as u32,
+                        },
+                        end: lsp_types::Position {
+                            line: 0,
+                            character: "architecture rtl of missing".len() as u32,
+                        },
+                    },
+                    code: Some(NumberOrString::String("missing_decl".to_owned())),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("vhdl ls".to_owned()),
+                    message: "No entity \'missing\' within library \'lib\'".to_owned(),
+                    ..Default::default()
+                }],
+                version: None,
+            },
+        );
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                typ: FileChangeType::CREATED,
+                uri: file2_uri,
+            }],
+        });
+        server.process_pending_file_changes();
+    }
+
+    #[test]
+    fn file_changed_on_disk_is_reloaded_unless_open() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file1_uri = write_file(&root_uri, "file1.vhd", "entity ent is end;");
+        write_file(&root_uri, "file2.vhd", "entity ent2 is end;");
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = [
+  '*.vhd'
+]
+",
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        expect_std_not_configured(&mock);
+        initialize_server(&mut server, root_uri.clone());
+
+        // The editor buffer of file1 takes precedence over the disk content
+        server.text_document_did_open_notification(&DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file1_uri.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: "entity ent is end;".to_owned(),
+            },
+        });
+        write_file(
+            &root_uri,
+            "file1.vhd",
+            "architecture rtl of missing is begin end;",
+        );
+        let file2_uri = write_file(
+            &root_uri,
+            "file2.vhd",
+            "architecture rtl of ent2 is begin end;",
+        );
+
+        let changes = [&file1_uri, &file2_uri].map(|uri| FileEvent {
+            typ: FileChangeType::CHANGED,
+            uri: uri.clone(),
+        });
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: changes.to_vec(),
+        });
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: changes.to_vec(),
+        });
+        assert!(server.pending_file_changes_deadline().is_some());
+
+        let missing_diagnostic = |uri: &Url, name: &str| PublishDiagnosticsParams {
+            uri: uri.clone(),
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ".len() as u32,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: ("architecture rtl of ".len() + name.len()) as u32,
+                    },
+                },
+                code: Some(NumberOrString::String("missing_decl".to_owned())),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("vhdl ls".to_owned()),
+                message: format!("No entity '{name}' within library 'lib'"),
+                ..Default::default()
+            }],
+            version: None,
+        };
+
+        mock.expect_message_contains("1 VHDL files have changed on disk, reloading them...");
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            missing_diagnostic(&file2_uri, "ent2"),
+        );
+        server.process_pending_file_changes();
+        assert!(server.pending_file_changes_deadline().is_none());
+
+        write_file(&root_uri, "file2.vhd", "entity ent2 is end;");
+        mock.expect_message_contains("1 VHDL files have changed on disk, reloading them...");
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            PublishDiagnosticsParams {
+                uri: file2_uri.clone(),
+                diagnostics: vec![],
+                version: None,
+            },
+        );
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: vec![changes[1].clone()],
+        });
+        server.process_pending_file_changes();
+
+        // Closing the editor discards the buffer
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            missing_diagnostic(&file1_uri, "missing"),
+        );
+        server.text_document_did_close_notification(&DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri: file1_uri },
+        });
     }
 }